@@ -19,23 +19,36 @@ fi
 
 echo "Using the profile at $url"
 
+# optional checks to make sure that the profile was not tampered with
+sha256=$(awk -F 'agama.auto_sha256=' '{sub(/ .*$/, "", $2); print $2}' < /proc/cmdline)
+signature=$(awk -F 'agama.auto_signature=' '{sub(/ .*$/, "", $2); print $2}' < /proc/cmdline)
+verify_args=""
+if [ -n "$sha256" ]
+then
+  verify_args="$verify_args --sha256 $sha256"
+fi
+if [ -n "$signature" ]
+then
+  verify_args="$verify_args --signature $signature"
+fi
+
 tmpdir=$(mktemp --directory --suffix "-agama")
 echo "working on $tmpdir"
 
 case "$url" in
 *.jsonnet )
-    /usr/bin/agama profile download "$url" > "${tmpdir}/profile.jsonnet"
+    /usr/bin/agama profile download $verify_args "$url" > "${tmpdir}/profile.jsonnet"
     /usr/bin/agama profile evaluate "${tmpdir}/profile.jsonnet" > "${tmpdir}/profile.json"
     /usr/bin/agama profile validate "${tmpdir}/profile.json" || echo "Validation failed"
     /usr/bin/agama config load "${tmpdir}/profile.json"
     /usr/bin/agama install;;
 *.json )
-    /usr/bin/agama profile download "$url" > "${tmpdir}/profile.json"
+    /usr/bin/agama profile download $verify_args "$url" > "${tmpdir}/profile.json"
     /usr/bin/agama profile validate "${tmpdir}/profile.json" || echo "Validation failed"
     /usr/bin/agama config load "${tmpdir}/profile.json"
     /usr/bin/agama install;;
 *.sh )
-    /usr/bin/agama profile download "$url" > "${tmpdir}/profile.sh"
+    /usr/bin/agama profile download $verify_args "$url" > "${tmpdir}/profile.sh"
     exec $SHELL "/${tmpdir}/profile.sh";;
 *)
     echo "Unrecognized suffix ${url}"
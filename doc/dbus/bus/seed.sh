@@ -64,6 +64,12 @@ abusctl introspect --xml-interface \
   | cleanup \
   > ${DD}1.Questions.WithPassword.bus.xml
 
+abusctl introspect --xml-interface \
+  ${DD}1 \
+  ${SS}1/Overview \
+  | cleanup \
+  > ${DD}1.Overview.bus.xml
+
 # Network interfaces
 abusctl call \
   ${DD}1 \
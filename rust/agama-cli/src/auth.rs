@@ -1,3 +1,4 @@
+use agama_lib::tr;
 use clap::{arg, Args, Subcommand};
 use home;
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
@@ -41,7 +42,7 @@ fn jwt() -> anyhow::Result<String> {
         }
     }
 
-    Err(anyhow::anyhow!("Authentication token not available"))
+    Err(anyhow::anyhow!(tr!("Authentication token not available")))
 }
 
 /// Stores user provided configuration for login command
@@ -145,7 +146,7 @@ fn read_credential(caption: String) -> io::Result<String> {
     if cred.pop().is_none() || cred.is_empty() {
         return Err(io::Error::new(
             io::ErrorKind::Other,
-            format!("Failed to read {}", caption),
+            tr!("Failed to read {}", caption),
         ));
     }
 
@@ -192,7 +193,7 @@ async fn get_jwt(url: String, password: String) -> anyhow::Result<String> {
         return Ok(token.clone());
     }
 
-    Err(anyhow::anyhow!("Failed to get authentication token"))
+    Err(anyhow::anyhow!(tr!("Failed to get authentication token")))
 }
 
 /// Logs into the installation web server and stores JWT for later use.
@@ -205,7 +206,9 @@ async fn login(password: String) -> anyhow::Result<()> {
         if let Some(dir) = path.parent() {
             fs::create_dir_all(dir)?;
         } else {
-            return Err(anyhow::anyhow!("Cannot store the authentication token"));
+            return Err(anyhow::anyhow!(tr!(
+                "Cannot store the authentication token"
+            )));
         }
 
         fs::write(path.as_path(), res)?;
@@ -3,7 +3,10 @@ use crate::config::ConfigCommands;
 use crate::logs::LogsCommands;
 use crate::profile::ProfileCommands;
 use crate::questions::QuestionsCommands;
-use clap::Subcommand;
+use crate::register::RegisterCommands;
+use agama_lib::transfer::Verification;
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
@@ -20,6 +23,20 @@ pub enum Commands {
     Probe,
     // Start Installation
     Install,
+    /// Download a file from a given location
+    ///
+    /// It supports the same locations as the installer: HTTP, HTTPS, FTP, TFTP, NFS or SMB URLs,
+    /// the "cd:" (or "dvd:") and "usb:" schemes to look for the file in the local devices
+    /// (e.g., "usb:/scripts/pre.sh") and the "hd:" scheme to read it from a given device
+    /// (e.g., "hd:/scripts/pre.sh?device=LABEL=AGAMA").
+    Download {
+        /// URL of the file
+        url: String,
+        /// Path to write the file to
+        destination: PathBuf,
+        #[command(flatten)]
+        verification: VerificationArgs,
+    },
     /// Autoinstallation profile handling
     #[command(subcommand)]
     Profile(ProfileCommands),
@@ -36,7 +53,37 @@ pub enum Commands {
     /// Collects logs
     #[command(subcommand)]
     Logs(LogsCommands),
+    /// Register the selected product and list its extensions
+    #[command(subcommand)]
+    Register(RegisterCommands),
     /// Request an action on the web server like Login / Logout
     #[command(subcommand)]
     Auth(AuthCommands),
 }
+
+/// Checks to run on the downloaded files
+///
+/// The file is not written if any of the checks fails.
+#[derive(Args, Debug)]
+pub struct VerificationArgs {
+    /// Expected SHA256 checksum of the file
+    #[arg(long)]
+    pub sha256: Option<String>,
+    /// URL of the detached GPG signature of the file
+    #[arg(long)]
+    pub signature: Option<String>,
+    /// Keyring containing the trusted keys to check the signature
+    /// (default: /etc/agama.d/trusted-keys.gpg)
+    #[arg(long)]
+    pub keyring: Option<PathBuf>,
+}
+
+impl From<VerificationArgs> for Verification {
+    fn from(value: VerificationArgs) -> Self {
+        Verification {
+            sha256: value.sha256,
+            signature: value.signature,
+            keyring: value.keyring,
+        }
+    }
+}
@@ -1,13 +1,18 @@
 use crate::error::CliError;
 use crate::printers::{print, Format};
 use agama_lib::connection;
-use agama_lib::install_settings::{InstallSettings, Scope};
-use agama_lib::Store as SettingsStore;
+use agama_lib::install_settings::{diff_patch, InstallSettings, Scope};
+use agama_lib::overrides::SettingsOverrides;
+use agama_lib::profile::{ProfileValidator, ValidationResult};
+use agama_lib::secrets;
+use agama_lib::tr;
+use agama_lib::Store;
 use agama_settings::{settings::Settings, SettingObject, SettingValue};
 use clap::Subcommand;
 use convert_case::{Case, Casing};
+use serde_json::json;
 use std::str::FromStr;
-use std::{collections::HashMap, error::Error, io};
+use std::{collections::HashMap, error::Error, io, path::PathBuf};
 
 #[derive(Subcommand, Debug)]
 pub enum ConfigCommands {
@@ -19,20 +24,58 @@ pub enum ConfigCommands {
         values: Vec<String>,
     },
     /// Shows the value of one or many configuration settings
-    Show,
+    ///
+    /// Secrets, like passwords, are not included in the output.
+    Show {
+        /// Section to show (e.g., "network"). All of them are shown if it is omitted.
+        section: Option<String>,
+    },
+    /// Generates an installation profile from the current configuration
+    ///
+    /// It queries all the services, so the resulting profile reproduces the selections made
+    /// through the web UI or the CLI. Secrets, like passwords, are not included unless
+    /// --with-secrets is given.
+    Generate {
+        /// File to write the profile to (the standard output is used if omitted)
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+        /// Include the secrets (e.g., passwords) in the profile
+        #[arg(long)]
+        with_secrets: bool,
+        /// Profile with the default values (e.g., one generated right after selecting the
+        /// product). Only the values that differ from it are included, although the product is
+        /// always kept.
+        #[arg(long)]
+        defaults: Option<PathBuf>,
+    },
     /// Loads the configuration from a JSON file
     Load { path: String },
+    /// Patches the configuration with one or many JSON fragments
+    ///
+    /// Only the keys that are present in the fragments are overridden. Collections whose
+    /// elements have an "id" (e.g., network connections) are merged by "id". When several
+    /// fragments are given (e.g., site, base and host profiles), they are applied in order.
+    Patch {
+        #[arg(required = true)]
+        paths: Vec<String>,
+    },
 }
 
 pub enum ConfigAction {
     Add(String, HashMap<String, String>),
     Set(HashMap<String, String>),
-    Show,
+    Show(Option<Scope>),
+    Generate {
+        output: Option<PathBuf>,
+        with_secrets: bool,
+        defaults: Option<PathBuf>,
+    },
     Load(String),
+    Patch(Vec<String>),
 }
 
 pub async fn run(subcommand: ConfigCommands, format: Format) -> anyhow::Result<()> {
-    let store = SettingsStore::new(connection().await?).await?;
+    let store = Store::new(connection().await?).await?;
 
     let command = parse_config_command(subcommand)?;
     match command {
@@ -47,9 +90,45 @@ pub async fn run(subcommand: ConfigCommands, format: Format) -> anyhow::Result<(
             }
             Ok(store.store(&model).await?)
         }
-        ConfigAction::Show => {
+        ConfigAction::Show(scope) => {
+            let model = store.load(scope.map(|s| vec![s])).await?;
+            let mut value = serde_json::to_value(model)?;
+            // skip the sections which were not loaded
+            if let Some(sections) = value.as_object_mut() {
+                sections.retain(|_, v| !v.is_null());
+            }
+            secrets::redact(&mut value);
+            print(value, io::stdout(), format)?;
+            Ok(())
+        }
+        ConfigAction::Generate {
+            output,
+            with_secrets,
+            defaults,
+        } => {
             let model = store.load(None).await?;
-            print(model, io::stdout(), format)?;
+            let mut profile = model.to_profile()?;
+            if !with_secrets {
+                secrets::redact(&mut profile);
+            }
+            if let Some(path) = defaults {
+                let contents = std::fs::read_to_string(path)?;
+                let defaults: serde_json::Value = serde_json::from_str(&contents)?;
+                profile = changed_settings(&profile, &defaults);
+            }
+            let contents = serde_json::to_string_pretty(&profile)?;
+            if let ValidationResult::NotValid(errors) =
+                ProfileValidator::default_schema()?.validate_str(&contents)?
+            {
+                eprintln!("{}", tr!("The generated profile is not valid:"));
+                for error in errors {
+                    eprintln!("* {error}")
+                }
+            }
+            match output {
+                Some(path) => std::fs::write(path, contents + "\n")?,
+                None => println!("{}", contents),
+            }
             Ok(())
         }
         ConfigAction::Add(key, values) => {
@@ -60,12 +139,44 @@ pub async fn run(subcommand: ConfigCommands, format: Format) -> anyhow::Result<(
         }
         ConfigAction::Load(path) => {
             let contents = std::fs::read_to_string(path)?;
-            let result: InstallSettings = serde_json::from_str(&contents)?;
+            let mut result: InstallSettings = serde_json::from_str(&contents)?;
+            SettingsOverrides::from_system().apply(&mut result)?;
             let scopes = result.defined_scopes();
             let mut model = store.load(Some(scopes)).await?;
             model.merge(&result);
             Ok(store.store(&model).await?)
         }
+        ConfigAction::Patch(paths) => {
+            let mut fragments = vec![];
+            let mut scopes = vec![];
+            for path in paths {
+                let contents = std::fs::read_to_string(path)?;
+                let fragment: serde_json::Value = serde_json::from_str(&contents)?;
+                let settings: InstallSettings = serde_json::from_value(fragment.clone())?;
+                for scope in settings.defined_scopes() {
+                    if !scopes.contains(&scope) {
+                        scopes.push(scope);
+                    }
+                }
+                fragments.push(fragment);
+            }
+
+            let overrides = SettingsOverrides::from_system();
+            let mut overridden = InstallSettings::default();
+            overrides.apply(&mut overridden)?;
+            for scope in overridden.defined_scopes() {
+                if !scopes.contains(&scope) {
+                    scopes.push(scope);
+                }
+            }
+
+            let mut model = store.load(Some(scopes)).await?;
+            for fragment in &fragments {
+                model = model.patch(fragment)?;
+            }
+            overrides.apply(&mut model)?;
+            Ok(store.store(&model).await?)
+        }
     }
 }
 
@@ -74,10 +185,42 @@ fn parse_config_command(subcommand: ConfigCommands) -> Result<ConfigAction, CliE
         ConfigCommands::Add { key, values } => {
             Ok(ConfigAction::Add(key, parse_keys_values(values)?))
         }
-        ConfigCommands::Show => Ok(ConfigAction::Show),
+        ConfigCommands::Show { section } => {
+            let scope = section
+                .map(|s| Scope::from_str(&s).map_err(|_| CliError::UnknownSection(s)))
+                .transpose()?;
+            Ok(ConfigAction::Show(scope))
+        }
         ConfigCommands::Set { values } => Ok(ConfigAction::Set(parse_keys_values(values)?)),
+        ConfigCommands::Generate {
+            output,
+            with_secrets,
+            defaults,
+        } => Ok(ConfigAction::Generate {
+            output,
+            with_secrets,
+            defaults,
+        }),
         ConfigCommands::Load { path } => Ok(ConfigAction::Load(path)),
+        ConfigCommands::Patch { paths } => Ok(ConfigAction::Patch(paths)),
+    }
+}
+
+/// Returns the part of the profile that differs from the defaults.
+///
+/// The product is always kept, as the rest of the settings depend on it.
+///
+/// * `profile`: complete profile.
+/// * `defaults`: profile with the default values.
+fn changed_settings(
+    profile: &serde_json::Value,
+    defaults: &serde_json::Value,
+) -> serde_json::Value {
+    let mut changes = diff_patch(profile, defaults).unwrap_or_else(|| json!({}));
+    if let Some(id) = profile.pointer("/product/id") {
+        changes["product"]["id"] = id.clone();
     }
+    changes
 }
 
 /// Split the elements on '=' to make a hash of them.
@@ -121,6 +264,29 @@ fn test_parse_keys_values() {
     );
 }
 
+#[test]
+fn test_changed_settings() {
+    let defaults = json!({
+        "product": { "id": "Tumbleweed" },
+        "localization": { "keyboard": "us", "timezone": "Europe/Berlin" }
+    });
+    let profile = json!({
+        "product": { "id": "Tumbleweed" },
+        "localization": { "keyboard": "es", "timezone": "Europe/Berlin" }
+    });
+    assert_eq!(
+        changed_settings(&profile, &defaults),
+        json!({
+            "product": { "id": "Tumbleweed" },
+            "localization": { "keyboard": "es" }
+        })
+    );
+    assert_eq!(
+        changed_settings(&defaults, &defaults),
+        json!({ "product": { "id": "Tumbleweed" } })
+    );
+}
+
 fn key_to_scope(key: &str) -> Result<Scope, Box<dyn Error>> {
     if let Some((name, _)) = key.split_once('.') {
         return Ok(Scope::from_str(name)?);
@@ -2,12 +2,14 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum CliError {
-    #[error("Invalid key name: '{0}'")]
+    #[error("{}", agama_lib::tr!("Invalid key name: '{}'", .0))]
     InvalidKeyName(String),
-    #[error("Cannot perform the installation as the settings are not valid")]
+    #[error("{}", agama_lib::tr!("Cannot perform the installation as the settings are not valid"))]
     ValidationError,
-    #[error("Could not start the installation")]
+    #[error("{}", agama_lib::tr!("Could not start the installation"))]
     InstallationError,
-    #[error("Missing the '=' separator in '{0}'")]
+    #[error("{}", agama_lib::tr!("Missing the '=' separator in '{}'", .0))]
     MissingSeparator(String),
+    #[error("{}", agama_lib::tr!("Unknown section: '{}'", .0))]
+    UnknownSection(String),
 }
@@ -1,3 +1,5 @@
+use agama_lib::manager::ManagerClient;
+use agama_lib::tr;
 use clap::Subcommand;
 use fs_extra::copy_items;
 use fs_extra::dir::CopyOptions;
@@ -10,6 +12,7 @@ use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use tempfile::TempDir;
+use tokio_stream::StreamExt;
 
 // definition of "agama logs" subcommands, see clap crate for details
 #[derive(Subcommand, Debug)]
@@ -26,6 +29,17 @@ pub enum LogsCommands {
     },
     /// List logs which will be collected
     List,
+    /// Shows the most recent logs of an Agama service
+    Tail {
+        /// Service name (manager, software, storage or users)
+        service: String,
+        #[clap(long, short = 'n', default_value_t = 10)]
+        /// Number of lines to show
+        lines: u32,
+        #[clap(long, short = 'f')]
+        /// Keep showing the new lines as they are written
+        follow: bool,
+    },
 }
 
 /// Main entry point called from agama CLI main loop
@@ -51,7 +65,39 @@ pub async fn run(subcommand: LogsCommands) -> anyhow::Result<()> {
 
             Ok(())
         }
+        LogsCommands::Tail {
+            service,
+            lines,
+            follow,
+        } => tail(&service, lines, follow).await,
+    }
+}
+
+/// Prints the most recent logs of an Agama service
+///
+/// The logs are read through D-Bus, so it works even if the journal is not directly available.
+///
+/// # Arguments:
+/// * service: service name
+/// * lines: number of lines to print
+/// * follow: whether to keep printing the new lines
+async fn tail(service: &str, lines: u32, follow: bool) -> anyhow::Result<()> {
+    let manager = ManagerClient::new(agama_lib::connection().await?).await?;
+
+    if !follow {
+        for line in manager.recent_logs(service, lines).await? {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+
+    let mut logs = Box::pin(manager.follow_logs(service, lines));
+    while let Some(lines) = logs.next().await {
+        for line in lines? {
+            println!("{}", line);
+        }
     }
+    Ok(())
 }
 
 /// Whatewer passed in destination formed into an absolute path with archive name
@@ -396,7 +442,7 @@ fn store(options: LogOptions) -> Result<(), io::Error> {
 
     // store it
     if verbose {
-        showln(true, format!("Storing result in: \"{}\"", result).as_str());
+        showln(true, tr!("Storing result in: \"{}\"", result).as_str());
     } else {
         showln(true, result.as_str());
     }
@@ -7,22 +7,30 @@ mod error;
 mod logs;
 mod printers;
 mod profile;
-mod progress;
 mod questions;
+mod register;
 
 use crate::error::CliError;
+use agama_lib::connection_manager::ConnectionManager;
 use agama_lib::error::ServiceError;
+use agama_lib::i18n;
 use agama_lib::manager::ManagerClient;
-use agama_lib::progress::ProgressMonitor;
+use agama_lib::progress::{IndicatifPresenter, ProgressMonitor};
+use agama_lib::remote::{self, RemoteHost};
+use agama_lib::tr;
+use agama_lib::transfer::{Transfer, Verification};
+use anyhow::Context;
 use auth::run as run_auth_cmd;
 use commands::Commands;
 use config::run as run_config_cmd;
 use logs::run as run_logs_cmd;
 use printers::Format;
 use profile::run as run_profile_cmd;
-use progress::InstallerProgress;
 use questions::run as run_questions_cmd;
+use register::run as run_register_cmd;
 use std::{
+    fs::{self, File},
+    path::Path,
     process::{ExitCode, Termination},
     thread::sleep,
     time::Duration,
@@ -37,6 +45,17 @@ struct Cli {
     /// Format output
     #[arg(value_enum, short, long, default_value_t = Format::Json)]
     pub format: Format,
+
+    /// Timeout (in seconds) for the calls to the Agama services
+    #[arg(long, global = true)]
+    pub timeout: Option<u64>,
+
+    /// Host running the installer (e.g., "ssh://root@installer.example.net")
+    ///
+    /// The D-Bus messages are tunneled through SSH, so the remote host must run an SSH server and
+    /// provide the systemd-stdio-bridge command.
+    #[arg(long, global = true)]
+    pub host: Option<RemoteHost>,
 }
 
 async fn probe() -> anyhow::Result<()> {
@@ -56,7 +75,10 @@ async fn probe() -> anyhow::Result<()> {
 /// * `manager`: the manager client.
 async fn install(manager: &ManagerClient<'_>, max_attempts: u8) -> anyhow::Result<()> {
     if manager.is_busy().await {
-        println!("Agama's manager is busy. Waiting until it is ready...");
+        println!(
+            "{}",
+            tr!("Agama's manager is busy. Waiting until it is ready...")
+        );
     }
 
     // Make sure that the manager is ready
@@ -74,19 +96,24 @@ async fn install(manager: &ManagerClient<'_>, max_attempts: u8) -> anyhow::Resul
             Ok(()) => break,
             Err(e) => {
                 eprintln!(
-                    "Could not start the installation process: {e}. Attempt {}/{}.",
-                    attempts, max_attempts
+                    "{}",
+                    tr!(
+                        "Could not start the installation process: {}. Attempt {}/{}.",
+                        e,
+                        attempts,
+                        max_attempts
+                    )
                 );
             }
         }
         if attempts == max_attempts {
-            eprintln!("Giving up.");
+            eprintln!("{}", tr!("Giving up."));
             return Err(CliError::InstallationError)?;
         }
         attempts += 1;
         sleep(Duration::from_secs(1));
     }
-    let _ = progress.await;
+    progress.await??;
     Ok(())
 }
 
@@ -94,12 +121,10 @@ async fn show_progress() -> Result<(), ServiceError> {
     // wait 1 second to give other task chance to start, so progress can display something
     tokio::time::sleep(Duration::from_secs(1)).await;
     let conn = agama_lib::connection().await?;
-    let mut monitor = ProgressMonitor::new(conn).await.unwrap();
-    let presenter = InstallerProgress::new();
-    monitor
-        .run(presenter)
-        .await
-        .expect("failed to monitor the progress");
+    let services = ConnectionManager::new(conn).await?;
+    let mut monitor = ProgressMonitor::with_manager(&services).await?;
+    let presenter = IndicatifPresenter::new();
+    monitor.run(presenter).await?;
     Ok(())
 }
 
@@ -107,7 +132,10 @@ async fn wait_for_services(manager: &ManagerClient<'_>) -> Result<(), ServiceErr
     let services = manager.busy_services().await?;
     // TODO: having it optional
     if !services.is_empty() {
-        eprintln!("The Agama service is busy. Waiting for it to be available...");
+        eprintln!(
+            "{}",
+            tr!("The Agama service is busy. Waiting for it to be available...")
+        );
         show_progress().await?
     }
     Ok(())
@@ -118,6 +146,41 @@ async fn build_manager<'a>() -> anyhow::Result<ManagerClient<'a>> {
     Ok(ManagerClient::new(conn).await?)
 }
 
+/// Downloads a file from any of the locations supported by the installer.
+///
+/// The file is removed if the download fails, so it does not leave partial content behind.
+///
+/// * `url`: URL of the file.
+/// * `destination`: path to write the file to.
+/// * `verification`: checks to run on the file before writing it.
+fn download_file(url: &str, destination: &Path, verification: &Verification) -> anyhow::Result<()> {
+    let mut file = File::create(destination)
+        .with_context(|| tr!("Could not create the file {}", destination.display()))?;
+    if let Err(error) = Transfer::get_verified(url, &mut file, verification) {
+        let _ = fs::remove_file(destination);
+        return Err(error).context(tr!("Could not download {}", url));
+    }
+    Ok(())
+}
+
+/// Sets up the translations.
+///
+/// The locale from the environment takes precedence. Otherwise, the locale of the user interface
+/// is used, so the messages are consistent with the web user interface.
+async fn init_locale() {
+    let env_locale = i18n::env_locale(|name| std::env::var(name).ok());
+    // the localization service is only asked when the environment does not define a locale
+    let ui_locale = match env_locale {
+        Some(_) => None,
+        None => match agama_lib::connection().await {
+            Ok(connection) => i18n::ui_locale(connection).await,
+            Err(_) => None,
+        },
+    };
+    let locale = i18n::negotiate(env_locale, ui_locale);
+    i18n::init(locale.as_deref());
+}
+
 async fn run_command(cli: Cli) -> anyhow::Result<()> {
     match cli.command {
         Commands::Config(subcommand) => {
@@ -130,6 +193,11 @@ async fn run_command(cli: Cli) -> anyhow::Result<()> {
             wait_for_services(&manager).await?;
             probe().await
         }
+        Commands::Download {
+            url,
+            destination,
+            verification,
+        } => download_file(&url, &destination, &verification.into()),
         Commands::Profile(subcommand) => Ok(run_profile_cmd(subcommand)?),
         Commands::Install => {
             let manager = build_manager().await?;
@@ -138,6 +206,7 @@ async fn run_command(cli: Cli) -> anyhow::Result<()> {
         Commands::Questions(subcommand) => run_questions_cmd(subcommand).await,
         Commands::Logs(subcommand) => run_logs_cmd(subcommand).await,
         Commands::Auth(subcommand) => run_auth_cmd(subcommand).await,
+        Commands::Register(subcommand) => run_register_cmd(subcommand, cli.format).await,
         _ => unimplemented!(),
     }
 }
@@ -159,6 +228,12 @@ impl Termination for CliResult {
 #[tokio::main]
 async fn main() -> CliResult {
     let cli = Cli::parse();
+    if let Some(seconds) = cli.timeout {
+        agama_lib::timeout::set_default_timeout(Duration::from_secs(seconds));
+    }
+    remote::set_remote_host(cli.host.clone());
+
+    init_locale().await;
 
     if let Err(error) = run_command(cli).await {
         eprintln!("{:?}", error);
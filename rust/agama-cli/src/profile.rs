@@ -1,12 +1,19 @@
+use crate::commands::VerificationArgs;
 use agama_lib::profile::{ProfileEvaluator, ProfileReader, ProfileValidator, ValidationResult};
+use agama_lib::tr;
+use agama_lib::transfer::Verification;
 use anyhow::Context;
 use clap::Subcommand;
 use std::path::Path;
 
 #[derive(Subcommand, Debug)]
 pub enum ProfileCommands {
-    /// Download the profile from a given location
-    Download { url: String },
+    /// Download the profile from a given location (e.g., an HTTP, FTP, NFS or SMB URL)
+    Download {
+        url: String,
+        #[command(flatten)]
+        verification: VerificationArgs,
+    },
 
     /// Validate a profile using JSON Schema
     Validate { path: String },
@@ -15,8 +22,8 @@ pub enum ProfileCommands {
     Evaluate { path: String },
 }
 
-fn download(url: &str) -> anyhow::Result<()> {
-    let reader = ProfileReader::new(url)?;
+fn download(url: &str, verification: Verification) -> anyhow::Result<()> {
+    let reader = ProfileReader::new(url)?.with_verification(verification);
     let contents = reader.read()?;
     print!("{}", contents);
     Ok(())
@@ -27,13 +34,16 @@ fn validate(path: String) -> anyhow::Result<()> {
     let path = Path::new(&path);
     let result = validator
         .validate_file(path)
-        .context(format!("Could not validate the profile {:?}", path))?;
+        .context(tr!("Could not validate the profile {}", path.display()))?;
     match result {
         ValidationResult::Valid => {
-            println!("The profile is valid")
+            println!("{}", tr!("The profile is valid"))
         }
         ValidationResult::NotValid(errors) => {
-            eprintln!("The profile is not valid. Please, check the following errors:\n");
+            eprintln!(
+                "{}\n",
+                tr!("The profile is not valid. Please, check the following errors:")
+            );
             for error in errors {
                 println!("* {error}")
             }
@@ -46,13 +56,13 @@ fn evaluate(path: String) -> anyhow::Result<()> {
     let evaluator = ProfileEvaluator {};
     evaluator
         .evaluate(Path::new(&path))
-        .context("Could not evaluate the profile".to_string())?;
+        .context(tr!("Could not evaluate the profile"))?;
     Ok(())
 }
 
 pub fn run(subcommand: ProfileCommands) -> anyhow::Result<()> {
     match subcommand {
-        ProfileCommands::Download { url } => download(&url),
+        ProfileCommands::Download { url, verification } => download(&url, verification.into()),
         ProfileCommands::Validate { path } => validate(path),
         ProfileCommands::Evaluate { path } => evaluate(path),
     }
@@ -1,5 +1,6 @@
 use agama_lib::connection;
 use agama_lib::proxies::Questions1Proxy;
+use agama_lib::tr;
 use anyhow::Context;
 use clap::{Args, Subcommand, ValueEnum};
 
@@ -40,7 +41,7 @@ async fn set_mode(proxy: Questions1Proxy<'_>, value: Modes) -> anyhow::Result<()
     proxy
         .set_interactive(value == Modes::Interactive)
         .await
-        .context("Failed to set mode for answering questions.")
+        .context(tr!("Failed to set mode for answering questions."))
 }
 
 async fn set_answers(proxy: Questions1Proxy<'_>, path: String) -> anyhow::Result<()> {
@@ -48,14 +49,14 @@ async fn set_answers(proxy: Questions1Proxy<'_>, path: String) -> anyhow::Result
     proxy
         .add_answer_file(path.as_str())
         .await
-        .context("Failed to set answers from answers file")
+        .context(tr!("Failed to set answers from answers file"))
 }
 
 pub async fn run(subcommand: QuestionsCommands) -> anyhow::Result<()> {
     let connection = connection().await?;
     let proxy = Questions1Proxy::new(&connection)
         .await
-        .context("Failed to connect to Questions service")?;
+        .context(tr!("Failed to connect to Questions service"))?;
 
     match subcommand {
         QuestionsCommands::Mode(value) => set_mode(proxy, value.value).await,
@@ -0,0 +1,101 @@
+use crate::printers::{print, Format};
+use agama_lib::error::ServiceError;
+use agama_lib::manager::ManagerClient;
+use agama_lib::product::ProductClient;
+use agama_lib::register::{ProductTarget, ProxySettings, SccClient, SystemCredentials};
+use clap::Subcommand;
+use serde::Serialize;
+use std::io;
+
+#[derive(Subcommand, Debug)]
+pub enum RegisterCommands {
+    /// Registers the selected product
+    ///
+    /// Once registered, the software is probed again to use the repositories of the product.
+    Product {
+        /// Registration code
+        code: String,
+        /// Email to associate with the registration
+        #[arg(long)]
+        email: Option<String>,
+    },
+    /// Deregisters the selected product
+    Deregister,
+    /// Shows the registration status of the selected product
+    Status,
+    /// Lists the extensions available for the registered product
+    ///
+    /// The extensions are read from the registration server using the credentials of the
+    /// registered system and the proxy settings of the installer.
+    Extensions {
+        /// Product identifier (the selected product by default)
+        #[arg(long)]
+        product: Option<String>,
+        /// Product version
+        #[arg(long, default_value = "1.0")]
+        version: String,
+        /// Registration server URL (SCC by default)
+        #[arg(long, default_value = agama_lib::register::DEFAULT_SCC_URL)]
+        url: String,
+    },
+}
+
+/// Registration status of the selected product
+#[derive(Debug, Serialize)]
+struct RegistrationStatus {
+    registered: bool,
+    email: String,
+    requirement: &'static str,
+}
+
+pub async fn run(subcommand: RegisterCommands, format: Format) -> anyhow::Result<()> {
+    let connection = agama_lib::connection().await?;
+    let client = ProductClient::new(connection.clone()).await?;
+
+    match subcommand {
+        RegisterCommands::Product { code, email } => {
+            let (result, message) = client.register(&code, &email.unwrap_or_default()).await?;
+            if result != 0 {
+                return Err(ServiceError::FailedRegistration(message))?;
+            }
+            let manager = ManagerClient::new(connection).await?;
+            manager.probe().await?;
+            Ok(())
+        }
+        RegisterCommands::Deregister => {
+            let (result, message) = client.deregister().await?;
+            if result != 0 {
+                return Err(ServiceError::FailedRegistration(message))?;
+            }
+            Ok(())
+        }
+        RegisterCommands::Status => {
+            let requirement = match client.registration_requirement().await? {
+                1 => "optional",
+                2 => "mandatory",
+                _ => "not required",
+            };
+            let status = RegistrationStatus {
+                registered: !client.registration_code().await?.is_empty(),
+                email: client.email().await?,
+                requirement,
+            };
+            print(status, io::stdout(), format)
+        }
+        RegisterCommands::Extensions {
+            product,
+            version,
+            url,
+        } => {
+            let identifier = match product {
+                Some(product) => product,
+                None => client.product().await?,
+            };
+            let target = ProductTarget::new(&identifier, &version);
+            let credentials = SystemCredentials::read_default()?;
+            let scc = SccClient::new(&url).with_proxy(ProxySettings::from_sysconfig()?);
+            let extensions = scc.extensions(&credentials, &target)?;
+            print(extensions, io::stdout(), format)
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Keeps track of the Agama services available on the bus.
+//!
+//! The Agama services might be restarted (e.g., after a crash), breaking the proxies that point to
+//! them: the cached properties get stale and the signals subscriptions are lost. The
+//! [ConnectionManager] watches the `NameOwnerChanged` signal and notifies its subscribers when an
+//! Agama service appears or disappears, so they can rebuild their proxies.
+//!
+//! ```no_run
+//! # use agama_lib::connection_manager::{ConnectionManager, ServiceEvent};
+//! async fn watch_services() {
+//!   let connection = agama_lib::connection().await.unwrap();
+//!   let manager = ConnectionManager::new(connection).await.unwrap();
+//!   let mut events = manager.subscribe();
+//!   while let Ok(event) = events.recv().await {
+//!       if let ServiceEvent::Started(name) = event {
+//!           println!("{} is available (again)", name);
+//!       }
+//!   }
+//! }
+//! ```
+
+use crate::error::ServiceError;
+use tokio::sync::broadcast;
+use tokio_stream::StreamExt;
+use zbus::{fdo::DBusProxy, Connection};
+
+/// Prefix of the well-known names of the Agama services.
+const AGAMA_SERVICES_PREFIX: &str = "org.opensuse.Agama";
+
+/// Number of events to keep for slow subscribers.
+const EVENTS_CAPACITY: usize = 16;
+
+/// Change in the availability of an Agama service.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ServiceEvent {
+    /// The service got a new owner (it was started or restarted).
+    Started(String),
+    /// The service is gone.
+    Stopped(String),
+}
+
+impl ServiceEvent {
+    /// Well-known name of the service.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Started(name) | Self::Stopped(name) => name,
+        }
+    }
+}
+
+/// Shares a D-Bus connection and watches the Agama services running on it.
+///
+/// Cloning the manager is cheap and all the clones share the same watcher.
+#[derive(Clone)]
+pub struct ConnectionManager {
+    connection: Connection,
+    events: broadcast::Sender<ServiceEvent>,
+}
+
+impl ConnectionManager {
+    /// Creates a manager and starts watching the services.
+    ///
+    /// * `connection`: D-Bus connection to share.
+    pub async fn new(connection: Connection) -> Result<Self, ServiceError> {
+        let proxy = DBusProxy::new(&connection).await?;
+        let (events, _) = broadcast::channel(EVENTS_CAPACITY);
+
+        let sender = events.clone();
+        tokio::spawn(async move {
+            if let Err(error) = Self::watch(proxy, sender).await {
+                log::error!("Could not watch the Agama services: {}", error);
+            }
+        });
+
+        Ok(Self { connection, events })
+    }
+
+    /// Returns the shared D-Bus connection.
+    pub fn connection(&self) -> Connection {
+        self.connection.clone()
+    }
+
+    /// Subscribes to the changes in the Agama services.
+    pub fn subscribe(&self) -> broadcast::Receiver<ServiceEvent> {
+        self.events.subscribe()
+    }
+
+    /// Turns the `NameOwnerChanged` signals into [ServiceEvent]s.
+    ///
+    /// * `proxy`: proxy to the bus.
+    /// * `events`: channel to send the events to.
+    async fn watch(
+        proxy: DBusProxy<'_>,
+        events: broadcast::Sender<ServiceEvent>,
+    ) -> Result<(), ServiceError> {
+        let mut stream = proxy.receive_name_owner_changed().await?;
+        while let Some(signal) = stream.next().await {
+            let args = signal.args()?;
+            let name = args.name().to_string();
+            if !name.starts_with(AGAMA_SERVICES_PREFIX) {
+                continue;
+            }
+
+            let event = if args.new_owner().is_some() {
+                ServiceEvent::Started(name)
+            } else {
+                ServiceEvent::Stopped(name)
+            };
+            log::info!("Agama service changed: {:?}", &event);
+            // there might be no subscribers at this point
+            _ = events.send(event);
+        }
+        Ok(())
+    }
+}
@@ -1,39 +1,46 @@
-use curl;
-use serde_json;
-use std::io;
 use thiserror::Error;
 use zbus;
 
 #[derive(Error, Debug)]
 pub enum ServiceError {
-    #[error("D-Bus service error: {0}")]
+    #[error("{}", crate::tr!("D-Bus service error: {}", .0))]
     DBus(#[from] zbus::Error),
-    #[error("Could not connect to Agama bus at '{0}': {1}")]
+    #[error("{}", crate::tr!("Could not connect to Agama bus at '{}': {}", .0, .1))]
     DBusConnectionError(String, #[source] zbus::Error),
     // it's fine to say only "Error" because the original
     // specific error will be printed too
-    #[error("Error: {0}")]
+    #[error("{}", crate::tr!("Error: {}", .0))]
     Anyhow(#[from] anyhow::Error),
-    #[error("Wrong user parameters: '{0:?}'")]
+    #[error("{}", crate::tr!("Wrong user parameters: '{}'", format!("{:?}", .0)))]
     WrongUser(Vec<String>),
-    #[error("Registration failed: '{0}'")]
+    #[error("{}", crate::tr!("Could not import the SSH host keys: '{}'", format!("{:?}", .0)))]
+    SshHostKeys(Vec<String>),
+    #[error("{}", crate::tr!("Registration failed: '{}'", .0))]
     FailedRegistration(String),
-    #[error("Failed to find these patterns: {0:?}")]
+    #[error("{}", crate::tr!("Failed to find these patterns: {}", format!("{:?}", .0)))]
     UnknownPatterns(Vec<String>),
-    #[error("Could not perform action '{0}'")]
+    #[error("{}", crate::tr!("Could not perform action '{}'", .0))]
     UnsuccessfulAction(String),
+    #[error("{}", crate::tr!("The operation failed: {}", .0))]
+    FailedOperation(String),
+    #[error("{0}")]
+    Remote(#[from] crate::remote::RemoteError),
+    #[cfg(feature = "network-client")]
+    #[error("{0}")]
+    InvalidSSID(#[from] crate::network::types::InvalidSSID),
 }
 
+#[cfg(feature = "transfer")]
 #[derive(Error, Debug)]
 pub enum ProfileError {
-    #[error("Could not read the profile")]
+    #[error("{}", crate::tr!("Could not read the profile"))]
     Unreachable(#[from] curl::Error),
-    #[error("Jsonnet evaluation failed:\n{0}")]
+    #[error("{}", crate::tr!("Jsonnet evaluation failed:\n{}", .0))]
     EvaluationError(String),
-    #[error("I/O error")]
-    InputOutputError(#[from] io::Error),
-    #[error("The profile is not a valid JSON file")]
+    #[error("{}", crate::tr!("I/O error"))]
+    InputOutputError(#[from] std::io::Error),
+    #[error("{}", crate::tr!("The profile is not a valid JSON file"))]
     FormatError(#[from] serde_json::Error),
-    #[error("Error: {0}")]
+    #[error("{}", crate::tr!("Error: {}", .0))]
     Anyhow(#[from] anyhow::Error),
 }
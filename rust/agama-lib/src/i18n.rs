@@ -0,0 +1,228 @@
+//! Translation of the user-facing messages.
+//!
+//! The messages are translated using gettext (`agama-cli` text domain). They are marked with the
+//! [tr](crate::tr) macro, which replaces the `{}` placeholders after translating the text.
+//! Translators can reorder the arguments using numbered placeholders (`{0}`, `{1}`, etc.).
+//!
+//! ```
+//! # use agama_lib::tr;
+//! let message = tr!("Could not download {}", "http://example.net/profile.json");
+//! ```
+//!
+//! ## Locale negotiation
+//!
+//! The locale is taken from the environment (`LC_ALL`, `LC_MESSAGES` or `LANG`, as usual). If it
+//! is not set (or it is "C" or "POSIX"), the `UILocale` of the localization service is used
+//! instead, so the messages use the same language as the web user interface (see [negotiate]).
+//!
+//! The `share/update-pot.sh` script extracts the messages into the `po/agama-cli.pot` file.
+
+use crate::localization::LocalizationClient;
+use gettextrs::{
+    bind_textdomain_codeset, bindtextdomain, gettext, ngettext, setlocale, textdomain,
+    LocaleCategory,
+};
+use std::{env, time::Duration};
+use zbus::Connection;
+
+/// Text domain of the messages.
+pub const TEXT_DOMAIN: &str = "agama-cli";
+/// Directory containing the compiled translations.
+pub const LOCALE_DIR: &str = "/usr/share/locale";
+
+/// Environment variables defining the locale of the messages, by precedence.
+const LOCALE_VARS: [&str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+/// Maximum time to wait for the localization service.
+const UI_LOCALE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Translates a message.
+///
+/// * `msgid`: message to translate.
+pub fn tr(msgid: &str) -> String {
+    gettext(msgid)
+}
+
+/// Translates a message with singular and plural forms.
+///
+/// * `singular`: singular form of the message.
+/// * `plural`: plural form of the message.
+/// * `n`: number which determines the form to use.
+pub fn ntr(singular: &str, plural: &str, n: u32) -> String {
+    ngettext(singular, plural, n)
+}
+
+/// Replaces the placeholders of a (translated) message.
+///
+/// The `{}` placeholders are replaced by the arguments in order, while the numbered ones (`{0}`,
+/// `{1}`, etc.) are replaced by the argument with the same index. The unknown placeholders are
+/// kept.
+///
+/// * `template`: message containing the placeholders.
+/// * `args`: values to insert.
+pub fn fill(template: &str, args: &[String]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let placeholder = &rest[1..end];
+        let index = if placeholder.is_empty() {
+            next += 1;
+            Some(next - 1)
+        } else {
+            placeholder.parse::<usize>().ok()
+        };
+        match index.and_then(|i| args.get(i)) {
+            Some(value) => result.push_str(value),
+            None => result.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Translates a message and fills its placeholders (see [fill]).
+#[macro_export]
+macro_rules! tr {
+    ($msgid:literal) => {
+        $crate::i18n::tr($msgid)
+    };
+    ($msgid:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill(&$crate::i18n::tr($msgid), &[$($arg.to_string()),+])
+    };
+}
+
+/// Returns the locale for the messages defined in the environment.
+///
+/// It returns `None` if no locale is defined or if it is "C" or "POSIX".
+///
+/// * `var`: function to read an environment variable.
+pub fn env_locale(var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let locale = LOCALE_VARS
+        .iter()
+        .filter_map(|name| var(name))
+        .find(|value| !value.is_empty())?;
+    let language = locale.split(['.', '@']).next().unwrap_or_default();
+    if language == "C" || language == "POSIX" {
+        return None;
+    }
+    Some(locale)
+}
+
+/// Decides which locale to use for the messages.
+///
+/// The locale from the environment takes precedence over the one of the user interface.
+///
+/// * `env`: locale defined in the environment (see [env_locale]).
+/// * `ui_locale`: locale of the user interface (e.g., "cs_CZ").
+pub fn negotiate(env: Option<String>, ui_locale: Option<String>) -> Option<String> {
+    env.or(ui_locale.filter(|l| !l.is_empty()))
+}
+
+/// Returns the locale of the user interface from the localization service.
+///
+/// It returns `None` if the service cannot be reached in a reasonable time.
+///
+/// * `connection`: D-Bus connection.
+pub async fn ui_locale(connection: Connection) -> Option<String> {
+    let client = LocalizationClient::new(connection)
+        .await
+        .ok()?
+        .with_timeout(UI_LOCALE_TIMEOUT);
+    client.ui_locale().await.ok()
+}
+
+/// Sets up the translations.
+///
+/// * `locale`: locale for the messages (e.g., "cs_CZ"). If it is `None`, the environment decides.
+pub fn init(locale: Option<&str>) {
+    setlocale(LocaleCategory::LcAll, "");
+    if let Some(locale) = locale {
+        set_messages_locale(locale);
+    }
+
+    if let Err(error) = bindtextdomain(TEXT_DOMAIN, LOCALE_DIR)
+        .and_then(|_| bind_textdomain_codeset(TEXT_DOMAIN, "UTF-8"))
+        .and_then(|_| textdomain(TEXT_DOMAIN))
+    {
+        log::warn!("Could not set up the translations: {}", error);
+    }
+}
+
+/// Sets the locale for the messages.
+///
+/// If the locale is not available in the system, the translations are selected through the
+/// `LANGUAGE` variable, which gettext honors unless the locale is "C".
+///
+/// * `locale`: locale for the messages (e.g., "cs_CZ" or "cs_CZ.UTF-8").
+fn set_messages_locale(locale: &str) {
+    let with_codeset = if locale.contains('.') {
+        locale.to_string()
+    } else {
+        format!("{locale}.UTF-8")
+    };
+    if setlocale(LocaleCategory::LcMessages, with_codeset).is_some() {
+        return;
+    }
+
+    let language = locale.split(['.', '@']).next().unwrap_or(locale);
+    env::set_var("LANGUAGE", language);
+    setlocale(LocaleCategory::LcMessages, "C.UTF-8");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_fill() {
+        let args = ["a".to_string(), "b".to_string()];
+        assert_eq!(fill("{} and {}", &args), "a and b");
+        assert_eq!(fill("{1} before {0}", &args), "b before a");
+        assert_eq!(fill("{} and {} and {}", &args), "a and b and {}");
+        assert_eq!(fill("{name} is {", &args), "{name} is {");
+        assert_eq!(fill("no placeholders", &args), "no placeholders");
+    }
+
+    #[test]
+    fn test_tr() {
+        // use a variable, so the text is not extracted for translation
+        let msgid = "Not translated";
+        assert_eq!(tr(msgid), msgid);
+    }
+
+    #[test]
+    fn test_env_locale() {
+        let vars = HashMap::from([
+            ("LC_MESSAGES", "de_DE.UTF-8".to_string()),
+            ("LANG", "cs_CZ.UTF-8".to_string()),
+        ]);
+        let var = |name: &str| vars.get(name).cloned();
+        assert_eq!(env_locale(var), Some("de_DE.UTF-8".to_string()));
+
+        let vars = HashMap::from([("LC_ALL", "".to_string()), ("LANG", "C.UTF-8".to_string())]);
+        let var = |name: &str| vars.get(name).cloned();
+        assert_eq!(env_locale(var), None);
+
+        assert_eq!(env_locale(|_| None), None);
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(
+            negotiate(Some("de_DE.UTF-8".to_string()), Some("cs_CZ".to_string())),
+            Some("de_DE.UTF-8".to_string())
+        );
+        assert_eq!(
+            negotiate(None, Some("cs_CZ".to_string())),
+            Some("cs_CZ".to_string())
+        );
+        assert_eq!(negotiate(None, Some("".to_string())), None);
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
 };
 use agama_settings::Settings;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::default::Default;
 use std::str::FromStr;
 
@@ -114,4 +115,279 @@ impl InstallSettings {
         }
         scopes
     }
+
+    /// Returns a copy of the settings with the given fragment applied on top.
+    ///
+    /// Unlike [Settings::merge](agama_settings::settings::Settings::merge), which replaces
+    /// collections as a whole, patching only overrides the keys that are present in the
+    /// fragment. It allows layering several profiles (e.g., site, base and host ones). See
+    /// [merge_patch] for the details.
+    ///
+    /// * `fragment`: JSON fragment to apply.
+    pub fn patch(&self, fragment: &Value) -> Result<Self, serde_json::Error> {
+        let mut value = serde_json::to_value(self)?;
+        merge_patch(&mut value, fragment);
+        serde_json::from_value(value)
+    }
+
+    /// Returns the settings as an installation profile.
+    ///
+    /// The unset values are omitted, as the profiles do not accept `null` values.
+    pub fn to_profile(&self) -> Result<Value, serde_json::Error> {
+        let mut value = serde_json::to_value(self)?;
+        remove_nulls(&mut value);
+        Ok(value)
+    }
+}
+
+/// Removes the `null` values from the objects in a JSON document.
+///
+/// * `value`: document to clean up.
+fn remove_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(remove_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
+
+/// Applies a JSON fragment to a JSON document.
+///
+/// It follows the [JSON Merge Patch](https://www.rfc-editor.org/rfc/rfc7396) semantics with an
+/// extension for collections:
+///
+/// * Objects are merged recursively and a `null` value removes the key.
+/// * Arrays of objects identified by an `id` key (e.g., network connections) are merged by `id`:
+///   known elements are patched and unknown ones are appended.
+/// * Any other value (including other arrays) replaces the original one.
+///
+/// * `target`: document to update.
+/// * `patch`: fragment to apply.
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    match (target, patch) {
+        (Value::Object(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.remove(key);
+                    continue;
+                }
+
+                match target.get_mut(key) {
+                    Some(current) => merge_patch(current, value),
+                    None => {
+                        let mut current = Value::Null;
+                        merge_patch(&mut current, value);
+                        target.insert(key.clone(), current);
+                    }
+                }
+            }
+        }
+        (Value::Array(target), Value::Array(patch)) if is_identified_collection(patch) => {
+            for element in patch {
+                let existing = target
+                    .iter_mut()
+                    .find(|e| e.is_object() && e.get("id") == element.get("id"));
+                match existing {
+                    Some(current) => merge_patch(current, element),
+                    None => target.push(element.clone()),
+                }
+            }
+        }
+        (target, Value::Object(_)) => {
+            *target = Value::Object(Default::default());
+            merge_patch(target, patch);
+        }
+        (target, patch) => *target = patch.clone(),
+    }
+}
+
+/// Returns the fragment that, applied to `base` with [merge_patch], gives `current`.
+///
+/// It is the inverse of [merge_patch], so it follows the same rules. The keys that are missing in
+/// `current` are ignored, as the fragment only contains values which were set or changed. It
+/// returns `None` if there are no differences.
+///
+/// * `current`: document to compare.
+/// * `base`: document to compare with (e.g., the default settings).
+pub fn diff_patch(current: &Value, base: &Value) -> Option<Value> {
+    match (current, base) {
+        (Value::Object(current), Value::Object(base)) => {
+            let diff: serde_json::Map<String, Value> = current
+                .iter()
+                .filter_map(|(key, value)| {
+                    let diff = match base.get(key) {
+                        Some(base_value) => diff_patch(value, base_value)?,
+                        None => value.clone(),
+                    };
+                    Some((key.clone(), diff))
+                })
+                .collect();
+            (!diff.is_empty()).then_some(Value::Object(diff))
+        }
+        (Value::Array(current), Value::Array(base)) if is_identified_collection(current) => {
+            let diff: Vec<Value> = current
+                .iter()
+                .filter_map(|element| {
+                    let Some(existing) = base.iter().find(|e| e.get("id") == element.get("id"))
+                    else {
+                        return Some(element.clone());
+                    };
+                    let mut diff = diff_patch(element, existing)?;
+                    diff["id"] = element["id"].clone();
+                    Some(diff)
+                })
+                .collect();
+            (!diff.is_empty()).then_some(Value::Array(diff))
+        }
+        (current, base) if current == base => None,
+        (current, _) => Some(current.clone()),
+    }
+}
+
+/// Determines whether all the elements of a collection are objects with an `id` key.
+fn is_identified_collection(elements: &[Value]) -> bool {
+    !elements.is_empty()
+        && elements
+            .iter()
+            .all(|e| e.get("id").is_some_and(|id| id.is_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_patch_objects() {
+        let mut target = json!({
+            "product": { "id": "Tumbleweed" },
+            "storage": { "lvm": false, "bootDevice": "/dev/sda" }
+        });
+        let patch = json!({
+            "storage": { "lvm": true, "bootDevice": null },
+            "localization": { "language": "en_US.UTF-8" }
+        });
+        merge_patch(&mut target, &patch);
+        assert_eq!(
+            target,
+            json!({
+                "product": { "id": "Tumbleweed" },
+                "storage": { "lvm": true },
+                "localization": { "language": "en_US.UTF-8" }
+            })
+        );
+    }
+
+    #[test]
+    fn test_merge_patch_collections() {
+        let mut target = json!({
+            "connections": [
+                { "id": "eth0", "method4": "auto" },
+                { "id": "eth1", "method4": "auto" }
+            ],
+            "patterns": ["base", "gnome"]
+        });
+        let patch = json!({
+            "connections": [
+                { "id": "eth1", "method4": "manual" },
+                { "id": "eth2", "method4": "auto" }
+            ],
+            "patterns": ["kde"]
+        });
+        merge_patch(&mut target, &patch);
+        assert_eq!(
+            target,
+            json!({
+                "connections": [
+                    { "id": "eth0", "method4": "auto" },
+                    { "id": "eth1", "method4": "manual" },
+                    { "id": "eth2", "method4": "auto" }
+                ],
+                "patterns": ["kde"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_diff_patch() {
+        let base = json!({
+            "product": { "id": "Tumbleweed" },
+            "localization": { "language": "en_US.UTF-8", "keyboard": "us" },
+            "network": {
+                "connections": [
+                    { "id": "eth0", "method4": "auto" },
+                    { "id": "eth1", "method4": "auto" }
+                ]
+            },
+            "software": { "patterns": ["gnome"] }
+        });
+        let current = json!({
+            "product": { "id": "Tumbleweed" },
+            "localization": { "language": "en_US.UTF-8", "keyboard": "cz(qwerty)" },
+            "network": {
+                "connections": [
+                    { "id": "eth0", "method4": "auto" },
+                    { "id": "eth1", "method4": "manual" },
+                    { "id": "eth2", "method4": "auto" }
+                ]
+            },
+            "software": { "patterns": ["gnome"] }
+        });
+
+        let diff = diff_patch(&current, &base).unwrap();
+        assert_eq!(
+            diff,
+            json!({
+                "localization": { "keyboard": "cz(qwerty)" },
+                "network": {
+                    "connections": [
+                        { "id": "eth1", "method4": "manual" },
+                        { "id": "eth2", "method4": "auto" }
+                    ]
+                }
+            })
+        );
+
+        let mut patched = base.clone();
+        merge_patch(&mut patched, &diff);
+        assert_eq!(patched, current);
+        assert_eq!(diff_patch(&current, &current), None);
+    }
+
+    #[test]
+    fn test_patch_install_settings() {
+        let base: InstallSettings = serde_json::from_value(json!({
+            "product": { "id": "Tumbleweed" },
+            "localization": { "language": "en_US.UTF-8", "keyboard": "us" }
+        }))
+        .unwrap();
+        let host = json!({ "localization": { "keyboard": "cz(qwerty)" } });
+        let settings = base.patch(&host).unwrap();
+
+        let localization = settings.localization.unwrap();
+        assert_eq!(localization.language, Some("en_US.UTF-8".to_string()));
+        assert_eq!(localization.keyboard, Some("cz(qwerty)".to_string()));
+        assert_eq!(settings.product.unwrap().id, Some("Tumbleweed".to_string()));
+    }
+
+    #[test]
+    fn test_to_profile() {
+        let settings: InstallSettings = serde_json::from_value(json!({
+            "product": { "id": "Tumbleweed" },
+            "network": {
+                "connections": [{ "id": "eth0", "method4": "auto" }]
+            }
+        }))
+        .unwrap();
+        let profile = settings.to_profile().unwrap();
+
+        assert_eq!(profile["product"], json!({ "id": "Tumbleweed" }));
+        let connection = &profile["network"]["connections"][0];
+        assert_eq!(connection["id"], json!("eth0"));
+        assert!(connection.get("gateway4").is_none());
+        assert!(profile.get("storage").is_none());
+    }
 }
@@ -4,7 +4,10 @@
 //!
 //! * Reading and writing [installation settings](install_settings::InstallSettings).
 //! * Monitoring the [progress](progress).
+//! * Watching the Agama services through a [connection_manager::ConnectionManager].
 //! * Triggering actions through the [manager] (e.g., starting installation).
+//! * Checking whether the system is ready to be installed through the [overview].
+//! * Driving an installation running on a [remote] host.
 //!
 //! ## Handling installation settings
 //!
@@ -18,33 +21,86 @@
 //! is expected to implement the [serde::Serialize], [serde::Deserialize] and
 //! [agama_settings::settings::Settings] traits.
 //! * A store: it is the responsible for reading/writing the settings to the service. Usually, it
-//! relies on a D-Bus client for communicating with the service, although it could implement that
-//! logic itself. Note: we are considering defining a trait for stores too.
+//!   relies on a D-Bus client for communicating with the service, although it could implement
+//!   that logic itself. It implements the [SettingsStore] trait, so the [Store] can load or store
+//!   just some sections.
 //!
 //! As said, those modules might implement additional stuff, like specific types, clients, etc.
+//!
+//! ## Features
+//!
+//! The core of the library (connecting to the bus, errors, translations, questions, etc.) is always
+//! available. The rest of it is split into features, all of them enabled by default, so tools
+//! embedding only some parts do not pull unneeded dependencies:
+//!
+//! * `network-client`: the [network] client and settings.
+//! * `progress`: the [progress] monitor and the [manager] client.
+//! * `settings`: the installation settings, their stores and the clients of the rest of the
+//!   services (software, storage, users, etc.). It implies `network-client` and `progress`.
+//! * `transfer`: the [transfer] of files, the [mount] of shares and the [profile] handling.
+//! * `cli`: a terminal presenter for the progress. It implies `progress`.
+//!
+//! For instance, a tool which only monitors the progress can use:
+//!
+//! ```toml
+//! agama-lib = { version = "1.0", default-features = false, features = ["progress"] }
+//! ```
 
+pub mod connection_manager;
 pub mod error;
+pub mod i18n;
+#[cfg(feature = "settings")]
 pub mod install_settings;
 pub mod localization;
+#[cfg(feature = "progress")]
 pub mod manager;
+#[cfg(feature = "transfer")]
+pub mod mount;
+#[cfg(feature = "network-client")]
 pub mod network;
+#[cfg(feature = "settings")]
+pub mod overrides;
+pub mod overview;
+#[cfg(feature = "settings")]
 pub mod product;
+#[cfg(feature = "transfer")]
 pub mod profile;
+#[cfg(feature = "settings")]
+pub mod register;
+pub mod remote;
+pub mod secrets;
+#[cfg(feature = "settings")]
 pub mod software;
+#[cfg(feature = "settings")]
 pub mod storage;
+pub mod timeout;
+#[cfg(feature = "transfer")]
+pub mod transfer;
+#[cfg(feature = "settings")]
 pub mod users;
+#[cfg(all(test, feature = "settings"))]
+mod vcr;
 // TODO: maybe expose only clients when we have it?
 pub mod dbus;
+#[cfg(feature = "progress")]
 pub mod progress;
 pub mod proxies;
+#[cfg(feature = "settings")]
 mod store;
-pub use store::Store;
+#[cfg(feature = "settings")]
+pub use store::{SettingsStore, Store};
 pub mod questions;
 use crate::error::ServiceError;
 
 const ADDRESS: &str = "unix:path=/run/agama/bus";
 
+/// Connects to the Agama bus.
+///
+/// If a remote host is set (see [remote::set_remote_host]), it connects to the bus of that host.
 pub async fn connection() -> Result<zbus::Connection, ServiceError> {
+    if let Some(host) = remote::remote_host() {
+        return remote::connection(&host, ADDRESS).await;
+    }
     connection_to(ADDRESS).await
 }
 
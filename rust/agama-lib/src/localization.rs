@@ -2,9 +2,13 @@
 
 mod client;
 mod proxies;
+#[cfg(feature = "settings")]
 mod settings;
+#[cfg(feature = "settings")]
 mod store;
 
 pub use client::LocalizationClient;
+#[cfg(feature = "settings")]
 pub use settings::LocalizationSettings;
+#[cfg(feature = "settings")]
 pub use store::LocalizationStore;
@@ -1,45 +1,80 @@
 use super::proxies::LocaleProxy;
 use crate::error::ServiceError;
+use crate::timeout::CallTimeout;
+use std::time::Duration;
 use zbus::Connection;
 
 /// D-Bus client for the software service
 pub struct LocalizationClient<'a> {
+    timeout: CallTimeout,
     localization_proxy: LocaleProxy<'a>,
 }
 
 impl<'a> LocalizationClient<'a> {
     pub async fn new(connection: Connection) -> Result<LocalizationClient<'a>, ServiceError> {
         Ok(Self {
+            timeout: CallTimeout::default(),
             localization_proxy: LocaleProxy::new(&connection).await?,
         })
     }
 
+    /// Sets the timeout for the D-Bus calls.
+    ///
+    /// * `timeout`: time to wait for each call.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = CallTimeout::new(timeout);
+        self
+    }
+
     pub async fn language(&self) -> Result<Option<String>, ServiceError> {
-        let locales = self.localization_proxy.locales().await?;
-        let mut iter = locales.into_iter();
-        let first = iter.next();
-        // may be None
-        Ok(first)
+        self.timeout
+            .run(async {
+                let locales = self.localization_proxy.locales().await?;
+                let mut iter = locales.into_iter();
+                let first = iter.next();
+                // may be None
+                Ok(first)
+            })
+            .await
+    }
+
+    /// Returns the locale of the user interface (e.g., "cs_CZ")
+    pub async fn ui_locale(&self) -> Result<String, ServiceError> {
+        self.timeout
+            .run(async { Ok(self.localization_proxy.uilocale().await?) })
+            .await
     }
 
     pub async fn keyboard(&self) -> Result<String, ServiceError> {
-        Ok(self.localization_proxy.keymap().await?)
+        self.timeout
+            .run(async { Ok(self.localization_proxy.keymap().await?) })
+            .await
     }
 
     pub async fn timezone(&self) -> Result<String, ServiceError> {
-        Ok(self.localization_proxy.timezone().await?)
+        self.timeout
+            .run(async { Ok(self.localization_proxy.timezone().await?) })
+            .await
     }
 
     pub async fn set_language(&self, language: &str) -> zbus::Result<()> {
-        let locales = [language];
-        self.localization_proxy.set_locales(&locales).await
+        self.timeout
+            .run(async {
+                let locales = [language];
+                self.localization_proxy.set_locales(&locales).await
+            })
+            .await
     }
 
     pub async fn set_keyboard(&self, keyboard: &str) -> zbus::Result<()> {
-        self.localization_proxy.set_keymap(keyboard).await
+        self.timeout
+            .run(async { self.localization_proxy.set_keymap(keyboard).await })
+            .await
     }
 
     pub async fn set_timezone(&self, timezone: &str) -> zbus::Result<()> {
-        self.localization_proxy.set_timezone(timezone).await
+        self.timeout
+            .run(async { self.localization_proxy.set_timezone(timezone).await })
+            .await
     }
 }
@@ -3,6 +3,9 @@
 
 use super::{LocalizationClient, LocalizationSettings};
 use crate::error::ServiceError;
+use crate::install_settings::{InstallSettings, Scope};
+use crate::store::SettingsStore;
+use async_trait::async_trait;
 use zbus::Connection;
 
 /// Loads and stores the storage settings from/to the D-Bus service.
@@ -49,3 +52,22 @@ impl<'a> LocalizationStore<'a> {
         Ok(())
     }
 }
+
+#[async_trait]
+impl<'a> SettingsStore for LocalizationStore<'a> {
+    fn scope(&self) -> Scope {
+        Scope::Localization
+    }
+
+    async fn load_section(&self, settings: &mut InstallSettings) -> Result<(), ServiceError> {
+        settings.localization = Some(self.load().await?);
+        Ok(())
+    }
+
+    async fn store_section(&self, settings: &InstallSettings) -> Result<(), ServiceError> {
+        if let Some(localization) = &settings.localization {
+            self.store(localization).await?;
+        }
+        Ok(())
+    }
+}
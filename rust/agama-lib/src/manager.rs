@@ -1,14 +1,20 @@
 use crate::error::ServiceError;
 use crate::proxies::ServiceStatusProxy;
+use crate::timeout::CallTimeout;
 use crate::{
     progress::Progress,
     proxies::{ManagerProxy, ProgressProxy},
 };
-use tokio_stream::StreamExt;
+use std::time::Duration;
+use tokio_stream::{Stream, StreamExt};
 use zbus::Connection;
 
+/// Interval between checks for new log lines when following the logs.
+const FOLLOW_LOGS_INTERVAL: Duration = Duration::from_secs(1);
+
 /// D-Bus client for the manager service
 pub struct ManagerClient<'a> {
+    timeout: CallTimeout,
     manager_proxy: ManagerProxy<'a>,
     progress_proxy: ProgressProxy<'a>,
     status_proxy: ServiceStatusProxy<'a>,
@@ -17,14 +23,25 @@ pub struct ManagerClient<'a> {
 impl<'a> ManagerClient<'a> {
     pub async fn new(connection: Connection) -> zbus::Result<ManagerClient<'a>> {
         Ok(Self {
+            timeout: CallTimeout::default(),
             manager_proxy: ManagerProxy::new(&connection).await?,
             progress_proxy: ProgressProxy::new(&connection).await?,
             status_proxy: ServiceStatusProxy::new(&connection).await?,
         })
     }
 
+    /// Sets the timeout for the D-Bus calls.
+    ///
+    /// * `timeout`: time to wait for each call.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = CallTimeout::new(timeout);
+        self
+    }
+
     pub async fn busy_services(&self) -> Result<Vec<String>, ServiceError> {
-        Ok(self.manager_proxy.busy_services().await?)
+        self.timeout
+            .run(async { Ok(self.manager_proxy.busy_services().await?) })
+            .await
     }
 
     pub async fn probe(&self) -> Result<(), ServiceError> {
@@ -37,11 +54,63 @@ impl<'a> ManagerClient<'a> {
     }
 
     pub async fn can_install(&self) -> Result<bool, ServiceError> {
-        Ok(self.manager_proxy.can_install().await?)
+        self.timeout
+            .run(async { Ok(self.manager_proxy.can_install().await?) })
+            .await
     }
 
     pub async fn progress(&self) -> zbus::Result<Progress> {
-        Progress::from_proxy(&self.progress_proxy).await
+        self.timeout
+            .run(async { Progress::from_proxy(&self.progress_proxy).await })
+            .await
+    }
+
+    /// Returns the most recent log lines of an Agama service.
+    ///
+    /// * `service`: service name ("manager", "software", "storage" or "users").
+    /// * `lines`: maximum number of lines to return.
+    pub async fn recent_logs(
+        &self,
+        service: &str,
+        lines: u32,
+    ) -> Result<Vec<String>, ServiceError> {
+        let (logs, _) = self.manager_proxy.get_recent_logs(service, lines).await?;
+        Ok(logs)
+    }
+
+    /// Returns a stream of the log lines of an Agama service, starting with the most recent ones.
+    ///
+    /// Each item contains the lines written since the previous one, which are checked
+    /// periodically. The stream ends after the first failed call.
+    ///
+    /// * `service`: service name ("manager", "software", "storage" or "users").
+    /// * `lines`: maximum number of already existing lines to include in the first item.
+    pub fn follow_logs(
+        &self,
+        service: &str,
+        lines: u32,
+    ) -> impl Stream<Item = Result<Vec<String>, ServiceError>> + 'a {
+        let proxy = self.manager_proxy.clone();
+        let service = service.to_string();
+        // the state is the cursor of the last line (None if nothing was read yet), or None to
+        // stop the stream
+        futures_util::stream::unfold(Some(None), move |state: Option<Option<String>>| {
+            let proxy = proxy.clone();
+            let service = service.clone();
+            async move {
+                let result = match state? {
+                    None => proxy.get_recent_logs(&service, lines).await,
+                    Some(cursor) => {
+                        tokio::time::sleep(FOLLOW_LOGS_INTERVAL).await;
+                        proxy.get_logs_after(&service, &cursor).await
+                    }
+                };
+                match result {
+                    Ok((logs, cursor)) => Some((Ok(logs), Some(Some(cursor)))),
+                    Err(error) => Some((Err(error.into()), None)),
+                }
+            }
+        })
     }
 
     /// Returns whether the service is busy or not
@@ -1,11 +1,15 @@
 //! Implements support for handling the network settings
 
+pub mod builder;
 mod client;
 mod proxies;
 pub mod settings;
+#[cfg(feature = "settings")]
 mod store;
 pub mod types;
 
+pub use builder::ConnectionBuilder;
 pub use client::NetworkClient;
 pub use settings::NetworkSettings;
+#[cfg(feature = "settings")]
 pub use store::NetworkStore;
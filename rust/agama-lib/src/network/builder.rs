@@ -0,0 +1,644 @@
+//! Strongly-typed builder for network connections
+//!
+//! Building a [NetworkConnection] by hand makes it easy to end up with settings that do not
+//! make sense (e.g., wireless settings on a bond), which are only rejected by the network
+//! service. [ConnectionBuilder] uses a type parameter to track the kind of connection, so only
+//! the relevant setters are available.
+//!
+//! ```
+//! # use agama_lib::network::builder::{ConnectionBuilder, Ipv4Method, WirelessSecurity};
+//! let wlan = ConnectionBuilder::wireless("Home", "MyWifi")
+//!     .security(WirelessSecurity::WpaPsk, "secret")
+//!     .method4(Ipv4Method::Auto)
+//!     .build();
+//! assert_eq!(wlan.wireless.unwrap().ssid, "MyWifi");
+//!
+//! let bond = ConnectionBuilder::bond("bond0")
+//!     .mode("active-backup")
+//!     .port("eth0")
+//!     .port("eth1")
+//!     .address("192.168.122.100/24".parse().unwrap())
+//!     .build();
+//! assert_eq!(bond.method4, Some("manual".to_string()));
+//! ```
+//!
+//! Setting the ports of an Ethernet connection does not compile:
+//!
+//! ```compile_fail
+//! # use agama_lib::network::builder::ConnectionBuilder;
+//! let eth0 = ConnectionBuilder::ethernet("eth0").port("eth1").build();
+//! ```
+
+use super::settings::{
+    BondSettings, IpvlanSettings, MacsecSettings, MacvlanSettings, ModemSettings,
+    NetworkConnection, PppoeSettings, VrfSettings, WirelessSettings,
+};
+use cidr::IpInet;
+use std::{fmt, marker::PhantomData, net::IpAddr};
+
+/// Ethernet connection (the default kind).
+pub struct Ethernet;
+/// Wireless connection.
+pub struct Wireless;
+/// Bonding connection.
+pub struct Bond;
+/// PPPoE connection.
+pub struct Pppoe;
+/// Mobile broadband connection.
+pub struct Modem;
+/// VRF connection.
+pub struct Vrf;
+/// MACVLAN connection.
+pub struct Macvlan;
+/// IPVLAN connection.
+pub struct Ipvlan;
+/// MACsec connection.
+pub struct Macsec;
+
+/// IPv4 configuration method
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ipv4Method {
+    Disabled,
+    Auto,
+    Manual,
+    LinkLocal,
+    Shared,
+}
+
+impl fmt::Display for Ipv4Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Ipv4Method::Disabled => "disabled",
+            Ipv4Method::Auto => "auto",
+            Ipv4Method::Manual => "manual",
+            Ipv4Method::LinkLocal => "link-local",
+            Ipv4Method::Shared => "shared",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// IPv6 configuration method
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ipv6Method {
+    Disabled,
+    Auto,
+    Manual,
+    LinkLocal,
+    Ignore,
+    Dhcp,
+}
+
+impl fmt::Display for Ipv6Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Ipv6Method::Disabled => "disabled",
+            Ipv6Method::Auto => "auto",
+            Ipv6Method::Manual => "manual",
+            Ipv6Method::LinkLocal => "link-local",
+            Ipv6Method::Ignore => "ignore",
+            Ipv6Method::Dhcp => "dhcp",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Wireless security protocol
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WirelessSecurity {
+    /// No encryption (or WEP)
+    None,
+    /// Opportunistic Wireless Encryption
+    Owe,
+    /// WPA2 + WPA3 personal
+    WpaPsk,
+    /// WPA3 personal only
+    Sae,
+}
+
+impl fmt::Display for WirelessSecurity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            WirelessSecurity::None => "none",
+            WirelessSecurity::Owe => "owe",
+            WirelessSecurity::WpaPsk => "wpa-psk",
+            WirelessSecurity::Sae => "sae",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Builds a [NetworkConnection] of a given kind
+///
+/// Use one of the constructors ([ConnectionBuilder::ethernet], [ConnectionBuilder::wireless],
+/// etc.) to select the kind of connection.
+pub struct ConnectionBuilder<T> {
+    connection: NetworkConnection,
+    kind: PhantomData<T>,
+}
+
+impl<T> ConnectionBuilder<T> {
+    fn with_connection(connection: NetworkConnection) -> Self {
+        Self {
+            connection,
+            kind: PhantomData,
+        }
+    }
+
+    /// Sets the interface name.
+    pub fn interface(mut self, name: &str) -> Self {
+        self.connection.interface = Some(name.to_string());
+        self
+    }
+
+    /// Sets the MAC address.
+    pub fn mac_address(mut self, address: &str) -> Self {
+        self.connection.mac_address = Some(address.to_string());
+        self
+    }
+
+    /// Sets the IPv4 configuration method.
+    pub fn method4(mut self, method: Ipv4Method) -> Self {
+        self.connection.method4 = Some(method.to_string());
+        self
+    }
+
+    /// Sets the IPv6 configuration method.
+    pub fn method6(mut self, method: Ipv6Method) -> Self {
+        self.connection.method6 = Some(method.to_string());
+        self
+    }
+
+    /// Adds a static address.
+    ///
+    /// If no method was set for the address family, it is set to "manual".
+    pub fn address(mut self, address: IpInet) -> Self {
+        let method = if address.is_ipv4() {
+            &mut self.connection.method4
+        } else {
+            &mut self.connection.method6
+        };
+        method.get_or_insert_with(|| "manual".to_string());
+        self.connection.addresses.push(address);
+        self
+    }
+
+    /// Sets the gateway for the family of the given address.
+    pub fn gateway(mut self, gateway: IpAddr) -> Self {
+        match gateway {
+            IpAddr::V4(_) => self.connection.gateway4 = Some(gateway),
+            IpAddr::V6(_) => self.connection.gateway6 = Some(gateway),
+        }
+        self
+    }
+
+    /// Adds a nameserver.
+    pub fn nameserver(mut self, nameserver: IpAddr) -> Self {
+        self.connection.nameservers.push(nameserver);
+        self
+    }
+
+    /// Sets the description.
+    pub fn description(mut self, description: &str) -> Self {
+        self.connection.description = Some(description.to_string());
+        self
+    }
+
+    /// Returns the connection.
+    pub fn build(self) -> NetworkConnection {
+        self.connection
+    }
+}
+
+impl ConnectionBuilder<Ethernet> {
+    /// Starts building an Ethernet connection.
+    ///
+    /// * `id`: connection ID.
+    pub fn ethernet(id: &str) -> Self {
+        Self::with_connection(NetworkConnection {
+            id: id.to_string(),
+            ..Default::default()
+        })
+    }
+
+    /// Sets the parent connection (e.g., for a VLAN).
+    pub fn parent(mut self, parent: &str) -> Self {
+        self.connection.parent = Some(parent.to_string());
+        self
+    }
+}
+
+impl ConnectionBuilder<Wireless> {
+    /// Starts building a wireless connection (an unprotected one in infrastructure mode).
+    ///
+    /// * `id`: connection ID.
+    /// * `ssid`: SSID of the network.
+    pub fn wireless(id: &str, ssid: &str) -> Self {
+        let wireless = WirelessSettings {
+            ssid: ssid.to_string(),
+            security: WirelessSecurity::None.to_string(),
+            mode: "infrastructure".to_string(),
+            ..Default::default()
+        };
+        Self::with_connection(NetworkConnection {
+            id: id.to_string(),
+            wireless: Some(wireless),
+            ..Default::default()
+        })
+    }
+
+    /// Sets the security protocol and the password.
+    pub fn security(mut self, security: WirelessSecurity, password: &str) -> Self {
+        let wireless = self.wireless_mut();
+        wireless.security = security.to_string();
+        wireless.password = Some(password.to_string());
+        self
+    }
+
+    /// Uses the access point mode instead of the infrastructure one.
+    ///
+    /// * `band`: frequency band ("a" or "bg").
+    /// * `channel`: channel to use.
+    pub fn access_point(mut self, band: Option<&str>, channel: Option<u32>) -> Self {
+        let wireless = self.wireless_mut();
+        wireless.mode = "ap".to_string();
+        wireless.band = band.map(str::to_string);
+        wireless.channel = channel;
+        self
+    }
+
+    fn wireless_mut(&mut self) -> &mut WirelessSettings {
+        self.connection
+            .wireless
+            .as_mut()
+            .expect("wireless settings are always set")
+    }
+}
+
+impl ConnectionBuilder<Bond> {
+    /// Starts building a bonding connection ("balance-rr" mode and no ports).
+    ///
+    /// * `id`: connection ID.
+    pub fn bond(id: &str) -> Self {
+        Self::with_connection(NetworkConnection {
+            id: id.to_string(),
+            bond: Some(BondSettings::default()),
+            ..Default::default()
+        })
+    }
+
+    /// Sets the bonding mode (e.g., "active-backup").
+    pub fn mode(mut self, mode: &str) -> Self {
+        self.bond_mut().mode = mode.to_string();
+        self
+    }
+
+    /// Sets the bonding options (e.g., "miimon=100").
+    pub fn options(mut self, options: &str) -> Self {
+        self.bond_mut().options = Some(options.to_string());
+        self
+    }
+
+    /// Adds a port.
+    ///
+    /// * `name`: interface or connection ID of the port.
+    pub fn port(mut self, name: &str) -> Self {
+        self.bond_mut().ports.push(name.to_string());
+        self
+    }
+
+    fn bond_mut(&mut self) -> &mut BondSettings {
+        self.connection
+            .bond
+            .as_mut()
+            .expect("bonding settings are always set")
+    }
+}
+
+impl ConnectionBuilder<Pppoe> {
+    /// Starts building a PPPoE connection.
+    ///
+    /// * `id`: connection ID.
+    /// * `parent`: Ethernet interface to establish the session on.
+    /// * `username`: user name to authenticate with.
+    pub fn pppoe(id: &str, parent: &str, username: &str) -> Self {
+        let pppoe = PppoeSettings {
+            parent: parent.to_string(),
+            username: username.to_string(),
+            ..Default::default()
+        };
+        Self::with_connection(NetworkConnection {
+            id: id.to_string(),
+            pppoe: Some(pppoe),
+            ..Default::default()
+        })
+    }
+
+    /// Sets the password.
+    pub fn password(mut self, password: &str) -> Self {
+        self.pppoe_mut().password = Some(password.to_string());
+        self
+    }
+
+    /// Sets the service name.
+    pub fn service(mut self, service: &str) -> Self {
+        self.pppoe_mut().service = Some(service.to_string());
+        self
+    }
+
+    fn pppoe_mut(&mut self) -> &mut PppoeSettings {
+        self.connection
+            .pppoe
+            .as_mut()
+            .expect("PPPoE settings are always set")
+    }
+}
+
+impl ConnectionBuilder<Modem> {
+    /// Starts building a mobile broadband connection.
+    ///
+    /// * `id`: connection ID.
+    /// * `apn`: Access Point Name of the carrier.
+    pub fn modem(id: &str, apn: &str) -> Self {
+        let modem = ModemSettings {
+            apn: apn.to_string(),
+            ..Default::default()
+        };
+        Self::with_connection(NetworkConnection {
+            id: id.to_string(),
+            modem: Some(modem),
+            ..Default::default()
+        })
+    }
+
+    /// Sets the PIN to unlock the SIM card.
+    pub fn pin(mut self, pin: &str) -> Self {
+        self.modem_mut().pin = Some(pin.to_string());
+        self
+    }
+
+    /// Sets the credentials.
+    pub fn credentials(mut self, username: &str, password: &str) -> Self {
+        let modem = self.modem_mut();
+        modem.username = Some(username.to_string());
+        modem.password = Some(password.to_string());
+        self
+    }
+
+    fn modem_mut(&mut self) -> &mut ModemSettings {
+        self.connection
+            .modem
+            .as_mut()
+            .expect("modem settings are always set")
+    }
+}
+
+impl ConnectionBuilder<Vrf> {
+    /// Starts building a VRF connection (no ports).
+    ///
+    /// * `id`: connection ID.
+    /// * `table`: routing table ID.
+    pub fn vrf(id: &str, table: u32) -> Self {
+        let vrf = VrfSettings {
+            table,
+            ..Default::default()
+        };
+        Self::with_connection(NetworkConnection {
+            id: id.to_string(),
+            vrf: Some(vrf),
+            ..Default::default()
+        })
+    }
+
+    /// Adds a port.
+    ///
+    /// * `name`: interface or connection ID of the port.
+    pub fn port(mut self, name: &str) -> Self {
+        self.vrf_mut().ports.push(name.to_string());
+        self
+    }
+
+    fn vrf_mut(&mut self) -> &mut VrfSettings {
+        self.connection
+            .vrf
+            .as_mut()
+            .expect("VRF settings are always set")
+    }
+}
+
+impl ConnectionBuilder<Macvlan> {
+    /// Starts building a MACVLAN connection.
+    ///
+    /// * `id`: connection ID.
+    /// * `parent`: parent interface name or connection UUID.
+    pub fn macvlan(id: &str, parent: &str) -> Self {
+        let macvlan = MacvlanSettings {
+            parent: parent.to_string(),
+            ..Default::default()
+        };
+        Self::with_connection(NetworkConnection {
+            id: id.to_string(),
+            macvlan: Some(macvlan),
+            ..Default::default()
+        })
+    }
+
+    /// Sets the MACVLAN mode (e.g., "bridge").
+    pub fn mode(mut self, mode: &str) -> Self {
+        self.connection
+            .macvlan
+            .as_mut()
+            .expect("MACVLAN settings are always set")
+            .mode = Some(mode.to_string());
+        self
+    }
+}
+
+impl ConnectionBuilder<Ipvlan> {
+    /// Starts building an IPVLAN connection.
+    ///
+    /// * `id`: connection ID.
+    /// * `parent`: parent interface name or connection UUID.
+    pub fn ipvlan(id: &str, parent: &str) -> Self {
+        let ipvlan = IpvlanSettings {
+            parent: parent.to_string(),
+            ..Default::default()
+        };
+        Self::with_connection(NetworkConnection {
+            id: id.to_string(),
+            ipvlan: Some(ipvlan),
+            ..Default::default()
+        })
+    }
+
+    /// Sets the IPVLAN mode (e.g., "l3").
+    pub fn mode(mut self, mode: &str) -> Self {
+        self.connection
+            .ipvlan
+            .as_mut()
+            .expect("IPVLAN settings are always set")
+            .mode = Some(mode.to_string());
+        self
+    }
+}
+
+impl ConnectionBuilder<Macsec> {
+    /// Starts building a MACsec connection.
+    ///
+    /// * `id`: connection ID.
+    /// * `parent`: parent interface name or connection UUID.
+    pub fn macsec(id: &str, parent: &str) -> Self {
+        let macsec = MacsecSettings {
+            parent: parent.to_string(),
+            ..Default::default()
+        };
+        Self::with_connection(NetworkConnection {
+            id: id.to_string(),
+            macsec: Some(macsec),
+            ..Default::default()
+        })
+    }
+
+    /// Uses a pre-shared key (PSK mode).
+    ///
+    /// * `cak`: Connectivity Association Key.
+    /// * `ckn`: Connectivity Association Key Name.
+    pub fn psk(mut self, cak: &str, ckn: &str) -> Self {
+        let macsec = self.macsec_mut();
+        macsec.mode = Some("psk".to_string());
+        macsec.cak = cak.to_string();
+        macsec.ckn = Some(ckn.to_string());
+        self
+    }
+
+    /// Negotiates the keys through 802.1X (EAP mode).
+    pub fn eap(mut self) -> Self {
+        self.macsec_mut().mode = Some("eap".to_string());
+        self
+    }
+
+    /// Sets whether to encrypt the traffic.
+    pub fn encrypt(mut self, encrypt: bool) -> Self {
+        self.macsec_mut().encrypt = Some(encrypt);
+        self
+    }
+
+    fn macsec_mut(&mut self) -> &mut MacsecSettings {
+        self.connection
+            .macsec
+            .as_mut()
+            .expect("MACsec settings are always set")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::types::DeviceType;
+
+    #[test]
+    fn test_build_ethernet() {
+        let conn = ConnectionBuilder::ethernet("eth0")
+            .interface("eth0")
+            .address("192.168.122.100/24".parse().unwrap())
+            .address("::ffff:c0a8:7ac7/64".parse().unwrap())
+            .gateway("192.168.122.1".parse().unwrap())
+            .method6(Ipv6Method::Auto)
+            .build();
+        assert_eq!(conn.device_type(), DeviceType::Ethernet);
+        assert_eq!(conn.interface, Some("eth0".to_string()));
+        assert_eq!(conn.method4, Some("manual".to_string()));
+        assert_eq!(conn.method6, Some("auto".to_string()));
+        assert_eq!(conn.addresses.len(), 2);
+        assert_eq!(conn.gateway4, Some("192.168.122.1".parse().unwrap()));
+        assert_eq!(conn.gateway6, None);
+    }
+
+    #[test]
+    fn test_build_wireless() {
+        let conn = ConnectionBuilder::wireless("Hotspot", "agama")
+            .security(WirelessSecurity::Sae, "secret")
+            .access_point(Some("a"), None)
+            .method4(Ipv4Method::Shared)
+            .build();
+        assert_eq!(conn.device_type(), DeviceType::Wireless);
+        assert_eq!(conn.method4, Some("shared".to_string()));
+        let wireless = conn.wireless.unwrap();
+        assert_eq!(wireless.ssid, "agama");
+        assert_eq!(wireless.security, "sae");
+        assert_eq!(wireless.password, Some("secret".to_string()));
+        assert_eq!(wireless.mode, "ap");
+        assert_eq!(wireless.band, Some("a".to_string()));
+    }
+
+    #[test]
+    fn test_build_bond() {
+        let conn = ConnectionBuilder::bond("bond0")
+            .mode("active-backup")
+            .port("eth0")
+            .port("eth1")
+            .build();
+        assert_eq!(conn.device_type(), DeviceType::Bond);
+        let bond = conn.bond.unwrap();
+        assert_eq!(bond.mode, "active-backup");
+        assert_eq!(bond.ports, vec!["eth0".to_string(), "eth1".to_string()]);
+    }
+
+    #[test]
+    fn test_build_pppoe_and_modem() {
+        let pppoe = ConnectionBuilder::pppoe("dsl", "eth0", "jane")
+            .password("secret")
+            .build();
+        assert_eq!(pppoe.device_type(), DeviceType::Pppoe);
+        assert_eq!(pppoe.pppoe.unwrap().parent, "eth0");
+
+        let modem = ConnectionBuilder::modem("mobile", "internet")
+            .pin("1234")
+            .build();
+        assert_eq!(modem.device_type(), DeviceType::Modem);
+        assert_eq!(modem.modem.unwrap().pin, Some("1234".to_string()));
+    }
+
+    #[test]
+    fn test_build_vrf() {
+        let vrf = ConnectionBuilder::vrf("mgmt", 10)
+            .port("eth0")
+            .port("eth1")
+            .build();
+        assert_eq!(vrf.device_type(), DeviceType::Vrf);
+        let settings = vrf.vrf.unwrap();
+        assert_eq!(settings.table, 10);
+        assert_eq!(settings.ports, vec!["eth0", "eth1"]);
+    }
+
+    #[test]
+    fn test_build_macvlan() {
+        let macvlan = ConnectionBuilder::macvlan("macvlan0", "eth0")
+            .mode("private")
+            .build();
+        assert_eq!(macvlan.device_type(), DeviceType::Macvlan);
+        let settings = macvlan.macvlan.unwrap();
+        assert_eq!(settings.parent, "eth0");
+        assert_eq!(settings.mode, Some("private".to_string()));
+    }
+
+    #[test]
+    fn test_build_ipvlan() {
+        let ipvlan = ConnectionBuilder::ipvlan("ipvlan0", "eth0").build();
+        assert_eq!(ipvlan.device_type(), DeviceType::Ipvlan);
+        assert_eq!(ipvlan.ipvlan.unwrap().mode, None);
+    }
+
+    #[test]
+    fn test_build_macsec() {
+        let macsec = ConnectionBuilder::macsec("macsec0", "eth0")
+            .psk("0123456789abcdef", "fedcba9876543210")
+            .encrypt(false)
+            .build();
+        assert_eq!(macsec.device_type(), DeviceType::Macsec);
+        let settings = macsec.macsec.unwrap();
+        assert_eq!(settings.mode, Some("psk".to_string()));
+        assert_eq!(settings.cak, "0123456789abcdef");
+        assert_eq!(settings.encrypt, Some(false));
+    }
+}
@@ -1,73 +1,466 @@
 use super::proxies::{
-    BondProxy, ConnectionProxy, ConnectionsProxy, DeviceProxy, DevicesProxy, IPProxy, MatchProxy,
-    WirelessProxy,
+    BondProxy, ConnectionProxy, ConnectionsProxy, ConnectivityProxy, DeviceProxy, DevicesProxy,
+    DnsProxy, IPProxy, IPVLANProxy, KdumpProxy, MACVLANProxy, MACsecProxy, MatchProxy, ModemProxy,
+    PPPoEProxy, VRFProxy, VersionsProxy, WirelessProxy, WirelessSettingsProxy,
 };
-use super::settings::{BondSettings, MatchSettings, NetworkConnection, WirelessSettings};
-use super::types::{Device, DeviceType, SSID};
+use super::settings::{
+    BondSettings, ConnectivitySettings, DnsSettings, IpvlanSettings, KdumpSettings, MacsecSettings,
+    MacvlanSettings, MatchSettings, ModemSettings, NetworkConnection, PppoeSettings, VrfSettings,
+    WirelessSettings,
+};
+use super::types::{ConnectionSummary, ConnectionsPage, Device, DeviceType, SSID};
 use crate::error::ServiceError;
+use crate::timeout::CallTimeout;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
 use tokio_stream::StreamExt;
-use zbus::zvariant::OwnedObjectPath;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
 use zbus::Connection;
 
 /// D-BUS client for the network service
 pub struct NetworkClient<'a> {
+    timeout: CallTimeout,
     pub connection: Connection,
     connections_proxy: ConnectionsProxy<'a>,
+    connectivity_proxy: ConnectivityProxy<'a>,
     devices_proxy: DevicesProxy<'a>,
+    dns_proxy: DnsProxy<'a>,
+    kdump_proxy: KdumpProxy<'a>,
+    wireless_settings_proxy: WirelessSettingsProxy<'a>,
 }
 
 impl<'a> NetworkClient<'a> {
     pub async fn new(connection: Connection) -> Result<NetworkClient<'a>, ServiceError> {
         Ok(Self {
+            timeout: CallTimeout::default(),
             connections_proxy: ConnectionsProxy::new(&connection).await?,
+            connectivity_proxy: ConnectivityProxy::new(&connection).await?,
             devices_proxy: DevicesProxy::new(&connection).await?,
+            dns_proxy: DnsProxy::new(&connection).await?,
+            kdump_proxy: KdumpProxy::new(&connection).await?,
+            wireless_settings_proxy: WirelessSettingsProxy::new(&connection).await?,
             connection,
         })
     }
 
+    /// Sets the timeout for the D-Bus calls.
+    ///
+    /// * `timeout`: time to wait for each call.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = CallTimeout::new(timeout);
+        self
+    }
+
     pub async fn get_connection(&self, id: &str) -> Result<NetworkConnection, ServiceError> {
-        let path = self.connections_proxy.get_connection_by_id(id).await?;
-        self.connection_from(path.as_str()).await
+        self.timeout
+            .run(async {
+                let path = self.connections_proxy.get_connection_by_id(id).await?;
+                self.connection_from(path.as_str()).await
+            })
+            .await
     }
 
     pub async fn available_devices(&self) -> Result<Vec<Device>, ServiceError> {
-        let devices_paths = self.devices_proxy.get_devices().await?;
-        let mut devices = vec![];
+        self.timeout
+            .run(async {
+                let devices_paths = self.devices_proxy.get_devices().await?;
+                let mut devices = vec![];
 
-        for path in devices_paths {
-            let device = self.device_from(path.as_str()).await?;
+                for path in devices_paths {
+                    let device = self.device_from(path.as_str()).await?;
 
-            devices.push(device);
-        }
+                    devices.push(device);
+                }
 
-        Ok(devices)
+                Ok(devices)
+            })
+            .await
+    }
+
+    /// Sets whether the network backend manages a device
+    ///
+    ///  * `name`: device name
+    ///  * `managed`: whether the device should be managed
+    pub async fn set_device_managed(&self, name: &str, managed: bool) -> Result<(), ServiceError> {
+        self.timeout
+            .run(async {
+                for path in self.devices_proxy.get_devices().await? {
+                    let device_proxy = DeviceProxy::builder(&self.connection)
+                        .path(path)?
+                        .build()
+                        .await?;
+                    if device_proxy.name().await? == name {
+                        device_proxy.set_managed(managed).await?;
+                        return Ok(());
+                    }
+                }
+                Err(ServiceError::UnsuccessfulAction(format!(
+                    "managing the unknown device '{}'",
+                    name
+                )))
+            })
+            .await
     }
 
     /// Returns an array of network connections
     pub async fn connections(&self) -> Result<Vec<NetworkConnection>, ServiceError> {
-        let connection_paths = self.connections_proxy.get_connections().await?;
-        let mut connections = vec![];
+        self.timeout
+            .run(async {
+                let connection_paths = self.connections_proxy.get_connections().await?;
+                let mut connections = vec![];
 
-        for path in connection_paths {
-            let mut connection = self.connection_from(path.as_str()).await?;
+                for path in connection_paths {
+                    let mut connection = self.connection_from(path.as_str()).await?;
 
-            if let Ok(bond) = self.bond_from(path.as_str()).await {
-                connection.bond = Some(bond);
-            }
+                    if let Ok(bond) = self.bond_from(path.as_str()).await {
+                        connection.bond = Some(bond);
+                    }
 
-            if let Ok(wireless) = self.wireless_from(path.as_str()).await {
-                connection.wireless = Some(wireless);
-            }
+                    if let Ok(wireless) = self.wireless_from(path.as_str()).await {
+                        connection.wireless = Some(wireless);
+                    }
 
-            let match_settings = self.match_settings_from(path.as_str()).await?;
-            if !match_settings.is_empty() {
-                connection.match_settings = Some(match_settings);
-            }
+                    if let Ok(pppoe) = self.pppoe_from(path.as_str()).await {
+                        connection.pppoe = Some(pppoe);
+                    }
 
-            connections.push(connection);
-        }
+                    if let Ok(modem) = self.modem_from(path.as_str()).await {
+                        connection.modem = Some(modem);
+                    }
+
+                    if let Ok(vrf) = self.vrf_from(path.as_str()).await {
+                        connection.vrf = Some(vrf);
+                    }
+
+                    if let Ok(macvlan) = self.macvlan_from(path.as_str()).await {
+                        connection.macvlan = Some(macvlan);
+                    }
+
+                    if let Ok(ipvlan) = self.ipvlan_from(path.as_str()).await {
+                        connection.ipvlan = Some(ipvlan);
+                    }
+
+                    if let Ok(macsec) = self.macsec_from(path.as_str()).await {
+                        connection.macsec = Some(macsec);
+                    }
+
+                    let match_settings = self.match_settings_from(path.as_str()).await?;
+                    if !match_settings.is_empty() {
+                        connection.match_settings = Some(match_settings);
+                    }
+
+                    connections.push(connection);
+                }
+
+                Ok(connections)
+            })
+            .await
+    }
+
+    /// Returns the connectivity check settings
+    pub async fn connectivity(&self) -> Result<ConnectivitySettings, ServiceError> {
+        self.timeout
+            .run(async {
+                let uri = self.connectivity_proxy.uri().await?;
+                let interval = self.connectivity_proxy.interval().await?;
+                Ok(ConnectivitySettings {
+                    enabled: Some(self.connectivity_proxy.enabled().await?),
+                    uri: if uri.is_empty() { None } else { Some(uri) },
+                    interval: if interval == 0 { None } else { Some(interval) },
+                })
+            })
+            .await
+    }
+
+    /// Updates the connectivity check settings
+    ///
+    /// Only the given values are changed.
+    ///
+    ///  * `settings`: connectivity check settings
+    pub async fn set_connectivity(
+        &self,
+        settings: &ConnectivitySettings,
+    ) -> Result<(), ServiceError> {
+        self.timeout
+            .run(async {
+                if let Some(enabled) = settings.enabled {
+                    self.connectivity_proxy.set_enabled(enabled).await?;
+                }
+                if let Some(uri) = &settings.uri {
+                    self.connectivity_proxy.set_uri(uri).await?;
+                }
+                if let Some(interval) = settings.interval {
+                    self.connectivity_proxy.set_interval(interval).await?;
+                }
+                Ok(())
+            })
+            .await
+    }
+
+    /// Determines whether the system has access to Internet
+    pub async fn is_online(&self) -> Result<bool, ServiceError> {
+        self.timeout
+            .run(async { Ok(self.connectivity_proxy.is_online().await?) })
+            .await
+    }
+
+    /// Returns the global DNS settings
+    pub async fn dns(&self) -> Result<DnsSettings, ServiceError> {
+        self.timeout
+            .run(async {
+                Ok(DnsSettings {
+                    servers: self.dns_proxy.servers().await?,
+                    searches: self.dns_proxy.searches().await?,
+                    policy: Some(self.dns_proxy.policy().await?),
+                    stack: Some(self.dns_proxy.stack().await?),
+                })
+            })
+            .await
+    }
+
+    /// Updates the global DNS settings
+    ///
+    /// The policy and the stack are only changed if they are given.
+    ///
+    ///  * `settings`: global DNS settings
+    pub async fn set_dns(&self, settings: &DnsSettings) -> Result<(), ServiceError> {
+        self.timeout
+            .run(async {
+                let servers: Vec<&str> = settings.servers.iter().map(String::as_str).collect();
+                self.dns_proxy.set_servers(&servers).await?;
+                let searches: Vec<&str> = settings.searches.iter().map(String::as_str).collect();
+                self.dns_proxy.set_searches(&searches).await?;
+                if let Some(policy) = &settings.policy {
+                    self.dns_proxy.set_policy(policy).await?;
+                }
+                if let Some(stack) = &settings.stack {
+                    self.dns_proxy.set_stack(stack).await?;
+                }
+                Ok(())
+            })
+            .await
+    }
+
+    /// Returns the network settings for kdump
+    ///
+    /// It returns `None` if there are no settings.
+    pub async fn kdump(&self) -> Result<Option<KdumpSettings>, ServiceError> {
+        self.timeout
+            .run(async {
+                let interface = self.kdump_proxy.interface().await?;
+                let addressing = self.kdump_proxy.addressing().await?;
+                let save_dir = self.kdump_proxy.save_dir().await?;
+                if interface.is_empty() && save_dir.is_empty() && addressing == "dhcp" {
+                    return Ok(None);
+                }
+
+                Ok(Some(KdumpSettings {
+                    interface: Some(interface).filter(|i| !i.is_empty()),
+                    addressing: Some(addressing),
+                    save_dir: Some(save_dir).filter(|d| !d.is_empty()),
+                }))
+            })
+            .await
+    }
+
+    /// Updates the network settings for kdump
+    ///
+    ///  * `settings`: kdump settings
+    pub async fn set_kdump(&self, settings: &KdumpSettings) -> Result<(), ServiceError> {
+        self.timeout
+            .run(async {
+                let interface = settings.interface.as_deref().unwrap_or_default();
+                self.kdump_proxy.set_interface(interface).await?;
+                let addressing = settings.addressing.as_deref().unwrap_or("dhcp");
+                self.kdump_proxy.set_addressing(addressing).await?;
+                let save_dir = settings.save_dir.as_deref().unwrap_or_default();
+                self.kdump_proxy.set_save_dir(save_dir).await?;
+                Ok(())
+            })
+            .await
+    }
 
-        Ok(connections)
+    /// Returns the name servers that a device would use once the configuration is applied
+    ///
+    ///  * `device`: device name
+    pub async fn effective_dns(&self, device: &str) -> Result<Vec<String>, ServiceError> {
+        self.timeout
+            .run(async { Ok(self.dns_proxy.get_effective_dns(device).await?) })
+            .await
+    }
+
+    /// Returns the wireless regulatory domain (if set)
+    pub async fn regulatory_domain(&self) -> Result<Option<String>, ServiceError> {
+        self.timeout
+            .run(async {
+                let code = self.wireless_settings_proxy.wireless_reg_dom().await?;
+                Ok(if code.is_empty() { None } else { Some(code) })
+            })
+            .await
+    }
+
+    /// Sets the wireless regulatory domain
+    ///
+    ///  * `code`: ISO 3166-1 alpha-2 country code
+    pub async fn set_regulatory_domain(&self, code: &str) -> Result<(), ServiceError> {
+        self.timeout
+            .run(async {
+                self.wireless_settings_proxy
+                    .set_wireless_reg_dom(code)
+                    .await?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Returns the default IPv6 address generation mode for the new connections (if set)
+    pub async fn addr_gen_mode(&self) -> Result<Option<String>, ServiceError> {
+        self.timeout
+            .run(async {
+                let mode = self.connections_proxy.addr_gen_mode().await?;
+                Ok(if mode.is_empty() { None } else { Some(mode) })
+            })
+            .await
+    }
+
+    /// Sets the default IPv6 address generation mode for the new connections
+    ///
+    ///  * `mode`: "eui64" or "stable-privacy"
+    pub async fn set_addr_gen_mode(&self, mode: &str) -> Result<(), ServiceError> {
+        self.timeout
+            .run(async {
+                self.connections_proxy.set_addr_gen_mode(mode).await?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Returns the versions of the network D-Bus API served by the service
+    ///
+    /// Older services do not expose that information, so only the version 1 is assumed.
+    pub async fn api_versions(&self) -> Result<Vec<u32>, ServiceError> {
+        self.timeout
+            .run(async {
+                let proxy = VersionsProxy::new(&self.connection).await?;
+                match proxy.supported().await {
+                    Ok(versions) => Ok(versions),
+                    Err(zbus::Error::FDO(error))
+                        if matches!(
+                            *error,
+                            zbus::fdo::Error::UnknownInterface(_)
+                                | zbus::fdo::Error::UnknownProperty(_)
+                        ) =>
+                    {
+                        Ok(vec![1])
+                    }
+                    Err(error) => Err(error.into()),
+                }
+            })
+            .await
+    }
+
+    /// Determines whether the service serves the given version of the network D-Bus API
+    ///
+    ///  * `version`: API version
+    pub async fn supports_api_version(&self, version: u32) -> Result<bool, ServiceError> {
+        Ok(self.api_versions().await?.contains(&version))
+    }
+
+    /// Returns the bandwidth limit (in Kbit/s) for the connections that do not set their own
+    pub async fn bandwidth_limit(&self) -> Result<Option<u32>, ServiceError> {
+        self.timeout
+            .run(async {
+                let limit = self.connections_proxy.bandwidth_limit().await?;
+                Ok((limit > 0).then_some(limit))
+            })
+            .await
+    }
+
+    /// Sets the bandwidth limit for the connections that do not set their own
+    ///
+    ///  * `limit`: limit in Kbit/s (0 means no limit)
+    pub async fn set_bandwidth_limit(&self, limit: u32) -> Result<(), ServiceError> {
+        self.timeout
+            .run(async {
+                self.connections_proxy.set_bandwidth_limit(limit).await?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Returns whether the destructive operations are performed without asking for confirmation
+    pub async fn force(&self) -> Result<bool, ServiceError> {
+        self.timeout
+            .run(async { Ok(self.connections_proxy.force().await?) })
+            .await
+    }
+
+    /// Sets whether the destructive operations (e.g., removing the connection which carries the
+    /// current session) are performed without asking for confirmation
+    ///
+    ///  * `force`: whether to skip the confirmation
+    pub async fn set_force(&self, force: bool) -> Result<(), ServiceError> {
+        self.timeout
+            .run(async {
+                self.connections_proxy.set_force(force).await?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Returns the static hostname (if set)
+    pub async fn hostname(&self) -> Result<Option<String>, ServiceError> {
+        self.timeout
+            .run(async {
+                let hostname = self.connections_proxy.hostname().await?;
+                Ok(if hostname.is_empty() {
+                    None
+                } else {
+                    Some(hostname)
+                })
+            })
+            .await
+    }
+
+    /// Sets the static hostname
+    ///
+    ///  * `hostname`: hostname to set
+    pub async fn set_hostname(&self, hostname: &str) -> Result<(), ServiceError> {
+        self.timeout
+            .run(async {
+                self.connections_proxy.set_hostname(hostname).await?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Removes all the connections
+    ///
+    /// Unless the force flag is set, it must be confirmed through the Questions service.
+    pub async fn remove_all_connections(&self) -> Result<(), ServiceError> {
+        self.connections_proxy.remove_all_connections().await?;
+        Ok(())
+    }
+
+    /// Returns the names of the ignored devices
+    pub async fn ignored_devices(&self) -> Result<Vec<String>, ServiceError> {
+        self.timeout
+            .run(async { Ok(self.devices_proxy.get_ignored_devices().await?) })
+            .await
+    }
+
+    /// Sets the devices to ignore
+    ///
+    ///  * `names`: names of the devices
+    pub async fn set_ignored_devices(&self, names: &[String]) -> Result<(), ServiceError> {
+        self.timeout
+            .run(async {
+                let names: Vec<&str> = names.iter().map(String::as_str).collect();
+                self.devices_proxy.set_ignored_devices(&names).await?;
+                Ok(())
+            })
+            .await
     }
 
     /// Applies the network configuration.
@@ -76,6 +469,134 @@ impl<'a> NetworkClient<'a> {
         Ok(())
     }
 
+    /// Applies the changes of the connections with the given tag.
+    ///
+    /// The unapplied changes of the rest of the connections are kept.
+    ///
+    ///  * `tag`: tag of the connections to apply
+    pub async fn apply_group(&self, tag: &str) -> Result<(), ServiceError> {
+        self.connections_proxy.apply_group(tag).await?;
+        Ok(())
+    }
+
+    /// Returns the IDs of the connections with the given tag
+    ///
+    ///  * `tag`: tag to search for
+    pub async fn connections_by_tag(&self, tag: &str) -> Result<Vec<String>, ServiceError> {
+        self.timeout
+            .run(async {
+                let paths = self.connections_proxy.get_connections_by_tag(tag).await?;
+                let mut ids = vec![];
+                for path in paths {
+                    let proxy = ConnectionProxy::builder(&self.connection)
+                        .path(path)?
+                        .build()
+                        .await?;
+                    ids.push(proxy.id().await?);
+                }
+                Ok(ids)
+            })
+            .await
+    }
+
+    /// Reloads the backend configuration and reads the devices and connections again.
+    ///
+    /// The unapplied changes are kept.
+    pub async fn refresh(&self) -> Result<(), ServiceError> {
+        self.connections_proxy.refresh().await?;
+        Ok(())
+    }
+
+    /// Adds a connection for a device based on its current IP configuration
+    ///
+    /// It returns the new connection, which is named after the device.
+    ///
+    ///  * `name`: device name
+    ///  * `as_static`: whether to copy the current configuration as a static one
+    pub async fn create_connection_from_device(
+        &self,
+        name: &str,
+        as_static: bool,
+    ) -> Result<NetworkConnection, ServiceError> {
+        self.timeout
+            .run(async {
+                let path = self
+                    .connections_proxy
+                    .create_from_device(name, as_static)
+                    .await?;
+                self.connection_from(path.as_str()).await
+            })
+            .await
+    }
+
+    /// Returns the summary of the connections
+    ///
+    /// It is way faster than [NetworkClient::connections], as it only needs a single D-Bus call.
+    pub async fn connections_summary(&self) -> Result<Vec<ConnectionSummary>, ServiceError> {
+        self.timeout
+            .run(async {
+                let summary = self.connections_proxy.get_summary().await?;
+                Ok(summary.iter().map(summary_from_dbus).collect())
+            })
+            .await
+    }
+
+    /// Returns a page of the connections summary
+    ///
+    /// When the token of the previous page is given, it fails if the connections changed in
+    /// the meantime.
+    ///
+    /// * `offset`: number of connections to skip
+    /// * `limit`: maximum number of connections to return (0 means no limit)
+    /// * `token`: change token of the previous page, if any
+    pub async fn connections_page(
+        &self,
+        offset: u32,
+        limit: u32,
+        token: Option<&str>,
+    ) -> Result<ConnectionsPage, ServiceError> {
+        self.timeout
+            .run(async {
+                let (token, total, connections) = self
+                    .connections_proxy
+                    .get_summary_page(offset, limit, token.unwrap_or_default())
+                    .await?;
+                Ok(ConnectionsPage {
+                    token,
+                    total,
+                    connections: connections.iter().map(summary_from_dbus).collect(),
+                })
+            })
+            .await
+    }
+
+    /// Returns the token identifying the current state of the connections
+    pub async fn change_token(&self) -> Result<String, ServiceError> {
+        self.timeout
+            .run(async { Ok(self.connections_proxy.get_change_token().await?) })
+            .await
+    }
+
+    /// Returns the team connections that were converted into bonds
+    ///
+    /// Each element contains the connection ID and the resulting bond mode.
+    pub async fn team_migrations(&self) -> Result<Vec<(String, String)>, ServiceError> {
+        self.timeout
+            .run(async { Ok(self.connections_proxy.get_team_migrations().await?) })
+            .await
+    }
+
+    /// Checks whether a bond has at least one active port with carrier
+    ///
+    /// It returns whether the bond is healthy and the list of issues found.
+    ///
+    ///  * `id`: bond connection ID
+    pub async fn verify_bond(&self, id: &str) -> Result<(bool, Vec<String>), ServiceError> {
+        self.timeout
+            .run(async { Ok(self.connections_proxy.verify_bond(id).await?) })
+            .await
+    }
+
     /// Returns the NetworkDevice for the given device path
     ///
     ///  * `path`: the connections path to get the config from
@@ -86,10 +607,12 @@ impl<'a> NetworkClient<'a> {
             .await?;
         let name = device_proxy.name().await?;
         let device_type = device_proxy.type_().await?;
+        let managed = device_proxy.managed().await?;
 
         Ok(Device {
             name,
             type_: DeviceType::try_from(device_type).unwrap(),
+            managed,
         })
     }
 
@@ -110,6 +633,37 @@ impl<'a> NetworkClient<'a> {
             "" => None,
             value => Some(value.to_string()),
         };
+        let mdns = match connection_proxy.mdns().await?.as_str() {
+            "default" => None,
+            value => Some(value.to_string()),
+        };
+        let llmnr = match connection_proxy.llmnr_mode().await?.as_str() {
+            "default" => None,
+            value => Some(value.to_string()),
+        };
+        let lldp = connection_proxy.lldp().await?.then_some(true);
+        let externally_managed = connection_proxy.externally_managed().await?.then_some(true);
+        let only_on_target = connection_proxy.only_on_target().await?.then_some(true);
+        let required_for_online = (!connection_proxy.required_for_online().await?).then_some(false);
+        let activation_timeout = match connection_proxy.activation_timeout().await? {
+            0 => None,
+            timeout => Some(timeout),
+        };
+        let bandwidth_limit = match connection_proxy.bandwidth_limit().await? {
+            0 => None,
+            limit => Some(limit),
+        };
+        let secret_storage = match connection_proxy.secret_storage().await?.as_str() {
+            "system" => None,
+            value => Some(value.to_string()),
+        };
+        let description = match connection_proxy.description().await?.as_str() {
+            "" => None,
+            value => Some(value.to_string()),
+        };
+        let metadata = connection_proxy.metadata().await?;
+        let permissions = connection_proxy.permissions().await?;
+        let tags = connection_proxy.tags().await?;
 
         let ip_proxy = IPProxy::builder(&self.connection)
             .path(path)?
@@ -120,6 +674,15 @@ impl<'a> NetworkClient<'a> {
         let gateway4 = ip_proxy.gateway4().await?.parse().ok();
         let method6 = ip_proxy.method6().await?;
         let gateway6 = ip_proxy.gateway6().await?.parse().ok();
+        let route_metric4 = u32::try_from(ip_proxy.route_metric4().await?).ok();
+        let route_metric6 = u32::try_from(ip_proxy.route_metric6().await?).ok();
+        let never_default4 = ip_proxy.never_default4().await?.then_some(true);
+        let never_default6 = ip_proxy.never_default6().await?.then_some(true);
+        let token6 = ip_proxy.token6().await?.parse().ok();
+        let addr_gen_mode6 = match ip_proxy.addr_gen_mode6().await?.as_str() {
+            "" => None,
+            value => Some(value.to_string()),
+        };
         let nameservers = ip_proxy.nameservers().await?;
         let nameservers = nameservers.iter().filter_map(|a| a.parse().ok()).collect();
         let addresses = ip_proxy.addresses().await?;
@@ -131,10 +694,29 @@ impl<'a> NetworkClient<'a> {
             gateway4,
             method6: Some(method6.to_string()),
             gateway6,
+            route_metric4,
+            route_metric6,
+            never_default4,
+            never_default6,
+            token6,
+            addr_gen_mode6,
             addresses,
             nameservers,
             interface,
             mac_address,
+            mdns,
+            llmnr,
+            lldp,
+            externally_managed,
+            only_on_target,
+            required_for_online,
+            activation_timeout,
+            bandwidth_limit,
+            secret_storage,
+            description,
+            metadata,
+            permissions,
+            tags,
             ..Default::default()
         })
     }
@@ -163,16 +745,134 @@ impl<'a> NetworkClient<'a> {
             .path(path)?
             .build()
             .await?;
+        let band = wireless_proxy.band().await?;
+        let channel = wireless_proxy.channel().await?;
         let wireless = WirelessSettings {
             mode: wireless_proxy.mode().await?,
-            password: wireless_proxy.password().await?,
+            password: None,
             security: wireless_proxy.security().await?,
             ssid: SSID(wireless_proxy.ssid().await?).to_string(),
+            band: if band.is_empty() { None } else { Some(band) },
+            channel: if channel == 0 { None } else { Some(channel) },
         };
 
         Ok(wireless)
     }
 
+    /// Returns the [PPPoE settings][PppoeSettings] for the given connection
+    ///
+    ///  * `path`: the connections path to get the PPPoE config from
+    async fn pppoe_from(&self, path: &str) -> Result<PppoeSettings, ServiceError> {
+        let pppoe_proxy = PPPoEProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+        let service = pppoe_proxy.service().await?;
+        let pppoe = PppoeSettings {
+            username: pppoe_proxy.username().await?,
+            password: None,
+            service: if service.is_empty() {
+                None
+            } else {
+                Some(service)
+            },
+            parent: pppoe_proxy.parent().await?,
+        };
+
+        Ok(pppoe)
+    }
+
+    /// Returns the [modem settings][ModemSettings] for the given connection
+    ///
+    ///  * `path`: the connections path to get the modem config from
+    async fn modem_from(&self, path: &str) -> Result<ModemSettings, ServiceError> {
+        let modem_proxy = ModemProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+        let username = modem_proxy.username().await?;
+        let modem = ModemSettings {
+            apn: modem_proxy.apn().await?,
+            pin: None,
+            username: if username.is_empty() {
+                None
+            } else {
+                Some(username)
+            },
+            password: None,
+        };
+
+        Ok(modem)
+    }
+
+    /// Returns the [VRF settings][VrfSettings] for the given connection
+    ///
+    ///  * `path`: the connections path to get the VRF config from
+    async fn vrf_from(&self, path: &str) -> Result<VrfSettings, ServiceError> {
+        let vrf_proxy = VRFProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+        let vrf = VrfSettings {
+            table: vrf_proxy.table().await?,
+            ports: vrf_proxy.ports().await?,
+        };
+
+        Ok(vrf)
+    }
+
+    /// Returns the [MACVLAN settings][MacvlanSettings] for the given connection
+    ///
+    ///  * `path`: the connections path to get the MACVLAN config from
+    async fn macvlan_from(&self, path: &str) -> Result<MacvlanSettings, ServiceError> {
+        let macvlan_proxy = MACVLANProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+        let macvlan = MacvlanSettings {
+            parent: macvlan_proxy.parent().await?,
+            mode: Some(macvlan_proxy.mode().await?),
+        };
+
+        Ok(macvlan)
+    }
+
+    /// Returns the [IPVLAN settings][IpvlanSettings] for the given connection
+    ///
+    ///  * `path`: the connections path to get the IPVLAN config from
+    async fn ipvlan_from(&self, path: &str) -> Result<IpvlanSettings, ServiceError> {
+        let ipvlan_proxy = IPVLANProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+        let ipvlan = IpvlanSettings {
+            parent: ipvlan_proxy.parent().await?,
+            mode: Some(ipvlan_proxy.mode().await?),
+        };
+
+        Ok(ipvlan)
+    }
+
+    /// Returns the [MACsec settings][MacsecSettings] for the given connection
+    ///
+    ///  * `path`: the connections path to get the MACsec config from
+    async fn macsec_from(&self, path: &str) -> Result<MacsecSettings, ServiceError> {
+        let macsec_proxy = MACsecProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+        let ckn = macsec_proxy.ckn().await?;
+        let macsec = MacsecSettings {
+            parent: macsec_proxy.parent().await?,
+            mode: Some(macsec_proxy.mode().await?),
+            cak: macsec_proxy.cak().await?,
+            ckn: if ckn.is_empty() { None } else { Some(ckn) },
+            encrypt: Some(macsec_proxy.encrypt().await?),
+        };
+
+        Ok(macsec)
+    }
+
     /// Returns the [match settings][MatchSettings] for the given connection
     ///
     ///  * `path`: the connections path to get the match settings from
@@ -200,13 +900,17 @@ impl<'a> NetworkClient<'a> {
         &self,
         conn: &NetworkConnection,
     ) -> Result<(), ServiceError> {
-        let path = match self.connections_proxy.get_connection_by_id(&conn.id).await {
-            Ok(path) => path,
-            Err(_) => self.add_connection(conn).await?,
-        };
+        self.timeout
+            .run(async {
+                let path = match self.connections_proxy.get_connection_by_id(&conn.id).await {
+                    Ok(path) => path,
+                    Err(_) => self.add_connection(conn).await?,
+                };
 
-        self.update_connection(&path, conn).await?;
-        Ok(())
+                self.update_connection(&path, conn).await?;
+                Ok(())
+            })
+            .await
     }
 
     /// Adds a network connection.
@@ -257,6 +961,58 @@ impl<'a> NetworkClient<'a> {
         let mac_address = conn.mac_address.as_deref().unwrap_or("");
         proxy.set_mac_address(mac_address).await?;
 
+        if let Some(ref mdns) = conn.mdns {
+            proxy.set_mdns(mdns).await?;
+        }
+
+        if let Some(ref llmnr) = conn.llmnr {
+            proxy.set_llmnr_mode(llmnr).await?;
+        }
+
+        if let Some(lldp) = conn.lldp {
+            proxy.set_lldp(lldp).await?;
+        }
+
+        if let Some(externally_managed) = conn.externally_managed {
+            proxy.set_externally_managed(externally_managed).await?;
+        }
+
+        if let Some(only_on_target) = conn.only_on_target {
+            proxy.set_only_on_target(only_on_target).await?;
+        }
+
+        if let Some(required_for_online) = conn.required_for_online {
+            proxy.set_required_for_online(required_for_online).await?;
+        }
+
+        if let Some(timeout) = conn.activation_timeout {
+            proxy.set_activation_timeout(timeout).await?;
+        }
+
+        if let Some(limit) = conn.bandwidth_limit {
+            proxy.set_bandwidth_limit(limit).await?;
+        }
+
+        if let Some(ref storage) = conn.secret_storage {
+            proxy.set_secret_storage(storage).await?;
+        }
+
+        let description = conn.description.as_deref().unwrap_or("");
+        proxy.set_description(description).await?;
+
+        let metadata = conn
+            .metadata
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        proxy.set_metadata(metadata).await?;
+
+        let permissions: Vec<_> = conn.permissions.iter().map(String::as_str).collect();
+        proxy.set_permissions(&permissions).await?;
+
+        let tags: Vec<_> = conn.tags.iter().map(String::as_str).collect();
+        proxy.set_tags(&tags).await?;
+
         self.update_ip_settings(path, conn).await?;
 
         if let Some(ref bond) = conn.bond {
@@ -267,6 +1023,30 @@ impl<'a> NetworkClient<'a> {
             self.update_wireless_settings(path, wireless).await?;
         }
 
+        if let Some(ref pppoe) = conn.pppoe {
+            self.update_pppoe_settings(path, pppoe).await?;
+        }
+
+        if let Some(ref modem) = conn.modem {
+            self.update_modem_settings(path, modem).await?;
+        }
+
+        if let Some(ref vrf) = conn.vrf {
+            self.update_vrf_settings(path, vrf).await?;
+        }
+
+        if let Some(ref macvlan) = conn.macvlan {
+            self.update_macvlan_settings(path, macvlan).await?;
+        }
+
+        if let Some(ref ipvlan) = conn.ipvlan {
+            self.update_ipvlan_settings(path, ipvlan).await?;
+        }
+
+        if let Some(ref macsec) = conn.macsec {
+            self.update_macsec_settings(path, macsec).await?;
+        }
+
         if let Some(ref match_settings) = conn.match_settings {
             self.update_match_settings(path, match_settings).await?;
         }
@@ -310,6 +1090,27 @@ impl<'a> NetworkClient<'a> {
         let gateway = conn.gateway6.map_or(String::from(""), |g| g.to_string());
         proxy.set_gateway6(&gateway).await?;
 
+        // -1 means using the default metric
+        let metric = conn.route_metric4.map_or(-1, i64::from);
+        proxy.set_route_metric4(metric).await?;
+
+        let metric = conn.route_metric6.map_or(-1, i64::from);
+        proxy.set_route_metric6(metric).await?;
+
+        proxy
+            .set_never_default4(conn.never_default4.unwrap_or_default())
+            .await?;
+        proxy
+            .set_never_default6(conn.never_default6.unwrap_or_default())
+            .await?;
+
+        let token = conn.token6.map_or(String::from(""), |t| t.to_string());
+        proxy.set_token6(&token).await?;
+
+        // an empty mode means using the default one
+        let mode = conn.addr_gen_mode6.as_deref().unwrap_or_default();
+        proxy.set_addr_gen_mode6(mode).await?;
+
         Ok(())
     }
 
@@ -336,6 +1137,154 @@ impl<'a> NetworkClient<'a> {
 
         Ok(())
     }
+
+    /// Updates the PPPoE settings for network connection.
+    ///
+    /// * `path`: connection D-Bus path.
+    /// * `pppoe`: PPPoE settings of the network connection.
+    async fn update_pppoe_settings(
+        &self,
+        path: &OwnedObjectPath,
+        pppoe: &PppoeSettings,
+    ) -> Result<(), ServiceError> {
+        let proxy = PPPoEProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+
+        proxy.set_username(&pppoe.username).await?;
+        // the password is write-only: None keeps the current value and an empty one removes it
+        if let Some(password) = &pppoe.password {
+            proxy.set_password(password).await?;
+        }
+        proxy
+            .set_service(pppoe.service.as_deref().unwrap_or_default())
+            .await?;
+        proxy.set_parent(&pppoe.parent).await?;
+        Ok(())
+    }
+
+    /// Updates the modem settings for network connection.
+    ///
+    /// * `path`: connection D-Bus path.
+    /// * `modem`: modem settings of the network connection.
+    async fn update_modem_settings(
+        &self,
+        path: &OwnedObjectPath,
+        modem: &ModemSettings,
+    ) -> Result<(), ServiceError> {
+        let proxy = ModemProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+
+        proxy.set_apn(&modem.apn).await?;
+        proxy
+            .set_username(modem.username.as_deref().unwrap_or_default())
+            .await?;
+        // the secrets are write-only: None keeps the current value and an empty one removes it
+        if let Some(pin) = &modem.pin {
+            proxy.set_pin(pin).await?;
+        }
+        if let Some(password) = &modem.password {
+            proxy.set_password(password).await?;
+        }
+        Ok(())
+    }
+
+    /// Updates the VRF settings for a network connection.
+    ///
+    /// * `path`: connection D-Bus path.
+    /// * `vrf`: VRF settings of the network connection.
+    async fn update_vrf_settings(
+        &self,
+        path: &OwnedObjectPath,
+        vrf: &VrfSettings,
+    ) -> Result<(), ServiceError> {
+        let proxy = VRFProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+
+        proxy.set_table(vrf.table).await?;
+        let ports: Vec<_> = vrf.ports.iter().map(String::as_ref).collect();
+        proxy.set_ports(ports.as_slice()).await?;
+        Ok(())
+    }
+
+    /// Updates the MACVLAN settings for a network connection.
+    ///
+    /// * `path`: connection D-Bus path.
+    /// * `macvlan`: MACVLAN settings of the network connection.
+    async fn update_macvlan_settings(
+        &self,
+        path: &OwnedObjectPath,
+        macvlan: &MacvlanSettings,
+    ) -> Result<(), ServiceError> {
+        let proxy = MACVLANProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+
+        proxy.set_parent(&macvlan.parent).await?;
+        if let Some(mode) = &macvlan.mode {
+            proxy.set_mode(mode).await?;
+        }
+        Ok(())
+    }
+
+    /// Updates the IPVLAN settings for a network connection.
+    ///
+    /// * `path`: connection D-Bus path.
+    /// * `ipvlan`: IPVLAN settings of the network connection.
+    async fn update_ipvlan_settings(
+        &self,
+        path: &OwnedObjectPath,
+        ipvlan: &IpvlanSettings,
+    ) -> Result<(), ServiceError> {
+        let proxy = IPVLANProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+
+        proxy.set_parent(&ipvlan.parent).await?;
+        if let Some(mode) = &ipvlan.mode {
+            proxy.set_mode(mode).await?;
+        }
+        Ok(())
+    }
+
+    /// Updates the MACsec settings for a network connection.
+    ///
+    /// * `path`: connection D-Bus path.
+    /// * `macsec`: MACsec settings of the network connection.
+    async fn update_macsec_settings(
+        &self,
+        path: &OwnedObjectPath,
+        macsec: &MacsecSettings,
+    ) -> Result<(), ServiceError> {
+        let proxy = MACsecProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+
+        proxy.set_parent(&macsec.parent).await?;
+        if let Some(mode) = &macsec.mode {
+            proxy.set_mode(mode).await?;
+        }
+        // the CAK is write-only, so an empty one means "keep the current value"
+        if !macsec.cak.is_empty() {
+            proxy.set_cak(&macsec.cak).await?;
+        }
+        proxy
+            .set_ckn(macsec.ckn.as_deref().unwrap_or_default())
+            .await?;
+        if let Some(encrypt) = macsec.encrypt {
+            proxy.set_encrypt(encrypt).await?;
+        }
+        Ok(())
+    }
+
     /// Updates the wireless settings for network connection.
     ///
     /// * `path`: connection D-Bus path.
@@ -350,12 +1299,23 @@ impl<'a> NetworkClient<'a> {
             .build()
             .await?;
 
-        proxy.set_ssid(wireless.ssid.as_bytes()).await?;
+        let ssid = SSID::from_str(&wireless.ssid)?;
+        proxy.set_ssid(ssid.to_vec()).await?;
         proxy.set_mode(wireless.mode.to_string().as_str()).await?;
         proxy
             .set_security(wireless.security.to_string().as_str())
             .await?;
-        proxy.set_password(&wireless.password).await?;
+        // the password is write-only: None keeps the current value and an empty one removes it
+        if let Some(password) = &wireless.password {
+            proxy.set_password(password).await?;
+        }
+        // the band must be set before the channel
+        proxy
+            .set_band(wireless.band.as_deref().unwrap_or_default())
+            .await?;
+        proxy
+            .set_channel(wireless.channel.unwrap_or_default())
+            .await?;
         Ok(())
     }
 
@@ -379,3 +1339,24 @@ impl<'a> NetworkClient<'a> {
         Ok(())
     }
 }
+
+/// Builds a connection summary from its D-Bus representation
+///
+/// * `summary`: summary as it comes from D-Bus
+fn summary_from_dbus(summary: &HashMap<String, OwnedValue>) -> ConnectionSummary {
+    let get = |key: &str| {
+        summary
+            .get(key)
+            .and_then(|v| String::try_from(v.clone()).ok())
+            .unwrap_or_default()
+    };
+    ConnectionSummary {
+        id: get("Id"),
+        uuid: get("Uuid"),
+        type_: get("Type"),
+        interface: get("Interface"),
+        method4: get("Method4"),
+        method6: get("Method6"),
+        state: get("State"),
+    }
+}
@@ -11,6 +11,18 @@ use zbus::dbus_proxy;
 trait Devices {
     /// GetDevices method
     fn get_devices(&self) -> zbus::Result<Vec<zbus::zvariant::OwnedObjectPath>>;
+
+    /// GetNeighbors method
+    fn get_neighbors(
+        &self,
+        device: &str,
+    ) -> zbus::Result<Vec<std::collections::HashMap<String, zbus::zvariant::OwnedValue>>>;
+
+    /// GetIgnoredDevices method
+    fn get_ignored_devices(&self) -> zbus::Result<Vec<String>>;
+
+    /// SetIgnoredDevices method
+    fn set_ignored_devices(&self, names: &[&str]) -> zbus::Result<()>;
 }
 
 #[dbus_proxy(
@@ -25,6 +37,11 @@ trait Device {
     /// Type property
     #[dbus_proxy(property)]
     fn type_(&self) -> zbus::Result<u8>;
+    /// Managed property
+    #[dbus_proxy(property)]
+    fn managed(&self) -> zbus::Result<bool>;
+    #[dbus_proxy(property)]
+    fn set_managed(&self, value: bool) -> zbus::Result<()>;
 }
 
 #[dbus_proxy(
@@ -36,9 +53,41 @@ trait Connections {
     /// AddConnection method
     fn add_connection(&self, id: &str, ty: u8) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
 
+    /// AddConnectionByName method
+    fn add_connection_by_name(
+        &self,
+        id: &str,
+        ty: &str,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+    /// AddConnections method
+    fn add_connections(
+        &self,
+        template: std::collections::HashMap<&str, zbus::zvariant::Value<'_>>,
+        interfaces: &[&str],
+    ) -> zbus::Result<Vec<zbus::zvariant::OwnedObjectPath>>;
+
     /// Apply method
     fn apply(&self) -> zbus::Result<()>;
 
+    /// ApplyGroup method
+    fn apply_group(&self, tag: &str) -> zbus::Result<()>;
+
+    /// CloneConnection method
+    fn clone_connection(
+        &self,
+        source_id: &str,
+        new_id: &str,
+        with_secrets: bool,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+    /// CreateFromDevice method
+    fn create_from_device(
+        &self,
+        name: &str,
+        as_static: bool,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
     /// GetConnection method
     fn get_connection(&self, uuid: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
 
@@ -48,12 +97,103 @@ trait Connections {
     /// GetConnections method
     fn get_connections(&self) -> zbus::Result<Vec<zbus::zvariant::OwnedObjectPath>>;
 
+    /// GetConnectionsByTag method
+    fn get_connections_by_tag(
+        &self,
+        tag: &str,
+    ) -> zbus::Result<Vec<zbus::zvariant::OwnedObjectPath>>;
+
+    /// GetSummary method
+    fn get_summary(
+        &self,
+    ) -> zbus::Result<Vec<std::collections::HashMap<String, zbus::zvariant::OwnedValue>>>;
+
+    /// GetSummaryPage method
+    #[allow(clippy::type_complexity)]
+    fn get_summary_page(
+        &self,
+        offset: u32,
+        limit: u32,
+        token: &str,
+    ) -> zbus::Result<(
+        String,
+        u32,
+        Vec<std::collections::HashMap<String, zbus::zvariant::OwnedValue>>,
+    )>;
+
+    /// GetChangeToken method
+    fn get_change_token(&self) -> zbus::Result<String>;
+
     /// RemoveConnection method
     fn remove_connection(&self, uuid: &str) -> zbus::Result<()>;
 
+    /// RemoveAllConnections method
+    fn remove_all_connections(&self) -> zbus::Result<()>;
+
+    /// Refresh method
+    fn refresh(&self) -> zbus::Result<()>;
+
+    /// CancelApply method
+    fn cancel_apply(&self) -> zbus::Result<()>;
+
+    /// GetLastApplyResult method
+    fn get_last_apply_result(&self) -> zbus::Result<Vec<(String, String, String, String)>>;
+
+    /// GetTeamMigrations method
+    fn get_team_migrations(&self) -> zbus::Result<Vec<(String, String)>>;
+
+    /// VerifyBond method
+    fn verify_bond(&self, id: &str) -> zbus::Result<(bool, Vec<String>)>;
+
+    /// AddrGenMode property
+    #[dbus_proxy(property)]
+    fn addr_gen_mode(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_addr_gen_mode(&self, value: &str) -> zbus::Result<()>;
+
+    /// Force property
+    #[dbus_proxy(property)]
+    fn force(&self) -> zbus::Result<bool>;
+    #[dbus_proxy(property)]
+    fn set_force(&self, value: bool) -> zbus::Result<()>;
+
+    /// BandwidthLimit property
+    #[dbus_proxy(property)]
+    fn bandwidth_limit(&self) -> zbus::Result<u32>;
+    #[dbus_proxy(property)]
+    fn set_bandwidth_limit(&self, value: u32) -> zbus::Result<()>;
+
+    /// Hostname property
+    #[dbus_proxy(property)]
+    fn hostname(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_hostname(&self, value: &str) -> zbus::Result<()>;
+
     /// ConnectionAdded signal
     #[dbus_proxy(signal)]
     fn connection_added(&self, id: &str, path: zbus::zvariant::ObjectPath<'_>) -> zbus::Result<()>;
+
+    /// ConnectionUpdated signal
+    #[dbus_proxy(signal)]
+    fn connection_updated(
+        &self,
+        id: &str,
+        path: zbus::zvariant::ObjectPath<'_>,
+    ) -> zbus::Result<()>;
+
+    /// ConnectionRemoved signal
+    #[dbus_proxy(signal)]
+    fn connection_removed(&self, id: &str) -> zbus::Result<()>;
+
+    /// AppliedConfiguration signal
+    #[dbus_proxy(signal)]
+    fn applied_configuration(
+        &self,
+        added: u32,
+        updated: u32,
+        removed: u32,
+        failed: Vec<String>,
+    ) -> zbus::Result<()>;
 }
 
 #[dbus_proxy(
@@ -71,11 +211,20 @@ trait Wireless {
     fn set_mode(&self, value: &str) -> zbus::Result<()>;
 
     /// Password property
+    ///
+    /// It is write-only, so reading it always returns an empty string.
     #[dbus_proxy(property)]
     fn password(&self) -> zbus::Result<String>;
     #[dbus_proxy(property)]
     fn set_password(&self, value: &str) -> zbus::Result<()>;
 
+    /// Whether a password is set
+    #[dbus_proxy(property)]
+    fn has_password(&self) -> zbus::Result<bool>;
+
+    /// Returns the password (requires authorization)
+    fn get_password(&self) -> zbus::Result<String>;
+
     /// SSID property
     #[dbus_proxy(property, name = "SSID")]
     fn ssid(&self) -> zbus::Result<Vec<u8>>;
@@ -90,6 +239,20 @@ trait Wireless {
     fn security(&self) -> zbus::Result<String>;
     #[dbus_proxy(property)]
     fn set_security(&self, value: &str) -> zbus::Result<()>;
+
+    /// Frequency band
+    ///
+    /// Possible values are 'a', 'bg' or '' (any band)
+    #[dbus_proxy(property)]
+    fn band(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_band(&self, value: &str) -> zbus::Result<()>;
+
+    /// Channel (0 means automatic selection)
+    #[dbus_proxy(property)]
+    fn channel(&self) -> zbus::Result<u32>;
+    #[dbus_proxy(property)]
+    fn set_channel(&self, value: u32) -> zbus::Result<()>;
 }
 
 #[dbus_proxy(
@@ -101,6 +264,9 @@ trait Connection {
     /// Id property
     #[dbus_proxy(property)]
     fn id(&self) -> zbus::Result<String>;
+    /// LastError property
+    #[dbus_proxy(property)]
+    fn last_error(&self) -> zbus::Result<String>;
     #[dbus_proxy(property)]
     fn interface(&self) -> zbus::Result<String>;
     #[dbus_proxy(property)]
@@ -109,6 +275,62 @@ trait Connection {
     fn mac_address(&self) -> zbus::Result<String>;
     #[dbus_proxy(property)]
     fn set_mac_address(&self, mac_address: &str) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn externally_managed(&self) -> zbus::Result<bool>;
+    #[dbus_proxy(property)]
+    fn set_externally_managed(&self, value: bool) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn activation_timeout(&self) -> zbus::Result<u32>;
+    #[dbus_proxy(property)]
+    fn set_activation_timeout(&self, timeout: u32) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn bandwidth_limit(&self) -> zbus::Result<u32>;
+    #[dbus_proxy(property)]
+    fn set_bandwidth_limit(&self, limit: u32) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn only_on_target(&self) -> zbus::Result<bool>;
+    #[dbus_proxy(property)]
+    fn set_only_on_target(&self, value: bool) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn required_for_online(&self) -> zbus::Result<bool>;
+    #[dbus_proxy(property)]
+    fn set_required_for_online(&self, value: bool) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn secret_storage(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_secret_storage(&self, value: &str) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn description(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_description(&self, value: &str) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn metadata(&self) -> zbus::Result<std::collections::HashMap<String, String>>;
+    #[dbus_proxy(property)]
+    fn set_metadata(&self, value: std::collections::HashMap<&str, &str>) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn permissions(&self) -> zbus::Result<Vec<String>>;
+    #[dbus_proxy(property)]
+    fn set_permissions(&self, value: &[&str]) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn tags(&self) -> zbus::Result<Vec<String>>;
+    #[dbus_proxy(property)]
+    fn set_tags(&self, value: &[&str]) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn mdns(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_mdns(&self, value: &str) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn llmnr_mode(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_llmnr_mode(&self, value: &str) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn lldp(&self) -> zbus::Result<bool>;
+    #[dbus_proxy(property)]
+    fn set_lldp(&self, value: bool) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn missing_secrets(&self) -> zbus::Result<Vec<String>>;
+    /// UnlockSecrets method
+    fn unlock_secrets(&self, secrets: std::collections::HashMap<&str, &str>) -> zbus::Result<()>;
 }
 
 #[dbus_proxy(
@@ -152,6 +374,42 @@ trait IP {
     fn nameservers(&self) -> zbus::Result<Vec<String>>;
     #[dbus_proxy(property)]
     fn set_nameservers(&self, value: &[&str]) -> zbus::Result<()>;
+
+    /// NeverDefault4 property
+    #[dbus_proxy(property)]
+    fn never_default4(&self) -> zbus::Result<bool>;
+    #[dbus_proxy(property)]
+    fn set_never_default4(&self, value: bool) -> zbus::Result<()>;
+
+    /// NeverDefault6 property
+    #[dbus_proxy(property)]
+    fn never_default6(&self) -> zbus::Result<bool>;
+    #[dbus_proxy(property)]
+    fn set_never_default6(&self, value: bool) -> zbus::Result<()>;
+
+    /// RouteMetric4 property
+    #[dbus_proxy(property)]
+    fn route_metric4(&self) -> zbus::Result<i64>;
+    #[dbus_proxy(property)]
+    fn set_route_metric4(&self, value: i64) -> zbus::Result<()>;
+
+    /// RouteMetric6 property
+    #[dbus_proxy(property)]
+    fn route_metric6(&self) -> zbus::Result<i64>;
+    #[dbus_proxy(property)]
+    fn set_route_metric6(&self, value: i64) -> zbus::Result<()>;
+
+    /// Token6 property
+    #[dbus_proxy(property)]
+    fn token6(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_token6(&self, value: &str) -> zbus::Result<()>;
+
+    /// AddrGenMode6 property
+    #[dbus_proxy(property)]
+    fn addr_gen_mode6(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_addr_gen_mode6(&self, value: &str) -> zbus::Result<()>;
 }
 
 #[dbus_proxy(
@@ -206,3 +464,285 @@ trait Bond {
     #[dbus_proxy(property)]
     fn set_ports(&self, value: &[&str]) -> zbus::Result<()>;
 }
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Network.Connection.PPPoE",
+    default_service = "org.opensuse.Agama1",
+    default_path = "/org/opensuse/Agama1/Network"
+)]
+trait PPPoE {
+    /// Username property
+    #[dbus_proxy(property)]
+    fn username(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_username(&self, value: &str) -> zbus::Result<()>;
+
+    /// Password property
+    #[dbus_proxy(property)]
+    fn password(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_password(&self, value: &str) -> zbus::Result<()>;
+
+    /// Service property
+    #[dbus_proxy(property)]
+    fn service(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_service(&self, value: &str) -> zbus::Result<()>;
+
+    /// Parent property
+    #[dbus_proxy(property)]
+    fn parent(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_parent(&self, value: &str) -> zbus::Result<()>;
+}
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Network.Connection.Modem",
+    default_service = "org.opensuse.Agama1",
+    default_path = "/org/opensuse/Agama1/Network"
+)]
+trait Modem {
+    /// Apn property
+    #[dbus_proxy(property)]
+    fn apn(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_apn(&self, value: &str) -> zbus::Result<()>;
+
+    /// Pin property
+    #[dbus_proxy(property)]
+    fn pin(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_pin(&self, value: &str) -> zbus::Result<()>;
+
+    /// Username property
+    #[dbus_proxy(property)]
+    fn username(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_username(&self, value: &str) -> zbus::Result<()>;
+
+    /// Password property
+    #[dbus_proxy(property)]
+    fn password(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_password(&self, value: &str) -> zbus::Result<()>;
+}
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Network.Connection.VRF",
+    default_service = "org.opensuse.Agama1",
+    default_path = "/org/opensuse/Agama1/Network"
+)]
+trait VRF {
+    /// Table property
+    #[dbus_proxy(property)]
+    fn table(&self) -> zbus::Result<u32>;
+    #[dbus_proxy(property)]
+    fn set_table(&self, value: u32) -> zbus::Result<()>;
+
+    /// Ports property
+    #[dbus_proxy(property)]
+    fn ports(&self) -> zbus::Result<Vec<String>>;
+    #[dbus_proxy(property)]
+    fn set_ports(&self, value: &[&str]) -> zbus::Result<()>;
+}
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Network.Connection.MACVLAN",
+    default_service = "org.opensuse.Agama1",
+    default_path = "/org/opensuse/Agama1/Network"
+)]
+trait MACVLAN {
+    /// Parent property
+    #[dbus_proxy(property)]
+    fn parent(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_parent(&self, value: &str) -> zbus::Result<()>;
+
+    /// Mode property
+    #[dbus_proxy(property)]
+    fn mode(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_mode(&self, value: &str) -> zbus::Result<()>;
+}
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Network.Connection.IPVLAN",
+    default_service = "org.opensuse.Agama1",
+    default_path = "/org/opensuse/Agama1/Network"
+)]
+trait IPVLAN {
+    /// Parent property
+    #[dbus_proxy(property)]
+    fn parent(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_parent(&self, value: &str) -> zbus::Result<()>;
+
+    /// Mode property
+    #[dbus_proxy(property)]
+    fn mode(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_mode(&self, value: &str) -> zbus::Result<()>;
+}
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Network.Connection.MACsec",
+    default_service = "org.opensuse.Agama1",
+    default_path = "/org/opensuse/Agama1/Network"
+)]
+trait MACsec {
+    /// GetCak method
+    fn get_cak(&self) -> zbus::Result<String>;
+
+    /// Parent property
+    #[dbus_proxy(property)]
+    fn parent(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_parent(&self, value: &str) -> zbus::Result<()>;
+
+    /// Mode property
+    #[dbus_proxy(property)]
+    fn mode(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_mode(&self, value: &str) -> zbus::Result<()>;
+
+    /// Cak property
+    #[dbus_proxy(property)]
+    fn cak(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_cak(&self, value: &str) -> zbus::Result<()>;
+
+    /// HasCak property
+    #[dbus_proxy(property)]
+    fn has_cak(&self) -> zbus::Result<bool>;
+
+    /// Ckn property
+    #[dbus_proxy(property)]
+    fn ckn(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_ckn(&self, value: &str) -> zbus::Result<()>;
+
+    /// Encrypt property
+    #[dbus_proxy(property)]
+    fn encrypt(&self) -> zbus::Result<bool>;
+    #[dbus_proxy(property)]
+    fn set_encrypt(&self, value: bool) -> zbus::Result<()>;
+}
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Network.Connectivity",
+    default_service = "org.opensuse.Agama1",
+    default_path = "/org/opensuse/Agama1/Network"
+)]
+trait Connectivity {
+    /// IsOnline method
+    fn is_online(&self) -> zbus::Result<bool>;
+
+    /// Enabled property
+    #[dbus_proxy(property)]
+    fn enabled(&self) -> zbus::Result<bool>;
+    #[dbus_proxy(property)]
+    fn set_enabled(&self, value: bool) -> zbus::Result<()>;
+
+    /// Interval property
+    #[dbus_proxy(property)]
+    fn interval(&self) -> zbus::Result<u32>;
+    #[dbus_proxy(property)]
+    fn set_interval(&self, value: u32) -> zbus::Result<()>;
+
+    /// Uri property
+    #[dbus_proxy(property)]
+    fn uri(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_uri(&self, value: &str) -> zbus::Result<()>;
+}
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Network.Dns",
+    default_service = "org.opensuse.Agama1",
+    default_path = "/org/opensuse/Agama1/Network"
+)]
+trait Dns {
+    /// GetEffectiveDns method
+    fn get_effective_dns(&self, device: &str) -> zbus::Result<Vec<String>>;
+
+    /// Policy property
+    #[dbus_proxy(property)]
+    fn policy(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_policy(&self, value: &str) -> zbus::Result<()>;
+
+    /// Searches property
+    #[dbus_proxy(property)]
+    fn searches(&self) -> zbus::Result<Vec<String>>;
+    #[dbus_proxy(property)]
+    fn set_searches(&self, value: &[&str]) -> zbus::Result<()>;
+
+    /// Servers property
+    #[dbus_proxy(property)]
+    fn servers(&self) -> zbus::Result<Vec<String>>;
+    #[dbus_proxy(property)]
+    fn set_servers(&self, value: &[&str]) -> zbus::Result<()>;
+
+    /// Stack property
+    #[dbus_proxy(property)]
+    fn stack(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_stack(&self, value: &str) -> zbus::Result<()>;
+}
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Network.Kdump",
+    default_service = "org.opensuse.Agama1",
+    default_path = "/org/opensuse/Agama1/Network"
+)]
+trait Kdump {
+    /// Addressing property
+    #[dbus_proxy(property)]
+    fn addressing(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_addressing(&self, value: &str) -> zbus::Result<()>;
+
+    /// Interface property
+    #[dbus_proxy(property)]
+    fn interface(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_interface(&self, value: &str) -> zbus::Result<()>;
+
+    /// SaveDir property
+    #[dbus_proxy(property)]
+    fn save_dir(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_save_dir(&self, value: &str) -> zbus::Result<()>;
+}
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Network.Wireless",
+    default_service = "org.opensuse.Agama1",
+    default_path = "/org/opensuse/Agama1/Network"
+)]
+trait WirelessSettings {
+    /// WirelessRegDom property
+    #[dbus_proxy(property)]
+    fn wireless_reg_dom(&self) -> zbus::Result<String>;
+    #[dbus_proxy(property)]
+    fn set_wireless_reg_dom(&self, value: &str) -> zbus::Result<()>;
+}
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Network.Versions",
+    default_service = "org.opensuse.Agama1",
+    default_path = "/org/opensuse/Agama1/Network"
+)]
+trait Versions {
+    /// Current property
+    #[dbus_proxy(property)]
+    fn current(&self) -> zbus::Result<u32>;
+
+    /// Supported property
+    #[dbus_proxy(property)]
+    fn supported(&self) -> zbus::Result<Vec<u32>>;
+
+    /// Deprecated property
+    #[dbus_proxy(property)]
+    fn deprecated(&self) -> zbus::Result<std::collections::HashMap<String, String>>;
+}
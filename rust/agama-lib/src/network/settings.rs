@@ -5,9 +5,10 @@ use agama_settings::error::ConversionError;
 use agama_settings::{SettingObject, SettingValue, Settings};
 use cidr::IpInet;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::default::Default;
-use std::net::IpAddr;
+use std::net::{IpAddr, Ipv6Addr};
 
 /// Network settings for installation
 #[derive(Debug, Default, Settings, Serialize, Deserialize)]
@@ -16,6 +17,96 @@ pub struct NetworkSettings {
     /// Connections to use in the installation
     #[settings(collection)]
     pub connections: Vec<NetworkConnection>,
+    /// Connectivity check settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[settings(nested)]
+    pub connectivity: Option<ConnectivitySettings>,
+    /// Global DNS settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[settings(nested)]
+    pub dns: Option<DnsSettings>,
+    /// Wireless regulatory domain (ISO 3166-1 alpha-2 country code)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regulatory_domain: Option<String>,
+    /// Names of the devices to ignore (e.g., ports shared with a BMC)
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    #[settings(collection)]
+    pub ignored_devices: Vec<String>,
+    /// Network settings for kdump in the installed system
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[settings(nested)]
+    pub kdump: Option<KdumpSettings>,
+    /// Default IPv6 address generation mode for the new connections ("eui64" or
+    /// "stable-privacy")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub addr_gen_mode: Option<String>,
+    /// Bandwidth limit (in Kbit/s) during the installation for the connections that do not set
+    /// their own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bandwidth_limit: Option<u32>,
+    /// Whether the destructive operations (e.g., removing the connection which carries the
+    /// current session) are performed without asking for confirmation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force: Option<bool>,
+    /// Static hostname of the system
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+}
+
+/// Connectivity check settings
+///
+/// NetworkManager uses the connectivity check to find out whether the system has access to
+/// Internet.
+#[derive(Clone, Debug, Default, PartialEq, Settings, Serialize, Deserialize)]
+pub struct ConnectivitySettings {
+    /// Whether the connectivity check is enabled
+    pub enabled: Option<bool>,
+    /// URI to check (if not set, NetworkManager's default is used)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// Interval (in seconds) between checks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u32>,
+}
+
+/// Global DNS settings
+///
+/// The global name servers are combined with the ones from each connection according to the
+/// policy ("append", "prepend" or "override").
+#[derive(Clone, Debug, Default, PartialEq, Settings, Serialize, Deserialize)]
+pub struct DnsSettings {
+    /// Global name servers
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    #[settings(collection)]
+    pub servers: Vec<String>,
+    /// Global search domains
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    #[settings(collection)]
+    pub searches: Vec<String>,
+    /// How to combine the global name servers with the per-connection ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<String>,
+    /// DNS resolver stack of the installed system ("default", "netconfig", "systemd-resolved" or
+    /// "file")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<String>,
+}
+
+/// Network settings for kdump in the installed system
+///
+/// They are needed to save the crash dumps to a remote location (e.g., an NFS share).
+#[derive(Clone, Debug, Default, PartialEq, Settings, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KdumpSettings {
+    /// Interface to use (if not set, the one with the default route is used)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interface: Option<String>,
+    /// How to configure the addresses of the interface ("dhcp", "dhcp6" or "static")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub addressing: Option<String>,
+    /// Location to save the crash dumps to (e.g., "nfs://server/var/crash")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub save_dir: Option<String>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -41,11 +132,21 @@ impl MatchSettings {
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct WirelessSettings {
-    #[serde(skip_serializing_if = "String::is_empty")]
-    pub password: String,
+    /// Password to connect to the network. As it is write-only, `None` keeps the current one and
+    /// an empty string removes it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
     pub security: String,
+    /// SSID, as plain text, in hexadecimal ("hex:...") or as a quoted string with escapes (see
+    /// [SSID](super::types::SSID))
     pub ssid: String,
     pub mode: String,
+    /// Frequency band ("a" or "bg"), mostly relevant for the AP mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub band: Option<String>,
+    /// Channel, mostly relevant for the AP mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<u32>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -67,6 +168,86 @@ impl Default for BondSettings {
     }
 }
 
+/// PPPoE settings
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PppoeSettings {
+    pub username: String,
+    /// Password to authenticate with the provider. As it is write-only, `None` keeps the current
+    /// one and an empty string removes it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Service name (only needed if the provider offers several ones)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    /// Ethernet interface to establish the session on
+    pub parent: String,
+}
+
+/// Mobile broadband (GSM/UMTS/LTE) settings
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ModemSettings {
+    /// Access Point Name of the carrier
+    pub apn: String,
+    /// PIN to unlock the SIM card. As it is write-only, `None` keeps the current one and an empty
+    /// string removes it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Password to authenticate with the carrier (same rules than the PIN)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+/// VRF (Virtual Routing and Forwarding) settings
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct VrfSettings {
+    /// Routing table ID
+    pub table: u32,
+    /// Interfaces enslaved to the VRF
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub ports: Vec<String>,
+}
+
+/// MACVLAN settings
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MacvlanSettings {
+    /// Parent interface name or connection UUID
+    pub parent: String,
+    /// MACVLAN mode ("vepa", "bridge", "private", "passthru" or "source")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+/// IPVLAN settings
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct IpvlanSettings {
+    /// Parent interface name or connection UUID
+    pub parent: String,
+    /// IPVLAN mode ("l2", "l3" or "l3s")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+/// MACsec settings
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MacsecSettings {
+    /// Parent interface name or connection UUID
+    pub parent: String,
+    /// Key agreement mode ("psk" or "eap")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// Connectivity Association Key (only for the "psk" mode)
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    pub cak: String,
+    /// Connectivity Association Key Name (only for the "psk" mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ckn: Option<String>,
+    /// Whether to encrypt the traffic (true by default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encrypt: Option<bool>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NetworkDevice {
     pub id: String,
@@ -84,6 +265,24 @@ pub struct NetworkConnection {
     pub method6: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gateway6: Option<IpAddr>,
+    /// Metric of the IPv4 routes (the lowest one owns the default route)
+    #[serde(rename = "routeMetric4", skip_serializing_if = "Option::is_none")]
+    pub route_metric4: Option<u32>,
+    /// Metric of the IPv6 routes (the lowest one owns the default route)
+    #[serde(rename = "routeMetric6", skip_serializing_if = "Option::is_none")]
+    pub route_metric6: Option<u32>,
+    /// Whether to never use the connection for the IPv4 default route
+    #[serde(rename = "neverDefault4", skip_serializing_if = "Option::is_none")]
+    pub never_default4: Option<bool>,
+    /// Whether to never use the connection for the IPv6 default route
+    #[serde(rename = "neverDefault6", skip_serializing_if = "Option::is_none")]
+    pub never_default6: Option<bool>,
+    /// IPv6 tokenized interface identifier (e.g., "::10")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token6: Option<Ipv6Addr>,
+    /// IPv6 address generation mode ("eui64" or "stable-privacy"), overriding the default one
+    #[serde(rename = "addrGenMode6", skip_serializing_if = "Option::is_none")]
+    pub addr_gen_mode6: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub addresses: Vec<IpInet>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
@@ -98,8 +297,59 @@ pub struct NetworkConnection {
     pub parent: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bond: Option<BondSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pppoe: Option<PppoeSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modem: Option<ModemSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vrf: Option<VrfSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub macvlan: Option<MacvlanSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipvlan: Option<IpvlanSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub macsec: Option<MacsecSettings>,
     #[serde(rename = "mac-address", skip_serializing_if = "Option::is_none")]
     pub mac_address: Option<String>,
+    /// Multicast DNS mode ("default", "no", "resolve" or "yes")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mdns: Option<String>,
+    /// LLMNR mode ("default", "no", "resolve" or "yes")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub llmnr: Option<String>,
+    /// Whether to listen for LLDP packets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lldp: Option<bool>,
+    /// Whether the connection is managed outside Agama (so it is not written)
+    #[serde(rename = "externallyManaged", skip_serializing_if = "Option::is_none")]
+    pub externally_managed: Option<bool>,
+    /// Whether the connection should only be activated in the installed system
+    #[serde(rename = "onlyOnTarget", skip_serializing_if = "Option::is_none")]
+    pub only_on_target: Option<bool>,
+    /// Whether the installed system must wait for the connection when booting (true by default)
+    #[serde(rename = "requiredForOnline", skip_serializing_if = "Option::is_none")]
+    pub required_for_online: Option<bool>,
+    /// Time (in seconds) to wait for the connection to be activated
+    #[serde(rename = "activationTimeout", skip_serializing_if = "Option::is_none")]
+    pub activation_timeout: Option<u32>,
+    /// Bandwidth limit (in Kbit/s) during the installation, overriding the global one
+    #[serde(rename = "bandwidthLimit", skip_serializing_if = "Option::is_none")]
+    pub bandwidth_limit: Option<u32>,
+    /// Where the secrets are stored ("system", "agent" or "not-saved")
+    #[serde(rename = "secretStorage", skip_serializing_if = "Option::is_none")]
+    pub secret_storage: Option<String>,
+    /// Free-form description of the connection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Free-form metadata (e.g., a ticket number or the role of the connection)
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub metadata: HashMap<String, String>,
+    /// Users allowed to use the connection (all of them if it is empty)
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub permissions: Vec<String>,
+    /// Tags to operate on groups of connections (e.g., "storage-net" or "mgmt")
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tags: Vec<String>,
 }
 
 impl NetworkConnection {
@@ -112,6 +362,18 @@ impl NetworkConnection {
             DeviceType::Wireless
         } else if self.bond.is_some() {
             DeviceType::Bond
+        } else if self.pppoe.is_some() {
+            DeviceType::Pppoe
+        } else if self.modem.is_some() {
+            DeviceType::Modem
+        } else if self.vrf.is_some() {
+            DeviceType::Vrf
+        } else if self.macvlan.is_some() {
+            DeviceType::Macvlan
+        } else if self.ipvlan.is_some() {
+            DeviceType::Ipvlan
+        } else if self.macsec.is_some() {
+            DeviceType::Macsec
         } else {
             DeviceType::Ethernet
         }
@@ -145,7 +407,6 @@ impl TryFrom<SettingObject> for NetworkConnection {
 mod tests {
     use super::*;
     use agama_settings::{settings::Settings, SettingObject, SettingValue};
-    use std::collections::HashMap;
 
     #[test]
     fn test_device_type() {
@@ -162,8 +423,44 @@ mod tests {
             ..Default::default()
         };
 
+        let pppoe = NetworkConnection {
+            pppoe: Some(PppoeSettings::default()),
+            ..Default::default()
+        };
+
         assert_eq!(wlan.device_type(), DeviceType::Wireless);
         assert_eq!(bond.device_type(), DeviceType::Bond);
+        assert_eq!(pppoe.device_type(), DeviceType::Pppoe);
+
+        let modem = NetworkConnection {
+            modem: Some(ModemSettings::default()),
+            ..Default::default()
+        };
+        assert_eq!(modem.device_type(), DeviceType::Modem);
+
+        let vrf = NetworkConnection {
+            vrf: Some(VrfSettings::default()),
+            ..Default::default()
+        };
+        assert_eq!(vrf.device_type(), DeviceType::Vrf);
+
+        let macvlan = NetworkConnection {
+            macvlan: Some(MacvlanSettings::default()),
+            ..Default::default()
+        };
+        assert_eq!(macvlan.device_type(), DeviceType::Macvlan);
+
+        let ipvlan = NetworkConnection {
+            ipvlan: Some(IpvlanSettings::default()),
+            ..Default::default()
+        };
+        assert_eq!(ipvlan.device_type(), DeviceType::Ipvlan);
+
+        let macsec = NetworkConnection {
+            macsec: Some(MacsecSettings::default()),
+            ..Default::default()
+        };
+        assert_eq!(macsec.device_type(), DeviceType::Macsec);
     }
 
     #[test]
@@ -203,4 +500,19 @@ mod tests {
         assert_eq!(conn.method4, Some("auto".to_string()));
         assert_eq!(conn.method6, Some("disabled".to_string()));
     }
+
+    #[test]
+    fn test_wireless_password() {
+        let wireless: WirelessSettings = serde_json::from_str(
+            r#"{ "ssid": "agama", "security": "wpa-psk", "mode": "infrastructure" }"#,
+        )
+        .unwrap();
+        assert_eq!(wireless.password, None);
+
+        let wireless: WirelessSettings = serde_json::from_str(
+            r#"{ "ssid": "agama", "security": "none", "mode": "infrastructure", "password": "" }"#,
+        )
+        .unwrap();
+        assert_eq!(wireless.password, Some("".to_string()));
+    }
 }
@@ -1,6 +1,9 @@
 use super::settings::NetworkConnection;
 use crate::error::ServiceError;
+use crate::install_settings::{InstallSettings, Scope};
 use crate::network::{NetworkClient, NetworkSettings};
+use crate::store::SettingsStore;
+use async_trait::async_trait;
 use zbus::Connection;
 
 /// Loads and stores the network settings from/to the D-Bus service.
@@ -18,19 +21,106 @@ impl<'a> NetworkStore<'a> {
     // TODO: read the settings from the service
     pub async fn load(&self) -> Result<NetworkSettings, ServiceError> {
         let connections = self.network_client.connections().await?;
+        let connectivity = self.network_client.connectivity().await?;
+        let dns = self.network_client.dns().await?;
+        let regulatory_domain = self.network_client.regulatory_domain().await?;
+        let ignored_devices = self.network_client.ignored_devices().await?;
+        let kdump = self.network_client.kdump().await?;
+        let addr_gen_mode = self.network_client.addr_gen_mode().await?;
+        let bandwidth_limit = self.network_client.bandwidth_limit().await?;
+        let force = self.network_client.force().await?.then_some(true);
+        let hostname = self.network_client.hostname().await?;
 
-        Ok(NetworkSettings { connections })
+        Ok(NetworkSettings {
+            connections,
+            connectivity: Some(connectivity),
+            dns: Some(dns),
+            regulatory_domain,
+            ignored_devices,
+            kdump,
+            addr_gen_mode,
+            bandwidth_limit,
+            force,
+            hostname,
+        })
     }
 
     pub async fn store(&self, settings: &NetworkSettings) -> Result<(), ServiceError> {
+        if let Some(force) = settings.force {
+            self.network_client.set_force(force).await?;
+        }
+        self.network_client
+            .set_ignored_devices(&settings.ignored_devices)
+            .await?;
+        // the default mode applies to the connections added afterwards
+        if let Some(mode) = &settings.addr_gen_mode {
+            self.network_client.set_addr_gen_mode(mode).await?;
+        }
         for id in ordered_connections(&settings.connections) {
             let id = id.as_str();
             let fallback = default_connection(id);
             let conn = find_connection(id, &settings.connections).unwrap_or(&fallback);
             self.network_client.add_or_update_connection(conn).await?;
         }
+        if let Some(connectivity) = &settings.connectivity {
+            self.network_client.set_connectivity(connectivity).await?;
+        }
+        if let Some(dns) = &settings.dns {
+            self.network_client.set_dns(dns).await?;
+        }
+        if let Some(hostname) = &settings.hostname {
+            self.network_client.set_hostname(hostname).await?;
+        }
+        if let Some(code) = &settings.regulatory_domain {
+            self.network_client.set_regulatory_domain(code).await?;
+        }
+        if let Some(kdump) = &settings.kdump {
+            self.network_client.set_kdump(kdump).await?;
+        }
+        if let Some(limit) = settings.bandwidth_limit {
+            self.network_client.set_bandwidth_limit(limit).await?;
+        }
         self.network_client.apply().await?;
+        self.verify_bonds(&settings.connections).await?;
+
+        Ok(())
+    }
 
+    /// Checks that the bonds have at least one working port.
+    ///
+    /// A bond without any active port with carrier makes the installation fail later in a
+    /// confusing way, so it is better to stop here.
+    ///
+    /// * `conns`: connections to check.
+    async fn verify_bonds(&self, conns: &[NetworkConnection]) -> Result<(), ServiceError> {
+        for conn in conns.iter().filter(|c| c.bond.is_some()) {
+            let (healthy, issues) = self.network_client.verify_bond(&conn.id).await?;
+            for issue in &issues {
+                log::warn!("{}", issue);
+            }
+            if !healthy {
+                return Err(ServiceError::FailedOperation(issues.join("; ")));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<'a> SettingsStore for NetworkStore<'a> {
+    fn scope(&self) -> Scope {
+        Scope::Network
+    }
+
+    async fn load_section(&self, settings: &mut InstallSettings) -> Result<(), ServiceError> {
+        settings.network = Some(self.load().await?);
+        Ok(())
+    }
+
+    async fn store_section(&self, settings: &InstallSettings) -> Result<(), ServiceError> {
+        if let Some(network) = &settings.network {
+            self.store(network).await?;
+        }
         Ok(())
     }
 }
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
-use std::{fmt, str};
+use std::{
+    fmt,
+    str::{self, FromStr},
+};
 use thiserror::Error;
 use zbus;
 
@@ -8,9 +11,45 @@ use zbus;
 pub struct Device {
     pub name: String,
     pub type_: DeviceType,
+    /// Whether the device is managed by the network backend
+    pub managed: bool,
 }
 
-#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+/// Most relevant information of a network connection
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ConnectionSummary {
+    pub id: String,
+    pub uuid: String,
+    /// Connection type (e.g., "ethernet", "wireless" or "bond")
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Interface name (empty if it is not set)
+    pub interface: String,
+    pub method4: String,
+    pub method6: String,
+    /// Connection state ("up" or "down")
+    pub state: String,
+}
+
+/// Page of the connections summary
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ConnectionsPage {
+    /// Token to detect changes when fetching the next page
+    pub token: String,
+    /// Total number of connections
+    pub total: u32,
+    pub connections: Vec<ConnectionSummary>,
+}
+
+/// Maximum length (in bytes) of an SSID
+const SSID_MAX_LEN: usize = 32;
+
+/// Network SSID
+///
+/// An SSID is just a sequence of (up to 32) bytes, so it is not necessarily valid UTF-8. The
+/// textual representation (see the [FromStr] implementation) follows the notations used by wpa_supplicant,
+/// so any SSID survives a round-trip through the settings (e.g., a JSON profile).
+#[derive(Debug, Default, PartialEq, Clone)]
 pub struct SSID(pub Vec<u8>);
 
 impl SSID {
@@ -19,9 +58,63 @@ impl SSID {
     }
 }
 
+#[derive(Debug, Error, PartialEq)]
+#[error("{}", crate::tr!("Invalid SSID: '{}'", .0))]
+pub struct InvalidSSID(String);
+
+impl FromStr for SSID {
+    type Err = InvalidSSID;
+
+    /// Parses an SSID from its textual representation.
+    ///
+    /// Supported notations:
+    ///
+    /// * "hex:" followed by the bytes in hexadecimal (e.g., "hex:6167616d61").
+    /// * A quoted string (optionally prefixed with "P", as wpa_supplicant does) with C-like
+    ///   escapes: `\\`, `\"`, `\n`, `\r`, `\t`, `\e` and `\xHH` (e.g., `"caf\xe9"`).
+    /// * Plain text otherwise (e.g., "agama").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidSSID(s.to_string());
+        let bytes = if let Some(hex) = s.strip_prefix("hex:") {
+            decode_hex(hex).ok_or_else(invalid)?
+        } else if let Some(quoted) = s.strip_prefix("P\"").or_else(|| s.strip_prefix('"')) {
+            let text = quoted.strip_suffix('"').ok_or_else(invalid)?;
+            unescape(text).ok_or_else(invalid)?
+        } else {
+            s.as_bytes().to_vec()
+        };
+
+        if bytes.len() > SSID_MAX_LEN {
+            return Err(invalid());
+        }
+        Ok(SSID(bytes))
+    }
+}
+
 impl fmt::Display for SSID {
+    /// Writes the SSID as plain text if possible. Otherwise (e.g., it is not valid UTF-8), it
+    /// uses the "hex:" notation.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", str::from_utf8(&self.0).unwrap())
+        match str::from_utf8(&self.0) {
+            Ok(text) if is_plain_ssid(text) => write!(f, "{}", text),
+            _ => {
+                write!(f, "hex:")?;
+                self.0.iter().try_for_each(|b| write!(f, "{:02x}", b))
+            }
+        }
+    }
+}
+
+impl Serialize for SSID {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SSID {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        SSID::from_str(&text).map_err(serde::de::Error::custom)
     }
 }
 
@@ -31,6 +124,55 @@ impl From<SSID> for Vec<u8> {
     }
 }
 
+/// Determines whether the SSID can be written as plain text.
+///
+/// It must not contain control characters nor look like any of the other notations.
+fn is_plain_ssid(text: &str) -> bool {
+    !text.starts_with("hex:")
+        && !text.starts_with('"')
+        && !text.starts_with("P\"")
+        && !text.chars().any(char::is_control)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Decodes a string with C-like escapes.
+fn unescape(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = vec![];
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+
+        let byte = match chars.next()? {
+            '\\' => b'\\',
+            '"' => b'"',
+            'n' => b'\n',
+            'r' => b'\r',
+            't' => b'\t',
+            'e' => 0x1b,
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                decode_hex(&hex)?.first().copied()?
+            }
+            _ => return None,
+        };
+        bytes.push(byte);
+    }
+    Some(bytes)
+}
+
 #[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum DeviceType {
     Loopback = 0,
@@ -40,6 +182,56 @@ pub enum DeviceType {
     Bond = 4,
     Vlan = 5,
     Bridge = 6,
+    Pppoe = 7,
+    Modem = 8,
+    Vrf = 9,
+    Macvlan = 10,
+    Ipvlan = 11,
+    Macsec = 12,
+}
+
+impl DeviceType {
+    /// All the known device types.
+    pub const ALL: [DeviceType; 13] = [
+        DeviceType::Loopback,
+        DeviceType::Ethernet,
+        DeviceType::Wireless,
+        DeviceType::Dummy,
+        DeviceType::Bond,
+        DeviceType::Vlan,
+        DeviceType::Bridge,
+        DeviceType::Pppoe,
+        DeviceType::Modem,
+        DeviceType::Vrf,
+        DeviceType::Macvlan,
+        DeviceType::Ipvlan,
+        DeviceType::Macsec,
+    ];
+
+    /// Textual representation of the device type (e.g., "ethernet" or "wireless").
+    pub fn name(&self) -> &'static str {
+        match self {
+            DeviceType::Loopback => "loopback",
+            DeviceType::Ethernet => "ethernet",
+            DeviceType::Wireless => "wireless",
+            DeviceType::Dummy => "dummy",
+            DeviceType::Bond => "bond",
+            DeviceType::Vlan => "vlan",
+            DeviceType::Bridge => "bridge",
+            DeviceType::Pppoe => "pppoe",
+            DeviceType::Modem => "modem",
+            DeviceType::Vrf => "vrf",
+            DeviceType::Macvlan => "macvlan",
+            DeviceType::Ipvlan => "ipvlan",
+            DeviceType::Macsec => "macsec",
+        }
+    }
+}
+
+impl fmt::Display for DeviceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
 }
 
 /// Bond mode
@@ -85,7 +277,7 @@ impl std::fmt::Display for BondMode {
 }
 
 #[derive(Debug, Error, PartialEq)]
-#[error("Invalid bond mode: {0}")]
+#[error("{}", crate::tr!("Invalid bond mode: {}", .0))]
 pub struct InvalidBondMode(String);
 
 impl TryFrom<&str> for BondMode {
@@ -128,8 +320,8 @@ impl From<InvalidBondMode> for zbus::fdo::Error {
 }
 
 #[derive(Debug, Error, PartialEq)]
-#[error("Invalid device type: {0}")]
-pub struct InvalidDeviceType(u8);
+#[error("{}", crate::tr!("Invalid device type: {}", .0))]
+pub struct InvalidDeviceType(pub u8);
 
 impl TryFrom<u8> for DeviceType {
     type Error = InvalidDeviceType;
@@ -143,6 +335,12 @@ impl TryFrom<u8> for DeviceType {
             4 => Ok(DeviceType::Bond),
             5 => Ok(DeviceType::Vlan),
             6 => Ok(DeviceType::Bridge),
+            7 => Ok(DeviceType::Pppoe),
+            8 => Ok(DeviceType::Modem),
+            9 => Ok(DeviceType::Vrf),
+            10 => Ok(DeviceType::Macvlan),
+            11 => Ok(DeviceType::Ipvlan),
+            12 => Ok(DeviceType::Macsec),
             _ => Err(InvalidDeviceType(value)),
         }
     }
@@ -154,6 +352,33 @@ impl From<InvalidDeviceType> for zbus::fdo::Error {
     }
 }
 
+#[derive(Debug, Error, PartialEq)]
+#[error("{}", crate::tr!("Unknown device type: '{}'", .0))]
+pub struct UnknownDeviceType(pub String);
+
+impl FromStr for DeviceType {
+    type Err = UnknownDeviceType;
+
+    /// Parses the device type from its name (e.g., "ethernet"). "wifi" is accepted as an alias
+    /// of "wireless", as it is the name used by NetworkManager.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        if name == "wifi" {
+            return Ok(DeviceType::Wireless);
+        }
+        DeviceType::ALL
+            .into_iter()
+            .find(|t| t.name() == name)
+            .ok_or_else(|| UnknownDeviceType(s.to_string()))
+    }
+}
+
+impl From<UnknownDeviceType> for zbus::fdo::Error {
+    fn from(value: UnknownDeviceType) -> zbus::fdo::Error {
+        zbus::fdo::Error::Failed(format!("Network error: {value}"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -164,6 +389,45 @@ mod tests {
         assert_eq!(format!("{}", ssid), "agama");
     }
 
+    #[test]
+    fn test_ssid_from_str() {
+        assert_eq!(SSID::from_str("agama"), Ok(SSID(b"agama".to_vec())));
+        assert_eq!(
+            SSID::from_str("hex:6167616d61"),
+            Ok(SSID(b"agama".to_vec()))
+        );
+        assert_eq!(
+            SSID::from_str("\"caf\\xe9 \\\"1\\\"\""),
+            Ok(SSID(b"caf\xe9 \"1\"".to_vec()))
+        );
+        assert_eq!(SSID::from_str("P\"a\\tb\""), Ok(SSID(b"a\tb".to_vec())));
+        assert!(SSID::from_str("hex:616").is_err());
+        assert!(SSID::from_str("\"unterminated").is_err());
+        assert!(SSID::from_str("\"bad \\q\"").is_err());
+        assert!(SSID::from_str(&"x".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn test_ssid_round_trip() {
+        let ssids = [
+            SSID("Café".as_bytes().to_vec()),
+            SSID(vec![0x63, 0x61, 0x66, 0xe9]),
+            SSID(b"hex:agama".to_vec()),
+            SSID(b"\"quoted\"".to_vec()),
+            SSID(b"new\nline".to_vec()),
+        ];
+        for ssid in ssids {
+            let json = serde_json::to_string(&ssid).unwrap();
+            assert_eq!(serde_json::from_str::<SSID>(&json).unwrap(), ssid);
+        }
+
+        assert_eq!(SSID("Café".as_bytes().to_vec()).to_string(), "Café");
+        assert_eq!(
+            SSID(vec![0x63, 0x61, 0x66, 0xe9]).to_string(),
+            "hex:636166e9"
+        );
+    }
+
     #[test]
     fn test_ssid_to_vec() {
         let vec = vec![97, 103, 97, 109, 97];
@@ -180,6 +444,21 @@ mod tests {
         assert_eq!(dtype, Err(InvalidDeviceType(128)));
     }
 
+    #[test]
+    fn test_device_type_from_str() {
+        assert_eq!(DeviceType::from_str("bond"), Ok(DeviceType::Bond));
+        assert_eq!(DeviceType::from_str("Ethernet"), Ok(DeviceType::Ethernet));
+        assert_eq!(DeviceType::from_str("wifi"), Ok(DeviceType::Wireless));
+        assert_eq!(
+            DeviceType::from_str("token-ring"),
+            Err(UnknownDeviceType("token-ring".to_string()))
+        );
+
+        for ty in DeviceType::ALL {
+            assert_eq!(ty.to_string().parse::<DeviceType>(), Ok(ty));
+        }
+    }
+
     #[test]
     fn test_display_bond_mode() {
         let mode = BondMode::try_from(1).unwrap();
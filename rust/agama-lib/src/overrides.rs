@@ -0,0 +1,249 @@
+//! Overrides for the installation settings
+//!
+//! This module implements a settings source layer that allows adjusting a few values of a profile
+//! without modifying it. The overrides can be specified through:
+//!
+//! * The kernel command line, using the `agama.` prefix and the dotted notation (e.g.,
+//!   `agama.localization.keyboard=es` or `agama.user.userName=jane`).
+//! * Environment variables, using the `AGAMA_` prefix and a double underscore as separator (e.g.,
+//!   `AGAMA_LOCALIZATION__KEYBOARD=es` or `AGAMA_USER__USER_NAME=jane`).
+//!
+//! When the same key is defined in several places, the following precedence applies (from lowest
+//! to highest): profile, kernel command line and environment variables.
+//!
+//! ```
+//! # use agama_lib::install_settings::InstallSettings;
+//! # use agama_lib::overrides::SettingsOverrides;
+//! let mut settings = InstallSettings::default();
+//! let cmdline = SettingsOverrides::from_cmdline("quiet agama.localization.keyboard=es");
+//! let env = SettingsOverrides::from_env(vec![(
+//!     "AGAMA_LOCALIZATION__KEYBOARD".to_string(),
+//!     "cz".to_string(),
+//! )]);
+//! cmdline.chain(env).apply(&mut settings).unwrap();
+//! let localization = settings.localization.unwrap();
+//! assert_eq!(localization.keyboard, Some("cz".to_string()));
+//! ```
+use crate::install_settings::InstallSettings;
+use agama_settings::{settings::Settings, SettingValue, SettingsError};
+use std::fs;
+
+const CMDLINE_PATH: &str = "/proc/cmdline";
+const CMDLINE_PREFIX: &str = "agama.";
+const ENV_PREFIX: &str = "AGAMA_";
+const ENV_SEPARATOR: &str = "__";
+
+/// Sections of the installation settings that can be overridden.
+///
+/// Other options using the same prefix (e.g., `agama.config_url` or `agama.web.ssl`) are not
+/// installation settings, so they are ignored.
+const SECTIONS: [&str; 7] = [
+    "localization",
+    "network",
+    "product",
+    "root",
+    "software",
+    "storage",
+    "user",
+];
+
+/// List of settings overrides, sorted by precedence (lowest first).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SettingsOverrides(Vec<(String, String)>);
+
+impl SettingsOverrides {
+    /// Reads the overrides from the kernel command line and the environment.
+    pub fn from_system() -> Self {
+        let cmdline = fs::read_to_string(CMDLINE_PATH).unwrap_or_default();
+        Self::from_cmdline(&cmdline).chain(Self::from_env(std::env::vars()))
+    }
+
+    /// Extracts the overrides from a kernel command line.
+    ///
+    /// Values containing spaces can be enclosed in double quotes, as the kernel does (e.g.,
+    /// `agama.user.fullName="Jane Doe"`).
+    ///
+    /// * `cmdline`: kernel command line (e.g., `quiet agama.user.userName=jane`).
+    pub fn from_cmdline(cmdline: &str) -> Self {
+        let overrides = split_cmdline(cmdline)
+            .iter()
+            .filter_map(|arg| arg.strip_prefix(CMDLINE_PREFIX))
+            .filter_map(|arg| arg.split_once('='))
+            .map(|(key, value)| (to_snake_case(key), value.to_string()))
+            .filter(|(key, _)| is_settings_key(key))
+            .collect();
+        Self(overrides)
+    }
+
+    /// Extracts the overrides from a set of environment variables.
+    ///
+    /// * `vars`: environment variables (name and value).
+    pub fn from_env<I>(vars: I) -> Self
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut overrides: Vec<_> = vars
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let key = name.strip_prefix(ENV_PREFIX)?;
+                let key = key.to_lowercase().replace(ENV_SEPARATOR, ".");
+                Some((key, value))
+            })
+            .filter(|(key, _)| is_settings_key(key))
+            .collect();
+        // the environment does not guarantee any order
+        overrides.sort();
+        Self(overrides)
+    }
+
+    /// Returns a new list including the overrides from `other`, which take precedence.
+    ///
+    /// * `other`: overrides to add.
+    pub fn chain(mut self, other: SettingsOverrides) -> Self {
+        self.0.extend(other.0);
+        self
+    }
+
+    /// Whether there are no overrides.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Applies the overrides to the given settings.
+    ///
+    /// Unknown keys are logged and ignored, as they might be meant for a different version of
+    /// Agama. However, invalid values are reported as errors.
+    ///
+    /// * `settings`: settings to update.
+    pub fn apply(&self, settings: &mut InstallSettings) -> Result<(), SettingsError> {
+        for (key, value) in &self.0 {
+            log::info!("Overriding setting '{}'", key);
+            match settings.set(key, SettingValue(value.to_string())) {
+                Err(SettingsError::UnknownAttribute(attr)) => {
+                    log::warn!("Ignoring unknown setting '{}'", attr);
+                }
+                result => result?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Determines whether a key belongs to the installation settings.
+fn is_settings_key(key: &str) -> bool {
+    key.split_once('.')
+        .is_some_and(|(section, _)| SECTIONS.contains(&section))
+}
+
+/// Converts a camelCase key (as used in the profiles) into the snake_case notation.
+fn to_snake_case(key: &str) -> String {
+    let mut result = String::with_capacity(key.len());
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            result.push('_');
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Splits the kernel command line into arguments.
+///
+/// Like the kernel, the whitespace between double quotes does not separate arguments, and the
+/// quotes are removed.
+fn split_cmdline(cmdline: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in cmdline.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_cmdline() {
+        let overrides = SettingsOverrides::from_cmdline(
+            "quiet agama.config_url=http://example.net/profile.json agama.web.ssl=true \
+             agama.user.fullName=\"Jane Doe\" agama.localization.keyboard=es",
+        );
+        assert_eq!(
+            overrides,
+            SettingsOverrides(vec![
+                ("user.full_name".to_string(), "Jane Doe".to_string()),
+                ("localization.keyboard".to_string(), "es".to_string())
+            ])
+        );
+    }
+
+    #[test]
+    fn test_split_cmdline() {
+        assert_eq!(
+            split_cmdline("  quiet agama.user.fullName=\"Jane  Doe\"\tsplash=\"\"  "),
+            vec![
+                "quiet".to_string(),
+                "agama.user.fullName=Jane  Doe".to_string(),
+                "splash=".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_env() {
+        let vars = vec![
+            ("HOME".to_string(), "/root".to_string()),
+            ("AGAMA_USER__USER_NAME".to_string(), "jane".to_string()),
+            ("AGAMA_DEBUG".to_string(), "1".to_string()),
+        ];
+        let overrides = SettingsOverrides::from_env(vars);
+        assert_eq!(
+            overrides,
+            SettingsOverrides(vec![("user.user_name".to_string(), "jane".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_apply_precedence() {
+        let mut settings = InstallSettings::default();
+        let cmdline = SettingsOverrides::from_cmdline("agama.product.id=Tumbleweed");
+        let env = SettingsOverrides::from_env(vec![(
+            "AGAMA_PRODUCT__ID".to_string(),
+            "MicroOS".to_string(),
+        )]);
+        cmdline.chain(env).apply(&mut settings).unwrap();
+        assert_eq!(settings.product.unwrap().id, Some("MicroOS".to_string()));
+    }
+
+    #[test]
+    fn test_apply_hostname() {
+        let mut settings = InstallSettings::default();
+        let overrides = SettingsOverrides::from_cmdline("agama.network.hostname=foo");
+        overrides.apply(&mut settings).unwrap();
+        assert_eq!(settings.network.unwrap().hostname, Some("foo".to_string()));
+    }
+
+    #[test]
+    fn test_apply_unknown_setting() {
+        let mut settings = InstallSettings::default();
+        let overrides = SettingsOverrides::from_cmdline("agama.network.unknown=foo");
+        assert!(!overrides.is_empty());
+        assert!(overrides.apply(&mut settings).is_ok());
+    }
+}
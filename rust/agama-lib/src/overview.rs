@@ -0,0 +1,210 @@
+//! Aggregated view of the installation status.
+//!
+//! The landing page of a front-end needs to know whether the system is ready to be installed and,
+//! if not, why. Instead of asking each service, it can read the [Overview] from the
+//! `org.opensuse.Agama1.Overview` interface, which combines:
+//!
+//! * The issues reported by all the services (`org.opensuse.Agama1.Issues` and
+//!   `org.opensuse.Agama1.Validation` interfaces).
+//! * The number of pending questions.
+//! * The busy services.
+//! * Whether the installation can start.
+
+use crate::error::ServiceError;
+use crate::proxies::OverviewProxy;
+use crate::timeout::CallTimeout;
+use serde::Serialize;
+use std::{collections::HashMap, time::Duration};
+use zbus::{
+    zvariant::{OwnedValue, Value},
+    Connection,
+};
+
+/// Severity of the issues that do not block the installation.
+pub const WARN_SEVERITY: u32 = 0;
+/// Severity of the issues that block the installation.
+pub const ERROR_SEVERITY: u32 = 1;
+
+/// Issue reported by an Agama service.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverviewIssue {
+    /// Service (or area) reporting the issue (e.g., "storage")
+    pub service: String,
+    /// Issue description
+    pub description: String,
+    /// Additional details
+    pub details: String,
+    /// Source of the issue (1 for system, 2 for config and 0 for unknown)
+    pub source: u32,
+    /// Severity of the issue ([WARN_SEVERITY] or [ERROR_SEVERITY])
+    pub severity: u32,
+}
+
+impl OverviewIssue {
+    /// Determines whether the issue blocks the installation.
+    pub fn is_error(&self) -> bool {
+        self.severity == ERROR_SEVERITY
+    }
+}
+
+/// D-Bus representation of an issue: service, description, details, source and severity.
+type OverviewIssueFromDBus = (String, String, String, u32, u32);
+
+impl From<OverviewIssueFromDBus> for OverviewIssue {
+    fn from(value: OverviewIssueFromDBus) -> Self {
+        let (service, description, details, source, severity) = value;
+        Self {
+            service,
+            description,
+            details,
+            source,
+            severity,
+        }
+    }
+}
+
+impl From<&OverviewIssue> for OverviewIssueFromDBus {
+    fn from(value: &OverviewIssue) -> Self {
+        (
+            value.service.clone(),
+            value.description.clone(),
+            value.details.clone(),
+            value.source,
+            value.severity,
+        )
+    }
+}
+
+/// Installation status.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Overview {
+    /// Issues reported by the services
+    pub issues: Vec<OverviewIssue>,
+    /// Number of questions waiting for an answer
+    pub pending_questions: u32,
+    /// Names of the busy services
+    pub busy_services: Vec<String>,
+    /// Whether the installation can start
+    pub can_install: bool,
+}
+
+impl Overview {
+    /// Converts the overview into a D-Bus hash.
+    ///
+    /// It uses the "Issues", "PendingQuestions", "BusyServices" and "CanInstall" keys.
+    pub fn to_dbus(&self) -> HashMap<String, OwnedValue> {
+        let issues: Vec<OverviewIssueFromDBus> = self.issues.iter().map(|i| i.into()).collect();
+        HashMap::from([
+            ("Issues".to_string(), Value::new(issues).to_owned()),
+            (
+                "PendingQuestions".to_string(),
+                Value::new(self.pending_questions).to_owned(),
+            ),
+            (
+                "BusyServices".to_string(),
+                Value::new(self.busy_services.clone()).to_owned(),
+            ),
+            (
+                "CanInstall".to_string(),
+                Value::new(self.can_install).to_owned(),
+            ),
+        ])
+    }
+
+    /// Builds an overview from a D-Bus hash.
+    ///
+    /// The missing or wrong keys are ignored.
+    ///
+    /// * `data`: D-Bus hash.
+    pub fn from_dbus(data: &HashMap<String, OwnedValue>) -> Self {
+        let issues = data
+            .get("Issues")
+            .and_then(|v| <Vec<OverviewIssueFromDBus>>::try_from(v.clone()).ok())
+            .unwrap_or_default();
+        let busy_services = data
+            .get("BusyServices")
+            .and_then(|v| <Vec<String>>::try_from(v.clone()).ok())
+            .unwrap_or_default();
+        Self {
+            issues: issues.into_iter().map(OverviewIssue::from).collect(),
+            pending_questions: data
+                .get("PendingQuestions")
+                .and_then(|v| v.downcast_ref::<u32>())
+                .copied()
+                .unwrap_or_default(),
+            busy_services,
+            can_install: data
+                .get("CanInstall")
+                .and_then(|v| v.downcast_ref::<bool>())
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// D-Bus client for the installation overview
+pub struct OverviewClient<'a> {
+    timeout: CallTimeout,
+    overview_proxy: OverviewProxy<'a>,
+}
+
+impl<'a> OverviewClient<'a> {
+    pub async fn new(connection: Connection) -> zbus::Result<OverviewClient<'a>> {
+        Ok(Self {
+            timeout: CallTimeout::default(),
+            overview_proxy: OverviewProxy::new(&connection).await?,
+        })
+    }
+
+    /// Sets the timeout for the D-Bus calls.
+    ///
+    /// * `timeout`: time to wait for each call.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = CallTimeout::new(timeout);
+        self
+    }
+
+    /// Returns the current installation status.
+    pub async fn overview(&self) -> Result<Overview, ServiceError> {
+        self.timeout
+            .run(async {
+                let data = self.overview_proxy.get().await?;
+                Ok(Overview::from_dbus(&data))
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overview_to_dbus_and_back() {
+        let overview = Overview {
+            issues: vec![OverviewIssue {
+                service: "storage".to_string(),
+                description: "No devices found".to_string(),
+                details: "".to_string(),
+                source: 1,
+                severity: ERROR_SEVERITY,
+            }],
+            pending_questions: 2,
+            busy_services: vec!["org.opensuse.Agama.Software1".to_string()],
+            can_install: false,
+        };
+        assert_eq!(Overview::from_dbus(&overview.to_dbus()), overview);
+        assert!(overview.issues[0].is_error());
+    }
+
+    #[test]
+    fn test_overview_from_incomplete_dbus() {
+        let data = HashMap::from([("CanInstall".to_string(), Value::new(true).to_owned())]);
+        let overview = Overview::from_dbus(&data);
+        assert!(overview.can_install);
+        assert!(overview.issues.is_empty());
+        assert_eq!(overview.pending_questions, 0);
+    }
+}
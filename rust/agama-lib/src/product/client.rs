@@ -2,7 +2,9 @@ use std::collections::HashMap;
 
 use crate::error::ServiceError;
 use crate::software::proxies::SoftwareProductProxy;
+use crate::timeout::CallTimeout;
 use serde::Serialize;
+use std::time::Duration;
 use zbus::Connection;
 
 use super::proxies::RegistrationProxy;
@@ -21,6 +23,7 @@ pub struct Product {
 /// D-Bus client for the software service
 #[derive(Clone)]
 pub struct ProductClient<'a> {
+    timeout: CallTimeout,
     product_proxy: SoftwareProductProxy<'a>,
     registration_proxy: RegistrationProxy<'a>,
 }
@@ -28,62 +31,92 @@ pub struct ProductClient<'a> {
 impl<'a> ProductClient<'a> {
     pub async fn new(connection: Connection) -> Result<ProductClient<'a>, ServiceError> {
         Ok(Self {
+            timeout: CallTimeout::default(),
             product_proxy: SoftwareProductProxy::new(&connection).await?,
             registration_proxy: RegistrationProxy::new(&connection).await?,
         })
     }
 
+    /// Sets the timeout for the D-Bus calls.
+    ///
+    /// * `timeout`: time to wait for each call.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = CallTimeout::new(timeout);
+        self
+    }
+
     /// Returns the available products
     pub async fn products(&self) -> Result<Vec<Product>, ServiceError> {
-        let products: Vec<Product> = self
-            .product_proxy
-            .available_products()
-            .await?
-            .into_iter()
-            .map(|(id, name, data)| {
-                let description = match data.get("description") {
-                    Some(value) => value.try_into().unwrap(),
-                    None => "",
-                };
-                Product {
-                    id,
-                    name,
-                    description: description.to_string(),
-                }
+        self.timeout
+            .run(async {
+                let products: Vec<Product> = self
+                    .product_proxy
+                    .available_products()
+                    .await?
+                    .into_iter()
+                    .map(|(id, name, data)| {
+                        let description = match data.get("description") {
+                            Some(value) => value.try_into().unwrap(),
+                            None => "",
+                        };
+                        Product {
+                            id,
+                            name,
+                            description: description.to_string(),
+                        }
+                    })
+                    .collect();
+                Ok(products)
             })
-            .collect();
-        Ok(products)
+            .await
     }
 
     /// Returns the id of the selected product to install
     pub async fn product(&self) -> Result<String, ServiceError> {
-        Ok(self.product_proxy.selected_product().await?)
+        self.timeout
+            .run(async { Ok(self.product_proxy.selected_product().await?) })
+            .await
     }
 
     /// Selects the product to install
     pub async fn select_product(&self, product_id: &str) -> Result<(), ServiceError> {
-        let result = self.product_proxy.select_product(product_id).await?;
+        self.timeout
+            .run(async {
+                let result = self.product_proxy.select_product(product_id).await?;
 
-        match result {
-            (0, _) => Ok(()),
-            (3, description) => {
-                let products = self.products().await?;
-                let ids: Vec<String> = products.into_iter().map(|p| p.id).collect();
-                let error = format!("{0}. Available products: '{1:?}'", description, ids);
-                Err(ServiceError::UnsuccessfulAction(error))
-            }
-            (_, description) => Err(ServiceError::UnsuccessfulAction(description)),
-        }
+                match result {
+                    (0, _) => Ok(()),
+                    (3, description) => {
+                        let products = self.products().await?;
+                        let ids: Vec<String> = products.into_iter().map(|p| p.id).collect();
+                        let error = format!("{0}. Available products: '{1:?}'", description, ids);
+                        Err(ServiceError::UnsuccessfulAction(error))
+                    }
+                    (_, description) => Err(ServiceError::UnsuccessfulAction(description)),
+                }
+            })
+            .await
     }
 
     /// registration code used to register product
     pub async fn registration_code(&self) -> Result<String, ServiceError> {
-        Ok(self.registration_proxy.reg_code().await?)
+        self.timeout
+            .run(async { Ok(self.registration_proxy.reg_code().await?) })
+            .await
     }
 
     /// email used to register product
     pub async fn email(&self) -> Result<String, ServiceError> {
-        Ok(self.registration_proxy.email().await?)
+        self.timeout
+            .run(async { Ok(self.registration_proxy.email().await?) })
+            .await
+    }
+
+    /// whether the registration is optional (1), mandatory (2) or not required (0)
+    pub async fn registration_requirement(&self) -> Result<u32, ServiceError> {
+        self.timeout
+            .run(async { Ok(self.registration_proxy.requirement().await?) })
+            .await
     }
 
     /// register product
@@ -94,4 +127,9 @@ impl<'a> ProductClient<'a> {
         }
         Ok(self.registration_proxy.register(code, options).await?)
     }
+
+    /// deregister product
+    pub async fn deregister(&self) -> Result<(u32, String), ServiceError> {
+        Ok(self.registration_proxy.deregister().await?)
+    }
 }
@@ -2,7 +2,10 @@
 
 use super::{ProductClient, ProductSettings};
 use crate::error::ServiceError;
+use crate::install_settings::{InstallSettings, Scope};
 use crate::manager::ManagerClient;
+use crate::store::SettingsStore;
+use async_trait::async_trait;
 use zbus::Connection;
 
 /// Loads and stores the product settings from/to the D-Bus service.
@@ -63,3 +66,22 @@ impl<'a> ProductStore<'a> {
         Ok(())
     }
 }
+
+#[async_trait]
+impl<'a> SettingsStore for ProductStore<'a> {
+    fn scope(&self) -> Scope {
+        Scope::Product
+    }
+
+    async fn load_section(&self, settings: &mut InstallSettings) -> Result<(), ServiceError> {
+        settings.product = Some(self.load().await?);
+        Ok(())
+    }
+
+    async fn store_section(&self, settings: &InstallSettings) -> Result<(), ServiceError> {
+        if let Some(product) = &settings.product {
+            self.store(product).await?;
+        }
+        Ok(())
+    }
+}
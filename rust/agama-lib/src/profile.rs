@@ -1,6 +1,6 @@
 use crate::error::ProfileError;
+use crate::transfer::{Transfer, Verification};
 use anyhow::Context;
-use curl::easy::Easy;
 use jsonschema::JSONSchema;
 use log::info;
 use serde_json;
@@ -11,17 +11,33 @@ use url::Url;
 /// Downloads a profile for a given location.
 pub struct ProfileReader {
     url: Url,
+    verification: Verification,
 }
 
 impl ProfileReader {
     pub fn new(url: &str) -> anyhow::Result<Self> {
         let url = Url::parse(url)?;
-        Ok(Self { url })
+        Ok(Self {
+            url,
+            verification: Verification::default(),
+        })
+    }
+
+    /// Sets the checks (checksum and signature) to run on the downloaded profile.
+    ///
+    /// * `verification`: checks to run.
+    pub fn with_verification(mut self, verification: Verification) -> Self {
+        self.verification = verification;
+        self
     }
 
     pub fn read(&self) -> anyhow::Result<String> {
         let path = self.url.path();
         if path.ends_with(".xml") || path.ends_with(".erb") || path.ends_with('/') {
+            // agama-autoyast fetches the profile (and the rules) on its own
+            if !self.verification.is_empty() {
+                anyhow::bail!("AutoYaST profiles cannot be verified");
+            }
             self.read_from_autoyast()
         } else {
             self.read_from_url()
@@ -30,17 +46,7 @@ impl ProfileReader {
 
     fn read_from_url(&self) -> anyhow::Result<String> {
         let mut buf = Vec::new();
-        {
-            let mut handle = Easy::new();
-            handle.url(self.url.as_str())?;
-
-            let mut transfer = handle.transfer();
-            transfer.write_function(|data| {
-                buf.extend(data);
-                Ok(data.len())
-            })?;
-            transfer.perform().unwrap();
-        }
+        Transfer::get_verified(self.url.as_str(), &mut buf, &self.verification)?;
         Ok(String::from_utf8(buf)?)
     }
 
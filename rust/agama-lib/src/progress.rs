@@ -37,6 +37,10 @@
 //!     async fn finish(&mut self) {
 //!         println!("Done");
 //!     }
+//!
+//!     async fn failed(&mut self, message: &str) {
+//!         println!("Failed: {}", message);
+//!     }
 //! }
 //!
 //! async fn run_monitor() {
@@ -45,11 +49,34 @@
 //!   monitor.run(SimplePresenter {}).await;
 //!}
 //! ```
+//!
+//! When the monitor starts, it replays the latest known state (including the progress detail), so
+//! a client that attaches once the operation is running does not need to wait for the next change.
+//! Additionally, the monitor keeps the latest updates in a [ProgressHistory]. To survive the restart
+//! of the services, build the monitor with [ProgressMonitor::with_manager].
+//!
+//! If you do not need a custom presenter, enable the `cli` feature to get an `IndicatifPresenter`,
+//! which draws the main progress and the progress detail in the terminal.
 
-use crate::{error::ServiceError, proxies::ProgressProxy};
+#[cfg(feature = "cli")]
+mod cli;
+#[cfg(feature = "cli")]
+pub use cli::IndicatifPresenter;
+
+use crate::{
+    connection_manager::{ConnectionManager, ServiceEvent},
+    error::ServiceError,
+    proxies::ProgressProxy,
+};
 use async_trait::async_trait;
 use serde::Serialize;
-use tokio_stream::{StreamExt, StreamMap};
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast;
+use tokio_stream::{Stream, StreamExt, StreamMap};
 use zbus::Connection;
 
 /// Represents the progress for an Agama service.
@@ -61,23 +88,130 @@ pub struct Progress {
     pub max_steps: u32,
     /// Title of the current step
     pub current_title: String,
+    /// Percentage of the current step which is already done, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_percentage: Option<u8>,
     /// Whether the progress reporting is finished
     pub finished: bool,
+    /// Error message if the operation failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 impl Progress {
     pub async fn from_proxy(proxy: &crate::proxies::ProgressProxy<'_>) -> zbus::Result<Progress> {
-        let (current_step, max_steps, finished) =
-            tokio::join!(proxy.current_step(), proxy.total_steps(), proxy.finished());
+        let (current_step, max_steps, percentage, finished, error) = tokio::join!(
+            proxy.current_step(),
+            proxy.total_steps(),
+            proxy.step_percentage(),
+            proxy.finished(),
+            proxy.error()
+        );
 
         let (current_step, current_title) = current_step?;
         Ok(Self {
             current_step,
             current_title,
             max_steps: max_steps?,
+            current_percentage: Self::percentage_from_dbus(percentage?),
             finished: finished?,
+            error: Some(error?).filter(|e| !e.is_empty()),
         })
     }
+
+    /// Whether the operation failed.
+    pub fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Converts the percentage reported through D-Bus, where a negative value means "unknown".
+    fn percentage_from_dbus(value: i32) -> Option<u8> {
+        u8::try_from(value).ok().map(|p| p.min(100))
+    }
+}
+
+/// Source of a progress update.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum ProgressSource {
+    /// Main progress (reported by the manager service).
+    Main,
+    /// Progress detail (reported by the software service).
+    Detail,
+}
+
+/// Progress update recorded in the [ProgressHistory].
+#[derive(Clone, Debug, Serialize)]
+pub struct ProgressEntry {
+    pub source: ProgressSource,
+    pub progress: Progress,
+}
+
+/// Ring buffer holding the latest progress updates.
+///
+/// When it is full, the oldest updates are discarded.
+#[derive(Clone, Debug)]
+pub struct ProgressHistory {
+    entries: VecDeque<ProgressEntry>,
+    capacity: usize,
+}
+
+impl ProgressHistory {
+    /// Default number of updates to keep.
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    /// Creates a new history.
+    ///
+    /// * `capacity`: maximum number of updates to keep.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Records a progress update.
+    ///
+    /// * `source`: where the update comes from.
+    /// * `progress`: progress update.
+    pub fn push(&mut self, source: ProgressSource, progress: Progress) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(ProgressEntry { source, progress });
+    }
+
+    /// Returns the latest update from the given source.
+    ///
+    /// * `source`: source of the update.
+    pub fn latest(&self, source: ProgressSource) -> Option<&Progress> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.source == source)
+            .map(|e| &e.progress)
+    }
+
+    /// Returns the recorded updates, from the oldest to the newest.
+    pub fn entries(&self) -> impl Iterator<Item = &ProgressEntry> {
+        self.entries.iter()
+    }
+}
+
+impl Default for ProgressHistory {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+/// Outcome of watching the progress changes.
+enum WatchOutcome {
+    /// The operation is over.
+    Finished,
+    /// A monitored service was restarted, so the proxies must be rebuilt.
+    Restarted,
 }
 
 /// Monitorizes and reports the progress of Agama's current operation.
@@ -85,77 +219,228 @@ impl Progress {
 /// It implements a main/details reporter by listening to the manager and software services,
 /// similar to Agama's web UI. How this information is displayed depends on the presenter (see
 /// [ProgressMonitor.run]).
+///
+/// When it is created through [ProgressMonitor::with_manager], it survives the restart of the
+/// monitored services by rebuilding its proxies.
 pub struct ProgressMonitor<'a> {
+    connection: Connection,
     manager_proxy: ProgressProxy<'a>,
     software_proxy: ProgressProxy<'a>,
+    history: Arc<Mutex<ProgressHistory>>,
+    services: Option<ConnectionManager>,
 }
 
 impl<'a> ProgressMonitor<'a> {
+    const MANAGER_SERVICE: &'static str = "org.opensuse.Agama.Manager1";
+    const SOFTWARE_SERVICE: &'static str = "org.opensuse.Agama.Software1";
+
     pub async fn new(connection: Connection) -> Result<ProgressMonitor<'a>, ServiceError> {
-        let manager_proxy = ProgressProxy::builder(&connection)
+        let (manager_proxy, software_proxy) = Self::build_proxies(&connection).await?;
+
+        Ok(Self {
+            connection,
+            manager_proxy,
+            software_proxy,
+            history: Arc::new(Mutex::new(ProgressHistory::default())),
+            services: None,
+        })
+    }
+
+    /// Creates a monitor which rebuilds its proxies when the monitored services are restarted.
+    ///
+    /// * `manager`: connection manager to get the connection and the services changes from.
+    pub async fn with_manager(
+        manager: &ConnectionManager,
+    ) -> Result<ProgressMonitor<'a>, ServiceError> {
+        let mut monitor = Self::new(manager.connection()).await?;
+        monitor.services = Some(manager.clone());
+        Ok(monitor)
+    }
+
+    async fn build_proxies(
+        connection: &Connection,
+    ) -> Result<(ProgressProxy<'a>, ProgressProxy<'a>), ServiceError> {
+        let manager_proxy = ProgressProxy::builder(connection)
             .path("/org/opensuse/Agama/Manager1")?
-            .destination("org.opensuse.Agama.Manager1")?
+            .destination(Self::MANAGER_SERVICE)?
             .build()
             .await?;
 
-        let software_proxy = ProgressProxy::builder(&connection)
+        let software_proxy = ProgressProxy::builder(connection)
             .path("/org/opensuse/Agama/Software1")?
-            .destination("org.opensuse.Agama.Software1")?
+            .destination(Self::SOFTWARE_SERVICE)?
             .build()
             .await?;
 
-        Ok(Self {
-            manager_proxy,
-            software_proxy,
-        })
+        Ok((manager_proxy, software_proxy))
+    }
+
+    /// Returns the history of progress updates.
+    ///
+    /// The history is shared, so it can be inspected while the monitor is running (e.g., to
+    /// replay the latest updates to a client that attaches late).
+    pub fn history(&self) -> Arc<Mutex<ProgressHistory>> {
+        Arc::clone(&self.history)
     }
 
     /// Runs the monitor until the current operation finishes.
+    ///
+    /// It starts by replaying the latest state, so the presenter gets the progress detail even
+    /// if it does not change anymore. If the operation fails, it calls
+    /// [ProgressPresenter::failed] and returns a [ServiceError::FailedOperation] error.
     pub async fn run(&mut self, mut presenter: impl ProgressPresenter) -> Result<(), ServiceError> {
-        presenter.start(&self.main_progress().await?).await;
+        let mut restarts = self.services.as_ref().map(|s| s.subscribe());
+
+        let main_progress = self.main_progress().await?;
+        if let Some(error) = main_progress.error {
+            presenter.failed(&error).await;
+            return Err(ServiceError::FailedOperation(error));
+        }
+        presenter.start(&main_progress).await;
+        self.replay_detail(&mut presenter).await?;
+
+        while let WatchOutcome::Restarted = self.watch(&mut presenter, &mut restarts).await? {
+            log::info!("Rebuilding the progress proxies after a service restart");
+            (self.manager_proxy, self.software_proxy) =
+                Self::build_proxies(&self.connection).await?;
+
+            let progress = self.main_progress().await?;
+            if self.report_main(&mut presenter, progress).await? {
+                break;
+            }
+            self.replay_detail(&mut presenter).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Reports the progress changes until the operation is over or a service is restarted.
+    ///
+    /// * `presenter`: presenter to report the changes to.
+    /// * `restarts`: services changes, if the monitor is watching them.
+    async fn watch(
+        &self,
+        presenter: &mut impl ProgressPresenter,
+        restarts: &mut Option<broadcast::Receiver<ServiceEvent>>,
+    ) -> Result<WatchOutcome, ServiceError> {
         let mut changes = self.build_stream().await;
 
-        while let Some(stream) = changes.next().await {
-            match stream {
-                ("/org/opensuse/Agama/Manager1", _) => {
-                    let progress = self.main_progress().await?;
-                    if progress.finished {
-                        presenter.finish().await;
-                        return Ok(());
-                    }
-                    presenter.update_main(&progress).await;
+        loop {
+            tokio::select! {
+                change = changes.next() => {
+                    let Some((path, _)) = change else {
+                        return Ok(WatchOutcome::Finished);
+                    };
+                    match path {
+                        "/org/opensuse/Agama/Manager1" => {
+                            let progress = self.main_progress().await?;
+                            if self.report_main(presenter, progress).await? {
+                                return Ok(WatchOutcome::Finished);
+                            }
+                        }
+                        "/org/opensuse/Agama/Software1" => {
+                            let progress = &self.detail_progress().await?;
+                            presenter.update_detail(progress).await;
+                        }
+                        _ => eprintln!("Unknown"),
+                    };
                 }
-                ("/org/opensuse/Agama/Software1", _) => {
-                    let progress = &self.detail_progress().await?;
-                    presenter.update_detail(progress).await;
+                Some(ServiceEvent::Started(name)) = Self::next_service_event(restarts) => {
+                    if name == Self::MANAGER_SERVICE || name == Self::SOFTWARE_SERVICE {
+                        return Ok(WatchOutcome::Restarted);
+                    }
                 }
-                _ => eprintln!("Unknown"),
-            };
+            }
         }
+    }
 
+    /// Reports a change in the main progress.
+    ///
+    /// It returns whether the operation is over.
+    ///
+    /// * `presenter`: presenter to report the change to.
+    /// * `progress`: main progress.
+    async fn report_main(
+        &self,
+        presenter: &mut impl ProgressPresenter,
+        progress: Progress,
+    ) -> Result<bool, ServiceError> {
+        if let Some(error) = progress.error {
+            presenter.failed(&error).await;
+            return Err(ServiceError::FailedOperation(error));
+        }
+        if progress.finished {
+            presenter.finish().await;
+            return Ok(true);
+        }
+        presenter.update_main(&progress).await;
+        Ok(false)
+    }
+
+    /// Reports the progress detail if there is an ongoing one.
+    ///
+    /// * `presenter`: presenter to report the progress detail to.
+    async fn replay_detail(
+        &self,
+        presenter: &mut impl ProgressPresenter,
+    ) -> Result<(), ServiceError> {
+        let detail_progress = self.detail_progress().await?;
+        if !detail_progress.finished && detail_progress.max_steps > 0 {
+            presenter.update_detail(&detail_progress).await;
+        }
         Ok(())
     }
 
-    /// Proxy that reports the progress.
+    /// Waits for the next change in the services, if the monitor is watching them.
+    ///
+    /// * `restarts`: services changes.
+    async fn next_service_event(
+        restarts: &mut Option<broadcast::Receiver<ServiceEvent>>,
+    ) -> Option<ServiceEvent> {
+        match restarts {
+            Some(restarts) => restarts.recv().await.ok(),
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Records a progress update in the history.
+    ///
+    /// * `source`: where the update comes from.
+    /// * `progress`: progress update.
+    fn record(&self, source: ProgressSource, progress: &Progress) {
+        if let Ok(mut history) = self.history.lock() {
+            history.push(source, progress.clone());
+        }
+    }
+
+    /// Reads the main progress and records it in the history.
     async fn main_progress(&self) -> Result<Progress, ServiceError> {
-        Ok(Progress::from_proxy(&self.manager_proxy).await?)
+        let progress = Progress::from_proxy(&self.manager_proxy).await?;
+        self.record(ProgressSource::Main, &progress);
+        Ok(progress)
     }
 
-    /// Proxy that reports the progress detail.
+    /// Reads the progress detail and records it in the history.
     async fn detail_progress(&self) -> Result<Progress, ServiceError> {
-        Ok(Progress::from_proxy(&self.software_proxy).await?)
+        let progress = Progress::from_proxy(&self.software_proxy).await?;
+        self.record(ProgressSource::Detail, &progress);
+        Ok(progress)
     }
 
     /// Builds an stream of progress changes.
     ///
-    /// It listens for changes in the `Current` property and generates a stream identifying the
-    /// proxy where the change comes from.
-    async fn build_stream(&self) -> StreamMap<&str, zbus::PropertyStream<'_, (u32, String)>> {
+    /// It listens for changes in the `CurrentStep`, `StepPercentage` and `Error` properties and
+    /// generates a stream identifying the proxy where the change comes from.
+    async fn build_stream(&self) -> StreamMap<&str, Pin<Box<dyn Stream<Item = ()> + Send + '_>>> {
         let mut streams = StreamMap::new();
 
         let proxies = [&self.manager_proxy, &self.software_proxy];
         for proxy in proxies.iter() {
-            let stream = proxy.receive_current_step_changed().await;
+            let steps = proxy.receive_current_step_changed().await.map(|_| ());
+            let percentages = proxy.receive_step_percentage_changed().await.map(|_| ());
+            let errors = proxy.receive_error_changed().await.map(|_| ());
+            let stream: Pin<Box<dyn Stream<Item = ()> + Send + '_>> =
+                Box::pin(steps.merge(percentages).merge(errors));
             let path = proxy.path().as_str();
             streams.insert(path, stream);
         }
@@ -183,4 +468,59 @@ pub trait ProgressPresenter {
 
     /// Finishes the progress reporting.
     async fn finish(&mut self);
+
+    /// Finishes the progress reporting because the operation failed.
+    ///
+    /// * `message`: error message.
+    async fn failed(&mut self, message: &str);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Progress, ProgressHistory, ProgressSource};
+
+    fn progress(current_step: u32) -> Progress {
+        Progress {
+            current_step,
+            max_steps: 3,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_percentage_from_dbus() {
+        assert_eq!(Progress::percentage_from_dbus(-1), None);
+        assert_eq!(Progress::percentage_from_dbus(42), Some(42));
+        assert_eq!(Progress::percentage_from_dbus(120), Some(100));
+    }
+
+    #[test]
+    fn test_history_discards_oldest_entries() {
+        let mut history = ProgressHistory::new(2);
+        history.push(ProgressSource::Main, progress(1));
+        history.push(ProgressSource::Detail, progress(1));
+        history.push(ProgressSource::Main, progress(2));
+
+        let steps: Vec<_> = history.entries().map(|e| e.progress.current_step).collect();
+        assert_eq!(steps, vec![1, 2]);
+        assert_eq!(
+            history.entries().next().unwrap().source,
+            ProgressSource::Detail
+        );
+    }
+
+    #[test]
+    fn test_history_latest() {
+        let mut history = ProgressHistory::default();
+        assert!(history.latest(ProgressSource::Main).is_none());
+
+        history.push(ProgressSource::Main, progress(1));
+        history.push(ProgressSource::Detail, progress(1));
+        history.push(ProgressSource::Main, progress(2));
+
+        let main = history.latest(ProgressSource::Main).unwrap();
+        assert_eq!(main.current_step, 2);
+        let detail = history.latest(ProgressSource::Detail).unwrap();
+        assert_eq!(detail.current_step, 1);
+    }
 }
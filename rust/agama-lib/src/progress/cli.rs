@@ -0,0 +1,111 @@
+//! Terminal presenter for the progress reported by Agama.
+//!
+//! It is only available when the `cli` feature is enabled.
+
+use super::{Progress, ProgressPresenter};
+use async_trait::async_trait;
+use console::style;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::time::Duration;
+
+/// Reports the progress through the terminal using [indicatif].
+///
+/// It draws two bars: one for the main progress and another one for the progress detail (e.g.,
+/// the package installation). The detail bar is only shown while the detail is being reported.
+pub struct IndicatifPresenter {
+    bars: MultiProgress,
+    main: Option<ProgressBar>,
+    detail: Option<ProgressBar>,
+}
+
+impl IndicatifPresenter {
+    pub fn new() -> Self {
+        Self {
+            bars: MultiProgress::new(),
+            main: None,
+            detail: None,
+        }
+    }
+
+    fn main_bar(&mut self) -> &ProgressBar {
+        self.main.get_or_insert_with(|| {
+            let style = ProgressStyle::with_template("{prefix:.bold.green} {wide_msg}").unwrap();
+            self.bars.add(ProgressBar::new(0).with_style(style))
+        })
+    }
+
+    fn detail_bar(&mut self) -> &ProgressBar {
+        self.detail.get_or_insert_with(|| {
+            let style = ProgressStyle::with_template("{spinner:.green} {wide_msg}").unwrap();
+            let bar = self.bars.add(ProgressBar::new(0).with_style(style));
+            bar.enable_steady_tick(Duration::from_millis(120));
+            bar
+        })
+    }
+
+    fn clear_detail(&mut self) {
+        if let Some(bar) = self.detail.take() {
+            bar.finish_and_clear();
+            self.bars.remove(&bar);
+        }
+    }
+
+    fn message(progress: &Progress) -> String {
+        match progress.current_percentage {
+            Some(percentage) => format!("{} ({}%)", progress.current_title, percentage),
+            None => progress.current_title.to_owned(),
+        }
+    }
+}
+
+impl Default for IndicatifPresenter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ProgressPresenter for IndicatifPresenter {
+    async fn start(&mut self, progress: &Progress) {
+        if !progress.finished {
+            self.update_main(progress).await;
+        }
+    }
+
+    async fn update_main(&mut self, progress: &Progress) {
+        let counter = format!("[{}/{}]", &progress.current_step, &progress.max_steps);
+        let bar = self.main_bar();
+        bar.set_length(progress.max_steps.into());
+        bar.set_position(progress.current_step.into());
+        bar.set_prefix(counter);
+        bar.set_message(Self::message(progress));
+    }
+
+    async fn update_detail(&mut self, progress: &Progress) {
+        if progress.finished {
+            self.clear_detail();
+            return;
+        }
+
+        let bar = self.detail_bar();
+        bar.set_length(progress.max_steps.into());
+        bar.set_position(progress.current_step.into());
+        bar.set_message(Self::message(progress));
+    }
+
+    async fn finish(&mut self) {
+        self.clear_detail();
+        if let Some(bar) = self.main.take() {
+            bar.finish_with_message(style("Finished").bold().to_string());
+        }
+    }
+
+    async fn failed(&mut self, message: &str) {
+        self.clear_detail();
+        let message = format!("{} {}", style("Failed:").bold().red(), message);
+        match self.main.take() {
+            Some(bar) => bar.abandon_with_message(message),
+            None => eprintln!("{}", message),
+        }
+    }
+}
@@ -16,10 +16,18 @@ trait Progress {
     #[dbus_proxy(property)]
     fn current_step(&self) -> zbus::Result<(u32, String)>;
 
+    /// Error property
+    #[dbus_proxy(property)]
+    fn error(&self) -> zbus::Result<String>;
+
     /// Finished property
     #[dbus_proxy(property)]
     fn finished(&self) -> zbus::Result<bool>;
 
+    /// StepPercentage property
+    #[dbus_proxy(property)]
+    fn step_percentage(&self) -> zbus::Result<i32>;
+
     /// TotalSteps property
     #[dbus_proxy(property)]
     fn total_steps(&self) -> zbus::Result<u32>;
@@ -57,6 +65,12 @@ trait Manager {
     /// Commit method
     fn commit(&self) -> zbus::Result<()>;
 
+    /// GetLogsAfter method
+    fn get_logs_after(&self, service: &str, cursor: &str) -> zbus::Result<(Vec<String>, String)>;
+
+    /// GetRecentLogs method
+    fn get_recent_logs(&self, service: &str, lines: u32) -> zbus::Result<(Vec<String>, String)>;
+
     /// Probe method
     fn probe(&self) -> zbus::Result<()>;
 
@@ -114,3 +128,65 @@ trait Questions1 {
     #[dbus_proxy(property)]
     fn set_interactive(&self, value: bool) -> zbus::Result<()>;
 }
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Questions.Generic",
+    default_service = "org.opensuse.Agama1"
+)]
+trait GenericQuestion {
+    /// Answer property
+    #[dbus_proxy(property)]
+    fn answer(&self) -> zbus::Result<String>;
+
+    /// Class property
+    #[dbus_proxy(property)]
+    fn class(&self) -> zbus::Result<String>;
+
+    /// DefaultOption property
+    #[dbus_proxy(property)]
+    fn default_option(&self) -> zbus::Result<String>;
+
+    /// Options property
+    #[dbus_proxy(property)]
+    fn options(&self) -> zbus::Result<Vec<String>>;
+
+    /// Text property
+    #[dbus_proxy(property)]
+    fn text(&self) -> zbus::Result<String>;
+}
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Issues",
+    default_service = "org.opensuse.Agama.Storage1",
+    default_path = "/org/opensuse/Agama/Storage1"
+)]
+trait Issues {
+    /// All property
+    #[dbus_proxy(property)]
+    fn all(&self) -> zbus::Result<Vec<(String, String, u32, u32)>>;
+}
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Validation",
+    default_service = "org.opensuse.Agama.Manager1",
+    default_path = "/org/opensuse/Agama/Users1"
+)]
+trait Validation {
+    /// Errors property
+    #[dbus_proxy(property)]
+    fn errors(&self) -> zbus::Result<Vec<String>>;
+
+    /// Valid property
+    #[dbus_proxy(property)]
+    fn valid(&self) -> zbus::Result<bool>;
+}
+
+#[dbus_proxy(
+    interface = "org.opensuse.Agama1.Overview",
+    default_service = "org.opensuse.Agama1",
+    default_path = "/org/opensuse/Agama1/Overview"
+)]
+trait Overview {
+    /// Get method
+    fn get(&self) -> zbus::Result<std::collections::HashMap<String, zbus::zvariant::OwnedValue>>;
+}
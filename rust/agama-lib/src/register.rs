@@ -0,0 +1,48 @@
+//! Registration of the products
+//!
+//! Registered products (e.g., SUSE Linux Enterprise) get their repositories from the SUSE Customer
+//! Center (SCC) or from a local Repository Mirroring Tool (RMT) server. The software service
+//! registers the selected product through its `org.opensuse.Agama1.Registration` D-Bus interface
+//! (see [crate::product::ProductClient]). This module offers a small HTTPS client to talk to those
+//! servers directly, which is useful for things that the service does not offer yet, like listing
+//! the available extensions:
+//!
+//! * [SccClient] implements the calls of the SCC connect API (announcing the system, activating a
+//!   product and listing its extensions).
+//! * [ProxySettings] reads the proxy configuration (`/etc/sysconfig/proxy`), which is written when
+//!   a proxy is set on the kernel command line.
+//! * [SystemCredentials] reads and writes the credentials of the announced system.
+//!
+//! ```no_run
+//! # use agama_lib::register::{ProductTarget, SccClient, SystemCredentials};
+//! let client = SccClient::default();
+//! let credentials = SystemCredentials::read_default().unwrap();
+//! let product = ProductTarget::new("SLES", "15.5");
+//! for extension in client.extensions(&credentials, &product).unwrap() {
+//!     println!("{} ({})", extension.name, extension.identifier);
+//! }
+//! ```
+
+mod credentials;
+mod proxy;
+mod scc;
+
+pub use credentials::SystemCredentials;
+pub use proxy::ProxySettings;
+pub use scc::{Extension, ProductTarget, SccClient, DEFAULT_SCC_URL};
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum RegistrationError {
+    #[error("{}", crate::tr!("Could not connect to the registration server: {}", .0))]
+    Connection(#[from] curl::Error),
+    #[error("{}", crate::tr!("The registration server returned an error ({}): {}", .0, .1))]
+    Server(u32, String),
+    #[error("{}", crate::tr!("Unexpected response from the registration server: {}", .0))]
+    InvalidResponse(#[from] serde_json::Error),
+    #[error("{}", crate::tr!("Invalid credentials file: '{}'", .0))]
+    InvalidCredentials(String),
+    #[error("{}", crate::tr!("I/O error: {}", .0))]
+    IO(#[from] std::io::Error),
+}
@@ -0,0 +1,120 @@
+//! Credentials of the announced system.
+use super::RegistrationError;
+use std::{
+    fs,
+    io::Write,
+    os::unix::fs::{OpenOptionsExt, PermissionsExt},
+    path::Path,
+};
+
+/// Default location of the system credentials (as written by SUSEConnect).
+pub const DEFAULT_CREDENTIALS_PATH: &str = "/etc/zypp/credentials.d/SCCcredentials";
+
+/// Credentials that the registration server assigns to the system when it is announced.
+///
+/// They are stored using the zypp credentials format:
+///
+/// ```text
+/// username=SCC_0123456789abcdef
+/// password=secret
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SystemCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl SystemCredentials {
+    pub fn new(username: &str, password: &str) -> Self {
+        Self {
+            username: username.to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    /// Reads the credentials from the default location.
+    pub fn read_default() -> Result<Self, RegistrationError> {
+        Self::read(Path::new(DEFAULT_CREDENTIALS_PATH))
+    }
+
+    /// Reads the credentials from a file.
+    ///
+    /// * `path`: credentials file.
+    pub fn read(path: &Path) -> Result<Self, RegistrationError> {
+        let content = fs::read_to_string(path)?;
+        Self::parse(&content)
+            .ok_or_else(|| RegistrationError::InvalidCredentials(path.display().to_string()))
+    }
+
+    /// Writes the credentials to a file which is only readable by its owner.
+    ///
+    /// * `path`: credentials file.
+    pub fn write(&self, path: &Path) -> Result<(), RegistrationError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)?;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        write!(file, "{}", self.to_content())?;
+        Ok(())
+    }
+
+    /// Parses the content of a credentials file.
+    ///
+    /// Unknown keys (e.g., `system_token`) are ignored.
+    ///
+    /// * `content`: content of the file.
+    fn parse(content: &str) -> Option<Self> {
+        let mut username = None;
+        let mut password = None;
+        for line in content.lines() {
+            match line.trim().split_once('=') {
+                Some(("username", value)) => username = Some(value.trim().to_string()),
+                Some(("password", value)) => password = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+        Some(Self {
+            username: username.filter(|u| !u.is_empty())?,
+            password: password?,
+        })
+    }
+
+    fn to_content(&self) -> String {
+        format!("username={}\npassword={}\n", self.username, self.password)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_credentials() {
+        let content = "username=SCC_0123\npassword=secret\nsystem_token=abcd\n";
+        assert_eq!(
+            SystemCredentials::parse(content),
+            Some(SystemCredentials::new("SCC_0123", "secret"))
+        );
+
+        assert_eq!(SystemCredentials::parse("password=secret\n"), None);
+    }
+
+    #[test]
+    fn test_write_and_read_credentials() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("credentials.d/SCCcredentials");
+        let credentials = SystemCredentials::new("SCC_0123", "secret");
+        credentials.write(&path).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(SystemCredentials::read(&path).unwrap(), credentials);
+    }
+}
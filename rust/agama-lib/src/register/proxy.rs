@@ -0,0 +1,107 @@
+//! Proxy configuration.
+use std::{fs, io, path::Path};
+
+/// Location of the system-wide proxy configuration.
+const SYSCONFIG_PROXY_PATH: &str = "/etc/sysconfig/proxy";
+
+/// Proxy to use for the HTTPS connections.
+///
+/// When no proxy is configured, curl still honors the usual environment variables (`https_proxy`,
+/// `no_proxy`, etc.).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProxySettings {
+    /// Proxy URL (e.g., "http://proxy.example.net:3128").
+    pub url: Option<String>,
+    /// Comma-separated list of hosts which should be reached directly.
+    pub no_proxy: Option<String>,
+}
+
+impl ProxySettings {
+    /// Reads the proxy settings from `/etc/sysconfig/proxy`.
+    ///
+    /// It returns the default settings (no proxy) if the file does not exist.
+    pub fn from_sysconfig() -> io::Result<Self> {
+        Self::read(Path::new(SYSCONFIG_PROXY_PATH))
+    }
+
+    /// Reads the proxy settings from a file using the sysconfig format.
+    ///
+    /// * `path`: path of the file.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(Self::parse(&content)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parses the content of the sysconfig file.
+    ///
+    /// The HTTPS proxy is preferred over the HTTP one, as the registration servers are reached
+    /// through HTTPS.
+    ///
+    /// * `content`: content of the file.
+    fn parse(content: &str) -> Self {
+        let mut enabled = false;
+        let mut https_proxy = None;
+        let mut http_proxy = None;
+        let mut no_proxy = None;
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().trim_matches('"').trim().to_string();
+            let value = Some(value).filter(|v| !v.is_empty());
+            match key.trim() {
+                "PROXY_ENABLED" => enabled = value.is_some_and(|v| v == "yes"),
+                "HTTPS_PROXY" => https_proxy = value,
+                "HTTP_PROXY" => http_proxy = value,
+                "NO_PROXY" => no_proxy = value,
+                _ => {}
+            }
+        }
+
+        if !enabled {
+            return Self::default();
+        }
+
+        Self {
+            url: https_proxy.or(http_proxy),
+            no_proxy: no_proxy.map(|v| v.replace(' ', "")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proxy_settings() {
+        let content = r#"
+## Path:	Network/Proxy
+PROXY_ENABLED="yes"
+HTTP_PROXY="http://proxy.example.net:3128"
+HTTPS_PROXY=""
+NO_PROXY="localhost, 127.0.0.1"
+"#;
+        assert_eq!(
+            ProxySettings::parse(content),
+            ProxySettings {
+                url: Some("http://proxy.example.net:3128".to_string()),
+                no_proxy: Some("localhost,127.0.0.1".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_disabled_proxy_settings() {
+        let content = "PROXY_ENABLED=\"no\"\nHTTPS_PROXY=\"http://proxy.example.net:3128\"\n";
+        assert_eq!(ProxySettings::parse(content), ProxySettings::default());
+    }
+}
@@ -0,0 +1,372 @@
+//! Client for the SCC connect API, which is implemented by SCC and RMT servers.
+use super::{ProxySettings, RegistrationError, SystemCredentials};
+use curl::easy::{Easy, List};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+/// URL of the SUSE Customer Center.
+pub const DEFAULT_SCC_URL: &str = "https://scc.suse.com";
+
+const ACCEPT_HEADER: &str = "Accept: application/json,application/vnd.scc.suse.com.v4+json";
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Product as the registration server identifies it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProductTarget {
+    /// Product identifier (e.g., "SLES").
+    pub identifier: String,
+    /// Product version (e.g., "15.5").
+    pub version: String,
+    /// Architecture using the RPM naming (e.g., "x86_64" or "ppc64le").
+    pub arch: String,
+}
+
+impl ProductTarget {
+    /// Creates a product target for the architecture of the running system.
+    ///
+    /// * `identifier`: product identifier.
+    /// * `version`: product version.
+    pub fn new(identifier: &str, version: &str) -> Self {
+        Self {
+            identifier: identifier.to_string(),
+            version: version.to_string(),
+            arch: rpm_arch().to_string(),
+        }
+    }
+
+    /// Distribution target used when announcing the system (e.g., "SLES-15-x86_64").
+    pub fn distro_target(&self) -> String {
+        let major = self.version.split('.').next().unwrap_or("1");
+        format!("{}-{}-{}", self.identifier, major, self.arch)
+    }
+}
+
+/// Extension (or module) of a product.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Extension {
+    /// Extension identifier (e.g., "sle-module-basesystem").
+    pub identifier: String,
+    /// Extension version (e.g., "15.5").
+    pub version: String,
+    /// Extension architecture.
+    pub arch: String,
+    /// Human readable name (e.g., "Basesystem Module 15 SP5 x86_64").
+    #[serde(rename = "friendly_name")]
+    pub name: String,
+    /// Whether the extension can be activated without a registration code.
+    #[serde(default)]
+    pub free: bool,
+    /// Whether the extension is recommended.
+    #[serde(default)]
+    pub recommended: bool,
+    /// Extensions that depend on this one.
+    #[serde(default)]
+    pub extensions: Vec<Extension>,
+}
+
+/// Product description returned by the server (only the relevant parts).
+#[derive(Deserialize)]
+struct ProductResponse {
+    #[serde(default)]
+    extensions: Vec<Extension>,
+}
+
+/// Authentication method for each API call.
+enum Auth<'a> {
+    /// Registration code (to announce the system).
+    Token(&'a str),
+    /// System credentials.
+    Basic(&'a SystemCredentials),
+}
+
+/// HTTPS client for a registration server (SCC or RMT).
+///
+/// The calls are blocking, as they are done through curl.
+#[derive(Clone, Debug)]
+pub struct SccClient {
+    url: String,
+    proxy: ProxySettings,
+}
+
+impl Default for SccClient {
+    fn default() -> Self {
+        Self::new(DEFAULT_SCC_URL)
+    }
+}
+
+impl SccClient {
+    /// Creates a client for the given registration server.
+    ///
+    /// * `url`: server URL (e.g., "https://rmt.example.net").
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            proxy: ProxySettings::default(),
+        }
+    }
+
+    /// Sets the proxy to use.
+    ///
+    /// * `proxy`: proxy settings.
+    pub fn with_proxy(mut self, proxy: ProxySettings) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// Announces the system to the registration server, getting the system credentials.
+    ///
+    /// * `reg_code`: registration code.
+    /// * `product`: product to register.
+    /// * `hostname`: name of the system.
+    pub fn announce(
+        &self,
+        reg_code: &str,
+        product: &ProductTarget,
+        hostname: &str,
+    ) -> Result<SystemCredentials, RegistrationError> {
+        #[derive(Deserialize)]
+        struct Announced {
+            login: String,
+            password: String,
+        }
+
+        let body = json!({
+            "hostname": hostname,
+            "distro_target": product.distro_target(),
+            "hwinfo": { "arch": product.arch }
+        });
+        let response = self.request(
+            "/connect/subscriptions/systems",
+            Auth::Token(reg_code),
+            Some(body),
+        )?;
+        let announced: Announced = serde_json::from_str(&response)?;
+        Ok(SystemCredentials::new(
+            &announced.login,
+            &announced.password,
+        ))
+    }
+
+    /// Activates a product (or an extension) for an announced system.
+    ///
+    /// * `credentials`: system credentials.
+    /// * `product`: product to activate.
+    /// * `reg_code`: registration code (not needed for free extensions).
+    /// * `email`: email to associate with the registration.
+    pub fn activate(
+        &self,
+        credentials: &SystemCredentials,
+        product: &ProductTarget,
+        reg_code: Option<&str>,
+        email: Option<&str>,
+    ) -> Result<(), RegistrationError> {
+        let body = json!({
+            "identifier": product.identifier,
+            "version": product.version,
+            "arch": product.arch,
+            "token": reg_code.unwrap_or_default(),
+            "email": email.unwrap_or_default()
+        });
+        self.request(
+            "/connect/systems/products",
+            Auth::Basic(credentials),
+            Some(body),
+        )?;
+        Ok(())
+    }
+
+    /// Announces the system and activates the product.
+    ///
+    /// * `reg_code`: registration code.
+    /// * `email`: email to associate with the registration.
+    /// * `product`: product to register.
+    /// * `hostname`: name of the system.
+    pub fn register(
+        &self,
+        reg_code: &str,
+        email: Option<&str>,
+        product: &ProductTarget,
+        hostname: &str,
+    ) -> Result<SystemCredentials, RegistrationError> {
+        let credentials = self.announce(reg_code, product, hostname)?;
+        self.activate(&credentials, product, Some(reg_code), email)?;
+        Ok(credentials)
+    }
+
+    /// Returns the extensions available for a product.
+    ///
+    /// * `credentials`: system credentials.
+    /// * `product`: product to get the extensions for.
+    pub fn extensions(
+        &self,
+        credentials: &SystemCredentials,
+        product: &ProductTarget,
+    ) -> Result<Vec<Extension>, RegistrationError> {
+        let path = format!(
+            "/connect/systems/products?{}",
+            url::form_urlencoded::Serializer::new(String::new())
+                .append_pair("identifier", &product.identifier)
+                .append_pair("version", &product.version)
+                .append_pair("arch", &product.arch)
+                .finish()
+        );
+        let response = self.request(&path, Auth::Basic(credentials), None)?;
+        let product: ProductResponse = serde_json::from_str(&response)?;
+        Ok(product.extensions)
+    }
+
+    /// Sends a request to the registration server and returns the response body.
+    ///
+    /// It sends a POST request when there is a body, and a GET request otherwise.
+    ///
+    /// * `path`: API path, including the query string.
+    /// * `auth`: authentication method.
+    /// * `body`: JSON document to send.
+    fn request(
+        &self,
+        path: &str,
+        auth: Auth,
+        body: Option<serde_json::Value>,
+    ) -> Result<String, RegistrationError> {
+        let mut handle = Easy::new();
+        let url = format!("{}{}", self.url, path);
+        handle.url(&url)?;
+        handle.timeout(TIMEOUT)?;
+        if let Some(proxy) = &self.proxy.url {
+            handle.proxy(proxy)?;
+        }
+        if let Some(no_proxy) = &self.proxy.no_proxy {
+            handle.noproxy(no_proxy)?;
+        }
+
+        let mut headers = List::new();
+        headers.append(ACCEPT_HEADER)?;
+        match auth {
+            Auth::Token(code) => headers.append(&format!("Authorization: Token token={}", code))?,
+            Auth::Basic(credentials) => {
+                handle.username(&credentials.username)?;
+                handle.password(&credentials.password)?;
+            }
+        }
+        if let Some(body) = body {
+            headers.append("Content-Type: application/json")?;
+            handle.post(true)?;
+            handle.post_fields_copy(body.to_string().as_bytes())?;
+        }
+        handle.http_headers(headers)?;
+
+        let mut buf = Vec::new();
+        {
+            let mut transfer = handle.transfer();
+            transfer.write_function(|data| {
+                buf.extend(data);
+                Ok(data.len())
+            })?;
+            transfer.perform()?;
+        }
+
+        let response = String::from_utf8_lossy(&buf).to_string();
+        let code = handle.response_code()?;
+        if !(200..300).contains(&code) {
+            log::warn!("Registration server error ({}) for {}", code, path);
+            return Err(RegistrationError::Server(code, error_message(&response)));
+        }
+        Ok(response)
+    }
+}
+
+/// Extracts the error message from a server response.
+///
+/// The connect API returns a JSON document with the "error" (and "localized_error") keys. If
+/// the response is something else (e.g., an HTML page from a proxy), it is returned as it is.
+///
+/// * `response`: response body.
+fn error_message(response: &str) -> String {
+    #[derive(Deserialize)]
+    struct ApiError {
+        error: String,
+        localized_error: Option<String>,
+    }
+
+    match serde_json::from_str::<ApiError>(response) {
+        Ok(error) => error.localized_error.unwrap_or(error.error),
+        Err(_) => response.trim().to_string(),
+    }
+}
+
+/// Architecture of the running system using the RPM naming.
+fn rpm_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "powerpc64" if cfg!(target_endian = "little") => "ppc64le",
+        "powerpc64" => "ppc64",
+        arch => arch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distro_target() {
+        let product = ProductTarget {
+            identifier: "SLES".to_string(),
+            version: "15.5".to_string(),
+            arch: "x86_64".to_string(),
+        };
+        assert_eq!(product.distro_target(), "SLES-15-x86_64");
+    }
+
+    #[test]
+    fn test_parse_extensions() {
+        let response = r#"{
+  "identifier": "SLES",
+  "version": "15.5",
+  "arch": "x86_64",
+  "friendly_name": "SUSE Linux Enterprise Server 15 SP5 x86_64",
+  "extensions": [
+    {
+      "identifier": "sle-module-basesystem",
+      "version": "15.5",
+      "arch": "x86_64",
+      "friendly_name": "Basesystem Module 15 SP5 x86_64",
+      "free": true,
+      "recommended": true,
+      "extensions": [
+        {
+          "identifier": "sle-ha",
+          "version": "15.5",
+          "arch": "x86_64",
+          "friendly_name": "SUSE Linux Enterprise High Availability Extension 15 SP5 x86_64",
+          "free": false
+        }
+      ]
+    }
+  ]
+}"#;
+        let extensions = serde_json::from_str::<ProductResponse>(response)
+            .unwrap()
+            .extensions;
+        assert_eq!(extensions.len(), 1);
+        let basesystem = &extensions[0];
+        assert_eq!(basesystem.identifier, "sle-module-basesystem");
+        assert_eq!(basesystem.name, "Basesystem Module 15 SP5 x86_64");
+        assert!(basesystem.free && basesystem.recommended);
+        let ha = &basesystem.extensions[0];
+        assert_eq!(ha.identifier, "sle-ha");
+        assert!(!ha.free && !ha.recommended);
+        assert!(ha.extensions.is_empty());
+    }
+
+    #[test]
+    fn test_error_message() {
+        let response = r#"{"type":"error","error":"Invalid registration code","localized_error":"Código de registro no válido"}"#;
+        assert_eq!(error_message(response), "Código de registro no válido");
+
+        let response = r#"{"type":"error","error":"Invalid registration code"}"#;
+        assert_eq!(error_message(response), "Invalid registration code");
+
+        assert_eq!(error_message("Bad Gateway\n"), "Bad Gateway");
+    }
+}
@@ -0,0 +1,229 @@
+//! Access to the Agama services running on a different machine.
+//!
+//! The Agama bus only listens on a local socket. To drive an installation running elsewhere, the
+//! D-Bus traffic is tunneled through SSH: `ssh` runs `systemd-stdio-bridge` on the remote host,
+//! which forwards the messages between its standard input/output and the Agama bus. As SSH takes
+//! care of the authentication, there is no need to export the bus.
+//!
+//! Once a [RemoteHost] is set through [set_remote_host], [crate::connection] connects to that host
+//! instead of the local bus. The connection is reused, so SSH asks for the credentials only once.
+//!
+//! ```no_run
+//! # use agama_lib::remote::{self, RemoteHost};
+//! async fn connect() {
+//!   let host: RemoteHost = "ssh://root@installer.example.net".parse().unwrap();
+//!   remote::set_remote_host(Some(host));
+//!   let connection = agama_lib::connection().await.unwrap();
+//! }
+//! ```
+
+use crate::error::ServiceError;
+use std::{
+    fmt,
+    os::{fd::OwnedFd, unix::net::UnixStream},
+    process::{Command, Stdio},
+    str::FromStr,
+    sync::{Mutex, RwLock},
+};
+use thiserror::Error;
+use url::Url;
+use zbus::{AuthMechanism, Connection, ConnectionBuilder};
+
+/// Command to forward the D-Bus messages on the remote host.
+const BRIDGE_COMMAND: &str = "systemd-stdio-bridge";
+
+static REMOTE_HOST: RwLock<Option<RemoteHost>> = RwLock::new(None);
+static CONNECTION: Mutex<Option<Connection>> = Mutex::new(None);
+
+#[derive(Error, Debug)]
+pub enum RemoteError {
+    #[error("{}", crate::tr!("Invalid host URL '{}' (e.g., ssh://root@installer.example.net)", .0))]
+    InvalidUrl(String),
+    #[error("{}", crate::tr!("The '{}' scheme is not supported yet, please use ssh://", .0))]
+    UnsupportedScheme(String),
+    #[error("{}", crate::tr!("Could not run ssh: {}", .0))]
+    Ssh(#[from] std::io::Error),
+}
+
+/// Host running the Agama services.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RemoteHost {
+    /// Host reachable through SSH.
+    Ssh {
+        host: String,
+        user: Option<String>,
+        port: Option<u16>,
+    },
+}
+
+impl FromStr for RemoteHost {
+    type Err = RemoteError;
+
+    /// Parses a host URL (e.g., "ssh://root@installer.example.net:2222").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let url = Url::parse(s).map_err(|_| RemoteError::InvalidUrl(s.to_string()))?;
+        if url.scheme() != "ssh" {
+            return Err(RemoteError::UnsupportedScheme(url.scheme().to_string()));
+        }
+        let Some(host) = url.host_str().filter(|h| !h.is_empty()) else {
+            return Err(RemoteError::InvalidUrl(s.to_string()));
+        };
+        let user = Some(url.username())
+            .filter(|u| !u.is_empty())
+            .map(str::to_string);
+        Ok(Self::Ssh {
+            host: host.to_string(),
+            user,
+            port: url.port(),
+        })
+    }
+}
+
+impl fmt::Display for RemoteHost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self::Ssh { host, user, port } = self;
+        write!(f, "ssh://")?;
+        if let Some(user) = user {
+            write!(f, "{}@", user)?;
+        }
+        write!(f, "{}", host)?;
+        if let Some(port) = port {
+            write!(f, ":{}", port)?;
+        }
+        Ok(())
+    }
+}
+
+impl RemoteHost {
+    /// Connects to the Agama bus on the remote host.
+    ///
+    /// * `address`: address of the Agama bus on the remote host.
+    pub async fn connect(&self, address: &str) -> Result<Connection, ServiceError> {
+        let (local, remote) = UnixStream::pair().map_err(RemoteError::from)?;
+        let stdout: OwnedFd = remote.try_clone().map_err(RemoteError::from)?.into();
+        let stdin: OwnedFd = remote.into();
+        // ssh exits as soon as the local end of the socket is closed
+        Command::new("ssh")
+            .args(self.ssh_args(address))
+            .stdin(Stdio::from(stdin))
+            .stdout(Stdio::from(stdout))
+            .spawn()
+            .map_err(RemoteError::from)?;
+
+        local.set_nonblocking(true).map_err(RemoteError::from)?;
+        let socket = tokio::net::UnixStream::from_std(local).map_err(RemoteError::from)?;
+        let connection = ConnectionBuilder::socket(socket)
+            .auth_mechanisms(&[AuthMechanism::External, AuthMechanism::Anonymous])
+            .build()
+            .await
+            .map_err(|e| ServiceError::DBusConnectionError(self.to_string(), e))?;
+        Ok(connection)
+    }
+
+    /// Returns the arguments for `ssh` to run the bridge on the remote host.
+    ///
+    /// * `address`: address of the Agama bus on the remote host.
+    fn ssh_args(&self, address: &str) -> Vec<String> {
+        let Self::Ssh { host, user, port } = self;
+        let mut args = vec!["-x".to_string(), "-T".to_string()];
+        if let Some(user) = user {
+            args.extend(["-l".to_string(), user.clone()]);
+        }
+        if let Some(port) = port {
+            args.extend(["-p".to_string(), port.to_string()]);
+        }
+        args.extend([
+            "--".to_string(),
+            host.clone(),
+            BRIDGE_COMMAND.to_string(),
+            format!("--bus-path={}", address),
+        ]);
+        args
+    }
+}
+
+/// Returns the host to connect to, if any.
+pub fn remote_host() -> Option<RemoteHost> {
+    REMOTE_HOST.read().ok().and_then(|h| h.clone())
+}
+
+/// Sets the host to connect to for the connections created afterwards.
+///
+/// * `host`: remote host or `None` to use the local bus.
+pub fn set_remote_host(host: Option<RemoteHost>) {
+    if let Ok(mut current) = REMOTE_HOST.write() {
+        *current = host;
+    }
+    if let Ok(mut connection) = CONNECTION.lock() {
+        *connection = None;
+    }
+}
+
+/// Returns the connection to the Agama bus on the remote host, reusing it if possible.
+///
+/// * `host`: remote host.
+/// * `address`: address of the Agama bus on the remote host.
+pub(crate) async fn connection(
+    host: &RemoteHost,
+    address: &str,
+) -> Result<Connection, ServiceError> {
+    if let Some(connection) = CONNECTION.lock().ok().and_then(|c| c.clone()) {
+        return Ok(connection);
+    }
+    let connection = host.connect(address).await?;
+    if let Ok(mut cached) = CONNECTION.lock() {
+        *cached = Some(connection.clone());
+    }
+    Ok(connection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_remote_host() {
+        let host: RemoteHost = "ssh://root@installer.example.net:2222".parse().unwrap();
+        assert_eq!(
+            host,
+            RemoteHost::Ssh {
+                host: "installer.example.net".to_string(),
+                user: Some("root".to_string()),
+                port: Some(2222)
+            }
+        );
+        assert_eq!(host.to_string(), "ssh://root@installer.example.net:2222");
+
+        let host: RemoteHost = "ssh://installer".parse().unwrap();
+        assert_eq!(host.to_string(), "ssh://installer");
+
+        assert!(matches!(
+            "https://installer".parse::<RemoteHost>(),
+            Err(RemoteError::UnsupportedScheme(_))
+        ));
+        assert!(matches!(
+            "installer".parse::<RemoteHost>(),
+            Err(RemoteError::InvalidUrl(_))
+        ));
+    }
+
+    #[test]
+    fn test_ssh_args() {
+        let host: RemoteHost = "ssh://root@installer:2222".parse().unwrap();
+        assert_eq!(
+            host.ssh_args("unix:path=/run/agama/bus"),
+            vec![
+                "-x",
+                "-T",
+                "-l",
+                "root",
+                "-p",
+                "2222",
+                "--",
+                "installer",
+                "systemd-stdio-bridge",
+                "--bus-path=unix:path=/run/agama/bus"
+            ]
+        );
+    }
+}
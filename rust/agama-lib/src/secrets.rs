@@ -0,0 +1,67 @@
+//! Handling of the secrets included in the installation settings
+//!
+//! Passwords and similar values should not be exposed when exporting or logging the settings. This
+//! module offers a way to remove them from a JSON representation of the settings.
+use serde_json::Value;
+
+/// Keys (as they appear in the profiles) that contain secrets.
+pub const SECRET_KEYS: [&str; 5] = [
+    "password",
+    "encryptionPassword",
+    "registrationCode",
+    "pin",
+    "cak",
+];
+
+/// Removes the secrets from a JSON value.
+///
+/// The value is traversed recursively, so the secrets within nested objects or collections (e.g.,
+/// the wireless passwords of the network connections) are removed too.
+///
+/// * `value`: JSON value to remove the secrets from.
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, _| !SECRET_KEYS.contains(&key.as_str()));
+            map.values_mut().for_each(redact);
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact() {
+        let mut value = json!({
+            "user": { "userName": "jane", "password": "nots3cr3t" },
+            "storage": { "bootDevice": "/dev/sda", "encryptionPassword": "s3cr3t" },
+            "network": {
+                "connections": [
+                    { "id": "wlan0", "wireless": { "ssid": "agama", "password": "wifis3cr3t" } },
+                    { "id": "wwan0", "modem": { "apn": "internet", "pin": "1234" } },
+                    { "id": "macsec0", "macsec": { "mode": "psk", "cak": "s3cr3tcak" } }
+                ]
+            }
+        });
+        redact(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "user": { "userName": "jane" },
+                "storage": { "bootDevice": "/dev/sda" },
+                "network": {
+                    "connections": [
+                        { "id": "wlan0", "wireless": { "ssid": "agama" } },
+                        { "id": "wwan0", "modem": { "apn": "internet" } },
+                        { "id": "macsec0", "macsec": { "mode": "psk" } }
+                    ]
+                }
+            })
+        );
+    }
+}
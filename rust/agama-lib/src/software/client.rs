@@ -1,7 +1,9 @@
 use super::proxies::Software1Proxy;
-use crate::error::ServiceError;
+use crate::timeout::CallTimeout;
+use crate::{error::ServiceError, network::NetworkClient};
 use serde::Serialize;
 use std::collections::HashMap;
+use std::time::Duration;
 use zbus::Connection;
 
 /// Represents a software product
@@ -33,7 +35,7 @@ pub enum SelectedBy {
 }
 
 #[derive(Debug, thiserror::Error)]
-#[error("Unknown selected by value: '{0}'")]
+#[error("{}", crate::tr!("Unknown selected by value: '{}'", .0))]
 pub struct UnknownSelectedBy(u8);
 
 impl TryFrom<u8> for SelectedBy {
@@ -51,95 +53,179 @@ impl TryFrom<u8> for SelectedBy {
 /// D-Bus client for the software service
 #[derive(Clone)]
 pub struct SoftwareClient<'a> {
+    timeout: CallTimeout,
     software_proxy: Software1Proxy<'a>,
+    connection: Connection,
 }
 
 impl<'a> SoftwareClient<'a> {
     pub async fn new(connection: Connection) -> Result<SoftwareClient<'a>, ServiceError> {
         Ok(Self {
+            timeout: CallTimeout::default(),
             software_proxy: Software1Proxy::new(&connection).await?,
+            connection,
         })
     }
 
+    /// Sets the timeout for the D-Bus calls.
+    ///
+    /// * `timeout`: time to wait for each call.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = CallTimeout::new(timeout);
+        self
+    }
+
     /// Returns the available patterns
     pub async fn patterns(&self, filtered: bool) -> Result<Vec<Pattern>, ServiceError> {
-        let patterns: Vec<Pattern> = self
-            .software_proxy
-            .list_patterns(filtered)
-            .await?
-            .into_iter()
-            .map(
-                |(id, (category, description, icon, summary, order))| Pattern {
-                    id,
-                    category,
-                    icon,
-                    description,
-                    summary,
-                    order,
-                },
-            )
-            .collect();
-        Ok(patterns)
+        self.timeout
+            .run(async {
+                let patterns: Vec<Pattern> = self
+                    .software_proxy
+                    .list_patterns(filtered)
+                    .await?
+                    .into_iter()
+                    .map(
+                        |(id, (category, description, icon, summary, order))| Pattern {
+                            id,
+                            category,
+                            icon,
+                            description,
+                            summary,
+                            order,
+                        },
+                    )
+                    .collect();
+                Ok(patterns)
+            })
+            .await
     }
 
     /// Returns the ids of patterns selected by user
     pub async fn user_selected_patterns(&self) -> Result<Vec<String>, ServiceError> {
-        let patterns: Vec<String> = self
-            .software_proxy
-            .selected_patterns()
-            .await?
-            .into_iter()
-            .filter_map(|(id, reason)| match SelectedBy::try_from(reason) {
-                Ok(reason) if reason == SelectedBy::User => Some(id),
-                Ok(_reason) => None,
-                Err(e) => {
-                    log::warn!("Ignoring pattern {}. Error: {}", &id, e);
-                    None
-                }
+        self.timeout
+            .run(async {
+                let patterns: Vec<String> = self
+                    .software_proxy
+                    .selected_patterns()
+                    .await?
+                    .into_iter()
+                    .filter_map(|(id, reason)| match SelectedBy::try_from(reason) {
+                        Ok(SelectedBy::User) => Some(id),
+                        Ok(_reason) => None,
+                        Err(e) => {
+                            log::warn!("Ignoring pattern {}. Error: {}", &id, e);
+                            None
+                        }
+                    })
+                    .collect();
+                Ok(patterns)
             })
-            .collect();
-        Ok(patterns)
+            .await
     }
 
     /// Returns the selected pattern and the reason each one selected.
     pub async fn selected_patterns(&self) -> Result<HashMap<String, SelectedBy>, ServiceError> {
-        let patterns = self.software_proxy.selected_patterns().await?;
-        let patterns = patterns
-            .into_iter()
-            .filter_map(|(id, reason)| match SelectedBy::try_from(reason) {
-                Ok(reason) => Some((id, reason)),
-                Err(e) => {
-                    log::warn!("Ignoring pattern {}. Error: {}", &id, e);
-                    None
-                }
+        self.timeout
+            .run(async {
+                let patterns = self.software_proxy.selected_patterns().await?;
+                let patterns = patterns
+                    .into_iter()
+                    .filter_map(|(id, reason)| match SelectedBy::try_from(reason) {
+                        Ok(reason) => Some((id, reason)),
+                        Err(e) => {
+                            log::warn!("Ignoring pattern {}. Error: {}", &id, e);
+                            None
+                        }
+                    })
+                    .collect();
+                Ok(patterns)
             })
-            .collect();
-        Ok(patterns)
+            .await
     }
 
     /// Selects patterns by user
     pub async fn select_patterns(&self, patterns: &[String]) -> Result<(), ServiceError> {
-        let patterns: Vec<&str> = patterns.iter().map(AsRef::as_ref).collect();
-        let wrong_patterns = self
-            .software_proxy
-            .set_user_patterns(patterns.as_slice())
-            .await?;
-        if !wrong_patterns.is_empty() {
-            Err(ServiceError::UnknownPatterns(wrong_patterns))
-        } else {
-            Ok(())
-        }
+        self.timeout
+            .run(async {
+                let patterns: Vec<&str> = patterns.iter().map(AsRef::as_ref).collect();
+                let wrong_patterns = self
+                    .software_proxy
+                    .set_user_patterns(patterns.as_slice())
+                    .await?;
+                if !wrong_patterns.is_empty() {
+                    Err(ServiceError::UnknownPatterns(wrong_patterns))
+                } else {
+                    Ok(())
+                }
+            })
+            .await
     }
 
     /// Returns the required space for installing the selected patterns.
     ///
     /// It returns a formatted string including the size and the unit.
     pub async fn used_disk_space(&self) -> Result<String, ServiceError> {
-        Ok(self.software_proxy.used_disk_space().await?)
+        self.timeout
+            .run(async { Ok(self.software_proxy.used_disk_space().await?) })
+            .await
     }
 
     /// Starts the process to read the repositories data.
+    ///
+    /// When the system is offline (e.g., installing from a DVD), the remote repositories are
+    /// skipped.
     pub async fn probe(&self) -> Result<(), ServiceError> {
+        let online = self.is_online().await;
+        self.software_proxy.set_offline(!online).await?;
         Ok(self.software_proxy.probe().await?)
     }
+
+    /// Determines whether the system is online according to the network service.
+    ///
+    /// If the network service cannot tell, it assumes that the system is online.
+    async fn is_online(&self) -> bool {
+        let result = match NetworkClient::new(self.connection.clone()).await {
+            Ok(client) => client.is_online().await,
+            Err(e) => Err(e),
+        };
+        result.unwrap_or_else(|e| {
+            log::warn!("Could not determine whether the system is online: {}", e);
+            true
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vcr::Cassette;
+
+    #[tokio::test]
+    async fn test_patterns() {
+        let cassette = Cassette::new("software_patterns").await.unwrap();
+        let client = SoftwareClient::new(cassette.connection()).await.unwrap();
+
+        let patterns = client.patterns(true).await.unwrap();
+        let gnome = patterns.iter().find(|p| p.id == "gnome").unwrap();
+        assert_eq!(gnome.category, "Graphical Environments");
+        assert_eq!(gnome.order, "1010");
+
+        let selected = client.selected_patterns().await.unwrap();
+        assert_eq!(selected.get("gnome"), Some(&SelectedBy::User));
+        assert_eq!(selected.get("base"), Some(&SelectedBy::Auto));
+        assert_eq!(
+            client.user_selected_patterns().await.unwrap(),
+            vec!["gnome"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_select_unknown_patterns() {
+        let cassette = Cassette::new("software_select_patterns").await.unwrap();
+        let client = SoftwareClient::new(cassette.connection()).await.unwrap();
+
+        let patterns = vec!["gnome".to_string(), "unknown".to_string()];
+        let error = client.select_patterns(&patterns).await.unwrap_err();
+        assert!(matches!(error, ServiceError::UnknownPatterns(p) if p == vec!["unknown"]));
+    }
 }
@@ -47,6 +47,9 @@ trait Software1 {
     /// RemovePattern method
     fn remove_pattern(&self, id: &str) -> zbus::Result<bool>;
 
+    /// SetOffline method
+    fn set_offline(&self, offline: bool) -> zbus::Result<()>;
+
     /// SetUserPatterns method
     fn set_user_patterns(&self, ids: &[&str]) -> zbus::Result<Vec<String>>;
 
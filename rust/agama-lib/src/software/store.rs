@@ -2,6 +2,9 @@
 
 use super::{SoftwareClient, SoftwareSettings};
 use crate::error::ServiceError;
+use crate::install_settings::{InstallSettings, Scope};
+use crate::store::SettingsStore;
+use async_trait::async_trait;
 use zbus::Connection;
 
 /// Loads and stores the software settings from/to the D-Bus service.
@@ -29,3 +32,22 @@ impl<'a> SoftwareStore<'a> {
         Ok(())
     }
 }
+
+#[async_trait]
+impl<'a> SettingsStore for SoftwareStore<'a> {
+    fn scope(&self) -> Scope {
+        Scope::Software
+    }
+
+    async fn load_section(&self, settings: &mut InstallSettings) -> Result<(), ServiceError> {
+        settings.software = Some(self.load().await?);
+        Ok(())
+    }
+
+    async fn store_section(&self, settings: &InstallSettings) -> Result<(), ServiceError> {
+        if let Some(software) = &settings.software {
+            self.store(software).await?;
+        }
+        Ok(())
+    }
+}
@@ -5,6 +5,6 @@ mod proxies;
 mod settings;
 mod store;
 
-pub use client::StorageClient;
-pub use settings::StorageSettings;
+pub use client::{ISCSINode, StorageClient};
+pub use settings::{ISCSISettings, ISCSITarget, StorageSettings};
 pub use store::StorageStore;
@@ -1,14 +1,22 @@
 //! Implements a client to access Agama's storage service.
 
-use super::proxies::{BlockDeviceProxy, ProposalCalculatorProxy, ProposalProxy, Storage1Proxy};
-use super::StorageSettings;
+use super::proxies::{
+    BlockDeviceProxy, ISCSIInitiatorProxy, ISCSINodeProxy, ProposalCalculatorProxy, ProposalProxy,
+    Storage1Proxy,
+};
+use super::{settings::ISCSITarget, StorageSettings};
 use crate::error::ServiceError;
+use crate::timeout::CallTimeout;
 use futures_util::future::join_all;
 use serde::Serialize;
 use std::collections::HashMap;
-use zbus::zvariant::OwnedObjectPath;
+use std::time::Duration;
+use zbus::fdo::ObjectManagerProxy;
+use zbus::zvariant::{OwnedObjectPath, Value};
 use zbus::Connection;
 
+const ISCSI_NODE_INTERFACE: &str = "org.opensuse.Agama.Storage1.ISCSI.Node";
+
 /// Represents a storage device
 #[derive(Serialize, Debug)]
 pub struct StorageDevice {
@@ -16,22 +24,52 @@ pub struct StorageDevice {
     description: String,
 }
 
+/// Represents an iSCSI node (a target offered by a portal)
+#[derive(Serialize, Debug, Clone)]
+pub struct ISCSINode {
+    /// D-Bus path of the node
+    #[serde(skip)]
+    pub path: OwnedObjectPath,
+    /// Target name
+    pub target: String,
+    /// IP address of the portal
+    pub address: String,
+    /// Port of the portal
+    pub port: u32,
+    /// Whether there is a session for the node
+    pub connected: bool,
+    /// Startup mode of the session (empty if not connected)
+    pub startup: String,
+}
+
 /// D-Bus client for the storage service
 pub struct StorageClient<'a> {
+    timeout: CallTimeout,
     pub connection: Connection,
     calculator_proxy: ProposalCalculatorProxy<'a>,
     storage_proxy: Storage1Proxy<'a>,
+    iscsi_proxy: ISCSIInitiatorProxy<'a>,
 }
 
 impl<'a> StorageClient<'a> {
     pub async fn new(connection: Connection) -> Result<StorageClient<'a>, ServiceError> {
         Ok(Self {
+            timeout: CallTimeout::default(),
             calculator_proxy: ProposalCalculatorProxy::new(&connection).await?,
             storage_proxy: Storage1Proxy::new(&connection).await?,
+            iscsi_proxy: ISCSIInitiatorProxy::new(&connection).await?,
             connection,
         })
     }
 
+    /// Sets the timeout for the D-Bus calls.
+    ///
+    /// * `timeout`: time to wait for each call.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = CallTimeout::new(timeout);
+        self
+    }
+
     /// Returns the proposal proxy
     ///
     /// The proposal might not exist.
@@ -44,15 +82,19 @@ impl<'a> StorageClient<'a> {
     ///
     /// These devices can be used for installing the system.
     pub async fn available_devices(&self) -> Result<Vec<StorageDevice>, ServiceError> {
-        let devices: Vec<_> = self
-            .calculator_proxy
-            .available_devices()
-            .await?
-            .into_iter()
-            .map(|path| self.storage_device(path))
-            .collect();
+        self.timeout
+            .run(async {
+                let devices: Vec<_> = self
+                    .calculator_proxy
+                    .available_devices()
+                    .await?
+                    .into_iter()
+                    .map(|path| self.storage_device(path))
+                    .collect();
 
-        join_all(devices).await.into_iter().collect()
+                join_all(devices).await.into_iter().collect()
+            })
+            .await
     }
 
     /// Returns the storage device for the given D-Bus path
@@ -75,32 +117,44 @@ impl<'a> StorageClient<'a> {
 
     /// Returns the boot device proposal setting
     pub async fn boot_device(&self) -> Result<Option<String>, ServiceError> {
-        let proxy = self.proposal_proxy().await?;
-        let value = self.proposal_value(proxy.boot_device().await)?;
+        self.timeout
+            .run(async {
+                let proxy = self.proposal_proxy().await?;
+                let value = self.proposal_value(proxy.boot_device().await)?;
 
-        match value {
-            Some(v) if v.is_empty() => Ok(None),
-            Some(v) => Ok(Some(v)),
-            None => Ok(None),
-        }
+                match value {
+                    Some(v) if v.is_empty() => Ok(None),
+                    Some(v) => Ok(Some(v)),
+                    None => Ok(None),
+                }
+            })
+            .await
     }
 
     /// Returns the lvm proposal setting
     pub async fn lvm(&self) -> Result<Option<bool>, ServiceError> {
-        let proxy = self.proposal_proxy().await?;
-        self.proposal_value(proxy.lvm().await)
+        self.timeout
+            .run(async {
+                let proxy = self.proposal_proxy().await?;
+                self.proposal_value(proxy.lvm().await)
+            })
+            .await
     }
 
     /// Returns the encryption password proposal setting
     pub async fn encryption_password(&self) -> Result<Option<String>, ServiceError> {
-        let proxy = self.proposal_proxy().await?;
-        let value = self.proposal_value(proxy.encryption_password().await)?;
+        self.timeout
+            .run(async {
+                let proxy = self.proposal_proxy().await?;
+                let value = self.proposal_value(proxy.encryption_password().await)?;
 
-        match value {
-            Some(v) if v.is_empty() => Ok(None),
-            Some(v) => Ok(Some(v)),
-            None => Ok(None),
-        }
+                match value {
+                    Some(v) if v.is_empty() => Ok(None),
+                    Some(v) => Ok(Some(v)),
+                    None => Ok(None),
+                }
+            })
+            .await
     }
 
     fn proposal_value<T>(&self, value: Result<T, zbus::Error>) -> Result<Option<T>, ServiceError> {
@@ -120,6 +174,102 @@ impl<'a> StorageClient<'a> {
         Ok(self.storage_proxy.probe().await?)
     }
 
+    /// Returns the iSCSI initiator name
+    pub async fn iscsi_initiator_name(&self) -> Result<String, ServiceError> {
+        Ok(self.iscsi_proxy.initiator_name().await?)
+    }
+
+    /// Sets the iSCSI initiator name
+    ///
+    /// * `name`: initiator name (e.g., "iqn.1996-04.de.suse:01:62b45cf7fc").
+    pub async fn set_iscsi_initiator_name(&self, name: &str) -> Result<(), ServiceError> {
+        Ok(self.iscsi_proxy.set_initiator_name(name).await?)
+    }
+
+    /// Discovers the targets offered by the portal of the given target
+    ///
+    /// * `target`: target settings, including the portal and the authentication.
+    pub async fn iscsi_discover(&self, target: &ISCSITarget) -> Result<(), ServiceError> {
+        let result = self
+            .iscsi_proxy
+            .discover(&target.address, target.port, iscsi_auth_options(target))
+            .await?;
+        if result != 0 {
+            return Err(ServiceError::FailedOperation(format!(
+                "Could not discover the iSCSI targets at {}:{}",
+                target.address, target.port
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the known iSCSI nodes
+    pub async fn iscsi_nodes(&self) -> Result<Vec<ISCSINode>, ServiceError> {
+        let manager = ObjectManagerProxy::builder(&self.connection)
+            .destination("org.opensuse.Agama.Storage1")?
+            .path("/org/opensuse/Agama/Storage1")?
+            .build()
+            .await?;
+
+        let mut nodes = vec![];
+        let objects = manager
+            .get_managed_objects()
+            .await
+            .map_err(zbus::Error::from)?;
+        for (path, interfaces) in objects {
+            if !interfaces
+                .keys()
+                .any(|i| i.as_str() == ISCSI_NODE_INTERFACE)
+            {
+                continue;
+            }
+            let proxy = ISCSINodeProxy::builder(&self.connection)
+                .path(path.clone())?
+                .build()
+                .await?;
+            nodes.push(ISCSINode {
+                path,
+                target: proxy.target().await?,
+                address: proxy.address().await?,
+                port: proxy.port().await?,
+                connected: proxy.connected().await?,
+                startup: proxy.startup().await?,
+            });
+        }
+        Ok(nodes)
+    }
+
+    /// Logs into an iSCSI node
+    ///
+    /// * `node`: node to log in.
+    /// * `target`: target settings, including the authentication and the startup mode.
+    pub async fn iscsi_login(
+        &self,
+        node: &ISCSINode,
+        target: &ISCSITarget,
+    ) -> Result<(), ServiceError> {
+        let proxy = ISCSINodeProxy::builder(&self.connection)
+            .path(node.path.clone())?
+            .build()
+            .await?;
+        let mut options = iscsi_auth_options(target);
+        if let Some(startup) = &target.startup {
+            options.insert("Startup", Value::new(startup.as_str()));
+        }
+
+        match proxy.login(options).await? {
+            0 => Ok(()),
+            1 => Err(ServiceError::FailedOperation(format!(
+                "Invalid iSCSI startup mode: '{}'",
+                target.startup.as_deref().unwrap_or_default()
+            ))),
+            _ => Err(ServiceError::FailedOperation(format!(
+                "Could not log into the iSCSI target {}",
+                node.target
+            ))),
+        }
+    }
+
     pub async fn calculate(&self, settings: &StorageSettings) -> Result<u32, ServiceError> {
         let mut dbus_settings: HashMap<&str, zbus::zvariant::Value<'_>> = HashMap::new();
 
@@ -141,3 +291,43 @@ impl<'a> StorageClient<'a> {
         Ok(self.calculator_proxy.calculate(dbus_settings).await?)
     }
 }
+
+/// Builds the authentication options for the iSCSI D-Bus methods
+///
+/// * `target`: target settings.
+fn iscsi_auth_options(target: &ISCSITarget) -> HashMap<&'static str, Value<'_>> {
+    let auth = [
+        ("Username", &target.username),
+        ("Password", &target.password),
+        ("ReverseUsername", &target.reverse_username),
+        ("ReversePassword", &target.reverse_password),
+    ];
+    auth.into_iter()
+        .filter_map(|(key, value)| value.as_deref().map(|v| (key, Value::new(v))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vcr::Cassette;
+
+    #[tokio::test]
+    async fn test_available_devices() {
+        let cassette = Cassette::new("storage_available_devices").await.unwrap();
+        let client = StorageClient::new(cassette.connection()).await.unwrap();
+
+        let devices = client.available_devices().await.unwrap();
+        let names: Vec<_> = devices.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["/dev/vda", "/dev/vdb"]);
+    }
+
+    #[tokio::test]
+    async fn test_proposal_settings_without_proposal() {
+        let cassette = Cassette::new("storage_no_proposal").await.unwrap();
+        let client = StorageClient::new(cassette.connection()).await.unwrap();
+
+        assert_eq!(client.boot_device().await.unwrap(), None);
+        assert_eq!(client.lvm().await.unwrap(), None);
+    }
+}
@@ -1,8 +1,12 @@
 //! Representation of the storage settings
 
-use agama_settings::Settings;
+use agama_settings::error::ConversionError;
+use agama_settings::{SettingObject, Settings};
 use serde::{Deserialize, Serialize};
 
+/// Default port of the iSCSI portals
+const DEFAULT_ISCSI_PORT: u32 = 3260;
+
 /// Storage settings for installation
 #[derive(Debug, Default, Settings, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -13,4 +17,151 @@ pub struct StorageSettings {
     pub encryption_password: Option<String>,
     /// Boot device to use in the installation
     pub boot_device: Option<String>,
+    /// iSCSI settings (applied before calculating the proposal)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[settings(nested)]
+    pub iscsi: Option<ISCSISettings>,
+}
+
+/// iSCSI settings
+#[derive(Clone, Debug, Default, PartialEq, Settings, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ISCSISettings {
+    /// iSCSI initiator name (e.g., "iqn.1996-04.de.suse:01:62b45cf7fc")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initiator_name: Option<String>,
+    /// Targets to log in
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    #[settings(collection)]
+    pub targets: Vec<ISCSITarget>,
+}
+
+/// iSCSI target to log in
+///
+/// The targets are discovered through the portal (address and port). If no name is given, all
+/// the targets offered by the portal are used.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ISCSITarget {
+    /// IP address of the portal
+    pub address: String,
+    /// Port of the portal
+    #[serde(default = "default_iscsi_port")]
+    pub port: u32,
+    /// Target name (e.g., "iqn.2023-01.com.example:12ac588")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Startup mode of the connection ("onboot", "manual" or "automatic")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub startup: Option<String>,
+    /// Username for the authentication by the target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Password for the authentication by the target (in clear text)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Username for the authentication by the initiator
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reverse_username: Option<String>,
+    /// Password for the authentication by the initiator (in clear text)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reverse_password: Option<String>,
+}
+
+fn default_iscsi_port() -> u32 {
+    DEFAULT_ISCSI_PORT
+}
+
+impl Default for ISCSITarget {
+    fn default() -> Self {
+        Self {
+            address: Default::default(),
+            port: DEFAULT_ISCSI_PORT,
+            name: None,
+            startup: None,
+            username: None,
+            password: None,
+            reverse_username: None,
+            reverse_password: None,
+        }
+    }
+}
+
+impl TryFrom<SettingObject> for ISCSITarget {
+    type Error = ConversionError;
+
+    fn try_from(value: SettingObject) -> Result<Self, Self::Error> {
+        let Some(address) = value.get("address") else {
+            return Err(ConversionError::MissingKey("address".to_string()));
+        };
+
+        let mut target = ISCSITarget {
+            address: address.clone().try_into()?,
+            ..Default::default()
+        };
+        if let Some(port) = value.get("port") {
+            target.port = port.clone().try_into()?;
+        }
+        if let Some(name) = value.get("name") {
+            target.name = name.clone().try_into()?;
+        }
+        if let Some(startup) = value.get("startup") {
+            target.startup = startup.clone().try_into()?;
+        }
+        Ok(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agama_settings::{settings::Settings, SettingValue};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_add_iscsi_target() {
+        let mut settings = StorageSettings::default();
+        let target = SettingObject(HashMap::from([
+            (
+                "address".to_string(),
+                SettingValue("192.168.100.1".to_string()),
+            ),
+            (
+                "name".to_string(),
+                SettingValue("iqn.2023-01.com.example:12ac588".to_string()),
+            ),
+        ]));
+        settings.add("iscsi.targets", target).unwrap();
+
+        let iscsi = settings.iscsi.unwrap();
+        assert_eq!(
+            iscsi.targets,
+            vec![ISCSITarget {
+                address: "192.168.100.1".to_string(),
+                port: 3260,
+                name: Some("iqn.2023-01.com.example:12ac588".to_string()),
+                ..Default::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_add_iscsi_target_without_address() {
+        let mut settings = StorageSettings::default();
+        let target = SettingObject(HashMap::from([(
+            "port".to_string(),
+            SettingValue("3260".to_string()),
+        )]));
+        assert!(settings.add("iscsi.targets", target).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_iscsi_settings() {
+        let json = r#"{ "initiatorName": "iqn.1996-04.de.suse:01:62b45cf7fc",
+            "targets": [{ "address": "192.168.100.1", "startup": "onboot" }] }"#;
+        let iscsi: ISCSISettings = serde_json::from_str(json).unwrap();
+        let target = iscsi.targets.first().unwrap();
+        assert_eq!(target.port, 3260);
+        assert_eq!(target.startup, Some("onboot".to_string()));
+    }
 }
@@ -1,7 +1,10 @@
 //! Implements the store for the storage settings.
 
-use super::{StorageClient, StorageSettings};
+use super::{ISCSISettings, ISCSITarget, StorageClient, StorageSettings};
 use crate::error::ServiceError;
+use crate::install_settings::{InstallSettings, Scope};
+use crate::store::SettingsStore;
+use async_trait::async_trait;
 use zbus::Connection;
 
 /// Loads and stores the storage settings from/to the D-Bus service.
@@ -20,16 +23,108 @@ impl<'a> StorageStore<'a> {
         let boot_device = self.storage_client.boot_device().await?;
         let lvm = self.storage_client.lvm().await?;
         let encryption_password = self.storage_client.encryption_password().await?;
+        let iscsi = self.load_iscsi().await?;
 
         Ok(StorageSettings {
             boot_device,
             lvm,
             encryption_password,
+            iscsi,
         })
     }
 
     pub async fn store(&self, settings: &StorageSettings) -> Result<(), ServiceError> {
+        // iSCSI disks must be available before calculating the proposal
+        if let Some(iscsi) = &settings.iscsi {
+            if self.store_iscsi(iscsi).await? {
+                self.storage_client.probe().await?;
+            }
+        }
         self.storage_client.calculate(settings).await?;
         Ok(())
     }
+
+    /// Reads the iSCSI settings
+    ///
+    /// It returns `None` if neither the initiator name is set nor there are connected targets.
+    async fn load_iscsi(&self) -> Result<Option<ISCSISettings>, ServiceError> {
+        let initiator_name = self.storage_client.iscsi_initiator_name().await?;
+        let targets: Vec<_> = self
+            .storage_client
+            .iscsi_nodes()
+            .await?
+            .into_iter()
+            .filter(|n| n.connected)
+            .map(|n| ISCSITarget {
+                address: n.address,
+                port: n.port,
+                name: Some(n.target),
+                startup: Some(n.startup).filter(|s| !s.is_empty()),
+                ..Default::default()
+            })
+            .collect();
+
+        if initiator_name.is_empty() && targets.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(ISCSISettings {
+            initiator_name: Some(initiator_name).filter(|n| !n.is_empty()),
+            targets,
+        }))
+    }
+
+    /// Sets the initiator name and logs into the targets
+    ///
+    /// It returns whether it logged into any target.
+    async fn store_iscsi(&self, settings: &ISCSISettings) -> Result<bool, ServiceError> {
+        if let Some(name) = &settings.initiator_name {
+            self.storage_client.set_iscsi_initiator_name(name).await?;
+        }
+
+        let mut logged_in = false;
+        for target in &settings.targets {
+            self.storage_client.iscsi_discover(target).await?;
+            let nodes: Vec<_> = self
+                .storage_client
+                .iscsi_nodes()
+                .await?
+                .into_iter()
+                .filter(|n| n.address == target.address && n.port == target.port)
+                .filter(|n| target.name.as_ref().is_none_or(|name| &n.target == name))
+                .collect();
+
+            if nodes.is_empty() {
+                return Err(ServiceError::FailedOperation(format!(
+                    "No iSCSI target found at {}:{}",
+                    target.address, target.port
+                )));
+            }
+
+            for node in nodes.iter().filter(|n| !n.connected) {
+                self.storage_client.iscsi_login(node, target).await?;
+                logged_in = true;
+            }
+        }
+        Ok(logged_in)
+    }
+}
+
+#[async_trait]
+impl<'a> SettingsStore for StorageStore<'a> {
+    fn scope(&self) -> Scope {
+        Scope::Storage
+    }
+
+    async fn load_section(&self, settings: &mut InstallSettings) -> Result<(), ServiceError> {
+        settings.storage = Some(self.load().await?);
+        Ok(())
+    }
+
+    async fn store_section(&self, settings: &InstallSettings) -> Result<(), ServiceError> {
+        if let Some(storage) = &settings.storage {
+            self.store(storage).await?;
+        }
+        Ok(())
+    }
 }
@@ -1,5 +1,8 @@
 //! Load/store the settings from/to the D-Bus services.
-// TODO: quickly explain difference between FooSettings and FooStore, with an example
+//!
+//! Each section of the [InstallSettings] (network, users, software, etc.) has its own store, which
+//! implements the [SettingsStore] trait. The [Store] struct puts all of them together, so it is
+//! possible to load or store all the settings or just some sections.
 
 use crate::error::ServiceError;
 use crate::install_settings::{InstallSettings, Scope};
@@ -7,92 +10,77 @@ use crate::{
     localization::LocalizationStore, network::NetworkStore, product::ProductStore,
     software::SoftwareStore, storage::StorageStore, users::UsersStore,
 };
+use async_trait::async_trait;
 use zbus::Connection;
 
+/// Loads and stores a section of the installation settings.
+///
+/// Adding a new section only requires implementing this trait and registering the store in
+/// [Store::new].
+#[async_trait]
+pub trait SettingsStore: Send + Sync {
+    /// Scope of the section handled by the store.
+    fn scope(&self) -> Scope;
+
+    /// Loads the section from the service into the given settings.
+    ///
+    /// * `settings`: settings to update.
+    async fn load_section(&self, settings: &mut InstallSettings) -> Result<(), ServiceError>;
+
+    /// Stores the section of the given settings in the service.
+    ///
+    /// It does nothing if the section is not defined.
+    ///
+    /// * `settings`: settings to store.
+    async fn store_section(&self, settings: &InstallSettings) -> Result<(), ServiceError>;
+}
+
 /// Struct that loads/stores the settings from/to the D-Bus services.
 ///
-/// It is composed by a set of "stores" that are able to load/store the
+/// It is composed by a set of [stores](SettingsStore) that are able to load/store the
 /// settings for each service.
 ///
 /// This struct uses the default connection built by [connection function](super::connection).
 pub struct Store<'a> {
-    users: UsersStore<'a>,
-    network: NetworkStore<'a>,
-    product: ProductStore<'a>,
-    software: SoftwareStore<'a>,
-    storage: StorageStore<'a>,
-    localization: LocalizationStore<'a>,
+    /// Stores in the order the sections must be written.
+    stores: Vec<Box<dyn SettingsStore + 'a>>,
 }
 
 impl<'a> Store<'a> {
     pub async fn new(connection: Connection) -> Result<Store<'a>, ServiceError> {
-        Ok(Self {
-            localization: LocalizationStore::new(connection.clone()).await?,
-            users: UsersStore::new(connection.clone()).await?,
-            network: NetworkStore::new(connection.clone()).await?,
-            product: ProductStore::new(connection.clone()).await?,
-            software: SoftwareStore::new(connection.clone()).await?,
-            storage: StorageStore::new(connection).await?,
-        })
+        // order is important here as network can be critical for connection
+        // to registration server and selecting product is important for rest.
+        // Localization goes after product as some product may miss some locales.
+        let stores: Vec<Box<dyn SettingsStore + 'a>> = vec![
+            Box::new(NetworkStore::new(connection.clone()).await?),
+            Box::new(ProductStore::new(connection.clone()).await?),
+            Box::new(LocalizationStore::new(connection.clone()).await?),
+            Box::new(SoftwareStore::new(connection.clone()).await?),
+            Box::new(UsersStore::new(connection.clone()).await?),
+            Box::new(StorageStore::new(connection).await?),
+        ];
+        Ok(Self { stores })
     }
 
     /// Loads the installation settings from the D-Bus service
+    ///
+    /// * `only`: sections to load (all of them if it is `None`).
     pub async fn load(&self, only: Option<Vec<Scope>>) -> Result<InstallSettings, ServiceError> {
-        let scopes = match only {
-            Some(scopes) => scopes,
-            None => Scope::all().to_vec(),
-        };
-
+        let scopes = only.unwrap_or_else(|| Scope::all().to_vec());
         let mut settings: InstallSettings = Default::default();
-        if scopes.contains(&Scope::Network) {
-            settings.network = Some(self.network.load().await?);
-        }
-        if scopes.contains(&Scope::Storage) {
-            settings.storage = Some(self.storage.load().await?);
-        }
-
-        if scopes.contains(&Scope::Software) {
-            settings.software = Some(self.software.load().await?);
-        }
-
-        if scopes.contains(&Scope::Users) {
-            settings.user = Some(self.users.load().await?);
-        }
-
-        if scopes.contains(&Scope::Product) {
-            settings.product = Some(self.product.load().await?);
-        }
-
-        if scopes.contains(&Scope::Localization) {
-            settings.localization = Some(self.localization.load().await?);
-        }
-
         // TODO: use try_join here
+        for store in &self.stores {
+            if scopes.contains(&store.scope()) {
+                store.load_section(&mut settings).await?;
+            }
+        }
         Ok(settings)
     }
 
     /// Stores the given installation settings in the D-Bus service
     pub async fn store(&self, settings: &InstallSettings) -> Result<(), ServiceError> {
-        if let Some(network) = &settings.network {
-            self.network.store(network).await?;
-        }
-        // order is important here as network can be critical for connection
-        // to registration server and selecting product is important for rest
-        if let Some(product) = &settings.product {
-            self.product.store(product).await?;
-        }
-        // ordering: localization after product as some product may miss some locales
-        if let Some(localization) = &settings.localization {
-            self.localization.store(localization).await?;
-        }
-        if let Some(software) = &settings.software {
-            self.software.store(software).await?;
-        }
-        if let Some(user) = &settings.user {
-            self.users.store(user).await?;
-        }
-        if let Some(storage) = &settings.storage {
-            self.storage.store(storage).await?;
+        for store in &self.stores {
+            store.store_section(settings).await?;
         }
         Ok(())
     }
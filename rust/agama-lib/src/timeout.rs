@@ -0,0 +1,110 @@
+//! Timeouts for the D-Bus calls performed by the clients.
+//!
+//! zbus waits forever for the reply to a method call, so a hung service would freeze the caller
+//! indefinitely. The clients wrap their calls with a [CallTimeout] instead. By default, they use
+//! the value returned by [default_timeout], which can be globally overridden through
+//! [set_default_timeout]. Each client offers a `with_timeout` method to set a different value.
+//!
+//! ```no_run
+//! # use agama_lib::{manager::ManagerClient, timeout};
+//! # use std::time::Duration;
+//! async fn build_client<'a>() -> ManagerClient<'a> {
+//!   timeout::set_default_timeout(Duration::from_secs(30));
+//!   let connection = agama_lib::connection().await.unwrap();
+//!   ManagerClient::new(connection)
+//!       .await
+//!       .unwrap()
+//!       .with_timeout(Duration::from_secs(10))
+//! }
+//! ```
+//!
+//! The operations that wait for a long-running task (e.g., probing or installing the system) are
+//! not limited.
+
+use std::{
+    future::Future,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// Default timeout for the D-Bus calls (in milliseconds).
+const DEFAULT_TIMEOUT_MS: u64 = 60_000;
+
+static TIMEOUT_MS: AtomicU64 = AtomicU64::new(DEFAULT_TIMEOUT_MS);
+
+/// Returns the timeout used by the clients unless they set their own.
+pub fn default_timeout() -> Duration {
+    Duration::from_millis(TIMEOUT_MS.load(Ordering::Relaxed))
+}
+
+/// Overrides the default timeout for the clients created afterwards.
+///
+/// * `timeout`: new default timeout.
+pub fn set_default_timeout(timeout: Duration) {
+    let millis = u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX);
+    TIMEOUT_MS.store(millis, Ordering::Relaxed);
+}
+
+/// Limits the time to wait for a D-Bus call.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CallTimeout(Duration);
+
+impl CallTimeout {
+    pub fn new(duration: Duration) -> Self {
+        Self(duration)
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.0
+    }
+
+    /// Runs the given call, failing with an `org.freedesktop.DBus.Error.Timeout` error if it does
+    /// not finish on time.
+    ///
+    /// * `call`: future to run.
+    pub async fn run<T, E, F>(&self, call: F) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>>,
+        E: From<zbus::Error>,
+    {
+        match tokio::time::timeout(self.0, call).await {
+            Ok(result) => result,
+            Err(_) => {
+                let message = format!("The D-Bus call did not finish in {:?}", self.0);
+                Err(zbus::Error::from(zbus::fdo::Error::Timeout(message)).into())
+            }
+        }
+    }
+}
+
+impl Default for CallTimeout {
+    fn default() -> Self {
+        Self(default_timeout())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CallTimeout;
+    use crate::error::ServiceError;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_run_on_time() {
+        let timeout = CallTimeout::new(Duration::from_secs(1));
+        let result: Result<u32, ServiceError> = timeout.run(async { Ok(1) }).await;
+        assert_eq!(result.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_too_late() {
+        let timeout = CallTimeout::new(Duration::from_millis(10));
+        let result: Result<(), ServiceError> = timeout
+            .run(async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok(())
+            })
+            .await;
+        assert!(matches!(result, Err(ServiceError::DBus(_))));
+    }
+}
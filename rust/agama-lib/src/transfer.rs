@@ -0,0 +1,571 @@
+//! File transfers from the locations supported by the installer
+//!
+//! Profiles, scripts and other assets can be retrieved from several kinds of locations:
+//!
+//! * `http`, `https`, `ftp`, `tftp` and `file` URLs, which are handled by curl.
+//! * `nfs`, `smb` and `cifs` URLs, which are mounted (see [crate::mount]).
+//! * `cd` (or `dvd`) and `usb` URLs (e.g., `usb:/profiles/profile.json`), which look for the
+//!   file in the CD/DVD drives or in the USB devices, respectively. The first device containing
+//!   the file wins.
+//! * `hd` URLs (e.g., `hd:/profile.json?device=LABEL=AGAMA`), which read the file from the device
+//!   given in the `device` parameter. The device can be identified by its file system label
+//!   (`LABEL=`), its file system UUID (`UUID=`) or its name (e.g., `sdb1` or
+//!   `/dev/disk/by-id/usb-stick-part1`).
+//!
+//! ```no_run
+//! # use agama_lib::transfer::Transfer;
+//! let mut file = std::fs::File::create("/tmp/profile.json").unwrap();
+//! Transfer::get("usb:/profiles/profile.json", &mut file).unwrap();
+//! ```
+//!
+//! Optionally, the downloaded content can be checked against a SHA256 checksum and a detached GPG
+//! signature (see [Verification]) before writing it, so tampered files are never used.
+
+use crate::mount::{self, MountError, MountedShare, NetworkShare};
+use curl::easy::Easy;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::Command,
+};
+use tempfile::TempDir;
+use thiserror::Error;
+use url::Url;
+
+#[derive(Error, Debug)]
+pub enum TransferError {
+    #[error("{}", crate::tr!("Could not parse the URL: {}", .0))]
+    WrongUrl(#[from] url::ParseError),
+    #[error("{}", crate::tr!("Unsupported URL: '{}'", .0))]
+    UnsupportedUrl(String),
+    #[error("{}", crate::tr!("Could not retrieve the file: {}", .0))]
+    CurlError(#[from] curl::Error),
+    #[error("{}", crate::tr!("Could not access the file: {}", .0))]
+    Mount(#[from] MountError),
+    #[error("{}", crate::tr!("Could not list the block devices: {}", .0))]
+    BlockDevices(String),
+    #[error("{}", crate::tr!("Could not find '{}' in any device", .0))]
+    FileNotFound(String),
+    #[error("{}", crate::tr!("Checksum mismatch: expected {}, got {}", .expected, .actual))]
+    ChecksumMismatch { expected: String, actual: String },
+    #[error("{}", crate::tr!("Invalid signature: {}", .0))]
+    InvalidSignature(String),
+    #[error("{}", crate::tr!("I/O error: {}", .0))]
+    IO(#[from] io::Error),
+}
+
+pub type TransferResult<T> = Result<T, TransferError>;
+
+/// Retrieves files from any of the supported locations.
+pub struct Transfer {}
+
+impl Transfer {
+    /// Retrieves the file from the given URL and writes its content to `out`.
+    ///
+    /// * `url`: URL of the file (e.g., "https://example.net/profile.json" or
+    ///   "cd:/profile.json").
+    /// * `out`: where to write the content to.
+    pub fn get(url: &str, out: &mut impl Write) -> TransferResult<()> {
+        let url = Url::parse(url)?;
+        match url.scheme() {
+            "http" | "https" | "ftp" | "tftp" | "file" => Self::get_with_curl(&url, out),
+            "nfs" | "smb" | "cifs" => Self::get_from_share(&url, out),
+            "cd" | "dvd" | "usb" => {
+                let kind = DeviceKind::from_scheme(url.scheme()).unwrap();
+                Self::get_from_devices(&url, |d| kind.matches(d), out)
+            }
+            "hd" => {
+                let spec = url
+                    .query_pairs()
+                    .find(|(k, _)| k == "device")
+                    .map(|(_, v)| DeviceSpec::parse(&v))
+                    .ok_or_else(|| TransferError::UnsupportedUrl(url.to_string()))?;
+                Self::get_from_devices(&url, |d| spec.matches(d), out)
+            }
+            _ => Err(TransferError::UnsupportedUrl(url.to_string())),
+        }
+    }
+
+    /// Retrieves the file from the given URL, verifies it and writes its content to `out`.
+    ///
+    /// Nothing is written if the verification fails.
+    ///
+    /// * `url`: URL of the file.
+    /// * `out`: where to write the content to.
+    /// * `verification`: checks to run on the content.
+    pub fn get_verified(
+        url: &str,
+        out: &mut impl Write,
+        verification: &Verification,
+    ) -> TransferResult<()> {
+        let mut content = vec![];
+        Self::get(url, &mut content)?;
+        verification.verify(&content)?;
+        out.write_all(&content)?;
+        Ok(())
+    }
+
+    fn get_with_curl(url: &Url, out: &mut impl Write) -> TransferResult<()> {
+        let mut handle = Easy::new();
+        handle.url(url.as_str())?;
+        handle.fail_on_error(true)?;
+
+        let mut transfer = handle.transfer();
+        transfer.write_function(|data| {
+            // returning a different size makes curl abort the transfer
+            match out.write_all(data) {
+                Ok(()) => Ok(data.len()),
+                Err(_) => Ok(0),
+            }
+        })?;
+        transfer.perform()?;
+        Ok(())
+    }
+
+    fn get_from_share(url: &Url, out: &mut impl Write) -> TransferResult<()> {
+        let share = NetworkShare::from_url(url)?;
+        let mounted = MountedShare::mount(&share)?;
+        let mut file = fs::File::open(mounted.file_path())?;
+        io::copy(&mut file, out)?;
+        mounted.unmount()?;
+        Ok(())
+    }
+
+    /// Looks for the file in the block devices selected by `filter`.
+    ///
+    /// The devices are mounted (read-only) one by one until the file is found.
+    ///
+    /// * `url`: URL of the file. Only the path is relevant.
+    /// * `filter`: function to select the devices.
+    /// * `out`: where to write the content to.
+    fn get_from_devices(
+        url: &Url,
+        filter: impl Fn(&BlockDevice) -> bool,
+        out: &mut impl Write,
+    ) -> TransferResult<()> {
+        let file = url.path().trim_start_matches('/');
+        if file.is_empty() {
+            return Err(TransferError::UnsupportedUrl(url.to_string()));
+        }
+
+        let devices = BlockDevice::all()?;
+        for device in devices.iter().filter(|d| d.fstype.is_some() && filter(d)) {
+            match Self::get_from_device(&device.path, file, out) {
+                Ok(true) => return Ok(()),
+                Ok(false) => {}
+                Err(e) => log::warn!("Could not look for the file in {}: {}", device.path, e),
+            }
+        }
+        Err(TransferError::FileNotFound(url.to_string()))
+    }
+
+    /// Copies the file from the given device and returns whether it was found.
+    ///
+    /// * `device`: device to mount (e.g., "/dev/sr0").
+    /// * `file`: path of the file relative to the root of the device.
+    /// * `out`: where to write the content to.
+    fn get_from_device(device: &str, file: &str, out: &mut impl Write) -> TransferResult<bool> {
+        let dir = TempDir::with_prefix("agama-device")?;
+        mount::mount_device(device, dir.path())?;
+        let path = dir.path().join(file);
+        let result = if path.is_file() {
+            fs::File::open(&path)
+                .and_then(|mut f| io::copy(&mut f, out))
+                .map(|_| true)
+        } else {
+            Ok(false)
+        };
+        mount::unmount(dir.path())?;
+        dir.close()?;
+        Ok(result?)
+    }
+}
+
+/// Checks to run on the downloaded content.
+///
+/// ```no_run
+/// # use agama_lib::transfer::{Transfer, Verification};
+/// let verification = Verification {
+///     signature: Some("https://example.net/profile.json.asc".to_string()),
+///     ..Default::default()
+/// };
+/// let mut profile = vec![];
+/// Transfer::get_verified("https://example.net/profile.json", &mut profile, &verification)
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Verification {
+    /// Expected SHA256 checksum (hex-encoded).
+    pub sha256: Option<String>,
+    /// URL of the detached GPG signature (any of the supported locations).
+    pub signature: Option<String>,
+    /// Keyring containing the keys to check the signature against. If it is not set,
+    /// [DEFAULT_KEYRING] is used.
+    pub keyring: Option<PathBuf>,
+}
+
+/// Keyring containing the trusted keys to check the signatures.
+pub const DEFAULT_KEYRING: &str = "/etc/agama.d/trusted-keys.gpg";
+
+impl Verification {
+    /// Whether there is nothing to check.
+    pub fn is_empty(&self) -> bool {
+        self.sha256.is_none() && self.signature.is_none()
+    }
+
+    /// Verifies the given content.
+    ///
+    /// * `content`: content to verify.
+    pub fn verify(&self, content: &[u8]) -> TransferResult<()> {
+        if let Some(expected) = &self.sha256 {
+            let actual = sha256_hex(content);
+            if !actual.eq_ignore_ascii_case(expected.trim()) {
+                return Err(TransferError::ChecksumMismatch {
+                    expected: expected.trim().to_string(),
+                    actual,
+                });
+            }
+        }
+
+        if let Some(signature_url) = &self.signature {
+            let mut signature = vec![];
+            Transfer::get(signature_url, &mut signature)?;
+            let keyring = self
+                .keyring
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_KEYRING));
+            verify_signature(content, &signature, &keyring)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the hex-encoded SHA256 checksum of the given content.
+///
+/// * `content`: content to calculate the checksum of.
+fn sha256_hex(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Checks a detached GPG signature using `gpgv`.
+///
+/// * `content`: signed content.
+/// * `signature`: detached signature (binary or ASCII-armored).
+/// * `keyring`: keyring containing the trusted keys.
+fn verify_signature(content: &[u8], signature: &[u8], keyring: &Path) -> TransferResult<()> {
+    let dir = TempDir::with_prefix("agama-verify")?;
+    let content_path = dir.path().join("content");
+    let signature_path = dir.path().join("content.sig");
+    fs::write(&content_path, content)?;
+    fs::write(&signature_path, signature)?;
+
+    let output = Command::new("gpgv")
+        .arg("--keyring")
+        .arg(keyring)
+        .arg(&signature_path)
+        .arg(&content_path)
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(TransferError::InvalidSignature(stderr));
+    }
+    Ok(())
+}
+
+/// Kind of devices to look for the files in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DeviceKind {
+    /// CD/DVD drives.
+    Optical,
+    /// USB devices.
+    Usb,
+}
+
+impl DeviceKind {
+    fn from_scheme(scheme: &str) -> Option<Self> {
+        match scheme {
+            "cd" | "dvd" => Some(Self::Optical),
+            "usb" => Some(Self::Usb),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, device: &BlockDevice) -> bool {
+        match self {
+            Self::Optical => device.kind == "rom",
+            Self::Usb => device.transport.as_deref() == Some("usb"),
+        }
+    }
+}
+
+/// Device given in the `device` parameter of an `hd` URL.
+#[derive(Clone, Debug, PartialEq)]
+enum DeviceSpec {
+    /// File system label.
+    Label(String),
+    /// File system UUID.
+    Uuid(String),
+    /// Device path (e.g., "/dev/sdb1" or "/dev/disk/by-label/AGAMA").
+    Path(String),
+}
+
+impl DeviceSpec {
+    /// Parses the value of the `device` parameter.
+    ///
+    /// The names which are not absolute are considered relative to `/dev` (e.g., "sdb1").
+    ///
+    /// * `value`: parameter value (e.g., "LABEL=AGAMA").
+    fn parse(value: &str) -> Self {
+        if let Some(label) = value.strip_prefix("LABEL=") {
+            Self::Label(label.to_string())
+        } else if let Some(uuid) = value.strip_prefix("UUID=") {
+            Self::Uuid(uuid.to_string())
+        } else if value.starts_with('/') {
+            Self::Path(value.to_string())
+        } else {
+            Self::Path(format!("/dev/{}", value))
+        }
+    }
+
+    fn matches(&self, device: &BlockDevice) -> bool {
+        match self {
+            Self::Label(label) => device.label.as_ref() == Some(label),
+            Self::Uuid(uuid) => device
+                .uuid
+                .as_ref()
+                .is_some_and(|u| u.eq_ignore_ascii_case(uuid)),
+            Self::Path(path) => {
+                // resolve the udev symlinks (e.g., /dev/disk/by-id/*)
+                let resolved = fs::canonicalize(path)
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_else(|_| path.clone());
+                device.path == resolved
+            }
+        }
+    }
+}
+
+/// Block device which might contain a file system.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockDevice {
+    /// Device path (e.g., "/dev/sdb1").
+    pub path: String,
+    /// Device type as reported by lsblk (e.g., "disk", "part" or "rom").
+    pub kind: String,
+    /// Transport (e.g., "usb" or "sata"). The partitions get the one from their disk.
+    pub transport: Option<String>,
+    pub fstype: Option<String>,
+    pub label: Option<String>,
+    pub uuid: Option<String>,
+}
+
+impl BlockDevice {
+    /// Returns all the block devices in the system, including the partitions.
+    pub fn all() -> TransferResult<Vec<Self>> {
+        let output = Command::new("lsblk")
+            .args([
+                "--json",
+                "--paths",
+                "--output",
+                "NAME,TYPE,TRAN,FSTYPE,LABEL,UUID",
+            ])
+            .output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(TransferError::BlockDevices(stderr));
+        }
+        Self::from_lsblk(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Builds the list of devices from the `lsblk --json` output.
+    ///
+    /// * `output`: lsblk output.
+    fn from_lsblk(output: &str) -> TransferResult<Vec<Self>> {
+        let lsblk: LsblkOutput =
+            serde_json::from_str(output).map_err(|e| TransferError::BlockDevices(e.to_string()))?;
+        let mut devices = vec![];
+        for device in lsblk.blockdevices {
+            Self::flatten(device, None, &mut devices);
+        }
+        Ok(devices)
+    }
+
+    fn flatten(device: LsblkDevice, transport: Option<String>, devices: &mut Vec<Self>) {
+        let transport = device.tran.or(transport);
+        devices.push(Self {
+            path: device.name,
+            kind: device.kind,
+            transport: transport.clone(),
+            fstype: device.fstype,
+            label: device.label,
+            uuid: device.uuid,
+        });
+        for child in device.children {
+            Self::flatten(child, transport.clone(), devices);
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LsblkOutput {
+    blockdevices: Vec<LsblkDevice>,
+}
+
+#[derive(Deserialize)]
+struct LsblkDevice {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    tran: Option<String>,
+    fstype: Option<String>,
+    label: Option<String>,
+    uuid: Option<String>,
+    #[serde(default)]
+    children: Vec<LsblkDevice>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LSBLK_OUTPUT: &str = r#"
+{
+   "blockdevices": [
+      {"name":"/dev/sda", "type":"disk", "tran":"sata", "fstype":null, "label":null, "uuid":null,
+         "children": [
+            {"name":"/dev/sda1", "type":"part", "tran":null, "fstype":"ext4", "label":"root", "uuid":"1f2e"}
+         ]
+      },
+      {"name":"/dev/sdb", "type":"disk", "tran":"usb", "fstype":null, "label":null, "uuid":null,
+         "children": [
+            {"name":"/dev/sdb1", "type":"part", "tran":null, "fstype":"vfat", "label":"AGAMA", "uuid":"ABCD-1234"}
+         ]
+      },
+      {"name":"/dev/sr0", "type":"rom", "tran":"sata", "fstype":"iso9660", "label":"Install", "uuid":"2024"}
+   ]
+}
+"#;
+
+    fn paths(devices: &[BlockDevice], kind: DeviceKind) -> Vec<&str> {
+        devices
+            .iter()
+            .filter(|d| d.fstype.is_some() && kind.matches(d))
+            .map(|d| d.path.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_block_devices_from_lsblk() {
+        let devices = BlockDevice::from_lsblk(LSBLK_OUTPUT).unwrap();
+        assert_eq!(devices.len(), 5);
+
+        let sdb1 = devices.iter().find(|d| d.path == "/dev/sdb1").unwrap();
+        assert_eq!(sdb1.kind, "part");
+        assert_eq!(sdb1.transport, Some("usb".to_string()));
+        assert_eq!(sdb1.label, Some("AGAMA".to_string()));
+        assert_eq!(sdb1.uuid, Some("ABCD-1234".to_string()));
+    }
+
+    #[test]
+    fn test_device_kind_matches() {
+        let devices = BlockDevice::from_lsblk(LSBLK_OUTPUT).unwrap();
+        assert_eq!(paths(&devices, DeviceKind::Optical), vec!["/dev/sr0"]);
+        assert_eq!(paths(&devices, DeviceKind::Usb), vec!["/dev/sdb1"]);
+        assert_eq!(DeviceKind::from_scheme("dvd"), Some(DeviceKind::Optical));
+        assert_eq!(DeviceKind::from_scheme("http"), None);
+    }
+
+    #[test]
+    fn test_device_spec_matches() {
+        let devices = BlockDevice::from_lsblk(LSBLK_OUTPUT).unwrap();
+        let find = |value: &str| -> Vec<&str> {
+            let spec = DeviceSpec::parse(value);
+            devices
+                .iter()
+                .filter(|d| spec.matches(d))
+                .map(|d| d.path.as_str())
+                .collect()
+        };
+
+        assert_eq!(find("LABEL=AGAMA"), vec!["/dev/sdb1"]);
+        assert_eq!(find("UUID=abcd-1234"), vec!["/dev/sdb1"]);
+        assert_eq!(find("sda1"), vec!["/dev/sda1"]);
+        assert_eq!(find("/dev/sr0"), vec!["/dev/sr0"]);
+        assert!(find("LABEL=missing").is_empty());
+    }
+
+    #[test]
+    fn test_get_unsupported_url() {
+        let mut out = vec![];
+        let result = Transfer::get("gopher://example.net/profile.json", &mut out);
+        assert!(matches!(result, Err(TransferError::UnsupportedUrl(_))));
+
+        let result = Transfer::get("usb:/", &mut out);
+        assert!(matches!(result, Err(TransferError::UnsupportedUrl(_))));
+
+        let result = Transfer::get("hd:/profile.json", &mut out);
+        assert!(matches!(result, Err(TransferError::UnsupportedUrl(_))));
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let checksum = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let verification = Verification {
+            sha256: Some(checksum.to_uppercase()),
+            ..Default::default()
+        };
+        assert!(verification.verify(b"hello").is_ok());
+
+        let result = verification.verify(b"tampered");
+        let Err(TransferError::ChecksumMismatch { expected, actual }) = result else {
+            panic!("the checksum should not match");
+        };
+        assert_eq!(expected, checksum.to_uppercase());
+        assert_eq!(actual, sha256_hex(b"tampered"));
+    }
+
+    #[test]
+    fn test_get_verified() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("script.sh");
+        fs::write(&path, "echo hello\n").unwrap();
+        let url = Url::from_file_path(&path).unwrap();
+
+        let mut out = vec![];
+        let verification = Verification {
+            sha256: Some(sha256_hex(b"echo bye\n")),
+            ..Default::default()
+        };
+        let result = Transfer::get_verified(url.as_str(), &mut out, &verification);
+        assert!(matches!(
+            result,
+            Err(TransferError::ChecksumMismatch { .. })
+        ));
+        assert!(out.is_empty());
+
+        let verification = Verification {
+            sha256: Some(sha256_hex(b"echo hello\n")),
+            ..Default::default()
+        };
+        Transfer::get_verified(url.as_str(), &mut out, &verification).unwrap();
+        assert_eq!(out, b"echo hello\n");
+    }
+
+    #[test]
+    fn test_get_from_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("script.sh");
+        fs::write(&path, "echo hello\n").unwrap();
+
+        let mut out = vec![];
+        let url = Url::from_file_path(&path).unwrap();
+        Transfer::get(url.as_str(), &mut out).unwrap();
+        assert_eq!(out, b"echo hello\n");
+    }
+}
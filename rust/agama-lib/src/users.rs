@@ -6,5 +6,7 @@ mod settings;
 mod store;
 
 pub use client::{FirstUser, UsersClient};
-pub use settings::{FirstUserSettings, RootUserSettings, UserSettings};
+pub use settings::{
+    FirstUserSettings, RootUserSettings, SshHostKeysSettings, UserAccountSettings, UserSettings,
+};
 pub use store::UsersStore;
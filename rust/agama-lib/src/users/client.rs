@@ -1,10 +1,16 @@
 //! Implements a client to access Agama's users service.
 
 use super::proxies::{FirstUser as FirstUserFromDBus, Users1Proxy};
+use super::{SshHostKeysSettings, UserAccountSettings};
 use crate::error::ServiceError;
+use crate::timeout::CallTimeout;
 use agama_settings::{settings::Settings, SettingValue, SettingsError};
 use serde::Serialize;
-use zbus::Connection;
+use std::{collections::HashMap, time::Duration};
+use zbus::{
+    zvariant::{OwnedValue, Value},
+    Connection,
+};
 
 /// Represents the settings for the first user
 #[derive(Serialize, Debug, Default)]
@@ -14,6 +20,8 @@ pub struct FirstUser {
     /// First user's username
     pub user_name: String,
     /// First user's password (in clear text)
+    ///
+    /// The service does not expose the password, so it is empty when reading the first user.
     pub password: String,
     /// Whether auto-login should enabled or not
     pub autologin: bool,
@@ -67,19 +75,31 @@ impl Settings for FirstUser {
 
 /// D-Bus client for the users service
 pub struct UsersClient<'a> {
+    timeout: CallTimeout,
     users_proxy: Users1Proxy<'a>,
 }
 
 impl<'a> UsersClient<'a> {
     pub async fn new(connection: Connection) -> zbus::Result<UsersClient<'a>> {
         Ok(Self {
+            timeout: CallTimeout::default(),
             users_proxy: Users1Proxy::new(&connection).await?,
         })
     }
 
+    /// Sets the timeout for the D-Bus calls.
+    ///
+    /// * `timeout`: time to wait for each call.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = CallTimeout::new(timeout);
+        self
+    }
+
     /// Returns the settings for first non admin user
     pub async fn first_user(&self) -> zbus::Result<FirstUser> {
-        FirstUser::from_dbus(self.users_proxy.first_user().await)
+        self.timeout
+            .run(async { FirstUser::from_dbus(self.users_proxy.first_user().await) })
+            .await
     }
 
     /// SetRootPassword method
@@ -88,22 +108,92 @@ impl<'a> UsersClient<'a> {
         value: &str,
         encrypted: bool,
     ) -> Result<u32, ServiceError> {
-        Ok(self.users_proxy.set_root_password(value, encrypted).await?)
+        self.timeout
+            .run(async { Ok(self.users_proxy.set_root_password(value, encrypted).await?) })
+            .await
     }
 
     /// Whether the root password is set or not
     pub async fn is_root_password(&self) -> Result<bool, ServiceError> {
-        Ok(self.users_proxy.root_password_set().await?)
+        self.timeout
+            .run(async { Ok(self.users_proxy.root_password_set().await?) })
+            .await
     }
 
     /// Returns the SSH key for the root user
     pub async fn root_ssh_key(&self) -> zbus::Result<String> {
-        self.users_proxy.root_sshkey().await
+        self.timeout
+            .run(async { self.users_proxy.root_sshkey().await })
+            .await
     }
 
     /// SetRootSSHKey method
     pub async fn set_root_sshkey(&self, value: &str) -> Result<u32, ServiceError> {
-        Ok(self.users_proxy.set_root_sshkey(value).await?)
+        self.timeout
+            .run(async { Ok(self.users_proxy.set_root_sshkey(value).await?) })
+            .await
+    }
+
+    /// Returns the additional users
+    pub async fn users(&self) -> zbus::Result<Vec<UserAccountSettings>> {
+        self.timeout
+            .run(async {
+                let users = self.users_proxy.users().await?;
+                Ok(users.iter().map(user_from_dbus).collect())
+            })
+            .await
+    }
+
+    /// Replaces the additional users
+    ///
+    /// It returns whether the users were set and, if not, the list of issues.
+    ///
+    /// * `users`: users to set.
+    pub async fn set_users(
+        &self,
+        users: &[UserAccountSettings],
+    ) -> zbus::Result<(bool, Vec<String>)> {
+        let users: Vec<_> = users.iter().map(user_to_dbus).collect();
+        self.timeout
+            .run(async { self.users_proxy.set_users(&users).await })
+            .await
+    }
+
+    /// Returns the location of the imported SSH host keys
+    ///
+    /// It returns `None` if no keys were imported.
+    pub async fn ssh_host_keys(&self) -> zbus::Result<Option<SshHostKeysSettings>> {
+        self.timeout
+            .run(async {
+                let source = self.users_proxy.sshhost_keys_source().await?;
+                Ok(ssh_host_keys_from_dbus(&source))
+            })
+            .await
+    }
+
+    /// Imports the SSH host keys from the given location
+    ///
+    /// It returns whether the keys were imported and, if not, the list of issues. Importing from
+    /// an empty location discards the previously imported keys.
+    ///
+    /// * `settings`: location of the keys.
+    pub async fn import_ssh_host_keys(
+        &self,
+        settings: &SshHostKeysSettings,
+    ) -> zbus::Result<(bool, Vec<String>)> {
+        let mut source: HashMap<&str, Value> = HashMap::new();
+        if let Some(device) = &settings.device {
+            source.insert("Device", device.as_str().into());
+        }
+        if let Some(url) = &settings.url {
+            source.insert("Url", url.as_str().into());
+        }
+        if let Some(sha256) = &settings.sha256 {
+            source.insert("Sha256", sha256.as_str().into());
+        }
+        self.timeout
+            .run(async { self.users_proxy.import_sshhost_keys(source).await })
+            .await
     }
 
     /// Set the configuration for the first user
@@ -111,14 +201,177 @@ impl<'a> UsersClient<'a> {
         &self,
         first_user: &FirstUser,
     ) -> zbus::Result<(bool, Vec<String>)> {
-        self.users_proxy
-            .set_first_user(
-                &first_user.full_name,
-                &first_user.user_name,
-                &first_user.password,
-                first_user.autologin,
-                std::collections::HashMap::new(),
-            )
+        self.timeout
+            .run(async {
+                self.users_proxy
+                    .set_first_user(
+                        &first_user.full_name,
+                        &first_user.user_name,
+                        &first_user.password,
+                        first_user.autologin,
+                        std::collections::HashMap::new(),
+                    )
+                    .await
+            })
             .await
     }
 }
+
+/// Converts an additional user into a D-Bus hash.
+///
+/// Only the defined settings are included.
+///
+/// * `user`: user settings.
+fn user_to_dbus(user: &UserAccountSettings) -> HashMap<&str, Value<'_>> {
+    let mut data: HashMap<&str, Value> =
+        HashMap::from([("UserName", user.user_name.as_str().into())]);
+    if let Some(full_name) = &user.full_name {
+        data.insert("FullName", full_name.as_str().into());
+    }
+    if let Some(password) = &user.password {
+        data.insert("Password", password.as_str().into());
+        data.insert(
+            "EncryptedPassword",
+            user.encrypted_password.unwrap_or_default().into(),
+        );
+    }
+    if let Some(uid) = user.uid {
+        data.insert("UID", uid.into());
+    }
+    if !user.groups.is_empty() {
+        data.insert("Groups", user.groups.clone().into());
+    }
+    if let Some(shell) = &user.shell {
+        data.insert("Shell", shell.as_str().into());
+    }
+    if let Some(sudo) = &user.sudo {
+        data.insert("Sudo", sudo.as_str().into());
+    }
+    data
+}
+
+/// Builds an additional user from a D-Bus hash.
+///
+/// The service does not expose the password, so it is not included.
+///
+/// * `data`: D-Bus hash.
+fn user_from_dbus(data: &HashMap<String, OwnedValue>) -> UserAccountSettings {
+    let string = |key: &str| -> Option<String> {
+        data.get(key)
+            .and_then(|v| v.downcast_ref::<str>())
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    // the service might send the UID as a signed integer
+    let uid = data.get("UID").and_then(|v| {
+        v.downcast_ref::<u32>()
+            .copied()
+            .or_else(|| v.downcast_ref::<i32>().and_then(|i| u32::try_from(*i).ok()))
+    });
+    let groups = data
+        .get("Groups")
+        .and_then(|v| <Vec<String>>::try_from(v.clone()).ok())
+        .unwrap_or_default();
+
+    UserAccountSettings {
+        user_name: string("UserName").unwrap_or_default(),
+        full_name: string("FullName"),
+        password: None,
+        encrypted_password: None,
+        uid,
+        groups,
+        shell: string("Shell"),
+        sudo: string("Sudo"),
+    }
+}
+
+/// Builds the SSH host keys location from a D-Bus hash.
+///
+/// * `data`: D-Bus hash.
+fn ssh_host_keys_from_dbus(data: &HashMap<String, OwnedValue>) -> Option<SshHostKeysSettings> {
+    let string = |key: &str| -> Option<String> {
+        data.get(key)
+            .and_then(|v| v.downcast_ref::<str>())
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let settings = SshHostKeysSettings {
+        device: string("Device"),
+        url: string("Url"),
+        sha256: string("Sha256"),
+    };
+    if settings == SshHostKeysSettings::default() {
+        return None;
+    }
+    Some(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_to_dbus_and_back() {
+        let user = UserAccountSettings {
+            user_name: "john".to_string(),
+            full_name: Some("John Doe".to_string()),
+            password: Some("$6$abc".to_string()),
+            encrypted_password: Some(true),
+            uid: Some(1010),
+            groups: vec!["wheel".to_string()],
+            shell: None,
+            sudo: Some("ALL=(ALL) ALL".to_string()),
+        };
+        let data: HashMap<String, OwnedValue> = user_to_dbus(&user)
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.into()))
+            .collect();
+        assert!(!data.contains_key("Shell"));
+        assert_eq!(
+            user_from_dbus(&data),
+            UserAccountSettings {
+                password: None,
+                encrypted_password: None,
+                ..user
+            }
+        );
+
+        let data = HashMap::from([
+            (
+                "UserName".to_string(),
+                OwnedValue::from(Value::from("jane")),
+            ),
+            ("UID".to_string(), OwnedValue::from(Value::from(1011_i32))),
+            ("Shell".to_string(), OwnedValue::from(Value::from(""))),
+        ]);
+        let user = user_from_dbus(&data);
+        assert_eq!(user.uid, Some(1011));
+        assert_eq!(user.shell, None);
+    }
+
+    #[test]
+    fn test_ssh_host_keys_from_dbus() {
+        assert_eq!(ssh_host_keys_from_dbus(&HashMap::new()), None);
+
+        let data = HashMap::from([(
+            "Device".to_string(),
+            OwnedValue::from(Value::from("/dev/sda2")),
+        )]);
+        let settings = ssh_host_keys_from_dbus(&data).unwrap();
+        assert_eq!(settings.device, Some("/dev/sda2".to_string()));
+        assert_eq!(settings.url, None);
+
+        let data = HashMap::from([
+            (
+                "Url".to_string(),
+                OwnedValue::from(Value::from("https://example.net/keys.tar")),
+            ),
+            (
+                "Sha256".to_string(),
+                OwnedValue::from(Value::from("abc123")),
+            ),
+        ]);
+        let settings = ssh_host_keys_from_dbus(&data).unwrap();
+        assert_eq!(settings.sha256, Some("abc123".to_string()));
+    }
+}
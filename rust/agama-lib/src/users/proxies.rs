@@ -26,6 +26,13 @@ pub type FirstUser = (
     default_path = "/org/opensuse/Agama/Users1"
 )]
 trait Users1 {
+    /// ImportSSHHostKeys method
+    #[dbus_proxy(name = "ImportSSHHostKeys")]
+    fn import_sshhost_keys(
+        &self,
+        source: std::collections::HashMap<&str, zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<(bool, Vec<String>)>;
+
     /// RemoveFirstUser method
     fn remove_first_user(&self) -> zbus::Result<u32>;
 
@@ -49,6 +56,12 @@ trait Users1 {
     #[dbus_proxy(name = "SetRootSSHKey")]
     fn set_root_sshkey(&self, value: &str) -> zbus::Result<u32>;
 
+    /// SetUsers method
+    fn set_users(
+        &self,
+        users: &[std::collections::HashMap<&str, zbus::zvariant::Value<'_>>],
+    ) -> zbus::Result<(bool, Vec<String>)>;
+
     /// Write method
     fn write(&self) -> zbus::Result<u32>;
 
@@ -63,4 +76,20 @@ trait Users1 {
     /// RootSSHKey property
     #[dbus_proxy(property, name = "RootSSHKey")]
     fn root_sshkey(&self) -> zbus::Result<String>;
+
+    /// SSHHostKeys property
+    #[dbus_proxy(property, name = "SSHHostKeys")]
+    fn sshhost_keys(&self) -> zbus::Result<Vec<String>>;
+
+    /// SSHHostKeysSource property
+    #[dbus_proxy(property, name = "SSHHostKeysSource")]
+    fn sshhost_keys_source(
+        &self,
+    ) -> zbus::Result<std::collections::HashMap<String, zbus::zvariant::OwnedValue>>;
+
+    /// Users property
+    #[dbus_proxy(property)]
+    fn users(
+        &self,
+    ) -> zbus::Result<Vec<std::collections::HashMap<String, zbus::zvariant::OwnedValue>>>;
 }
@@ -1,4 +1,4 @@
-use agama_settings::Settings;
+use agama_settings::{error::ConversionError, SettingObject, Settings};
 use serde::{Deserialize, Serialize};
 
 /// User settings
@@ -12,6 +12,14 @@ pub struct UserSettings {
     pub first_user: Option<FirstUserSettings>,
     #[settings(nested)]
     pub root: Option<RootUserSettings>,
+    /// Additional users
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    #[settings(collection)]
+    pub users: Vec<UserAccountSettings>,
+    /// Where to import the SSH host keys from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[settings(nested)]
+    pub ssh_host_keys: Option<SshHostKeysSettings>,
 }
 
 /// First user settings
@@ -43,10 +51,91 @@ pub struct RootUserSettings {
     pub ssh_public_key: Option<String>,
 }
 
+/// SSH host keys settings
+///
+/// Holds the location of the SSH host keys to import into the installed system, so the server
+/// fingerprints do not change after a reinstallation. Only one of the locations is expected.
+#[derive(Clone, Debug, Default, PartialEq, Settings, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshHostKeysSettings {
+    /// Device containing a previous installation (e.g., "/dev/sda2")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    /// URL of a tar archive containing the keys
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// SHA-256 checksum of the archive (required when using a URL)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+/// Additional user settings
+///
+/// Holds the settings for a user other than root and the first user.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserAccountSettings {
+    /// User's username
+    pub user_name: String,
+    /// User's full name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_name: Option<String>,
+    /// User's password (in clear text unless `encrypted_password` is true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Whether the password is already encrypted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encrypted_password: Option<bool>,
+    /// User ID (`None` means using the next available one)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u32>,
+    /// Supplementary groups (they are created if they do not exist)
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub groups: Vec<String>,
+    /// Login shell
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    /// Sudo rule for the user (e.g., "ALL=(ALL) ALL")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sudo: Option<String>,
+}
+
+impl TryFrom<SettingObject> for UserAccountSettings {
+    type Error = ConversionError;
+
+    fn try_from(value: SettingObject) -> Result<Self, Self::Error> {
+        let Some(user_name) = value.get("user_name") else {
+            return Err(ConversionError::MissingKey("user_name".to_string()));
+        };
+
+        let mut user = UserAccountSettings {
+            user_name: user_name.clone().try_into()?,
+            ..Default::default()
+        };
+        if let Some(full_name) = value.get("full_name") {
+            user.full_name = full_name.clone().try_into()?;
+        }
+        if let Some(uid) = value.get("uid") {
+            user.uid = uid.clone().try_into()?;
+        }
+        if let Some(groups) = value.get("groups") {
+            user.groups = groups.to_string().split(',').map(str::to_string).collect();
+        }
+        if let Some(shell) = value.get("shell") {
+            user.shell = shell.clone().try_into()?;
+        }
+        if let Some(sudo) = value.get("sudo") {
+            user.sudo = sudo.clone().try_into()?;
+        }
+        Ok(user)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use agama_settings::settings::Settings;
+    use std::collections::HashMap;
 
     #[test]
     fn test_user_settings_merge() {
@@ -60,6 +149,7 @@ mod tests {
                 password: Some("nots3cr3t".to_string()),
                 ..Default::default()
             }),
+            ..Default::default()
         };
         user1.merge(&user2);
         let first_user = user1.first_user.unwrap();
@@ -79,4 +169,23 @@ mod tests {
         user1.merge(&user2);
         assert_eq!(user1.full_name.unwrap(), "Jane Doe")
     }
+
+    #[test]
+    fn test_user_account_from_setting_object() {
+        let object = SettingObject::from(HashMap::from([
+            ("user_name".to_string(), "john".to_string()),
+            ("uid".to_string(), "1010".to_string()),
+            ("groups".to_string(), "wheel,video".to_string()),
+        ]));
+        let user = UserAccountSettings::try_from(object).unwrap();
+        assert_eq!(user.user_name, "john");
+        assert_eq!(user.uid, Some(1010));
+        assert_eq!(user.groups, vec!["wheel".to_string(), "video".to_string()]);
+
+        let object = SettingObject::from(HashMap::from([(
+            "full_name".to_string(),
+            "John Doe".to_string(),
+        )]));
+        assert!(UserAccountSettings::try_from(object).is_err());
+    }
 }
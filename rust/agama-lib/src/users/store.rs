@@ -1,5 +1,11 @@
-use super::{FirstUser, FirstUserSettings, RootUserSettings, UserSettings, UsersClient};
+use super::{
+    FirstUser, FirstUserSettings, RootUserSettings, SshHostKeysSettings, UserAccountSettings,
+    UserSettings, UsersClient,
+};
 use crate::error::ServiceError;
+use crate::install_settings::{InstallSettings, Scope};
+use crate::store::SettingsStore;
+use async_trait::async_trait;
 use zbus::Connection;
 
 /// Loads and stores the users settings from/to the D-Bus service.
@@ -20,16 +26,21 @@ impl<'a> UsersStore<'a> {
             user_name: Some(first_user.user_name),
             autologin: Some(first_user.autologin),
             full_name: Some(first_user.full_name),
-            password: Some(first_user.password),
+            // the password is write-only, so it is not included when it is empty
+            password: Some(first_user.password).filter(|p| !p.is_empty()),
         };
         let mut root_user = RootUserSettings::default();
         let ssh_public_key = self.users_client.root_ssh_key().await?;
         if !ssh_public_key.is_empty() {
             root_user.ssh_public_key = Some(ssh_public_key)
         }
+        let users = self.users_client.users().await?;
+        let ssh_host_keys = self.users_client.ssh_host_keys().await?;
         Ok(UserSettings {
             first_user: Some(first_user),
             root: Some(root_user),
+            users,
+            ssh_host_keys,
         })
     }
 
@@ -42,6 +53,33 @@ impl<'a> UsersStore<'a> {
         if let Some(settings) = &settings.root {
             self.store_root_user(settings).await?;
         }
+
+        if !settings.users.is_empty() {
+            self.store_users(&settings.users).await?;
+        }
+
+        if let Some(settings) = &settings.ssh_host_keys {
+            self.store_ssh_host_keys(settings).await?;
+        }
+        Ok(())
+    }
+
+    async fn store_ssh_host_keys(
+        &self,
+        settings: &SshHostKeysSettings,
+    ) -> Result<(), ServiceError> {
+        let (success, issues) = self.users_client.import_ssh_host_keys(settings).await?;
+        if !success {
+            return Err(ServiceError::SshHostKeys(issues));
+        }
+        Ok(())
+    }
+
+    async fn store_users(&self, users: &[UserAccountSettings]) -> Result<(), ServiceError> {
+        let (success, issues) = self.users_client.set_users(users).await?;
+        if !success {
+            return Err(ServiceError::WrongUser(issues));
+        }
         Ok(())
     }
 
@@ -74,3 +112,22 @@ impl<'a> UsersStore<'a> {
         Ok(())
     }
 }
+
+#[async_trait]
+impl<'a> SettingsStore for UsersStore<'a> {
+    fn scope(&self) -> Scope {
+        Scope::Users
+    }
+
+    async fn load_section(&self, settings: &mut InstallSettings) -> Result<(), ServiceError> {
+        settings.user = Some(self.load().await?);
+        Ok(())
+    }
+
+    async fn store_section(&self, settings: &InstallSettings) -> Result<(), ServiceError> {
+        if let Some(user) = &settings.user {
+            self.store(user).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,394 @@
+//! Record/playback of D-Bus interactions for the tests.
+//!
+//! Testing the clients against the real services requires the whole installer (including the Ruby
+//! services) to be running. Instead, the tests can use a [Cassette], which offers a connection that
+//! replies to the method calls using the responses recorded in a fixture
+//! (`tests/fixtures/dbus/<name>.json`).
+//!
+//! ```ignore
+//! let cassette = Cassette::new("software_patterns").await.unwrap();
+//! let client = SoftwareClient::new(cassette.connection()).await.unwrap();
+//! let patterns = client.selected_patterns().await.unwrap();
+//! ```
+//!
+//! To record (or refresh) the fixtures, run the tests with the `AGAMA_VCR_RECORD` environment
+//! variable set. The calls are forwarded to the Agama bus (or to the address given in the variable,
+//! e.g., `AGAMA_VCR_RECORD=unix:path=/tmp/bus`) and the fixture is written when the test finishes.
+//!
+//! The calls are matched by destination, path, interface, method and arguments. When the same call
+//! was recorded several times (e.g., because the state of the service changed), the responses are
+//! replayed in order and the last one is repeated. The calls that were not recorded get an
+//! `org.freedesktop.DBus.Error.Failed` error.
+
+use anyhow::Context;
+use byteorder::{NativeEndian, LE};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use zbus::{
+    zvariant::{self, DynamicDeserialize, EncodingContext, Structure},
+    Connection, ConnectionBuilder, Guid, Message, MessageBuilder, MessageStream, MessageType,
+};
+
+/// Environment variable to enable the recording mode.
+const RECORD_VAR: &str = "AGAMA_VCR_RECORD";
+const FAILED_ERROR: &str = "org.freedesktop.DBus.Error.Failed";
+
+/// Method call, as stored in the fixtures.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Call {
+    destination: String,
+    path: String,
+    interface: String,
+    member: String,
+    signature: String,
+    /// Arguments (little-endian D-Bus encoding, in hexadecimal)
+    body: String,
+    /// Human-readable arguments (only informative)
+    args: String,
+}
+
+impl Call {
+    fn from_message(message: &Message) -> zbus::Result<Self> {
+        let header = message.header()?;
+        let (signature, body, args) = encode_body(message)?;
+        Ok(Self {
+            destination: header
+                .destination()?
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+            path: message.path().map(|p| p.to_string()).unwrap_or_default(),
+            interface: message
+                .interface()
+                .map(|i| i.to_string())
+                .unwrap_or_default(),
+            member: message.member().map(|m| m.to_string()).unwrap_or_default(),
+            signature,
+            body,
+            args,
+        })
+    }
+
+    /// Forwards the call to the given connection and returns the reply.
+    ///
+    /// * `connection`: connection to send the call through.
+    async fn forward(&self, connection: &Connection) -> zbus::Result<Reply> {
+        let mut builder = MessageBuilder::method_call(self.path.as_str(), self.member.as_str())?;
+        if !self.interface.is_empty() {
+            builder = builder.interface(self.interface.as_str())?;
+        }
+        if !self.destination.is_empty() {
+            builder = builder.destination(self.destination.as_str())?;
+        }
+        let message = build_message(builder, &self.signature, &self.body)?;
+
+        let mut stream = MessageStream::from(connection);
+        let serial = connection.send_message(message).await?;
+        while let Some(message) = stream.next().await {
+            let message = message?;
+            if message.reply_serial() == Some(serial) {
+                return Reply::from_message(&message);
+            }
+        }
+        Err(zbus::Error::Failure(format!("No reply for {}", self)))
+    }
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}.{}{}",
+            self.destination, self.path, self.interface, self.member, self.args
+        )
+    }
+}
+
+/// Reply to a method call, as stored in the fixtures.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Reply {
+    /// Error name, if the call failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    signature: String,
+    /// Reply body (little-endian D-Bus encoding, in hexadecimal)
+    body: String,
+    /// Human-readable reply (only informative)
+    value: String,
+}
+
+impl Reply {
+    fn from_message(message: &Message) -> zbus::Result<Self> {
+        let error = match message.message_type() {
+            MessageType::Error => message.header()?.error_name()?.map(|e| e.to_string()),
+            _ => None,
+        };
+        let (signature, body, value) = encode_body(message)?;
+        Ok(Self {
+            error,
+            signature,
+            body,
+            value,
+        })
+    }
+
+    /// Sends the reply to a method call.
+    ///
+    /// * `connection`: connection to send the reply through.
+    /// * `call`: method call to reply to.
+    async fn send(&self, connection: &Connection, call: &Message) -> zbus::Result<()> {
+        let header = call.header()?;
+        let builder = match &self.error {
+            Some(name) => MessageBuilder::error(&header, name.as_str())?,
+            None => MessageBuilder::method_return(&header)?,
+        };
+        let message = build_message(builder, &self.signature, &self.body)?;
+        connection.send_message(message).await?;
+        Ok(())
+    }
+}
+
+/// Recorded interaction: a method call and its reply.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Interaction {
+    call: Call,
+    reply: Reply,
+}
+
+/// Provides a D-Bus connection which replays (or records) the interactions of a test.
+pub struct Cassette {
+    path: PathBuf,
+    connection: Connection,
+    /// Recorded interactions (only in recording mode)
+    recorded: Option<Arc<Mutex<Vec<Interaction>>>>,
+}
+
+impl Cassette {
+    /// Creates a cassette for the given fixture.
+    ///
+    /// It replays the interactions of the fixture unless the recording mode is enabled (see the
+    /// module documentation).
+    ///
+    /// * `name`: fixture name (without the extension).
+    pub async fn new(name: &str) -> anyhow::Result<Self> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/dbus")
+            .join(format!("{}.json", name));
+        let (server, connection) = p2p_connections().await?;
+        // the stream must exist before the first call is sent, or the call would be lost
+        let stream = MessageStream::from(&server);
+
+        let recorded = match std::env::var(RECORD_VAR) {
+            Ok(address) => {
+                let upstream = if address.contains(':') {
+                    crate::connection_to(&address).await?
+                } else {
+                    crate::connection().await?
+                };
+                let recorded = Arc::new(Mutex::new(vec![]));
+                tokio::spawn(record(server, stream, upstream, Arc::clone(&recorded)));
+                Some(recorded)
+            }
+            Err(_) => {
+                let content = fs::read_to_string(&path).with_context(|| {
+                    format!(
+                        "Could not read {} (run the test with {} to record it)",
+                        path.display(),
+                        RECORD_VAR
+                    )
+                })?;
+                let interactions: Vec<Interaction> = serde_json::from_str(&content)?;
+                tokio::spawn(play(server, stream, interactions));
+                None
+            }
+        };
+
+        Ok(Self {
+            path,
+            connection,
+            recorded,
+        })
+    }
+
+    /// Returns the connection to use in the test.
+    pub fn connection(&self) -> Connection {
+        self.connection.clone()
+    }
+}
+
+impl Drop for Cassette {
+    /// Writes the recorded interactions, unless the test failed.
+    fn drop(&mut self) {
+        let Some(recorded) = &self.recorded else {
+            return;
+        };
+        if std::thread::panicking() {
+            return;
+        }
+        let interactions = recorded.lock().unwrap();
+        let content = serde_json::to_string_pretty(&*interactions).unwrap();
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(&self.path, content + "\n").unwrap();
+    }
+}
+
+/// Returns both ends of a peer-to-peer connection: the server and the client.
+async fn p2p_connections() -> zbus::Result<(Connection, Connection)> {
+    let (server_socket, client_socket) = tokio::net::UnixStream::pair()?;
+    let guid = Guid::generate();
+    let server = ConnectionBuilder::socket(server_socket)
+        .server(&guid)
+        .p2p()
+        .build();
+    let client = ConnectionBuilder::socket(client_socket).p2p().build();
+    futures_util::try_join!(server, client)
+}
+
+/// Replies to the method calls using the recorded interactions.
+///
+/// * `server`: connection to reply through.
+/// * `stream`: stream of the messages received by the server.
+/// * `interactions`: recorded interactions.
+async fn play(server: Connection, mut stream: MessageStream, mut interactions: Vec<Interaction>) {
+    while let Some(Ok(message)) = stream.next().await {
+        if message.message_type() != MessageType::MethodCall {
+            continue;
+        }
+        let result = match Call::from_message(&message) {
+            Ok(call) => match take_reply(&mut interactions, &call) {
+                Some(reply) => reply.send(&server, &message).await,
+                None => {
+                    let error = format!("No recorded reply for {}", call);
+                    server
+                        .reply_error(&message, FAILED_ERROR, &error)
+                        .await
+                        .map(|_| ())
+                }
+            },
+            Err(error) => Err(error),
+        };
+        if let Err(error) = result {
+            log::warn!("Could not reply to a recorded call: {}", error);
+        }
+    }
+}
+
+/// Forwards the method calls to the real services and records the interactions.
+///
+/// * `server`: connection to reply through.
+/// * `stream`: stream of the messages received by the server.
+/// * `upstream`: connection to the real services.
+/// * `recorded`: recorded interactions.
+async fn record(
+    server: Connection,
+    mut stream: MessageStream,
+    upstream: Connection,
+    recorded: Arc<Mutex<Vec<Interaction>>>,
+) {
+    while let Some(Ok(message)) = stream.next().await {
+        if message.message_type() != MessageType::MethodCall {
+            continue;
+        }
+        let server = server.clone();
+        let upstream = upstream.clone();
+        let recorded = Arc::clone(&recorded);
+        // each call is forwarded in its own task, so a slow call does not block the rest
+        tokio::spawn(async move {
+            let result = match Call::from_message(&message) {
+                Ok(call) => match call.forward(&upstream).await {
+                    Ok(reply) => {
+                        let result = reply.send(&server, &message).await;
+                        recorded.lock().unwrap().push(Interaction { call, reply });
+                        result
+                    }
+                    Err(error) => server
+                        .reply_error(&message, FAILED_ERROR, &error.to_string())
+                        .await
+                        .map(|_| ()),
+                },
+                Err(error) => Err(error),
+            };
+            if let Err(error) = result {
+                log::warn!("Could not record a call: {}", error);
+            }
+        });
+    }
+}
+
+/// Returns the reply for the given call.
+///
+/// The reply is consumed unless it is the last one for that call.
+///
+/// * `interactions`: recorded interactions.
+/// * `call`: method call to reply to.
+fn take_reply(interactions: &mut Vec<Interaction>, call: &Call) -> Option<Reply> {
+    let mut matching = interactions
+        .iter()
+        .enumerate()
+        .filter(|(_, i)| &i.call == call)
+        .map(|(index, _)| index);
+    let first = matching.next()?;
+    if matching.next().is_some() {
+        Some(interactions.remove(first).reply)
+    } else {
+        Some(interactions[first].reply.clone())
+    }
+}
+
+/// Returns the signature, the (little-endian) encoded body and a description of the body of a
+/// message.
+///
+/// * `message`: message to take the body from.
+fn encode_body(message: &Message) -> zbus::Result<(String, String, String)> {
+    let signature = match message.body_signature() {
+        Ok(signature) => signature.to_string(),
+        Err(zbus::Error::NoBodySignature) => String::new(),
+        Err(error) => return Err(error),
+    };
+    if signature.is_empty() {
+        return Ok((signature, String::new(), "()".to_string()));
+    }
+    let body: Structure = message.body()?;
+    let bytes = zvariant::to_bytes(EncodingContext::<LE>::new_dbus(0), &body)?;
+    Ok((signature, to_hex(&bytes), body.to_string()))
+}
+
+/// Builds a message with the given body.
+///
+/// * `builder`: message builder.
+/// * `signature`: body signature.
+/// * `body`: little-endian encoded body, in hexadecimal.
+fn build_message(builder: MessageBuilder, signature: &str, body: &str) -> zbus::Result<Message> {
+    let bytes = if signature.is_empty() {
+        vec![]
+    } else {
+        let bytes = from_hex(body)?;
+        let seed = Structure::deserializer_for_signature(signature)?;
+        let body: Structure =
+            zvariant::from_slice_with_seed(&bytes, EncodingContext::<LE>::new_dbus(0), seed)?;
+        zvariant::to_bytes(EncodingContext::<NativeEndian>::new_dbus(0), &body)?
+    };
+    // SAFETY: the body was encoded using the given signature
+    unsafe { builder.build_raw_body(&bytes, signature, vec![]) }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> zbus::Result<Vec<u8>> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+                .ok_or_else(|| zbus::Error::Failure(format!("Invalid hexadecimal body: {}", hex)))
+        })
+        .collect()
+}
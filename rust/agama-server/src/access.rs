@@ -0,0 +1,267 @@
+//! Access control based on the credentials of the D-Bus peers.
+//!
+//! In environments where polkit is not available, it is possible to restrict the operations that
+//! modify the system to a set of users or groups. The credentials of the caller are obtained from
+//! the bus daemon (see the `GetConnectionCredentials` method of `org.freedesktop.DBus`).
+//!
+//! The methods check the policy by themselves. However, zbus does not pass the message header to
+//! the property setters, so the properties are protected by replacing the standard
+//! `org.freedesktop.DBus.Properties` interface (see [AccessPolicy::guard_properties]).
+use async_trait::async_trait;
+use std::{collections::HashMap, fs, io};
+use zbus::{
+    fdo::{self, DBusProxy},
+    names::{BusName, InterfaceName, MemberName},
+    zvariant::{OwnedValue, Value},
+    Connection, DispatchResult, Interface, Message, MessageHeader, ObjectServer, SignalContext,
+};
+
+/// Set of users and groups that are allowed to perform restricted operations.
+///
+/// An empty policy (the default one) does not impose any restriction.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessPolicy {
+    uids: Vec<u32>,
+    gids: Vec<u32>,
+}
+
+impl AccessPolicy {
+    /// Creates a new policy.
+    ///
+    /// * `uids`: allowed user IDs.
+    /// * `gids`: allowed group IDs.
+    pub fn new(uids: Vec<u32>, gids: Vec<u32>) -> Self {
+        Self { uids, gids }
+    }
+
+    /// Whether the policy allows any caller.
+    pub fn is_unrestricted(&self) -> bool {
+        self.uids.is_empty() && self.gids.is_empty()
+    }
+
+    /// Determines whether a caller with the given credentials is allowed.
+    ///
+    /// * `uid`: user ID of the caller.
+    /// * `gids`: group IDs of the caller.
+    pub fn is_allowed(&self, uid: u32, gids: &[u32]) -> bool {
+        self.is_unrestricted()
+            || self.uids.contains(&uid)
+            || gids.iter().any(|gid| self.gids.contains(gid))
+    }
+
+    /// Checks whether the sender of a message is allowed.
+    ///
+    /// It returns an `AccessDenied` error if the caller is not allowed.
+    ///
+    /// * `connection`: connection the message was received on.
+    /// * `header`: message header.
+    pub async fn check(
+        &self,
+        connection: &zbus::Connection,
+        header: &MessageHeader<'_>,
+    ) -> fdo::Result<()> {
+        if self.is_unrestricted() {
+            return Ok(());
+        }
+
+        let Some(sender) = header.sender()? else {
+            return Err(fdo::Error::AccessDenied("Unknown sender".to_string()));
+        };
+
+        let dbus = DBusProxy::new(connection).await?;
+        let credentials = dbus
+            .get_connection_credentials(BusName::from(sender.to_owned()))
+            .await?;
+        let Some(uid) = credentials.unix_user_id() else {
+            return Err(fdo::Error::AccessDenied(
+                "Could not determine the caller's user".to_string(),
+            ));
+        };
+        let gids = credentials.unix_group_ids().cloned().unwrap_or_default();
+
+        if self.is_allowed(uid, &gids) {
+            Ok(())
+        } else {
+            log::warn!("User {} is not allowed to perform this operation", uid);
+            Err(fdo::Error::AccessDenied(
+                "Not allowed to perform this operation".to_string(),
+            ))
+        }
+    }
+
+    /// Applies the policy to the properties of the objects on the given path.
+    ///
+    /// It replaces the standard `org.freedesktop.DBus.Properties` interface with one that checks
+    /// the caller before setting any property. It must be called after adding an interface to
+    /// the path, and it does nothing if the policy is unrestricted.
+    ///
+    /// * `connection`: connection the objects are exported on.
+    /// * `path`: object path.
+    pub async fn guard_properties(&self, connection: &Connection, path: &str) -> zbus::Result<()> {
+        if self.is_unrestricted() {
+            return Ok(());
+        }
+
+        let object_server = connection.object_server();
+        if object_server
+            .interface::<_, GuardedProperties>(path)
+            .await
+            .is_ok()
+        {
+            return Ok(());
+        }
+        object_server.remove::<fdo::Properties, _>(path).await?;
+        object_server
+            .at(path, GuardedProperties::new(self.clone()))
+            .await?;
+        Ok(())
+    }
+}
+
+/// Returns the group IDs of a process, including the supplementary ones.
+///
+/// It is meant for the callers which are not connected through D-Bus (e.g., the gRPC API), so
+/// they get the same groups than the bus daemon reports for the D-Bus ones.
+///
+/// * `pid`: process ID.
+/// * `gid`: primary group ID of the process.
+pub fn process_groups(pid: i32, gid: u32) -> io::Result<Vec<u32>> {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid))?;
+    let mut gids = parse_groups(&status);
+    if !gids.contains(&gid) {
+        gids.insert(0, gid);
+    }
+    Ok(gids)
+}
+
+/// Extracts the supplementary groups from the content of a `/proc/<pid>/status` file.
+fn parse_groups(status: &str) -> Vec<u32> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Groups:"))
+        .map(|groups| {
+            groups
+                .split_whitespace()
+                .filter_map(|gid| gid.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `org.freedesktop.DBus.Properties` implementation that applies an [AccessPolicy] when setting
+/// a property.
+///
+/// Reading the properties is not restricted, and the rest of the work is delegated to the
+/// standard implementation.
+struct GuardedProperties {
+    inner: fdo::Properties,
+    access: AccessPolicy,
+}
+
+impl GuardedProperties {
+    fn new(access: AccessPolicy) -> Self {
+        Self {
+            inner: fdo::Properties,
+            access,
+        }
+    }
+}
+
+#[async_trait]
+impl Interface for GuardedProperties {
+    fn name() -> InterfaceName<'static> {
+        fdo::Properties::name()
+    }
+
+    async fn get(&self, property_name: &str) -> Option<fdo::Result<OwnedValue>> {
+        self.inner.get(property_name).await
+    }
+
+    async fn get_all(&self) -> HashMap<String, OwnedValue> {
+        self.inner.get_all().await
+    }
+
+    async fn set_mut(
+        &mut self,
+        property_name: &str,
+        value: &Value<'_>,
+        ctxt: &SignalContext<'_>,
+    ) -> Option<fdo::Result<()>> {
+        self.inner.set_mut(property_name, value, ctxt).await
+    }
+
+    fn call<'call>(
+        &'call self,
+        server: &'call ObjectServer,
+        connection: &'call Connection,
+        msg: &'call Message,
+        name: MemberName<'call>,
+    ) -> DispatchResult<'call> {
+        if name.as_str() != "Set" {
+            return self.inner.call(server, connection, msg, name);
+        }
+
+        // the future does nothing until it is polled, so it is safe to create it before checking
+        // the caller; any other result (e.g., NotFound) is forwarded unchanged
+        match self.inner.call(server, connection, msg, name) {
+            DispatchResult::Async(future) => DispatchResult::Async(Box::pin(async move {
+                let header = msg.header()?;
+                if let Err(error) = self.access.check(connection, &header).await {
+                    return connection
+                        .reply_dbus_error(&header, error)
+                        .await
+                        .map(|_| ());
+                }
+                future.await
+            })),
+            result => result,
+        }
+    }
+
+    fn call_mut<'call>(
+        &'call mut self,
+        server: &'call ObjectServer,
+        connection: &'call Connection,
+        msg: &'call Message,
+        name: MemberName<'call>,
+    ) -> DispatchResult<'call> {
+        self.inner.call_mut(server, connection, msg, name)
+    }
+
+    fn introspect_to_writer(&self, writer: &mut dyn std::fmt::Write, level: usize) {
+        self.inner.introspect_to_writer(writer, level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unrestricted_policy() {
+        let policy = AccessPolicy::default();
+        assert!(policy.is_unrestricted());
+        assert!(policy.is_allowed(1000, &[100]));
+    }
+
+    #[test]
+    fn test_is_allowed() {
+        let policy = AccessPolicy::new(vec![0], vec![484]);
+        assert!(policy.is_allowed(0, &[]));
+        assert!(policy.is_allowed(1000, &[100, 484]));
+        assert!(!policy.is_allowed(1000, &[100]));
+    }
+
+    #[test]
+    fn test_parse_groups() {
+        let status = "Name:\tbash\nGid:\t100\t100\t100\t100\nGroups:\t100 484 \nNgid:\t0\n";
+        assert_eq!(parse_groups(status), vec![100, 484]);
+        assert_eq!(parse_groups("Name:\tbash\n"), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_process_groups() {
+        let gids = process_groups(std::process::id() as i32, 12345).unwrap();
+        assert!(gids.contains(&12345));
+    }
+}
@@ -1,18 +1,65 @@
 use agama_server::{
+    access::AccessPolicy,
+    clock, introspection,
     l10n::{self, helpers},
-    network, questions,
+    manager, network, questions, s390,
 };
 
 use agama_lib::connection_to;
 use anyhow::Context;
+use clap::{Parser, ValueEnum};
+use libsystemd::daemon::{self, NotifyState};
 use log::{self, LevelFilter};
 use std::future::pending;
 
 const ADDRESS: &str = "unix:path=/run/agama/bus";
 const SERVICE_NAME: &str = "org.opensuse.Agama1";
+const OBJECT_PATH: &str = "/org/opensuse/Agama1";
+
+/// D-Bus bus to serve the API on.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Bus {
+    /// Agama's private bus.
+    Agama,
+    /// Session bus (useful for testing).
+    Session,
+    /// System bus.
+    System,
+}
+
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    about = "Starts the Agama D-Bus services.",
+    long_about = None)]
+struct Cli {
+    /// Bus to serve the API on.
+    #[arg(long, value_enum, default_value_t = Bus::Agama, env = "AGAMA_BUS")]
+    bus: Bus,
+    /// Custom D-Bus address to serve the API on (e.g., "unix:path=/tmp/agama/bus"). It takes
+    /// precedence over the `--bus` option.
+    #[arg(long, env = "AGAMA_BUS_ADDRESS")]
+    address: Option<String>,
+    /// User ID allowed to modify the system (e.g., the network configuration). It can be
+    /// repeated. If neither users nor groups are given, any caller is allowed.
+    #[arg(long = "allow-uid", value_name = "UID")]
+    allowed_uids: Vec<u32>,
+    /// Group ID allowed to modify the system. It can be repeated.
+    #[arg(long = "allow-gid", value_name = "GID")]
+    allowed_gids: Vec<u32>,
+    /// Unix socket to serve the gRPC API on (e.g., "/run/agama/grpc.sock"). If not given, the
+    /// gRPC API is not served.
+    #[cfg(feature = "grpc")]
+    #[arg(long, env = "AGAMA_GRPC_SOCKET", value_name = "PATH")]
+    grpc_socket: Option<std::path::PathBuf>,
+    /// Prints a JSON description of the exported interfaces and exits.
+    #[arg(long)]
+    schema: bool,
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
     let locale = helpers::init_locale()?;
 
     // be smart with logging and log directly to journal if connected to it
@@ -32,7 +79,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .unwrap(); // unwrap here as we are sure no other logger active
     }
 
-    let connection = connection_to(ADDRESS)
+    let connection = connect(cli.bus, cli.address.as_deref())
         .await
         .expect("Could not connect to the D-Bus daemon");
 
@@ -41,16 +88,82 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     log::info!("Started questions interface");
     l10n::export_dbus_objects(&connection, &locale).await?;
     log::info!("Started locale interface");
-    network::export_dbus_objects(&connection).await?;
+    let access = AccessPolicy::new(cli.allowed_uids, cli.allowed_gids);
+    if !access.is_unrestricted() {
+        log::info!("Restricting the access according to {:?}", access);
+    }
+    s390::export_dbus_objects(&connection, access.clone()).await?;
+    network::export_dbus_objects(&connection, access.clone()).await?;
     log::info!("Started network interface");
+    clock::export_dbus_objects(&connection, access.clone()).await?;
+    log::info!("Started clock interface");
+    manager::export_dbus_objects(&connection, access.clone()).await?;
+    log::info!("Started manager interface");
 
     connection
         .request_name(SERVICE_NAME)
         .await
         .context(format!("Requesting name {SERVICE_NAME}"))?;
 
+    if cli.schema {
+        let schema =
+            introspection::schema(&connection, SERVICE_NAME, OBJECT_PATH, SERVICE_NAME).await?;
+        println!("{}", serde_json::to_string_pretty(&schema)?);
+        return Ok(());
+    }
+
+    #[cfg(feature = "grpc")]
+    if let Some(path) = cli.grpc_socket {
+        let client_connection = connect(cli.bus, cli.address.as_deref()).await?;
+        tokio::spawn(async move {
+            if let Err(error) = agama_server::grpc::serve(client_connection, &path, access).await {
+                log::error!("Could not serve the gRPC API: {}", error);
+            }
+        });
+    }
+
+    notify_systemd();
+
     // Do other things or go to wait forever
     pending::<()>().await;
 
     Ok(())
 }
+
+/// Tells systemd that the service is ready and, if the watchdog is enabled, keeps it updated.
+///
+/// It does nothing if the service was not started by systemd.
+fn notify_systemd() {
+    if let Err(error) = daemon::notify(false, &[NotifyState::Ready]) {
+        log::warn!("Could not notify systemd: {}", error);
+        return;
+    }
+
+    let Some(timeout) = daemon::watchdog_enabled(false) else {
+        return;
+    };
+    log::info!("Enabling the systemd watchdog (timeout: {:?})", timeout);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(timeout / 2);
+        loop {
+            interval.tick().await;
+            if let Err(error) = daemon::notify(false, &[NotifyState::Watchdog]) {
+                log::warn!("Could not notify the systemd watchdog: {}", error);
+            }
+        }
+    });
+}
+
+/// Connects to the bus to serve the API on.
+///
+/// * `bus`: well-known bus to connect to.
+/// * `address`: custom address. If given, `bus` is ignored.
+async fn connect(bus: Bus, address: Option<&str>) -> anyhow::Result<zbus::Connection> {
+    let connection = match (address, bus) {
+        (Some(address), _) => connection_to(address).await?,
+        (None, Bus::Agama) => connection_to(ADDRESS).await?,
+        (None, Bus::Session) => zbus::Connection::session().await?,
+        (None, Bus::System) => zbus::Connection::system().await?,
+    };
+    Ok(connection)
+}
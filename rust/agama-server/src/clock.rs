@@ -0,0 +1,57 @@
+//! Clock sanity checks
+//!
+//! A wrong system clock breaks the TLS connections to the repositories (e.g., the certificates
+//! look expired or not valid yet) and the checks of the repositories metadata signatures. The
+//! problem is usually hard to spot, as it shows up as a generic download error.
+//!
+//! This module exposes the `/org/opensuse/Agama1/Clock` object, which implements:
+//!
+//! * The `org.opensuse.Agama1.Clock` interface, which compares the system clock with a time
+//!   reference (an NTP server or, as a fallback, the `Date` header of an HTTP server) and,
+//!   optionally, corrects it.
+//! * The `org.opensuse.Agama1.Issues` interface, which reports an error when the difference between
+//!   the system clock and the reference is too big (see [MAX_SKEW]).
+//!
+//! The check runs each time NetworkManager reports full connectivity, so there is no need to
+//! call it explicitly.
+
+mod check;
+mod dbus;
+
+pub use check::{ClockSample, ClockSettings, MAX_SKEW};
+pub use dbus::{Clock, ClockIssues};
+
+use crate::access::AccessPolicy;
+use thiserror::Error;
+use zbus::Connection;
+
+/// Errors related to the clock check.
+#[derive(Error, Debug)]
+pub enum ClockError {
+    #[error("Could not reach '{0}': {1}")]
+    Unreachable(String, std::io::Error),
+    #[error("'{0}' did not answer in time")]
+    Timeout(String),
+    #[error("Invalid answer from '{0}': {1}")]
+    InvalidResponse(String, String),
+    #[error("Could not set the system clock: {0}")]
+    NotCorrected(String),
+}
+
+impl From<ClockError> for zbus::fdo::Error {
+    fn from(value: ClockError) -> zbus::fdo::Error {
+        zbus::fdo::Error::Failed(format!("Clock error: {value}"))
+    }
+}
+
+/// Exports the clock D-Bus objects and starts checking the clock when the network is up.
+///
+/// * `connection`: D-Bus connection to use.
+/// * `access`: policy to restrict the operations that modify the system.
+pub async fn export_dbus_objects(
+    connection: &Connection,
+    access: AccessPolicy,
+) -> Result<(), Box<dyn std::error::Error>> {
+    dbus::export(connection, access).await?;
+    Ok(())
+}
@@ -0,0 +1,259 @@
+//! Comparison of the system clock with a time reference.
+//!
+//! The reference time is read using SNTP (RFC 4330) or, if the NTP server cannot be reached (e.g.,
+//! UDP traffic is filtered), from the `Date` header of an HTTP response. The network delay is not
+//! taken into account: a precision of a few seconds is more than enough to detect a clock that
+//! breaks TLS.
+use super::ClockError;
+use chrono::{DateTime, Utc};
+use std::{process::Command, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpStream, UdpSocket},
+    time::timeout,
+};
+
+/// Maximum difference (in seconds) between the system clock and the reference.
+///
+/// Certificates and signatures are usually created some time before they are used, so a few
+/// minutes of difference are harmless. However, a clock which is way behind makes new
+/// certificates look not valid yet.
+pub const MAX_SKEW: i64 = 300;
+
+/// Seconds between the NTP epoch (1900) and the UNIX epoch (1970).
+const NTP_UNIX_OFFSET: i64 = 2_208_988_800;
+const NTP_PACKET_SIZE: usize = 48;
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time references to check the clock against.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClockSettings {
+    /// NTP server (e.g., "pool.ntp.org").
+    pub ntp_server: String,
+    /// HTTP server to read the `Date` header from (e.g., "download.opensuse.org").
+    pub http_server: String,
+    /// Whether to correct the system clock when the skew is too big.
+    pub auto_correct: bool,
+}
+
+impl Default for ClockSettings {
+    fn default() -> Self {
+        Self {
+            ntp_server: "pool.ntp.org".to_string(),
+            http_server: "download.opensuse.org".to_string(),
+            auto_correct: false,
+        }
+    }
+}
+
+/// Result of comparing the system clock with a reference.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClockSample {
+    /// Server used as reference.
+    pub reference: String,
+    /// Time according to the reference.
+    pub time: DateTime<Utc>,
+    /// Difference (in seconds) between the system clock and the reference. It is positive when
+    /// the system clock is ahead.
+    pub skew: i64,
+}
+
+impl ClockSample {
+    fn new(reference: &str, time: DateTime<Utc>) -> Self {
+        Self {
+            reference: reference.to_string(),
+            time,
+            skew: (Utc::now() - time).num_seconds(),
+        }
+    }
+
+    /// Whether the difference is big enough to cause problems.
+    pub fn is_skewed(&self) -> bool {
+        self.skew.abs() > MAX_SKEW
+    }
+}
+
+/// Compares the system clock with the NTP server, falling back to the HTTP server.
+///
+/// * `settings`: time references.
+pub async fn check(settings: &ClockSettings) -> Result<ClockSample, ClockError> {
+    match ntp_time(&settings.ntp_server).await {
+        Ok(time) => Ok(ClockSample::new(&settings.ntp_server, time)),
+        Err(error) => {
+            log::warn!("{}. Trying with {}.", error, settings.http_server);
+            let time = http_time(&settings.http_server).await?;
+            Ok(ClockSample::new(&settings.http_server, time))
+        }
+    }
+}
+
+/// Sets the system clock (and the hardware clock) to the given time.
+///
+/// * `time`: time to set.
+pub fn set_system_clock(time: DateTime<Utc>) -> Result<(), ClockError> {
+    let output = Command::new("date")
+        .args(["--utc", "--set", &format!("@{}", time.timestamp())])
+        .output()
+        .map_err(|e| ClockError::NotCorrected(e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(ClockError::NotCorrected(stderr));
+    }
+
+    // not all the systems have a hardware clock, so the error is ignored
+    if let Err(error) = Command::new("hwclock")
+        .args(["--systohc", "--utc"])
+        .status()
+    {
+        log::warn!("Could not update the hardware clock: {}", error);
+    }
+    Ok(())
+}
+
+/// Reads the time from an NTP server.
+///
+/// * `server`: server name or address.
+async fn ntp_time(server: &str) -> Result<DateTime<Utc>, ClockError> {
+    let unreachable = |e| ClockError::Unreachable(server.to_string(), e);
+    let socket = UdpSocket::bind("0.0.0.0:0").await.map_err(unreachable)?;
+    socket.connect((server, 123)).await.map_err(unreachable)?;
+
+    // LI = 0 (no warning), VN = 4 (version 4), Mode = 3 (client)
+    let mut request = [0u8; NTP_PACKET_SIZE];
+    request[0] = 0b00_100_011;
+    // a random transmit timestamp makes it harder to spoof the response
+    let transmit: [u8; 8] = rand::random();
+    request[40..48].copy_from_slice(&transmit);
+    socket.send(&request).await.map_err(unreachable)?;
+
+    let mut response = [0u8; NTP_PACKET_SIZE];
+    let size = timeout(TIMEOUT, socket.recv(&mut response))
+        .await
+        .map_err(|_| ClockError::Timeout(server.to_string()))?
+        .map_err(unreachable)?;
+    time_from_ntp_packet(&response[..size], &transmit)
+        .map_err(|e| ClockError::InvalidResponse(server.to_string(), e))
+}
+
+/// Reads the time from the `Date` header of an HTTP server.
+///
+/// Plain HTTP is used on purpose: a TLS handshake might fail precisely because of the clock.
+///
+/// * `server`: server name or address.
+async fn http_time(server: &str) -> Result<DateTime<Utc>, ClockError> {
+    let unreachable = |e| ClockError::Unreachable(server.to_string(), e);
+    let request = format!(
+        "HEAD / HTTP/1.1\r\nHost: {}\r\nUser-Agent: agama\r\nConnection: close\r\n\r\n",
+        server
+    );
+
+    let response = timeout(TIMEOUT, async {
+        let mut stream = TcpStream::connect((server, 80)).await?;
+        stream.write_all(request.as_bytes()).await?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await?;
+        Ok(response)
+    })
+    .await
+    .map_err(|_| ClockError::Timeout(server.to_string()))?
+    .map_err(unreachable)?;
+
+    time_from_http_response(&response)
+        .map_err(|e| ClockError::InvalidResponse(server.to_string(), e))
+}
+
+/// Extracts the transmit timestamp from an NTP response.
+///
+/// * `packet`: NTP packet.
+/// * `transmit`: transmit timestamp of the request, which the server copies to the originate
+///   timestamp of the response.
+fn time_from_ntp_packet(packet: &[u8], transmit: &[u8; 8]) -> Result<DateTime<Utc>, String> {
+    if packet.len() < NTP_PACKET_SIZE {
+        return Err(format!("packet too short ({} bytes)", packet.len()));
+    }
+    // Mode = 4 (server)
+    if packet[0] & 0b111 != 4 {
+        return Err("not a server response".to_string());
+    }
+    if &packet[24..32] != transmit {
+        return Err("the response does not match the request".to_string());
+    }
+    // stratum 0 means a "kiss-o'-death" packet (e.g., rate limiting)
+    if packet[1] == 0 {
+        return Err("the server refused the request".to_string());
+    }
+
+    let seconds = u32::from_be_bytes([packet[40], packet[41], packet[42], packet[43]]);
+    let fraction = u32::from_be_bytes([packet[44], packet[45], packet[46], packet[47]]);
+    let nanos = ((fraction as u64 * 1_000_000_000) >> 32) as u32;
+    DateTime::from_timestamp(seconds as i64 - NTP_UNIX_OFFSET, nanos)
+        .ok_or_else(|| "invalid timestamp".to_string())
+}
+
+/// Extracts the `Date` header from an HTTP response.
+///
+/// * `response`: HTTP response (only the headers are needed).
+fn time_from_http_response(response: &str) -> Result<DateTime<Utc>, String> {
+    let date = response
+        .lines()
+        .take_while(|l| !l.is_empty())
+        .filter_map(|l| l.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("date"))
+        .map(|(_, value)| value.trim())
+        .ok_or_else(|| "missing Date header".to_string())?;
+    DateTime::parse_from_rfc2822(date)
+        .map(|d| d.with_timezone(&Utc))
+        .map_err(|e| format!("invalid date '{}': {}", date, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_from_ntp_packet() {
+        let transmit = [1, 2, 3, 4, 5, 6, 7, 8];
+        let mut packet = [0u8; NTP_PACKET_SIZE];
+        packet[0] = 0b00_100_100;
+        packet[1] = 2;
+        packet[24..32].copy_from_slice(&transmit);
+        // 2024-03-01T12:00:00.5Z
+        let seconds = (1_709_294_400 + NTP_UNIX_OFFSET) as u32;
+        packet[40..44].copy_from_slice(&seconds.to_be_bytes());
+        packet[44..48].copy_from_slice(&(1u32 << 31).to_be_bytes());
+
+        let time = time_from_ntp_packet(&packet, &transmit).unwrap();
+        assert_eq!(time.to_rfc3339(), "2024-03-01T12:00:00.500+00:00");
+
+        // the originate timestamp does not match the request
+        assert!(time_from_ntp_packet(&packet, &[0; 8]).is_err());
+
+        packet[1] = 0;
+        assert!(time_from_ntp_packet(&packet, &transmit).is_err());
+        assert!(time_from_ntp_packet(&packet[..12], &transmit).is_err());
+    }
+
+    #[test]
+    fn test_time_from_http_response() {
+        let response = "HTTP/1.1 301 Moved Permanently\r\n\
+            Server: nginx\r\n\
+            date: Fri, 01 Mar 2024 12:00:00 GMT\r\n\
+            Connection: close\r\n\r\n";
+        let time = time_from_http_response(response).unwrap();
+        assert_eq!(time.to_rfc3339(), "2024-03-01T12:00:00+00:00");
+
+        let response = "HTTP/1.1 200 OK\r\nServer: nginx\r\n\r\n";
+        assert!(time_from_http_response(response).is_err());
+    }
+
+    #[test]
+    fn test_is_skewed() {
+        let time = Utc::now() - chrono::Duration::try_hours(2).unwrap();
+        let sample = ClockSample::new("pool.ntp.org", time);
+        assert!(sample.is_skewed());
+        assert!(sample.skew >= 7200);
+
+        let sample = ClockSample::new("pool.ntp.org", Utc::now());
+        assert!(!sample.is_skewed());
+    }
+}
@@ -0,0 +1,292 @@
+//! D-Bus interfaces for the clock check.
+use super::{
+    check::{self, ClockSample, ClockSettings},
+    ClockError,
+};
+use crate::access::AccessPolicy;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio_stream::StreamExt;
+use zbus::{dbus_interface, dbus_proxy, Connection, MessageHeader};
+
+const PATH: &str = "/org/opensuse/Agama1/Clock";
+/// NetworkManager state when the system has global connectivity (`NM_STATE_CONNECTED_GLOBAL`).
+const NM_STATE_CONNECTED_GLOBAL: u32 = 70;
+/// Source of the issues (see the `org.opensuse.Agama1.Issues` interface).
+const SYSTEM_SOURCE: u32 = 1;
+/// Severity of the issues (see the `org.opensuse.Agama1.Issues` interface).
+const ERROR_SEVERITY: u32 = 1;
+
+#[dbus_proxy(
+    interface = "org.freedesktop.NetworkManager",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager",
+    gen_blocking = false
+)]
+trait NetworkManager {
+    /// State property
+    #[dbus_proxy(property)]
+    fn state(&self) -> zbus::Result<u32>;
+}
+
+/// State shared by the clock interfaces.
+#[derive(Debug, Default)]
+struct ClockState {
+    settings: ClockSettings,
+    /// Result of the last successful check.
+    sample: Option<ClockSample>,
+}
+
+/// D-Bus interface to check (and correct) the system clock.
+pub struct Clock {
+    access: AccessPolicy,
+    state: Arc<Mutex<ClockState>>,
+}
+
+impl Clock {
+    fn new(access: AccessPolicy, state: Arc<Mutex<ClockState>>) -> Self {
+        Self { access, state }
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Clock")]
+impl Clock {
+    /// Difference (in seconds) between the system clock and the reference, as found in the last
+    /// check. It is positive when the system clock is ahead.
+    #[dbus_interface(property)]
+    async fn skew(&self) -> i64 {
+        let state = self.state.lock().await;
+        state.sample.as_ref().map(|s| s.skew).unwrap_or_default()
+    }
+
+    /// Server used as reference in the last check. It is empty if the clock was not checked yet
+    /// or the references could not be reached.
+    #[dbus_interface(property)]
+    async fn reference(&self) -> String {
+        let state = self.state.lock().await;
+        state
+            .sample
+            .as_ref()
+            .map(|s| s.reference.clone())
+            .unwrap_or_default()
+    }
+
+    /// NTP server to check the clock against.
+    #[dbus_interface(property)]
+    async fn ntp_server(&self) -> String {
+        self.state.lock().await.settings.ntp_server.clone()
+    }
+
+    #[dbus_interface(property)]
+    async fn set_ntp_server(&mut self, server: String) {
+        self.state.lock().await.settings.ntp_server = server;
+    }
+
+    /// HTTP server to use when the NTP server cannot be reached.
+    #[dbus_interface(property)]
+    async fn http_server(&self) -> String {
+        self.state.lock().await.settings.http_server.clone()
+    }
+
+    #[dbus_interface(property)]
+    async fn set_http_server(&mut self, server: String) {
+        self.state.lock().await.settings.http_server = server;
+    }
+
+    /// Whether to correct the system clock when the check finds a big difference.
+    #[dbus_interface(property)]
+    async fn auto_correct(&self) -> bool {
+        self.state.lock().await.settings.auto_correct
+    }
+
+    #[dbus_interface(property)]
+    async fn set_auto_correct(&mut self, enabled: bool) {
+        self.state.lock().await.settings.auto_correct = enabled;
+    }
+
+    /// Compares the system clock with the reference.
+    ///
+    /// The clock is corrected if the difference is too big and `AutoCorrect` is enabled.
+    async fn check(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<()> {
+        let auto_correct = self.state.lock().await.settings.auto_correct;
+        if auto_correct {
+            self.access.check(connection, &header).await?;
+        }
+        run_check(connection, &self.state, false).await?;
+        Ok(())
+    }
+
+    /// Sets the system clock to the time of the reference.
+    async fn correct(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        run_check(connection, &self.state, true).await?;
+        Ok(())
+    }
+}
+
+/// D-Bus interface to report a wrong system clock.
+pub struct ClockIssues {
+    state: Arc<Mutex<ClockState>>,
+}
+
+impl ClockIssues {
+    fn new(state: Arc<Mutex<ClockState>>) -> Self {
+        Self { state }
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Issues")]
+impl ClockIssues {
+    /// Issues found in the last check.
+    ///
+    /// Each issue contains the description, the details (the reference), the source (1 for
+    /// system) and the severity (1 for error).
+    #[dbus_interface(property)]
+    async fn all(&self) -> Vec<(String, String, u32, u32)> {
+        let state = self.state.lock().await;
+        let Some(sample) = state.sample.as_ref().filter(|s| s.is_skewed()) else {
+            return vec![];
+        };
+        let description = format!(
+            "The system clock is {} minutes {}, so the secure connections to the repositories might fail",
+            sample.skew.abs() / 60,
+            if sample.skew > 0 { "ahead" } else { "behind" }
+        );
+        vec![(
+            description,
+            sample.reference.clone(),
+            SYSTEM_SOURCE,
+            ERROR_SEVERITY,
+        )]
+    }
+}
+
+/// Checks the clock, correcting it if needed, and notifies the changes.
+///
+/// * `connection`: D-Bus connection where the interfaces are exported.
+/// * `state`: clock state.
+/// * `correct`: whether to correct the clock regardless of the `auto_correct` setting.
+async fn run_check(
+    connection: &Connection,
+    state: &Mutex<ClockState>,
+    correct: bool,
+) -> Result<(), ClockError> {
+    let settings = state.lock().await.settings.clone();
+    let result = match check::check(&settings).await {
+        Ok(sample) if correct || (settings.auto_correct && sample.is_skewed()) => {
+            log::info!(
+                "Setting the system clock to {} ({} seconds off)",
+                sample.time,
+                sample.skew
+            );
+            check::set_system_clock(sample.time)?;
+            check::check(&settings).await
+        }
+        result => result,
+    };
+
+    let error = {
+        let mut state = state.lock().await;
+        match result {
+            Ok(sample) => {
+                if sample.is_skewed() {
+                    log::warn!(
+                        "The system clock is {} seconds off according to {}",
+                        sample.skew,
+                        sample.reference
+                    );
+                }
+                state.sample = Some(sample);
+                None
+            }
+            Err(error) => {
+                state.sample = None;
+                Some(error)
+            }
+        }
+    };
+
+    if let Err(error) = notify_changes(connection).await {
+        log::warn!("Could not notify the clock changes: {}", error);
+    }
+    error.map_or(Ok(()), Err)
+}
+
+/// Emits the signals for the properties which depend on the last check.
+///
+/// * `connection`: D-Bus connection where the interfaces are exported.
+async fn notify_changes(connection: &Connection) -> zbus::Result<()> {
+    let clock_ref = connection
+        .object_server()
+        .interface::<_, Clock>(PATH)
+        .await?;
+    let clock = clock_ref.get().await;
+    clock.skew_changed(clock_ref.signal_context()).await?;
+    clock.reference_changed(clock_ref.signal_context()).await?;
+
+    let issues_ref = connection
+        .object_server()
+        .interface::<_, ClockIssues>(PATH)
+        .await?;
+    let issues = issues_ref.get().await;
+    issues.all_changed(issues_ref.signal_context()).await
+}
+
+/// Checks the clock each time NetworkManager reports global connectivity.
+///
+/// * `connection`: D-Bus connection where the interfaces are exported.
+/// * `state`: clock state.
+async fn watch_network(connection: &Connection, state: Arc<Mutex<ClockState>>) -> zbus::Result<()> {
+    let system = Connection::system().await?;
+    let proxy = NetworkManagerProxy::new(&system).await?;
+    let mut changes = proxy.receive_state_changed().await;
+    let mut online = false;
+    let mut nm_state = proxy.state().await?;
+    loop {
+        let was_online = online;
+        online = nm_state == NM_STATE_CONNECTED_GLOBAL;
+        if online && !was_online {
+            if let Err(error) = run_check(connection, &state, false).await {
+                log::warn!("Could not check the system clock: {}", error);
+            }
+        }
+
+        let Some(change) = changes.next().await else {
+            return Ok(());
+        };
+        nm_state = change.get().await?;
+    }
+}
+
+/// Exports the clock object and starts watching the network state.
+///
+/// * `connection`: D-Bus connection to use.
+/// * `access`: policy to restrict the operations that modify the system.
+pub async fn export(connection: &Connection, access: AccessPolicy) -> zbus::Result<()> {
+    let state = Arc::new(Mutex::new(ClockState::default()));
+    let object_server = connection.object_server();
+    object_server
+        .at(PATH, Clock::new(access.clone(), Arc::clone(&state)))
+        .await?;
+    object_server
+        .at(PATH, ClockIssues::new(Arc::clone(&state)))
+        .await?;
+    // the setters cannot check the caller, so the policy is applied to the properties interface
+    access.guard_properties(connection, PATH).await?;
+
+    let connection = connection.clone();
+    tokio::spawn(async move {
+        if let Err(error) = watch_network(&connection, state).await {
+            log::warn!("Could not watch the network state: {}", error);
+        }
+    });
+    Ok(())
+}
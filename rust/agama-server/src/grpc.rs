@@ -0,0 +1,368 @@
+//! gRPC façade for the Agama API.
+//!
+//! It exposes the main operations of the D-Bus API (see `proto/agama.proto`), relying on the
+//! agama-lib clients. It is only available when the `grpc` feature is enabled.
+//!
+//! The API is served on a Unix socket and every call is checked against the
+//! [AccessPolicy] using the credentials of the peer. If the policy is unrestricted, only the user
+//! running the service is allowed.
+use crate::access::{self, AccessPolicy};
+use agama_lib::{
+    error::ServiceError, install_settings::InstallSettings, manager::ManagerClient,
+    network::NetworkClient, overview::OverviewClient, product::ProductClient, secrets,
+    software::SoftwareClient, storage::StorageClient, Store,
+};
+use futures_util::stream;
+use std::{
+    fs, io,
+    os::unix::fs::{MetadataExt, PermissionsExt},
+    path::Path,
+};
+use tokio::net::UnixListener;
+use tonic::{
+    transport::{server::UdsConnectInfo, Server},
+    Request, Response, Status,
+};
+
+pub mod proto {
+    tonic::include_proto!("agama.v1");
+}
+
+use proto::{
+    agama_server::{Agama, AgamaServer},
+    BusyServices, Config, Empty, Flag, Id, Ids, Json, Pattern, Patterns, Product, Products,
+    Progress, Registration,
+};
+
+/// gRPC service which forwards the requests to the D-Bus services.
+pub struct AgamaService {
+    connection: zbus::Connection,
+    access: AccessPolicy,
+    owner: u32,
+}
+
+impl AgamaService {
+    /// Creates a new service.
+    ///
+    /// * `connection`: connection to Agama's D-Bus API.
+    /// * `access`: policy to restrict the callers.
+    /// * `owner`: user ID which is allowed when the policy is unrestricted.
+    pub fn new(connection: zbus::Connection, access: AccessPolicy, owner: u32) -> Self {
+        Self {
+            connection,
+            access,
+            owner,
+        }
+    }
+
+    /// Checks whether the peer which sent the request is allowed to use the API.
+    ///
+    /// * `request`: gRPC request.
+    #[allow(clippy::result_large_err)] // tonic methods return a Status anyway
+    fn authorize<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let Some(credentials) = request
+            .extensions()
+            .get::<UdsConnectInfo>()
+            .and_then(|info| info.peer_cred)
+        else {
+            return Err(Status::unauthenticated("Unknown peer"));
+        };
+
+        let uid = credentials.uid();
+        let allowed = if self.access.is_unrestricted() {
+            uid == self.owner
+        } else {
+            // the peer credentials only include the primary group
+            let gids = credentials
+                .pid()
+                .and_then(|pid| access::process_groups(pid, credentials.gid()).ok())
+                .unwrap_or_else(|| vec![credentials.gid()]);
+            self.access.is_allowed(uid, &gids)
+        };
+
+        if allowed {
+            Ok(())
+        } else {
+            log::warn!("User {} is not allowed to use the gRPC API", uid);
+            Err(Status::permission_denied(
+                "Not allowed to perform this operation",
+            ))
+        }
+    }
+
+    async fn manager(&self) -> Result<ManagerClient<'static>, Status> {
+        ManagerClient::new(self.connection.clone())
+            .await
+            .map_err(|e| to_status(e.into()))
+    }
+
+    async fn store(&self) -> Result<Store<'static>, Status> {
+        Store::new(self.connection.clone()).await.map_err(to_status)
+    }
+
+    async fn product(&self) -> Result<ProductClient<'static>, Status> {
+        ProductClient::new(self.connection.clone())
+            .await
+            .map_err(to_status)
+    }
+
+    async fn software(&self) -> Result<SoftwareClient<'static>, Status> {
+        SoftwareClient::new(self.connection.clone())
+            .await
+            .map_err(to_status)
+    }
+
+    async fn network(&self) -> Result<NetworkClient<'static>, Status> {
+        NetworkClient::new(self.connection.clone())
+            .await
+            .map_err(to_status)
+    }
+}
+
+#[tonic::async_trait]
+impl Agama for AgamaService {
+    async fn get_config(&self, request: Request<Empty>) -> Result<Response<Config>, Status> {
+        self.authorize(&request)?;
+        let settings = self.store().await?.load(None).await.map_err(to_status)?;
+        let mut value =
+            serde_json::to_value(settings).map_err(|e| Status::internal(e.to_string()))?;
+        secrets::redact(&mut value);
+        Ok(Response::new(Config {
+            json: value.to_string(),
+        }))
+    }
+
+    async fn set_config(&self, request: Request<Config>) -> Result<Response<Empty>, Status> {
+        self.authorize(&request)?;
+        let settings: InstallSettings = serde_json::from_str(&request.into_inner().json)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        self.store()
+            .await?
+            .store(&settings)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn probe(&self, request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        self.authorize(&request)?;
+        self.manager().await?.probe().await.map_err(to_status)?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn install(&self, request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        self.authorize(&request)?;
+        self.manager().await?.install().await.map_err(to_status)?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn can_install(&self, request: Request<Empty>) -> Result<Response<Flag>, Status> {
+        self.authorize(&request)?;
+        let value = self
+            .manager()
+            .await?
+            .can_install()
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(Flag { value }))
+    }
+
+    async fn get_progress(&self, request: Request<Empty>) -> Result<Response<Progress>, Status> {
+        self.authorize(&request)?;
+        let progress = self
+            .manager()
+            .await?
+            .progress()
+            .await
+            .map_err(|e| to_status(e.into()))?;
+        Ok(Response::new(Progress {
+            current_step: progress.current_step,
+            max_steps: progress.max_steps,
+            current_title: progress.current_title,
+            current_percentage: progress.current_percentage.map(u32::from),
+            finished: progress.finished,
+        }))
+    }
+
+    async fn get_busy_services(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<BusyServices>, Status> {
+        self.authorize(&request)?;
+        let names = self
+            .manager()
+            .await?
+            .busy_services()
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(BusyServices { names }))
+    }
+
+    async fn get_overview(&self, request: Request<Empty>) -> Result<Response<Json>, Status> {
+        self.authorize(&request)?;
+        let client = OverviewClient::new(self.connection.clone())
+            .await
+            .map_err(|e| to_status(e.into()))?;
+        let overview = client.overview().await.map_err(to_status)?;
+        let json = serde_json::to_string(&overview).map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(Json { json }))
+    }
+
+    async fn get_products(&self, request: Request<Empty>) -> Result<Response<Products>, Status> {
+        self.authorize(&request)?;
+        let client = self.product().await?;
+        let selected = client.product().await.map_err(to_status)?;
+        let products = client
+            .products()
+            .await
+            .map_err(to_status)?
+            .into_iter()
+            .map(|p| Product {
+                id: p.id,
+                name: p.name,
+                description: p.description,
+            })
+            .collect();
+        Ok(Response::new(Products { products, selected }))
+    }
+
+    async fn select_product(&self, request: Request<Id>) -> Result<Response<Empty>, Status> {
+        self.authorize(&request)?;
+        let id = request.into_inner().id;
+        self.product()
+            .await?
+            .select_product(&id)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn register_product(
+        &self,
+        request: Request<Registration>,
+    ) -> Result<Response<Empty>, Status> {
+        self.authorize(&request)?;
+        let registration = request.into_inner();
+        let (result, message) = self
+            .product()
+            .await?
+            .register(&registration.code, &registration.email)
+            .await
+            .map_err(to_status)?;
+        if result != 0 {
+            return Err(Status::failed_precondition(message));
+        }
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn get_patterns(&self, request: Request<Empty>) -> Result<Response<Patterns>, Status> {
+        self.authorize(&request)?;
+        let client = self.software().await?;
+        let selected = client.user_selected_patterns().await.map_err(to_status)?;
+        let patterns = client
+            .patterns(true)
+            .await
+            .map_err(to_status)?
+            .into_iter()
+            .map(|p| Pattern {
+                id: p.id,
+                category: p.category,
+                summary: p.summary,
+                description: p.description,
+            })
+            .collect();
+        Ok(Response::new(Patterns { patterns, selected }))
+    }
+
+    async fn select_patterns(&self, request: Request<Ids>) -> Result<Response<Empty>, Status> {
+        self.authorize(&request)?;
+        let ids = request.into_inner().ids;
+        self.software()
+            .await?
+            .select_patterns(&ids)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn probe_storage(&self, request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        self.authorize(&request)?;
+        StorageClient::new(self.connection.clone())
+            .await
+            .map_err(to_status)?
+            .probe()
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn get_network_connections(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<Json>, Status> {
+        self.authorize(&request)?;
+        let connections = self
+            .network()
+            .await?
+            .connections()
+            .await
+            .map_err(to_status)?;
+        let mut value =
+            serde_json::to_value(connections).map_err(|e| Status::internal(e.to_string()))?;
+        secrets::redact(&mut value);
+        Ok(Response::new(Json {
+            json: value.to_string(),
+        }))
+    }
+
+    async fn apply_network(&self, request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        self.authorize(&request)?;
+        self.network().await?.apply().await.map_err(to_status)?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn is_online(&self, request: Request<Empty>) -> Result<Response<Flag>, Status> {
+        self.authorize(&request)?;
+        let value = self.network().await?.is_online().await.map_err(to_status)?;
+        Ok(Response::new(Flag { value }))
+    }
+}
+
+/// Serves the gRPC API on the given Unix socket.
+///
+/// An existing socket on the same path is replaced. Any user can connect to the socket, but the
+/// calls are checked against the access policy.
+///
+/// * `connection`: connection to Agama's D-Bus API.
+/// * `path`: path of the socket.
+/// * `access`: policy to restrict the callers.
+pub async fn serve(
+    connection: zbus::Connection,
+    path: &Path,
+    access: AccessPolicy,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match fs::remove_file(path) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error.into()),
+        _ => {}
+    }
+    let listener = UnixListener::bind(path)?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o666))?;
+    let owner = fs::metadata(path)?.uid();
+
+    log::info!("Serving the gRPC API on {}", path.display());
+    let incoming = stream::unfold(listener, |listener| async move {
+        let result = listener.accept().await.map(|(stream, _)| stream);
+        Some((result, listener))
+    });
+    Server::builder()
+        .add_service(AgamaServer::new(AgamaService::new(
+            connection, access, owner,
+        )))
+        .serve_with_incoming(incoming)
+        .await?;
+    Ok(())
+}
+
+fn to_status(error: ServiceError) -> Status {
+    Status::internal(error.to_string())
+}
@@ -0,0 +1,170 @@
+//! Machine-readable description of the D-Bus API.
+//!
+//! This module walks through the objects exported under a given path and builds a description of
+//! their interfaces (methods, properties and signals, including their types). The result can be
+//! serialized as JSON to generate typed clients (e.g., for the web UI).
+//!
+//! As it relies on the exported objects, the interfaces that depend on the system state (e.g., the
+//! ones for each kind of network connection) are only described if there is an object
+//! implementing them.
+use quick_xml::de::from_str;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, error::Error};
+use zbus::fdo::IntrospectableProxy;
+
+/// Description of the interfaces exposed by a service.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Schema {
+    pub interfaces: Vec<Interface>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Interface {
+    #[serde(rename(deserialize = "@name"))]
+    pub name: String,
+    #[serde(rename(deserialize = "method"), default)]
+    pub methods: Vec<Method>,
+    #[serde(rename(deserialize = "property"), default)]
+    pub properties: Vec<Property>,
+    #[serde(rename(deserialize = "signal"), default)]
+    pub signals: Vec<Signal>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Method {
+    #[serde(rename(deserialize = "@name"))]
+    pub name: String,
+    #[serde(rename(deserialize = "arg"), default)]
+    pub args: Vec<Arg>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Signal {
+    #[serde(rename(deserialize = "@name"))]
+    pub name: String,
+    #[serde(rename(deserialize = "arg"), default)]
+    pub args: Vec<Arg>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Arg {
+    #[serde(rename(deserialize = "@name"), skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// D-Bus type signature (e.g., "a{sv}").
+    #[serde(rename(deserialize = "@type"))]
+    pub r#type: String,
+    /// "in" or "out" (only for methods).
+    #[serde(
+        rename(deserialize = "@direction"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub direction: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Property {
+    #[serde(rename(deserialize = "@name"))]
+    pub name: String,
+    /// D-Bus type signature.
+    #[serde(rename(deserialize = "@type"))]
+    pub r#type: String,
+    /// "read", "write" or "readwrite".
+    #[serde(rename(deserialize = "@access"))]
+    pub access: String,
+}
+
+/// Introspection data of a single object.
+#[derive(Debug, Deserialize)]
+struct Node {
+    #[serde(rename = "interface", default)]
+    interfaces: Vec<Interface>,
+    #[serde(rename = "node", default)]
+    nodes: Vec<ChildNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChildNode {
+    #[serde(rename = "@name")]
+    name: String,
+}
+
+/// Builds the schema for the interfaces exported by a service.
+///
+/// Objects sharing the same interface (e.g., the network connections) are described only once.
+///
+/// * `connection`: D-Bus connection.
+/// * `destination`: service name (e.g., "org.opensuse.Agama1").
+/// * `root`: path to start the search from (e.g., "/org/opensuse/Agama1").
+/// * `prefix`: only the interfaces starting with this prefix are included.
+pub async fn schema(
+    connection: &zbus::Connection,
+    destination: &str,
+    root: &str,
+    prefix: &str,
+) -> Result<Schema, Box<dyn Error>> {
+    let mut interfaces = BTreeMap::new();
+    // using a queue instead of recursion because of the limitations of async functions
+    let mut paths = vec![root.to_string()];
+
+    while let Some(path) = paths.pop() {
+        let proxy = IntrospectableProxy::builder(connection)
+            .destination(destination)?
+            .path(path.as_str())?
+            .build()
+            .await?;
+        let node = parse(&proxy.introspect().await?)?;
+        for iface in node.interfaces {
+            if iface.name.starts_with(prefix) {
+                interfaces.entry(iface.name.clone()).or_insert(iface);
+            }
+        }
+        for child in node.nodes {
+            paths.push(format!("{}/{}", path.trim_end_matches('/'), child.name));
+        }
+    }
+
+    Ok(Schema {
+        interfaces: interfaces.into_values().collect(),
+    })
+}
+
+fn parse(xml: &str) -> Result<Node, quick_xml::DeError> {
+    from_str(xml)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let xml = r#"<!DOCTYPE node PUBLIC "-//freedesktop//DTD D-BUS Object Introspection 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd">
+<node>
+  <interface name="org.opensuse.Agama1.Network.Connections">
+    <method name="AddConnection">
+      <arg name="id" type="s" direction="in"/>
+      <arg type="o" direction="out"/>
+    </method>
+    <property name="ConflictPolicy" type="s" access="readwrite"/>
+    <method name="Apply">
+    </method>
+    <signal name="ConnectionAdded">
+      <arg name="id" type="s"/>
+    </signal>
+  </interface>
+  <node name="0"/>
+</node>"#;
+        let node = parse(xml).unwrap();
+        assert_eq!(node.nodes.len(), 1);
+        assert_eq!(node.nodes[0].name, "0");
+
+        let iface = &node.interfaces[0];
+        assert_eq!(iface.name, "org.opensuse.Agama1.Network.Connections");
+        let methods: Vec<_> = iface.methods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(methods, vec!["AddConnection", "Apply"]);
+        assert_eq!(iface.methods[0].args[1].r#type, "o");
+        assert_eq!(iface.properties[0].access, "readwrite");
+        assert_eq!(iface.signals[0].args.len(), 1);
+    }
+}
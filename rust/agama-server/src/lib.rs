@@ -1,8 +1,16 @@
+pub mod access;
 pub mod cert;
+pub mod clock;
 pub mod error;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod introspection;
 pub mod l10n;
+pub mod manager;
 pub mod network;
+pub mod polkit;
 pub mod questions;
+pub mod s390;
 pub mod software;
 pub mod web;
 pub use web::service;
@@ -0,0 +1,66 @@
+//! Manager service for Agama
+//!
+//! This module starts porting the manager service (`org.opensuse.Agama.Manager1`, written in
+//! Ruby) to Rust. The `/org/opensuse/Agama1/Manager` object implements the
+//! `org.opensuse.Agama1.Manager` interface, which keeps track of:
+//!
+//! * The installation phases (startup, config and install), only allowing the valid transitions
+//!   (see [InstallationPhase]).
+//! * The busy services, aggregating the `org.opensuse.Agama1.ServiceStatus` interface of the Agama
+//!   services (see [ServicesStatus]).
+//!
+//! Additionally, the `/org/opensuse/Agama1/Overview` object implements the
+//! `org.opensuse.Agama1.Overview` interface, which combines the issues of all the services, the
+//! pending questions and the busy services (see [Overview]).
+//!
+//! ## Limitations
+//!
+//! The work of each phase (probing the system, writing the configuration, etc.) is still done by
+//! the Ruby service, which is called when moving to a new phase. The current phase mirrors the one
+//! of the Ruby service, so both objects agree even if the Ruby service is used directly. Other parts of the API, like
+//! `CollectLogs` or `Finish`, are not ported yet.
+
+mod dbus;
+mod overview;
+mod phases;
+mod services;
+
+pub use dbus::Manager;
+pub use overview::Overview;
+pub use phases::InstallationPhase;
+pub use services::ServicesStatus;
+
+use crate::access::AccessPolicy;
+use thiserror::Error;
+use zbus::Connection;
+
+/// Errors related to the installation process.
+#[derive(Error, Debug)]
+pub enum ManagerError {
+    #[error("Cannot move from the '{0}' phase to the '{1}' phase")]
+    InvalidTransition(InstallationPhase, InstallationPhase),
+    #[error("Unknown installation phase: '{0}'")]
+    UnknownPhase(u32),
+    #[error("Some services are busy: {}", .0.join(", "))]
+    Busy(Vec<String>),
+    #[error("Installation settings are invalid")]
+    InvalidSettings,
+}
+
+impl From<ManagerError> for zbus::fdo::Error {
+    fn from(value: ManagerError) -> zbus::fdo::Error {
+        zbus::fdo::Error::Failed(format!("Manager error: {value}"))
+    }
+}
+
+/// Exports the manager D-Bus objects and starts watching the status of the services.
+///
+/// * `connection`: D-Bus connection to use.
+/// * `access`: policy to restrict the operations that modify the system.
+pub async fn export_dbus_objects(
+    connection: &Connection,
+    access: AccessPolicy,
+) -> Result<(), Box<dyn std::error::Error>> {
+    dbus::export(connection, access).await?;
+    Ok(())
+}
@@ -0,0 +1,287 @@
+//! D-Bus interface for the manager.
+use super::{overview, InstallationPhase, ManagerError, Overview, ServicesStatus};
+use crate::access::AccessPolicy;
+use agama_lib::proxies::{ManagerProxy, ServiceStatusProxy};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+use tokio_stream::StreamExt;
+use zbus::{
+    dbus_interface,
+    zvariant::{OwnedValue, Value},
+    Connection, MessageHeader, SignalContext,
+};
+
+const PATH: &str = "/org/opensuse/Agama1/Manager";
+
+/// Services whose status is aggregated (well-known name and path of the `ServiceStatus` object).
+const SERVICES: [(&str, &str); 3] = [
+    (
+        "org.opensuse.Agama.Manager1",
+        "/org/opensuse/Agama/Manager1",
+    ),
+    (
+        "org.opensuse.Agama.Software1",
+        "/org/opensuse/Agama/Software1",
+    ),
+    (
+        "org.opensuse.Agama.Storage1",
+        "/org/opensuse/Agama/Storage1",
+    ),
+];
+
+/// D-Bus interface to drive the installation process.
+///
+/// The phases and the services status are shared with the tasks that watch the other services,
+/// so the methods do not need exclusive access to the object while the phases are running. The
+/// current phase follows the one of the Ruby manager service, which does the real work.
+pub struct Manager {
+    phase: Arc<Mutex<InstallationPhase>>,
+    services: Arc<Mutex<ServicesStatus>>,
+    backend: ManagerProxy<'static>,
+    access: AccessPolicy,
+}
+
+impl Manager {
+    /// Creates a Manager interface object.
+    ///
+    /// * `connection`: D-Bus connection to reach the Ruby manager service.
+    /// * `access`: policy to restrict the operations that modify the system.
+    pub async fn new(connection: &Connection, access: AccessPolicy) -> zbus::Result<Self> {
+        Ok(Self {
+            phase: Default::default(),
+            services: Default::default(),
+            backend: ManagerProxy::new(connection).await?,
+            access,
+        })
+    }
+
+    /// Fails if any of the services is busy.
+    async fn ensure_idle(&self) -> Result<(), ManagerError> {
+        let services = self.services.lock().await;
+        if services.is_busy() {
+            return Err(ManagerError::Busy(services.busy_services()));
+        }
+        Ok(())
+    }
+
+    /// Fails if the installation cannot move to the given phase.
+    ///
+    /// * `next`: phase to move to.
+    async fn ensure_can_move_to(&self, next: InstallationPhase) -> Result<(), ManagerError> {
+        self.phase.lock().await.move_to(next)?;
+        Ok(())
+    }
+
+    /// Moves the installation to the given phase and notifies the change.
+    ///
+    /// It does nothing if the phase was already updated from the Ruby manager service.
+    ///
+    /// * `next`: phase to move to.
+    /// * `ctxt`: signal context to notify the change.
+    async fn move_to(
+        &self,
+        next: InstallationPhase,
+        ctxt: &SignalContext<'_>,
+    ) -> zbus::fdo::Result<()> {
+        {
+            let mut phase = self.phase.lock().await;
+            if *phase == next {
+                return Ok(());
+            }
+            *phase = phase.move_to(next)?;
+        }
+        self.current_installation_phase_changed(ctxt).await?;
+        Ok(())
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Manager")]
+impl Manager {
+    /// Runs the config phase (probing the system and calculating the proposals).
+    ///
+    /// It fails if any service is busy. The phase does not change if the probing fails.
+    async fn probe(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        self.ensure_idle().await?;
+        self.ensure_can_move_to(InstallationPhase::Config).await?;
+        self.backend.probe().await?;
+        self.move_to(InstallationPhase::Config, &ctxt).await?;
+        Ok(())
+    }
+
+    /// Runs the install phase.
+    ///
+    /// It fails if any service is busy or the installation settings are not valid. The phase does
+    /// not change if the installation fails.
+    async fn commit(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        self.ensure_idle().await?;
+        self.ensure_can_move_to(InstallationPhase::Install).await?;
+        if !self.backend.can_install().await? {
+            return Err(ManagerError::InvalidSettings.into());
+        }
+        self.backend.commit().await?;
+        self.move_to(InstallationPhase::Install, &ctxt).await?;
+        Ok(())
+    }
+
+    /// Determines whether the installation settings are valid.
+    async fn can_install(&self) -> zbus::fdo::Result<bool> {
+        Ok(self.backend.can_install().await?)
+    }
+
+    /// All the installation phases, including their ID ("id") and label ("label").
+    #[dbus_interface(property)]
+    fn installation_phases(&self) -> Vec<HashMap<String, OwnedValue>> {
+        InstallationPhase::ALL
+            .iter()
+            .map(|phase| {
+                HashMap::from([
+                    ("id".to_string(), Value::new(*phase as u32).to_owned()),
+                    (
+                        "label".to_string(),
+                        Value::new(phase.to_string()).to_owned(),
+                    ),
+                ])
+            })
+            .collect()
+    }
+
+    /// ID of the current installation phase.
+    #[dbus_interface(property)]
+    async fn current_installation_phase(&self) -> u32 {
+        *self.phase.lock().await as u32
+    }
+
+    /// Names of the busy services.
+    #[dbus_interface(property)]
+    async fn busy_services(&self) -> Vec<String> {
+        self.services.lock().await.busy_services()
+    }
+}
+
+/// Exports the manager object and starts watching the other services.
+///
+/// * `connection`: D-Bus connection to use.
+/// * `access`: policy to restrict the operations that modify the system.
+pub async fn export(connection: &Connection, access: AccessPolicy) -> zbus::Result<()> {
+    let manager = Manager::new(connection, access).await?;
+    let phase = Arc::clone(&manager.phase);
+    let services = Arc::clone(&manager.services);
+    let backend = manager.backend.clone();
+    connection.object_server().at(PATH, manager).await?;
+
+    let overview = Overview::new(connection, Arc::clone(&services), backend.clone());
+    connection
+        .object_server()
+        .at(overview::PATH, overview)
+        .await?;
+
+    // the Ruby service can move to another phase on its own (e.g., the config phase runs
+    // automatically when the product is known)
+    let conn = connection.clone();
+    tokio::spawn(async move {
+        if let Err(error) = watch_phase(&conn, backend, phase).await {
+            log::warn!("Could not watch the current installation phase: {}", error);
+        }
+    });
+
+    for (name, path) in SERVICES {
+        let conn = connection.clone();
+        let services = Arc::clone(&services);
+        tokio::spawn(async move {
+            if let Err(error) = watch_service(&conn, name, path, services).await {
+                log::warn!("Could not watch the status of {}: {}", name, error);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Keeps the current phase in sync with the one of the Ruby manager service, notifying the
+/// changes.
+///
+/// * `connection`: D-Bus connection to use.
+/// * `backend`: proxy to the Ruby manager service.
+/// * `phase`: current phase to update.
+async fn watch_phase(
+    connection: &Connection,
+    backend: ManagerProxy<'static>,
+    phase: Arc<Mutex<InstallationPhase>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let iface_ref = connection
+        .object_server()
+        .interface::<_, Manager>(PATH)
+        .await?;
+
+    let mut changes = backend.receive_current_installation_phase_changed().await;
+    let mut current = backend.current_installation_phase().await?;
+    loop {
+        let next = InstallationPhase::try_from(current)?;
+        let changed = {
+            let mut phase = phase.lock().await;
+            std::mem::replace(&mut *phase, next) != next
+        };
+        if changed {
+            let iface = iface_ref.get().await;
+            iface
+                .current_installation_phase_changed(iface_ref.signal_context())
+                .await?;
+        }
+
+        let Some(change) = changes.next().await else {
+            return Ok(());
+        };
+        current = change.get().await?;
+    }
+}
+
+/// Keeps the status of a service up to date, notifying the changes in the busy services.
+///
+/// * `connection`: D-Bus connection to use.
+/// * `name`: well-known name of the service.
+/// * `path`: path of the object that implements the `ServiceStatus` interface.
+/// * `services`: services status to update.
+async fn watch_service(
+    connection: &Connection,
+    name: &'static str,
+    path: &'static str,
+    services: Arc<Mutex<ServicesStatus>>,
+) -> zbus::Result<()> {
+    let proxy = ServiceStatusProxy::builder(connection)
+        .destination(name)?
+        .path(path)?
+        .build()
+        .await?;
+    let iface_ref = connection
+        .object_server()
+        .interface::<_, Manager>(PATH)
+        .await?;
+
+    let mut changes = proxy.receive_current_changed().await;
+    let mut status = proxy.current().await?;
+    loop {
+        if services.lock().await.update(name, status) {
+            let iface = iface_ref.get().await;
+            iface
+                .busy_services_changed(iface_ref.signal_context())
+                .await?;
+        }
+
+        let Some(change) = changes.next().await else {
+            return Ok(());
+        };
+        status = change.get().await?;
+    }
+}
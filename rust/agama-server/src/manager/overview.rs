@@ -0,0 +1,249 @@
+//! D-Bus interface to get an overview of the installation status.
+use super::ServicesStatus;
+use agama_lib::{
+    overview::{self, OverviewIssue, ERROR_SEVERITY},
+    proxies::{IssuesProxy, ManagerProxy, ValidationProxy},
+};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+use zbus::{
+    dbus_interface,
+    fdo::{ManagedObjects, ObjectManagerProxy},
+    zvariant::OwnedValue,
+    Connection,
+};
+
+pub const PATH: &str = "/org/opensuse/Agama1/Overview";
+
+/// Objects implementing the `org.opensuse.Agama1.Issues` interface (area, well-known name of the
+/// service and path).
+const ISSUES_OBJECTS: [(&str, &str, &str); 5] = [
+    (
+        "software",
+        "org.opensuse.Agama.Software1",
+        "/org/opensuse/Agama/Software1",
+    ),
+    (
+        "product",
+        "org.opensuse.Agama.Software1",
+        "/org/opensuse/Agama/Software1/Product",
+    ),
+    (
+        "storage",
+        "org.opensuse.Agama.Storage1",
+        "/org/opensuse/Agama/Storage1",
+    ),
+    (
+        "network",
+        "org.opensuse.Agama1",
+        "/org/opensuse/Agama1/Network",
+    ),
+    ("clock", "org.opensuse.Agama1", "/org/opensuse/Agama1/Clock"),
+];
+
+/// Objects implementing the `org.opensuse.Agama1.Validation` interface (area, well-known name of
+/// the service and path).
+const VALIDATION_OBJECTS: [(&str, &str, &str); 1] = [(
+    "users",
+    "org.opensuse.Agama.Manager1",
+    "/org/opensuse/Agama/Users1",
+)];
+
+/// Source of the validation errors (see the `org.opensuse.Agama1.Issues` interface).
+const CONFIG_SOURCE: u32 = 2;
+
+const QUESTIONS_SERVICE: &str = "org.opensuse.Agama1";
+const QUESTIONS_PATH: &str = "/org/opensuse/Agama1/Questions";
+const GENERIC_QUESTION_INTERFACE: &str = "org.opensuse.Agama1.Questions.Generic";
+
+/// D-Bus interface that aggregates the status of the installation.
+///
+/// The status is collected from the services on each call, so it is always up to date. The
+/// services that are not available are skipped.
+pub struct Overview {
+    connection: Connection,
+    services: Arc<Mutex<ServicesStatus>>,
+    backend: ManagerProxy<'static>,
+}
+
+impl Overview {
+    /// Creates an Overview interface object.
+    ///
+    /// * `connection`: D-Bus connection to reach the services.
+    /// * `services`: status of the services, shared with the manager.
+    /// * `backend`: proxy to the Ruby manager service.
+    pub fn new(
+        connection: &Connection,
+        services: Arc<Mutex<ServicesStatus>>,
+        backend: ManagerProxy<'static>,
+    ) -> Self {
+        Self {
+            connection: connection.clone(),
+            services,
+            backend,
+        }
+    }
+
+    /// Collects the issues from all the services.
+    async fn issues(&self) -> Vec<OverviewIssue> {
+        let mut issues = vec![];
+        for (area, name, path) in ISSUES_OBJECTS {
+            match self.object_issues(area, name, path).await {
+                Ok(found) => issues.extend(found),
+                Err(error) => log::warn!("Could not read the {} issues: {}", area, error),
+            }
+        }
+        for (area, name, path) in VALIDATION_OBJECTS {
+            match self.validation_issues(area, name, path).await {
+                Ok(found) => issues.extend(found),
+                Err(error) => log::warn!("Could not read the {} issues: {}", area, error),
+            }
+        }
+        issues
+    }
+
+    /// Reads the issues of an object implementing the `org.opensuse.Agama1.Issues` interface.
+    async fn object_issues(
+        &self,
+        area: &str,
+        name: &'static str,
+        path: &'static str,
+    ) -> zbus::Result<Vec<OverviewIssue>> {
+        let proxy = IssuesProxy::builder(&self.connection)
+            .destination(name)?
+            .path(path)?
+            .cache_properties(zbus::CacheProperties::No)
+            .build()
+            .await?;
+        let issues = proxy.all().await?;
+        Ok(issues
+            .into_iter()
+            .map(|(description, details, source, severity)| OverviewIssue {
+                service: area.to_string(),
+                description,
+                details,
+                source,
+                severity,
+            })
+            .collect())
+    }
+
+    /// Reads the errors of an object implementing the `org.opensuse.Agama1.Validation` interface.
+    ///
+    /// The errors are reported as configuration issues that block the installation.
+    async fn validation_issues(
+        &self,
+        area: &str,
+        name: &'static str,
+        path: &'static str,
+    ) -> zbus::Result<Vec<OverviewIssue>> {
+        let proxy = ValidationProxy::builder(&self.connection)
+            .destination(name)?
+            .path(path)?
+            .cache_properties(zbus::CacheProperties::No)
+            .build()
+            .await?;
+        let errors = proxy.errors().await?;
+        Ok(errors
+            .into_iter()
+            .map(|description| OverviewIssue {
+                service: area.to_string(),
+                description,
+                source: CONFIG_SOURCE,
+                severity: ERROR_SEVERITY,
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    /// Counts the questions that are not answered yet.
+    async fn pending_questions(&self) -> zbus::Result<u32> {
+        let proxy = ObjectManagerProxy::builder(&self.connection)
+            .destination(QUESTIONS_SERVICE)?
+            .path(QUESTIONS_PATH)?
+            .build()
+            .await?;
+        let objects = proxy.get_managed_objects().await?;
+        Ok(count_pending_questions(&objects))
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Overview")]
+impl Overview {
+    /// Returns the installation status.
+    ///
+    /// It contains the issues of all the services ("Issues"), the number of pending questions
+    /// ("PendingQuestions"), the busy services ("BusyServices") and whether the installation can
+    /// start ("CanInstall").
+    async fn get(&self) -> zbus::fdo::Result<HashMap<String, OwnedValue>> {
+        let issues = self.issues().await;
+        let pending_questions = self.pending_questions().await.unwrap_or_else(|error| {
+            log::warn!("Could not read the pending questions: {}", error);
+            0
+        });
+        let busy_services = self.services.lock().await.busy_services();
+        let valid = self.backend.can_install().await?;
+
+        let can_install = valid && busy_services.is_empty() && !issues.iter().any(|i| i.is_error());
+        let overview = overview::Overview {
+            issues,
+            pending_questions,
+            busy_services,
+            can_install,
+        };
+        Ok(overview.to_dbus())
+    }
+}
+
+/// Counts the questions without an answer.
+///
+/// * `objects`: questions as returned by the `GetManagedObjects` method.
+fn count_pending_questions(objects: &ManagedObjects) -> u32 {
+    let pending = objects
+        .values()
+        .filter_map(|interfaces| {
+            interfaces
+                .iter()
+                .find(|(name, _)| name.as_str() == GENERIC_QUESTION_INTERFACE)
+                .map(|(_, properties)| properties)
+        })
+        .filter(|properties| {
+            properties
+                .get("Answer")
+                .and_then(|answer| answer.downcast_ref::<str>())
+                .is_some_and(str::is_empty)
+        })
+        .count();
+    pending as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zbus::{
+        names::OwnedInterfaceName,
+        zvariant::{OwnedObjectPath, Value},
+    };
+
+    fn question(answer: &str) -> HashMap<OwnedInterfaceName, HashMap<String, OwnedValue>> {
+        let properties = HashMap::from([("Answer".to_string(), Value::new(answer).to_owned())]);
+        HashMap::from([(
+            OwnedInterfaceName::try_from(GENERIC_QUESTION_INTERFACE).unwrap(),
+            properties,
+        )])
+    }
+
+    #[test]
+    fn test_count_pending_questions() {
+        let path = |id: u32| {
+            OwnedObjectPath::try_from(format!("/org/opensuse/Agama1/Questions/{}", id)).unwrap()
+        };
+        let objects: ManagedObjects = HashMap::from([
+            (path(0), question("yes")),
+            (path(1), question("")),
+            (path(2), question("")),
+        ]);
+        assert_eq!(count_pending_questions(&objects), 2);
+        assert_eq!(count_pending_questions(&HashMap::new()), 0);
+    }
+}
@@ -0,0 +1,107 @@
+//! Installation phases state machine.
+use super::ManagerError;
+use std::fmt;
+
+/// Phase of the installation process.
+///
+/// The installation starts in the startup phase and it does not move to the config phase until a
+/// client asks for it. The configuration can be repeated (e.g., to probe the system again) but,
+/// once the installation starts, there is no way back.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InstallationPhase {
+    /// Initial phase.
+    #[default]
+    Startup = 0,
+    /// The system is probed and the proposals are calculated.
+    Config = 1,
+    /// The system is installed according to the configuration.
+    Install = 2,
+}
+
+impl InstallationPhase {
+    /// All the phases, in order.
+    pub const ALL: [InstallationPhase; 3] = [
+        InstallationPhase::Startup,
+        InstallationPhase::Config,
+        InstallationPhase::Install,
+    ];
+
+    /// Determines whether the installation can move from this phase to the given one.
+    ///
+    /// * `next`: phase to move to.
+    pub fn can_move_to(&self, next: InstallationPhase) -> bool {
+        matches!(
+            (self, next),
+            (InstallationPhase::Startup, InstallationPhase::Config)
+                | (InstallationPhase::Config, InstallationPhase::Config)
+                | (InstallationPhase::Config, InstallationPhase::Install)
+        )
+    }
+
+    /// Returns the given phase if the installation can move to it.
+    ///
+    /// * `next`: phase to move to.
+    pub fn move_to(&self, next: InstallationPhase) -> Result<InstallationPhase, ManagerError> {
+        if !self.can_move_to(next) {
+            return Err(ManagerError::InvalidTransition(*self, next));
+        }
+        Ok(next)
+    }
+}
+
+impl TryFrom<u32> for InstallationPhase {
+    type Error = ManagerError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(InstallationPhase::Startup),
+            1 => Ok(InstallationPhase::Config),
+            2 => Ok(InstallationPhase::Install),
+            _ => Err(ManagerError::UnknownPhase(value)),
+        }
+    }
+}
+
+impl fmt::Display for InstallationPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            InstallationPhase::Startup => "startup",
+            InstallationPhase::Config => "config",
+            InstallationPhase::Install => "install",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InstallationPhase;
+    use crate::manager::ManagerError;
+
+    #[test]
+    fn test_move_to() {
+        let phase = InstallationPhase::default();
+        assert_eq!(phase, InstallationPhase::Startup);
+
+        let phase = phase.move_to(InstallationPhase::Config).unwrap();
+        let phase = phase.move_to(InstallationPhase::Config).unwrap();
+        let phase = phase.move_to(InstallationPhase::Install).unwrap();
+        assert_eq!(phase, InstallationPhase::Install);
+
+        let error = phase.move_to(InstallationPhase::Config).unwrap_err();
+        assert!(matches!(
+            error,
+            ManagerError::InvalidTransition(InstallationPhase::Install, InstallationPhase::Config)
+        ));
+        assert!(!InstallationPhase::Startup.can_move_to(InstallationPhase::Install));
+    }
+
+    #[test]
+    fn test_try_from_u32() {
+        assert_eq!(
+            InstallationPhase::try_from(1).unwrap(),
+            InstallationPhase::Config
+        );
+        assert!(InstallationPhase::try_from(3).is_err());
+    }
+}
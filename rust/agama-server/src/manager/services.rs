@@ -0,0 +1,65 @@
+//! Aggregation of the status of the Agama services.
+use std::collections::BTreeSet;
+
+/// Status of the service when it is busy (see the `org.opensuse.Agama1.ServiceStatus`
+/// interface).
+const BUSY: u32 = 1;
+
+/// Keeps track of the busy Agama services.
+#[derive(Clone, Debug, Default)]
+pub struct ServicesStatus {
+    busy: BTreeSet<String>,
+}
+
+impl ServicesStatus {
+    /// Records the status of a service and returns whether the set of busy services changed.
+    ///
+    /// * `service`: service name (e.g., "org.opensuse.Agama.Software1").
+    /// * `status`: value of the `Current` property of the `ServiceStatus` interface.
+    pub fn update(&mut self, service: &str, status: u32) -> bool {
+        if status == BUSY {
+            self.busy.insert(service.to_string())
+        } else {
+            self.busy.remove(service)
+        }
+    }
+
+    /// Names of the busy services, sorted alphabetically.
+    pub fn busy_services(&self) -> Vec<String> {
+        self.busy.iter().cloned().collect()
+    }
+
+    /// Determines whether any service is busy.
+    pub fn is_busy(&self) -> bool {
+        !self.busy.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ServicesStatus;
+
+    #[test]
+    fn test_update() {
+        let mut status = ServicesStatus::default();
+        assert!(!status.is_busy());
+
+        assert!(status.update("org.opensuse.Agama.Storage1", 1));
+        assert!(status.update("org.opensuse.Agama.Software1", 1));
+        assert!(!status.update("org.opensuse.Agama.Software1", 1));
+        assert_eq!(
+            status.busy_services(),
+            vec![
+                "org.opensuse.Agama.Software1".to_string(),
+                "org.opensuse.Agama.Storage1".to_string()
+            ]
+        );
+
+        assert!(status.update("org.opensuse.Agama.Storage1", 0));
+        assert!(!status.update("org.opensuse.Agama.Storage1", 0));
+        assert_eq!(
+            status.busy_services(),
+            vec!["org.opensuse.Agama.Software1".to_string()]
+        );
+    }
+}
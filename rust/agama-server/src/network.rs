@@ -11,11 +11,17 @@
 //! `/org/opensuse/Agama1/Network/devices/[0-9]+`. At this point, those objects expose a bit of
 //! information about network devices. The entry point for the devices is the
 //! `/org/opensuse/Agama1/Network/devices` object, that expose a `GetDevices` method that returns
-//! the paths for the devices objects.
+//! the paths for the devices objects. Additionally, it allows ignoring some devices (e.g., ports
+//! shared with a BMC) through the `SetIgnoredDevices` method: they are not included in the devices
+//! collection and their connections are not written when applying the configuration. To debug
+//! the IPv6 autoconfiguration, the `GetIpv6RaInfo` method reports the router advertisements
+//! received on a device.
 //!
 //! The network configuration is exposed through the connections objects as
-//! `/org/opensuse/Agama1/Network/connections/[0-9]+`. Those objects are composed of several
-//! D-Bus interfaces depending on its type:
+//! `/org/opensuse/Agama1/Network/connections/<id>`, where `<id>` is the connection ID with the
+//! characters that are not allowed in an object path escaped (e.g., "Wired connection 1" is
+//! published as `Wired_20connection_201`). Those objects are composed of several D-Bus
+//! interfaces depending on its type:
 //!
 //! * `org.opensuse.Agama1.Network.Connection` exposes common information across all connection
 //! types.
@@ -29,36 +35,170 @@
 //! `GetConnections`, `AddConnection` and `RemoveConnection`. Additionally, it implements an
 //! `Apply` method to write the changes to the NetworkManager service.
 //!
+//! Finally, the `/org/opensuse/Agama1/Network` object implements the
+//! `org.opensuse.Agama1.Network.Health` interface, which reports the service status (pending
+//! actions, outcome of the last apply, etc.) without waiting for the ongoing actions.
+//! It also implements the `org.opensuse.Agama1.Issues` interface, which reports potential
+//! problems in the configuration (e.g., a gateway outside the connection subnets) and the static
+//! addresses found to be in use by other hosts when applying the configuration, as warnings.
+//! When the `network-debug` feature is enabled, the `org.opensuse.Agama1.Network.Debug`
+//! interface allows dumping the state and the last processed actions, and injecting new actions,
+//! during troubleshooting sessions.
+//!
+//! ## Configuration
+//!
+//! The backend, the devices to ignore, the default activation timeout, the default DNS policy and
+//! the default IPv6 address generation mode can be set in the `/etc/agama.d/network.toml` file
+//! (see [config]). It is even possible to use a NetworkManager which is not running in the system
+//! bus (e.g., a remote one over TCP), which is useful to test the adapter against real hardware.
+//!
+//! ## Simulation
+//!
+//! For development purposes, the NetworkManager backend can be replaced with a simulated one,
+//! which adds some latency and injects failures (see [simulation]). Set `backend = "simulation"`
+//! in the configuration file or the `AGAMA_NETWORK_SIMULATION` environment variable to "1" or to
+//! the path of a simulation profile to enable it.
+//!
 //! ## Limitations
 //!
 //! We expect to address the following problems as we evolve the API, but it is noteworthy to have
 //! them in mind:
 //!
 //! * The devices list does not reflect the changes in the system. For instance, it is not updated
-//! when a device is connected to the system.
+//!   when a device is connected to the system. However, the changes in the connections made
+//!   directly in NetworkManager (e.g., using nmcli) are merged into Agama's state, keeping the
+//!   changes that were not applied yet.
 //! * Many configuration types are still missing (bridges, bonding, etc.).
 
 mod action;
 mod adapter;
+pub mod config;
+mod dad;
 pub mod dbus;
 pub mod error;
+mod health;
+mod kdump;
 pub mod model;
+mod ndisc;
 mod nm;
+mod regdom;
+mod session;
+mod shaping;
+pub mod simulation;
 pub mod system;
+mod wait_online;
+pub mod wpa_supplicant;
 
-pub use action::Action;
-pub use adapter::{Adapter, NetworkAdapterError};
+pub use action::{Action, RequestId, TrackedAction};
+pub use adapter::{Adapter, CancellationFlag, NetworkAdapterError};
+pub use config::{Backend, NetworkConfig};
 pub use dbus::NetworkService;
+pub use health::{ApplyStatus, Health};
 pub use model::NetworkState;
-pub use nm::NetworkManagerAdapter;
+pub use nm::{NetworkManagerAdapter, NetworkManagerWatcher};
+pub use simulation::{SimulationAdapter, SimulationProfile};
 pub use system::NetworkSystem;
+
+use std::path::PathBuf;
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
 use zbus::Connection;
 
+use crate::access::AccessPolicy;
+
+/// Environment variable to simulate the network backend. Its value is the path of a
+/// [SimulationProfile] file or "1" to use the default one. It takes precedence over the
+/// configuration file.
+const SIMULATION_ENV: &str = "AGAMA_NETWORK_SIMULATION";
+
+/// Exports the network D-Bus objects.
+///
+/// * `connection`: D-Bus connection to export the objects on.
+/// * `access`: policy to restrict the operations that modify the system.
 pub async fn export_dbus_objects(
     connection: &Connection,
+    access: AccessPolicy,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let adapter = NetworkManagerAdapter::from_system()
-        .await
-        .expect("Could not connect to NetworkManager to read the configuration.");
-    NetworkService::start(connection, adapter).await
+    let mut config = NetworkConfig::load().unwrap_or_else(|e| {
+        log::error!("Could not read the network configuration: {}", e);
+        NetworkConfig::default()
+    });
+    if let Ok(path) = std::env::var(SIMULATION_ENV) {
+        config.backend = Backend::Simulation;
+        config.simulation_profile = Some(path)
+            .filter(|p| !p.is_empty() && p != "1")
+            .map(PathBuf::from);
+    }
+
+    if let Some(level) = config.log_level {
+        log::set_max_level(level);
+    }
+
+    let actions = match config.backend {
+        Backend::Simulation => {
+            let profile = match &config.simulation_profile {
+                Some(path) => {
+                    log::warn!("Simulating the network backend using {}", path.display());
+                    SimulationProfile::from_file(path)?
+                }
+                None => {
+                    log::warn!("Simulating the network backend");
+                    SimulationProfile::default()
+                }
+            };
+            let adapter =
+                SimulationAdapter::new(profile).with_activation_timeout(config.activation_timeout);
+            NetworkService::start_with_access(connection, adapter, access).await?
+        }
+        Backend::NetworkManager => {
+            let nm_connection = match &config.networkmanager_address {
+                Some(address) => {
+                    log::info!("Using the NetworkManager at {}", address);
+                    agama_lib::connection_to(address).await?
+                }
+                None => zbus::Connection::system().await?,
+            };
+            let adapter = NetworkManagerAdapter::new(nm_connection.clone())
+                .await
+                .expect("Could not connect to NetworkManager to read the configuration.")
+                .with_activation_timeout(config.activation_timeout);
+            let watcher = NetworkManagerWatcher::new(nm_connection);
+            let actions = NetworkService::start_with_access(connection, adapter, access).await?;
+            let watcher_actions = actions.clone();
+            tokio::spawn(async move {
+                if let Err(e) = watcher.run(watcher_actions).await {
+                    log::error!("Could not watch the NetworkManager changes: {}", e);
+                }
+            });
+            actions
+        }
+    };
+
+    apply_config(&config, &actions).await?;
+    Ok(())
+}
+
+/// Applies the settings from the configuration file to the network state.
+///
+/// * `config`: network service configuration.
+/// * `actions`: channel to send the actions to the [NetworkSystem].
+async fn apply_config(
+    config: &NetworkConfig,
+    actions: &UnboundedSender<TrackedAction>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !config.ignored_devices.is_empty() {
+        actions.send(Action::SetIgnoredDevices(config.ignored_devices.clone()).into())?;
+    }
+
+    if let Some(policy) = config.dns_policy {
+        let (tx, rx) = oneshot::channel();
+        actions.send(Action::GetDns(tx).into())?;
+        let mut dns = rx.await?;
+        dns.policy = policy;
+        actions.send(Action::UpdateDns(Box::new(dns)).into())?;
+    }
+
+    if let Some(mode) = config.addr_gen_mode {
+        actions.send(Action::SetAddrGenMode(Some(mode)).into())?;
+    }
+    Ok(())
 }
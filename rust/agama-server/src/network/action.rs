@@ -1,5 +1,17 @@
-use crate::network::model::Connection;
-use agama_lib::network::types::DeviceType;
+use crate::network::model::{
+    ApplyResult, ApplySummary, BondCheck, ConfigIssue, ConflictPolicy, Connection,
+    ConnectionSummary, ConnectionTemplate, ConnectivityConfig, DnsConfig, IdMapping,
+    Ipv6AddrGenMode, KdumpConfig, LldpNeighbor, RouterAdvertisement,
+};
+use agama_lib::network::types::{BondMode, DeviceType};
+use std::{
+    fmt,
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+};
 use tokio::sync::oneshot;
 use uuid::Uuid;
 use zbus::zvariant::OwnedObjectPath;
@@ -7,8 +19,27 @@ use zbus::zvariant::OwnedObjectPath;
 use super::{error::NetworkStateError, NetworkAdapterError};
 
 pub type Responder<T> = oneshot::Sender<T>;
+
+/// Number of actions that were sent but not processed yet.
+static PENDING_ACTIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the number of actions waiting to be processed.
+pub fn pending_actions() -> usize {
+    PENDING_ACTIONS.load(Ordering::SeqCst)
+}
 pub type ControllerConnection = (Connection, Vec<String>);
 
+/// Page of the connections summary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionsPage {
+    /// Token identifying the state of the connections when the page was built.
+    pub token: String,
+    /// Total number of connections.
+    pub total: usize,
+    /// Summary and D-Bus path of the connections in the page.
+    pub items: Vec<(ConnectionSummary, OwnedObjectPath)>,
+}
+
 /// Networking actions, like adding, updating or removing connections.
 ///
 /// These actions are meant to be processed by [crate::network::system::NetworkSystem], updating the model
@@ -21,21 +52,104 @@ pub enum Action {
         DeviceType,
         Responder<Result<OwnedObjectPath, NetworkStateError>>,
     ),
+    /// Add one connection per interface using the given template.
+    AddConnections(
+        Box<ConnectionTemplate>,
+        Vec<String>,
+        Responder<Result<Vec<(String, OwnedObjectPath)>, NetworkStateError>>,
+    ),
+    /// Add the given connections (e.g., imported from a wpa_supplicant configuration file) if
+    /// none of them exists.
+    ImportConnections(
+        Vec<Connection>,
+        Responder<Result<Vec<(String, OwnedObjectPath)>, NetworkStateError>>,
+    ),
+    /// Copies the connection with the given ID using a new ID. The last argument determines
+    /// whether the secrets are copied too.
+    CloneConnection(
+        String,
+        String,
+        bool,
+        Responder<Result<OwnedObjectPath, NetworkStateError>>,
+    ),
+    /// Adds a connection for the given device based on its current IP configuration (the
+    /// boolean tells whether to copy it as a static one)
+    CreateFromDevice(
+        String,
+        bool,
+        Responder<Result<OwnedObjectPath, NetworkStateError>>,
+    ),
     /// Gets a connection
-    GetConnection(Uuid, Responder<Option<Connection>>),
+    GetConnection(Uuid, Responder<Option<Arc<Connection>>>),
     /// Gets a connection
     GetConnectionPath(Uuid, Responder<Option<OwnedObjectPath>>),
     /// Gets a connection
     GetConnectionPathById(String, Responder<Option<OwnedObjectPath>>),
     /// Get connections paths
     GetConnectionsPaths(Responder<Vec<OwnedObjectPath>>),
+    /// Gets the paths of the connections with the given tag
+    GetConnectionsByTag(String, Responder<Vec<OwnedObjectPath>>),
+    /// Gets the summary of the connections and their D-Bus paths
+    GetConnectionsSummary(Responder<Vec<(ConnectionSummary, OwnedObjectPath)>>),
+    /// Gets a page (offset and limit) of the connections summary. If a change token is given,
+    /// it fails when the connections changed since the token was issued.
+    GetConnectionsPage(
+        usize,
+        usize,
+        Option<String>,
+        Responder<Result<ConnectionsPage, NetworkStateError>>,
+    ),
+    /// Gets the token which identifies the current state of the connections
+    GetChangeToken(Responder<String>),
     /// Gets a controller connection
     GetController(
         Uuid,
         Responder<Result<ControllerConnection, NetworkStateError>>,
     ),
+    /// Gets the wireless regulatory domain
+    GetRegulatoryDomain(Responder<Option<String>>),
+    /// Sets the wireless regulatory domain
+    SetRegulatoryDomain(Option<String>, Responder<Result<(), NetworkStateError>>),
+    /// Gets the static hostname
+    GetHostname(Responder<Option<String>>),
+    /// Sets the static hostname
+    SetHostname(Option<String>, Responder<Result<(), NetworkStateError>>),
+    /// Determines whether the system has access to Internet
+    IsOnline(Responder<Result<bool, NetworkAdapterError>>),
+    /// Gets the connectivity check settings
+    GetConnectivity(Responder<ConnectivityConfig>),
+    /// Gets the global DNS settings
+    GetDns(Responder<DnsConfig>),
+    /// Gets the name servers that the given device would use
+    GetEffectiveDns(String, Responder<Result<Vec<IpAddr>, NetworkStateError>>),
+    /// Gets the network settings for kdump
+    GetKdump(Responder<KdumpConfig>),
+    /// Gets the names of the ignored devices
+    GetIgnoredDevices(Responder<Vec<String>>),
+    /// Sets the names of the devices to ignore
+    SetIgnoredDevices(Vec<String>),
+    /// Gets the default IPv6 address generation mode for the new connections
+    GetAddrGenMode(Responder<Option<Ipv6AddrGenMode>>),
+    /// Sets the default IPv6 address generation mode for the new connections
+    SetAddrGenMode(Option<Ipv6AddrGenMode>),
+    /// Gets the bandwidth limit for the connections that do not set their own
+    GetBandwidthLimit(Responder<Option<u32>>),
+    /// Sets the bandwidth limit for the connections that do not set their own
+    SetBandwidthLimit(Option<u32>),
     /// Get devices paths
     GetDevicesPaths(Responder<Vec<OwnedObjectPath>>),
+    /// Sets whether the backend manages the given device
+    SetDeviceManaged(String, bool, Responder<Result<(), NetworkAdapterError>>),
+    /// Gets the neighbors discovered through LLDP on the given device
+    GetLldpNeighbors(
+        String,
+        Responder<Result<Vec<LldpNeighbor>, NetworkAdapterError>>,
+    ),
+    /// Gets the IPv6 router advertisements received on the given device
+    GetIpv6RaInfo(
+        String,
+        Responder<Result<Vec<RouterAdvertisement>, NetworkStateError>>,
+    ),
     /// Sets a controller's ports. It uses the Uuid of the controller and the IDs or interface names
     /// of the ports.
     SetPorts(
@@ -45,8 +159,225 @@ pub enum Action {
     ),
     /// Update a connection (replacing the old one).
     UpdateConnection(Box<Connection>),
+    /// Update the connectivity check settings.
+    UpdateConnectivity(Box<ConnectivityConfig>),
+    /// Update the global DNS settings.
+    UpdateDns(Box<DnsConfig>),
+    /// Update the network settings for kdump.
+    UpdateKdump(Box<KdumpConfig>),
+    /// Synchronizes the connections with the ones in the backend (e.g., after they were changed
+    /// using nmcli).
+    SyncConnections,
+    /// Reloads the backend configuration and reads the devices and connections again, keeping
+    /// the pending changes (e.g., after udev renamed an interface).
+    Refresh(Responder<Result<(), NetworkAdapterError>>),
     /// Remove the connection with the given Uuid.
     RemoveConnection(Uuid),
+    /// Remove all the connections.
+    RemoveAllConnections,
+    /// Gets the UUIDs of the connections which carry a network session (e.g., SSH or the web UI).
+    GetSessionConnections(Responder<Vec<Uuid>>),
     /// Apply the current configuration.
-    Apply(Responder<Result<(), NetworkAdapterError>>),
+    Apply(Responder<Result<ApplySummary, NetworkAdapterError>>),
+    /// Apply the pending changes of the connections with the given tag only.
+    ApplyGroup(String, Responder<Result<ApplySummary, NetworkAdapterError>>),
+    /// Gets the result of the last time the configuration was applied
+    GetLastApplyResult(Responder<ApplyResult>),
+    /// Gets the error from the last time the connection was written, if any
+    GetLastError(Uuid, Responder<Option<String>>),
+    /// Gets the policy to resolve the conflicts between connections sharing the same ID.
+    GetConflictPolicy(Responder<ConflictPolicy>),
+    /// Sets the policy to resolve the conflicts between connections sharing the same ID. The
+    /// conflicts are resolved again, keeping the unapplied changes.
+    SetConflictPolicy(ConflictPolicy, Responder<Result<(), NetworkAdapterError>>),
+    /// Gets the mapping between the original connection IDs and the resulting ones.
+    GetIdsMapping(Responder<Vec<IdMapping>>),
+    /// Gets the team connections that were converted into bonds (ID and bond mode).
+    GetTeamMigrations(Responder<Vec<(String, BondMode)>>),
+    /// Gets the potential problems in the configuration (e.g., unreachable gateways) and the
+    /// address conflicts found when applying it.
+    GetIssues(Responder<Vec<ConfigIssue>>),
+    /// Checks the runtime state of the bond with the given ID.
+    VerifyBond(String, Responder<Result<BondCheck, NetworkStateError>>),
+    /// Gets a JSON representation of the network state (without secrets).
+    #[cfg(feature = "network-debug")]
+    DumpState(Responder<String>),
+    /// Gets the last processed actions (request ID and action name).
+    #[cfg(feature = "network-debug")]
+    DumpActions(Responder<Vec<(String, String)>>),
+}
+
+impl Action {
+    /// Attaches the action to the given request.
+    ///
+    /// The action is considered as pending until it is taken through [TrackedAction::take].
+    ///
+    /// * `request_id`: request the action comes from.
+    pub fn tracked(self, request_id: RequestId) -> TrackedAction {
+        PENDING_ACTIONS.fetch_add(1, Ordering::SeqCst);
+        TrackedAction {
+            request_id,
+            action: self,
+        }
+    }
+
+    /// Returns the name of the action.
+    ///
+    /// It is meant to be used in the logs, as the [Debug] representation might include
+    /// sensitive data (e.g., the wireless passwords).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::AddConnection(..) => "AddConnection",
+            Self::AddConnections(..) => "AddConnections",
+            Self::ImportConnections(..) => "ImportConnections",
+            Self::CloneConnection(..) => "CloneConnection",
+            Self::CreateFromDevice(..) => "CreateFromDevice",
+            Self::GetConnection(..) => "GetConnection",
+            Self::GetConnectionPath(..) => "GetConnectionPath",
+            Self::GetConnectionPathById(..) => "GetConnectionPathById",
+            Self::GetConnectionsPaths(..) => "GetConnectionsPaths",
+            Self::GetConnectionsByTag(..) => "GetConnectionsByTag",
+            Self::GetConnectionsSummary(..) => "GetConnectionsSummary",
+            Self::GetConnectionsPage(..) => "GetConnectionsPage",
+            Self::GetChangeToken(..) => "GetChangeToken",
+            Self::GetController(..) => "GetController",
+            Self::GetRegulatoryDomain(..) => "GetRegulatoryDomain",
+            Self::SetRegulatoryDomain(..) => "SetRegulatoryDomain",
+            Self::GetHostname(..) => "GetHostname",
+            Self::SetHostname(..) => "SetHostname",
+            Self::IsOnline(..) => "IsOnline",
+            Self::GetConnectivity(..) => "GetConnectivity",
+            Self::GetDns(..) => "GetDns",
+            Self::GetEffectiveDns(..) => "GetEffectiveDns",
+            Self::GetKdump(..) => "GetKdump",
+            Self::GetIgnoredDevices(..) => "GetIgnoredDevices",
+            Self::SetIgnoredDevices(..) => "SetIgnoredDevices",
+            Self::GetAddrGenMode(..) => "GetAddrGenMode",
+            Self::SetAddrGenMode(..) => "SetAddrGenMode",
+            Self::GetBandwidthLimit(..) => "GetBandwidthLimit",
+            Self::SetBandwidthLimit(..) => "SetBandwidthLimit",
+            Self::GetDevicesPaths(..) => "GetDevicesPaths",
+            Self::GetLldpNeighbors(..) => "GetLldpNeighbors",
+            Self::GetIpv6RaInfo(..) => "GetIpv6RaInfo",
+            Self::SetDeviceManaged(..) => "SetDeviceManaged",
+            Self::SetPorts(..) => "SetPorts",
+            Self::UpdateConnection(..) => "UpdateConnection",
+            Self::UpdateConnectivity(..) => "UpdateConnectivity",
+            Self::UpdateDns(..) => "UpdateDns",
+            Self::UpdateKdump(..) => "UpdateKdump",
+            Self::SyncConnections => "SyncConnections",
+            Self::Refresh(..) => "Refresh",
+            Self::RemoveConnection(..) => "RemoveConnection",
+            Self::RemoveAllConnections => "RemoveAllConnections",
+            Self::GetSessionConnections(..) => "GetSessionConnections",
+            Self::Apply(..) => "Apply",
+            Self::ApplyGroup(..) => "ApplyGroup",
+            Self::GetLastApplyResult(..) => "GetLastApplyResult",
+            Self::GetLastError(..) => "GetLastError",
+            Self::GetConflictPolicy(..) => "GetConflictPolicy",
+            Self::SetConflictPolicy(..) => "SetConflictPolicy",
+            Self::GetIdsMapping(..) => "GetIdsMapping",
+            Self::GetTeamMigrations(..) => "GetTeamMigrations",
+            Self::GetIssues(..) => "GetIssues",
+            Self::VerifyBond(..) => "VerifyBond",
+            #[cfg(feature = "network-debug")]
+            Self::DumpState(..) => "DumpState",
+            #[cfg(feature = "network-debug")]
+            Self::DumpActions(..) => "DumpActions",
+        }
+    }
+
+    /// Determines whether the action might change the connections (so the change token must
+    /// be renewed).
+    pub fn modifies_connections(&self) -> bool {
+        matches!(
+            self,
+            Self::AddConnection(..)
+                | Self::AddConnections(..)
+                | Self::ImportConnections(..)
+                | Self::CloneConnection(..)
+                | Self::CreateFromDevice(..)
+                | Self::SetPorts(..)
+                | Self::UpdateConnection(..)
+                | Self::SyncConnections
+                | Self::Refresh(..)
+                | Self::RemoveConnection(..)
+                | Self::RemoveAllConnections
+                | Self::Apply(..)
+                | Self::ApplyGroup(..)
+                | Self::SetConflictPolicy(..)
+        )
+    }
+}
+
+/// Identifies the request (usually, a D-Bus method call) an action comes from.
+///
+/// It is included in the log messages, so the actions coming from the same request can be
+/// correlated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RequestId(u64);
+
+impl RequestId {
+    /// Returns a new request ID.
+    pub fn new() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Action along with the request it comes from.
+#[derive(Debug)]
+pub struct TrackedAction {
+    pub request_id: RequestId,
+    pub action: Action,
+}
+
+impl TrackedAction {
+    /// Takes the action to process it, so it is not pending anymore.
+    pub fn take(self) -> (RequestId, Action) {
+        PENDING_ACTIONS.fetch_sub(1, Ordering::SeqCst);
+        (self.request_id, self.action)
+    }
+}
+
+impl From<Action> for TrackedAction {
+    /// Attaches the action to a new request.
+    fn from(action: Action) -> Self {
+        action.tracked(RequestId::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, RequestId};
+
+    #[test]
+    fn test_request_ids_are_unique() {
+        let first = RequestId::new();
+        let second = RequestId::new();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_tracked_action() {
+        let request_id = RequestId::new();
+        let tracked = Action::SyncConnections.tracked(request_id);
+        assert_eq!(tracked.request_id, request_id);
+        let (id, action) = tracked.take();
+        assert_eq!(id, request_id);
+        assert_eq!(action.name(), "SyncConnections");
+    }
+
+    #[test]
+    fn test_modifies_connections() {
+        assert!(Action::SyncConnections.modifies_connections());
+        let (tx, _rx) = tokio::sync::oneshot::channel();
+        assert!(!Action::GetChangeToken(tx).modifies_connections());
+    }
 }
@@ -1,6 +1,13 @@
-use crate::network::NetworkState;
+use crate::network::{
+    model::{ApplyResult, IpConfig, LldpNeighbor, PortState},
+    NetworkState,
+};
 use agama_lib::error::ServiceError;
 use async_trait::async_trait;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,15 +16,111 @@ pub enum NetworkAdapterError {
     Read(ServiceError),
     #[error("Could not update the network configuration: {0}")]
     Write(ServiceError),
+    #[error("Could not write the connections: {}", .0.join(", "))]
+    Failed(Vec<String>),
+    #[error("Could not update the wireless regulatory domain: {0}")]
+    RegulatoryDomain(String),
     #[error("Checkpoint handling error: {0}")]
     Checkpoint(ServiceError), // only relevant for adapters that implement a checkpoint mechanism
 }
 
+/// Flag to cancel an ongoing write operation.
+///
+/// It is shared between the [crate::network::NetworkSystem] and the D-Bus interfaces, so the
+/// write operation can be cancelled while it is running.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationFlag(Arc<AtomicBool>);
+
+impl CancellationFlag {
+    /// Requests the cancellation.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether the cancellation was requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Clears the cancellation request.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
 /// A trait for the ability to read/write from/to a network service
 #[async_trait]
-pub trait Adapter {
+pub trait Adapter: Sync {
     async fn read(&self) -> Result<NetworkState, NetworkAdapterError>;
-    async fn write(&self, network: &NetworkState) -> Result<(), NetworkAdapterError>;
+    /// Determines whether the system has access to Internet.
+    ///
+    /// Adapters that cannot find it out assume that the system is online.
+    async fn is_online(&self) -> Result<bool, NetworkAdapterError> {
+        Ok(true)
+    }
+    /// Returns the runtime state of the ports of a bond.
+    ///
+    /// Adapters that cannot find it out report an error.
+    ///
+    /// * `interface`: bond interface name.
+    async fn bond_ports(&self, _interface: &str) -> Result<Vec<PortState>, NetworkAdapterError> {
+        Err(NetworkAdapterError::Read(ServiceError::UnsuccessfulAction(
+            "checking the bond ports".to_string(),
+        )))
+    }
+    /// Returns the neighbors discovered through LLDP on a device.
+    ///
+    /// Adapters that cannot find it out report an error.
+    ///
+    /// * `device`: device name.
+    async fn lldp_neighbors(
+        &self,
+        _device: &str,
+    ) -> Result<Vec<LldpNeighbor>, NetworkAdapterError> {
+        Err(NetworkAdapterError::Read(ServiceError::UnsuccessfulAction(
+            "reading the LLDP neighbors".to_string(),
+        )))
+    }
+    /// Returns the current IP configuration of a device.
+    ///
+    /// Adapters that cannot find it out report an error.
+    ///
+    /// * `device`: device name.
+    async fn device_ip_config(&self, _device: &str) -> Result<IpConfig, NetworkAdapterError> {
+        Err(NetworkAdapterError::Read(ServiceError::UnsuccessfulAction(
+            "reading the IP configuration of the device".to_string(),
+        )))
+    }
+    /// Sets whether the backend manages a device.
+    ///
+    /// Adapters that cannot change it report an error.
+    ///
+    /// * `device`: device name.
+    /// * `managed`: whether the device should be managed.
+    async fn set_device_managed(
+        &self,
+        _device: &str,
+        _managed: bool,
+    ) -> Result<(), NetworkAdapterError> {
+        Err(NetworkAdapterError::Write(
+            ServiceError::UnsuccessfulAction("changing whether the device is managed".to_string()),
+        ))
+    }
+    /// Asks the backend to reload its configuration (e.g., the connection files on disk).
+    ///
+    /// Adapters that do not keep any configuration to reload do nothing.
+    async fn reload(&self) -> Result<(), NetworkAdapterError> {
+        Ok(())
+    }
+    /// Writes the network state and returns the result for each written connection.
+    ///
+    /// A connection that cannot be written does not prevent writing the rest of them. When the
+    /// `cancel` flag is set, the remaining connections are not written.
+    async fn write(
+        &self,
+        network: &NetworkState,
+        cancel: &CancellationFlag,
+    ) -> Result<ApplyResult, NetworkAdapterError>;
 }
 
 impl From<NetworkAdapterError> for zbus::fdo::Error {
@@ -0,0 +1,152 @@
+//! Network service configuration
+//!
+//! The configuration is read, when the service starts, from the following TOML files:
+//!
+//! * `/usr/etc/agama.d/network.toml`
+//! * `/etc/agama.d/network.toml`
+//!
+//! All the settings are optional and the values in the latter location take precedence. For
+//! instance:
+//!
+//! ```toml
+//! backend = "networkmanager"
+//! networkmanager_address = "tcp:host=192.168.122.10,port=55556"
+//! log_level = "debug"
+//! ignored_devices = ["eno1"]
+//! activation_timeout = 30
+//! dns_policy = "prepend"
+//! addr_gen_mode = "stable-privacy"
+//! ```
+
+use super::model::{DnsPolicy, Ipv6AddrGenMode};
+use config::{Config, ConfigError, File, FileFormat};
+use log::LevelFilter;
+use serde::Deserialize;
+use serde_with::{serde_as, DisplayFromStr};
+use std::path::PathBuf;
+
+/// Locations of the configuration file, from lower to higher precedence.
+const CONFIG_PATHS: [&str; 2] = ["/usr/etc/agama.d/network.toml", "/etc/agama.d/network.toml"];
+
+/// Backend to read and write the network configuration
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    NetworkManager,
+    /// Simulated backend (see [crate::network::simulation]).
+    Simulation,
+}
+
+/// Network service configuration.
+#[serde_as]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Backend to use.
+    pub backend: Backend,
+    /// Simulation profile to use with the simulated backend. If it is not set, the default
+    /// profile is used.
+    pub simulation_profile: Option<PathBuf>,
+    /// D-Bus address of the bus where NetworkManager lives (e.g.,
+    /// "tcp:host=192.168.122.10,port=55556" for a NetworkManager running in a test VM). If it
+    /// is not set, the system bus is used.
+    pub networkmanager_address: Option<String>,
+    /// Maximum log level (e.g., "debug"). As the logger is shared by all the services running in
+    /// the same process, it affects all of them.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub log_level: Option<LevelFilter>,
+    /// Names of the devices to ignore (e.g., ports shared with a BMC).
+    pub ignored_devices: Vec<String>,
+    /// Time (in seconds) to wait for each connection to be activated when applying the
+    /// configuration. It is used for the connections that do not set their own timeout.
+    pub activation_timeout: Option<u32>,
+    /// Default policy to combine the global name servers with the per-connection ones.
+    pub dns_policy: Option<DnsPolicy>,
+    /// Default IPv6 address generation mode for the new connections (e.g., "stable-privacy" when
+    /// the hardware-derived addresses are not allowed).
+    pub addr_gen_mode: Option<Ipv6AddrGenMode>,
+}
+
+impl NetworkConfig {
+    /// Reads the configuration from the default locations.
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::from_files(&CONFIG_PATHS)
+    }
+
+    /// Reads and merges the configuration from the given files.
+    ///
+    /// The files that do not exist are skipped.
+    ///
+    /// * `paths`: files to read, from lower to higher precedence.
+    pub fn from_files(paths: &[&str]) -> Result<Self, ConfigError> {
+        let mut builder = Config::builder();
+        for path in paths {
+            builder = builder.add_source(File::new(path, FileFormat::Toml).required(false));
+        }
+        builder.build()?.try_deserialize()
+    }
+
+    /// Reads the configuration from a TOML string.
+    ///
+    /// * `content`: configuration in TOML format.
+    pub fn from_toml(content: &str) -> Result<Self, ConfigError> {
+        Config::builder()
+            .add_source(File::from_str(content, FileFormat::Toml))
+            .build()?
+            .try_deserialize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml() {
+        let toml = r#"
+backend = "simulation"
+simulation_profile = "/tmp/profile.json"
+networkmanager_address = "tcp:host=localhost,port=55556"
+log_level = "debug"
+ignored_devices = ["eno1", "eno2"]
+activation_timeout = 30
+dns_policy = "override"
+addr_gen_mode = "stable-privacy"
+"#;
+        let config = NetworkConfig::from_toml(toml).unwrap();
+        assert_eq!(
+            config,
+            NetworkConfig {
+                backend: Backend::Simulation,
+                simulation_profile: Some(PathBuf::from("/tmp/profile.json")),
+                networkmanager_address: Some("tcp:host=localhost,port=55556".to_string()),
+                log_level: Some(LevelFilter::Debug),
+                ignored_devices: vec!["eno1".to_string(), "eno2".to_string()],
+                activation_timeout: Some(30),
+                dns_policy: Some(DnsPolicy::Override),
+                addr_gen_mode: Some(Ipv6AddrGenMode::StablePrivacy),
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_toml_defaults() {
+        let config = NetworkConfig::from_toml("").unwrap();
+        assert_eq!(config, NetworkConfig::default());
+        assert_eq!(config.backend, Backend::NetworkManager);
+    }
+
+    #[test]
+    fn test_from_toml_invalid() {
+        assert!(NetworkConfig::from_toml(r#"backend = "wicked""#).is_err());
+        assert!(NetworkConfig::from_toml(r#"log_level = "verbose""#).is_err());
+        assert!(NetworkConfig::from_toml(r#"addr_gen_mode = "random""#).is_err());
+    }
+
+    #[test]
+    fn test_from_missing_files() {
+        let config = NetworkConfig::from_files(&["/non-existing/network.toml"]).unwrap();
+        assert_eq!(config, NetworkConfig::default());
+    }
+}
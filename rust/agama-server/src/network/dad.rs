@@ -0,0 +1,167 @@
+//! Duplicate address detection for the static IPv4 addresses.
+//!
+//! Before activating a connection with static IPv4 addresses, each address is probed using
+//! `arping` in duplicate address detection mode. If another host answers, the address is already
+//! in use: activating the connection would lead to connectivity problems which are hard to
+//! diagnose after the installation, so the conflict is reported instead.
+//!
+//! The check is best-effort: if `arping` is not available or cannot probe the interface (e.g.,
+//! because it is down), the address is considered free.
+use super::model::{AddressConflict, Connection, Ipv4Method};
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    process::Command,
+};
+
+/// Number of probes to send for each address.
+const PROBES_COUNT: &str = "2";
+/// Maximum time (in seconds) to wait for the replies.
+const PROBES_DEADLINE: &str = "2";
+
+/// Returns the static IPv4 addresses of the connection which are already in use.
+///
+/// Only the connections that are going to be activated are checked.
+///
+/// * `conn`: connection to check.
+pub async fn check(conn: &Connection) -> Vec<AddressConflict> {
+    let Some(interface) = conn.interface.clone() else {
+        return vec![];
+    };
+    if !conn.is_up() || conn.only_on_target {
+        return vec![];
+    }
+
+    let mut conflicts = vec![];
+    for address in static_ipv4_addresses(conn) {
+        let interface = interface.clone();
+        let probe = tokio::task::spawn_blocking(move || probe(&interface, address)).await;
+        if let Ok(Some(mac)) = probe {
+            log::warn!(
+                "The address {} of the connection {} is already in use by {}",
+                address,
+                conn.id,
+                mac
+            );
+            conflicts.push(AddressConflict {
+                connection: conn.id.clone(),
+                address,
+                mac,
+            });
+        }
+    }
+    conflicts
+}
+
+/// Returns the static IPv4 addresses of a connection.
+///
+/// * `conn`: connection to take the addresses from.
+fn static_ipv4_addresses(conn: &Connection) -> Vec<Ipv4Addr> {
+    if conn.ip_config.method4 != Ipv4Method::Manual {
+        return vec![];
+    }
+    conn.ip_config
+        .addresses
+        .iter()
+        .filter_map(|inet| match inet.address() {
+            IpAddr::V4(address) => Some(address),
+            IpAddr::V6(_) => None,
+        })
+        .collect()
+}
+
+/// Probes an address and returns the MAC address of the host using it, if any.
+///
+/// * `interface`: interface to send the probes through.
+/// * `address`: address to probe.
+fn probe(interface: &str, address: Ipv4Addr) -> Option<String> {
+    let address = address.to_string();
+    let args = [
+        "-D",
+        "-c",
+        PROBES_COUNT,
+        "-w",
+        PROBES_DEADLINE,
+        "-I",
+        interface,
+        &address,
+    ];
+    let output = match Command::new("arping").args(args).output() {
+        Ok(output) => output,
+        Err(e) => {
+            log::warn!(
+                "Could not run arping to check the address {}: {}",
+                address,
+                e
+            );
+            return None;
+        }
+    };
+
+    // arping exits with 1 when it receives any reply and with 2 when it cannot send the probes
+    match output.status.code() {
+        Some(1) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            Some(mac_from_arping(&stdout).unwrap_or_else(|| "an unknown host".to_string()))
+        }
+        Some(0) => None,
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            log::warn!("Could not check the address {}: {}", address, stderr.trim());
+            None
+        }
+    }
+}
+
+/// Extracts the MAC address of the first reply from the `arping` output.
+///
+/// The replies look like `Unicast reply from 192.168.1.10 [52:54:00:12:34:56]  1.012ms`.
+///
+/// * `output`: `arping` output.
+fn mac_from_arping(output: &str) -> Option<String> {
+    output
+        .lines()
+        .filter(|l| l.contains("reply from"))
+        .find_map(|l| {
+            let start = l.find('[')? + 1;
+            let end = start + l[start..].find(']')?;
+            Some(l[start..end].to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agama_lib::network::types::DeviceType;
+
+    #[test]
+    fn test_static_ipv4_addresses() {
+        let mut conn = Connection::new("eth0".to_string(), DeviceType::Ethernet);
+        conn.ip_config.addresses = vec![
+            "192.168.1.10/24".parse().unwrap(),
+            "2001:db8::10/64".parse().unwrap(),
+        ];
+        assert!(static_ipv4_addresses(&conn).is_empty());
+
+        conn.ip_config.method4 = Ipv4Method::Manual;
+        assert_eq!(
+            static_ipv4_addresses(&conn),
+            vec![Ipv4Addr::new(192, 168, 1, 10)]
+        );
+    }
+
+    #[test]
+    fn test_mac_from_arping() {
+        let output = "ARPING 192.168.1.10 from 0.0.0.0 eth0\n\
+                      Unicast reply from 192.168.1.10 [52:54:00:12:34:56]  1.012ms\n\
+                      Sent 1 probes (1 broadcast(s))\n\
+                      Received 1 response(s)\n";
+        assert_eq!(
+            mac_from_arping(output),
+            Some("52:54:00:12:34:56".to_string())
+        );
+        assert_eq!(
+            mac_from_arping("Sent 2 probes\nReceived 0 response(s)\n"),
+            None
+        );
+    }
+}
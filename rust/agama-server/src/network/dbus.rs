@@ -3,9 +3,15 @@
 //! This module contains a [D-Bus network service](NetworkService) which expose the network
 //! configuration for Agama.
 
+pub mod compat;
+mod confirmation;
+mod dispatcher;
 mod interfaces;
+pub mod path;
 pub mod service;
 mod tree;
+pub mod types;
 
+pub(crate) use dispatcher::{Dispatcher, Signal};
 pub use service::NetworkService;
-pub(crate) use tree::Tree;
+pub(crate) use tree::{Tree, NETWORK_PATH};
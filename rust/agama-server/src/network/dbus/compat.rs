@@ -0,0 +1,66 @@
+//! Versioning of the network D-Bus API.
+//!
+//! The `org.opensuse.Agama1.*` interfaces are stable: they only get additive changes (new
+//! interfaces, properties or methods). When a breaking change is needed, the new shape is exported
+//! under an `org.opensuse.Agama2.*` interface on the same objects, next to the old one, and the old
+//! interface is kept as a compatibility shim for, at least, one more release. That way the web UI
+//! and the CLI can be upgraded independently of the installation media.
+//!
+//! This module keeps track of the supported versions and of the deprecated names, which are exposed
+//! through the [Versions](super::interfaces::Versions) interface so clients can find out which API
+//! they are talking to.
+
+/// Current (newest) version of the network D-Bus API.
+pub const API_VERSION: u32 = 1;
+
+/// Versions of the network D-Bus API which are served, from the oldest to the newest.
+pub const SUPPORTED_VERSIONS: &[u32] = &[1];
+
+/// A deprecated D-Bus name which is still served as a compatibility shim.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Deprecation {
+    /// Deprecated name (e.g., an interface name).
+    pub name: &'static str,
+    /// Name which replaces the deprecated one.
+    pub replacement: &'static str,
+    /// API version in which the deprecated name is removed.
+    pub removed_in: u32,
+}
+
+/// Deprecated names which are still served.
+pub const DEPRECATIONS: &[Deprecation] = &[];
+
+/// Returns the replacement of a deprecated name, if any.
+///
+/// * `name`: D-Bus name to check.
+pub fn replacement_for(name: &str) -> Option<&'static str> {
+    DEPRECATIONS
+        .iter()
+        .find(|d| d.name == name)
+        .map(|d| d.replacement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_versions() {
+        assert!(SUPPORTED_VERSIONS.contains(&API_VERSION));
+        assert_eq!(SUPPORTED_VERSIONS.last(), Some(&API_VERSION));
+        assert!(SUPPORTED_VERSIONS.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_deprecations() {
+        for deprecation in DEPRECATIONS {
+            assert!(deprecation.removed_in > API_VERSION);
+            assert_ne!(deprecation.name, deprecation.replacement);
+            assert!(replacement_for(deprecation.replacement).is_none());
+        }
+        assert_eq!(
+            replacement_for("org.opensuse.Agama1.Network.Connection"),
+            None
+        );
+    }
+}
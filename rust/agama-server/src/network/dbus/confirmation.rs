@@ -0,0 +1,63 @@
+//! Confirmation of the destructive operations.
+//!
+//! Some operations (e.g., removing the connection that carries the current session) might leave
+//! the system unreachable, so they are confirmed through the Questions service. In unattended
+//! mode, the questions are answered with the default option, which denies the operation. The
+//! profile can skip the confirmation by setting the `force` flag. A question which is not answered
+//! in time is withdrawn and the operation is denied.
+use agama_lib::proxies::{GenericQuestionProxy, Questions1Proxy};
+use std::{collections::HashMap, time::Duration};
+use tokio_stream::StreamExt;
+
+const YES: &str = "yes";
+const NO: &str = "no";
+
+/// Asks the user to confirm an operation.
+///
+/// It returns whether the operation was confirmed. If the question is not answered within the
+/// given timeout, it is deleted and the operation is considered as not confirmed.
+///
+/// * `connection`: D-Bus connection to reach the Questions service.
+/// * `class`: question class (e.g., "network.remove_session_connection").
+/// * `text`: question text.
+/// * `data`: additional data to identify the question (e.g., the connection ID).
+/// * `timeout`: maximum time to wait for the answer.
+pub async fn confirm(
+    connection: &zbus::Connection,
+    class: &str,
+    text: &str,
+    data: HashMap<&str, &str>,
+    timeout: Duration,
+) -> zbus::Result<bool> {
+    let questions = Questions1Proxy::new(connection).await?;
+    let path = questions
+        .new_quetion(class, text, &[YES, NO], NO, data)
+        .await?;
+    let question = GenericQuestionProxy::builder(connection)
+        .path(path.clone())?
+        .build()
+        .await?;
+
+    let wait_for_answer = async {
+        let mut changes = question.receive_answer_changed().await;
+        let mut answer = question.answer().await?;
+        while answer.is_empty() {
+            let Some(change) = changes.next().await else {
+                break;
+            };
+            answer = change.get().await?;
+        }
+        Ok::<_, zbus::Error>(answer)
+    };
+    let answer = match tokio::time::timeout(timeout, wait_for_answer).await {
+        Ok(answer) => answer?,
+        Err(_) => {
+            log::warn!("Question '{}' was not answered in time", class);
+            String::new()
+        }
+    };
+    questions.delete(&path.as_ref()).await?;
+
+    log::info!("Question '{}' answered with '{}'", class, answer);
+    Ok(answer == YES)
+}
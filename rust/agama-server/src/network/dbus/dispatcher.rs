@@ -0,0 +1,139 @@
+//! Channel between the D-Bus interfaces and the rest of the network service.
+//!
+//! All the D-Bus objects share a single [Dispatcher], which sends the actions to the
+//! [NetworkSystem](crate::network::NetworkSystem) and queues the signals to emit. The signals are
+//! emitted, in order, by a single task, so the interfaces do not need to hold their own channels
+//! nor look up other objects in the object server.
+use crate::network::{dbus::interfaces, model::ApplySummary, TrackedAction};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::mpsc::{self, error::SendError, UnboundedReceiver, UnboundedSender};
+use zbus::{
+    names::InterfaceName,
+    zvariant::{OwnedObjectPath, Value},
+    SignalContext,
+};
+
+/// Signals that can be emitted through the [Dispatcher].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Signal {
+    /// A connection has been added (see `Connections.ConnectionAdded`).
+    ConnectionAdded { id: String, path: OwnedObjectPath },
+    /// A connection has been updated outside Agama (see `Connections.ConnectionUpdated`).
+    ConnectionUpdated { id: String, path: OwnedObjectPath },
+    /// A connection has been removed outside Agama (see `Connections.ConnectionRemoved`).
+    ConnectionRemoved { id: String },
+    /// The configuration has been applied (see `Connections.AppliedConfiguration`).
+    AppliedConfiguration(ApplySummary),
+    /// The given properties have changed. They are notified as invalidated, so the clients
+    /// read them again.
+    PropertiesChanged {
+        interface: &'static str,
+        properties: Vec<&'static str>,
+    },
+}
+
+/// Handle to send actions and emit signals.
+///
+/// It is cheap to clone, so each D-Bus interface object keeps its own copy.
+#[derive(Clone)]
+pub struct Dispatcher {
+    inner: Arc<DispatcherChannels>,
+}
+
+struct DispatcherChannels {
+    actions: UnboundedSender<TrackedAction>,
+    signals: UnboundedSender<(OwnedObjectPath, Signal)>,
+}
+
+impl Dispatcher {
+    /// Creates a dispatcher and starts the task that emits the signals.
+    ///
+    /// * `connection`: D-Bus connection to emit the signals on.
+    /// * `actions`: sending-half of a channel to send actions.
+    pub fn new(connection: zbus::Connection, actions: UnboundedSender<TrackedAction>) -> Self {
+        let (signals, signals_rx) = mpsc::unbounded_channel();
+        tokio::spawn(emit_signals(connection, signals_rx));
+        Self {
+            inner: Arc::new(DispatcherChannels { actions, signals }),
+        }
+    }
+
+    /// Sends an action to the network system.
+    ///
+    /// * `action`: action to send.
+    pub fn send(&self, action: TrackedAction) -> Result<(), SendError<TrackedAction>> {
+        self.inner.actions.send(action)
+    }
+
+    /// Queues a signal to be emitted on the given object.
+    ///
+    /// * `path`: D-Bus path of the object emitting the signal.
+    /// * `signal`: signal to emit.
+    pub fn emit(&self, path: OwnedObjectPath, signal: Signal) {
+        if self.inner.signals.send((path, signal)).is_err() {
+            log::warn!("Could not queue the signal: the signals task is not running");
+        }
+    }
+}
+
+/// Emits the queued signals until all the dispatchers are dropped.
+///
+/// * `connection`: D-Bus connection to emit the signals on.
+/// * `signals`: receiving-half of the signals channel.
+async fn emit_signals(
+    connection: zbus::Connection,
+    mut signals: UnboundedReceiver<(OwnedObjectPath, Signal)>,
+) {
+    while let Some((path, signal)) = signals.recv().await {
+        if let Err(e) = emit_signal(&connection, &path, &signal).await {
+            log::error!(
+                "Could not emit the signal {:?} on '{}': {}",
+                signal,
+                path,
+                e
+            );
+        }
+    }
+}
+
+/// Emits a signal on the given object.
+///
+/// * `connection`: D-Bus connection to emit the signal on.
+/// * `path`: D-Bus path of the object emitting the signal.
+/// * `signal`: signal to emit.
+async fn emit_signal(
+    connection: &zbus::Connection,
+    path: &OwnedObjectPath,
+    signal: &Signal,
+) -> zbus::Result<()> {
+    let ctxt = SignalContext::new(connection, path.as_ref())?;
+    match signal {
+        Signal::ConnectionAdded { id, path } => {
+            interfaces::Connections::connection_added(&ctxt, id, path).await
+        }
+        Signal::ConnectionUpdated { id, path } => {
+            interfaces::Connections::connection_updated(&ctxt, id, path).await
+        }
+        Signal::ConnectionRemoved { id } => {
+            interfaces::Connections::connection_removed(&ctxt, id).await
+        }
+        Signal::AppliedConfiguration(summary) => {
+            interfaces::Connections::applied_configuration(
+                &ctxt,
+                summary.added,
+                summary.updated,
+                summary.removed,
+                &summary.failed,
+            )
+            .await
+        }
+        Signal::PropertiesChanged {
+            interface,
+            properties,
+        } => {
+            let interface = InterfaceName::try_from(*interface)?;
+            let changed: HashMap<&str, &Value<'_>> = HashMap::new();
+            zbus::fdo::Properties::properties_changed(&ctxt, interface, &changed, properties).await
+        }
+    }
+}
@@ -6,9 +6,29 @@
 mod common;
 mod connection_configs;
 mod connections;
+mod connectivity;
+#[cfg(feature = "network-debug")]
+mod debug;
 mod devices;
+mod dns;
+mod health;
 mod ip_config;
-pub use connection_configs::{Bond, Wireless};
-pub use connections::{Connection, Connections, Match};
-pub use devices::{Device, Devices};
+mod issues;
+mod kdump;
+mod match_config;
+mod versions;
+mod wireless;
+pub use connection_configs::{Bond, Ipvlan, Macsec, Macvlan, Modem, Pppoe, Vrf, Wireless};
+pub use connections::{Connection, Connections};
+pub use connectivity::Connectivity;
+#[cfg(feature = "network-debug")]
+pub use debug::Debug;
+pub use devices::{Device, Devices, ModemDevice, WirelessDevice};
+pub use dns::Dns;
+pub use health::Health;
 pub use ip_config::Ip;
+pub use issues::Issues;
+pub use kdump::Kdump;
+pub use match_config::Match;
+pub use versions::Versions;
+pub use wireless::WirelessSettings;
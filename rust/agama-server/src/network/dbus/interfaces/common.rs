@@ -2,30 +2,42 @@
 //!
 //! There are a set of operations that are shared by many D-Bus interfaces (retrieving or updating a connection, a configuration etc.).
 //! The traits in this module implements the common pieces to make it easier to build new
-//! interfaces and reduce code duplication.
+//! interfaces and reduce code duplication. The [connection_interface] macro takes care of the
+//! rest of the boilerplate (the struct, its constructor and the traits implementation).
 //!
 //! Note: it is not clear to us whether using traits or simple structs is better for this use case.
 //! We could change the approach in the future.
 use crate::network::{
+    dbus::Dispatcher,
     error::NetworkStateError,
     model::{Connection as NetworkConnection, ConnectionConfig},
-    Action,
+    Action, RequestId,
 };
 use async_trait::async_trait;
-use tokio::sync::{mpsc::UnboundedSender, oneshot, MutexGuard};
+use std::sync::Arc;
+use tokio::sync::oneshot;
 use uuid::Uuid;
 
 #[async_trait]
 pub trait ConnectionInterface {
     fn uuid(&self) -> Uuid;
 
-    async fn actions(&self) -> MutexGuard<UnboundedSender<Action>>;
+    fn dispatcher(&self) -> &Dispatcher;
 
-    async fn get_connection(&self) -> Result<NetworkConnection, NetworkStateError> {
-        let actions = self.actions().await;
+    async fn get_connection(&self) -> Result<Arc<NetworkConnection>, NetworkStateError> {
+        self.get_connection_for(RequestId::new()).await
+    }
+
+    /// Gets the connection as part of the given request.
+    ///
+    /// * `request_id`: request the connection is retrieved for.
+    async fn get_connection_for(
+        &self,
+        request_id: RequestId,
+    ) -> Result<Arc<NetworkConnection>, NetworkStateError> {
         let (tx, rx) = oneshot::channel();
-        actions
-            .send(Action::GetConnection(self.uuid(), tx))
+        self.dispatcher()
+            .send(Action::GetConnection(self.uuid(), tx).tracked(request_id))
             .unwrap();
         rx.await
             .unwrap()
@@ -41,14 +53,39 @@ pub trait ConnectionInterface {
     where
         F: FnOnce(&mut NetworkConnection) + std::marker::Send,
     {
-        let mut connection = self.get_connection().await?;
+        let request_id = RequestId::new();
+        let snapshot = self.get_connection_for(request_id).await?;
+        let mut connection = NetworkConnection::clone(&snapshot);
         func(&mut connection);
-        let actions = self.actions().await;
-        actions
-            .send(Action::UpdateConnection(Box::new(connection)))
+        self.dispatcher()
+            .send(Action::UpdateConnection(Box::new(connection)).tracked(request_id))
             .unwrap();
         Ok(())
     }
+
+    /// Returns the ports of a controller connection (e.g., a bond).
+    ///
+    /// For the port names, it uses the interface name (preferred) or, as a fallback, the
+    /// connection ID of the port.
+    async fn get_ports(&self) -> Result<Vec<String>, NetworkStateError> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher()
+            .send(Action::GetController(self.uuid(), tx).into())
+            .unwrap();
+        let (_, ports) = rx.await.unwrap()?;
+        Ok(ports)
+    }
+
+    /// Replaces the ports of a controller connection.
+    ///
+    /// * `ports`: interface names or connection IDs of the new ports.
+    async fn update_ports(&self, ports: Vec<String>) -> Result<(), NetworkStateError> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher()
+            .send(Action::SetPorts(self.uuid(), Box::new(ports), tx).into())
+            .unwrap();
+        rx.await.unwrap()
+    }
 }
 
 #[async_trait]
@@ -58,7 +95,7 @@ pub trait ConnectionConfigInterface: ConnectionInterface {
         T: TryFrom<ConnectionConfig, Error = NetworkStateError>,
     {
         let connection = self.get_connection().await?;
-        connection.config.try_into()
+        connection.config.clone().try_into()
     }
 
     async fn update_config<T, F>(&self, func: F) -> Result<(), NetworkStateError>
@@ -68,14 +105,64 @@ pub trait ConnectionConfigInterface: ConnectionInterface {
             + TryFrom<ConnectionConfig, Error = NetworkStateError>
             + std::marker::Send,
     {
-        let mut connection = self.get_connection().await?;
+        let request_id = RequestId::new();
+        let snapshot = self.get_connection_for(request_id).await?;
+        let mut connection = NetworkConnection::clone(&snapshot);
         let mut config: T = connection.config.clone().try_into()?;
         func(&mut config);
         connection.config = config.into();
-        let actions = self.actions().await;
-        actions
-            .send(Action::UpdateConnection(Box::new(connection)))
+        self.dispatcher()
+            .send(Action::UpdateConnection(Box::new(connection)).tracked(request_id))
             .unwrap();
         Ok(())
     }
 }
+
+/// Declares a D-Bus interface object bound to a network connection.
+///
+/// It defines the struct, its constructor and the [ConnectionInterface] implementation, so only
+/// the `#[dbus_interface]` block needs to be written. Adding `: config` after the name implements
+/// [ConnectionConfigInterface] too.
+///
+/// ```ignore
+/// connection_interface! {
+///     /// D-Bus interface for VRF settings.
+///     Vrf: config
+/// }
+/// ```
+macro_rules! connection_interface {
+    ($(#[$attr:meta])* $name:ident: config) => {
+        connection_interface!($(#[$attr])* $name);
+
+        impl $crate::network::dbus::interfaces::common::ConnectionConfigInterface for $name {}
+    };
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        pub struct $name {
+            dispatcher: $crate::network::dbus::Dispatcher,
+            uuid: ::uuid::Uuid,
+        }
+
+        impl $name {
+            #[doc = concat!("Creates a ", stringify!($name), " interface object.")]
+            ///
+            /// * `dispatcher`: dispatcher to send actions and emit signals.
+            /// * `uuid`: connection UUID.
+            pub fn new(dispatcher: $crate::network::dbus::Dispatcher, uuid: ::uuid::Uuid) -> Self {
+                Self { dispatcher, uuid }
+            }
+        }
+
+        impl $crate::network::dbus::interfaces::common::ConnectionInterface for $name {
+            fn uuid(&self) -> ::uuid::Uuid {
+                self.uuid
+            }
+
+            fn dispatcher(&self) -> &$crate::network::dbus::Dispatcher {
+                &self.dispatcher
+            }
+        }
+    };
+}
+
+pub(crate) use connection_interface;
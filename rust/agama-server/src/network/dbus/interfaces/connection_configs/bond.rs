@@ -0,0 +1,64 @@
+use agama_lib::network::types::BondMode;
+use zbus::dbus_interface;
+
+use crate::network::{
+    dbus::{
+        interfaces::common::{
+            connection_interface, ConnectionConfigInterface, ConnectionInterface,
+        },
+        types::DBusString,
+    },
+    model::BondConfig,
+};
+
+connection_interface! {
+    /// D-Bus interface for Bond settings.
+    Bond: config
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Connection.Bond")]
+impl Bond {
+    /// Bonding mode.
+    #[dbus_interface(property)]
+    pub async fn mode(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<BondConfig>().await?;
+        Ok(config.mode.to_dbus())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_mode(&mut self, mode: &str) -> zbus::fdo::Result<()> {
+        let mode = BondMode::from_dbus(mode)?;
+        self.update_config::<BondConfig, _>(|c| c.mode = mode)
+            .await?;
+        Ok(())
+    }
+
+    /// List of bonding options.
+    #[dbus_interface(property)]
+    pub async fn options(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<BondConfig>().await?;
+        Ok(config.options.to_string())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_options(&mut self, opts: &str) -> zbus::fdo::Result<()> {
+        let opts = opts.try_into()?;
+        self.update_config::<BondConfig, _>(|c| c.options = opts)
+            .await?;
+        Ok(())
+    }
+
+    /// List of bond ports.
+    ///
+    /// For the port names, it uses the interface name (preferred) or, as a fallback,
+    /// the connection ID of the port.
+    #[dbus_interface(property)]
+    pub async fn ports(&self) -> zbus::fdo::Result<Vec<String>> {
+        Ok(self.get_ports().await?)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_ports(&mut self, ports: Vec<String>) -> zbus::fdo::Result<()> {
+        Ok(self.update_ports(ports).await?)
+    }
+}
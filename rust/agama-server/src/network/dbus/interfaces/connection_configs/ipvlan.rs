@@ -0,0 +1,48 @@
+use zbus::dbus_interface;
+
+use crate::network::{
+    dbus::{
+        interfaces::common::{connection_interface, ConnectionConfigInterface},
+        types::DBusString,
+    },
+    model::{IpvlanConfig, IpvlanMode},
+};
+
+connection_interface! {
+    /// D-Bus interface for IPVLAN settings.
+    Ipvlan: config
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Connection.IPVLAN")]
+impl Ipvlan {
+    /// Parent interface name or connection UUID.
+    #[dbus_interface(property)]
+    pub async fn parent(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<IpvlanConfig>().await?;
+        Ok(config.parent)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_parent(&mut self, parent: String) -> zbus::fdo::Result<()> {
+        self.update_config::<IpvlanConfig, _>(|c| c.parent = parent)
+            .await?;
+        Ok(())
+    }
+
+    /// IPVLAN mode.
+    ///
+    /// Possible values: "l2", "l3" or "l3s".
+    #[dbus_interface(property)]
+    pub async fn mode(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<IpvlanConfig>().await?;
+        Ok(config.mode.to_dbus())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_mode(&mut self, mode: &str) -> zbus::fdo::Result<()> {
+        let mode = IpvlanMode::from_dbus(mode)?;
+        self.update_config::<IpvlanConfig, _>(|c| c.mode = mode)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use zbus::{dbus_interface, MessageHeader};
+
+use crate::{
+    network::{
+        dbus::{
+            interfaces::common::{connection_interface, ConnectionConfigInterface},
+            types::DBusString,
+        },
+        model::{MacsecConfig, MacsecMode},
+    },
+    polkit::{self, READ_NETWORK_SECRETS},
+};
+
+connection_interface! {
+    /// D-Bus interface for MACsec settings.
+    Macsec: config
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Connection.MACsec")]
+impl Macsec {
+    /// Parent interface name or connection UUID.
+    #[dbus_interface(property)]
+    pub async fn parent(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<MacsecConfig>().await?;
+        Ok(config.parent)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_parent(&mut self, parent: String) -> zbus::fdo::Result<()> {
+        self.update_config::<MacsecConfig, _>(|c| c.parent = parent)
+            .await?;
+        Ok(())
+    }
+
+    /// Key agreement mode.
+    ///
+    /// Possible values: "psk" (pre-shared CAK/CKN) or "eap" (802.1X).
+    #[dbus_interface(property)]
+    pub async fn mode(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<MacsecConfig>().await?;
+        Ok(config.mode.to_dbus())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_mode(&mut self, mode: &str) -> zbus::fdo::Result<()> {
+        let mode = MacsecMode::from_dbus(mode)?;
+        self.update_config::<MacsecConfig, _>(|c| c.mode = mode)
+            .await?;
+        Ok(())
+    }
+
+    /// Connectivity Association Key (CAK) for the PSK mode.
+    ///
+    /// It is a write-only property: reading it always returns an empty string. Use the `GetCak`
+    /// method to get the real value.
+    #[dbus_interface(property)]
+    pub async fn cak(&self) -> String {
+        String::new()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_cak(&mut self, cak: String) -> zbus::fdo::Result<()> {
+        self.update_config::<MacsecConfig, _>(|c| {
+            c.cak = if cak.is_empty() { None } else { Some(cak) };
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Whether a CAK is set.
+    #[dbus_interface(property)]
+    pub async fn has_cak(&self) -> zbus::fdo::Result<bool> {
+        let config = self.get_config::<MacsecConfig>().await?;
+        Ok(config.cak.is_some())
+    }
+
+    /// Returns the Connectivity Association Key.
+    ///
+    /// The caller must be authorized to perform the
+    /// [READ_NETWORK_SECRETS](crate::polkit::READ_NETWORK_SECRETS) polkit action.
+    pub async fn get_cak(
+        &self,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        polkit::check_authorization(connection, &header, READ_NETWORK_SECRETS).await?;
+        let config = self.get_config::<MacsecConfig>().await?;
+        Ok(config.cak.unwrap_or_default())
+    }
+
+    /// Connectivity Association Key Name (CKN) for the PSK mode.
+    ///
+    /// An empty string means no CKN.
+    #[dbus_interface(property)]
+    pub async fn ckn(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<MacsecConfig>().await?;
+        Ok(config.ckn.unwrap_or_default())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_ckn(&mut self, ckn: String) -> zbus::fdo::Result<()> {
+        self.update_config::<MacsecConfig, _>(|c| {
+            c.ckn = if ckn.is_empty() { None } else { Some(ckn) };
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Whether to encrypt the traffic. Otherwise, it is only authenticated.
+    #[dbus_interface(property)]
+    pub async fn encrypt(&self) -> zbus::fdo::Result<bool> {
+        let config = self.get_config::<MacsecConfig>().await?;
+        Ok(config.encrypt)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_encrypt(&mut self, encrypt: bool) -> zbus::fdo::Result<()> {
+        self.update_config::<MacsecConfig, _>(|c| c.encrypt = encrypt)
+            .await?;
+        Ok(())
+    }
+}
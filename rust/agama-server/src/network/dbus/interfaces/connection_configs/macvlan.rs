@@ -0,0 +1,48 @@
+use zbus::dbus_interface;
+
+use crate::network::{
+    dbus::{
+        interfaces::common::{connection_interface, ConnectionConfigInterface},
+        types::DBusString,
+    },
+    model::{MacvlanConfig, MacvlanMode},
+};
+
+connection_interface! {
+    /// D-Bus interface for MACVLAN settings.
+    Macvlan: config
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Connection.MACVLAN")]
+impl Macvlan {
+    /// Parent interface name or connection UUID.
+    #[dbus_interface(property)]
+    pub async fn parent(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<MacvlanConfig>().await?;
+        Ok(config.parent)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_parent(&mut self, parent: String) -> zbus::fdo::Result<()> {
+        self.update_config::<MacvlanConfig, _>(|c| c.parent = parent)
+            .await?;
+        Ok(())
+    }
+
+    /// MACVLAN mode.
+    ///
+    /// Possible values: "vepa", "bridge", "private", "passthru" or "source".
+    #[dbus_interface(property)]
+    pub async fn mode(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<MacvlanConfig>().await?;
+        Ok(config.mode.to_dbus())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_mode(&mut self, mode: &str) -> zbus::fdo::Result<()> {
+        let mode = MacvlanMode::from_dbus(mode)?;
+        self.update_config::<MacvlanConfig, _>(|c| c.mode = mode)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use zbus::{dbus_interface, MessageHeader};
+
+use crate::{
+    network::{
+        dbus::interfaces::common::{connection_interface, ConnectionConfigInterface},
+        model::ModemConfig,
+    },
+    polkit::{self, READ_NETWORK_SECRETS},
+};
+
+connection_interface! {
+    /// D-Bus interface for mobile broadband (GSM/UMTS/LTE) settings.
+    Modem: config
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Connection.Modem")]
+impl Modem {
+    /// Access Point Name (APN) of the carrier.
+    #[dbus_interface(property)]
+    pub async fn apn(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<ModemConfig>().await?;
+        Ok(config.apn)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_apn(&mut self, apn: String) -> zbus::fdo::Result<()> {
+        self.update_config::<ModemConfig, _>(|c| c.apn = apn)
+            .await?;
+        Ok(())
+    }
+
+    /// PIN to unlock the SIM card.
+    ///
+    /// It is a write-only property: reading it always returns an empty string. Use the `GetPin`
+    /// method to get the real value.
+    #[dbus_interface(property)]
+    pub async fn pin(&self) -> String {
+        String::new()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_pin(&mut self, pin: String) -> zbus::fdo::Result<()> {
+        self.update_config::<ModemConfig, _>(|c| {
+            c.pin = if pin.is_empty() { None } else { Some(pin) };
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Whether a PIN is set.
+    #[dbus_interface(property)]
+    pub async fn has_pin(&self) -> zbus::fdo::Result<bool> {
+        let config = self.get_config::<ModemConfig>().await?;
+        Ok(config.pin.is_some())
+    }
+
+    /// Returns the PIN to unlock the SIM card.
+    ///
+    /// The caller must be authorized to perform the
+    /// [READ_NETWORK_SECRETS](crate::polkit::READ_NETWORK_SECRETS) polkit action.
+    pub async fn get_pin(
+        &self,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        polkit::check_authorization(connection, &header, READ_NETWORK_SECRETS).await?;
+        let config = self.get_config::<ModemConfig>().await?;
+        Ok(config.pin.unwrap_or_default())
+    }
+
+    /// Username to authenticate with the carrier.
+    ///
+    /// Most carriers do not need it. An empty string means no username.
+    #[dbus_interface(property)]
+    pub async fn username(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<ModemConfig>().await?;
+        Ok(config.username.unwrap_or_default())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_username(&mut self, username: String) -> zbus::fdo::Result<()> {
+        self.update_config::<ModemConfig, _>(|c| {
+            c.username = if username.is_empty() {
+                None
+            } else {
+                Some(username)
+            };
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Password to authenticate with the carrier.
+    ///
+    /// It is a write-only property: reading it always returns an empty string. Use the
+    /// `GetPassword` method to get the real value.
+    #[dbus_interface(property)]
+    pub async fn password(&self) -> String {
+        String::new()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_password(&mut self, password: String) -> zbus::fdo::Result<()> {
+        self.update_config::<ModemConfig, _>(|c| {
+            c.password = if password.is_empty() {
+                None
+            } else {
+                Some(password)
+            };
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Whether a password is set.
+    #[dbus_interface(property)]
+    pub async fn has_password(&self) -> zbus::fdo::Result<bool> {
+        let config = self.get_config::<ModemConfig>().await?;
+        Ok(config.password.is_some())
+    }
+
+    /// Returns the password to authenticate with the carrier.
+    ///
+    /// The caller must be authorized to perform the
+    /// [READ_NETWORK_SECRETS](crate::polkit::READ_NETWORK_SECRETS) polkit action.
+    pub async fn get_password(
+        &self,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        polkit::check_authorization(connection, &header, READ_NETWORK_SECRETS).await?;
+        let config = self.get_config::<ModemConfig>().await?;
+        Ok(config.password.unwrap_or_default())
+    }
+}
@@ -0,0 +1,111 @@
+use zbus::{dbus_interface, MessageHeader};
+
+use crate::{
+    network::{
+        dbus::interfaces::common::{connection_interface, ConnectionConfigInterface},
+        model::PppoeConfig,
+    },
+    polkit::{self, READ_NETWORK_SECRETS},
+};
+
+connection_interface! {
+    /// D-Bus interface for PPPoE settings.
+    Pppoe: config
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Connection.PPPoE")]
+impl Pppoe {
+    /// Username to authenticate with the provider.
+    #[dbus_interface(property)]
+    pub async fn username(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<PppoeConfig>().await?;
+        Ok(config.username)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_username(&mut self, username: String) -> zbus::fdo::Result<()> {
+        self.update_config::<PppoeConfig, _>(|c| c.username = username)
+            .await?;
+        Ok(())
+    }
+
+    /// Password to authenticate with the provider.
+    ///
+    /// It is a write-only property: reading it always returns an empty string. Use the
+    /// `GetPassword` method to get the real value.
+    #[dbus_interface(property)]
+    pub async fn password(&self) -> String {
+        String::new()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_password(&mut self, password: String) -> zbus::fdo::Result<()> {
+        self.update_config::<PppoeConfig, _>(|c| {
+            c.password = if password.is_empty() {
+                None
+            } else {
+                Some(password)
+            };
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Whether a password is set.
+    #[dbus_interface(property)]
+    pub async fn has_password(&self) -> zbus::fdo::Result<bool> {
+        let config = self.get_config::<PppoeConfig>().await?;
+        Ok(config.password.is_some())
+    }
+
+    /// Returns the password to authenticate with the provider.
+    ///
+    /// The caller must be authorized to perform the
+    /// [READ_NETWORK_SECRETS](crate::polkit::READ_NETWORK_SECRETS) polkit action.
+    pub async fn get_password(
+        &self,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        polkit::check_authorization(connection, &header, READ_NETWORK_SECRETS).await?;
+        let config = self.get_config::<PppoeConfig>().await?;
+        Ok(config.password.unwrap_or_default())
+    }
+
+    /// PPPoE service name.
+    ///
+    /// It is only needed if the provider offers several services. An empty string means any
+    /// service.
+    #[dbus_interface(property)]
+    pub async fn service(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<PppoeConfig>().await?;
+        Ok(config.service.unwrap_or_default())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_service(&mut self, service: String) -> zbus::fdo::Result<()> {
+        self.update_config::<PppoeConfig, _>(|c| {
+            c.service = if service.is_empty() {
+                None
+            } else {
+                Some(service)
+            };
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Ethernet interface to establish the session on (e.g., "eth0").
+    #[dbus_interface(property)]
+    pub async fn parent(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<PppoeConfig>().await?;
+        Ok(config.parent)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_parent(&mut self, parent: String) -> zbus::fdo::Result<()> {
+        self.update_config::<PppoeConfig, _>(|c| c.parent = parent)
+            .await?;
+        Ok(())
+    }
+}
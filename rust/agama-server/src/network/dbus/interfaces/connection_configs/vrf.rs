@@ -0,0 +1,44 @@
+use zbus::dbus_interface;
+
+use crate::network::{
+    dbus::interfaces::common::{
+        connection_interface, ConnectionConfigInterface, ConnectionInterface,
+    },
+    model::VrfConfig,
+};
+
+connection_interface! {
+    /// D-Bus interface for VRF settings.
+    Vrf: config
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Connection.VRF")]
+impl Vrf {
+    /// Routing table ID of the VRF.
+    #[dbus_interface(property)]
+    pub async fn table(&self) -> zbus::fdo::Result<u32> {
+        let config = self.get_config::<VrfConfig>().await?;
+        Ok(config.table)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_table(&mut self, table: u32) -> zbus::fdo::Result<()> {
+        self.update_config::<VrfConfig, _>(|c| c.table = table)
+            .await?;
+        Ok(())
+    }
+
+    /// List of interfaces enslaved to the VRF.
+    ///
+    /// For the port names, it uses the interface name (preferred) or, as a fallback,
+    /// the connection ID of the port.
+    #[dbus_interface(property)]
+    pub async fn ports(&self) -> zbus::fdo::Result<Vec<String>> {
+        Ok(self.get_ports().await?)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_ports(&mut self, ports: Vec<String>) -> zbus::fdo::Result<()> {
+        Ok(self.update_ports(ports).await?)
+    }
+}
@@ -0,0 +1,181 @@
+use agama_lib::network::types::SSID;
+use zbus::{dbus_interface, MessageHeader};
+
+use crate::{
+    network::{
+        dbus::{
+            interfaces::common::{
+                connection_interface, ConnectionConfigInterface, ConnectionInterface,
+            },
+            types::DBusString,
+        },
+        error::NetworkStateError,
+        model::{SecurityProtocol, WirelessBand, WirelessConfig, WirelessMode},
+    },
+    polkit::{self, READ_NETWORK_SECRETS},
+};
+
+connection_interface! {
+    /// D-Bus interface for wireless settings.
+    Wireless: config
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Connection.Wireless")]
+impl Wireless {
+    /// Network SSID.
+    #[dbus_interface(property, name = "SSID")]
+    pub async fn ssid(&self) -> zbus::fdo::Result<Vec<u8>> {
+        let config = self.get_config::<WirelessConfig>().await?;
+        Ok(config.ssid.into())
+    }
+
+    #[dbus_interface(property, name = "SSID")]
+    pub async fn set_ssid(&mut self, ssid: Vec<u8>) -> zbus::fdo::Result<()> {
+        self.update_config::<WirelessConfig, _>(|c| c.ssid = SSID(ssid))
+            .await?;
+        Ok(())
+    }
+
+    /// Wireless connection mode.
+    ///
+    /// Possible values: "unknown", "adhoc", "infrastructure", "ap" or "mesh".
+    ///
+    /// See [crate::network::model::WirelessMode].
+    #[dbus_interface(property)]
+    pub async fn mode(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<WirelessConfig>().await?;
+        Ok(config.mode.to_dbus())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_mode(&mut self, mode: &str) -> zbus::fdo::Result<()> {
+        let mode = WirelessMode::from_dbus(mode)?;
+        let mut connection = (*self.get_connection().await?).clone();
+        connection.set_wireless_mode(mode)?;
+        self.update_connection(|c| *c = connection).await?;
+        Ok(())
+    }
+
+    /// Frequency band.
+    ///
+    /// Possible values: "a" (5GHz), "bg" (2.4GHz) or "" (any band). It is especially relevant when
+    /// working in the AP mode.
+    ///
+    /// See [crate::network::model::WirelessBand].
+    #[dbus_interface(property)]
+    pub async fn band(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<WirelessConfig>().await?;
+        Ok(config.band.to_dbus())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_band(&mut self, band: &str) -> zbus::fdo::Result<()> {
+        let band = Option::<WirelessBand>::from_dbus(band)?;
+        self.update_config::<WirelessConfig, _>(|c| {
+            if band.is_none() {
+                c.channel = None;
+            }
+            c.band = band;
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Wireless channel (0 means automatic selection).
+    ///
+    /// It requires setting the band first.
+    #[dbus_interface(property)]
+    pub async fn channel(&self) -> zbus::fdo::Result<u32> {
+        let config = self.get_config::<WirelessConfig>().await?;
+        Ok(config.channel.unwrap_or_default())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_channel(&mut self, channel: u32) -> zbus::fdo::Result<()> {
+        let config = self.get_config::<WirelessConfig>().await?;
+        if channel != 0 && !config.band.is_some_and(|b| b.has_channel(channel)) {
+            return Err(NetworkStateError::InvalidWirelessChannel(channel).into());
+        }
+        let channel = if channel == 0 { None } else { Some(channel) };
+        self.update_config::<WirelessConfig, _>(|c| c.channel = channel)
+            .await?;
+        Ok(())
+    }
+
+    /// Password to connect to the wireless network.
+    ///
+    /// It is a write-only property: reading it always returns an empty string. Use the
+    /// `GetPassword` method to get the real value.
+    #[dbus_interface(property)]
+    pub async fn password(&self) -> String {
+        String::new()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_password(&mut self, password: String) -> zbus::fdo::Result<()> {
+        self.update_config::<WirelessConfig, _>(|c| {
+            c.password = if password.is_empty() {
+                None
+            } else {
+                Some(password)
+            };
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Whether a password is set.
+    #[dbus_interface(property)]
+    pub async fn has_password(&self) -> zbus::fdo::Result<bool> {
+        let config = self.get_config::<WirelessConfig>().await?;
+        Ok(config.password.is_some())
+    }
+
+    /// Returns the password to connect to the wireless network.
+    ///
+    /// The caller must be authorized to perform the
+    /// [READ_NETWORK_SECRETS](crate::polkit::READ_NETWORK_SECRETS) polkit action.
+    pub async fn get_password(
+        &self,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        polkit::check_authorization(connection, &header, READ_NETWORK_SECRETS).await?;
+        let config = self.get_config::<WirelessConfig>().await?;
+        Ok(config.password.unwrap_or_default())
+    }
+
+    /// Wireless security protocol.
+    ///
+    /// Possible values: "none", "owe", "ieee8021x", "wpa-psk", "sae", "wpa-eap",
+    /// "wpa-eap-suite-b192".
+    ///
+    /// See [crate::network::model::SecurityProtocol].
+    #[dbus_interface(property)]
+    pub async fn security(&self) -> zbus::fdo::Result<String> {
+        let config = self.get_config::<WirelessConfig>().await?;
+        Ok(config.security.to_dbus())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_security(&mut self, security: &str) -> zbus::fdo::Result<()> {
+        let security = SecurityProtocol::from_dbus(security)?;
+        self.update_config::<WirelessConfig, _>(|c| c.security = security)
+            .await?;
+        Ok(())
+    }
+
+    /// Whether the network is hidden or not.
+    #[dbus_interface(property)]
+    pub async fn hidden(&self) -> zbus::fdo::Result<bool> {
+        let config = self.get_config::<WirelessConfig>().await?;
+        Ok(config.hidden)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_hidden(&mut self, hidden: bool) -> zbus::fdo::Result<()> {
+        self.update_config::<WirelessConfig, _>(|c| c.hidden = hidden)
+            .await?;
+        Ok(())
+    }
+}
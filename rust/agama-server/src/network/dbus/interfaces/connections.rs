@@ -1,31 +1,128 @@
-use async_trait::async_trait;
-use std::{str::FromStr, sync::Arc};
-use tokio::sync::{mpsc::UnboundedSender, oneshot, Mutex, MutexGuard};
+use agama_lib::network::types::DeviceType;
+use std::{collections::HashMap, path::Path, str::FromStr, time::Duration};
+use tokio::sync::oneshot;
 use uuid::Uuid;
 use zbus::{
     dbus_interface,
-    zvariant::{ObjectPath, OwnedObjectPath},
-    SignalContext,
+    zvariant::{self, ObjectPath, OwnedObjectPath, OwnedValue},
+    MessageHeader, SignalContext,
 };
 
 use super::common::ConnectionInterface;
-use crate::network::{error::NetworkStateError, model::MacAddress, Action};
+use crate::{
+    access::AccessPolicy,
+    network::{
+        dbus::{confirmation, types::DBusString, Dispatcher, Signal, NETWORK_PATH},
+        error::NetworkStateError,
+        model::{
+            ConflictPolicy, Connection as NetworkConnection, ConnectionSummary, ConnectionTemplate,
+            Ipv4Method, Ipv6AddrGenMode, Ipv6Method, MacAddress, ResolveMode, SecretStorage,
+        },
+        wpa_supplicant, Action, CancellationFlag, NetworkAdapterError,
+    },
+};
+
+/// Maximum time to wait for the user to confirm a destructive operation.
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(300);
 
 /// D-Bus interface for the set of connections.
 ///
 /// It offers an API to query the connections collection.
 pub struct Connections {
-    actions: Arc<Mutex<UnboundedSender<Action>>>,
+    dispatcher: Dispatcher,
+    access: AccessPolicy,
+    apply_cancellation: CancellationFlag,
+    force: bool,
 }
 
 impl Connections {
     /// Creates a Connections interface object.
     ///
-    /// * `objects`: Objects paths registry.
-    pub fn new(actions: UnboundedSender<Action>) -> Self {
+    /// * `dispatcher`: dispatcher to send actions and emit signals.
+    /// * `access`: policy to restrict the operations that modify the system.
+    /// * `apply_cancellation`: flag to cancel applying the configuration.
+    pub fn new(
+        dispatcher: Dispatcher,
+        access: AccessPolicy,
+        apply_cancellation: CancellationFlag,
+    ) -> Self {
         Self {
-            actions: Arc::new(Mutex::new(actions)),
+            dispatcher,
+            access,
+            apply_cancellation,
+            force: false,
+        }
+    }
+
+    /// Adds a new network connection and emits the `ConnectionAdded` signal.
+    ///
+    /// * `id`: connection name.
+    /// * `ty`: connection type.
+    /// * `ctxt`: signal context to emit the signal on.
+    async fn add(
+        &self,
+        id: String,
+        ty: DeviceType,
+        ctxt: &SignalContext<'_>,
+    ) -> zbus::fdo::Result<OwnedObjectPath> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::AddConnection(id.clone(), ty, tx).into())
+            .unwrap();
+        let path = rx.await.unwrap()?;
+        Self::connection_added(ctxt, &id, &path).await?;
+        Ok(path)
+    }
+
+    /// Performs a destructive operation once it is confirmed, unless the `Force` property is set.
+    ///
+    /// zbus keeps the interface locked while a method call is running, so waiting for the answer
+    /// there would block the rest of the calls (e.g., setting the `Force` property). Instead, the
+    /// question is asked from a separate task and the action is sent once it is confirmed. If
+    /// the question is denied or not answered in time, the operation is discarded.
+    ///
+    /// * `connection`: D-Bus connection to reach the Questions service.
+    /// * `class`: question class.
+    /// * `text`: question text.
+    /// * `data`: additional data to identify the question.
+    /// * `action`: action to perform once the operation is confirmed.
+    fn confirm(
+        &self,
+        connection: &zbus::Connection,
+        class: &str,
+        text: &str,
+        data: HashMap<String, String>,
+        action: Action,
+    ) {
+        if self.force {
+            log::info!("Skipping the confirmation of '{}' (forced)", class);
+            self.dispatcher.send(action.into()).unwrap();
+            return;
         }
+
+        let connection = connection.clone();
+        let dispatcher = self.dispatcher.clone();
+        let class = class.to_string();
+        let text = text.to_string();
+        tokio::spawn(async move {
+            let data = data.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+            match confirmation::confirm(&connection, &class, &text, data, CONFIRMATION_TIMEOUT)
+                .await
+            {
+                Ok(true) => dispatcher.send(action.into()).unwrap(),
+                Ok(false) => log::warn!("{}", NetworkStateError::NotConfirmed(text)),
+                Err(error) => log::error!("Could not ask for confirmation: {}", error),
+            }
+        });
+    }
+
+    /// Returns the UUIDs of the connections which carry a network session.
+    async fn session_connections(&self) -> Vec<Uuid> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetSessionConnections(tx).into())
+            .unwrap();
+        rx.await.unwrap()
     }
 }
 
@@ -33,13 +130,96 @@ impl Connections {
 impl Connections {
     /// Returns the D-Bus paths of the network connections.
     pub async fn get_connections(&self) -> zbus::fdo::Result<Vec<OwnedObjectPath>> {
-        let actions = self.actions.lock().await;
         let (tx, rx) = oneshot::channel();
-        actions.send(Action::GetConnectionsPaths(tx)).unwrap();
+        self.dispatcher
+            .send(Action::GetConnectionsPaths(tx).into())
+            .unwrap();
+        let result = rx.await.unwrap();
+        Ok(result)
+    }
+
+    /// Returns the D-Bus paths of the connections with the given tag.
+    ///
+    /// * `tag`: tag to search for.
+    pub async fn get_connections_by_tag(
+        &self,
+        tag: &str,
+    ) -> zbus::fdo::Result<Vec<OwnedObjectPath>> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetConnectionsByTag(tag.to_string(), tx).into())
+            .unwrap();
         let result = rx.await.unwrap();
         Ok(result)
     }
 
+    /// Returns the most relevant information of each connection.
+    ///
+    /// It allows listing the connections without reading the properties of each one. Each
+    /// element contains:
+    ///
+    /// * `Id` (s): connection ID.
+    /// * `Uuid` (s): connection UUID.
+    /// * `Path` (o): D-Bus path of the connection.
+    /// * `Type` (s): connection type (e.g., "ethernet", "wireless" or "bond").
+    /// * `Interface` (s): interface name (empty if it is not set).
+    /// * `Method4` (s) and `Method6` (s): IPv4 and IPv6 configuration methods.
+    /// * `State` (s): "up" or "down".
+    pub async fn get_summary(&self) -> Vec<HashMap<String, OwnedValue>> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetConnectionsSummary(tx).into())
+            .unwrap();
+        rx.await
+            .unwrap()
+            .into_iter()
+            .map(|(summary, path)| summary_to_dbus(summary, path))
+            .collect()
+    }
+
+    /// Returns a page of the connections summary (see `GetSummary`).
+    ///
+    /// The connections are sorted by their D-Bus path, so the pages are stable as long as the
+    /// connections do not change. Passing the change token of the previous page makes the call
+    /// fail if the connections changed in the meantime, so the client can start over.
+    ///
+    /// It returns the change token, the total number of connections and the page content.
+    ///
+    /// * `offset`: number of connections to skip.
+    /// * `limit`: maximum number of connections to return (0 means no limit).
+    /// * `token`: change token of a previous page (empty to skip the check).
+    pub async fn get_summary_page(
+        &self,
+        offset: u32,
+        limit: u32,
+        token: &str,
+    ) -> zbus::fdo::Result<(String, u32, Vec<HashMap<String, OwnedValue>>)> {
+        let token = Some(token.to_string()).filter(|t| !t.is_empty());
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetConnectionsPage(offset as usize, limit as usize, token, tx).into())
+            .unwrap();
+        let page = rx.await.unwrap()?;
+        let items = page
+            .items
+            .into_iter()
+            .map(|(summary, path)| summary_to_dbus(summary, path))
+            .collect();
+        let total = u32::try_from(page.total).unwrap_or(u32::MAX);
+        Ok((page.token, total, items))
+    }
+
+    /// Returns the token identifying the current state of the connections.
+    ///
+    /// The token changes each time the connections are added, modified or removed.
+    pub async fn get_change_token(&self) -> String {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetChangeToken(tx).into())
+            .unwrap();
+        rx.await.unwrap()
+    }
+
     /// Adds a new network connection.
     ///
     /// * `id`: connection name.
@@ -48,18 +228,150 @@ impl Connections {
         &mut self,
         id: String,
         ty: u8,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<OwnedObjectPath> {
+        self.access.check(connection, &header).await?;
+        self.add(id, ty.try_into()?, &ctxt).await
+    }
+
+    /// Adds a new network connection using the name of the connection type.
+    ///
+    /// * `id`: connection name.
+    /// * `ty`: connection type name, like "ethernet" or "wireless" (see
+    ///   [agama_lib::network::types::DeviceType]).
+    pub async fn add_connection_by_name(
+        &mut self,
+        id: String,
+        ty: String,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<OwnedObjectPath> {
+        self.access.check(connection, &header).await?;
+        self.add(id, DeviceType::from_str(&ty)?, &ctxt).await
+    }
+
+    /// Adds one network connection per interface using a template.
+    ///
+    /// The connections are added only if all of them can be added. It returns their D-Bus paths.
+    ///
+    /// * `template`: connection template. Supported keys are "Type" (connection type, as a number
+    ///   or a name, see [agama_lib::network::types::DeviceType]), "Id" (where "{interface}" is
+    ///   replaced with the interface name), "Method4", "Method6", "Nameservers" and "Controller"
+    ///   (ID of a bond connection). Only "Type" is mandatory.
+    /// * `interfaces`: names of the interfaces.
+    pub async fn add_connections(
+        &mut self,
+        template: HashMap<String, OwnedValue>,
+        interfaces: Vec<String>,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<Vec<OwnedObjectPath>> {
+        self.access.check(connection, &header).await?;
+        let template = template_from_dbus(&template)?;
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::AddConnections(Box::new(template), interfaces, tx).into())
+            .unwrap();
+        let added = rx.await.unwrap()?;
+        let mut paths = Vec::with_capacity(added.len());
+        for (id, path) in added {
+            Self::connection_added(&ctxt, &id, &path).await?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Copies an existing network connection using a new ID.
+    ///
+    /// The copy gets a new UUID. Secrets (e.g., wireless passwords) are not copied unless
+    /// `with_secrets` is true.
+    ///
+    /// * `source_id`: ID of the connection to copy.
+    /// * `new_id`: ID of the new connection.
+    /// * `with_secrets`: whether to copy the secrets.
+    pub async fn clone_connection(
+        &mut self,
+        source_id: String,
+        new_id: String,
+        with_secrets: bool,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<OwnedObjectPath> {
+        self.access.check(connection, &header).await?;
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::CloneConnection(source_id, new_id.clone(), with_secrets, tx).into())
+            .unwrap();
+        let path = rx.await.unwrap()?;
+        Self::connection_added(&ctxt, &new_id, &path).await?;
+        Ok(path)
+    }
+
+    /// Adds a network connection for a device based on its current IP configuration.
+    ///
+    /// The connection is named after the device. If `as_static` is true, the current addresses,
+    /// gateways, routes and name servers are copied as a static configuration (e.g., to turn a
+    /// DHCP-configured network into a fixed one). Otherwise, the connection uses automatic
+    /// configuration.
+    ///
+    /// * `name`: device name (e.g., "eth0").
+    /// * `as_static`: whether to copy the current configuration as a static one.
+    pub async fn create_from_device(
+        &mut self,
+        name: String,
+        as_static: bool,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
         #[zbus(signal_context)] ctxt: SignalContext<'_>,
     ) -> zbus::fdo::Result<OwnedObjectPath> {
-        let actions = self.actions.lock().await;
+        self.access.check(connection, &header).await?;
         let (tx, rx) = oneshot::channel();
-        actions
-            .send(Action::AddConnection(id.clone(), ty.try_into()?, tx))
+        self.dispatcher
+            .send(Action::CreateFromDevice(name.clone(), as_static, tx).into())
             .unwrap();
         let path = rx.await.unwrap()?;
-        Self::connection_added(&ctxt, &id, &path).await?;
+        Self::connection_added(&ctxt, &name, &path).await?;
         Ok(path)
     }
 
+    /// Imports the wireless networks defined in a wpa_supplicant configuration file.
+    ///
+    /// The connections are added only if all of them can be added. It returns their D-Bus paths.
+    ///
+    /// * `path`: path of the configuration file. If it is empty, the default one
+    ///   (/etc/wpa_supplicant/wpa_supplicant.conf) is used.
+    pub async fn import_wpa_supplicant(
+        &mut self,
+        path: String,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<Vec<OwnedObjectPath>> {
+        self.access.check(connection, &header).await?;
+        let path = if path.is_empty() {
+            wpa_supplicant::WPA_SUPPLICANT_CONF_PATH
+        } else {
+            path.as_str()
+        };
+        let conns = wpa_supplicant::read(Path::new(path))?;
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::ImportConnections(conns, tx).into())
+            .unwrap();
+        let added = rx.await.unwrap()?;
+        let mut paths = Vec::with_capacity(added.len());
+        for (id, path) in added {
+            Self::connection_added(&ctxt, &id, &path).await?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
     /// Returns the D-Bus path of the network connection by its UUID.
     ///
     /// * `uuid`: connection UUID.
@@ -67,9 +379,10 @@ impl Connections {
         let uuid: Uuid = uuid
             .parse()
             .map_err(|_| NetworkStateError::InvalidUuid(uuid.to_string()))?;
-        let actions = self.actions.lock().await;
         let (tx, rx) = oneshot::channel();
-        actions.send(Action::GetConnectionPath(uuid, tx)).unwrap();
+        self.dispatcher
+            .send(Action::GetConnectionPath(uuid, tx).into())
+            .unwrap();
         let path = rx
             .await
             .unwrap()
@@ -81,10 +394,9 @@ impl Connections {
     ///
     /// * `id`: connection ID.
     pub async fn get_connection_by_id(&self, id: &str) -> zbus::fdo::Result<OwnedObjectPath> {
-        let actions = self.actions.lock().await;
         let (tx, rx) = oneshot::channel();
-        actions
-            .send(Action::GetConnectionPathById(id.to_string(), tx))
+        self.dispatcher
+            .send(Action::GetConnectionPathById(id.to_string(), tx).into())
             .unwrap();
         let path = rx
             .await
@@ -95,27 +407,359 @@ impl Connections {
 
     /// Removes a network connection.
     ///
+    /// Removing a connection which carries a network session (e.g., SSH or the web UI) must be
+    /// confirmed through the Questions service, unless the `Force` property is set. In that
+    /// case, the method returns without waiting for the answer and the connection is removed
+    /// once the operation is confirmed.
+    ///
     /// * `uuid`: connection UUID..
-    pub async fn remove_connection(&mut self, uuid: &str) -> zbus::fdo::Result<()> {
-        let uuid = uuid
+    pub async fn remove_connection(
+        &self,
+        uuid: &str,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        let uuid: Uuid = uuid
             .parse()
             .map_err(|_| NetworkStateError::InvalidUuid(uuid.to_string()))?;
-        let actions = self.actions.lock().await;
-        actions.send(Action::RemoveConnection(uuid)).unwrap();
+        if self.session_connections().await.contains(&uuid) {
+            self.confirm(
+                connection,
+                "network.remove_session_connection",
+                "The connection carries the current session, so removing it might cut the \
+                 session. Do you want to remove it?",
+                HashMap::from([("uuid".to_string(), uuid.to_string())]),
+                Action::RemoveConnection(uuid),
+            );
+        } else {
+            self.dispatcher
+                .send(Action::RemoveConnection(uuid).into())
+                .unwrap();
+        }
+        Ok(())
+    }
+
+    /// Removes all the network connections.
+    ///
+    /// It must be confirmed through the Questions service, unless the `Force` property is set.
+    /// The method returns without waiting for the answer and the connections are removed once
+    /// the operation is confirmed.
+    pub async fn remove_all_connections(
+        &self,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        let text = if self.session_connections().await.is_empty() {
+            "Do you want to remove all the network connections?"
+        } else {
+            "Removing all the network connections might cut the current session. Do you want to \
+             remove them?"
+        };
+        self.confirm(
+            connection,
+            "network.remove_all_connections",
+            text,
+            HashMap::new(),
+            Action::RemoveAllConnections,
+        );
         Ok(())
     }
 
     /// Applies the network configuration.
     ///
-    /// It includes adding, updating and removing connections as needed.
-    pub async fn apply(&self) -> zbus::fdo::Result<()> {
-        let actions = self.actions.lock().await;
+    /// It includes adding, updating and removing connections as needed. A connection that cannot
+    /// be written does not prevent writing the rest of them. When an
+    /// [access policy](crate::access::AccessPolicy) is set, only the allowed callers can apply
+    /// the changes. Once the configuration is written, it emits the `AppliedConfiguration`
+    /// signal. The static IPv4 addresses that are already in use by other hosts are reported
+    /// through the `org.opensuse.Agama1.Issues` interface.
+    pub async fn apply(
+        &self,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher.send(Action::Apply(tx).into()).unwrap();
+        let summary = rx.await.unwrap()?;
+        let failed = summary.failed.clone();
+        self.dispatcher.emit(
+            ctxt.path().to_owned().into(),
+            Signal::AppliedConfiguration(summary),
+        );
+        // the address conflicts found when applying are reported as issues
+        self.dispatcher.emit(
+            OwnedObjectPath::try_from(NETWORK_PATH).unwrap(),
+            Signal::PropertiesChanged {
+                interface: "org.opensuse.Agama1.Issues",
+                properties: vec!["All"],
+            },
+        );
+        if !failed.is_empty() {
+            return Err(NetworkAdapterError::Failed(failed).into());
+        }
+        Ok(())
+    }
+
+    /// Applies the changes of the connections with the given tag.
+    ///
+    /// It works like `Apply`, but the pending changes of the rest of the connections are kept so
+    /// they can be applied later. The new controllers of the tagged connections (and their new
+    /// ports) are applied too. The global settings (e.g., DNS) are written too.
+    ///
+    /// * `tag`: tag of the connections to apply.
+    pub async fn apply_group(
+        &self,
+        tag: &str,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::ApplyGroup(tag.to_string(), tx).into())
+            .unwrap();
+        let summary = rx.await.unwrap()?;
+        let failed = summary.failed.clone();
+        self.dispatcher.emit(
+            ctxt.path().to_owned().into(),
+            Signal::AppliedConfiguration(summary),
+        );
+        self.dispatcher.emit(
+            OwnedObjectPath::try_from(NETWORK_PATH).unwrap(),
+            Signal::PropertiesChanged {
+                interface: "org.opensuse.Agama1.Issues",
+                properties: vec!["All"],
+            },
+        );
+        if !failed.is_empty() {
+            return Err(NetworkAdapterError::Failed(failed).into());
+        }
+        Ok(())
+    }
+
+    /// Reloads the backend configuration and reads the devices and connections again.
+    ///
+    /// It is useful when the system changed during the live session (e.g., udev renamed an
+    /// interface or a device appeared after loading its firmware). The pending changes are kept
+    /// and the `ConnectionAdded`, `ConnectionUpdated` and `ConnectionRemoved` signals are emitted
+    /// for the connections that changed in the backend.
+    pub async fn refresh(
+        &self,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher.send(Action::Refresh(tx).into()).unwrap();
+        Ok(rx.await.unwrap()?)
+    }
+
+    /// Cancels applying the network configuration.
+    ///
+    /// The connections that were not written yet are skipped and reported as failed. It also stops
+    /// waiting for the activation of the current connection.
+    pub async fn cancel_apply(
+        &self,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        self.apply_cancellation.cancel();
+        Ok(())
+    }
+
+    /// Returns the result of the last time the configuration was applied.
+    ///
+    /// Each element contains the connection ID, the UUID, the kind of change ("added",
+    /// "updated" or "removed") and the error message (empty if the connection was written).
+    pub async fn get_last_apply_result(&self) -> Vec<(String, String, String, String)> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetLastApplyResult(tx).into())
+            .unwrap();
+        rx.await
+            .unwrap()
+            .connections
+            .into_iter()
+            .map(|r| {
+                (
+                    r.id,
+                    r.uuid.to_string(),
+                    r.change.to_string(),
+                    r.error.unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    /// Policy to resolve the conflicts between connections sharing the same ID.
+    ///
+    /// Possible values: "rename" (default), "skip" or "replace".
+    ///
+    /// Setting this property resolves the conflicts again. The unapplied changes are kept.
+    ///
+    /// See [crate::network::model::ConflictPolicy].
+    #[dbus_interface(property)]
+    pub async fn conflict_policy(&self) -> String {
         let (tx, rx) = oneshot::channel();
-        actions.send(Action::Apply(tx)).unwrap();
+        self.dispatcher
+            .send(Action::GetConflictPolicy(tx).into())
+            .unwrap();
+        rx.await.unwrap().to_dbus()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_conflict_policy(&mut self, policy: &str) -> zbus::fdo::Result<()> {
+        let policy = ConflictPolicy::from_dbus(policy)?;
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::SetConflictPolicy(policy, tx).into())
+            .unwrap();
         rx.await.unwrap()?;
         Ok(())
     }
 
+    /// Whether the destructive operations (e.g., removing the connection which carries the
+    /// current session) are performed without asking for confirmation.
+    #[dbus_interface(property)]
+    pub fn force(&self) -> bool {
+        self.force
+    }
+
+    #[dbus_interface(property)]
+    pub fn set_force(&mut self, value: bool) {
+        self.force = value;
+    }
+
+    /// Static hostname of the system.
+    ///
+    /// It is written to the system when applying the configuration. An empty string means
+    /// keeping the hostname the system already has.
+    #[dbus_interface(property)]
+    pub async fn hostname(&self) -> String {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetHostname(tx).into())
+            .unwrap();
+        rx.await.unwrap().unwrap_or_default()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_hostname(&mut self, hostname: String) -> zbus::fdo::Result<()> {
+        let hostname = if hostname.is_empty() {
+            None
+        } else {
+            Some(hostname)
+        };
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::SetHostname(hostname, tx).into())
+            .unwrap();
+        rx.await.unwrap()?;
+        Ok(())
+    }
+
+    /// Default IPv6 address generation mode for the new connections.
+    ///
+    /// Possible values: "eui64", "stable-privacy" or an empty string to use the backend's default.
+    /// The connections can override it through their own `AddrGenMode6` property.
+    #[dbus_interface(property)]
+    pub async fn addr_gen_mode(&self) -> String {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetAddrGenMode(tx).into())
+            .unwrap();
+        rx.await.unwrap().to_dbus()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_addr_gen_mode(&mut self, mode: &str) -> zbus::fdo::Result<()> {
+        let mode = Option::<Ipv6AddrGenMode>::from_dbus(mode)?;
+        self.dispatcher
+            .send(Action::SetAddrGenMode(mode).into())
+            .unwrap();
+        Ok(())
+    }
+
+    /// Bandwidth limit (in Kbit/s) for the connections that do not set their own.
+    ///
+    /// The limit applies to the live system only, once the configuration is applied. If it is
+    /// set to 0, there is no limit.
+    #[dbus_interface(property)]
+    pub async fn bandwidth_limit(&self) -> u32 {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetBandwidthLimit(tx).into())
+            .unwrap();
+        rx.await.unwrap().unwrap_or_default()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_bandwidth_limit(&mut self, limit: u32) {
+        self.dispatcher
+            .send(Action::SetBandwidthLimit((limit > 0).then_some(limit)).into())
+            .unwrap();
+    }
+
+    /// Returns the mapping between the connection IDs read from the backend and the resulting
+    /// ones after resolving the conflicts.
+    ///
+    /// Each element contains the original ID, the UUID and the resulting ID. The latter is empty
+    /// if the connection was skipped or replaced.
+    pub async fn get_ids_mapping(&self) -> Vec<(String, String, String)> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetIdsMapping(tx).into())
+            .unwrap();
+        rx.await
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.original_id, m.uuid.to_string(), m.id.unwrap_or_default()))
+            .collect()
+    }
+
+    /// Returns the team connections that were converted into bonds.
+    ///
+    /// Teaming is not supported, so the team connections are read as bonds and their profiles
+    /// are replaced when applying the configuration. Each element contains the connection ID
+    /// and the resulting bond mode (e.g., "active-backup").
+    pub async fn get_team_migrations(&self) -> Vec<(String, String)> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetTeamMigrations(tx).into())
+            .unwrap();
+        rx.await
+            .unwrap()
+            .into_iter()
+            .map(|(id, mode)| (id, mode.to_string()))
+            .collect()
+    }
+
+    /// Checks whether a bond works once the configuration is applied.
+    ///
+    /// A bond is considered healthy if at least one of its ports is active and has carrier. It
+    /// returns whether the bond is healthy and the list of issues found (e.g., ports without
+    /// carrier).
+    ///
+    /// * `id`: bond connection ID.
+    pub async fn verify_bond(&self, id: &str) -> zbus::fdo::Result<(bool, Vec<String>)> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::VerifyBond(id.to_string(), tx).into())
+            .unwrap();
+        let check = rx.await.unwrap()?;
+        let issues = check.issues(id);
+        if !issues.is_empty() {
+            log::warn!("Bond '{}' issues: {}", id, issues.join("; "));
+        }
+        Ok((check.is_healthy(), issues))
+    }
+
     /// Notifies than a new interface has been added.
     #[dbus_interface(signal)]
     pub async fn connection_added(
@@ -123,40 +767,173 @@ impl Connections {
         id: &str,
         path: &ObjectPath<'_>,
     ) -> zbus::Result<()>;
+
+    /// Notifies that a connection has been updated outside Agama (e.g., using nmcli).
+    #[dbus_interface(signal)]
+    pub async fn connection_updated(
+        ctxt: &SignalContext<'_>,
+        id: &str,
+        path: &ObjectPath<'_>,
+    ) -> zbus::Result<()>;
+
+    /// Notifies that a connection has been removed outside Agama (e.g., using nmcli).
+    #[dbus_interface(signal)]
+    pub async fn connection_removed(ctxt: &SignalContext<'_>, id: &str) -> zbus::Result<()>;
+
+    /// Notifies that the configuration has been applied.
+    ///
+    /// * `added`: number of added connections.
+    /// * `updated`: number of updated connections.
+    /// * `removed`: number of removed connections.
+    /// * `failed`: IDs of the connections that could not be written.
+    #[dbus_interface(signal)]
+    pub async fn applied_configuration(
+        ctxt: &SignalContext<'_>,
+        added: u32,
+        updated: u32,
+        removed: u32,
+        failed: &[String],
+    ) -> zbus::Result<()>;
+}
+
+/// Converts a connection summary into its D-Bus representation.
+///
+/// * `summary`: connection summary.
+/// * `path`: D-Bus path of the connection.
+fn summary_to_dbus(
+    summary: ConnectionSummary,
+    path: OwnedObjectPath,
+) -> HashMap<String, OwnedValue> {
+    let str_value = |value: String| OwnedValue::from(zvariant::Str::from(value));
+    HashMap::from([
+        ("Id".to_string(), str_value(summary.id)),
+        ("Uuid".to_string(), str_value(summary.uuid.to_string())),
+        ("Path".to_string(), OwnedValue::from(path.into_inner())),
+        ("Type".to_string(), str_value(summary.type_name.to_string())),
+        (
+            "Interface".to_string(),
+            str_value(summary.interface.unwrap_or_default()),
+        ),
+        ("Method4".to_string(), str_value(summary.method4.to_dbus())),
+        ("Method6".to_string(), str_value(summary.method6.to_dbus())),
+        ("State".to_string(), str_value(summary.status.to_string())),
+    ])
+}
+
+/// Reads a device type from a D-Bus value.
+///
+/// * `value`: device type as a number (see [DeviceType]) or as a name (e.g., "ethernet").
+fn device_type_from_value(value: &zvariant::Value<'_>) -> zbus::fdo::Result<DeviceType> {
+    match value {
+        zvariant::Value::U8(number) => Ok(DeviceType::try_from(*number)?),
+        zvariant::Value::Str(name) => Ok(DeviceType::from_str(name)?),
+        _ => Err(zbus::fdo::Error::InvalidArgs(format!(
+            "Invalid device type: {:?}",
+            value
+        ))),
+    }
+}
+
+/// Builds a connection template from its D-Bus representation.
+///
+/// * `template`: template as received through D-Bus.
+fn template_from_dbus(
+    template: &HashMap<String, OwnedValue>,
+) -> Result<ConnectionTemplate, NetworkStateError> {
+    let invalid = |key: &str| NetworkStateError::InvalidTemplate(format!("invalid '{key}'"));
+    let get_str = |key: &str| -> Result<Option<String>, NetworkStateError> {
+        template
+            .get(key)
+            .map(|v| {
+                v.downcast_ref::<str>()
+                    .map(|s| s.to_string())
+                    .ok_or(invalid(key))
+            })
+            .transpose()
+    };
+
+    let ty = template
+        .get("Type")
+        .ok_or(NetworkStateError::InvalidTemplate(
+            "missing 'Type'".to_string(),
+        ))?;
+    let ty = device_type_from_value(ty).map_err(|_| invalid("Type"))?;
+    let mut result = ConnectionTemplate::new(ty);
+
+    if let Some(id) = get_str("Id")? {
+        result.id = id;
+    }
+    if let Some(method) = get_str("Method4")? {
+        result.ip_config.method4 =
+            Ipv4Method::from_dbus(&method).map_err(|_| invalid("Method4"))?;
+    }
+    if let Some(method) = get_str("Method6")? {
+        result.ip_config.method6 =
+            Ipv6Method::from_dbus(&method).map_err(|_| invalid("Method6"))?;
+    }
+    if let Some(nameservers) = template.get("Nameservers") {
+        let nameservers = nameservers
+            .downcast_ref::<zvariant::Array>()
+            .ok_or(invalid("Nameservers"))?;
+        for nameserver in nameservers.get() {
+            let nameserver = nameserver
+                .downcast_ref::<str>()
+                .and_then(|s| s.parse().ok())
+                .ok_or(invalid("Nameservers"))?;
+            result.ip_config.nameservers.push(nameserver);
+        }
+    }
+    result.controller = get_str("Controller")?;
+
+    Ok(result)
 }
 
 /// D-Bus interface for a network connection
 ///
 /// It offers an API to query a connection.
 pub struct Connection {
-    actions: Arc<Mutex<UnboundedSender<Action>>>,
+    dispatcher: Dispatcher,
     uuid: Uuid,
+    access: AccessPolicy,
 }
 
 impl Connection {
     /// Creates a Connection interface object.
     ///
-    /// * `actions`: sending-half of a channel to send actions.
-    /// * `uuid`: network connection's UUID.
-    pub fn new(actions: UnboundedSender<Action>, uuid: Uuid) -> Self {
+    /// * `dispatcher`: dispatcher to send actions and emit signals.
+    /// * `uuid`: connection UUID.
+    /// * `access`: policy to restrict the operations that modify the system.
+    pub fn new(dispatcher: Dispatcher, uuid: Uuid, access: AccessPolicy) -> Self {
         Self {
-            actions: Arc::new(Mutex::new(actions)),
+            dispatcher,
             uuid,
+            access,
         }
     }
 }
 
+impl ConnectionInterface for Connection {
+    fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn dispatcher(&self) -> &Dispatcher {
+        &self.dispatcher
+    }
+}
+
 #[dbus_interface(name = "org.opensuse.Agama1.Network.Connection")]
 impl Connection {
     /// Connection ID.
     ///
     /// Unique identifier of the network connection. It may or not be the same that the used by the
     /// backend. For instance, when using NetworkManager (which is the only supported backend by
-    /// now), it uses the original ID but appending a number in case the ID is duplicated.
+    /// now), duplicated IDs are resolved according to the `ConflictPolicy` property of the
+    /// connections collection (by default, appending a number).
     #[dbus_interface(property)]
     pub async fn id(&self) -> zbus::fdo::Result<String> {
         let connection = self.get_connection().await?;
-        Ok(connection.id)
+        Ok(connection.id.clone())
     }
 
     /// Connection UUID.
@@ -168,6 +945,19 @@ impl Connection {
         self.uuid.to_string()
     }
 
+    /// Error from the last time the connection was written (empty if it did not fail).
+    ///
+    /// It is set when the connection cannot be written or activated while applying the
+    /// configuration, and it is cleared once it is written successfully.
+    #[dbus_interface(property)]
+    pub async fn last_error(&self) -> String {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetLastError(self.uuid, tx).into())
+            .unwrap();
+        rx.await.unwrap().unwrap_or_default()
+    }
+
     #[dbus_interface(property)]
     pub async fn controller(&self) -> zbus::fdo::Result<String> {
         let connection = self.get_connection().await?;
@@ -181,7 +971,7 @@ impl Connection {
     #[dbus_interface(property)]
     pub async fn interface(&self) -> zbus::fdo::Result<String> {
         let connection = self.get_connection().await?;
-        Ok(connection.interface.unwrap_or_default())
+        Ok(connection.interface.clone().unwrap_or_default())
     }
 
     #[dbus_interface(property)]
@@ -206,122 +996,286 @@ impl Connection {
         Ok(())
     }
 
-    /// Whether the network interface should be active or not
+    /// Multicast DNS (mDNS) mode.
+    ///
+    /// Possible values: "default", "no", "resolve" or "yes".
+    ///
+    /// See [crate::network::model::ResolveMode].
     #[dbus_interface(property)]
-    pub async fn active(&self) -> zbus::fdo::Result<bool> {
+    pub async fn mdns(&self) -> zbus::fdo::Result<String> {
         let connection = self.get_connection().await?;
-        Ok(connection.is_up())
+        Ok(connection.mdns.to_dbus())
     }
 
     #[dbus_interface(property)]
-    pub async fn set_active(&mut self, active: bool) -> zbus::fdo::Result<()> {
-        self.update_connection(|c| {
-            if active {
-                c.set_up();
-            } else {
-                c.set_down();
-            }
-        })
-        .await?;
+    pub async fn set_mdns(&mut self, mode: &str) -> zbus::fdo::Result<()> {
+        let mode = ResolveMode::from_dbus(mode)?;
+        self.update_connection(|c| c.mdns = mode).await?;
         Ok(())
     }
-}
 
-#[async_trait]
-impl ConnectionInterface for Connection {
-    fn uuid(&self) -> Uuid {
-        self.uuid
+    /// Link-Local Multicast Name Resolution (LLMNR) mode.
+    ///
+    /// Possible values: "default", "no", "resolve" or "yes".
+    ///
+    /// See [crate::network::model::ResolveMode].
+    #[dbus_interface(property)]
+    pub async fn llmnr_mode(&self) -> zbus::fdo::Result<String> {
+        let connection = self.get_connection().await?;
+        Ok(connection.llmnr.to_dbus())
     }
 
-    async fn actions(&self) -> MutexGuard<UnboundedSender<Action>> {
-        self.actions.lock().await
+    #[dbus_interface(property)]
+    pub async fn set_llmnr_mode(&mut self, mode: &str) -> zbus::fdo::Result<()> {
+        let mode = ResolveMode::from_dbus(mode)?;
+        self.update_connection(|c| c.llmnr = mode).await?;
+        Ok(())
     }
-}
 
-/// D-Bus interface for Match settings
-pub struct Match {
-    actions: Arc<Mutex<UnboundedSender<Action>>>,
-    uuid: Uuid,
-}
+    /// Whether to listen for LLDP packets.
+    ///
+    /// The discovered neighbors (e.g., the switch the device is connected to) can be queried
+    /// through the `GetNeighbors` method of the devices collection.
+    #[dbus_interface(property)]
+    pub async fn lldp(&self) -> zbus::fdo::Result<bool> {
+        let connection = self.get_connection().await?;
+        Ok(connection.lldp)
+    }
 
-impl Match {
-    /// Creates a Match Settings interface object.
+    #[dbus_interface(property)]
+    pub async fn set_lldp(&mut self, value: bool) -> zbus::fdo::Result<()> {
+        self.update_connection(|c| c.lldp = value).await?;
+        Ok(())
+    }
+
+    /// Whether the connection is managed outside Agama.
     ///
-    /// * `actions`: sending-half of a channel to send actions.
-    /// * `uuid`: nework connection's UUID.
-    pub fn new(actions: UnboundedSender<Action>, uuid: Uuid) -> Self {
-        Self {
-            actions: Arc::new(Mutex::new(actions)),
-            uuid,
-        }
+    /// Externally managed connections are not written when applying the configuration. It is
+    /// useful, for instance, to keep the connection used by a remote (e.g., SSH) session.
+    #[dbus_interface(property)]
+    pub async fn externally_managed(&self) -> zbus::fdo::Result<bool> {
+        let connection = self.get_connection().await?;
+        Ok(connection.externally_managed)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_externally_managed(&mut self, value: bool) -> zbus::fdo::Result<()> {
+        self.update_connection(|c| c.externally_managed = value)
+            .await?;
+        Ok(())
+    }
+
+    /// Whether the connection should only be configured in the installed system.
+    ///
+    /// Such connections are written but not activated in the live system. It is useful when the
+    /// final network (e.g., a production VLAN) must not be used during the installation.
+    #[dbus_interface(property)]
+    pub async fn only_on_target(&self) -> zbus::fdo::Result<bool> {
+        let connection = self.get_connection().await?;
+        Ok(connection.only_on_target)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_only_on_target(&mut self, value: bool) -> zbus::fdo::Result<()> {
+        self.update_connection(|c| c.only_on_target = value).await?;
+        Ok(())
     }
-}
 
-#[dbus_interface(name = "org.opensuse.Agama1.Network.Connection.Match")]
-impl Match {
-    /// List of driver names to match.
+    /// Whether the installed system must wait for the connection when booting.
+    ///
+    /// The services that need the network wait for the required connections only. Marking a
+    /// connection as optional avoids delaying the boot when its port is unplugged.
     #[dbus_interface(property)]
-    pub async fn driver(&self) -> zbus::fdo::Result<Vec<String>> {
+    pub async fn required_for_online(&self) -> zbus::fdo::Result<bool> {
         let connection = self.get_connection().await?;
-        Ok(connection.match_config.driver)
+        Ok(connection.required_for_online)
     }
 
     #[dbus_interface(property)]
-    pub async fn set_driver(&mut self, driver: Vec<String>) -> zbus::fdo::Result<()> {
-        self.update_connection(|c| c.match_config.driver = driver)
+    pub async fn set_required_for_online(&mut self, value: bool) -> zbus::fdo::Result<()> {
+        self.update_connection(|c| c.required_for_online = value)
             .await?;
         Ok(())
     }
 
-    /// List of paths to match agains the ID_PATH udev property of devices.
+    /// Free-form description of the connection. An empty string means no description.
     #[dbus_interface(property)]
-    pub async fn path(&self) -> zbus::fdo::Result<Vec<String>> {
+    pub async fn description(&self) -> zbus::fdo::Result<String> {
         let connection = self.get_connection().await?;
-        Ok(connection.match_config.path)
+        Ok(connection.description.clone().unwrap_or_default())
     }
 
     #[dbus_interface(property)]
-    pub async fn set_path(&mut self, path: Vec<String>) -> zbus::fdo::Result<()> {
-        self.update_connection(|c| c.match_config.path = path)
+    pub async fn set_description(&mut self, description: String) -> zbus::fdo::Result<()> {
+        let description = (!description.is_empty()).then_some(description);
+        self.update_connection(|c| c.description = description)
             .await?;
         Ok(())
     }
-    /// List of interface names to match.
+
+    /// Free-form metadata (e.g., a ticket number or the role of the connection).
+    ///
+    /// The keys can only contain ASCII alphanumeric characters, '-', '_' and '.'.
+    #[dbus_interface(property)]
+    pub async fn metadata(&self) -> zbus::fdo::Result<HashMap<String, String>> {
+        let connection = self.get_connection().await?;
+        Ok(connection.metadata.clone())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_metadata(
+        &mut self,
+        metadata: HashMap<String, String>,
+    ) -> zbus::fdo::Result<()> {
+        NetworkConnection::validate_metadata(&metadata)?;
+        self.update_connection(|c| c.metadata = metadata).await?;
+        Ok(())
+    }
+
+    /// Users allowed to use the connection.
+    ///
+    /// An empty list makes the connection available to all the users. Bear in mind that
+    /// NetworkManager does not activate a restricted connection unless one of the users is
+    /// logged in, so it is usually combined with `OnlyOnTarget`.
+    #[dbus_interface(property)]
+    pub async fn permissions(&self) -> zbus::fdo::Result<Vec<String>> {
+        let connection = self.get_connection().await?;
+        Ok(connection.permissions.clone())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_permissions(&mut self, users: Vec<String>) -> zbus::fdo::Result<()> {
+        NetworkConnection::validate_permissions(&users)?;
+        self.update_connection(|c| c.permissions = users).await?;
+        Ok(())
+    }
+
+    /// Tags to operate on groups of connections (e.g., "storage-net" or "mgmt").
+    ///
+    /// The tags can only contain ASCII alphanumeric characters, '-', '_' and '.'.
     #[dbus_interface(property)]
-    pub async fn interface(&self) -> zbus::fdo::Result<Vec<String>> {
+    pub async fn tags(&self) -> zbus::fdo::Result<Vec<String>> {
         let connection = self.get_connection().await?;
-        Ok(connection.match_config.interface)
+        Ok(connection.tags.clone())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_tags(&mut self, tags: Vec<String>) -> zbus::fdo::Result<()> {
+        NetworkConnection::validate_tags(&tags)?;
+        self.update_connection(|c| c.tags = tags).await?;
+        Ok(())
     }
 
+    /// Time (in seconds) to wait for the connection to be activated when applying the
+    /// configuration.
+    ///
+    /// If it is set to 0, Agama does not wait for the activation.
     #[dbus_interface(property)]
-    pub async fn set_interface(&mut self, interface: Vec<String>) -> zbus::fdo::Result<()> {
-        self.update_connection(|c| c.match_config.interface = interface)
+    pub async fn activation_timeout(&self) -> zbus::fdo::Result<u32> {
+        let connection = self.get_connection().await?;
+        Ok(connection.activation_timeout.unwrap_or_default())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_activation_timeout(&mut self, timeout: u32) -> zbus::fdo::Result<()> {
+        self.update_connection(|c| c.activation_timeout = (timeout > 0).then_some(timeout))
             .await?;
         Ok(())
     }
 
-    /// List of kernel options to match.
+    /// Bandwidth limit (in Kbit/s) of the interface in the live system.
+    ///
+    /// If it is set to 0, the limit of the connections collection applies (see its
+    /// `BandwidthLimit` property). The limit is not written to the installed system.
     #[dbus_interface(property)]
-    pub async fn kernel(&self) -> zbus::fdo::Result<Vec<String>> {
+    pub async fn bandwidth_limit(&self) -> zbus::fdo::Result<u32> {
         let connection = self.get_connection().await?;
-        Ok(connection.match_config.kernel)
+        Ok(connection.bandwidth_limit.unwrap_or_default())
     }
 
     #[dbus_interface(property)]
-    pub async fn set_kernel(&mut self, kernel: Vec<String>) -> zbus::fdo::Result<()> {
-        self.update_connection(|c| c.match_config.kernel = kernel)
+    pub async fn set_bandwidth_limit(&mut self, limit: u32) -> zbus::fdo::Result<()> {
+        self.update_connection(|c| c.bandwidth_limit = (limit > 0).then_some(limit))
             .await?;
         Ok(())
     }
-}
 
-#[async_trait]
-impl ConnectionInterface for Match {
-    fn uuid(&self) -> Uuid {
-        self.uuid
+    /// Where the secrets (e.g., the wireless password) are stored.
+    ///
+    /// Possible values: "system" (in the connection profile), "agent" (owned by a secret agent,
+    /// e.g., the user's keyring) or "not-saved".
+    ///
+    /// See [crate::network::model::SecretStorage].
+    #[dbus_interface(property)]
+    pub async fn secret_storage(&self) -> zbus::fdo::Result<String> {
+        let connection = self.get_connection().await?;
+        Ok(connection.secret_storage.to_dbus())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_secret_storage(&mut self, storage: &str) -> zbus::fdo::Result<()> {
+        let storage = SecretStorage::from_dbus(storage)?;
+        self.update_connection(|c| c.secret_storage = storage)
+            .await?;
+        Ok(())
+    }
+
+    /// Names of the secrets the connection lacks to be activated (e.g., "psk" or
+    /// "eap-password").
+    ///
+    /// They can be supplied using the `UnlockSecrets` method.
+    #[dbus_interface(property)]
+    pub async fn missing_secrets(&self) -> zbus::fdo::Result<Vec<String>> {
+        let connection = self.get_connection().await?;
+        Ok(connection.missing_secrets())
     }
 
-    async fn actions(&self) -> MutexGuard<UnboundedSender<Action>> {
-        self.actions.lock().await
+    /// Supplies the secrets the connection lacks.
+    ///
+    /// It fails if any of the secrets does not apply to the connection, and none of them is set.
+    ///
+    /// * `secrets`: secrets indexed by their name (see the `MissingSecrets` property).
+    pub async fn unlock_secrets(
+        &mut self,
+        secrets: HashMap<String, String>,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        let mut connection = (*self.get_connection().await?).clone();
+        for (name, value) in &secrets {
+            connection.set_secret(name, value)?;
+        }
+        self.update_connection(|c| c.config = connection.config)
+            .await?;
+        self.dispatcher.emit(
+            ctxt.path().to_owned().into(),
+            Signal::PropertiesChanged {
+                interface: "org.opensuse.Agama1.Network.Connection",
+                properties: vec!["MissingSecrets"],
+            },
+        );
+        Ok(())
+    }
+
+    /// Whether the network interface should be active or not
+    #[dbus_interface(property)]
+    pub async fn active(&self) -> zbus::fdo::Result<bool> {
+        let connection = self.get_connection().await?;
+        Ok(connection.is_up())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_active(&mut self, active: bool) -> zbus::fdo::Result<()> {
+        self.update_connection(|c| {
+            if active {
+                c.set_up();
+            } else {
+                c.set_down();
+            }
+        })
+        .await?;
+        Ok(())
     }
 }
@@ -0,0 +1,88 @@
+use crate::network::{dbus::Dispatcher, model::ConnectivityConfig, Action};
+use tokio::sync::oneshot;
+use zbus::dbus_interface;
+
+/// D-Bus interface for the connectivity check settings
+///
+/// NetworkManager checks whether the system has access to Internet by periodically fetching a
+/// well-known URI. This interface allows to configure (or disable) such a check.
+pub struct Connectivity {
+    dispatcher: Dispatcher,
+}
+
+impl Connectivity {
+    /// Creates a Connectivity interface object.
+    ///
+    /// * `dispatcher`: dispatcher to send actions and emit signals.
+    pub fn new(dispatcher: Dispatcher) -> Self {
+        Self { dispatcher }
+    }
+
+    /// Returns the current connectivity check settings.
+    async fn get_config(&self) -> ConnectivityConfig {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetConnectivity(tx).into())
+            .unwrap();
+        rx.await.unwrap()
+    }
+
+    /// Updates the connectivity check settings.
+    ///
+    /// * `func`: function to update the settings.
+    async fn update_config<F>(&self, func: F)
+    where
+        F: FnOnce(&mut ConnectivityConfig),
+    {
+        let mut config = self.get_config().await;
+        func(&mut config);
+        self.dispatcher
+            .send(Action::UpdateConnectivity(Box::new(config)).into())
+            .unwrap();
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Connectivity")]
+impl Connectivity {
+    /// Determines whether the system has access to Internet.
+    pub async fn is_online(&self) -> zbus::fdo::Result<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher.send(Action::IsOnline(tx).into()).unwrap();
+        Ok(rx.await.unwrap()?)
+    }
+
+    /// Whether the connectivity check is enabled.
+    #[dbus_interface(property)]
+    pub async fn enabled(&self) -> bool {
+        self.get_config().await.enabled
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_enabled(&mut self, enabled: bool) {
+        self.update_config(|c| c.enabled = enabled).await
+    }
+
+    /// URI to use for the connectivity check. An empty string means the default one.
+    #[dbus_interface(property)]
+    pub async fn uri(&self) -> String {
+        self.get_config().await.uri.unwrap_or_default()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_uri(&mut self, uri: String) {
+        let uri = if uri.is_empty() { None } else { Some(uri) };
+        self.update_config(|c| c.uri = uri).await
+    }
+
+    /// Interval (in seconds) between connectivity checks. 0 means the default one.
+    #[dbus_interface(property)]
+    pub async fn interval(&self) -> u32 {
+        self.get_config().await.interval.unwrap_or_default()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_interval(&mut self, interval: u32) {
+        let interval = if interval == 0 { None } else { Some(interval) };
+        self.update_config(|c| c.interval = interval).await
+    }
+}
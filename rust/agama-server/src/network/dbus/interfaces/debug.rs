@@ -0,0 +1,130 @@
+use crate::{
+    access::AccessPolicy,
+    network::{dbus::Dispatcher, model::Connection, Action},
+};
+use serde::Deserialize;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+use zbus::{dbus_interface, MessageHeader};
+
+/// Actions that can be injected through the [Debug] interface.
+///
+/// They are written in JSON, using the `action` key for the action name (e.g.,
+/// `{ "action": "removeConnection", "uuid": "..." }`).
+#[derive(Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+enum DebugAction {
+    /// Synchronizes the connections with the ones in the backend.
+    SyncConnections,
+    /// Replaces the connection with the same UUID (in the serialized form of [Connection]).
+    UpdateConnection { connection: Box<Connection> },
+    /// Removes the connection with the given UUID.
+    RemoveConnection { uuid: Uuid },
+    /// Sets the names of the devices to ignore.
+    SetIgnoredDevices { devices: Vec<String> },
+}
+
+impl From<DebugAction> for Action {
+    fn from(value: DebugAction) -> Self {
+        match value {
+            DebugAction::SyncConnections => Action::SyncConnections,
+            DebugAction::UpdateConnection { connection } => Action::UpdateConnection(connection),
+            DebugAction::RemoveConnection { uuid } => Action::RemoveConnection(uuid),
+            DebugAction::SetIgnoredDevices { devices } => Action::SetIgnoredDevices(devices),
+        }
+    }
+}
+
+/// D-Bus interface to inspect and manipulate the network service during troubleshooting.
+///
+/// It is only available when the `network-debug` feature is enabled and, as it might expose
+/// sensitive information, the access policy applies to all its methods.
+pub struct Debug {
+    dispatcher: Dispatcher,
+    access: AccessPolicy,
+}
+
+impl Debug {
+    /// Creates a Debug interface object.
+    ///
+    /// * `dispatcher`: dispatcher to send actions and emit signals.
+    /// * `access`: policy to restrict the callers.
+    pub fn new(dispatcher: Dispatcher, access: AccessPolicy) -> Self {
+        Self { dispatcher, access }
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Debug")]
+impl Debug {
+    /// Returns a JSON representation of the network state.
+    ///
+    /// It includes the connections (without secrets), the devices and the global settings.
+    pub async fn dump_state(
+        &self,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        self.access.check(connection, &header).await?;
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher.send(Action::DumpState(tx).into()).unwrap();
+        Ok(rx.await.unwrap())
+    }
+
+    /// Returns the last processed actions (request ID and action name), from the oldest to
+    /// the newest.
+    pub async fn dump_actions(
+        &self,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<Vec<(String, String)>> {
+        self.access.check(connection, &header).await?;
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::DumpActions(tx).into())
+            .unwrap();
+        Ok(rx.await.unwrap())
+    }
+
+    /// Injects an action into the network service queue.
+    ///
+    /// The supported actions are `syncConnections`, `updateConnection` (`connection`),
+    /// `removeConnection` (`uuid`) and `setIgnoredDevices` (`devices`).
+    ///
+    /// * `action`: action in JSON format (e.g., `{ "action": "syncConnections" }`).
+    pub async fn inject_action(
+        &self,
+        action: &str,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        let action: DebugAction = serde_json::from_str(action)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid action: {}", e)))?;
+        let action = Action::from(action);
+        log::warn!("Injecting action {}", action.name());
+        self.dispatcher.send(action.into()).unwrap();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_action_from_json() {
+        let action: DebugAction =
+            serde_json::from_str(r#"{ "action": "syncConnections" }"#).unwrap();
+        assert!(matches!(Action::from(action), Action::SyncConnections));
+
+        let json = r#"{ "action": "setIgnoredDevices", "devices": ["eno1"] }"#;
+        let action: DebugAction = serde_json::from_str(json).unwrap();
+        assert!(matches!(Action::from(action), Action::SetIgnoredDevices(d) if d == vec!["eno1"]));
+
+        let json = r#"{ "action": "updateConnection", "connection": { "id": "eth0" } }"#;
+        let action: DebugAction = serde_json::from_str(json).unwrap();
+        assert!(matches!(Action::from(action), Action::UpdateConnection(c) if c.id == "eth0"));
+
+        assert!(serde_json::from_str::<DebugAction>(r#"{ "action": "apply" }"#).is_err());
+    }
+}
@@ -1,23 +1,37 @@
-use crate::network::{model::Device as NetworkDevice, Action};
-use std::sync::Arc;
-use tokio::sync::{mpsc::UnboundedSender, oneshot, Mutex};
-use zbus::{dbus_interface, zvariant::OwnedObjectPath};
+use crate::{
+    access::AccessPolicy,
+    network::{
+        dbus::Dispatcher,
+        model::{
+            Device as NetworkDevice, LldpNeighbor, ModemCapabilities, RouterAdvertisement,
+            RouterPrefix, WirelessCapabilities,
+        },
+        Action,
+    },
+};
+use std::collections::HashMap;
+use tokio::sync::oneshot;
+use zbus::{
+    dbus_interface,
+    zvariant::{OwnedObjectPath, OwnedValue, Str, Value},
+    MessageHeader,
+};
 
 /// D-Bus interface for the network devices collection
 ///
 /// It offers an API to query the devices collection.
 pub struct Devices {
-    actions: Arc<Mutex<UnboundedSender<Action>>>,
+    dispatcher: Dispatcher,
+    access: AccessPolicy,
 }
 
 impl Devices {
     /// Creates a Devices interface object.
     ///
-    /// * `objects`: Objects paths registry.
-    pub fn new(actions: UnboundedSender<Action>) -> Self {
-        Self {
-            actions: Arc::new(Mutex::new(actions)),
-        }
+    /// * `dispatcher`: dispatcher to send actions.
+    /// * `access`: policy to restrict the operations that modify the system.
+    pub fn new(dispatcher: Dispatcher, access: AccessPolicy) -> Self {
+        Self { dispatcher, access }
     }
 }
 
@@ -25,27 +39,183 @@ impl Devices {
 impl Devices {
     /// Returns the D-Bus paths of the network devices.
     pub async fn get_devices(&self) -> zbus::fdo::Result<Vec<OwnedObjectPath>> {
-        let actions = self.actions.lock().await;
         let (tx, rx) = oneshot::channel();
-        actions.send(Action::GetDevicesPaths(tx)).unwrap();
+        self.dispatcher
+            .send(Action::GetDevicesPaths(tx).into())
+            .unwrap();
         let result = rx.await.unwrap();
         Ok(result)
     }
+
+    /// Returns the neighbors discovered through LLDP on a device (e.g., the switch it is
+    /// connected to).
+    ///
+    /// Each element contains the "ChassisId" and the "PortId" and, if known, the "SystemName",
+    /// the "PortDescription" and the "VlanId". LLDP must be enabled in the device's connection.
+    ///
+    /// * `device`: device name (e.g., "eth0").
+    pub async fn get_neighbors(
+        &self,
+        device: &str,
+    ) -> zbus::fdo::Result<Vec<HashMap<String, OwnedValue>>> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetLldpNeighbors(device.to_string(), tx).into())
+            .unwrap();
+        let neighbors = rx.await.unwrap()?;
+        Ok(neighbors.iter().map(neighbor_to_dbus).collect())
+    }
+
+    /// Returns the IPv6 router advertisements received on a device.
+    ///
+    /// A router solicitation is sent through the device, so the answers reflect the current
+    /// routers configuration. Each element contains the "Router" (link-local address), the
+    /// "RouterLifetime" (in seconds, 0 if it is not a default router), the "Managed" and
+    /// "OtherConfig" flags (DHCPv6), the "Prefixes", the "DnsServers" (RDNSS) and, if
+    /// advertised, the "Mtu". Each prefix contains the "Prefix", the "OnLink" and "Autonomous"
+    /// (SLAAC) flags, the "ValidLifetime" and the "PreferredLifetime".
+    ///
+    /// * `device`: device name (e.g., "eth0").
+    pub async fn get_ipv6_ra_info(
+        &self,
+        device: &str,
+    ) -> zbus::fdo::Result<Vec<HashMap<String, OwnedValue>>> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetIpv6RaInfo(device.to_string(), tx).into())
+            .unwrap();
+        let advertisements = rx.await.unwrap()?;
+        Ok(advertisements.iter().map(advertisement_to_dbus).collect())
+    }
+
+    /// Returns the names of the ignored devices.
+    pub async fn get_ignored_devices(&self) -> Vec<String> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetIgnoredDevices(tx).into())
+            .unwrap();
+        rx.await.unwrap()
+    }
+
+    /// Sets the devices to ignore.
+    ///
+    /// Ignored devices are not included in the devices collection and the connections bound to
+    /// them are not written when applying the configuration.
+    ///
+    /// * `names`: names of the devices (e.g., "eth1").
+    pub async fn set_ignored_devices(
+        &self,
+        names: Vec<String>,
+        #[zbus(connection)] connection: &zbus::Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        self.dispatcher
+            .send(Action::SetIgnoredDevices(names).into())
+            .unwrap();
+        Ok(())
+    }
+}
+
+/// Converts a LLDP neighbor to a D-Bus dictionary.
+///
+/// * `neighbor`: LLDP neighbor.
+fn neighbor_to_dbus(neighbor: &LldpNeighbor) -> HashMap<String, OwnedValue> {
+    let mut dict = HashMap::from([
+        (
+            "ChassisId".to_string(),
+            OwnedValue::from(Str::from(neighbor.chassis_id.clone())),
+        ),
+        (
+            "PortId".to_string(),
+            OwnedValue::from(Str::from(neighbor.port_id.clone())),
+        ),
+    ]);
+    if let Some(name) = &neighbor.system_name {
+        dict.insert(
+            "SystemName".to_string(),
+            OwnedValue::from(Str::from(name.clone())),
+        );
+    }
+    if let Some(description) = &neighbor.port_description {
+        dict.insert(
+            "PortDescription".to_string(),
+            OwnedValue::from(Str::from(description.clone())),
+        );
+    }
+    if let Some(vlan_id) = neighbor.vlan_id {
+        dict.insert("VlanId".to_string(), OwnedValue::from(vlan_id));
+    }
+    dict
+}
+
+/// Converts a router advertisement to a D-Bus dictionary.
+///
+/// * `ra`: router advertisement.
+fn advertisement_to_dbus(ra: &RouterAdvertisement) -> HashMap<String, OwnedValue> {
+    let prefixes: Vec<HashMap<String, OwnedValue>> =
+        ra.prefixes.iter().map(prefix_to_dbus).collect();
+    let dns_servers: Vec<String> = ra.dns_servers.iter().map(|s| s.to_string()).collect();
+    let mut dict = HashMap::from([
+        (
+            "Router".to_string(),
+            OwnedValue::from(Str::from(ra.router.to_string())),
+        ),
+        (
+            "RouterLifetime".to_string(),
+            OwnedValue::from(ra.router_lifetime),
+        ),
+        ("Managed".to_string(), OwnedValue::from(ra.managed)),
+        ("OtherConfig".to_string(), OwnedValue::from(ra.other_config)),
+        ("Prefixes".to_string(), Value::from(prefixes).into()),
+        ("DnsServers".to_string(), Value::from(dns_servers).into()),
+    ]);
+    if let Some(mtu) = ra.mtu {
+        dict.insert("Mtu".to_string(), OwnedValue::from(mtu));
+    }
+    dict
+}
+
+/// Converts a prefix of a router advertisement to a D-Bus dictionary.
+///
+/// * `prefix`: advertised prefix.
+fn prefix_to_dbus(prefix: &RouterPrefix) -> HashMap<String, OwnedValue> {
+    HashMap::from([
+        (
+            "Prefix".to_string(),
+            OwnedValue::from(Str::from(prefix.prefix.to_string())),
+        ),
+        ("OnLink".to_string(), OwnedValue::from(prefix.on_link)),
+        (
+            "Autonomous".to_string(),
+            OwnedValue::from(prefix.autonomous),
+        ),
+        (
+            "ValidLifetime".to_string(),
+            OwnedValue::from(prefix.valid_lifetime),
+        ),
+        (
+            "PreferredLifetime".to_string(),
+            OwnedValue::from(prefix.preferred_lifetime),
+        ),
+    ])
 }
 
 /// D-Bus interface for a network device
 ///
 /// It offers an API to query basic networking devices information (e.g., the name).
 pub struct Device {
+    dispatcher: Dispatcher,
     device: NetworkDevice,
 }
 
 impl Device {
     /// Creates an interface object.
     ///
+    /// * `dispatcher`: dispatcher to send actions and emit signals.
     /// * `device`: network device.
-    pub fn new(device: NetworkDevice) -> Self {
-        Self { device }
+    pub fn new(dispatcher: Dispatcher, device: NetworkDevice) -> Self {
+        Self { dispatcher, device }
     }
 }
 
@@ -68,4 +238,117 @@ impl Device {
     pub fn device_type(&self) -> u8 {
         self.device.type_ as u8
     }
+
+    /// Whether the device is managed by the backend (e.g., NetworkManager).
+    ///
+    /// Setting it to false takes the device out of the backend's hands (e.g., to pass it through
+    /// to a virtual machine). The change is applied immediately.
+    #[dbus_interface(property)]
+    pub fn managed(&self) -> bool {
+        self.device.managed
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_managed(&mut self, managed: bool) -> zbus::fdo::Result<()> {
+        let (tx, rx) = oneshot::channel();
+        let action = Action::SetDeviceManaged(self.device.name.clone(), managed, tx);
+        self.dispatcher.send(action.into()).unwrap();
+        rx.await.unwrap()?;
+        self.device.managed = managed;
+        Ok(())
+    }
+}
+
+/// D-Bus interface for the wireless capabilities of a network device
+///
+/// It allows to find out which settings are supported by the hardware (e.g., whether it can work
+/// as an access point) before trying to apply them.
+pub struct WirelessDevice {
+    capabilities: WirelessCapabilities,
+}
+
+impl WirelessDevice {
+    /// Creates an interface object.
+    ///
+    /// * `capabilities`: wireless capabilities of the device.
+    pub fn new(capabilities: WirelessCapabilities) -> Self {
+        Self { capabilities }
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Device.Wireless")]
+impl WirelessDevice {
+    /// Supported wireless modes.
+    ///
+    /// Possible values: "infrastructure", "adhoc", "ap" or "mesh".
+    ///
+    /// See [crate::network::model::WirelessMode].
+    #[dbus_interface(property)]
+    pub fn supported_modes(&self) -> Vec<String> {
+        self.capabilities
+            .modes
+            .iter()
+            .map(|m| m.to_string())
+            .collect()
+    }
+
+    /// Supported frequency bands.
+    ///
+    /// Possible values: "bg" (2.4GHz) or "a" (5GHz). It is empty if the information is not
+    /// available.
+    ///
+    /// See [crate::network::model::WirelessBand].
+    #[dbus_interface(property)]
+    pub fn supported_bands(&self) -> Vec<String> {
+        self.capabilities
+            .bands
+            .iter()
+            .map(|b| b.to_string())
+            .collect()
+    }
+
+    /// Supported encryption ciphers.
+    ///
+    /// Possible values: "wep40", "wep104", "tkip" or "ccmp".
+    ///
+    /// See [crate::network::model::WirelessCipher].
+    #[dbus_interface(property)]
+    pub fn supported_ciphers(&self) -> Vec<String> {
+        self.capabilities
+            .ciphers
+            .iter()
+            .map(|c| c.to_string())
+            .collect()
+    }
+}
+
+/// D-Bus interface for the capabilities of a mobile broadband device
+pub struct ModemDevice {
+    capabilities: ModemCapabilities,
+}
+
+impl ModemDevice {
+    /// Creates an interface object.
+    ///
+    /// * `capabilities`: modem capabilities of the device.
+    pub fn new(capabilities: ModemCapabilities) -> Self {
+        Self { capabilities }
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Device.Modem")]
+impl ModemDevice {
+    /// Access technologies the modem currently supports.
+    ///
+    /// Possible values: "pots", "cdma-evdo", "gsm-umts", "lte" or "5gnr".
+    ///
+    /// See [crate::network::model::ModemTechnology].
+    #[dbus_interface(property)]
+    pub fn technologies(&self) -> Vec<String> {
+        self.capabilities
+            .technologies
+            .iter()
+            .map(|t| t.to_string())
+            .collect()
+    }
 }
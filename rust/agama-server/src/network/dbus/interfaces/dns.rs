@@ -0,0 +1,124 @@
+use crate::network::{
+    dbus::Dispatcher,
+    error::NetworkStateError,
+    model::{DnsConfig, DnsPolicy, DnsStack},
+    Action,
+};
+use std::net::IpAddr;
+use tokio::sync::oneshot;
+use zbus::dbus_interface;
+
+/// D-Bus interface for the global DNS settings
+///
+/// The global name servers are combined with the per-connection ones according to a policy
+/// ("append", "prepend" or "override"). The `GetEffectiveDns` method allows to find out which
+/// servers a device would use before applying the configuration. Additionally, it allows to
+/// select the resolver stack of the installed system.
+pub struct Dns {
+    dispatcher: Dispatcher,
+}
+
+impl Dns {
+    /// Creates a Dns interface object.
+    ///
+    /// * `dispatcher`: dispatcher to send actions and emit signals.
+    pub fn new(dispatcher: Dispatcher) -> Self {
+        Self { dispatcher }
+    }
+
+    /// Returns the current DNS settings.
+    async fn get_config(&self) -> DnsConfig {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher.send(Action::GetDns(tx).into()).unwrap();
+        rx.await.unwrap()
+    }
+
+    /// Updates the DNS settings.
+    ///
+    /// * `func`: function to update the settings.
+    async fn update_config<F>(&self, func: F)
+    where
+        F: FnOnce(&mut DnsConfig),
+    {
+        let mut config = self.get_config().await;
+        func(&mut config);
+        self.dispatcher
+            .send(Action::UpdateDns(Box::new(config)).into())
+            .unwrap();
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Dns")]
+impl Dns {
+    /// Returns the name servers that the given device would use once the configuration is
+    /// applied.
+    ///
+    /// * `device`: device name (e.g., "eth0").
+    pub async fn get_effective_dns(&self, device: &str) -> zbus::fdo::Result<Vec<String>> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetEffectiveDns(device.to_string(), tx).into())
+            .unwrap();
+        let servers = rx.await.unwrap()?;
+        Ok(servers.iter().map(IpAddr::to_string).collect())
+    }
+
+    /// Global name servers.
+    #[dbus_interface(property)]
+    pub async fn servers(&self) -> Vec<String> {
+        let config = self.get_config().await;
+        config.servers.iter().map(IpAddr::to_string).collect()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_servers(&mut self, servers: Vec<String>) -> zbus::fdo::Result<()> {
+        let servers = servers
+            .into_iter()
+            .map(|s| s.parse().map_err(|_| NetworkStateError::InvalidIpAddr(s)))
+            .collect::<Result<Vec<IpAddr>, _>>()?;
+        self.update_config(|c| c.servers = servers).await;
+        Ok(())
+    }
+
+    /// Global search domains.
+    #[dbus_interface(property)]
+    pub async fn searches(&self) -> Vec<String> {
+        self.get_config().await.searches
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_searches(&mut self, searches: Vec<String>) {
+        self.update_config(|c| c.searches = searches).await
+    }
+
+    /// Policy to combine the global name servers with the per-connection ones.
+    ///
+    /// Possible values: "append", "prepend" or "override".
+    #[dbus_interface(property)]
+    pub async fn policy(&self) -> String {
+        self.get_config().await.policy.to_string()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_policy(&mut self, policy: &str) -> zbus::fdo::Result<()> {
+        let policy = DnsPolicy::try_from(policy)?;
+        self.update_config(|c| c.policy = policy).await;
+        Ok(())
+    }
+
+    /// DNS resolver stack to use in the installed system.
+    ///
+    /// Possible values: "default" (the product's default), "netconfig", "systemd-resolved" or
+    /// "file".
+    #[dbus_interface(property)]
+    pub async fn stack(&self) -> String {
+        self.get_config().await.stack.to_string()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_stack(&mut self, stack: &str) -> zbus::fdo::Result<()> {
+        let stack = DnsStack::try_from(stack)?;
+        self.update_config(|c| c.stack = stack).await;
+        Ok(())
+    }
+}
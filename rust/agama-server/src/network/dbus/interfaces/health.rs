@@ -0,0 +1,35 @@
+use crate::network::Health as NetworkHealth;
+use std::collections::HashMap;
+use zbus::{dbus_interface, zvariant::OwnedValue};
+
+/// D-Bus interface to check the health of the network service.
+///
+/// Unlike the rest of the interfaces, it does not go through the actions queue, so it answers
+/// even when the service is busy.
+pub struct Health {
+    health: NetworkHealth,
+}
+
+impl Health {
+    /// Creates a Health interface object.
+    ///
+    /// * `health`: health information shared with the network system.
+    pub fn new(health: NetworkHealth) -> Self {
+        Self { health }
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Health")]
+impl Health {
+    /// Answers immediately if the service is alive.
+    pub fn ping(&self) {}
+
+    /// Returns the health information.
+    ///
+    /// It includes the number of pending actions (`PendingActions`), whether NetworkManager could
+    /// be reached (`BackendAvailable`), the outcome of the last apply (`LastApply`) and the
+    /// related error (`LastApplyError`).
+    pub fn get_health(&self) -> HashMap<String, OwnedValue> {
+        self.health.to_dbus()
+    }
+}
@@ -1,45 +1,41 @@
 //! Network D-Bus interfaces for IP configuration.
 //!
-//! This module contains the D-Bus interfaces to deal with IPv4 and IPv6 configuration.
-//! The `dbus_interface` macro should be applied to structs, that's the reason there are
-//! two different structs for IPv4 and IPv6 settings. The common code have been moved
-//! to the `Ip<T>` struct.
+//! This module contains the D-Bus interface to deal with the IPv4 and IPv6 configuration. Both
+//! families are exposed through a single `org.opensuse.Agama1.Network.Connection.IP` interface,
+//! using the `4` and `6` suffixes to tell the properties apart (e.g., `Method4` and `Method6`).
 use crate::network::{
-    action::Action,
+    dbus::types::DBusString,
     error::NetworkStateError,
-    model::{IpConfig, Ipv4Method, Ipv6Method},
+    model::{Connection as NetworkConnection, IpConfig, Ipv4Method, Ipv6AddrGenMode, Ipv6Method},
 };
-use async_trait::async_trait;
 use cidr::IpInet;
-use std::{net::IpAddr, sync::Arc};
-use tokio::sync::mpsc::UnboundedSender;
-use tokio::sync::{Mutex, MutexGuard};
-use uuid::Uuid;
+use std::{net::IpAddr, ops::Deref, sync::Arc};
 use zbus::dbus_interface;
 
-use super::common::ConnectionInterface;
+use super::common::{connection_interface, ConnectionInterface};
 
-/// D-Bus interface for IPv4 and IPv6 settings
-pub struct Ip {
-    actions: Arc<Mutex<UnboundedSender<Action>>>,
-    uuid: Uuid,
-}
+/// IP configuration of a connection snapshot
+///
+/// It gives access to the IP configuration without copying it (addresses, routes, etc.).
+struct IpConfigSnapshot(Arc<NetworkConnection>);
 
-impl Ip {
-    /// Creates an IP interface object.
-    ///
-    /// * `actions`: sending-half of a channel to send actions.
-    /// * `uuid`: connection UUID..
-    pub fn new(actions: UnboundedSender<Action>, uuid: Uuid) -> Self {
-        Self {
-            actions: Arc::new(Mutex::new(actions)),
-            uuid,
-        }
+impl Deref for IpConfigSnapshot {
+    type Target = IpConfig;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0.ip_config
     }
+}
+
+connection_interface! {
+    /// D-Bus interface for IPv4 and IPv6 settings
+    Ip
+}
 
-    /// Returns the IpConfig struct.
-    async fn get_ip_config(&self) -> Result<IpConfig, NetworkStateError> {
-        self.get_connection().await.map(|c| c.ip_config)
+impl Ip {
+    /// Returns the IP configuration of the connection.
+    async fn get_ip_config(&self) -> Result<IpConfigSnapshot, NetworkStateError> {
+        self.get_connection().await.map(IpConfigSnapshot)
     }
 
     /// Updates the IpConfig struct.
@@ -76,18 +72,18 @@ impl Ip {
 
     /// IPv4 configuration method.
     ///
-    /// Possible values: "disabled", "auto", "manual" or "link-local".
+    /// Possible values: "disabled", "auto", "manual", "link-local" or "shared".
     ///
     /// See [crate::network::model::Ipv4Method].
     #[dbus_interface(property)]
     pub async fn method4(&self) -> zbus::fdo::Result<String> {
         let ip_config = self.get_ip_config().await?;
-        Ok(ip_config.method4.to_string())
+        Ok(ip_config.method4.to_dbus())
     }
 
     #[dbus_interface(property)]
     pub async fn set_method4(&mut self, method: &str) -> zbus::fdo::Result<()> {
-        let method: Ipv4Method = method.parse()?;
+        let method = Ipv4Method::from_dbus(method)?;
         self.update_ip_config(|ip| ip.method4 = method).await
     }
 
@@ -99,12 +95,12 @@ impl Ip {
     #[dbus_interface(property)]
     pub async fn method6(&self) -> zbus::fdo::Result<String> {
         let ip_config = self.get_ip_config().await?;
-        Ok(ip_config.method6.to_string())
+        Ok(ip_config.method6.to_dbus())
     }
 
     #[dbus_interface(property)]
     pub async fn set_method6(&mut self, method: &str) -> zbus::fdo::Result<()> {
-        let method: Ipv6Method = method.parse()?;
+        let method = Ipv6Method::from_dbus(method)?;
         self.update_ip_config(|ip| ip.method6 = method).await
     }
 
@@ -164,6 +160,95 @@ impl Ip {
         let gateway = helpers::parse_gateway(gateway)?;
         self.update_ip_config(|ip| ip.gateway6 = gateway).await
     }
+
+    /// Metric of the IPv4 routes.
+    ///
+    /// The interface with the lowest metric owns the default route. -1 means using the
+    /// backend's default.
+    #[dbus_interface(property)]
+    pub async fn route_metric4(&self) -> zbus::fdo::Result<i64> {
+        let ip_config = self.get_ip_config().await?;
+        Ok(helpers::route_metric_to_dbus(ip_config.route_metric4))
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_route_metric4(&mut self, metric: i64) -> zbus::fdo::Result<()> {
+        let metric = helpers::parse_route_metric(metric)?;
+        self.update_ip_config(|ip| ip.route_metric4 = metric).await
+    }
+
+    /// Metric of the IPv6 routes.
+    ///
+    /// The interface with the lowest metric owns the default route. -1 means using the
+    /// backend's default.
+    #[dbus_interface(property)]
+    pub async fn route_metric6(&self) -> zbus::fdo::Result<i64> {
+        let ip_config = self.get_ip_config().await?;
+        Ok(helpers::route_metric_to_dbus(ip_config.route_metric6))
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_route_metric6(&mut self, metric: i64) -> zbus::fdo::Result<()> {
+        let metric = helpers::parse_route_metric(metric)?;
+        self.update_ip_config(|ip| ip.route_metric6 = metric).await
+    }
+
+    /// Whether to never use this connection for the IPv4 default route.
+    #[dbus_interface(property)]
+    pub async fn never_default4(&self) -> zbus::fdo::Result<bool> {
+        let ip_config = self.get_ip_config().await?;
+        Ok(ip_config.never_default4)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_never_default4(&mut self, value: bool) -> zbus::fdo::Result<()> {
+        self.update_ip_config(|ip| ip.never_default4 = value).await
+    }
+
+    /// Whether to never use this connection for the IPv6 default route.
+    #[dbus_interface(property)]
+    pub async fn never_default6(&self) -> zbus::fdo::Result<bool> {
+        let ip_config = self.get_ip_config().await?;
+        Ok(ip_config.never_default6)
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_never_default6(&mut self, value: bool) -> zbus::fdo::Result<()> {
+        self.update_ip_config(|ip| ip.never_default6 = value).await
+    }
+
+    /// IPv6 tokenized interface identifier (e.g., "::10").
+    ///
+    /// It is used as the host part of the addresses built through SLAAC. Only the lower 64 bits
+    /// can be set. An empty string removes the current value.
+    #[dbus_interface(property)]
+    pub async fn token6(&self) -> zbus::fdo::Result<String> {
+        let ip_config = self.get_ip_config().await?;
+        Ok(ip_config.token6.map(|t| t.to_string()).unwrap_or_default())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_token6(&mut self, token: String) -> zbus::fdo::Result<()> {
+        let token = helpers::parse_token(token)?;
+        self.update_ip_config(|ip| ip.token6 = token).await
+    }
+
+    /// How to build the IPv6 addresses through SLAAC.
+    ///
+    /// Possible values: "eui64", "stable-privacy" or an empty string to use the default one (see
+    /// the `AddrGenMode` property of the connections collection). It is ignored when a token is
+    /// set, as the token requires "eui64".
+    #[dbus_interface(property)]
+    pub async fn addr_gen_mode6(&self) -> zbus::fdo::Result<String> {
+        let ip_config = self.get_ip_config().await?;
+        Ok(ip_config.addr_gen_mode6.to_dbus())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_addr_gen_mode6(&mut self, mode: String) -> zbus::fdo::Result<()> {
+        let mode = Option::<Ipv6AddrGenMode>::from_dbus(&mode)?;
+        self.update_ip_config(|ip| ip.addr_gen_mode6 = mode).await
+    }
 }
 
 mod helpers {
@@ -171,6 +256,7 @@ mod helpers {
     use log;
     use std::{
         fmt::{Debug, Display},
+        net::Ipv6Addr,
         str::FromStr,
     };
 
@@ -212,15 +298,38 @@ mod helpers {
             Ok(Some(parsed))
         }
     }
-}
 
-#[async_trait]
-impl ConnectionInterface for Ip {
-    fn uuid(&self) -> Uuid {
-        self.uuid
+    /// Converts a route metric to its D-Bus representation (-1 means the default one).
+    ///
+    /// * `metric`: route metric.
+    pub fn route_metric_to_dbus(metric: Option<u32>) -> i64 {
+        metric.map_or(-1, i64::from)
+    }
+
+    /// Parses a route metric from its D-Bus representation.
+    ///
+    /// * `metric`: route metric (-1 means the default one).
+    pub fn parse_route_metric(metric: i64) -> Result<Option<u32>, NetworkStateError> {
+        if metric == -1 {
+            return Ok(None);
+        }
+        let metric =
+            u32::try_from(metric).map_err(|_| NetworkStateError::InvalidRouteMetric(metric))?;
+        Ok(Some(metric))
     }
 
-    async fn actions(&self) -> MutexGuard<UnboundedSender<Action>> {
-        self.actions.lock().await
+    /// Parses an IPv6 token.
+    ///
+    /// The token is only valid if the upper 64 bits are zero.
+    ///
+    /// * `token`: token in textual form (an empty string means no token).
+    pub fn parse_token(token: String) -> Result<Option<Ipv6Addr>, NetworkStateError> {
+        if token.is_empty() {
+            return Ok(None);
+        }
+        match token.parse::<Ipv6Addr>() {
+            Ok(parsed) if parsed.segments()[..4].iter().all(|s| *s == 0) => Ok(Some(parsed)),
+            _ => Err(NetworkStateError::InvalidIpv6Token(token)),
+        }
     }
 }
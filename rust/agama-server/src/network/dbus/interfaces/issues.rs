@@ -0,0 +1,43 @@
+use crate::network::{dbus::Dispatcher, Action};
+use tokio::sync::oneshot;
+use zbus::dbus_interface;
+
+/// Source of the issues (see the `org.opensuse.Agama1.Issues` interface).
+const CONFIG_SOURCE: u32 = 2;
+/// Severity of the issues (see the `org.opensuse.Agama1.Issues` interface).
+const WARN_SEVERITY: u32 = 0;
+
+/// D-Bus interface to report the potential problems in the network configuration
+///
+/// The problems are reported as warnings, so they do not block the installation. The idea is to
+/// catch typos (e.g., a gateway outside the subnet) before applying the configuration.
+pub struct Issues {
+    dispatcher: Dispatcher,
+}
+
+impl Issues {
+    /// Creates an Issues interface object.
+    ///
+    /// * `dispatcher`: dispatcher to send actions and emit signals.
+    pub fn new(dispatcher: Dispatcher) -> Self {
+        Self { dispatcher }
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Issues")]
+impl Issues {
+    /// Issues found in the connections configuration.
+    ///
+    /// Each issue contains the description, the details (the connection ID), the source (2 for
+    /// config) and the severity (0 for warning).
+    #[dbus_interface(property)]
+    pub async fn all(&self) -> Vec<(String, String, u32, u32)> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher.send(Action::GetIssues(tx).into()).unwrap();
+        let issues = rx.await.unwrap();
+        issues
+            .into_iter()
+            .map(|i| (i.description, i.connection, CONFIG_SOURCE, WARN_SEVERITY))
+            .collect()
+    }
+}
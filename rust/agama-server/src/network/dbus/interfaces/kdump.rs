@@ -0,0 +1,103 @@
+use crate::network::{
+    dbus::Dispatcher,
+    error::NetworkStateError,
+    model::{KdumpAddressing, KdumpConfig},
+    Action,
+};
+use tokio::sync::oneshot;
+use zbus::dbus_interface;
+
+/// D-Bus interface for the kdump network settings
+///
+/// These settings are not applied to the live system. They are written to the kdump configuration
+/// of the installed system, so the kdump kernel can save the crash dumps over the network.
+pub struct Kdump {
+    dispatcher: Dispatcher,
+}
+
+impl Kdump {
+    /// Creates a Kdump interface object.
+    ///
+    /// * `dispatcher`: dispatcher to send actions and emit signals.
+    pub fn new(dispatcher: Dispatcher) -> Self {
+        Self { dispatcher }
+    }
+
+    /// Returns the current kdump settings.
+    async fn get_config(&self) -> KdumpConfig {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher.send(Action::GetKdump(tx).into()).unwrap();
+        rx.await.unwrap()
+    }
+
+    /// Updates the kdump settings.
+    ///
+    /// * `func`: function to update the settings.
+    async fn update_config<F>(&self, func: F) -> Result<(), NetworkStateError>
+    where
+        F: FnOnce(&mut KdumpConfig) -> Result<(), NetworkStateError>,
+    {
+        let mut config = self.get_config().await;
+        func(&mut config)?;
+        self.dispatcher
+            .send(Action::UpdateKdump(Box::new(config)).into())
+            .unwrap();
+        Ok(())
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Kdump")]
+impl Kdump {
+    /// Interface to use (e.g., "eth0").
+    ///
+    /// An empty string means using the interface with the default route.
+    #[dbus_interface(property)]
+    pub async fn interface(&self) -> String {
+        self.get_config().await.interface.unwrap_or_default()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_interface(&mut self, interface: String) -> zbus::fdo::Result<()> {
+        self.update_config(|c| {
+            c.interface = Some(interface).filter(|i| !i.is_empty());
+            Ok(())
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// How to configure the addresses of the interface.
+    ///
+    /// Possible values: "dhcp", "dhcp6" or "static" (the addresses of the interface in the
+    /// installed system).
+    #[dbus_interface(property)]
+    pub async fn addressing(&self) -> String {
+        self.get_config().await.addressing.to_string()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_addressing(&mut self, addressing: &str) -> zbus::fdo::Result<()> {
+        let addressing = KdumpAddressing::try_from(addressing)?;
+        self.update_config(|c| {
+            c.addressing = addressing;
+            Ok(())
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Location to save the crash dumps to (e.g., "nfs://server/var/crash").
+    ///
+    /// An empty string means using the kdump default.
+    #[dbus_interface(property)]
+    pub async fn save_dir(&self) -> String {
+        self.get_config().await.save_dir.unwrap_or_default()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_save_dir(&mut self, save_dir: String) -> zbus::fdo::Result<()> {
+        self.update_config(|c| c.set_save_dir(Some(save_dir).filter(|d| !d.is_empty())))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use zbus::dbus_interface;
+
+use super::common::{connection_interface, ConnectionInterface};
+
+connection_interface! {
+    /// D-Bus interface for Match settings
+    Match
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Connection.Match")]
+impl Match {
+    /// List of driver names to match.
+    #[dbus_interface(property)]
+    pub async fn driver(&self) -> zbus::fdo::Result<Vec<String>> {
+        let connection = self.get_connection().await?;
+        Ok(connection.match_config.driver.clone())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_driver(&mut self, driver: Vec<String>) -> zbus::fdo::Result<()> {
+        self.update_connection(|c| c.match_config.driver = driver)
+            .await?;
+        Ok(())
+    }
+
+    /// List of paths to match agains the ID_PATH udev property of devices.
+    #[dbus_interface(property)]
+    pub async fn path(&self) -> zbus::fdo::Result<Vec<String>> {
+        let connection = self.get_connection().await?;
+        Ok(connection.match_config.path.clone())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_path(&mut self, path: Vec<String>) -> zbus::fdo::Result<()> {
+        self.update_connection(|c| c.match_config.path = path)
+            .await?;
+        Ok(())
+    }
+    /// List of interface names to match.
+    #[dbus_interface(property)]
+    pub async fn interface(&self) -> zbus::fdo::Result<Vec<String>> {
+        let connection = self.get_connection().await?;
+        Ok(connection.match_config.interface.clone())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_interface(&mut self, interface: Vec<String>) -> zbus::fdo::Result<()> {
+        self.update_connection(|c| c.match_config.interface = interface)
+            .await?;
+        Ok(())
+    }
+
+    /// List of kernel options to match.
+    #[dbus_interface(property)]
+    pub async fn kernel(&self) -> zbus::fdo::Result<Vec<String>> {
+        let connection = self.get_connection().await?;
+        Ok(connection.match_config.kernel.clone())
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_kernel(&mut self, kernel: Vec<String>) -> zbus::fdo::Result<()> {
+        self.update_connection(|c| c.match_config.kernel = kernel)
+            .await?;
+        Ok(())
+    }
+}
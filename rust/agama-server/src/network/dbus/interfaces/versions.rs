@@ -0,0 +1,41 @@
+use crate::network::dbus::compat::{API_VERSION, DEPRECATIONS, SUPPORTED_VERSIONS};
+use std::collections::HashMap;
+use zbus::dbus_interface;
+
+/// D-Bus interface to find out the versions of the network API.
+///
+/// It does not go through the actions queue, as the information is static. Clients must consider
+/// version 1 when the interface is not available (older services).
+#[derive(Default)]
+pub struct Versions;
+
+impl Versions {
+    /// Creates a Versions interface object.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Versions")]
+impl Versions {
+    /// Current (newest) version of the API.
+    #[dbus_interface(property)]
+    pub fn current(&self) -> u32 {
+        API_VERSION
+    }
+
+    /// Served versions of the API, from the oldest to the newest.
+    #[dbus_interface(property)]
+    pub fn supported(&self) -> Vec<u32> {
+        SUPPORTED_VERSIONS.to_vec()
+    }
+
+    /// Deprecated names which are still served, mapped to their replacements.
+    #[dbus_interface(property)]
+    pub fn deprecated(&self) -> HashMap<String, String> {
+        DEPRECATIONS
+            .iter()
+            .map(|d| (d.name.to_string(), d.replacement.to_string()))
+            .collect()
+    }
+}
@@ -0,0 +1,48 @@
+use crate::network::{dbus::Dispatcher, error::NetworkStateError, Action};
+use tokio::sync::oneshot;
+use zbus::dbus_interface;
+
+/// D-Bus interface for the global wireless settings
+///
+/// Unlike the `org.opensuse.Agama1.Network.Connection.Wireless` interface, the settings in this
+/// interface are not bound to any connection.
+pub struct WirelessSettings {
+    dispatcher: Dispatcher,
+}
+
+impl WirelessSettings {
+    /// Creates a WirelessSettings interface object.
+    ///
+    /// * `dispatcher`: dispatcher to send actions and emit signals.
+    pub fn new(dispatcher: Dispatcher) -> Self {
+        Self { dispatcher }
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Network.Wireless")]
+impl WirelessSettings {
+    /// Wireless regulatory domain.
+    ///
+    /// ISO 3166-1 alpha-2 country code (e.g., "DE"). An empty string means the default (world)
+    /// domain. It is written to the system (and to the installed one) when applying the
+    /// configuration.
+    #[dbus_interface(property)]
+    pub async fn wireless_reg_dom(&self) -> String {
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::GetRegulatoryDomain(tx).into())
+            .unwrap();
+        rx.await.unwrap().unwrap_or_default()
+    }
+
+    #[dbus_interface(property)]
+    pub async fn set_wireless_reg_dom(&mut self, code: String) -> zbus::fdo::Result<()> {
+        let code = if code.is_empty() { None } else { Some(code) };
+        let (tx, rx) = oneshot::channel();
+        self.dispatcher
+            .send(Action::SetRegulatoryDomain(code, tx).into())
+            .unwrap();
+        let result: Result<(), NetworkStateError> = rx.await.unwrap();
+        Ok(result?)
+    }
+}
@@ -0,0 +1,102 @@
+//! Encoding of arbitrary strings as D-Bus object path elements.
+//!
+//! An object path element can only contain the `[A-Za-z0-9_]` characters, so names like
+//! connection IDs (which can include spaces, dots or even emojis when they are derived from an
+//! SSID) cannot be used as they are. This module implements the same scheme used by systemd
+//! (`sd_bus_path_encode`): ASCII alphanumeric characters are kept and any other byte is replaced
+//! by `_` followed by its two hexadecimal digits. An empty string is encoded as `_`.
+//!
+//! The encoding is deterministic and reversible, so the same name always produces the same path.
+
+/// Encodes a string as a valid object path element.
+///
+/// * `name`: string to encode (e.g., "Wired connection 1").
+pub fn escape_path_element(name: &str) -> String {
+    if name.is_empty() {
+        return "_".to_string();
+    }
+
+    let mut escaped = String::with_capacity(name.len());
+    for (i, byte) in name.bytes().enumerate() {
+        // an element starting with a digit is valid, but systemd escapes it anyway
+        if byte.is_ascii_alphabetic() || (byte.is_ascii_digit() && i > 0) {
+            escaped.push(byte as char);
+        } else {
+            escaped.push_str(&format!("_{:02x}", byte));
+        }
+    }
+    escaped
+}
+
+/// Decodes an object path element encoded with [escape_path_element].
+///
+/// It returns `None` if the element is not properly encoded.
+///
+/// * `element`: encoded element.
+pub fn unescape_path_element(element: &str) -> Option<String> {
+    if element == "_" {
+        return Some(String::new());
+    }
+
+    let mut bytes = Vec::with_capacity(element.len());
+    let mut chars = element.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '_' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                if hex.len() != 2 {
+                    return None;
+                }
+                bytes.push(u8::from_str_radix(&hex, 16).ok()?);
+            }
+            c if c.is_ascii_alphanumeric() => bytes.push(c as u8),
+            _ => return None,
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zbus::zvariant::ObjectPath;
+
+    #[test]
+    fn test_escape_path_element() {
+        assert_eq!(escape_path_element("eth0"), "eth0");
+        assert_eq!(
+            escape_path_element("Wired connection 1"),
+            "Wired_20connection_201"
+        );
+        assert_eq!(escape_path_element("my_wifi"), "my_5fwifi");
+        assert_eq!(escape_path_element("0wifi"), "_30wifi");
+        assert_eq!(escape_path_element("café"), "caf_c3_a9");
+        assert_eq!(escape_path_element(""), "_");
+    }
+
+    #[test]
+    fn test_escaped_elements_are_valid() {
+        for name in [
+            "Wired connection 1",
+            "Café 📶",
+            "",
+            "a/b",
+            "5G-network.home",
+        ] {
+            let path = format!("/org/opensuse/Agama1/{}", escape_path_element(name));
+            assert!(ObjectPath::try_from(path.as_str()).is_ok(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_unescape_path_element() {
+        for name in ["Wired connection 1", "Café 📶", "", "my_wifi", "0wifi"] {
+            let escaped = escape_path_element(name);
+            assert_eq!(unescape_path_element(&escaped), Some(name.to_string()));
+        }
+
+        assert_eq!(unescape_path_element("wifi_2"), None);
+        assert_eq!(unescape_path_element("wifi_zz"), None);
+        assert_eq!(unescape_path_element("wi-fi"), None);
+    }
+}
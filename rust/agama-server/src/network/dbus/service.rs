@@ -1,9 +1,12 @@
 //! Network D-Bus service.
 //!
 //! This module defines a D-Bus service which exposes Agama's network configuration.
-use crate::network::{Adapter, NetworkSystem};
+use crate::{
+    access::AccessPolicy,
+    network::{Adapter, NetworkSystem, TrackedAction},
+};
 use std::error::Error;
-use tokio;
+use tokio::{self, sync::mpsc::UnboundedSender};
 use zbus::Connection;
 
 /// Represents the Agama networking D-Bus service.
@@ -17,7 +20,21 @@ impl NetworkService {
         connection: &Connection,
         adapter: T,
     ) -> Result<(), Box<dyn Error>> {
-        let mut network = NetworkSystem::new(connection.clone(), adapter);
+        Self::start_with_access(connection, adapter, AccessPolicy::default()).await?;
+        Ok(())
+    }
+
+    /// Starts listening and dispatching events on the D-Bus connection, restricting the
+    /// operations that modify the system according to the given policy.
+    ///
+    /// It returns the sending-half of the channel to send actions to the [NetworkSystem].
+    pub async fn start_with_access<T: Adapter + std::marker::Send + 'static>(
+        connection: &Connection,
+        adapter: T,
+        access: AccessPolicy,
+    ) -> Result<UnboundedSender<TrackedAction>, Box<dyn Error>> {
+        let mut network = NetworkSystem::new(connection.clone(), adapter, access);
+        let actions = network.actions_tx();
 
         tokio::spawn(async move {
             network
@@ -27,6 +44,6 @@ impl NetworkService {
 
             network.listen().await;
         });
-        Ok(())
+        Ok(actions)
     }
 }
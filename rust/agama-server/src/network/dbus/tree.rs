@@ -2,19 +2,31 @@ use agama_lib::error::ServiceError;
 use uuid::Uuid;
 use zbus::zvariant::{ObjectPath, OwnedObjectPath};
 
-use crate::network::{action::Action, dbus::interfaces, model::*};
+use crate::{
+    access::AccessPolicy,
+    network::{
+        action::TrackedAction,
+        dbus::{interfaces, path::escape_path_element, Dispatcher, Signal},
+        model::*,
+        CancellationFlag, Health,
+    },
+};
 use log;
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::mpsc::UnboundedSender;
 
 const CONNECTIONS_PATH: &str = "/org/opensuse/Agama1/Network/connections";
 const DEVICES_PATH: &str = "/org/opensuse/Agama1/Network/devices";
+pub(crate) const NETWORK_PATH: &str = "/org/opensuse/Agama1/Network";
 
 /// Handle the objects in the D-Bus tree for the network state
 pub struct Tree {
     connection: zbus::Connection,
-    actions: UnboundedSender<Action>,
+    dispatcher: Dispatcher,
     objects: ObjectsRegistry,
+    access: AccessPolicy,
+    apply_cancellation: CancellationFlag,
+    health: Health,
 }
 
 impl Tree {
@@ -22,11 +34,23 @@ impl Tree {
     ///
     /// * `connection`: D-Bus connection to use.
     /// * `actions`: sending-half of a channel to send actions.
-    pub fn new(connection: zbus::Connection, actions: UnboundedSender<Action>) -> Self {
+    /// * `access`: policy to restrict the operations that modify the system.
+    /// * `apply_cancellation`: flag to cancel applying the configuration.
+    /// * `health`: health information to expose.
+    pub fn new(
+        connection: zbus::Connection,
+        actions: UnboundedSender<TrackedAction>,
+        access: AccessPolicy,
+        apply_cancellation: CancellationFlag,
+        health: Health,
+    ) -> Self {
         Self {
+            dispatcher: Dispatcher::new(connection.clone(), actions),
             connection,
-            actions,
             objects: Default::default(),
+            access,
+            apply_cancellation,
+            health,
         }
     }
 
@@ -38,7 +62,7 @@ impl Tree {
     /// * `connections`: list of connections.
     pub async fn set_connections(
         &mut self,
-        connections: &mut [Connection],
+        connections: &[Arc<Connection>],
     ) -> Result<(), ServiceError> {
         self.remove_connections().await?;
         self.add_connections(connections).await?;
@@ -61,14 +85,92 @@ impl Tree {
         for (i, dev) in devices.iter().enumerate() {
             let path = format!("{}/{}", DEVICES_PATH, i);
             let path = ObjectPath::try_from(path.as_str()).unwrap();
-            self.add_interface(&path, interfaces::Device::new(dev.clone()))
-                .await?;
+            self.add_interface(
+                &path,
+                interfaces::Device::new(self.dispatcher.clone(), dev.clone()),
+            )
+            .await?;
+            if let Some(capabilities) = &dev.wireless_capabilities {
+                self.add_interface(&path, interfaces::WirelessDevice::new(capabilities.clone()))
+                    .await?;
+            }
+            if let Some(capabilities) = &dev.modem_capabilities {
+                self.add_interface(&path, interfaces::ModemDevice::new(capabilities.clone()))
+                    .await?;
+            }
             self.objects.register_device(&dev.name, path);
         }
 
-        self.add_interface(DEVICES_PATH, interfaces::Devices::new(self.actions.clone()))
-            .await?;
+        self.add_interface(
+            DEVICES_PATH,
+            interfaces::Devices::new(self.dispatcher.clone(), self.access.clone()),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Emits the signals to notify about the changes in the connections.
+    ///
+    /// It is expected to be called after updating the D-Bus tree, so the objects paths are known.
+    ///
+    /// * `changes`: changes in the connections.
+    /// * `connections`: current list of connections.
+    pub fn notify_connections_changes(
+        &self,
+        changes: &ConnectionsChanges,
+        connections: &[Arc<Connection>],
+    ) {
+        let find = |uuid: &Uuid| {
+            let conn = connections.iter().find(|c| c.uuid == *uuid)?;
+            Some((conn.id.clone(), self.connection_path(*uuid)?))
+        };
+
+        for (id, path) in changes.added.iter().filter_map(find) {
+            self.emit(Signal::ConnectionAdded { id, path });
+        }
+        for (id, path) in changes.updated.iter().filter_map(find) {
+            self.emit(Signal::ConnectionUpdated { id, path });
+        }
+        for id in &changes.removed {
+            self.emit(Signal::ConnectionRemoved { id: id.clone() });
+        }
+    }
 
+    /// Adds the global settings (connectivity check, DNS, wireless settings, etc.) to the D-Bus tree.
+    pub async fn add_global_settings(&mut self) -> Result<(), ServiceError> {
+        self.add_interface(NETWORK_PATH, interfaces::Versions::new())
+            .await?;
+        self.add_interface(
+            NETWORK_PATH,
+            interfaces::Connectivity::new(self.dispatcher.clone()),
+        )
+        .await?;
+        self.add_interface(NETWORK_PATH, interfaces::Dns::new(self.dispatcher.clone()))
+            .await?;
+        self.add_interface(
+            NETWORK_PATH,
+            interfaces::Kdump::new(self.dispatcher.clone()),
+        )
+        .await?;
+        self.add_interface(
+            NETWORK_PATH,
+            interfaces::WirelessSettings::new(self.dispatcher.clone()),
+        )
+        .await?;
+        self.add_interface(NETWORK_PATH, interfaces::Health::new(self.health.clone()))
+            .await?;
+        self.add_interface(
+            NETWORK_PATH,
+            interfaces::Issues::new(self.dispatcher.clone()),
+        )
+        .await?;
+        #[cfg(feature = "network-debug")]
+        self.add_interface(
+            NETWORK_PATH,
+            interfaces::Debug::new(self.dispatcher.clone(), self.access.clone()),
+        )
+        .await?;
         Ok(())
     }
 
@@ -81,7 +183,7 @@ impl Tree {
         conn: &Connection,
     ) -> Result<OwnedObjectPath, ServiceError> {
         let uuid = conn.uuid;
-        let path: OwnedObjectPath = self.objects.register_connection(conn.uuid).into();
+        let path: OwnedObjectPath = self.objects.register_connection(conn.uuid, &conn.id).into();
         log::info!(
             "Publishing network connection '{}' on '{}'",
             &conn.id,
@@ -90,26 +192,68 @@ impl Tree {
 
         self.add_interface(
             &path,
-            interfaces::Connection::new(self.actions.clone(), uuid),
+            interfaces::Connection::new(self.dispatcher.clone(), uuid, self.access.clone()),
         )
         .await?;
 
-        self.add_interface(&path, interfaces::Ip::new(self.actions.clone(), uuid))
+        self.add_interface(&path, interfaces::Ip::new(self.dispatcher.clone(), uuid))
             .await?;
 
-        self.add_interface(&path, interfaces::Match::new(self.actions.clone(), uuid))
+        self.add_interface(&path, interfaces::Match::new(self.dispatcher.clone(), uuid))
             .await?;
 
         if let ConnectionConfig::Bond(_) = conn.config {
-            self.add_interface(&path, interfaces::Bond::new(self.actions.clone(), uuid))
+            self.add_interface(&path, interfaces::Bond::new(self.dispatcher.clone(), uuid))
                 .await?;
         }
 
         if let ConnectionConfig::Wireless(_) = conn.config {
-            self.add_interface(&path, interfaces::Wireless::new(self.actions.clone(), uuid))
+            self.add_interface(
+                &path,
+                interfaces::Wireless::new(self.dispatcher.clone(), uuid),
+            )
+            .await?;
+        }
+
+        if let ConnectionConfig::Pppoe(_) = conn.config {
+            self.add_interface(&path, interfaces::Pppoe::new(self.dispatcher.clone(), uuid))
+                .await?;
+        }
+
+        if let ConnectionConfig::Modem(_) = conn.config {
+            self.add_interface(&path, interfaces::Modem::new(self.dispatcher.clone(), uuid))
+                .await?;
+        }
+
+        if let ConnectionConfig::Vrf(_) = conn.config {
+            self.add_interface(&path, interfaces::Vrf::new(self.dispatcher.clone(), uuid))
                 .await?;
         }
 
+        if let ConnectionConfig::Macvlan(_) = conn.config {
+            self.add_interface(
+                &path,
+                interfaces::Macvlan::new(self.dispatcher.clone(), uuid),
+            )
+            .await?;
+        }
+
+        if let ConnectionConfig::Ipvlan(_) = conn.config {
+            self.add_interface(
+                &path,
+                interfaces::Ipvlan::new(self.dispatcher.clone(), uuid),
+            )
+            .await?;
+        }
+
+        if let ConnectionConfig::Macsec(_) = conn.config {
+            self.add_interface(
+                &path,
+                interfaces::Macsec::new(self.dispatcher.clone(), uuid),
+            )
+            .await?;
+        }
+
         Ok(path)
     }
 
@@ -125,6 +269,14 @@ impl Tree {
         Ok(())
     }
 
+    /// Queues a signal to be emitted on the connections collection object.
+    ///
+    /// * `signal`: signal to emit.
+    fn emit(&self, signal: Signal) {
+        let path = ObjectPath::from_static_str_unchecked(CONNECTIONS_PATH);
+        self.dispatcher.emit(path.into(), signal);
+    }
+
     /// Returns all devices paths.
     pub fn devices_paths(&self) -> Vec<OwnedObjectPath> {
         self.objects.devices_paths()
@@ -141,18 +293,25 @@ impl Tree {
 
     /// Adds connections to the D-Bus tree.
     ///
+    /// The removed connections are not published, so they do not take the path of the ones
+    /// sharing the same ID.
+    ///
     /// * `connections`: list of connections.
     async fn add_connections(
         &mut self,
-        connections: &mut [Connection],
+        connections: &[Arc<Connection>],
     ) -> Result<(), ServiceError> {
-        for conn in connections.iter_mut() {
+        for conn in connections.iter().filter(|c| !c.is_removed()) {
             self.add_connection(conn).await?;
         }
 
         self.add_interface(
             CONNECTIONS_PATH,
-            interfaces::Connections::new(self.actions.clone()),
+            interfaces::Connections::new(
+                self.dispatcher.clone(),
+                self.access.clone(),
+                self.apply_cancellation.clone(),
+            ),
         )
         .await?;
 
@@ -172,6 +331,12 @@ impl Tree {
     async fn remove_devices(&mut self) -> Result<(), ServiceError> {
         let object_server = self.connection.object_server();
         for path in self.objects.devices.values() {
+            _ = object_server
+                .remove::<interfaces::WirelessDevice, _>(path.as_str())
+                .await;
+            _ = object_server
+                .remove::<interfaces::ModemDevice, _>(path.as_str())
+                .await;
             object_server
                 .remove::<interfaces::Device, _>(path.as_str())
                 .await?;
@@ -187,6 +352,12 @@ impl Tree {
         let object_server = self.connection.object_server();
         _ = object_server.remove::<interfaces::Bond, _>(path).await;
         _ = object_server.remove::<interfaces::Wireless, _>(path).await;
+        _ = object_server.remove::<interfaces::Pppoe, _>(path).await;
+        _ = object_server.remove::<interfaces::Modem, _>(path).await;
+        _ = object_server.remove::<interfaces::Vrf, _>(path).await;
+        _ = object_server.remove::<interfaces::Macvlan, _>(path).await;
+        _ = object_server.remove::<interfaces::Ipvlan, _>(path).await;
+        _ = object_server.remove::<interfaces::Macsec, _>(path).await;
         object_server.remove::<interfaces::Ip, _>(path).await?;
         object_server.remove::<interfaces::Match, _>(path).await?;
         object_server
@@ -200,7 +371,9 @@ impl Tree {
         T: zbus::Interface,
     {
         let object_server = self.connection.object_server();
-        Ok(object_server.at(path, iface).await?)
+        let added = object_server.at(path, iface).await?;
+        self.access.guard_properties(&self.connection, path).await?;
+        Ok(added)
     }
 }
 
@@ -226,12 +399,24 @@ impl ObjectsRegistry {
 
     /// Registers a network connection and returns its D-Bus path.
     ///
-    /// It returns the connection Id and the D-Bus path. Bear in mind that the Id can be different
-    /// in case the original one already existed.
+    /// The path is derived from the connection ID (see [escape_path_element]). If the path is
+    /// already taken by another connection (IDs are not unique), a "#2", "#3", etc. suffix is added
+    /// to the ID before encoding it.
     ///
     /// * `uuid`: network connection's UUID.
-    pub fn register_connection(&mut self, uuid: Uuid) -> ObjectPath {
-        let path = format!("{}/{}", CONNECTIONS_PATH, self.connections.len());
+    /// * `id`: network connection's ID.
+    pub fn register_connection(&mut self, uuid: Uuid, id: &str) -> ObjectPath<'static> {
+        self.connections.remove(&uuid);
+        let mut candidate = id.to_string();
+        let mut index = 1;
+        let path = loop {
+            let path = format!("{}/{}", CONNECTIONS_PATH, escape_path_element(&candidate));
+            if !self.connections.values().any(|p| p.as_str() == path) {
+                break path;
+            }
+            index += 1;
+            candidate = format!("{}#{}", id, index);
+        };
         let path = ObjectPath::try_from(path).unwrap();
         self.connections.insert(uuid, path.clone().into());
         path
@@ -256,8 +441,68 @@ impl ObjectsRegistry {
         self.devices.values().cloned().collect()
     }
 
-    /// Returns all connection paths.
+    /// Returns all connection paths sorted alphabetically.
     pub fn connections_paths(&self) -> Vec<OwnedObjectPath> {
-        self.connections.values().cloned().collect()
+        let mut paths: Vec<_> = self.connections.values().cloned().collect();
+        paths.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_connection() {
+        let mut objects = ObjectsRegistry::default();
+        let uuid = Uuid::new_v4();
+        let path = objects.register_connection(uuid, "Wired connection 1");
+        assert_eq!(
+            path.as_str(),
+            "/org/opensuse/Agama1/Network/connections/Wired_20connection_201"
+        );
+        assert_eq!(objects.connection_path(uuid), Some(path));
+    }
+
+    #[test]
+    fn test_register_connection_with_duplicated_id() {
+        let mut objects = ObjectsRegistry::default();
+        let first = objects.register_connection(Uuid::new_v4(), "Café");
+        let second = objects.register_connection(Uuid::new_v4(), "Café");
+        assert_eq!(
+            first.as_str(),
+            "/org/opensuse/Agama1/Network/connections/Caf_c3_a9"
+        );
+        assert_eq!(
+            second.as_str(),
+            "/org/opensuse/Agama1/Network/connections/Caf_c3_a9_232"
+        );
+        assert_eq!(objects.connections_paths().len(), 2);
+    }
+
+    #[test]
+    fn test_register_connection_after_removing() {
+        let mut objects = ObjectsRegistry::default();
+        let eth0 = Uuid::new_v4();
+        objects.register_connection(eth0, "eth0");
+        let eth1 = Uuid::new_v4();
+        let eth1_path = objects.register_connection(eth1, "eth1");
+        objects.deregister_connection(eth0);
+
+        let eth2_path = objects.register_connection(Uuid::new_v4(), "eth2");
+        assert_ne!(eth1_path, eth2_path);
+        let paths: Vec<_> = objects
+            .connections_paths()
+            .into_iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/org/opensuse/Agama1/Network/connections/eth1",
+                "/org/opensuse/Agama1/Network/connections/eth2"
+            ]
+        );
     }
 }
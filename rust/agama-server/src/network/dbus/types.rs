@@ -0,0 +1,281 @@
+//! Conversions between the network model and the D-Bus values.
+//!
+//! Many model types are exposed as strings through D-Bus (IP methods, wireless modes, security
+//! protocols, etc.). The [DBusString] trait defines how to convert them in both directions,
+//! mapping any parsing problem to a [NetworkStateError], so the D-Bus interfaces do not need to
+//! hand-roll the parsing, the formatting or the error mapping.
+use agama_lib::network::types::BondMode;
+
+use crate::network::{
+    error::NetworkStateError,
+    model::{
+        ConflictPolicy, Ipv4Method, Ipv6AddrGenMode, Ipv6Method, IpvlanMode, MacsecMode,
+        MacvlanMode, ResolveMode, SecretStorage, SecurityProtocol, WirelessBand, WirelessMode,
+    },
+};
+
+/// Model values which are represented as strings on D-Bus.
+pub trait DBusString: Sized {
+    /// Returns the D-Bus representation of the value.
+    fn to_dbus(&self) -> String;
+
+    /// Builds the value from its D-Bus representation.
+    ///
+    /// * `value`: D-Bus value.
+    fn from_dbus(value: &str) -> Result<Self, NetworkStateError>;
+}
+
+/// Implements [DBusString] for types which already implement [std::fmt::Display] and
+/// `TryFrom<&str>` with [NetworkStateError] as the error type.
+macro_rules! impl_dbus_string {
+    ($($type:ty),+ $(,)?) => {
+        $(
+            impl DBusString for $type {
+                fn to_dbus(&self) -> String {
+                    self.to_string()
+                }
+
+                fn from_dbus(value: &str) -> Result<Self, NetworkStateError> {
+                    Self::try_from(value)
+                }
+            }
+        )+
+    };
+}
+
+impl_dbus_string!(
+    ConflictPolicy,
+    Ipv6AddrGenMode,
+    IpvlanMode,
+    MacsecMode,
+    MacvlanMode,
+    ResolveMode,
+    SecretStorage,
+    SecurityProtocol,
+    WirelessMode,
+);
+
+impl DBusString for Ipv4Method {
+    fn to_dbus(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_dbus(value: &str) -> Result<Self, NetworkStateError> {
+        value
+            .parse()
+            .map_err(|_| NetworkStateError::UnknownIpMethod(value.to_string()))
+    }
+}
+
+impl DBusString for Ipv6Method {
+    fn to_dbus(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_dbus(value: &str) -> Result<Self, NetworkStateError> {
+        value
+            .parse()
+            .map_err(|_| NetworkStateError::UnknownIpMethod(value.to_string()))
+    }
+}
+
+impl DBusString for WirelessBand {
+    fn to_dbus(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_dbus(value: &str) -> Result<Self, NetworkStateError> {
+        WirelessBand::try_from(value)
+            .map_err(|_| NetworkStateError::InvalidWirelessBand(value.to_string()))
+    }
+}
+
+impl DBusString for BondMode {
+    fn to_dbus(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_dbus(value: &str) -> Result<Self, NetworkStateError> {
+        BondMode::try_from(value).map_err(|_| NetworkStateError::InvalidBondMode(value.to_string()))
+    }
+}
+
+/// Optional values are represented as an empty string when they are not set.
+impl<T: DBusString> DBusString for Option<T> {
+    fn to_dbus(&self) -> String {
+        self.as_ref().map(|v| v.to_dbus()).unwrap_or_default()
+    }
+
+    fn from_dbus(value: &str) -> Result<Self, NetworkStateError> {
+        if value.is_empty() {
+            return Ok(None);
+        }
+        T::from_dbus(value).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Debug;
+
+    fn assert_round_trip<T: DBusString + PartialEq + Debug>(values: &[T], names: &[&str]) {
+        assert_eq!(values.len(), names.len());
+        for (value, name) in values.iter().zip(names) {
+            assert_eq!(&value.to_dbus(), name);
+            assert_eq!(&T::from_dbus(name).unwrap(), value);
+        }
+        assert!(T::from_dbus("not-valid").is_err());
+        assert!(T::from_dbus("").is_err());
+    }
+
+    #[test]
+    fn test_ip_methods() {
+        assert_round_trip(
+            &[
+                Ipv4Method::Disabled,
+                Ipv4Method::Auto,
+                Ipv4Method::Manual,
+                Ipv4Method::LinkLocal,
+                Ipv4Method::Shared,
+            ],
+            &["disabled", "auto", "manual", "link-local", "shared"],
+        );
+        assert_round_trip(
+            &[
+                Ipv6Method::Disabled,
+                Ipv6Method::Auto,
+                Ipv6Method::Manual,
+                Ipv6Method::LinkLocal,
+                Ipv6Method::Ignore,
+                Ipv6Method::Dhcp,
+            ],
+            &["disabled", "auto", "manual", "link-local", "ignore", "dhcp"],
+        );
+
+        let error = Ipv4Method::from_dbus("dhcp").unwrap_err();
+        assert!(matches!(error, NetworkStateError::UnknownIpMethod(name) if name == "dhcp"));
+    }
+
+    #[test]
+    fn test_wireless_values() {
+        assert_round_trip(
+            &[
+                WirelessMode::Unknown,
+                WirelessMode::AdHoc,
+                WirelessMode::Infra,
+                WirelessMode::AP,
+                WirelessMode::Mesh,
+            ],
+            &["unknown", "adhoc", "infrastructure", "ap", "mesh"],
+        );
+        assert_round_trip(
+            &[
+                SecurityProtocol::WEP,
+                SecurityProtocol::OWE,
+                SecurityProtocol::DynamicWEP,
+                SecurityProtocol::WPA2,
+                SecurityProtocol::WPA3Personal,
+                SecurityProtocol::WPA2Enterprise,
+                SecurityProtocol::WPA3Only,
+            ],
+            &[
+                "none",
+                "owe",
+                "ieee8021x",
+                "wpa-psk",
+                "sae",
+                "wpa-eap",
+                "wpa-eap-suite-b192",
+            ],
+        );
+        assert_round_trip(&[WirelessBand::A, WirelessBand::BG], &["a", "bg"]);
+
+        let error = WirelessBand::from_dbus("ac").unwrap_err();
+        assert!(matches!(error, NetworkStateError::InvalidWirelessBand(band) if band == "ac"));
+    }
+
+    #[test]
+    fn test_connection_modes() {
+        assert_round_trip(
+            &[
+                BondMode::RoundRobin,
+                BondMode::ActiveBackup,
+                BondMode::BalanceXOR,
+                BondMode::Broadcast,
+                BondMode::LACP,
+                BondMode::BalanceTLB,
+                BondMode::BalanceALB,
+            ],
+            &[
+                "balance-rr",
+                "active-backup",
+                "balance-xor",
+                "broadcast",
+                "802.3ad",
+                "balance-tlb",
+                "balance-alb",
+            ],
+        );
+        assert_round_trip(
+            &[
+                MacvlanMode::Vepa,
+                MacvlanMode::Bridge,
+                MacvlanMode::Private,
+                MacvlanMode::Passthru,
+                MacvlanMode::Source,
+            ],
+            &["vepa", "bridge", "private", "passthru", "source"],
+        );
+        assert_round_trip(
+            &[IpvlanMode::L2, IpvlanMode::L3, IpvlanMode::L3s],
+            &["l2", "l3", "l3s"],
+        );
+        assert_round_trip(&[MacsecMode::Psk, MacsecMode::Eap], &["psk", "eap"]);
+    }
+
+    #[test]
+    fn test_connection_settings() {
+        assert_round_trip(
+            &[
+                ResolveMode::Default,
+                ResolveMode::No,
+                ResolveMode::Resolve,
+                ResolveMode::Yes,
+            ],
+            &["default", "no", "resolve", "yes"],
+        );
+        assert_round_trip(
+            &[
+                SecretStorage::System,
+                SecretStorage::Agent,
+                SecretStorage::NotSaved,
+            ],
+            &["system", "agent", "not-saved"],
+        );
+        assert_round_trip(
+            &[
+                ConflictPolicy::Rename,
+                ConflictPolicy::Skip,
+                ConflictPolicy::Replace,
+            ],
+            &["rename", "skip", "replace"],
+        );
+        assert_round_trip(
+            &[Ipv6AddrGenMode::Eui64, Ipv6AddrGenMode::StablePrivacy],
+            &["eui64", "stable-privacy"],
+        );
+    }
+
+    #[test]
+    fn test_optional_values() {
+        assert_eq!(None::<WirelessBand>.to_dbus(), "");
+        assert_eq!(Some(WirelessBand::A).to_dbus(), "a");
+        assert_eq!(Option::<WirelessBand>::from_dbus("").unwrap(), None);
+        assert_eq!(
+            Option::<Ipv6AddrGenMode>::from_dbus("eui64").unwrap(),
+            Some(Ipv6AddrGenMode::Eui64)
+        );
+        assert!(Option::<WirelessBand>::from_dbus("ac").is_err());
+    }
+}
@@ -12,6 +12,8 @@ pub enum NetworkStateError {
     InvalidIpAddr(String),
     #[error("Invalid IP method: '{0}'")]
     InvalidIpMethod(u8),
+    #[error("Unknown IP method: '{0}'")]
+    UnknownIpMethod(String),
     #[error("Invalid wireless mode: '{0}'")]
     InvalidWirelessMode(String),
     #[error("Connection '{0}' already exists")]
@@ -22,16 +24,72 @@ pub enum NetworkStateError {
     AdapterError(String),
     #[error("Invalid bond mode '{0}'")]
     InvalidBondMode(String),
+    #[error("Invalid MACVLAN mode '{0}'")]
+    InvalidMacvlanMode(String),
+    #[error("Invalid IPVLAN mode '{0}'")]
+    InvalidIpvlanMode(String),
+    #[error("Invalid MACsec mode '{0}'")]
+    InvalidMacsecMode(String),
     #[error("Invalid bond options")]
     InvalidBondOptions,
     #[error("Not a controller connection: '{0}'")]
     NotControllerConnection(String),
+    #[error("Not a bond connection: '{0}'")]
+    NotBondConnection(String),
     #[error("Unexpected configuration")]
     UnexpectedConfiguration,
     #[error("Invalid WEP authentication algorithm: '{0}'")]
     InvalidWEPAuthAlg(String),
     #[error("Invalid WEP key type: '{0}'")]
     InvalidWEPKeyType(u32),
+    #[error("Invalid conflict resolution policy: '{0}'")]
+    InvalidConflictPolicy(String),
+    #[error("Invalid connection template: {0}")]
+    InvalidTemplate(String),
+    #[error("Invalid name resolution mode: '{0}'")]
+    InvalidResolveMode(String),
+    #[error("Invalid wireless band: '{0}'")]
+    InvalidWirelessBand(String),
+    #[error("Invalid wireless channel: '{0}'")]
+    InvalidWirelessChannel(u32),
+    #[error("Invalid regulatory domain: '{0}'")]
+    InvalidRegulatoryDomain(String),
+    #[error("Invalid hostname: '{0}'")]
+    InvalidHostname(String),
+    #[error("Invalid metadata key: '{0}'")]
+    InvalidMetadataKey(String),
+    #[error("Invalid user name: '{0}'")]
+    InvalidUserName(String),
+    #[error("Invalid tag: '{0}'")]
+    InvalidTag(String),
+    #[error("Unknown device '{0}'")]
+    UnknownDevice(String),
+    #[error("Invalid DNS policy: '{0}'")]
+    InvalidDnsPolicy(String),
+    #[error("Invalid DNS stack: '{0}'")]
+    InvalidDnsStack(String),
+    #[error("Invalid route metric: '{0}'")]
+    InvalidRouteMetric(i64),
+    #[error("Invalid IPv6 token: '{0}'")]
+    InvalidIpv6Token(String),
+    #[error("Invalid IPv6 address generation mode: '{0}'")]
+    InvalidAddrGenMode(String),
+    #[error("Invalid kdump addressing: '{0}'")]
+    InvalidKdumpAddressing(String),
+    #[error("Invalid kdump save directory: '{0}'")]
+    InvalidKdumpSaveDir(String),
+    #[error("Invalid secret for this connection: '{0}'")]
+    InvalidSecret(String),
+    #[error("Invalid secret storage: '{0}'")]
+    InvalidSecretStorage(String),
+    #[error("The connections changed since the token '{0}' was issued")]
+    OutdatedChangeToken(String),
+    #[error("The operation was not confirmed: {0}")]
+    NotConfirmed(String),
+    #[error("Invalid wpa_supplicant configuration: {0}")]
+    InvalidWpaSupplicantConf(String),
+    #[error("Could not get the router advertisements: {0}")]
+    RouterAdvertisements(String),
 }
 
 impl From<NetworkStateError> for zbus::fdo::Error {
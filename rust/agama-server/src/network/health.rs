@@ -0,0 +1,160 @@
+//! Health information about the network service.
+//!
+//! The information is shared between the [crate::network::NetworkSystem], which updates it, and
+//! the `org.opensuse.Agama1.Network.Health` D-Bus interface, which exposes it without going
+//! through the actions queue. This way, it is possible to find out the service status even when
+//! it is busy (e.g., applying the configuration).
+
+use crate::network::{action, model::ApplyResult};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+use zbus::zvariant::OwnedValue;
+
+/// Outcome of the last time the configuration was applied.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ApplyStatus {
+    /// The configuration was not applied yet.
+    #[default]
+    None,
+    /// All the connections were written.
+    Success,
+    /// Some connections could not be written.
+    Partial(Vec<String>),
+    /// The configuration could not be written at all.
+    Failure(String),
+}
+
+impl ApplyStatus {
+    /// Builds the status from the result of writing the connections.
+    ///
+    /// * `result`: result of writing the connections.
+    pub fn from_result(result: &ApplyResult) -> Self {
+        let summary = result.summary();
+        if summary.failed.is_empty() {
+            Self::Success
+        } else {
+            Self::Partial(summary.failed)
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Success => "success",
+            Self::Partial(_) => "partial",
+            Self::Failure(_) => "failure",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct HealthState {
+    backend_available: AtomicBool,
+    last_apply: Mutex<ApplyStatus>,
+}
+
+/// Health of the network service.
+///
+/// Cloning it is cheap, as all the clones share the same state.
+#[derive(Clone, Debug, Default)]
+pub struct Health(Arc<HealthState>);
+
+impl Health {
+    /// Records whether the backend (e.g., NetworkManager) could be reached.
+    ///
+    /// * `available`: whether the last operation on the backend succeeded.
+    pub fn set_backend_available(&self, available: bool) {
+        self.0.backend_available.store(available, Ordering::SeqCst);
+    }
+
+    /// Whether the backend could be reached the last time it was used.
+    pub fn is_backend_available(&self) -> bool {
+        self.0.backend_available.load(Ordering::SeqCst)
+    }
+
+    /// Records the outcome of applying the configuration.
+    ///
+    /// * `status`: outcome of the last apply.
+    pub fn set_last_apply(&self, status: ApplyStatus) {
+        if let Ok(mut last_apply) = self.0.last_apply.lock() {
+            *last_apply = status;
+        }
+    }
+
+    /// Returns the outcome of the last apply.
+    pub fn last_apply(&self) -> ApplyStatus {
+        self.0
+            .last_apply
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the health information as a D-Bus dictionary.
+    ///
+    /// * `PendingActions`: number of actions waiting to be processed.
+    /// * `BackendAvailable`: whether the backend could be reached.
+    /// * `LastApply`: outcome of the last apply ("none", "success", "partial" or "failure").
+    /// * `LastApplyError`: IDs of the connections that could not be written or the error message.
+    pub fn to_dbus(&self) -> HashMap<String, OwnedValue> {
+        let last_apply = self.last_apply();
+        let error = match &last_apply {
+            ApplyStatus::Partial(failed) => failed.join(", "),
+            ApplyStatus::Failure(message) => message.clone(),
+            _ => String::new(),
+        };
+        let pending = u32::try_from(action::pending_actions()).unwrap_or(u32::MAX);
+
+        HashMap::from([
+            ("PendingActions".to_string(), OwnedValue::from(pending)),
+            (
+                "BackendAvailable".to_string(),
+                OwnedValue::from(self.is_backend_available()),
+            ),
+            (
+                "LastApply".to_string(),
+                OwnedValue::from(zbus::zvariant::Str::from(last_apply.name())),
+            ),
+            (
+                "LastApplyError".to_string(),
+                OwnedValue::from(zbus::zvariant::Str::from(error)),
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApplyStatus, Health};
+
+    #[test]
+    fn test_health_is_shared() {
+        let health = Health::default();
+        let clone = health.clone();
+        clone.set_backend_available(true);
+        clone.set_last_apply(ApplyStatus::Failure("error".to_string()));
+
+        assert!(health.is_backend_available());
+        assert_eq!(
+            health.last_apply(),
+            ApplyStatus::Failure("error".to_string())
+        );
+    }
+
+    #[test]
+    fn test_to_dbus() {
+        let health = Health::default();
+        health.set_last_apply(ApplyStatus::Partial(vec!["eth0".to_string()]));
+        let dict = health.to_dbus();
+
+        let last_apply: String = dict["LastApply"].clone().try_into().unwrap();
+        assert_eq!(last_apply, "partial");
+        let error: String = dict["LastApplyError"].clone().try_into().unwrap();
+        assert_eq!(error, "eth0");
+    }
+}
@@ -0,0 +1,68 @@
+//! Support for the kdump network settings.
+//!
+//! The settings are not applied to the live system. Instead, they are written to a file which is
+//! merged into the kdump configuration (`/etc/sysconfig/kdump`) of the installed system.
+use super::model::KdumpConfig;
+use std::{fs, io, path::Path};
+
+/// File to write the kdump settings for the installed system.
+pub const TARGET_KDUMP_CONF_PATH: &str = "/run/agama/network/kdump";
+
+/// Writes the kdump settings for the installed system.
+///
+/// The file is removed if there are no settings, so the kdump configuration is not touched.
+///
+/// * `config`: kdump settings.
+pub fn write(config: &KdumpConfig) -> io::Result<()> {
+    let path = Path::new(TARGET_KDUMP_CONF_PATH);
+    if config.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, kdump_to_conf(config))
+}
+
+/// Renders the kdump settings as sysconfig variables.
+///
+/// * `config`: kdump settings.
+fn kdump_to_conf(config: &KdumpConfig) -> String {
+    let mut conf = format!("KDUMP_NETCONFIG=\"{}\"\n", config.netconfig());
+    if let Some(save_dir) = &config.save_dir {
+        conf.push_str(&format!("KDUMP_SAVEDIR=\"{}\"\n", save_dir));
+    }
+    conf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::model::KdumpAddressing;
+
+    #[test]
+    fn test_kdump_to_conf() {
+        let config = KdumpConfig {
+            interface: Some("eth0".to_string()),
+            addressing: KdumpAddressing::Static,
+            save_dir: Some("nfs://192.168.1.1/var/crash".to_string()),
+        };
+        assert_eq!(
+            kdump_to_conf(&config),
+            "KDUMP_NETCONFIG=\"eth0:static\"\nKDUMP_SAVEDIR=\"nfs://192.168.1.1/var/crash\"\n"
+        );
+
+        let config = KdumpConfig {
+            addressing: KdumpAddressing::Dhcp6,
+            ..Default::default()
+        };
+        assert_eq!(
+            kdump_to_conf(&config),
+            "KDUMP_NETCONFIG=\"default:dhcp6\"\n"
+        );
+    }
+}
@@ -2,24 +2,47 @@
 //!
 //! * This module contains the types that represent the network concepts. They are supposed to be
 //! agnostic from the real network service (e.g., NetworkManager).
-use crate::network::error::NetworkStateError;
+use crate::network::{error::NetworkStateError, regdom};
 use agama_lib::network::types::{BondMode, DeviceType, SSID};
 use cidr::IpInet;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     default::Default,
     fmt,
-    net::IpAddr,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
     str::{self, FromStr},
+    sync::Arc,
 };
 use thiserror::Error;
 use uuid::Uuid;
 use zbus::zvariant::Value;
 
+/// Network state
+///
+/// The connections are kept as immutable snapshots that are shared (e.g., with the D-Bus
+/// interfaces) instead of copied. A connection is only copied when it is modified while a
+/// snapshot is still in use (copy-on-write).
 #[derive(Default, Clone, Debug)]
 pub struct NetworkState {
     pub devices: Vec<Device>,
-    pub connections: Vec<Connection>,
+    pub connections: Vec<Arc<Connection>>,
+    pub connectivity: ConnectivityConfig,
+    /// Wireless regulatory domain (ISO 3166-1 alpha-2 country code).
+    pub regulatory_domain: Option<String>,
+    /// Static hostname of the system.
+    pub hostname: Option<String>,
+    /// Names of the devices to ignore (e.g., ports shared with a BMC).
+    pub ignored_devices: Vec<String>,
+    /// Global DNS settings.
+    pub dns: DnsConfig,
+    /// Network settings for kdump in the installed system.
+    pub kdump: KdumpConfig,
+    /// IPv6 address generation mode for the new connections that do not set their own.
+    pub addr_gen_mode: Option<Ipv6AddrGenMode>,
+    /// Bandwidth limit (in Kbit/s) for the connections that do not set their own.
+    pub bandwidth_limit: Option<u32>,
 }
 
 impl NetworkState {
@@ -30,7 +53,8 @@ impl NetworkState {
     pub fn new(devices: Vec<Device>, connections: Vec<Connection>) -> Self {
         Self {
             devices,
-            connections,
+            connections: connections.into_iter().map(Arc::new).collect(),
+            ..Default::default()
         }
     }
 
@@ -41,18 +65,61 @@ impl NetworkState {
         self.devices.iter().find(|d| d.name == name)
     }
 
+    /// Sets the wireless regulatory domain.
+    ///
+    /// * `code`: ISO 3166-1 alpha-2 country code. `None` means the default (world) domain.
+    pub fn set_regulatory_domain(&mut self, code: Option<String>) -> Result<(), NetworkStateError> {
+        if let Some(code) = &code {
+            if !regdom::is_valid(code) {
+                return Err(NetworkStateError::InvalidRegulatoryDomain(code.to_string()));
+            }
+        }
+        self.regulatory_domain = code;
+        Ok(())
+    }
+
+    /// Sets the static hostname.
+    ///
+    /// * `hostname`: hostname. `None` means keeping the one the system already has.
+    pub fn set_hostname(&mut self, hostname: Option<String>) -> Result<(), NetworkStateError> {
+        if let Some(hostname) = &hostname {
+            if !is_valid_hostname(hostname) {
+                return Err(NetworkStateError::InvalidHostname(hostname.to_string()));
+            }
+        }
+        self.hostname = hostname;
+        Ok(())
+    }
+
     /// Get connection by UUID
     ///
     /// * `uuid`: connection UUID
     pub fn get_connection_by_uuid(&self, uuid: Uuid) -> Option<&Connection> {
-        self.connections.iter().find(|c| c.uuid == uuid)
+        self.connections
+            .iter()
+            .find(|c| c.uuid == uuid)
+            .map(Arc::as_ref)
+    }
+
+    /// Get a snapshot of the connection with the given UUID
+    ///
+    /// The snapshot shares the data with the state, so it is cheap to get.
+    ///
+    /// * `uuid`: connection UUID
+    pub fn get_connection_snapshot(&self, uuid: Uuid) -> Option<Arc<Connection>> {
+        self.connections.iter().find(|c| c.uuid == uuid).cloned()
     }
 
     /// Get connection by UUID as mutable
     ///
+    /// The connection is copied if there are snapshots of it.
+    ///
     /// * `uuid`: connection UUID
     pub fn get_connection_by_uuid_mut(&mut self, uuid: Uuid) -> Option<&mut Connection> {
-        self.connections.iter_mut().find(|c| c.uuid == uuid)
+        self.connections
+            .iter_mut()
+            .find(|c| c.uuid == uuid)
+            .map(Arc::make_mut)
     }
 
     /// Get connection by interface
@@ -63,20 +130,33 @@ impl NetworkState {
         self.connections
             .iter()
             .find(|c| c.interface.as_deref() == interface)
+            .map(Arc::as_ref)
     }
 
     /// Get connection by ID
     ///
+    /// The removed connections are ignored, as they might share the ID with the one replacing
+    /// them (see [ConflictPolicy::Replace]).
+    ///
     /// * `id`: connection ID
     pub fn get_connection(&self, id: &str) -> Option<&Connection> {
-        self.connections.iter().find(|c| c.id == id)
+        self.connections
+            .iter()
+            .find(|c| c.id == id && !c.is_removed())
+            .map(Arc::as_ref)
     }
 
     /// Get connection by ID as mutable
     ///
+    /// The connection is copied if there are snapshots of it. The removed connections are
+    /// ignored.
+    ///
     /// * `id`: connection ID
     pub fn get_connection_mut(&mut self, id: &str) -> Option<&mut Connection> {
-        self.connections.iter_mut().find(|c| c.id == id)
+        self.connections
+            .iter_mut()
+            .find(|c| c.id == id && !c.is_removed())
+            .map(Arc::make_mut)
     }
 
     pub fn get_controlled_by(&mut self, uuid: Uuid) -> Vec<&Connection> {
@@ -84,17 +164,19 @@ impl NetworkState {
         self.connections
             .iter()
             .filter(|c| c.controller == uuid)
+            .map(Arc::as_ref)
             .collect()
     }
 
     /// Adds a new connection.
     ///
     /// It uses the `id` to decide whether the connection already exists.
-    pub fn add_connection(&mut self, conn: Connection) -> Result<(), NetworkStateError> {
+    pub fn add_connection(&mut self, mut conn: Connection) -> Result<(), NetworkStateError> {
         if self.get_connection(&conn.id).is_some() {
             return Err(NetworkStateError::ConnectionExists(conn.id));
         }
-        self.connections.push(conn);
+        self.apply_defaults(&mut conn);
+        self.connections.push(Arc::new(conn));
 
         Ok(())
     }
@@ -105,14 +187,175 @@ impl NetworkState {
     ///
     /// Additionally, it registers the connection to be removed when the changes are applied.
     pub fn update_connection(&mut self, conn: Connection) -> Result<(), NetworkStateError> {
-        let Some(old_conn) = self.get_connection_mut(&conn.id) else {
+        let Some(old_conn) = self.connections.iter_mut().find(|c| c.id == conn.id) else {
             return Err(NetworkStateError::UnknownConnection(conn.id.clone()));
         };
-        *old_conn = conn;
+        *old_conn = Arc::new(conn);
+
+        Ok(())
+    }
+
+    /// Adds a copy of an existing connection using a different ID.
+    ///
+    /// The copy gets a new UUID and, unless `with_secrets` is true, it does not include any
+    /// secret.
+    ///
+    /// * `source_id`: ID of the connection to copy.
+    /// * `new_id`: ID of the new connection.
+    /// * `with_secrets`: whether to copy the secrets too.
+    pub fn clone_connection(
+        &mut self,
+        source_id: &str,
+        new_id: &str,
+        with_secrets: bool,
+    ) -> Result<&Connection, NetworkStateError> {
+        let source = self
+            .get_connection(source_id)
+            .filter(|c| !c.is_removed())
+            .ok_or(NetworkStateError::UnknownConnection(source_id.to_string()))?;
+        let mut conn = Connection {
+            id: new_id.to_string(),
+            uuid: Uuid::new_v4(),
+            ..source.clone()
+        };
+        if !with_secrets {
+            conn.remove_secrets();
+        }
+        self.add_connection(conn)?;
+        Ok(self.connections.last().map(Arc::as_ref).unwrap())
+    }
+
+    /// Returns the potential problems in the connections configuration.
+    ///
+    /// See [IpConfig::issues] for the checks.
+    pub fn issues(&self) -> Vec<ConfigIssue> {
+        self.connections
+            .iter()
+            .filter(|c| !c.is_removed())
+            .flat_map(|c| {
+                c.ip_config
+                    .issues()
+                    .into_iter()
+                    .map(|description| ConfigIssue {
+                        connection: c.id.clone(),
+                        description,
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the team connections that were converted into bonds.
+    ///
+    /// Each element contains the connection ID and the resulting bond mode.
+    pub fn team_migrations(&self) -> Vec<(String, BondMode)> {
+        self.connections
+            .iter()
+            .filter(|c| c.migrated_from_team && !c.is_removed())
+            .filter_map(|c| match &c.config {
+                ConnectionConfig::Bond(bond) => Some((c.id.clone(), bond.mode)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Adds a connection for a device based on its current IP configuration.
+    ///
+    /// The connection is named after the device. If `as_static` is true, the runtime
+    /// configuration is turned into a static one (see [IpConfig::into_static]). Otherwise, the
+    /// connection uses automatic configuration. It returns the added connection.
+    ///
+    /// * `name`: device name.
+    /// * `runtime`: current IP configuration of the device.
+    /// * `as_static`: whether to copy the current configuration as a static one.
+    pub fn add_connection_from_device(
+        &mut self,
+        name: &str,
+        runtime: IpConfig,
+        as_static: bool,
+    ) -> Result<&Connection, NetworkStateError> {
+        let device = self
+            .get_device(name)
+            .ok_or(NetworkStateError::UnknownDevice(name.to_string()))?;
+        let mut conn = Connection::new(name.to_string(), device.type_);
+        conn.interface = Some(name.to_string());
+        conn.ip_config = if as_static {
+            runtime.into_static()
+        } else {
+            IpConfig {
+                method4: Ipv4Method::Auto,
+                method6: Ipv6Method::Auto,
+                ..Default::default()
+            }
+        };
+        self.add_connection(conn)?;
+        Ok(self.connections.last().map(Arc::as_ref).unwrap())
+    }
+
+    /// Adds one connection per interface using the given template.
+    ///
+    /// No connection is added if any of them cannot be added (e.g., because its ID is already
+    /// taken). It returns the added connections.
+    ///
+    /// * `template`: template to create the connections from.
+    /// * `interfaces`: names of the interfaces.
+    pub fn add_connections_from_template(
+        &mut self,
+        template: &ConnectionTemplate,
+        interfaces: &[String],
+    ) -> Result<Vec<Connection>, NetworkStateError> {
+        let controller = match &template.controller {
+            Some(id) => {
+                let controller = self
+                    .get_connection(id)
+                    .ok_or(NetworkStateError::UnknownConnection(id.to_string()))?;
+                if !controller.is_controller() {
+                    return Err(NetworkStateError::NotControllerConnection(id.to_string()));
+                }
+                Some(controller.uuid)
+            }
+            None => None,
+        };
+
+        let mut conns: Vec<Connection> = vec![];
+        for interface in interfaces {
+            let mut conn = template.instantiate(interface);
+            if self.get_connection(&conn.id).is_some() || conns.iter().any(|c| c.id == conn.id) {
+                return Err(NetworkStateError::ConnectionExists(conn.id));
+            }
+            conn.controller = controller;
+            self.apply_defaults(&mut conn);
+            conns.push(conn);
+        }
+
+        self.connections.extend(conns.iter().cloned().map(Arc::new));
+        Ok(conns)
+    }
 
+    /// Adds a set of connections.
+    ///
+    /// The connections are added only if none of them exists.
+    ///
+    /// * `conns`: connections to add.
+    pub fn add_connections(&mut self, conns: Vec<Connection>) -> Result<(), NetworkStateError> {
+        if let Some(conn) = conns.iter().find(|c| self.get_connection(&c.id).is_some()) {
+            return Err(NetworkStateError::ConnectionExists(conn.id.clone()));
+        }
+        for mut conn in conns {
+            self.apply_defaults(&mut conn);
+            self.connections.push(Arc::new(conn));
+        }
         Ok(())
     }
 
+    /// Sets the site-wide defaults in a new connection, unless the connection overrides them.
+    ///
+    /// * `conn`: connection to update.
+    fn apply_defaults(&self, conn: &mut Connection) {
+        if conn.ip_config.addr_gen_mode6.is_none() {
+            conn.ip_config.addr_gen_mode6 = self.addr_gen_mode;
+        }
+    }
+
     /// Removes a connection from the state.
     ///
     /// Additionally, it registers the connection to be removed when the changes are applied.
@@ -136,7 +379,7 @@ impl NetworkState {
         controller: &Connection,
         ports: Vec<String>,
     ) -> Result<(), NetworkStateError> {
-        if let ConnectionConfig::Bond(_) = &controller.config {
+        if controller.is_controller() {
             let mut controlled = vec![];
             for port in ports {
                 let connection = self
@@ -148,9 +391,9 @@ impl NetworkState {
 
             for conn in self.connections.iter_mut() {
                 if controlled.contains(&conn.uuid) {
-                    conn.controller = Some(controller.uuid);
+                    Arc::make_mut(conn).controller = Some(controller.uuid);
                 } else if conn.controller == Some(controller.uuid) {
-                    conn.controller = None;
+                    Arc::make_mut(conn).controller = None;
                 }
             }
             Ok(())
@@ -160,134 +403,1520 @@ impl NetworkState {
             ))
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::network::error::NetworkStateError;
-    use uuid::Uuid;
+    /// Resolves the conflicts between connections sharing the same ID.
+    ///
+    /// It returns the mapping between the original IDs and the resulting ones.
+    ///
+    /// * `policy`: how to resolve the conflicts.
+    pub fn resolve_conflicts(&mut self, policy: ConflictPolicy) -> Vec<IdMapping> {
+        let mut mapping: Vec<_> = self
+            .connections
+            .iter()
+            .map(|c| IdMapping {
+                original_id: c.id.clone(),
+                uuid: c.uuid,
+                id: Some(c.id.clone()),
+            })
+            .collect();
+
+        match policy {
+            ConflictPolicy::Rename => {
+                let mut taken: HashSet<String> =
+                    self.connections.iter().map(|c| c.id.clone()).collect();
+                let mut seen = HashSet::new();
+                for (conn, entry) in self.connections.iter_mut().zip(mapping.iter_mut()) {
+                    if seen.insert(conn.id.clone()) {
+                        continue;
+                    }
+                    let id = (1..)
+                        .map(|n| format!("{}-{}", conn.id, n))
+                        .find(|id| !taken.contains(id))
+                        .unwrap();
+                    log::info!("Renaming duplicated connection '{}' to '{}'", conn.id, id);
+                    taken.insert(id.clone());
+                    seen.insert(id.clone());
+                    Arc::make_mut(conn).id = id.clone();
+                    entry.id = Some(id);
+                }
+            }
+            ConflictPolicy::Skip => {
+                let mut seen = HashSet::new();
+                for entry in mapping.iter_mut() {
+                    if !seen.insert(entry.original_id.clone()) {
+                        log::info!("Skipping duplicated connection '{}'", entry.original_id);
+                        entry.id = None;
+                    }
+                }
+                let skipped: Vec<_> = mapping
+                    .iter()
+                    .filter(|e| e.id.is_none())
+                    .map(|e| e.uuid)
+                    .collect();
+                self.connections.retain(|c| !skipped.contains(&c.uuid));
+            }
+            ConflictPolicy::Replace => {
+                let mut seen = HashSet::new();
+                for (conn, entry) in self.connections.iter_mut().zip(mapping.iter_mut()).rev() {
+                    if !seen.insert(conn.id.clone()) {
+                        log::info!("Replacing duplicated connection '{}'", conn.id);
+                        Arc::make_mut(conn).remove();
+                        entry.id = None;
+                    }
+                }
+            }
+        }
 
-    #[test]
-    fn test_macaddress() {
-        let mut val: Option<String> = None;
-        assert!(matches!(
-            MacAddress::try_from(&val).unwrap(),
-            MacAddress::Unset
-        ));
+        mapping
+    }
 
-        val = Some(String::from(""));
-        assert!(matches!(
-            MacAddress::try_from(&val).unwrap(),
-            MacAddress::Unset
-        ));
+    /// Returns the devices that are not ignored.
+    pub fn visible_devices(&self) -> Vec<Device> {
+        self.devices
+            .iter()
+            .filter(|d| !self.ignored_devices.contains(&d.name))
+            .cloned()
+            .collect()
+    }
 
-        val = Some(String::from("preserve"));
-        assert!(matches!(
-            MacAddress::try_from(&val).unwrap(),
-            MacAddress::Preserve
-        ));
+    /// Determines whether a connection is bound to an ignored device.
+    ///
+    /// * `conn`: connection to check.
+    pub fn is_ignored(&self, conn: &Connection) -> bool {
+        conn.interface
+            .as_ref()
+            .is_some_and(|i| self.ignored_devices.contains(i))
+    }
 
-        val = Some(String::from("permanent"));
-        assert!(matches!(
-            MacAddress::try_from(&val).unwrap(),
-            MacAddress::Permanent
-        ));
+    /// Returns the bandwidth limit (in Kbit/s) that applies to a connection, if any.
+    ///
+    /// * `conn`: connection to get the limit for.
+    pub fn bandwidth_limit(&self, conn: &Connection) -> Option<u32> {
+        conn.bandwidth_limit.or(self.bandwidth_limit)
+    }
 
-        val = Some(String::from("random"));
-        assert!(matches!(
-            MacAddress::try_from(&val).unwrap(),
-            MacAddress::Random
-        ));
+    /// Copies the settings that only exist in Agama (i.e., they are not stored in the backend)
+    /// from a previous state.
+    ///
+    /// * `previous`: state to copy the settings from.
+    pub fn keep_local_settings(&mut self, previous: &NetworkState) {
+        self.ignored_devices = previous.ignored_devices.clone();
+        self.dns = previous.dns.clone();
+        self.kdump = previous.kdump.clone();
+        self.addr_gen_mode = previous.addr_gen_mode;
+        self.bandwidth_limit = previous.bandwidth_limit;
+        for conn in self.connections.iter_mut() {
+            if let Some(prev) = previous.get_connection_by_uuid(conn.uuid) {
+                let conn = Arc::make_mut(conn);
+                conn.externally_managed = prev.externally_managed;
+                conn.activation_timeout = prev.activation_timeout;
+                conn.bandwidth_limit = prev.bandwidth_limit;
+            }
+        }
+    }
 
-        val = Some(String::from("stable"));
-        assert!(matches!(
-            MacAddress::try_from(&val).unwrap(),
-            MacAddress::Stable
-        ));
+    /// Returns the name servers that a device would use once the configuration is applied.
+    ///
+    /// The servers of the device's connection (or of its controller, if the device is a port) are
+    /// merged with the global ones according to the [DnsPolicy]. With a single active device, it
+    /// is the list that ends up in resolv.conf.
+    ///
+    /// * `device`: device name.
+    pub fn effective_dns(&self, device: &str) -> Result<Vec<IpAddr>, NetworkStateError> {
+        let conn = self
+            .connections
+            .iter()
+            .map(Arc::as_ref)
+            .find(|c| c.interface.as_deref() == Some(device) && !c.is_removed());
+        if conn.is_none() && self.get_device(device).is_none() {
+            return Err(NetworkStateError::UnknownDevice(device.to_string()));
+        }
 
-        val = Some(String::from("This is not a MACAddr"));
-        assert!(matches!(
-            MacAddress::try_from(&val),
-            Err(InvalidMacAddress(_))
-        ));
+        let conn = conn.map(|c| {
+            c.controller
+                .and_then(|uuid| self.get_connection_by_uuid(uuid))
+                .unwrap_or(c)
+        });
+        let local = conn
+            .filter(|c| c.is_up())
+            .map(|c| c.ip_config.nameservers.as_slice())
+            .unwrap_or_default();
+        Ok(self.dns.merge(local))
+    }
 
-        val = Some(String::from("de:ad:be:ef:2b:ad"));
-        assert_eq!(
-            MacAddress::try_from(&val).unwrap().to_string(),
-            String::from("de:ad:be:ef:2b:ad").to_uppercase()
-        );
+    /// Returns the name servers of the whole system.
+    ///
+    /// The servers of the active connections, in the order they are defined, are merged with the
+    /// global ones according to the [DnsPolicy].
+    pub fn system_dns(&self) -> Vec<IpAddr> {
+        let local: Vec<IpAddr> = self
+            .connections
+            .iter()
+            .filter(|c| c.is_up() && c.controller.is_none() && !c.is_loopback())
+            .flat_map(|c| c.ip_config.nameservers.iter().copied())
+            .collect();
+        self.dns.merge(&local)
     }
 
-    #[test]
-    fn test_add_connection() {
-        let mut state = NetworkState::default();
-        let uuid = Uuid::new_v4();
-        let conn0 = Connection {
-            id: "eth0".to_string(),
-            uuid,
-            ..Default::default()
-        };
-        state.add_connection(conn0).unwrap();
-        let found = state.get_connection("eth0").unwrap();
-        assert_eq!(found.uuid, uuid);
+    /// Returns the UUIDs of the connections that belong to the group with the given tag.
+    ///
+    /// Besides the tagged connections, the group includes the new controllers they depend on
+    /// (and the new ports of those controllers), as they could not be written otherwise.
+    ///
+    /// * `tag`: tag of the group.
+    /// * `base`: connections as they were read from the backend.
+    pub fn group_members(&self, tag: &str, base: &[Arc<Connection>]) -> HashSet<Uuid> {
+        let is_new = |uuid: Uuid| !base.iter().any(|b| b.uuid == uuid);
+        let mut members: HashSet<Uuid> = self
+            .connections
+            .iter()
+            .filter(|c| c.has_tag(tag))
+            .map(|c| c.uuid)
+            .collect();
+
+        loop {
+            let required: Vec<Uuid> = self
+                .connections
+                .iter()
+                .filter(|c| !members.contains(&c.uuid) && is_new(c.uuid))
+                .filter(|c| {
+                    let is_controller = self
+                        .connections
+                        .iter()
+                        .any(|p| p.controller == Some(c.uuid) && members.contains(&p.uuid));
+                    let is_port = c
+                        .controller
+                        .is_some_and(|ctrl| members.contains(&ctrl) && is_new(ctrl));
+                    is_controller || is_port
+                })
+                .map(|c| c.uuid)
+                .collect();
+            if required.is_empty() {
+                return members;
+            }
+            members.extend(required);
+        }
     }
 
-    #[test]
-    fn test_add_duplicated_connection() {
-        let mut state = NetworkState::default();
-        let mut conn0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
-        conn0.uuid = Uuid::new_v4();
-        state.add_connection(conn0.clone()).unwrap();
-        let error = state.add_connection(conn0).unwrap_err();
-        assert!(matches!(error, NetworkStateError::ConnectionExists(_)));
+    /// Returns a copy of the state in which only the connections of the given group keep their
+    /// pending changes (see [Self::group_members]).
+    ///
+    /// The rest of the connections are restored to their base version (i.e., as they were read
+    /// from the backend), so writing the resulting state only affects the group. The global
+    /// settings (DNS, connectivity check, etc.) are kept as they are.
+    ///
+    /// * `tag`: tag of the group.
+    /// * `base`: connections as they were read from the backend.
+    pub fn group_state(&self, tag: &str, base: &[Arc<Connection>]) -> NetworkState {
+        let members = self.group_members(tag, base);
+        let connections = self
+            .connections
+            .iter()
+            .filter_map(|conn| {
+                if members.contains(&conn.uuid) {
+                    Some(Arc::clone(conn))
+                } else {
+                    base.iter().find(|b| b.uuid == conn.uuid).cloned()
+                }
+            })
+            .collect();
+        NetworkState {
+            connections,
+            ..self.clone()
+        }
     }
 
-    #[test]
-    fn test_update_connection() {
-        let mut state = NetworkState::default();
-        let conn0 = Connection {
-            id: "eth0".to_string(),
-            uuid: Uuid::new_v4(),
-            ..Default::default()
-        };
-        state.add_connection(conn0).unwrap();
+    /// Merges the connections defined in the backend into the state.
+    ///
+    /// The connections that were changed in the state (e.g., through the D-Bus API) but not
+    /// written yet take precedence over the ones in the backend, so those changes are not lost.
+    /// Otherwise, the connections from the backend are added, updated or removed as needed.
+    ///
+    /// * `base`: connections as they were when last read from the backend.
+    /// * `external`: connections currently defined in the backend.
+    pub fn reconcile(
+        &mut self,
+        base: &[Arc<Connection>],
+        external: Vec<Arc<Connection>>,
+    ) -> ConnectionsChanges {
+        let mut changes = ConnectionsChanges::default();
+        let is_pristine = |conn: &Arc<Connection>| base.iter().any(|b| b == conn);
+        let is_known = |uuid: Uuid| base.iter().any(|b| b.uuid == uuid);
+        let external_uuids: HashSet<Uuid> = external.iter().map(|c| c.uuid).collect();
+
+        self.connections.retain(|conn| {
+            let gone = !external_uuids.contains(&conn.uuid) && is_pristine(conn);
+            if gone {
+                changes.removed.push(conn.id.clone());
+            }
+            !gone
+        });
+
+        for ext in external {
+            match self.connections.iter().position(|c| c.uuid == ext.uuid) {
+                Some(index) => {
+                    let local = &self.connections[index];
+                    if *local != ext && is_pristine(local) {
+                        changes.updated.push(ext.uuid);
+                        self.connections[index] = ext;
+                    }
+                }
+                None if !is_known(ext.uuid) => {
+                    changes.added.push(ext.uuid);
+                    self.connections.push(ext);
+                }
+                None => {}
+            }
+        }
 
-        let uuid = Uuid::new_v4();
-        let conn1 = Connection {
-            id: "eth0".to_string(),
-            uuid,
-            ..Default::default()
+        changes
+    }
+}
+
+/// Changes in the connections after synchronizing the state with the backend.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConnectionsChanges {
+    /// UUIDs of the added connections.
+    pub added: Vec<Uuid>,
+    /// UUIDs of the updated connections.
+    pub updated: Vec<Uuid>,
+    /// IDs of the removed connections.
+    pub removed: Vec<String>,
+}
+
+impl ConnectionsChanges {
+    /// Whether there are no changes.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Summary of the changes made when applying the configuration.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ApplySummary {
+    /// Number of added connections.
+    pub added: u32,
+    /// Number of updated connections.
+    pub updated: u32,
+    /// Number of removed connections.
+    pub removed: u32,
+    /// IDs of the connections that could not be written.
+    pub failed: Vec<String>,
+}
+
+/// Kind of change made to a connection when applying the configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionChange {
+    Added,
+    Updated,
+    Removed,
+}
+
+impl fmt::Display for ConnectionChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match &self {
+            ConnectionChange::Added => "added",
+            ConnectionChange::Updated => "updated",
+            ConnectionChange::Removed => "removed",
         };
-        state.update_connection(conn1).unwrap();
-        let found = state.get_connection("eth0").unwrap();
-        assert_eq!(found.uuid, uuid);
+        write!(f, "{}", name)
+    }
+}
+
+/// Result of writing a connection when applying the configuration.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConnectionApplyResult {
+    pub id: String,
+    pub uuid: Uuid,
+    pub change: ConnectionChange,
+    /// Error message if the connection could not be written.
+    pub error: Option<String>,
+}
+
+/// Result of applying the configuration, including the outcome for each written connection.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ApplyResult {
+    pub connections: Vec<ConnectionApplyResult>,
+    /// Static addresses that were already in use by other hosts.
+    pub conflicts: Vec<AddressConflict>,
+}
+
+impl ApplyResult {
+    /// Updates the last error of each written connection.
+    ///
+    /// The error is removed if the connection was written successfully.
+    ///
+    /// * `errors`: last errors indexed by connection UUID.
+    pub fn update_last_errors(&self, errors: &mut HashMap<Uuid, String>) {
+        for result in &self.connections {
+            match &result.error {
+                Some(error) => errors.insert(result.uuid, error.clone()),
+                None => errors.remove(&result.uuid),
+            };
+        }
+    }
+
+    /// Returns a summary of the changes.
+    pub fn summary(&self) -> ApplySummary {
+        let mut summary = ApplySummary::default();
+        for result in &self.connections {
+            if result.error.is_some() {
+                summary.failed.push(result.id.clone());
+                continue;
+            }
+            match result.change {
+                ConnectionChange::Added => summary.added += 1,
+                ConnectionChange::Updated => summary.updated += 1,
+                ConnectionChange::Removed => summary.removed += 1,
+            }
+        }
+        summary
+    }
+}
+
+/// Static IPv4 address which is already in use by another host.
+///
+/// The connections with a conflicting address are not activated when applying the configuration.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AddressConflict {
+    /// ID of the connection which contains the address.
+    pub connection: String,
+    pub address: Ipv4Addr,
+    /// MAC address of the host using the address.
+    pub mac: String,
+}
+
+impl fmt::Display for AddressConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The address {} is already in use by {}",
+            self.address, self.mac
+        )
+    }
+}
+
+impl From<&AddressConflict> for ConfigIssue {
+    fn from(value: &AddressConflict) -> Self {
+        ConfigIssue {
+            connection: value.connection.clone(),
+            description: value.to_string(),
+        }
+    }
+}
+
+/// Runtime state of a port (e.g., of a bond).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortState {
+    /// Interface name.
+    pub name: String,
+    /// Whether the port is activated.
+    pub active: bool,
+    /// Whether the port has carrier (e.g., the cable is plugged in).
+    pub carrier: bool,
+}
+
+/// Result of checking a bond once the configuration is applied.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BondCheck {
+    /// Runtime state of the bond ports.
+    pub ports: Vec<PortState>,
+}
+
+impl BondCheck {
+    /// Whether the bond has at least one active port with carrier.
+    pub fn is_healthy(&self) -> bool {
+        self.ports.iter().any(|p| p.active && p.carrier)
+    }
+
+    /// Returns the issues found in the bond.
+    ///
+    /// * `bond`: bond name to include in the messages.
+    pub fn issues(&self, bond: &str) -> Vec<String> {
+        let mut issues = vec![];
+        if !self.is_healthy() {
+            issues.push(format!(
+                "Bond '{}' does not have any active port with carrier",
+                bond
+            ));
+        }
+        for port in &self.ports {
+            if !port.active {
+                issues.push(format!(
+                    "Port '{}' of bond '{}' is not active",
+                    port.name, bond
+                ));
+            } else if !port.carrier {
+                issues.push(format!(
+                    "Port '{}' of bond '{}' has no carrier",
+                    port.name, bond
+                ));
+            }
+        }
+        issues
+    }
+}
+
+/// Connectivity check settings.
+///
+/// The connectivity check is used to determine whether the system has access to Internet.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConnectivityConfig {
+    /// Whether the connectivity check is enabled.
+    pub enabled: bool,
+    /// URI to check. If not set, the default one is used.
+    pub uri: Option<String>,
+    /// Interval (in seconds) between checks. If not set, the default one is used.
+    pub interval: Option<u32>,
+}
+
+/// Global DNS settings.
+///
+/// The global name servers are combined with the ones from each connection according to the
+/// [DnsPolicy]. Duplicated servers are removed, keeping the first occurrence.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DnsConfig {
+    /// Global name servers.
+    pub servers: Vec<IpAddr>,
+    /// Global search domains.
+    pub searches: Vec<String>,
+    /// How to combine the global name servers with the per-connection ones.
+    pub policy: DnsPolicy,
+    /// DNS resolver stack to use in the installed system.
+    pub stack: DnsStack,
+}
+
+impl DnsConfig {
+    /// Whether there are no global settings.
+    pub fn is_empty(&self) -> bool {
+        self.servers.is_empty() && self.searches.is_empty()
+    }
+
+    /// Merges the global name servers with the given ones.
+    ///
+    /// * `local`: per-connection name servers.
+    pub fn merge(&self, local: &[IpAddr]) -> Vec<IpAddr> {
+        let global = self.servers.as_slice();
+        let (first, second) = match self.policy {
+            DnsPolicy::Append => (local, global),
+            DnsPolicy::Prepend => (global, local),
+            DnsPolicy::Override if global.is_empty() => (local, global),
+            DnsPolicy::Override => (global, &[] as &[IpAddr]),
+        };
+
+        let mut servers = Vec::with_capacity(first.len() + second.len());
+        for server in first.iter().chain(second) {
+            if !servers.contains(server) {
+                servers.push(*server);
+            }
+        }
+        servers
+    }
+}
+
+/// Policy to combine the global name servers with the per-connection ones.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DnsPolicy {
+    /// Uses the global servers after the per-connection ones (e.g., as a fallback).
+    #[default]
+    Append,
+    /// Uses the global servers before the per-connection ones.
+    Prepend,
+    /// Uses only the global servers (unless there are none).
+    Override,
+}
+
+impl fmt::Display for DnsPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            DnsPolicy::Append => "append",
+            DnsPolicy::Prepend => "prepend",
+            DnsPolicy::Override => "override",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl TryFrom<&str> for DnsPolicy {
+    type Error = NetworkStateError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "append" => Ok(DnsPolicy::Append),
+            "prepend" => Ok(DnsPolicy::Prepend),
+            "override" => Ok(DnsPolicy::Override),
+            _ => Err(NetworkStateError::InvalidDnsPolicy(value.to_string())),
+        }
+    }
+}
+
+/// DNS resolver stack of the installed system.
+///
+/// It determines who manages resolv.conf. Each product has its own default, so Agama does not
+/// change it unless a different stack is requested.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum DnsStack {
+    /// Keeps the product's default.
+    #[default]
+    Default,
+    /// NetworkManager hands the DNS settings to netconfig.
+    Netconfig,
+    /// NetworkManager hands the DNS settings to systemd-resolved.
+    Resolved,
+    /// NetworkManager writes resolv.conf as a plain file.
+    File,
+}
+
+impl fmt::Display for DnsStack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            DnsStack::Default => "default",
+            DnsStack::Netconfig => "netconfig",
+            DnsStack::Resolved => "systemd-resolved",
+            DnsStack::File => "file",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl TryFrom<&str> for DnsStack {
+    type Error = NetworkStateError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "default" => Ok(DnsStack::Default),
+            "netconfig" => Ok(DnsStack::Netconfig),
+            "systemd-resolved" => Ok(DnsStack::Resolved),
+            "file" => Ok(DnsStack::File),
+            _ => Err(NetworkStateError::InvalidDnsStack(value.to_string())),
+        }
+    }
+}
+
+/// Network settings for kdump in the installed system.
+///
+/// When the crash dumps are saved to a remote location (e.g., "nfs://server/var/crash"), the kdump
+/// kernel needs to know which interface to bring up and how to configure its addresses.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct KdumpConfig {
+    /// Interface to use (e.g., "eth0"). If not set, the one with the default route is used.
+    pub interface: Option<String>,
+    /// How to configure the addresses of the interface.
+    pub addressing: KdumpAddressing,
+    /// Location to save the crash dumps to (e.g., "nfs://server/var/crash").
+    pub save_dir: Option<String>,
+}
+
+impl KdumpConfig {
+    /// Supported schemes for the save directory.
+    pub const SAVE_DIR_SCHEMES: [&'static str; 6] = ["file", "ftp", "sftp", "ssh", "nfs", "cifs"];
+
+    /// Whether there are no kdump settings (so the kdump configuration is not touched).
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Sets the location to save the crash dumps to.
+    ///
+    /// * `save_dir`: URL of the location. `None` means using the kdump default.
+    pub fn set_save_dir(&mut self, save_dir: Option<String>) -> Result<(), NetworkStateError> {
+        if let Some(save_dir) = &save_dir {
+            let valid = save_dir
+                .split_once("://")
+                .is_some_and(|(scheme, _)| Self::SAVE_DIR_SCHEMES.contains(&scheme));
+            if !valid {
+                return Err(NetworkStateError::InvalidKdumpSaveDir(save_dir.to_string()));
+            }
+        }
+        self.save_dir = save_dir;
+        Ok(())
+    }
+
+    /// Value for the `KDUMP_NETCONFIG` setting (e.g., "eth0:static").
+    pub fn netconfig(&self) -> String {
+        format!(
+            "{}:{}",
+            self.interface.as_deref().unwrap_or("default"),
+            self.addressing
+        )
+    }
+}
+
+/// How kdump configures the addresses of the interface.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum KdumpAddressing {
+    /// IPv4 DHCP.
+    #[default]
+    Dhcp,
+    /// IPv6 DHCP.
+    Dhcp6,
+    /// The addresses of the interface in the installed system.
+    Static,
+}
+
+impl fmt::Display for KdumpAddressing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            KdumpAddressing::Dhcp => "dhcp",
+            KdumpAddressing::Dhcp6 => "dhcp6",
+            KdumpAddressing::Static => "static",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl TryFrom<&str> for KdumpAddressing {
+    type Error = NetworkStateError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "dhcp" => Ok(KdumpAddressing::Dhcp),
+            "dhcp6" => Ok(KdumpAddressing::Dhcp6),
+            "static" => Ok(KdumpAddressing::Static),
+            _ => Err(NetworkStateError::InvalidKdumpAddressing(value.to_string())),
+        }
+    }
+}
+
+/// Template to create several connections with the same settings (e.g., one per network
+/// interface).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionTemplate {
+    /// ID of the connections. The [INTERFACE_PLACEHOLDER](Self::INTERFACE_PLACEHOLDER) is
+    /// replaced with the name of the interface.
+    pub id: String,
+    pub device_type: DeviceType,
+    pub ip_config: IpConfig,
+    /// ID of the controller connection (e.g., a bond).
+    pub controller: Option<String>,
+}
+
+impl ConnectionTemplate {
+    pub const INTERFACE_PLACEHOLDER: &'static str = "{interface}";
+
+    /// Creates a template for the given type of connections.
+    ///
+    /// * `device_type`: type of the connections.
+    pub fn new(device_type: DeviceType) -> Self {
+        Self {
+            id: Self::INTERFACE_PLACEHOLDER.to_string(),
+            device_type,
+            ip_config: Default::default(),
+            controller: None,
+        }
+    }
+
+    /// Creates a connection for the given interface.
+    ///
+    /// * `interface`: interface name.
+    pub fn instantiate(&self, interface: &str) -> Connection {
+        let id = self.id.replace(Self::INTERFACE_PLACEHOLDER, interface);
+        let mut conn = Connection::new(id, self.device_type);
+        conn.interface = Some(interface.to_string());
+        conn.ip_config = self.ip_config.clone();
+        conn
+    }
+}
+
+/// Policy to resolve the conflicts between connections sharing the same ID.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ConflictPolicy {
+    /// Appends a number to the ID of the duplicated connections.
+    #[default]
+    Rename,
+    /// Keeps the first connection and ignores the rest.
+    Skip,
+    /// Keeps the last connection and removes the rest when the changes are applied.
+    Replace,
+}
+
+impl fmt::Display for ConflictPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            ConflictPolicy::Rename => "rename",
+            ConflictPolicy::Skip => "skip",
+            ConflictPolicy::Replace => "replace",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl TryFrom<&str> for ConflictPolicy {
+    type Error = NetworkStateError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "rename" => Ok(ConflictPolicy::Rename),
+            "skip" => Ok(ConflictPolicy::Skip),
+            "replace" => Ok(ConflictPolicy::Replace),
+            _ => Err(NetworkStateError::InvalidConflictPolicy(value.to_string())),
+        }
+    }
+}
+
+/// Relationship between the original ID of a connection and the resulting one after resolving
+/// the conflicts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdMapping {
+    /// ID of the connection as read from the backend.
+    pub original_id: String,
+    /// Connection UUID.
+    pub uuid: Uuid,
+    /// Resulting ID, if the connection was kept.
+    pub id: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::error::NetworkStateError;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_connection_summary() {
+        let mut wlan0 = Connection::new("Home Wi-Fi".to_string(), DeviceType::Wireless);
+        wlan0.interface = Some("wlan0".to_string());
+        wlan0.ip_config.method4 = Ipv4Method::Manual;
+        wlan0.set_down();
+
+        let summary = wlan0.summary();
+        assert_eq!(summary.id, "Home Wi-Fi");
+        assert_eq!(summary.uuid, wlan0.uuid);
+        assert_eq!(summary.type_name, "wireless");
+        assert_eq!(summary.interface, Some("wlan0".to_string()));
+        assert_eq!(summary.method4.to_string(), "manual");
+        assert_eq!(summary.method6.to_string(), "disabled");
+        assert_eq!(summary.status.to_string(), "down");
+    }
+
+    #[test]
+    fn test_macaddress() {
+        let mut val: Option<String> = None;
+        assert!(matches!(
+            MacAddress::try_from(&val).unwrap(),
+            MacAddress::Unset
+        ));
+
+        val = Some(String::from(""));
+        assert!(matches!(
+            MacAddress::try_from(&val).unwrap(),
+            MacAddress::Unset
+        ));
+
+        val = Some(String::from("preserve"));
+        assert!(matches!(
+            MacAddress::try_from(&val).unwrap(),
+            MacAddress::Preserve
+        ));
+
+        val = Some(String::from("permanent"));
+        assert!(matches!(
+            MacAddress::try_from(&val).unwrap(),
+            MacAddress::Permanent
+        ));
+
+        val = Some(String::from("random"));
+        assert!(matches!(
+            MacAddress::try_from(&val).unwrap(),
+            MacAddress::Random
+        ));
+
+        val = Some(String::from("stable"));
+        assert!(matches!(
+            MacAddress::try_from(&val).unwrap(),
+            MacAddress::Stable
+        ));
+
+        val = Some(String::from("This is not a MACAddr"));
+        assert!(matches!(
+            MacAddress::try_from(&val),
+            Err(InvalidMacAddress(_))
+        ));
+
+        val = Some(String::from("de:ad:be:ef:2b:ad"));
+        assert_eq!(
+            MacAddress::try_from(&val).unwrap().to_string(),
+            String::from("de:ad:be:ef:2b:ad").to_uppercase()
+        );
+    }
+
+    #[test]
+    fn test_add_connection() {
+        let mut state = NetworkState::default();
+        let uuid = Uuid::new_v4();
+        let conn0 = Connection {
+            id: "eth0".to_string(),
+            uuid,
+            ..Default::default()
+        };
+        state.add_connection(conn0).unwrap();
+        let found = state.get_connection("eth0").unwrap();
+        assert_eq!(found.uuid, uuid);
+    }
+
+    #[test]
+    fn test_add_connection_with_addr_gen_mode() {
+        let mut state = NetworkState {
+            addr_gen_mode: Some(Ipv6AddrGenMode::StablePrivacy),
+            ..Default::default()
+        };
+        let eth0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
+        let mut eth1 = Connection::new("eth1".to_string(), DeviceType::Ethernet);
+        eth1.ip_config.addr_gen_mode6 = Some(Ipv6AddrGenMode::Eui64);
+        state.add_connections(vec![eth0, eth1]).unwrap();
+
+        let eth0 = state.get_connection("eth0").unwrap();
+        assert_eq!(
+            eth0.ip_config.addr_gen_mode6,
+            Some(Ipv6AddrGenMode::StablePrivacy)
+        );
+        let eth1 = state.get_connection("eth1").unwrap();
+        assert_eq!(eth1.ip_config.addr_gen_mode6, Some(Ipv6AddrGenMode::Eui64));
+    }
+
+    #[test]
+    fn test_bandwidth_limit() {
+        let mut state = NetworkState::default();
+        let mut eth0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
+        assert_eq!(state.bandwidth_limit(&eth0), None);
+
+        state.bandwidth_limit = Some(10_000);
+        assert_eq!(state.bandwidth_limit(&eth0), Some(10_000));
+
+        eth0.bandwidth_limit = Some(5_000);
+        assert_eq!(state.bandwidth_limit(&eth0), Some(5_000));
+    }
+
+    #[test]
+    fn test_add_duplicated_connection() {
+        let mut state = NetworkState::default();
+        let mut conn0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
+        conn0.uuid = Uuid::new_v4();
+        state.add_connection(conn0.clone()).unwrap();
+        let error = state.add_connection(conn0).unwrap_err();
+        assert!(matches!(error, NetworkStateError::ConnectionExists(_)));
+    }
+
+    #[test]
+    fn test_update_connection() {
+        let mut state = NetworkState::default();
+        let conn0 = Connection {
+            id: "eth0".to_string(),
+            uuid: Uuid::new_v4(),
+            ..Default::default()
+        };
+        state.add_connection(conn0).unwrap();
+
+        let uuid = Uuid::new_v4();
+        let conn1 = Connection {
+            id: "eth0".to_string(),
+            uuid,
+            ..Default::default()
+        };
+        state.update_connection(conn1).unwrap();
+        let found = state.get_connection("eth0").unwrap();
+        assert_eq!(found.uuid, uuid);
+    }
+
+    #[test]
+    fn test_update_unknown_connection() {
+        let mut state = NetworkState::default();
+        let conn0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
+        let error = state.update_connection(conn0).unwrap_err();
+        assert!(matches!(error, NetworkStateError::UnknownConnection(_)));
+    }
+
+    #[test]
+    fn test_remove_connection() {
+        let mut state = NetworkState::default();
+        let conn0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
+        let uuid = conn0.uuid;
+        state.add_connection(conn0).unwrap();
+        state.remove_connection(uuid).unwrap();
+        let found = state.get_connection_by_uuid(uuid).unwrap();
+        assert!(found.is_removed());
+        assert!(state.get_connection("eth0").is_none());
+    }
+
+    #[test]
+    fn test_remove_unknown_connection() {
+        let mut state = NetworkState::default();
+        let error = state.remove_connection(Uuid::new_v4()).unwrap_err();
+        assert!(matches!(error, NetworkStateError::UnknownConnection(_)));
+    }
+
+    #[test]
+    fn test_clone_connection() {
+        let mut state = NetworkState::default();
+        let mut wlan0 = Connection::new("wlan0".to_string(), DeviceType::Wireless);
+        wlan0.config = ConnectionConfig::Wireless(WirelessConfig {
+            ssid: SSID(b"agama".to_vec()),
+            password: Some("nots3cr3t".to_string()),
+            ..Default::default()
+        });
+        let uuid = wlan0.uuid;
+        state.add_connection(wlan0).unwrap();
+
+        let copy = state.clone_connection("wlan0", "wlan1", false).unwrap();
+        assert_ne!(copy.uuid, uuid);
+        let ConnectionConfig::Wireless(wireless) = &copy.config else {
+            panic!("Unexpected configuration");
+        };
+        assert_eq!(wireless.ssid, SSID(b"agama".to_vec()));
+        assert_eq!(wireless.password, None);
+
+        let copy = state.clone_connection("wlan0", "wlan2", true).unwrap();
+        let ConnectionConfig::Wireless(wireless) = &copy.config else {
+            panic!("Unexpected configuration");
+        };
+        assert_eq!(wireless.password, Some("nots3cr3t".to_string()));
+
+        let error = state.clone_connection("wlan0", "wlan1", true).unwrap_err();
+        assert!(matches!(error, NetworkStateError::ConnectionExists(_)));
+        let error = state.clone_connection("eth0", "eth1", true).unwrap_err();
+        assert!(matches!(error, NetworkStateError::UnknownConnection(_)));
+    }
+
+    #[test]
+    fn test_add_connections_from_template() {
+        let mut state = NetworkState::default();
+        let bond0 = Connection::new("bond0".to_string(), DeviceType::Bond);
+        let bond_uuid = bond0.uuid;
+        state.add_connection(bond0).unwrap();
+
+        let mut template = ConnectionTemplate::new(DeviceType::Ethernet);
+        template.id = "port-{interface}".to_string();
+        template.controller = Some("bond0".to_string());
+        template.ip_config.method4 = Ipv4Method::Disabled;
+        let interfaces = vec!["eth0".to_string(), "eth1".to_string()];
+        let added = state
+            .add_connections_from_template(&template, &interfaces)
+            .unwrap();
+        assert_eq!(added.len(), 2);
+
+        let eth1 = state.get_connection("port-eth1").unwrap();
+        assert_eq!(eth1.interface, Some("eth1".to_string()));
+        assert_eq!(eth1.controller, Some(bond_uuid));
+
+        // nothing is added if any of the IDs is taken
+        let interfaces = vec!["eth2".to_string(), "eth1".to_string()];
+        let error = state
+            .add_connections_from_template(&template, &interfaces)
+            .unwrap_err();
+        assert!(matches!(error, NetworkStateError::ConnectionExists(_)));
+        assert!(state.get_connection("port-eth2").is_none());
+    }
+
+    #[test]
+    fn test_set_regulatory_domain() {
+        let mut state = NetworkState::default();
+        state.set_regulatory_domain(Some("DE".to_string())).unwrap();
+        assert_eq!(state.regulatory_domain, Some("DE".to_string()));
+
+        let error = state
+            .set_regulatory_domain(Some("Germany".to_string()))
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            NetworkStateError::InvalidRegulatoryDomain(_)
+        ));
+        assert_eq!(state.regulatory_domain, Some("DE".to_string()));
+    }
+
+    #[test]
+    fn test_set_hostname() {
+        let mut state = NetworkState::default();
+        state
+            .set_hostname(Some("agama.example.net".to_string()))
+            .unwrap();
+        assert_eq!(state.hostname, Some("agama.example.net".to_string()));
+
+        for hostname in ["", "-agama", "agama_1", "agama..example.net"] {
+            let error = state.set_hostname(Some(hostname.to_string())).unwrap_err();
+            assert!(matches!(error, NetworkStateError::InvalidHostname(_)));
+        }
+        assert_eq!(state.hostname, Some("agama.example.net".to_string()));
+
+        state.set_hostname(None).unwrap();
+        assert_eq!(state.hostname, None);
+    }
+
+    #[test]
+    fn test_set_wireless_mode() {
+        let mut conn = Connection::new("wlan0".to_string(), DeviceType::Wireless);
+        conn.ip_config.method4 = Ipv4Method::Auto;
+
+        conn.set_wireless_mode(WirelessMode::AP).unwrap();
+        assert_eq!(conn.ip_config.method4, Ipv4Method::Shared);
+        let ConnectionConfig::Wireless(config) = &conn.config else {
+            panic!("Unexpected connection type");
+        };
+        assert_eq!(config.mode, WirelessMode::AP);
+
+        conn.set_wireless_mode(WirelessMode::Infra).unwrap();
+        assert_eq!(conn.ip_config.method4, Ipv4Method::Auto);
+
+        conn.ip_config.method4 = Ipv4Method::Manual;
+        conn.set_wireless_mode(WirelessMode::AP).unwrap();
+        assert_eq!(conn.ip_config.method4, Ipv4Method::Manual);
+
+        let mut conn = Connection::new("eth0".to_string(), DeviceType::Ethernet);
+        let error = conn.set_wireless_mode(WirelessMode::AP).unwrap_err();
+        assert!(matches!(error, NetworkStateError::UnexpectedConfiguration));
+    }
+
+    #[test]
+    fn test_add_connection_from_device() {
+        let device = Device {
+            name: "eth0".to_string(),
+            type_: DeviceType::Ethernet,
+            wireless_capabilities: None,
+            modem_capabilities: None,
+            managed: true,
+        };
+        let mut state = NetworkState::new(vec![device], vec![]);
+        let runtime = IpConfig {
+            addresses: vec![
+                "192.168.1.10/24".parse().unwrap(),
+                "fe80::1/64".parse().unwrap(),
+            ],
+            gateway4: Some("192.168.1.1".parse().unwrap()),
+            nameservers: vec!["192.168.1.1".parse().unwrap()],
+            ..Default::default()
+        };
+
+        let conn = state
+            .add_connection_from_device("eth0", runtime.clone(), true)
+            .unwrap();
+        assert_eq!(conn.interface, Some("eth0".to_string()));
+        let ip_config = &conn.ip_config;
+        assert_eq!(ip_config.method4, Ipv4Method::Manual);
+        assert_eq!(ip_config.method6, Ipv6Method::Auto);
+        assert_eq!(
+            ip_config.addresses,
+            vec!["192.168.1.10/24".parse().unwrap()]
+        );
+        assert_eq!(ip_config.gateway4, Some("192.168.1.1".parse().unwrap()));
+        assert_eq!(
+            ip_config.nameservers,
+            vec!["192.168.1.1".parse::<IpAddr>().unwrap()]
+        );
+
+        let error = state
+            .add_connection_from_device("eth0", runtime.clone(), false)
+            .unwrap_err();
+        assert!(matches!(error, NetworkStateError::ConnectionExists(_)));
+
+        let error = state
+            .add_connection_from_device("eth1", runtime, false)
+            .unwrap_err();
+        assert!(matches!(error, NetworkStateError::UnknownDevice(_)));
+    }
+
+    #[test]
+    fn test_team_migrations() {
+        let mut team0 = Connection::new("team0".to_string(), DeviceType::Bond);
+        team0.migrated_from_team = true;
+        team0.config = ConnectionConfig::Bond(BondConfig {
+            mode: BondMode::ActiveBackup,
+            ..Default::default()
+        });
+        let bond0 = Connection::new("bond0".to_string(), DeviceType::Bond);
+        let state = NetworkState::new(vec![], vec![team0, bond0]);
+
+        assert_eq!(
+            state.team_migrations(),
+            vec![("team0".to_string(), BondMode::ActiveBackup)]
+        );
+    }
+
+    #[test]
+    fn test_ip_config_issues() {
+        let ip_config = IpConfig {
+            addresses: vec![
+                "192.168.1.10/24".parse().unwrap(),
+                "224.0.0.5/24".parse().unwrap(),
+                "10.0.0.0/8".parse().unwrap(),
+                "2001:db8::10/64".parse().unwrap(),
+            ],
+            gateway4: Some("192.168.2.1".parse().unwrap()),
+            gateway6: Some("fe80::1".parse().unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(
+            ip_config.issues(),
+            vec![
+                "Address 224.0.0.5/24 is a multicast address".to_string(),
+                "Address 10.0.0.0/8 is the network address of its subnet".to_string(),
+                "Gateway 192.168.2.1 is not part of any of the connection subnets".to_string(),
+            ]
+        );
+
+        let ip_config = IpConfig {
+            addresses: vec!["192.168.1.10/24".parse().unwrap()],
+            gateway4: Some("192.168.1.1".parse().unwrap()),
+            gateway6: Some("2001:db8::1".parse().unwrap()),
+            ..Default::default()
+        };
+        assert!(ip_config.issues().is_empty());
+    }
+
+    #[test]
+    fn test_state_issues() {
+        let mut eth0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
+        eth0.ip_config.addresses = vec!["127.0.0.2/8".parse().unwrap()];
+        let mut eth1 = eth0.clone();
+        eth1.id = "eth1".to_string();
+        eth1.uuid = Uuid::new_v4();
+        eth1.status = Status::Removed;
+        let state = NetworkState::new(vec![], vec![eth0, eth1]);
+
+        assert_eq!(
+            state.issues(),
+            vec![ConfigIssue {
+                connection: "eth0".to_string(),
+                description: "Address 127.0.0.2/8 is a loopback address".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_missing_secrets() {
+        let mut wlan0 = Connection::new("wlan0".to_string(), DeviceType::Wireless);
+        assert!(wlan0.missing_secrets().is_empty());
+
+        wlan0.config = ConnectionConfig::Wireless(WirelessConfig {
+            security: SecurityProtocol::WPA2,
+            ..Default::default()
+        });
+        assert_eq!(wlan0.missing_secrets(), vec!["psk".to_string()]);
+        wlan0.set_secret("psk", "nots3cr3t").unwrap();
+        assert!(wlan0.missing_secrets().is_empty());
+
+        let mut ppp0 = Connection::new("ppp0".to_string(), DeviceType::Pppoe);
+        assert_eq!(ppp0.missing_secrets(), vec!["pppoe-password".to_string()]);
+        let error = ppp0.set_secret("psk", "nots3cr3t").unwrap_err();
+        assert!(matches!(error, NetworkStateError::InvalidSecret(_)));
+        ppp0.set_secret("pppoe-password", "nots3cr3t").unwrap();
+        assert!(ppp0.missing_secrets().is_empty());
+
+        let mut macsec0 = Connection::new("macsec0".to_string(), DeviceType::Macsec);
+        assert_eq!(macsec0.missing_secrets(), vec!["macsec-cak".to_string()]);
+        macsec0.set_secret("macsec-cak", "nots3cr3t").unwrap();
+        assert!(macsec0.missing_secrets().is_empty());
+        macsec0.config = ConnectionConfig::Macsec(MacsecConfig {
+            mode: MacsecMode::Eap,
+            ..Default::default()
+        });
+        assert!(macsec0.missing_secrets().is_empty());
+    }
+
+    #[test]
+    fn test_ignored_devices() {
+        let devices = ["eth0", "eth1"]
+            .iter()
+            .map(|name| Device {
+                name: name.to_string(),
+                type_: DeviceType::Ethernet,
+                wireless_capabilities: None,
+                modem_capabilities: None,
+                managed: true,
+            })
+            .collect();
+        let mut eth1 = Connection::new("eth1".to_string(), DeviceType::Ethernet);
+        eth1.interface = Some("eth1".to_string());
+        let mut state = NetworkState::new(devices, vec![eth1.clone()]);
+        state.ignored_devices = vec!["eth1".to_string()];
+
+        let names: Vec<_> = state
+            .visible_devices()
+            .into_iter()
+            .map(|d| d.name)
+            .collect();
+        assert_eq!(names, vec!["eth0".to_string()]);
+        assert!(state.is_ignored(&eth1));
+    }
+
+    #[test]
+    fn test_apply_result_summary() {
+        let result = |id: &str, change, error: Option<&str>| ConnectionApplyResult {
+            id: id.to_string(),
+            uuid: Uuid::new_v4(),
+            change,
+            error: error.map(|e| e.to_string()),
+        };
+        let apply_result = ApplyResult {
+            connections: vec![
+                result("eth0", ConnectionChange::Added, None),
+                result("eth1", ConnectionChange::Updated, None),
+                result("eth2", ConnectionChange::Updated, Some("failed")),
+                result("eth3", ConnectionChange::Removed, None),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            apply_result.summary(),
+            ApplySummary {
+                added: 1,
+                updated: 1,
+                removed: 1,
+                failed: vec!["eth2".to_string()]
+            }
+        );
+    }
+
+    #[test]
+    fn test_apply_result_update_last_errors() {
+        let (eth0, eth1, eth2) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let result = |uuid: Uuid, error: Option<&str>| ConnectionApplyResult {
+            id: uuid.to_string(),
+            uuid,
+            change: ConnectionChange::Updated,
+            error: error.map(|e| e.to_string()),
+        };
+        let mut errors = HashMap::from([
+            (eth0, "old error".to_string()),
+            (eth2, "old error".to_string()),
+        ]);
+        let apply_result = ApplyResult {
+            connections: vec![result(eth0, None), result(eth1, Some("failed"))],
+            ..Default::default()
+        };
+
+        apply_result.update_last_errors(&mut errors);
+        assert_eq!(errors.get(&eth0), None);
+        assert_eq!(errors.get(&eth1), Some(&"failed".to_string()));
+        // not written, so the error is kept
+        assert_eq!(errors.get(&eth2), Some(&"old error".to_string()));
+    }
+
+    #[test]
+    fn test_keep_local_settings() {
+        let mut eth0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
+        eth0.externally_managed = true;
+        let mut previous = NetworkState::new(vec![], vec![eth0.clone()]);
+        previous.ignored_devices = vec!["eth2".to_string()];
+
+        eth0.externally_managed = false;
+        let eth1 = Connection::new("eth1".to_string(), DeviceType::Ethernet);
+        let mut state = NetworkState::new(vec![], vec![eth0, eth1]);
+        state.keep_local_settings(&previous);
+
+        assert!(state.get_connection("eth0").unwrap().externally_managed);
+        assert!(!state.get_connection("eth1").unwrap().externally_managed);
+        assert_eq!(state.ignored_devices, vec!["eth2".to_string()]);
+    }
+
+    #[test]
+    fn test_dns_merge() {
+        let global: IpAddr = "192.168.1.1".parse().unwrap();
+        let local: Vec<IpAddr> = vec!["10.0.0.1".parse().unwrap(), global];
+        let mut dns = DnsConfig {
+            servers: vec![global],
+            ..Default::default()
+        };
+
+        assert_eq!(dns.merge(&local), vec![local[0], global]);
+
+        dns.policy = DnsPolicy::Prepend;
+        assert_eq!(dns.merge(&local), vec![global, local[0]]);
+
+        dns.policy = DnsPolicy::Override;
+        assert_eq!(dns.merge(&local), vec![global]);
+
+        dns.servers.clear();
+        assert_eq!(dns.merge(&local), local);
+    }
+
+    #[test]
+    fn test_kdump_config() {
+        let mut kdump = KdumpConfig::default();
+        assert!(kdump.is_empty());
+        assert_eq!(kdump.netconfig(), "default:dhcp");
+
+        kdump.interface = Some("eth0".to_string());
+        kdump.addressing = KdumpAddressing::try_from("static").unwrap();
+        assert_eq!(kdump.netconfig(), "eth0:static");
+
+        kdump
+            .set_save_dir(Some("nfs://192.168.1.1/var/crash".to_string()))
+            .unwrap();
+        let error = kdump
+            .set_save_dir(Some("http://192.168.1.1/crash".to_string()))
+            .unwrap_err();
+        assert!(matches!(error, NetworkStateError::InvalidKdumpSaveDir(_)));
+        assert_eq!(
+            kdump.save_dir,
+            Some("nfs://192.168.1.1/var/crash".to_string())
+        );
+    }
+
+    #[test]
+    fn test_effective_dns() {
+        let global: IpAddr = "192.168.1.1".parse().unwrap();
+        let local: IpAddr = "10.0.0.1".parse().unwrap();
+
+        let bond0 = Connection {
+            id: "bond0".to_string(),
+            interface: Some("bond0".to_string()),
+            ip_config: IpConfig {
+                nameservers: vec![local],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let eth0 = Connection {
+            id: "eth0".to_string(),
+            interface: Some("eth0".to_string()),
+            controller: Some(bond0.uuid),
+            ..Default::default()
+        };
+        let mut state = NetworkState::new(vec![], vec![bond0, eth0]);
+        state.dns = DnsConfig {
+            servers: vec![global],
+            policy: DnsPolicy::Prepend,
+            ..Default::default()
+        };
+
+        assert_eq!(state.effective_dns("bond0").unwrap(), vec![global, local]);
+        assert_eq!(state.effective_dns("eth0").unwrap(), vec![global, local]);
+        assert_eq!(state.system_dns(), vec![global, local]);
+        assert!(matches!(
+            state.effective_dns("eth1"),
+            Err(NetworkStateError::UnknownDevice(_))
+        ));
+    }
+
+    #[test]
+    fn test_bond_check() {
+        let port = |name: &str, active, carrier| PortState {
+            name: name.to_string(),
+            active,
+            carrier,
+        };
+
+        let check = BondCheck {
+            ports: vec![port("eth0", true, true), port("eth1", true, false)],
+        };
+        assert!(check.is_healthy());
+        assert_eq!(
+            check.issues("bond0"),
+            vec!["Port 'eth1' of bond 'bond0' has no carrier".to_string()]
+        );
+
+        let check = BondCheck {
+            ports: vec![port("eth0", false, true)],
+        };
+        assert!(!check.is_healthy());
+        assert_eq!(check.issues("bond0").len(), 2);
+
+        assert!(!BondCheck::default().is_healthy());
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let base: Vec<_> = ["eth0", "eth1", "wlan0"]
+            .iter()
+            .map(|id| Arc::new(Connection::new(id.to_string(), DeviceType::Ethernet)))
+            .collect();
+        let conns = base.iter().map(|c| Connection::clone(c)).collect();
+        let mut state = NetworkState::new(vec![], conns);
+        // pending changes
+        Arc::make_mut(&mut state.connections[0]).interface = Some("eth0".to_string());
+        let br0 = Connection::new("br0".to_string(), DeviceType::Bridge);
+        state.add_connection(br0).unwrap();
+
+        // external changes
+        let mut external = base.clone();
+        Arc::make_mut(&mut external[0]).interface = Some("enp1s0".to_string());
+        Arc::make_mut(&mut external[1]).interface = Some("enp2s0".to_string());
+        external.remove(2);
+        let eth2 = Connection::new("eth2".to_string(), DeviceType::Ethernet);
+        external.push(Arc::new(eth2.clone()));
+
+        let changes = state.reconcile(&base, external);
+        assert_eq!(changes.added, vec![eth2.uuid]);
+        assert_eq!(changes.updated, vec![base[1].uuid]);
+        assert_eq!(changes.removed, vec!["wlan0".to_string()]);
+
+        let eth0 = state.get_connection("eth0").unwrap();
+        assert_eq!(eth0.interface, Some("eth0".to_string()));
+        let eth1 = state.get_connection("eth1").unwrap();
+        assert_eq!(eth1.interface, Some("enp2s0".to_string()));
+        assert!(state.get_connection("wlan0").is_none());
+        assert!(state.get_connection("eth2").is_some());
+        assert!(state.get_connection("br0").is_some());
+    }
+
+    #[test]
+    fn test_connection_snapshots() {
+        let eth0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
+        let eth1 = Connection::new("eth1".to_string(), DeviceType::Ethernet);
+        let (eth0_uuid, eth1_uuid) = (eth0.uuid, eth1.uuid);
+        let mut state = NetworkState::new(vec![], vec![eth0, eth1]);
+
+        let eth0_snapshot = state.get_connection_snapshot(eth0_uuid).unwrap();
+        let eth1_snapshot = state.get_connection_snapshot(eth1_uuid).unwrap();
+        let copy = state.clone();
+        assert!(Arc::ptr_eq(&copy.connections[0], &eth0_snapshot));
+
+        let eth0 = state.get_connection_by_uuid_mut(eth0_uuid).unwrap();
+        eth0.interface = Some("eth0".to_string());
+        assert_eq!(eth0_snapshot.interface, None);
+        assert_eq!(
+            state.get_connection("eth0").unwrap().interface,
+            Some("eth0".to_string())
+        );
+        let eth1_current = state.get_connection_snapshot(eth1_uuid).unwrap();
+        assert!(Arc::ptr_eq(&eth1_current, &eth1_snapshot));
+    }
+
+    fn duplicated_connections() -> NetworkState {
+        let conns = ["eth0", "eth0-1", "eth0", "eth0"]
+            .iter()
+            .map(|id| Connection::new(id.to_string(), DeviceType::Ethernet))
+            .collect();
+        NetworkState::new(vec![], conns)
     }
 
     #[test]
-    fn test_update_unknown_connection() {
-        let mut state = NetworkState::default();
-        let conn0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
-        let error = state.update_connection(conn0).unwrap_err();
-        assert!(matches!(error, NetworkStateError::UnknownConnection(_)));
+    fn test_resolve_conflicts_rename() {
+        let mut state = duplicated_connections();
+        let mapping = state.resolve_conflicts(ConflictPolicy::Rename);
+        let ids: Vec<_> = state.connections.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["eth0", "eth0-1", "eth0-2", "eth0-3"]);
+        assert_eq!(mapping[2].original_id, "eth0");
+        assert_eq!(mapping[2].id, Some("eth0-2".to_string()));
     }
 
     #[test]
-    fn test_remove_connection() {
-        let mut state = NetworkState::default();
-        let conn0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
-        let uuid = conn0.uuid;
-        state.add_connection(conn0).unwrap();
-        state.remove_connection(uuid).unwrap();
-        let found = state.get_connection("eth0").unwrap();
-        assert!(found.is_removed());
+    fn test_resolve_conflicts_skip() {
+        let mut state = duplicated_connections();
+        let first = state.connections[0].uuid;
+        let mapping = state.resolve_conflicts(ConflictPolicy::Skip);
+        assert_eq!(state.connections.len(), 2);
+        assert_eq!(state.get_connection("eth0").unwrap().uuid, first);
+        assert_eq!(mapping[3].id, None);
     }
 
     #[test]
-    fn test_remove_unknown_connection() {
-        let mut state = NetworkState::default();
-        let error = state.remove_connection(Uuid::new_v4()).unwrap_err();
-        assert!(matches!(error, NetworkStateError::UnknownConnection(_)));
+    fn test_resolve_conflicts_replace() {
+        let mut state = duplicated_connections();
+        let last = state.connections[3].uuid;
+        let mapping = state.resolve_conflicts(ConflictPolicy::Replace);
+        let kept: Vec<_> = state
+            .connections
+            .iter()
+            .filter(|c| c.id == "eth0" && !c.is_removed())
+            .collect();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].uuid, last);
+        assert_eq!(state.get_connection("eth0").unwrap().uuid, last);
+        assert_eq!(mapping[0].id, None);
+        assert_eq!(mapping[3].id, Some("eth0".to_string()));
     }
 
     #[test]
@@ -299,6 +1928,106 @@ mod tests {
         assert!(conn.is_loopback());
     }
 
+    #[test]
+    fn test_set_metadata() {
+        let mut conn = Connection::default();
+        let metadata = HashMap::from([("ticket".to_string(), "ABC-123".to_string())]);
+        conn.set_metadata(metadata.clone()).unwrap();
+        assert_eq!(conn.metadata, metadata);
+
+        let invalid = HashMap::from([("my ticket".to_string(), "ABC-123".to_string())]);
+        assert!(matches!(
+            conn.set_metadata(invalid),
+            Err(NetworkStateError::InvalidMetadataKey(_))
+        ));
+        let invalid = HashMap::from([("role..name".to_string(), "admin".to_string())]);
+        assert!(conn.set_metadata(invalid).is_err());
+        assert_eq!(conn.metadata, metadata);
+    }
+
+    #[test]
+    fn test_set_permissions() {
+        let mut conn = Connection::default();
+        conn.set_permissions(vec!["alice".to_string()]).unwrap();
+        assert_eq!(conn.permissions, vec!["alice".to_string()]);
+
+        let error = conn
+            .set_permissions(vec!["bob:admin".to_string()])
+            .unwrap_err();
+        assert!(matches!(error, NetworkStateError::InvalidUserName(_)));
+        assert!(conn.set_permissions(vec!["".to_string()]).is_err());
+        assert_eq!(conn.permissions, vec!["alice".to_string()]);
+
+        conn.set_permissions(vec![]).unwrap();
+        assert!(conn.permissions.is_empty());
+    }
+
+    #[test]
+    fn test_set_tags() {
+        let mut conn = Connection::default();
+        conn.set_tags(vec!["storage-net".to_string(), "mgmt".to_string()])
+            .unwrap();
+        assert!(conn.has_tag("mgmt"));
+        assert!(!conn.has_tag("storage"));
+
+        let error = conn.set_tags(vec!["a,b".to_string()]).unwrap_err();
+        assert!(matches!(error, NetworkStateError::InvalidTag(_)));
+        assert!(conn.set_tags(vec!["".to_string()]).is_err());
+        assert_eq!(conn.tags.len(), 2);
+    }
+
+    #[test]
+    fn test_group_state() {
+        let eth0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
+        let eth1 = Connection::new("eth1".to_string(), DeviceType::Ethernet);
+        let mut state = NetworkState::new(vec![], vec![eth0, eth1.clone()]);
+        let base = state.connections.clone();
+
+        let conn = state.get_connection_mut("eth0").unwrap();
+        conn.tags = vec!["mgmt".to_string()];
+        conn.set_up();
+        state.get_connection_mut("eth1").unwrap().set_down();
+        let eth2 = Connection::new("eth2".to_string(), DeviceType::Ethernet);
+        state.add_connection(eth2).unwrap();
+
+        let group = state.group_state("mgmt", &base);
+        assert_eq!(group.connections.len(), 2);
+        assert_eq!(group.get_connection("eth0"), state.get_connection("eth0"));
+        assert_eq!(group.get_connection("eth1"), Some(&eth1));
+        assert!(group.get_connection("eth2").is_none());
+    }
+
+    #[test]
+    fn test_group_state_with_new_controller() {
+        let eth0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
+        let mut state = NetworkState::new(vec![], vec![eth0]);
+        let base = state.connections.clone();
+
+        let bond0 = Connection {
+            id: "bond0".to_string(),
+            interface: Some("bond0".to_string()),
+            config: ConnectionConfig::Bond(Default::default()),
+            ..Default::default()
+        };
+        let eth1 = Connection::new("eth1".to_string(), DeviceType::Ethernet);
+        let eth2 = Connection::new("eth2".to_string(), DeviceType::Ethernet);
+        state.add_connection(bond0.clone()).unwrap();
+        state.add_connection(eth1).unwrap();
+        state.add_connection(eth2).unwrap();
+        state
+            .set_ports(&bond0, vec!["eth0".to_string(), "eth1".to_string()])
+            .unwrap();
+        state.get_connection_mut("eth0").unwrap().tags = vec!["mgmt".to_string()];
+
+        let group = state.group_state("mgmt", &base);
+        assert_eq!(group.connections.len(), 3);
+        let eth0 = group.get_connection("eth0").unwrap();
+        assert_eq!(eth0.controller, Some(bond0.uuid));
+        assert!(group.get_connection_by_uuid(bond0.uuid).is_some());
+        assert!(group.get_connection("eth1").is_some());
+        assert!(group.get_connection("eth2").is_none());
+    }
+
     #[test]
     fn test_set_bonding_ports() {
         let mut state = NetworkState::default();
@@ -348,6 +2077,23 @@ mod tests {
         assert!(matches!(error, NetworkStateError::UnknownConnection(_)));
     }
 
+    #[test]
+    fn test_set_vrf_ports() {
+        let mut state = NetworkState::default();
+        let vrf0 = Connection::new("vrf0".to_string(), DeviceType::Vrf);
+        let eth0 = Connection {
+            id: "eth0".to_string(),
+            interface: Some("eth0".to_string()),
+            ..Default::default()
+        };
+        state.add_connection(vrf0.clone()).unwrap();
+        state.add_connection(eth0).unwrap();
+
+        state.set_ports(&vrf0, vec!["eth0".to_string()]).unwrap();
+        let eth0 = state.get_connection("eth0").unwrap();
+        assert_eq!(eth0.controller, Some(vrf0.uuid));
+    }
+
     #[test]
     fn test_set_non_controller_ports() {
         let mut state = NetworkState::default();
@@ -365,6 +2111,49 @@ mod tests {
             NetworkStateError::NotControllerConnection(_),
         ));
     }
+
+    #[test]
+    fn test_connection_serde() {
+        let mut wlan0 = Connection::new("Home Wi-Fi".to_string(), DeviceType::Wireless);
+        wlan0.mac_address = MacAddress::from_str("00:11:22:33:44:55").unwrap();
+        wlan0.ip_config.method4 = Ipv4Method::LinkLocal;
+        wlan0.ip_config.addresses = vec!["192.168.122.100/24".parse().unwrap()];
+        wlan0.match_config.interface = vec!["wlan*".to_string()];
+        wlan0.config = ConnectionConfig::Wireless(WirelessConfig {
+            ssid: SSID(b"agama".to_vec()),
+            security: SecurityProtocol::WPA2,
+            mode: WirelessMode::AP,
+            band: Some(WirelessBand::A),
+            bssid: Some(macaddr::MacAddr6::new(0, 0x11, 0x22, 0x33, 0x44, 0x66)),
+            ..Default::default()
+        });
+
+        let json = serde_json::to_value(&wlan0).unwrap();
+        assert_eq!(json["macAddress"], "00:11:22:33:44:55");
+        assert_eq!(json["ipConfig"]["method4"], "link-local");
+        assert_eq!(json["matchConfig"]["interface"][0], "wlan*");
+        assert_eq!(json["config"]["type"], "wireless");
+        assert_eq!(json["config"]["settings"]["security"], "wpa-psk");
+        assert_eq!(json["config"]["settings"]["mode"], "ap");
+
+        let conn: Connection = serde_json::from_value(json).unwrap();
+        assert_eq!(conn, wlan0);
+    }
+
+    #[test]
+    fn test_connection_deserialize_defaults() {
+        let json = serde_json::json!({
+            "id": "eth0",
+            "uuid": "5d2b2b6e-3d4e-4a4b-9c1e-0f2d3c4b5a69",
+            "ipConfig": { "method4": "auto" }
+        });
+        let conn: Connection = serde_json::from_value(json).unwrap();
+        assert_eq!(conn.id, "eth0");
+        assert_eq!(conn.ip_config.method4, Ipv4Method::Auto);
+        assert_eq!(conn.ip_config.method6, Ipv6Method::Disabled);
+        assert_eq!(conn.mac_address, MacAddress::Unset);
+        assert_eq!(conn.config, ConnectionConfig::Ethernet);
+    }
 }
 
 /// Network device
@@ -372,13 +2161,155 @@ mod tests {
 pub struct Device {
     pub name: String,
     pub type_: DeviceType,
+    /// Wireless capabilities (only for wireless devices).
+    pub wireless_capabilities: Option<WirelessCapabilities>,
+    /// Modem capabilities (only for mobile broadband devices).
+    pub modem_capabilities: Option<ModemCapabilities>,
+    /// Whether the device is managed by the backend (e.g., NetworkManager).
+    pub managed: bool,
 }
 
-/// Represents an availble network connection.
+/// Neighbor discovered through LLDP (e.g., the switch a device is connected to).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LldpNeighbor {
+    /// Chassis ID (usually, the switch MAC address).
+    pub chassis_id: String,
+    /// System name (e.g., the switch host name).
+    pub system_name: Option<String>,
+    /// Port ID.
+    pub port_id: String,
+    /// Port description.
+    pub port_description: Option<String>,
+    /// Port VLAN ID.
+    pub vlan_id: Option<u32>,
+}
+
+/// IPv6 router advertisement received on a device.
+///
+/// It describes how the router expects the hosts to configure themselves (SLAAC, DHCPv6, etc.).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouterAdvertisement {
+    /// Link-local address of the router.
+    pub router: Ipv6Addr,
+    /// Lifetime (in seconds) of the router as the default one (0 if it is not a default router).
+    pub router_lifetime: u32,
+    /// Whether the addresses must be obtained through DHCPv6 ("managed" flag).
+    pub managed: bool,
+    /// Whether the other settings (e.g., name servers) must be obtained through DHCPv6.
+    pub other_config: bool,
+    /// Advertised prefixes.
+    pub prefixes: Vec<RouterPrefix>,
+    /// Advertised name servers (RDNSS).
+    pub dns_servers: Vec<Ipv6Addr>,
+    /// Advertised MTU.
+    pub mtu: Option<u32>,
+}
+
+impl RouterAdvertisement {
+    /// Creates a router advertisement for the given router.
+    ///
+    /// * `router`: link-local address of the router.
+    pub fn new(router: Ipv6Addr) -> Self {
+        Self {
+            router,
+            router_lifetime: 0,
+            managed: false,
+            other_config: false,
+            prefixes: vec![],
+            dns_servers: vec![],
+            mtu: None,
+        }
+    }
+}
+
+/// Prefix included in a router advertisement.
 #[derive(Debug, Clone, PartialEq)]
+pub struct RouterPrefix {
+    /// Prefix (e.g., "2001:db8::/64").
+    pub prefix: IpInet,
+    /// Whether the prefix can be considered on-link.
+    pub on_link: bool,
+    /// Whether the prefix can be used for stateless address autoconfiguration (SLAAC).
+    pub autonomous: bool,
+    /// Valid lifetime (in seconds).
+    pub valid_lifetime: u32,
+    /// Preferred lifetime (in seconds).
+    pub preferred_lifetime: u32,
+}
+
+/// Capabilities of a wireless device
+///
+/// They allow to find out whether the hardware supports a given configuration (e.g., working as an
+/// access point) before trying to apply it.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct WirelessCapabilities {
+    /// Supported modes.
+    pub modes: Vec<WirelessMode>,
+    /// Supported frequency bands.
+    pub bands: Vec<WirelessBand>,
+    /// Supported ciphers.
+    pub ciphers: Vec<WirelessCipher>,
+}
+
+/// Wireless encryption cipher
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WirelessCipher {
+    Wep40,
+    Wep104,
+    Tkip,
+    Ccmp,
+}
+
+impl fmt::Display for WirelessCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            WirelessCipher::Wep40 => "wep40",
+            WirelessCipher::Wep104 => "wep104",
+            WirelessCipher::Tkip => "tkip",
+            WirelessCipher::Ccmp => "ccmp",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Capabilities of a mobile broadband device (as reported by ModemManager)
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ModemCapabilities {
+    /// Access technologies the modem currently supports.
+    pub technologies: Vec<ModemTechnology>,
+}
+
+/// Mobile broadband access technology
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModemTechnology {
+    Pots,
+    CdmaEvdo,
+    GsmUmts,
+    Lte,
+    FiveGNr,
+}
+
+impl fmt::Display for ModemTechnology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            ModemTechnology::Pots => "pots",
+            ModemTechnology::CdmaEvdo => "cdma-evdo",
+            ModemTechnology::GsmUmts => "gsm-umts",
+            ModemTechnology::Lte => "lte",
+            ModemTechnology::FiveGNr => "5gnr",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Represents an availble network connection.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct Connection {
     pub id: String,
     pub uuid: Uuid,
+    #[serde_as(as = "DisplayFromStr")]
     pub mac_address: MacAddress,
     pub firewall_zone: Option<String>,
     pub ip_config: IpConfig,
@@ -388,6 +2319,43 @@ pub struct Connection {
     pub port_config: PortConfig,
     pub match_config: MatchConfig,
     pub config: ConnectionConfig,
+    /// Multicast DNS (mDNS) mode.
+    pub mdns: ResolveMode,
+    /// Link-Local Multicast Name Resolution (LLMNR) mode.
+    pub llmnr: ResolveMode,
+    /// Whether to listen for LLDP packets (e.g., to find out which switch port the device is
+    /// connected to).
+    pub lldp: bool,
+    /// Whether the connection is managed outside Agama. Such connections are not written when
+    /// applying the configuration (e.g., to keep the connection used by a remote session).
+    pub externally_managed: bool,
+    /// Whether the connection should only be configured in the installed system. Such
+    /// connections are written but not activated in the live system.
+    pub only_on_target: bool,
+    /// Whether the installed system must wait for the connection when booting (e.g., before
+    /// starting the services that need the network). If it is not required, an unplugged port
+    /// does not delay the boot.
+    pub required_for_online: bool,
+    /// Time (in seconds) to wait for the connection to be activated when applying the
+    /// configuration. If it is not set, Agama does not wait for the activation.
+    pub activation_timeout: Option<u32>,
+    /// Bandwidth limit (in Kbit/s) of the interface in the live system (e.g., to not saturate a
+    /// shared link while downloading the packages). It is not written to the backend.
+    pub bandwidth_limit: Option<u32>,
+    /// Free-form description of the connection.
+    pub description: Option<String>,
+    /// Free-form metadata (e.g., a ticket number or the role of the connection).
+    pub metadata: HashMap<String, String>,
+    /// Users allowed to use the connection. If it is empty, the connection is available to all
+    /// the users of the system.
+    pub permissions: Vec<String>,
+    /// Tags to operate on groups of connections (e.g., "storage-net" or "mgmt").
+    pub tags: Vec<String>,
+    /// Whether the connection was read as a team and converted into a bond. Teaming is not
+    /// supported, so the original profile is replaced when writing the connection.
+    pub migrated_from_team: bool,
+    /// Where the secrets (e.g., wireless passwords) of the connection are stored.
+    pub secret_storage: SecretStorage,
 }
 
 impl Connection {
@@ -400,10 +2368,30 @@ impl Connection {
             DeviceType::Bond => ConnectionConfig::Bond(Default::default()),
             DeviceType::Vlan => ConnectionConfig::Vlan(Default::default()),
             DeviceType::Bridge => ConnectionConfig::Bridge(Default::default()),
+            DeviceType::Pppoe => ConnectionConfig::Pppoe(Default::default()),
+            DeviceType::Modem => ConnectionConfig::Modem(Default::default()),
+            DeviceType::Vrf => ConnectionConfig::Vrf(Default::default()),
+            DeviceType::Macvlan => ConnectionConfig::Macvlan(Default::default()),
+            DeviceType::Ipvlan => ConnectionConfig::Ipvlan(Default::default()),
+            DeviceType::Macsec => ConnectionConfig::Macsec(Default::default()),
+        };
+        // PPPoE and modems get the addresses from the PPP session or the carrier
+        let ip_config = if matches!(
+            config,
+            ConnectionConfig::Pppoe(_) | ConnectionConfig::Modem(_)
+        ) {
+            IpConfig {
+                method4: Ipv4Method::Auto,
+                method6: Ipv6Method::Auto,
+                ..Default::default()
+            }
+        } else {
+            Default::default()
         };
         Self {
             id,
             config,
+            ip_config,
             ..Default::default()
         }
     }
@@ -428,6 +2416,217 @@ impl Connection {
         self.status = Status::Down
     }
 
+    /// Returns the summary of the connection.
+    pub fn summary(&self) -> ConnectionSummary {
+        ConnectionSummary {
+            id: self.id.clone(),
+            uuid: self.uuid,
+            type_name: self.config.type_name(),
+            interface: self.interface.clone(),
+            method4: self.ip_config.method4,
+            method6: self.ip_config.method6,
+            status: self.status,
+        }
+    }
+
+    /// Replaces the metadata of the connection.
+    ///
+    /// * `metadata`: new metadata.
+    pub fn set_metadata(
+        &mut self,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), NetworkStateError> {
+        Self::validate_metadata(&metadata)?;
+        self.metadata = metadata;
+        Ok(())
+    }
+
+    /// Checks whether the metadata keys are valid.
+    ///
+    /// The keys can only contain ASCII alphanumeric characters, '-', '_' and '.' (but no empty
+    /// segments), as they are stored in NetworkManager's user settings.
+    ///
+    /// * `metadata`: metadata to check.
+    pub fn validate_metadata(metadata: &HashMap<String, String>) -> Result<(), NetworkStateError> {
+        match metadata.keys().find(|k| !Self::is_valid_metadata_key(k)) {
+            Some(key) => Err(NetworkStateError::InvalidMetadataKey(key.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Restricts the connection to the given users.
+    ///
+    /// An empty list makes the connection available to all the users.
+    ///
+    /// * `users`: names of the allowed users.
+    pub fn set_permissions(&mut self, users: Vec<String>) -> Result<(), NetworkStateError> {
+        Self::validate_permissions(&users)?;
+        self.permissions = users;
+        Ok(())
+    }
+
+    /// Checks whether the user names are valid.
+    ///
+    /// NetworkManager stores the permissions as "user:<name>:" entries, so the user names cannot
+    /// be empty nor contain colons.
+    ///
+    /// * `users`: user names to check.
+    pub fn validate_permissions(users: &[String]) -> Result<(), NetworkStateError> {
+        match users.iter().find(|u| u.is_empty() || u.contains(':')) {
+            Some(user) => Err(NetworkStateError::InvalidUserName(user.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Replaces the tags of the connection.
+    ///
+    /// * `tags`: new tags.
+    pub fn set_tags(&mut self, tags: Vec<String>) -> Result<(), NetworkStateError> {
+        Self::validate_tags(&tags)?;
+        self.tags = tags;
+        Ok(())
+    }
+
+    /// Checks whether the tags are valid.
+    ///
+    /// The tags can only contain ASCII alphanumeric characters, '-', '_' and '.', as they are
+    /// stored as a comma-separated list in NetworkManager's user settings.
+    ///
+    /// * `tags`: tags to check.
+    pub fn validate_tags(tags: &[String]) -> Result<(), NetworkStateError> {
+        let is_valid = |tag: &String| {
+            !tag.is_empty()
+                && tag
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        };
+        match tags.iter().find(|t| !is_valid(t)) {
+            Some(tag) => Err(NetworkStateError::InvalidTag(tag.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Determines whether the connection has the given tag.
+    ///
+    /// * `tag`: tag to check.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    fn is_valid_metadata_key(key: &str) -> bool {
+        key.split('.').all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+    }
+
+    /// Sets the wireless mode, adapting the IPv4 method if needed.
+    ///
+    /// An access point (e.g., a hotspot) must provide addresses to its clients, so the IPv4
+    /// method is set to "shared" when switching to the AP mode, unless it was manually
+    /// configured. Switching back to a different mode restores the "auto" method.
+    ///
+    /// * `mode`: wireless mode.
+    pub fn set_wireless_mode(&mut self, mode: WirelessMode) -> Result<(), NetworkStateError> {
+        let ConnectionConfig::Wireless(config) = &mut self.config else {
+            return Err(NetworkStateError::UnexpectedConfiguration);
+        };
+        config.mode = mode;
+
+        let method4 = &mut self.ip_config.method4;
+        if mode == WirelessMode::AP {
+            if matches!(method4, Ipv4Method::Auto | Ipv4Method::Disabled) {
+                *method4 = Ipv4Method::Shared;
+            }
+        } else if *method4 == Ipv4Method::Shared {
+            *method4 = Ipv4Method::Auto;
+        }
+        Ok(())
+    }
+
+    /// Removes the secrets (e.g., wireless passwords or WEP keys) from the connection.
+    pub fn remove_secrets(&mut self) {
+        match &mut self.config {
+            ConnectionConfig::Wireless(wireless) => {
+                wireless.password = None;
+                if let Some(wep) = &mut wireless.wep_security {
+                    wep.keys.clear();
+                }
+            }
+            ConnectionConfig::Pppoe(pppoe) => pppoe.password = None,
+            ConnectionConfig::Modem(modem) => {
+                modem.pin = None;
+                modem.password = None;
+            }
+            ConnectionConfig::Macsec(macsec) => macsec.cak = None,
+            _ => {}
+        }
+    }
+
+    /// Returns the names of the secrets the connection needs to be activated but are not set.
+    ///
+    /// The names are the ones accepted by [Connection::set_secret]: "psk", "eap-password",
+    /// "wep-key", "pppoe-password" and "macsec-cak".
+    pub fn missing_secrets(&self) -> Vec<String> {
+        let missing = match &self.config {
+            ConnectionConfig::Wireless(wireless) => match wireless.security {
+                SecurityProtocol::WPA2 | SecurityProtocol::WPA3Personal
+                    if wireless.password.is_none() =>
+                {
+                    Some("psk")
+                }
+                SecurityProtocol::DynamicWEP
+                | SecurityProtocol::WPA2Enterprise
+                | SecurityProtocol::WPA3Only
+                    if wireless.password.is_none() =>
+                {
+                    Some("eap-password")
+                }
+                SecurityProtocol::WEP => wireless
+                    .wep_security
+                    .as_ref()
+                    .filter(|w| w.wep_key_type != WEPKeyType::Unknown && w.keys.is_empty())
+                    .map(|_| "wep-key"),
+                _ => None,
+            },
+            ConnectionConfig::Pppoe(pppoe) if pppoe.password.is_none() => Some("pppoe-password"),
+            ConnectionConfig::Macsec(macsec)
+                if macsec.mode == MacsecMode::Psk && macsec.cak.is_none() =>
+            {
+                Some("macsec-cak")
+            }
+            _ => None,
+        };
+        missing.into_iter().map(str::to_string).collect()
+    }
+
+    /// Sets the secret with the given name.
+    ///
+    /// * `name`: secret name (see [Connection::missing_secrets]).
+    /// * `value`: secret value.
+    pub fn set_secret(&mut self, name: &str, value: &str) -> Result<(), NetworkStateError> {
+        match (name, &mut self.config) {
+            ("psk" | "eap-password", ConnectionConfig::Wireless(wireless)) => {
+                wireless.password = Some(value.to_string());
+            }
+            ("wep-key", ConnectionConfig::Wireless(wireless)) => {
+                let wep = wireless.wep_security.get_or_insert_with(Default::default);
+                wep.keys = vec![value.to_string()];
+                wep.wep_key_index = 0;
+            }
+            ("pppoe-password", ConnectionConfig::Pppoe(pppoe)) => {
+                pppoe.password = Some(value.to_string());
+            }
+            ("macsec-cak", ConnectionConfig::Macsec(macsec)) => {
+                macsec.cak = Some(value.to_string());
+            }
+            _ => return Err(NetworkStateError::InvalidSecret(name.to_string())),
+        }
+        Ok(())
+    }
+
     /// Determines whether it is a loopback interface.
     pub fn is_loopback(&self) -> bool {
         matches!(self.config, ConnectionConfig::Loopback)
@@ -441,6 +2640,14 @@ impl Connection {
             || matches!(self.config, ConnectionConfig::Vlan(_))
             || matches!(self.config, ConnectionConfig::Bridge(_))
     }
+
+    /// Determines whether other connections can be attached to this one as ports.
+    pub fn is_controller(&self) -> bool {
+        matches!(
+            self.config,
+            ConnectionConfig::Bond(_) | ConnectionConfig::Vrf(_)
+        )
+    }
 }
 
 impl Default for Connection {
@@ -457,11 +2664,26 @@ impl Default for Connection {
             port_config: Default::default(),
             match_config: Default::default(),
             config: Default::default(),
+            mdns: Default::default(),
+            llmnr: Default::default(),
+            lldp: Default::default(),
+            externally_managed: Default::default(),
+            only_on_target: Default::default(),
+            required_for_online: true,
+            activation_timeout: Default::default(),
+            bandwidth_limit: Default::default(),
+            description: Default::default(),
+            metadata: Default::default(),
+            permissions: Default::default(),
+            tags: Default::default(),
+            migrated_from_team: Default::default(),
+            secret_storage: Default::default(),
         }
     }
 }
 
-#[derive(Default, Debug, PartialEq, Clone)]
+#[derive(Default, Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "settings", rename_all = "lowercase")]
 pub enum ConnectionConfig {
     #[default]
     Ethernet,
@@ -472,9 +2694,38 @@ pub enum ConnectionConfig {
     Vlan(VlanConfig),
     Bridge(BridgeConfig),
     Infiniband(InfinibandConfig),
+    Pppoe(PppoeConfig),
+    Modem(ModemConfig),
+    Vrf(VrfConfig),
+    Macvlan(MacvlanConfig),
+    Ipvlan(IpvlanConfig),
+    Macsec(MacsecConfig),
+}
+
+impl ConnectionConfig {
+    /// Returns the name of the connection type (e.g., "ethernet" or "wireless").
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ConnectionConfig::Ethernet => "ethernet",
+            ConnectionConfig::Wireless(_) => "wireless",
+            ConnectionConfig::Loopback => "loopback",
+            ConnectionConfig::Dummy => "dummy",
+            ConnectionConfig::Bond(_) => "bond",
+            ConnectionConfig::Vlan(_) => "vlan",
+            ConnectionConfig::Bridge(_) => "bridge",
+            ConnectionConfig::Infiniband(_) => "infiniband",
+            ConnectionConfig::Pppoe(_) => "pppoe",
+            ConnectionConfig::Modem(_) => "modem",
+            ConnectionConfig::Vrf(_) => "vrf",
+            ConnectionConfig::Macvlan(_) => "macvlan",
+            ConnectionConfig::Ipvlan(_) => "ipvlan",
+            ConnectionConfig::Macsec(_) => "macsec",
+        }
+    }
 }
 
-#[derive(Default, Debug, PartialEq, Clone)]
+#[derive(Default, Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "settings", rename_all = "lowercase")]
 pub enum PortConfig {
     #[default]
     None,
@@ -493,6 +2744,42 @@ impl From<WirelessConfig> for ConnectionConfig {
     }
 }
 
+impl From<PppoeConfig> for ConnectionConfig {
+    fn from(value: PppoeConfig) -> Self {
+        Self::Pppoe(value)
+    }
+}
+
+impl From<ModemConfig> for ConnectionConfig {
+    fn from(value: ModemConfig) -> Self {
+        Self::Modem(value)
+    }
+}
+
+impl From<VrfConfig> for ConnectionConfig {
+    fn from(value: VrfConfig) -> Self {
+        Self::Vrf(value)
+    }
+}
+
+impl From<MacvlanConfig> for ConnectionConfig {
+    fn from(value: MacvlanConfig) -> Self {
+        Self::Macvlan(value)
+    }
+}
+
+impl From<IpvlanConfig> for ConnectionConfig {
+    fn from(value: IpvlanConfig) -> Self {
+        Self::Ipvlan(value)
+    }
+}
+
+impl From<MacsecConfig> for ConnectionConfig {
+    fn from(value: MacsecConfig) -> Self {
+        Self::Macsec(value)
+    }
+}
+
 #[derive(Debug, Error)]
 #[error("Invalid MAC address: {0}")]
 pub struct InvalidMacAddress(String);
@@ -557,7 +2844,22 @@ impl From<InvalidMacAddress> for zbus::fdo::Error {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
+/// Most relevant information of a connection, to list the connections without reading all
+/// their settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionSummary {
+    pub id: String,
+    pub uuid: Uuid,
+    /// Connection type (see [ConnectionConfig::type_name]).
+    pub type_name: &'static str,
+    pub interface: Option<String>,
+    pub method4: Ipv4Method,
+    pub method6: Ipv6Method,
+    pub status: Status,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Status {
     #[default]
     Up,
@@ -565,7 +2867,19 @@ pub enum Status {
     Removed,
 }
 
-#[derive(Default, Debug, PartialEq, Clone)]
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            Status::Up => "up",
+            Status::Down => "down",
+            Status::Removed => "removed",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Default, Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct IpConfig {
     pub method4: Ipv4Method,
     pub method6: Ipv6Method,
@@ -575,9 +2889,161 @@ pub struct IpConfig {
     pub gateway6: Option<IpAddr>,
     pub routes4: Option<Vec<IpRoute>>,
     pub routes6: Option<Vec<IpRoute>>,
+    /// Metric of the IPv4 routes (`None` means using the backend's default)
+    pub route_metric4: Option<u32>,
+    /// Metric of the IPv6 routes (`None` means using the backend's default)
+    pub route_metric6: Option<u32>,
+    /// Whether to never use this connection for the IPv4 default route
+    pub never_default4: bool,
+    /// Whether to never use this connection for the IPv6 default route
+    pub never_default6: bool,
+    /// Interface identifier to use when building the IPv6 addresses through SLAAC (only the
+    /// lower 64 bits are relevant)
+    pub token6: Option<Ipv6Addr>,
+    /// How to build the IPv6 addresses through SLAAC (`None` means using the backend's default).
+    /// It is ignored when a token is set, as the token requires EUI-64.
+    pub addr_gen_mode6: Option<Ipv6AddrGenMode>,
+}
+
+impl IpConfig {
+    /// Turns a runtime IP configuration into a static one.
+    ///
+    /// IPv6 link-local addresses are dropped because the kernel assigns them anyway. Each family
+    /// is configured manually if it has any address left; otherwise, it uses automatic
+    /// configuration.
+    pub fn into_static(self) -> Self {
+        let addresses: Vec<IpInet> = self
+            .addresses
+            .into_iter()
+            .filter(|a| match a.address() {
+                IpAddr::V6(addr) => (addr.segments()[0] & 0xffc0) != 0xfe80,
+                IpAddr::V4(_) => true,
+            })
+            .collect();
+        let method4 = if addresses.iter().any(|a| a.is_ipv4()) {
+            Ipv4Method::Manual
+        } else {
+            Ipv4Method::Auto
+        };
+        let method6 = if addresses.iter().any(|a| a.is_ipv6()) {
+            Ipv6Method::Manual
+        } else {
+            Ipv6Method::Auto
+        };
+        Self {
+            method4,
+            method6,
+            addresses,
+            ..self
+        }
+    }
+
+    /// Returns the potential problems in the IP configuration.
+    ///
+    /// It reports the addresses in reserved or multicast ranges and the gateways that are not
+    /// reachable through any of the configured subnets. IPv6 link-local gateways are not
+    /// checked, as they are not expected to belong to those subnets.
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues: Vec<String> = self
+            .addresses
+            .iter()
+            .filter_map(|a| {
+                let reason = unexpected_address_reason(a)?;
+                Some(format!("Address {} is {}", a, reason))
+            })
+            .collect();
+
+        let gateways = [self.gateway4, self.gateway6];
+        for gateway in gateways.iter().flatten() {
+            if let IpAddr::V6(addr) = gateway {
+                if (addr.segments()[0] & 0xffc0) == 0xfe80 {
+                    continue;
+                }
+            }
+            let mut subnets = self
+                .addresses
+                .iter()
+                .filter(|a| a.is_ipv4() == gateway.is_ipv4())
+                .peekable();
+            // without static addresses, the subnet is not known
+            if subnets.peek().is_none() {
+                continue;
+            }
+            if !subnets.any(|a| a.contains(gateway)) {
+                issues.push(format!(
+                    "Gateway {} is not part of any of the connection subnets",
+                    gateway
+                ));
+            }
+        }
+
+        issues
+    }
+}
+
+/// Determines whether a hostname is valid according to RFC 1123.
+///
+/// * `hostname`: hostname to check.
+fn is_valid_hostname(hostname: &str) -> bool {
+    hostname.len() <= 253
+        && hostname.split('.').all(|label| {
+            (1..=63).contains(&label.len())
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Returns why an address is not expected to be assigned to an interface, if so.
+///
+/// * `inet`: address (including the prefix) to check.
+fn unexpected_address_reason(inet: &IpInet) -> Option<&'static str> {
+    match inet.address() {
+        IpAddr::V4(addr) => {
+            if addr.is_unspecified() {
+                Some("unspecified")
+            } else if addr.is_loopback() {
+                Some("a loopback address")
+            } else if addr.is_multicast() {
+                Some("a multicast address")
+            } else if addr.is_broadcast() || addr.octets()[0] >= 240 || addr.octets()[0] == 0 {
+                Some("in a reserved range")
+            } else if inet.network_length() < 31 && inet.address() == inet.first_address() {
+                Some("the network address of its subnet")
+            } else if inet.network_length() < 31 && inet.address() == inet.last_address() {
+                Some("the broadcast address of its subnet")
+            } else {
+                None
+            }
+        }
+        IpAddr::V6(addr) => {
+            if addr.is_unspecified() {
+                Some("unspecified")
+            } else if addr.is_loopback() {
+                Some("a loopback address")
+            } else if addr.is_multicast() {
+                Some("a multicast address")
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Potential problem in the network configuration.
+///
+/// Unlike the errors, the issues do not prevent applying the configuration. However, they usually
+/// point to a typo (e.g., a gateway that is not reachable).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    /// ID of the affected connection.
+    pub connection: String,
+    /// Description of the problem.
+    pub description: String,
 }
 
-#[derive(Debug, Default, PartialEq, Clone)]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct MatchConfig {
     pub driver: Vec<String>,
     pub interface: Vec<String>,
@@ -589,13 +3055,15 @@ pub struct MatchConfig {
 #[error("Unknown IP configuration method name: {0}")]
 pub struct UnknownIpMethod(String);
 
-#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum Ipv4Method {
     #[default]
     Disabled = 0,
     Auto = 1,
     Manual = 2,
     LinkLocal = 3,
+    Shared = 4,
 }
 
 impl fmt::Display for Ipv4Method {
@@ -605,6 +3073,7 @@ impl fmt::Display for Ipv4Method {
             Ipv4Method::Auto => "auto",
             Ipv4Method::Manual => "manual",
             Ipv4Method::LinkLocal => "link-local",
+            Ipv4Method::Shared => "shared",
         };
         write!(f, "{}", name)
     }
@@ -619,12 +3088,14 @@ impl FromStr for Ipv4Method {
             "auto" => Ok(Ipv4Method::Auto),
             "manual" => Ok(Ipv4Method::Manual),
             "link-local" => Ok(Ipv4Method::LinkLocal),
+            "shared" => Ok(Ipv4Method::Shared),
             _ => Err(UnknownIpMethod(s.to_string())),
         }
     }
 }
 
-#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum Ipv6Method {
     #[default]
     Disabled = 0,
@@ -671,7 +3142,40 @@ impl From<UnknownIpMethod> for zbus::fdo::Error {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+/// How to build the interface identifier of the IPv6 addresses obtained through SLAAC.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Ipv6AddrGenMode {
+    /// Derives the identifier from the hardware address (EUI-64).
+    Eui64,
+    /// Generates a stable identifier which does not reveal the hardware address (RFC 7217).
+    StablePrivacy,
+}
+
+impl fmt::Display for Ipv6AddrGenMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            Ipv6AddrGenMode::Eui64 => "eui64",
+            Ipv6AddrGenMode::StablePrivacy => "stable-privacy",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl TryFrom<&str> for Ipv6AddrGenMode {
+    type Error = NetworkStateError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "eui64" => Ok(Ipv6AddrGenMode::Eui64),
+            "stable-privacy" => Ok(Ipv6AddrGenMode::StablePrivacy),
+            _ => Err(NetworkStateError::InvalidAddrGenMode(value.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct IpRoute {
     pub destination: IpInet,
     pub next_hop: Option<IpAddr>,
@@ -697,10 +3201,12 @@ impl From<&IpRoute> for HashMap<&str, Value<'_>> {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Clone)]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
 pub enum VlanProtocol {
     #[default]
+    #[serde(rename = "802.1Q")]
     IEEE802_1Q,
+    #[serde(rename = "802.1ad")]
     IEEE802_1ad,
 }
 
@@ -730,14 +3236,17 @@ impl fmt::Display for VlanProtocol {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Clone)]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct VlanConfig {
     pub parent: String,
     pub id: u32,
     pub protocol: VlanProtocol,
 }
 
-#[derive(Debug, Default, PartialEq, Clone)]
+#[serde_as]
+#[derive(Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct WirelessConfig {
     pub mode: WirelessMode,
     pub ssid: SSID,
@@ -745,11 +3254,31 @@ pub struct WirelessConfig {
     pub security: SecurityProtocol,
     pub band: Option<WirelessBand>,
     pub channel: Option<u32>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
     pub bssid: Option<macaddr::MacAddr6>,
     pub wep_security: Option<WEPSecurity>,
     pub hidden: bool,
 }
 
+/// Placeholder to use instead of the secrets when debugging or logging.
+const REDACTED: &str = "<redacted>";
+
+impl fmt::Debug for WirelessConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WirelessConfig")
+            .field("mode", &self.mode)
+            .field("ssid", &self.ssid)
+            .field("password", &self.password.as_ref().map(|_| REDACTED))
+            .field("security", &self.security)
+            .field("band", &self.band)
+            .field("channel", &self.channel)
+            .field("bssid", &self.bssid)
+            .field("wep_security", &self.wep_security)
+            .field("hidden", &self.hidden)
+            .finish()
+    }
+}
+
 impl TryFrom<ConnectionConfig> for WirelessConfig {
     type Error = NetworkStateError;
 
@@ -761,12 +3290,16 @@ impl TryFrom<ConnectionConfig> for WirelessConfig {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum WirelessMode {
     Unknown = 0,
+    #[serde(rename = "adhoc")]
     AdHoc = 1,
     #[default]
+    #[serde(rename = "infrastructure")]
     Infra = 2,
+    #[serde(rename = "ap")]
     AP = 3,
     Mesh = 4,
 }
@@ -799,16 +3332,134 @@ impl fmt::Display for WirelessMode {
     }
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq)]
+/// Mode for the multicast name resolution protocols (mDNS and LLMNR).
+///
+/// See the `connection.mdns` and `connection.llmnr` settings in nm-settings(5).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResolveMode {
+    /// Use the global default.
+    #[default]
+    Default = -1,
+    /// Disable the protocol.
+    No = 0,
+    /// Only resolve names, do not register the hostname.
+    Resolve = 1,
+    /// Resolve names and register the hostname.
+    Yes = 2,
+}
+
+impl TryFrom<&str> for ResolveMode {
+    type Error = NetworkStateError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "default" => Ok(ResolveMode::Default),
+            "no" => Ok(ResolveMode::No),
+            "resolve" => Ok(ResolveMode::Resolve),
+            "yes" => Ok(ResolveMode::Yes),
+            _ => Err(NetworkStateError::InvalidResolveMode(value.to_string())),
+        }
+    }
+}
+
+impl TryFrom<i32> for ResolveMode {
+    type Error = NetworkStateError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            -1 => Ok(ResolveMode::Default),
+            0 => Ok(ResolveMode::No),
+            1 => Ok(ResolveMode::Resolve),
+            2 => Ok(ResolveMode::Yes),
+            _ => Err(NetworkStateError::InvalidResolveMode(value.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for ResolveMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            ResolveMode::Default => "default",
+            ResolveMode::No => "no",
+            ResolveMode::Resolve => "resolve",
+            ResolveMode::Yes => "yes",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Where the secrets of a connection are stored.
+///
+/// See the `*-flags` settings (e.g., `802-11-wireless-security.psk-flags`) in nm-settings(5).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SecretStorage {
+    /// Stored in the (plain text) connection profile.
+    #[default]
+    System = 0,
+    /// Owned by a secret agent (e.g., stored in the user's keyring).
+    Agent = 1,
+    /// Not stored at all, so they are requested on each activation.
+    NotSaved = 2,
+}
+
+impl TryFrom<&str> for SecretStorage {
+    type Error = NetworkStateError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "system" => Ok(SecretStorage::System),
+            "agent" => Ok(SecretStorage::Agent),
+            "not-saved" => Ok(SecretStorage::NotSaved),
+            _ => Err(NetworkStateError::InvalidSecretStorage(value.to_string())),
+        }
+    }
+}
+
+impl From<u32> for SecretStorage {
+    /// Builds the storage from NetworkManager's secret flags.
+    ///
+    /// The "not required" flag (0x4) is ignored.
+    fn from(flags: u32) -> Self {
+        if flags & SecretStorage::NotSaved as u32 != 0 {
+            SecretStorage::NotSaved
+        } else if flags & SecretStorage::Agent as u32 != 0 {
+            SecretStorage::Agent
+        } else {
+            SecretStorage::System
+        }
+    }
+}
+
+impl fmt::Display for SecretStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            SecretStorage::System => "system",
+            SecretStorage::Agent => "agent",
+            SecretStorage::NotSaved => "not-saved",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub enum SecurityProtocol {
     #[default]
+    #[serde(rename = "none")]
     WEP, // No encryption or WEP ("none")
-    OWE,            // Opportunistic Wireless Encryption ("owe")
-    DynamicWEP,     // Dynamic WEP ("ieee8021x")
-    WPA2,           // WPA2 + WPA3 personal ("wpa-psk")
-    WPA3Personal,   // WPA3 personal only ("sae")
+    #[serde(rename = "owe")]
+    OWE, // Opportunistic Wireless Encryption ("owe")
+    #[serde(rename = "ieee8021x")]
+    DynamicWEP, // Dynamic WEP ("ieee8021x")
+    #[serde(rename = "wpa-psk")]
+    WPA2, // WPA2 + WPA3 personal ("wpa-psk")
+    #[serde(rename = "sae")]
+    WPA3Personal, // WPA3 personal only ("sae")
+    #[serde(rename = "wpa-eap")]
     WPA2Enterprise, // WPA2 + WPA3 Enterprise ("wpa-eap")
-    WPA3Only,       // WPA3 only ("wpa-eap-suite-b192")
+    #[serde(rename = "wpa-eap-suite-b192")]
+    WPA3Only, // WPA3 only ("wpa-eap-suite-b192")
 }
 
 impl fmt::Display for SecurityProtocol {
@@ -845,7 +3496,8 @@ impl TryFrom<&str> for SecurityProtocol {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Clone)]
+#[derive(Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct WEPSecurity {
     pub auth_alg: WEPAuthAlg,
     pub wep_key_type: WEPKeyType,
@@ -853,7 +3505,20 @@ pub struct WEPSecurity {
     pub wep_key_index: u32,
 }
 
-#[derive(Debug, Default, PartialEq, Clone)]
+impl fmt::Debug for WEPSecurity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<_> = self.keys.iter().map(|_| REDACTED).collect();
+        f.debug_struct("WEPSecurity")
+            .field("auth_alg", &self.auth_alg)
+            .field("wep_key_type", &self.wep_key_type)
+            .field("keys", &keys)
+            .field("wep_key_index", &self.wep_key_index)
+            .finish()
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum WEPKeyType {
     #[default]
     Unknown = 0,
@@ -874,7 +3539,8 @@ impl TryFrom<u32> for WEPKeyType {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Clone)]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum WEPAuthAlg {
     #[default]
     Unset,
@@ -909,7 +3575,8 @@ impl fmt::Display for WEPAuthAlg {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum WirelessBand {
     A,  // 5GHz
     BG, // 2.4GHz
@@ -925,6 +3592,18 @@ impl fmt::Display for WirelessBand {
     }
 }
 
+impl WirelessBand {
+    /// Determines whether the channel belongs to the band.
+    ///
+    /// * `channel`: channel number.
+    pub fn has_channel(&self, channel: u32) -> bool {
+        match self {
+            WirelessBand::A => (32..=177).contains(&channel),
+            WirelessBand::BG => (1..=14).contains(&channel),
+        }
+    }
+}
+
 impl TryFrom<&str> for WirelessBand {
     type Error = anyhow::Error;
 
@@ -937,7 +3616,7 @@ impl TryFrom<&str> for WirelessBand {
     }
 }
 
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BondOptions(pub HashMap<String, String>);
 
 impl TryFrom<&str> for BondOptions {
@@ -970,7 +3649,8 @@ impl fmt::Display for BondOptions {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Clone)]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct BondConfig {
     pub mode: BondMode,
     pub options: BondOptions,
@@ -987,7 +3667,8 @@ impl TryFrom<ConnectionConfig> for BondConfig {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Clone)]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct BridgeConfig {
     pub stp: bool,
     pub priority: Option<u32>,
@@ -997,20 +3678,371 @@ pub struct BridgeConfig {
     pub ageing_time: Option<u32>,
 }
 
-#[derive(Debug, Default, PartialEq, Clone)]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct BridgePortConfig {
     pub priority: Option<u32>,
     pub path_cost: Option<u32>,
 }
 
-#[derive(Default, Debug, PartialEq, Clone)]
+/// PPPoE settings (e.g., to connect to a DSL line without a separate router)
+#[derive(Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PppoeConfig {
+    /// Username to authenticate with the provider.
+    pub username: String,
+    /// Password to authenticate with the provider.
+    pub password: Option<String>,
+    /// PPPoE service name, only needed if the provider offers several ones.
+    pub service: Option<String>,
+    /// Ethernet interface to establish the session on (e.g., "eth0").
+    pub parent: String,
+}
+
+impl fmt::Debug for PppoeConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PppoeConfig")
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| REDACTED))
+            .field("service", &self.service)
+            .field("parent", &self.parent)
+            .finish()
+    }
+}
+
+impl TryFrom<ConnectionConfig> for PppoeConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(value: ConnectionConfig) -> Result<Self, Self::Error> {
+        match value {
+            ConnectionConfig::Pppoe(config) => Ok(config),
+            _ => Err(NetworkStateError::UnexpectedConfiguration),
+        }
+    }
+}
+
+/// Mobile broadband (GSM/UMTS/LTE) settings
+#[derive(Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModemConfig {
+    /// Access Point Name (APN) of the carrier.
+    pub apn: String,
+    /// PIN to unlock the SIM card.
+    pub pin: Option<String>,
+    /// Username to authenticate with the carrier (rarely needed).
+    pub username: Option<String>,
+    /// Password to authenticate with the carrier (rarely needed).
+    pub password: Option<String>,
+}
+
+impl fmt::Debug for ModemConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModemConfig")
+            .field("apn", &self.apn)
+            .field("pin", &self.pin.as_ref().map(|_| REDACTED))
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| REDACTED))
+            .finish()
+    }
+}
+
+impl TryFrom<ConnectionConfig> for ModemConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(value: ConnectionConfig) -> Result<Self, Self::Error> {
+        match value {
+            ConnectionConfig::Modem(config) => Ok(config),
+            _ => Err(NetworkStateError::UnexpectedConfiguration),
+        }
+    }
+}
+
+/// VRF (Virtual Routing and Forwarding) settings
+///
+/// A VRF device isolates the routing of its ports in a separate table (e.g., to keep the
+/// management traffic apart from the data plane). The ports are attached using the controller
+/// mechanism, as in the bonds.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct VrfConfig {
+    /// Routing table ID of the VRF.
+    pub table: u32,
+}
+
+impl TryFrom<ConnectionConfig> for VrfConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(value: ConnectionConfig) -> Result<Self, Self::Error> {
+        match value {
+            ConnectionConfig::Vrf(config) => Ok(config),
+            _ => Err(NetworkStateError::UnexpectedConfiguration),
+        }
+    }
+}
+
+/// MACVLAN mode
+///
+/// It determines whether the MACVLAN devices sharing the same parent can talk to each other. See
+/// the `macvlan.mode` setting in nm-settings(5).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MacvlanMode {
+    Vepa = 1,
+    #[default]
+    Bridge = 2,
+    Private = 3,
+    Passthru = 4,
+    Source = 5,
+}
+
+impl TryFrom<&str> for MacvlanMode {
+    type Error = NetworkStateError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "vepa" => Ok(MacvlanMode::Vepa),
+            "bridge" => Ok(MacvlanMode::Bridge),
+            "private" => Ok(MacvlanMode::Private),
+            "passthru" => Ok(MacvlanMode::Passthru),
+            "source" => Ok(MacvlanMode::Source),
+            _ => Err(NetworkStateError::InvalidMacvlanMode(value.to_string())),
+        }
+    }
+}
+
+impl TryFrom<u32> for MacvlanMode {
+    type Error = NetworkStateError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(MacvlanMode::Vepa),
+            2 => Ok(MacvlanMode::Bridge),
+            3 => Ok(MacvlanMode::Private),
+            4 => Ok(MacvlanMode::Passthru),
+            5 => Ok(MacvlanMode::Source),
+            _ => Err(NetworkStateError::InvalidMacvlanMode(value.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for MacvlanMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            MacvlanMode::Vepa => "vepa",
+            MacvlanMode::Bridge => "bridge",
+            MacvlanMode::Private => "private",
+            MacvlanMode::Passthru => "passthru",
+            MacvlanMode::Source => "source",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// MACVLAN settings
+///
+/// A MACVLAN device gets its own MAC address on top of an existing (parent) interface. It is
+/// commonly used to give containers direct access to the host's network.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MacvlanConfig {
+    /// Interface name or connection UUID of the parent.
+    pub parent: String,
+    pub mode: MacvlanMode,
+}
+
+impl TryFrom<ConnectionConfig> for MacvlanConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(value: ConnectionConfig) -> Result<Self, Self::Error> {
+        match value {
+            ConnectionConfig::Macvlan(config) => Ok(config),
+            _ => Err(NetworkStateError::UnexpectedConfiguration),
+        }
+    }
+}
+
+/// IPVLAN mode
+///
+/// See the `ipvlan.mode` setting in nm-settings(5).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IpvlanMode {
+    #[default]
+    L2 = 1,
+    L3 = 2,
+    L3s = 3,
+}
+
+impl TryFrom<&str> for IpvlanMode {
+    type Error = NetworkStateError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "l2" => Ok(IpvlanMode::L2),
+            "l3" => Ok(IpvlanMode::L3),
+            "l3s" => Ok(IpvlanMode::L3s),
+            _ => Err(NetworkStateError::InvalidIpvlanMode(value.to_string())),
+        }
+    }
+}
+
+impl TryFrom<u32> for IpvlanMode {
+    type Error = NetworkStateError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(IpvlanMode::L2),
+            2 => Ok(IpvlanMode::L3),
+            3 => Ok(IpvlanMode::L3s),
+            _ => Err(NetworkStateError::InvalidIpvlanMode(value.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for IpvlanMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            IpvlanMode::L2 => "l2",
+            IpvlanMode::L3 => "l3",
+            IpvlanMode::L3s => "l3s",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// IPVLAN settings
+///
+/// Unlike MACVLAN, all the IPVLAN devices share the MAC address of the parent interface, which is
+/// needed when the switch limits the number of addresses per port.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct IpvlanConfig {
+    /// Interface name or connection UUID of the parent.
+    pub parent: String,
+    pub mode: IpvlanMode,
+}
+
+impl TryFrom<ConnectionConfig> for IpvlanConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(value: ConnectionConfig) -> Result<Self, Self::Error> {
+        match value {
+            ConnectionConfig::Ipvlan(config) => Ok(config),
+            _ => Err(NetworkStateError::UnexpectedConfiguration),
+        }
+    }
+}
+
+/// MACsec key agreement mode
+///
+/// See the `macsec.mode` setting in nm-settings(5).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MacsecMode {
+    /// The keys are derived from a pre-shared connectivity association key (CAK/CKN).
+    #[default]
+    Psk = 0,
+    /// The keys are negotiated through 802.1X (EAP).
+    Eap = 1,
+}
+
+impl TryFrom<&str> for MacsecMode {
+    type Error = NetworkStateError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "psk" => Ok(MacsecMode::Psk),
+            "eap" => Ok(MacsecMode::Eap),
+            _ => Err(NetworkStateError::InvalidMacsecMode(value.to_string())),
+        }
+    }
+}
+
+impl TryFrom<i32> for MacsecMode {
+    type Error = NetworkStateError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(MacsecMode::Psk),
+            1 => Ok(MacsecMode::Eap),
+            _ => Err(NetworkStateError::InvalidMacsecMode(value.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for MacsecMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            MacsecMode::Psk => "psk",
+            MacsecMode::Eap => "eap",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// MACsec (IEEE 802.1AE) settings
+///
+/// A MACsec device encrypts the traffic of its parent interface at the link layer. In the EAP
+/// mode, the 802.1X settings of the NetworkManager profile are kept as they are.
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MacsecConfig {
+    /// Interface name or connection UUID of the parent.
+    pub parent: String,
+    pub mode: MacsecMode,
+    /// Connectivity Association Key (only for the PSK mode).
+    pub cak: Option<String>,
+    /// Connectivity Association Key Name (only for the PSK mode).
+    pub ckn: Option<String>,
+    /// Whether to encrypt the traffic. Otherwise, it is only authenticated.
+    pub encrypt: bool,
+}
+
+impl Default for MacsecConfig {
+    fn default() -> Self {
+        Self {
+            parent: String::new(),
+            mode: MacsecMode::default(),
+            cak: None,
+            ckn: None,
+            encrypt: true,
+        }
+    }
+}
+
+impl fmt::Debug for MacsecConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MacsecConfig")
+            .field("parent", &self.parent)
+            .field("mode", &self.mode)
+            .field("cak", &self.cak.as_ref().map(|_| REDACTED))
+            .field("ckn", &self.ckn)
+            .field("encrypt", &self.encrypt)
+            .finish()
+    }
+}
+
+impl TryFrom<ConnectionConfig> for MacsecConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(value: ConnectionConfig) -> Result<Self, Self::Error> {
+        match value {
+            ConnectionConfig::Macsec(config) => Ok(config),
+            _ => Err(NetworkStateError::UnexpectedConfiguration),
+        }
+    }
+}
+
+#[derive(Default, Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct InfinibandConfig {
     pub p_key: Option<i32>,
     pub parent: Option<String>,
     pub transport_mode: InfinibandTransportMode,
 }
 
-#[derive(Default, Debug, PartialEq, Clone)]
+#[derive(Default, Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum InfinibandTransportMode {
     #[default]
     Datagram,
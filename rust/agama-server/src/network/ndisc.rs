@@ -0,0 +1,216 @@
+//! IPv6 router advertisements.
+//!
+//! When the IPv6 autoconfiguration picks unexpected addresses (or none at all), the reason is
+//! usually in the router advertisements: the advertised prefixes and their flags, the
+//! "managed" and "other configuration" flags (which ask for DHCPv6) or the router lifetime. To
+//! find out, a router solicitation is sent through the device using `rdisc6` and the answers are
+//! reported.
+//!
+//! NetworkManager processes the router advertisements by itself but it does not expose them, so
+//! asking the routers again is the only way to get the whole picture.
+use super::{
+    error::NetworkStateError,
+    model::{RouterAdvertisement, RouterPrefix},
+};
+use std::{net::Ipv6Addr, process::Command};
+
+/// Time (in milliseconds) to wait for the answers to each solicitation.
+const WAIT_TIME: &str = "1000";
+/// Number of solicitations to send.
+const RETRIES: &str = "2";
+
+/// Returns the router advertisements received on a device.
+///
+/// It returns an empty list if no router answers.
+///
+/// * `device`: device name (e.g., "eth0").
+pub async fn router_advertisements(
+    device: &str,
+) -> Result<Vec<RouterAdvertisement>, NetworkStateError> {
+    let device = device.to_string();
+    tokio::task::spawn_blocking(move || solicit(&device))
+        .await
+        .map_err(|e| NetworkStateError::RouterAdvertisements(e.to_string()))?
+}
+
+/// Sends a router solicitation through a device and parses the answers.
+///
+/// * `device`: device to send the solicitation through.
+fn solicit(device: &str) -> Result<Vec<RouterAdvertisement>, NetworkStateError> {
+    let args = ["-w", WAIT_TIME, "-r", RETRIES, "--", device];
+    let output = Command::new("rdisc6").args(args).output().map_err(|e| {
+        NetworkStateError::RouterAdvertisements(format!("could not run rdisc6: {e}"))
+    })?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let advertisements = parse(&stdout);
+    if output.status.success() || !advertisements.is_empty() || stdout.contains("Timed out") {
+        return Ok(advertisements);
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(NetworkStateError::RouterAdvertisements(format!(
+        "{}: {}",
+        device,
+        stderr.trim()
+    )))
+}
+
+/// Parses the `rdisc6` output.
+///
+/// Each advertisement is a list of "name: value" lines, ending with the router address (e.g.,
+/// `from fe80::1`). The prefixes and the name servers are indented under the advertisement, and
+/// the details of each prefix under the prefix itself.
+///
+/// * `output`: `rdisc6` output.
+fn parse(output: &str) -> Vec<RouterAdvertisement> {
+    let mut advertisements = vec![];
+    let mut current = RouterAdvertisement::new(Ipv6Addr::UNSPECIFIED);
+
+    for line in output.lines().map(str::trim) {
+        if let Some(router) = line.strip_prefix("from ") {
+            if let Ok(router) = router.trim().parse() {
+                current.router = router;
+                advertisements.push(current);
+            }
+            current = RouterAdvertisement::new(Ipv6Addr::UNSPECIFIED);
+            continue;
+        }
+
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim() {
+            "Stateful address conf." => current.managed = value == "Yes",
+            "Stateful other conf." => current.other_config = value == "Yes",
+            "Router lifetime" => current.router_lifetime = parse_time(value),
+            "MTU" => current.mtu = value.split_whitespace().next().and_then(|v| v.parse().ok()),
+            "Recursive DNS server" => {
+                if let Ok(address) = value.parse() {
+                    current.dns_servers.push(address);
+                }
+            }
+            "Prefix" => {
+                if let Ok(prefix) = value.parse() {
+                    current.prefixes.push(RouterPrefix {
+                        prefix,
+                        on_link: false,
+                        autonomous: false,
+                        valid_lifetime: 0,
+                        preferred_lifetime: 0,
+                    });
+                }
+            }
+            name => {
+                let Some(prefix) = current.prefixes.last_mut() else {
+                    continue;
+                };
+                match name {
+                    "On-link" => prefix.on_link = value == "Yes",
+                    "Autonomous address conf." => prefix.autonomous = value == "Yes",
+                    "Valid time" => prefix.valid_lifetime = parse_time(value),
+                    "Pref. time" => prefix.preferred_lifetime = parse_time(value),
+                    _ => {}
+                }
+            }
+        }
+    }
+    advertisements
+}
+
+/// Parses a time value (e.g., `1800 (0x00000708) seconds` or `infinite (0xffffffff)`).
+///
+/// The hexadecimal value is always present, so it is used instead of the decimal one.
+///
+/// * `value`: value to parse.
+fn parse_time(value: &str) -> u32 {
+    value
+        .split_once("(0x")
+        .and_then(|(_, hex)| hex.split_once(')'))
+        .and_then(|(hex, _)| u32::from_str_radix(hex, 16).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "Soliciting ff02::2 (ff02::2) on eth0...
+
+Hop limit                 :           64 (      0x40)
+Stateful address conf.    :           No
+Stateful other conf.      :          Yes
+Mobile home agent         :           No
+Router preference         :       medium
+Neighbor discovery proxy  :           No
+Router lifetime           :         1800 (0x00000708) seconds
+Reachable time            :  unspecified (0x00000000)
+Retransmit time           :  unspecified (0x00000000)
+ Prefix                   : 2001:db8:1::/64
+  On-link                 :          Yes
+  Autonomous address conf.:          Yes
+  Valid time              :      2592000 (0x00278d00) seconds
+  Pref. time              :       604800 (0x00093a80) seconds
+ Prefix                   : 2001:db8:2::/64
+  On-link                 :          Yes
+  Autonomous address conf.:           No
+  Valid time              :     infinite (0xffffffff)
+  Pref. time              :     infinite (0xffffffff)
+ Recursive DNS server     : 2001:db8:1::53
+  DNS server lifetime     :          600 (0x00000258) seconds
+ MTU                      :         1500 (0x000005dc) bytes
+ Source link-layer address: 52:54:00:12:34:56
+ from fe80::5054:ff:fe12:3456
+";
+
+    #[test]
+    fn test_parse() {
+        let advertisements = parse(OUTPUT);
+        assert_eq!(advertisements.len(), 1);
+        let ra = &advertisements[0];
+        assert_eq!(
+            ra.router,
+            "fe80::5054:ff:fe12:3456".parse::<Ipv6Addr>().unwrap()
+        );
+        assert_eq!(ra.router_lifetime, 1800);
+        assert!(!ra.managed);
+        assert!(ra.other_config);
+        assert_eq!(ra.mtu, Some(1500));
+        assert_eq!(
+            ra.dns_servers,
+            vec!["2001:db8:1::53".parse::<Ipv6Addr>().unwrap()]
+        );
+        assert_eq!(
+            ra.prefixes,
+            vec![
+                RouterPrefix {
+                    prefix: "2001:db8:1::/64".parse().unwrap(),
+                    on_link: true,
+                    autonomous: true,
+                    valid_lifetime: 2592000,
+                    preferred_lifetime: 604800
+                },
+                RouterPrefix {
+                    prefix: "2001:db8:2::/64".parse().unwrap(),
+                    on_link: true,
+                    autonomous: false,
+                    valid_lifetime: u32::MAX,
+                    preferred_lifetime: u32::MAX
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_without_answers() {
+        assert!(parse("Soliciting ff02::2 (ff02::2) on eth0...\nTimed out.\n").is_empty());
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("1800 (0x00000708) seconds"), 1800);
+        assert_eq!(parse_time("infinite (0xffffffff)"), u32::MAX);
+        assert_eq!(parse_time("unspecified (0x00000000)"), 0);
+    }
+}
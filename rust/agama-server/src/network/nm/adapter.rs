@@ -1,7 +1,11 @@
 use crate::network::{
-    model::{Connection, NetworkState},
+    dad, kdump,
+    model::{
+        ApplyResult, Connection, ConnectionApplyResult, ConnectionChange, IpConfig, LldpNeighbor,
+        NetworkState, PortState,
+    },
     nm::NetworkManagerClient,
-    Adapter, NetworkAdapterError,
+    regdom, shaping, wait_online, Adapter, CancellationFlag, NetworkAdapterError,
 };
 use agama_lib::error::ServiceError;
 use async_trait::async_trait;
@@ -10,20 +14,51 @@ use log;
 /// An adapter for NetworkManager
 pub struct NetworkManagerAdapter<'a> {
     client: NetworkManagerClient<'a>,
+    activation_timeout: Option<u32>,
 }
 
 impl<'a> NetworkManagerAdapter<'a> {
     /// Returns the adapter for system's NetworkManager.
     pub async fn from_system() -> Result<NetworkManagerAdapter<'a>, ServiceError> {
         let client = NetworkManagerClient::from_system().await?;
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            activation_timeout: None,
+        })
+    }
+
+    /// Returns the adapter for the NetworkManager reachable through the given D-Bus connection.
+    ///
+    /// It allows using a NetworkManager which does not live in the system bus (e.g., a remote
+    /// one in a test virtual machine).
+    ///
+    /// * `connection`: connection to the bus where NetworkManager lives.
+    pub async fn new(
+        connection: zbus::Connection,
+    ) -> Result<NetworkManagerAdapter<'a>, ServiceError> {
+        let client = NetworkManagerClient::new(connection).await?;
+        Ok(Self {
+            client,
+            activation_timeout: None,
+        })
+    }
+
+    /// Sets the time (in seconds) to wait for the activation of the connections that do not
+    /// define their own timeout.
+    ///
+    /// * `timeout`: activation timeout.
+    pub fn with_activation_timeout(mut self, timeout: Option<u32>) -> Self {
+        self.activation_timeout = timeout;
+        self
     }
 
     /// Determines whether the write operation is supported for a connection
     ///
+    /// Loopback connections and the ones managed outside Agama are not written.
+    ///
     /// * `conn`: connection
     fn is_writable(conn: &Connection) -> bool {
-        !conn.is_loopback()
+        !conn.is_loopback() && !conn.externally_managed
     }
 }
 
@@ -40,7 +75,67 @@ impl<'a> Adapter for NetworkManagerAdapter<'a> {
             .connections()
             .await
             .map_err(NetworkAdapterError::Read)?;
-        Ok(NetworkState::new(devices, connections))
+        let mut state = NetworkState::new(devices, connections);
+        state.connectivity = self
+            .client
+            .connectivity()
+            .await
+            .map_err(NetworkAdapterError::Read)?;
+        state.regulatory_domain = regdom::current().unwrap_or_else(|e| {
+            log::warn!("Could not read the wireless regulatory domain: {}", e);
+            None
+        });
+        state.hostname = self.client.hostname().await.unwrap_or_else(|e| {
+            log::warn!("Could not read the hostname: {}", e);
+            None
+        });
+        Ok(state)
+    }
+
+    async fn is_online(&self) -> Result<bool, NetworkAdapterError> {
+        self.client
+            .is_online()
+            .await
+            .map_err(NetworkAdapterError::Read)
+    }
+
+    async fn bond_ports(&self, interface: &str) -> Result<Vec<PortState>, NetworkAdapterError> {
+        self.client
+            .ports(interface)
+            .await
+            .map_err(NetworkAdapterError::Read)
+    }
+
+    async fn lldp_neighbors(&self, device: &str) -> Result<Vec<LldpNeighbor>, NetworkAdapterError> {
+        self.client
+            .lldp_neighbors(device)
+            .await
+            .map_err(NetworkAdapterError::Read)
+    }
+
+    async fn device_ip_config(&self, device: &str) -> Result<IpConfig, NetworkAdapterError> {
+        self.client
+            .runtime_ip_config(device)
+            .await
+            .map_err(NetworkAdapterError::Read)
+    }
+
+    async fn set_device_managed(
+        &self,
+        device: &str,
+        managed: bool,
+    ) -> Result<(), NetworkAdapterError> {
+        self.client
+            .set_managed(device, managed)
+            .await
+            .map_err(NetworkAdapterError::Write)
+    }
+
+    async fn reload(&self) -> Result<(), NetworkAdapterError> {
+        self.client
+            .reload()
+            .await
+            .map_err(NetworkAdapterError::Read)
     }
 
     /// Writes the connections to NetworkManager.
@@ -49,50 +144,152 @@ impl<'a> Adapter for NetworkManagerAdapter<'a> {
     /// that using async recursive functions is giving us some troubles, so we decided to go with a
     /// simpler approach.
     ///
+    /// The connections that cannot be written are reported in the result, but they do not prevent
+    /// writing the rest of them. If the operation is cancelled, the remaining connections are
+    /// reported as failed. The connections whose static IPv4 addresses are already in use are not
+    /// written either (see [dad]). However, if the wireless regulatory domain cannot be updated,
+    /// an error is returned and no connection is written.
+    ///
     /// * `network`: network model.
-    async fn write(&self, network: &NetworkState) -> Result<(), NetworkAdapterError> {
+    /// * `cancel`: flag to cancel the operation.
+    async fn write(
+        &self,
+        network: &NetworkState,
+        cancel: &CancellationFlag,
+    ) -> Result<ApplyResult, NetworkAdapterError> {
         let old_state = self.read().await?;
+        // not handled by NetworkManager, so it is written before creating the checkpoint
+        if network.regulatory_domain != old_state.regulatory_domain {
+            log::info!("Updating the wireless regulatory domain");
+            regdom::write(network.regulatory_domain.as_deref())
+                .map_err(|e| NetworkAdapterError::RegulatoryDomain(e.to_string()))?;
+        }
+
         let checkpoint = self
             .client
             .create_checkpoint()
             .await
             .map_err(NetworkAdapterError::Checkpoint)?;
 
+        if network.connectivity != old_state.connectivity {
+            log::info!("Updating the connectivity check settings");
+            if let Err(e) = self.client.update_connectivity(&network.connectivity).await {
+                log::error!("Could not update the connectivity check settings: {}", e);
+            }
+        }
+
+        let servers = if network.dns.is_empty() {
+            vec![]
+        } else {
+            network.system_dns()
+        };
+        if let Err(e) = self
+            .client
+            .update_global_dns(&servers, &network.dns.searches)
+            .await
+        {
+            log::error!("Could not update the global DNS settings: {}", e);
+        }
+        if let Err(e) = self.client.update_target_dns(&network.dns, &servers) {
+            log::error!(
+                "Could not write the DNS settings for the installed system: {}",
+                e
+            );
+        }
+
+        if let Err(e) = kdump::write(&network.kdump) {
+            log::error!("Could not write the kdump settings: {}", e);
+        }
+
+        if let Err(e) = wait_online::write(network) {
+            log::error!("Could not write the wait-online settings: {}", e);
+        }
+
+        if let Some(hostname) = &network.hostname {
+            if network.hostname != old_state.hostname {
+                log::info!("Updating the hostname");
+                if let Err(e) = self.client.save_hostname(hostname).await {
+                    log::error!("Could not update the hostname: {}", e);
+                }
+            }
+        }
+
+        let mut apply_result = ApplyResult::default();
         for conn in ordered_connections(network) {
-            if !Self::is_writable(conn) {
+            if !Self::is_writable(conn) || network.is_ignored(conn) {
                 continue;
             }
 
-            if let Some(old_conn) = old_state.get_connection_by_uuid(conn.uuid) {
-                if old_conn == conn {
-                    continue;
-                }
+            let old_conn = old_state.get_connection_by_uuid(conn.uuid);
+            if old_conn == Some(conn) {
+                continue;
             }
 
-            log::info!("Updating connection {} ({})", conn.id, conn.uuid);
-            let result = if conn.is_removed() {
+            let result = if cancel.is_cancelled() {
+                Err(ServiceError::UnsuccessfulAction(
+                    "applying the configuration was cancelled".to_string(),
+                ))
+            } else if conn.is_removed() {
+                log::info!("Updating connection {} ({})", conn.id, conn.uuid);
                 self.client.remove_connection(conn.uuid).await
             } else {
+                log::info!("Updating connection {} ({})", conn.id, conn.uuid);
                 let ctrl = conn
                     .controller
                     .and_then(|uuid| network.get_connection_by_uuid(uuid));
-                self.client.add_or_update_connection(conn, ctrl).await
+                let mut conn = conn.clone();
+                conn.activation_timeout = conn.activation_timeout.or(self.activation_timeout);
+                let conflicts = dad::check(&conn).await;
+                if conflicts.is_empty() {
+                    self.client
+                        .add_or_update_connection(&conn, ctrl, cancel)
+                        .await
+                } else {
+                    let error = conflicts
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    apply_result.conflicts.extend(conflicts);
+                    Err(ServiceError::FailedOperation(error))
+                }
             };
 
-            if let Err(e) = result {
-                self.client
-                    .rollback_checkpoint(&checkpoint.as_ref())
-                    .await
-                    .map_err(NetworkAdapterError::Checkpoint)?;
+            let change = if conn.is_removed() {
+                ConnectionChange::Removed
+            } else if old_conn.is_some() {
+                ConnectionChange::Updated
+            } else {
+                ConnectionChange::Added
+            };
+            let error = result.err().map(|e| {
                 log::error!("Could not process the connection {}: {}", conn.id, &e);
-                return Err(NetworkAdapterError::Write(e));
-            }
+                e.to_string()
+            });
+            apply_result.connections.push(ConnectionApplyResult {
+                id: conn.id.clone(),
+                uuid: conn.uuid,
+                change,
+                error,
+            });
         }
         self.client
             .destroy_checkpoint(&checkpoint.as_ref())
             .await
             .map_err(NetworkAdapterError::Checkpoint)?;
-        Ok(())
+
+        for conn in &network.connections {
+            let Some(interface) = &conn.interface else {
+                continue;
+            };
+            if !conn.is_up() || conn.only_on_target || network.is_ignored(conn) {
+                continue;
+            }
+            if let Err(e) = shaping::write(interface, network.bandwidth_limit(conn)) {
+                log::error!("Could not set the bandwidth limit of {}: {}", interface, e);
+            }
+        }
+        Ok(apply_result)
     }
 }
 
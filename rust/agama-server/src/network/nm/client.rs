@@ -1,19 +1,55 @@
 //! NetworkManager client.
 use std::collections::HashMap;
 
+use super::conf::{connectivity_interval_from_conf, connectivity_to_conf, dns_to_conf};
 use super::dbus::{
     cleanup_dbus_connection, connection_from_dbus, connection_to_dbus, controller_from_dbus,
-    merge_dbus_connections,
+    global_dns_to_dbus, lldp_neighbor_from_dbus, merge_dbus_connections, merge_secrets_from_dbus,
+    runtime_addresses_from_dbus, runtime_routes_from_dbus, secret_settings_from_dbus,
 };
-use super::model::NmDeviceType;
-use super::proxies::{ConnectionProxy, DeviceProxy, NetworkManagerProxy, SettingsProxy};
-use crate::network::model::{Connection, Device};
-use agama_lib::error::ServiceError;
+use super::model::{NmDeviceType, NmModemCapabilities, NmWirelessCapabilities};
+use super::proxies::{
+    ActiveConnectionProxy, ConnectionProxy, DeviceProxy, IP4ConfigProxy, IP6ConfigProxy,
+    ModemDeviceProxy, NetworkManagerProxy, SettingsProxy, WirelessDeviceProxy,
+};
+use crate::network::adapter::CancellationFlag;
+use crate::network::model::{
+    Connection, ConnectivityConfig, Device, DnsConfig, DnsStack, IpConfig, LldpNeighbor,
+    ModemCapabilities, PortState, WirelessCapabilities,
+};
+use agama_lib::{error::ServiceError, network::types::DeviceType};
 use log;
+use std::{
+    fs, io,
+    net::{IpAddr, Ipv6Addr},
+    path::Path,
+    time::Duration,
+};
 use uuid::Uuid;
 use zbus;
 use zbus::zvariant::{ObjectPath, OwnedObjectPath};
 
+/// Agama's NetworkManager configuration snippet for the connectivity check.
+const CONNECTIVITY_CONF_PATH: &str = "/etc/NetworkManager/conf.d/90-agama-connectivity.conf";
+/// NetworkManager configuration snippet for the DNS settings of the installed system.
+///
+/// It is not written to the `conf.d` directory because it should not affect the live system. The
+/// installer copies it to the installed system.
+const TARGET_DNS_CONF_PATH: &str = "/run/agama/network/90-agama-dns.conf";
+/// Flag to reload the configuration files (see NMManagerReloadFlags).
+const NM_RELOAD_FLAG_CONF: u32 = 0x1;
+/// Global connectivity state (see NMState).
+const NM_STATE_CONNECTED_GLOBAL: u32 = 70;
+/// Active connection states (see NMActiveConnectionState).
+const NM_ACTIVE_CONNECTION_STATE_ACTIVATED: u32 = 2;
+const NM_ACTIVE_CONNECTION_STATE_DEACTIVATED: u32 = 4;
+/// Device state when it is fully activated (see NMDeviceState).
+const NM_DEVICE_STATE_ACTIVATED: u32 = 100;
+/// Device interface flag set when the device has carrier (see NMDeviceInterfaceFlags).
+const NM_DEVICE_INTERFACE_FLAG_CARRIER: u32 = 0x10000;
+/// Interval to check the state of a connection which is being activated.
+const ACTIVATION_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
 /// Simplified NetworkManager D-Bus client.
 ///
 /// Implements a minimal API to be used internally. At this point, it allows to query the list of
@@ -53,10 +89,23 @@ impl<'a> NetworkManagerClient<'a> {
 
             let device_name = proxy.interface().await?;
             let device_type = NmDeviceType(proxy.device_type().await?);
-            if let Ok(device_type) = device_type.try_into() {
+            if let Ok(device_type) = DeviceType::try_from(device_type) {
+                let wireless_capabilities = if device_type == DeviceType::Wireless {
+                    Some(self.wireless_capabilities(path.as_str()).await?)
+                } else {
+                    None
+                };
+                let modem_capabilities = if device_type == DeviceType::Modem {
+                    Some(self.modem_capabilities(path.as_str()).await?)
+                } else {
+                    None
+                };
                 devs.push(Device {
                     name: device_name,
                     type_: device_type,
+                    wireless_capabilities,
+                    modem_capabilities,
+                    managed: proxy.managed().await?,
                 });
             } else {
                 // TODO: use a logger
@@ -71,6 +120,148 @@ impl<'a> NetworkManagerClient<'a> {
         Ok(devs)
     }
 
+    /// Returns the runtime state of the ports of a device (e.g., a bond).
+    ///
+    /// * `interface`: controller interface name.
+    pub async fn ports(&self, interface: &str) -> Result<Vec<PortState>, ServiceError> {
+        let path = self.nm_proxy.get_device_by_ip_iface(interface).await?;
+        let proxy = DeviceProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+
+        let mut ports = vec![];
+        for path in proxy.ports().await? {
+            let port = DeviceProxy::builder(&self.connection)
+                .path(path)?
+                .build()
+                .await?;
+            ports.push(PortState {
+                name: port.interface().await?,
+                active: port.state().await? == NM_DEVICE_STATE_ACTIVATED,
+                carrier: port.interface_flags().await? & NM_DEVICE_INTERFACE_FLAG_CARRIER != 0,
+            });
+        }
+        Ok(ports)
+    }
+
+    /// Returns the neighbors discovered through LLDP on a device.
+    ///
+    /// NetworkManager only collects them if LLDP is enabled in the active connection.
+    ///
+    /// * `device`: device name.
+    pub async fn lldp_neighbors(&self, device: &str) -> Result<Vec<LldpNeighbor>, ServiceError> {
+        let path = self.nm_proxy.get_device_by_ip_iface(device).await?;
+        let proxy = DeviceProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+        let neighbors = proxy
+            .lldp_neighbors()
+            .await?
+            .iter()
+            .filter_map(lldp_neighbor_from_dbus)
+            .collect();
+        Ok(neighbors)
+    }
+
+    /// Returns the current IP configuration of a device (addresses, gateways, routes and name
+    /// servers).
+    ///
+    /// The IP methods are not set because they are not part of the runtime state.
+    ///
+    /// * `device`: device name.
+    pub async fn runtime_ip_config(&self, device: &str) -> Result<IpConfig, ServiceError> {
+        let path = self.nm_proxy.get_device_by_ip_iface(device).await?;
+        let proxy = DeviceProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+        let mut ip_config = IpConfig::default();
+
+        let ip4_path = proxy.ip4_config().await?;
+        if ip4_path.as_str() != "/" {
+            let ip4 = IP4ConfigProxy::builder(&self.connection)
+                .path(ip4_path)?
+                .build()
+                .await?;
+            let addresses = runtime_addresses_from_dbus(&ip4.address_data().await?);
+            ip_config.addresses.extend(addresses);
+            ip_config.gateway4 = ip4.gateway().await?.parse().ok();
+            ip_config.routes4 = Some(runtime_routes_from_dbus(&ip4.route_data().await?));
+            let nameservers = runtime_addresses_from_dbus(&ip4.nameserver_data().await?);
+            ip_config
+                .nameservers
+                .extend(nameservers.iter().map(|n| n.address()));
+        }
+
+        let ip6_path = proxy.ip6_config().await?;
+        if ip6_path.as_str() != "/" {
+            let ip6 = IP6ConfigProxy::builder(&self.connection)
+                .path(ip6_path)?
+                .build()
+                .await?;
+            let addresses = runtime_addresses_from_dbus(&ip6.address_data().await?);
+            ip_config.addresses.extend(addresses);
+            ip_config.gateway6 = ip6.gateway().await?.parse().ok();
+            ip_config.routes6 = Some(runtime_routes_from_dbus(&ip6.route_data().await?));
+            let nameservers = ip6.nameservers().await?;
+            ip_config.nameservers.extend(
+                nameservers
+                    .into_iter()
+                    .filter_map(|n| <[u8; 16]>::try_from(n).ok())
+                    .map(|n| IpAddr::V6(Ipv6Addr::from(n))),
+            );
+        }
+
+        Ok(ip_config)
+    }
+
+    /// Sets whether NetworkManager manages a device.
+    ///
+    /// Unmanaged devices are left alone (e.g., to pass them through to a virtual machine).
+    ///
+    /// * `device`: device name.
+    /// * `managed`: whether the device should be managed.
+    pub async fn set_managed(&self, device: &str, managed: bool) -> Result<(), ServiceError> {
+        let path = self.nm_proxy.get_device_by_ip_iface(device).await?;
+        let proxy = DeviceProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+        proxy.set_managed(managed).await?;
+        Ok(())
+    }
+
+    /// Returns the capabilities of a wireless device.
+    ///
+    /// * `path`: device D-Bus path.
+    async fn wireless_capabilities(
+        &self,
+        path: &str,
+    ) -> Result<WirelessCapabilities, ServiceError> {
+        let proxy = WirelessDeviceProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+        let caps = NmWirelessCapabilities(proxy.wireless_capabilities().await?);
+        Ok(caps.into())
+    }
+
+    /// Returns the capabilities of a modem device.
+    ///
+    /// NetworkManager gets them from ModemManager, which is the one handling the modems.
+    ///
+    /// * `path`: device D-Bus path.
+    async fn modem_capabilities(&self, path: &str) -> Result<ModemCapabilities, ServiceError> {
+        let proxy = ModemDeviceProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+        let caps = NmModemCapabilities(proxy.current_capabilities().await?);
+        Ok(caps.into())
+    }
+
     /// Returns the list of network connections.
     pub async fn connections(&self) -> Result<Vec<Connection>, ServiceError> {
         let mut controlled_by: HashMap<Uuid, String> = HashMap::new();
@@ -84,7 +275,13 @@ impl<'a> NetworkManagerClient<'a> {
                 .path(path.as_str())?
                 .build()
                 .await?;
-            let settings = proxy.get_settings().await?;
+            let mut settings = proxy.get_settings().await?;
+            for setting in secret_settings_from_dbus(&settings) {
+                match proxy.get_secrets(setting).await {
+                    Ok(secrets) => merge_secrets_from_dbus(&mut settings, secrets),
+                    Err(e) => log::debug!("Could not read the '{}' secrets: {}", setting, e),
+                }
+            }
 
             if let Some(connection) = connection_from_dbus(settings.clone()) {
                 if let Some(controller) = controller_from_dbus(&settings) {
@@ -116,17 +313,141 @@ impl<'a> NetworkManagerClient<'a> {
         Ok(connections)
     }
 
+    /// Returns the connectivity check settings.
+    ///
+    /// NetworkManager does not expose the interval through D-Bus, so it is read from Agama's
+    /// configuration snippet (if it exists).
+    pub async fn connectivity(&self) -> Result<ConnectivityConfig, ServiceError> {
+        let uri = self.nm_proxy.connectivity_check_uri().await?;
+        let interval = fs::read_to_string(CONNECTIVITY_CONF_PATH)
+            .ok()
+            .and_then(|c| connectivity_interval_from_conf(&c));
+        Ok(ConnectivityConfig {
+            enabled: self.nm_proxy.connectivity_check_enabled().await?,
+            uri: if uri.is_empty() { None } else { Some(uri) },
+            interval,
+        })
+    }
+
+    /// Returns the static hostname, if any.
+    pub async fn hostname(&self) -> Result<Option<String>, ServiceError> {
+        let proxy = SettingsProxy::new(&self.connection).await?;
+        let hostname = proxy.hostname().await?;
+        Ok(if hostname.is_empty() {
+            None
+        } else {
+            Some(hostname)
+        })
+    }
+
+    /// Writes the static hostname.
+    ///
+    /// * `hostname`: hostname. An empty string removes the static hostname.
+    pub async fn save_hostname(&self, hostname: &str) -> Result<(), ServiceError> {
+        let proxy = SettingsProxy::new(&self.connection).await?;
+        proxy.save_hostname(hostname).await?;
+        Ok(())
+    }
+
+    /// Determines whether the system has global connectivity.
+    pub async fn is_online(&self) -> Result<bool, ServiceError> {
+        Ok(self.nm_proxy.state().await? == NM_STATE_CONNECTED_GLOBAL)
+    }
+
+    /// Writes the connectivity check settings and reloads NetworkManager's configuration.
+    ///
+    /// * `config`: connectivity check settings.
+    pub async fn update_connectivity(
+        &self,
+        config: &ConnectivityConfig,
+    ) -> Result<(), ServiceError> {
+        write_file(CONNECTIVITY_CONF_PATH, &connectivity_to_conf(config))
+            .map_err(anyhow::Error::from)?;
+        self.nm_proxy.reload(NM_RELOAD_FLAG_CONF).await?;
+        Ok(())
+    }
+
+    /// Reloads NetworkManager's configuration and the connection profiles stored on disk.
+    ///
+    /// The connections that were changed on disk are updated in NetworkManager, so they are
+    /// picked up by the next read.
+    pub async fn reload(&self) -> Result<(), ServiceError> {
+        self.nm_proxy.reload(NM_RELOAD_FLAG_CONF).await?;
+        let proxy = SettingsProxy::new(&self.connection).await?;
+        proxy.reload_connections().await?;
+        Ok(())
+    }
+
+    /// Sets NetworkManager's global DNS configuration.
+    ///
+    /// The global configuration takes precedence over the per-connection one, so it is only
+    /// written when there are global settings. Otherwise, any previous global configuration is
+    /// removed.
+    ///
+    /// * `servers`: name servers (already merged with the per-connection ones).
+    /// * `searches`: search domains.
+    pub async fn update_global_dns(
+        &self,
+        servers: &[IpAddr],
+        searches: &[String],
+    ) -> Result<(), ServiceError> {
+        let config = global_dns_to_dbus(servers, searches);
+        if config.is_empty() && self.nm_proxy.global_dns_configuration().await?.is_empty() {
+            return Ok(());
+        }
+        self.nm_proxy.set_global_dns_configuration(config).await?;
+        Ok(())
+    }
+
+    /// Writes the DNS configuration for the installed system.
+    ///
+    /// The configuration is removed if it only contains the default values.
+    ///
+    /// * `config`: DNS settings.
+    /// * `servers`: name servers (already merged with the per-connection ones).
+    pub fn update_target_dns(
+        &self,
+        config: &DnsConfig,
+        servers: &[IpAddr],
+    ) -> Result<(), ServiceError> {
+        if config.stack == DnsStack::Default && config.is_empty() {
+            if Path::new(TARGET_DNS_CONF_PATH).exists() {
+                fs::remove_file(TARGET_DNS_CONF_PATH).map_err(anyhow::Error::from)?;
+            }
+            return Ok(());
+        }
+
+        let conf = dns_to_conf(config.stack, servers, &config.searches);
+        write_file(TARGET_DNS_CONF_PATH, &conf).map_err(anyhow::Error::from)?;
+        Ok(())
+    }
+
     /// Adds or updates a connection if it already exists.
     ///
+    /// If the connection has an activation timeout, it waits for the connection to be activated
+    /// until the timeout expires or the operation is cancelled.
+    ///
     /// * `conn`: connection to add or update.
+    /// * `controller`: controller connection, if any.
+    /// * `cancel`: flag to cancel waiting for the activation.
     pub async fn add_or_update_connection(
         &self,
         conn: &Connection,
         controller: Option<&Connection>,
+        cancel: &CancellationFlag,
     ) -> Result<(), ServiceError> {
         let mut new_conn = connection_to_dbus(conn, controller);
 
-        let path = if let Ok(proxy) = self.get_connection_proxy(conn.uuid).await {
+        let mut original = self.get_connection_proxy(conn.uuid).await.ok();
+        if conn.migrated_from_team {
+            // NetworkManager does not allow changing the type of a connection, so the team
+            // profile is replaced with a new one.
+            if let Some(proxy) = original.take() {
+                proxy.delete().await?;
+            }
+        }
+
+        let path = if let Some(proxy) = original {
             let original = proxy.get_settings().await?;
             let merged = merge_dbus_connections(&original, &new_conn);
             proxy.update(merged).await?;
@@ -137,8 +458,12 @@ impl<'a> NetworkManagerClient<'a> {
             proxy.add_connection(new_conn).await?
         };
 
-        if conn.is_up() {
-            self.activate_connection(path).await?;
+        if conn.is_up() && !conn.only_on_target {
+            let active = self.activate_connection(path).await?;
+            if let Some(timeout) = conn.activation_timeout {
+                self.wait_for_activation(active, Duration::from_secs(timeout.into()), cancel)
+                    .await?;
+            }
         } else {
             self.deactivate_connection(path).await?;
         }
@@ -169,27 +494,68 @@ impl<'a> NetworkManagerClient<'a> {
         Ok(())
     }
 
-    /// Rolls the configuration back to the given checkpoint.
-    ///
-    /// * `checkpoint`: checkpoint's D-Bus path.
-    pub async fn rollback_checkpoint(
-        &self,
-        checkpoint: &ObjectPath<'_>,
-    ) -> Result<(), ServiceError> {
-        self.nm_proxy.checkpoint_rollback(checkpoint).await?;
-        Ok(())
-    }
-
     /// Activates a NetworkManager connection.
     ///
+    /// It returns the D-Bus path of the active connection.
+    ///
     /// * `path`: D-Bus patch of the connection.
-    async fn activate_connection(&self, path: OwnedObjectPath) -> Result<(), ServiceError> {
+    async fn activate_connection(
+        &self,
+        path: OwnedObjectPath,
+    ) -> Result<OwnedObjectPath, ServiceError> {
         let proxy = NetworkManagerProxy::new(&self.connection).await?;
         let root = ObjectPath::try_from("/").unwrap();
-        proxy
+        let active = proxy
             .activate_connection(&path.as_ref(), &root, &root)
             .await?;
-        Ok(())
+        Ok(active)
+    }
+
+    /// Waits for an active connection to be activated.
+    ///
+    /// * `path`: D-Bus path of the active connection.
+    /// * `timeout`: maximum time to wait.
+    /// * `cancel`: flag to stop waiting.
+    async fn wait_for_activation(
+        &self,
+        path: OwnedObjectPath,
+        timeout: Duration,
+        cancel: &CancellationFlag,
+    ) -> Result<(), ServiceError> {
+        let proxy = ActiveConnectionProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+        let started = tokio::time::Instant::now();
+        loop {
+            // the active connection object vanishes when the activation fails
+            let state = proxy
+                .state()
+                .await
+                .unwrap_or(NM_ACTIVE_CONNECTION_STATE_DEACTIVATED);
+            match state {
+                NM_ACTIVE_CONNECTION_STATE_ACTIVATED => return Ok(()),
+                NM_ACTIVE_CONNECTION_STATE_DEACTIVATED => {
+                    return Err(ServiceError::UnsuccessfulAction(
+                        "connection activation failed".to_string(),
+                    ))
+                }
+                _ => {}
+            }
+
+            if cancel.is_cancelled() {
+                return Err(ServiceError::UnsuccessfulAction(
+                    "connection activation cancelled".to_string(),
+                ));
+            }
+
+            if started.elapsed() >= timeout {
+                return Err(ServiceError::UnsuccessfulAction(
+                    "connection activation timed out".to_string(),
+                ));
+            }
+            tokio::time::sleep(ACTIVATION_CHECK_INTERVAL).await;
+        }
     }
 
     /// Deactivates a NetworkManager connection.
@@ -221,3 +587,14 @@ impl<'a> NetworkManagerClient<'a> {
         Ok(proxy)
     }
 }
+
+/// Writes a file, creating the parent directory if needed.
+///
+/// * `path`: file path.
+/// * `content`: file content.
+fn write_file(path: &str, content: &str) -> io::Result<()> {
+    if let Some(parent) = Path::new(path).parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)
+}
@@ -0,0 +1,124 @@
+//! Support for NetworkManager configuration files.
+//!
+//! Some settings (e.g., the connectivity check ones) cannot be changed through the D-Bus API, so
+//! Agama writes its own snippet to the `conf.d` directory (see NetworkManager.conf(5)).
+use crate::network::model::{ConnectivityConfig, DnsStack};
+use std::net::IpAddr;
+
+/// Renders the NetworkManager configuration for the connectivity check.
+///
+/// * `config`: connectivity check settings.
+pub fn connectivity_to_conf(config: &ConnectivityConfig) -> String {
+    let mut conf = String::from("[connectivity]\n");
+    conf.push_str(&format!("enabled={}\n", config.enabled));
+    if let Some(uri) = &config.uri {
+        conf.push_str(&format!("uri={}\n", uri));
+    }
+    if let Some(interval) = config.interval {
+        conf.push_str(&format!("interval={}\n", interval));
+    }
+    conf
+}
+
+/// Renders the NetworkManager configuration for the DNS settings of the installed system.
+///
+/// It selects the resolver stack and includes the global DNS settings, if any.
+///
+/// * `stack`: DNS resolver stack.
+/// * `servers`: global name servers (already merged with the per-connection ones).
+/// * `searches`: global search domains.
+pub fn dns_to_conf(stack: DnsStack, servers: &[IpAddr], searches: &[String]) -> String {
+    let mut conf = match stack {
+        DnsStack::Default => String::new(),
+        DnsStack::Netconfig => String::from("[main]\ndns=default\nrc-manager=netconfig\n"),
+        DnsStack::Resolved => String::from("[main]\ndns=systemd-resolved\n"),
+        DnsStack::File => String::from("[main]\ndns=default\nrc-manager=file\n"),
+    };
+
+    if servers.is_empty() && searches.is_empty() {
+        return conf;
+    }
+
+    if !conf.is_empty() {
+        conf.push('\n');
+    }
+    conf.push_str("[global-dns]\n");
+    if !searches.is_empty() {
+        conf.push_str(&format!("searches={}\n", searches.join(",")));
+    }
+    if !servers.is_empty() {
+        let servers: Vec<String> = servers.iter().map(IpAddr::to_string).collect();
+        conf.push_str(&format!(
+            "\n[global-dns-domain-*]\nservers={}\n",
+            servers.join(",")
+        ));
+    }
+    conf
+}
+
+/// Extracts the connectivity check interval from a NetworkManager configuration file.
+///
+/// * `conf`: configuration file content.
+pub fn connectivity_interval_from_conf(conf: &str) -> Option<u32> {
+    let mut in_section = false;
+    for line in conf.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_section = line == "[connectivity]";
+        } else if in_section {
+            if let Some(("interval", value)) = line.split_once('=').map(|(k, v)| (k.trim(), v)) {
+                return value.trim().parse().ok();
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connectivity_to_conf() {
+        let config = ConnectivityConfig {
+            enabled: true,
+            uri: Some("http://example.net/check".to_string()),
+            interval: Some(600),
+        };
+        assert_eq!(
+            connectivity_to_conf(&config),
+            "[connectivity]\nenabled=true\nuri=http://example.net/check\ninterval=600\n"
+        );
+
+        let config = ConnectivityConfig::default();
+        assert_eq!(
+            connectivity_to_conf(&config),
+            "[connectivity]\nenabled=false\n"
+        );
+    }
+
+    #[test]
+    fn test_dns_to_conf() {
+        let servers: Vec<IpAddr> = vec![
+            "192.168.1.1".parse().unwrap(),
+            "192.168.1.2".parse().unwrap(),
+        ];
+        let searches = vec!["example.net".to_string()];
+        assert_eq!(
+            dns_to_conf(DnsStack::Resolved, &servers, &searches),
+            "[main]\ndns=systemd-resolved\n\n[global-dns]\nsearches=example.net\n\n\
+            [global-dns-domain-*]\nservers=192.168.1.1,192.168.1.2\n"
+        );
+        assert_eq!(
+            dns_to_conf(DnsStack::Netconfig, &[], &[]),
+            "[main]\ndns=default\nrc-manager=netconfig\n"
+        );
+        assert_eq!(dns_to_conf(DnsStack::Default, &[], &[]), "");
+    }
+
+    #[test]
+    fn test_connectivity_interval_from_conf() {
+        let conf = "[main]\ninterval=1\n\n[connectivity]\nenabled=true\ninterval = 300\n";
+        assert_eq!(connectivity_interval_from_conf(conf), Some(300));
+        assert_eq!(connectivity_interval_from_conf("[connectivity]\n"), None);
+    }
+}
@@ -18,12 +18,49 @@ const ETHERNET_KEY: &str = "802-3-ethernet";
 const BOND_KEY: &str = "bond";
 const WIRELESS_KEY: &str = "802-11-wireless";
 const WIRELESS_SECURITY_KEY: &str = "802-11-wireless-security";
+const IEEE8021X_KEY: &str = "802-1x";
 const LOOPBACK_KEY: &str = "loopback";
 const DUMMY_KEY: &str = "dummy";
 const VLAN_KEY: &str = "vlan";
 const BRIDGE_KEY: &str = "bridge";
 const BRIDGE_PORT_KEY: &str = "bridge-port";
 const INFINIBAND_KEY: &str = "infiniband";
+const TEAM_KEY: &str = "team";
+const PPPOE_KEY: &str = "pppoe";
+const GSM_KEY: &str = "gsm";
+const VRF_KEY: &str = "vrf";
+const MACVLAN_KEY: &str = "macvlan";
+const IPVLAN_KEY: &str = "ipvlan";
+const MACSEC_KEY: &str = "macsec";
+const USER_KEY: &str = "user";
+/// User data key to mark the connections that should only be activated in the installed system.
+const ONLY_ON_TARGET_KEY: &str = "org.opensuse.agama.only-on-target";
+/// User data key to mark the connections the installed system does not wait for when booting.
+const REQUIRED_FOR_ONLINE_KEY: &str = "org.opensuse.agama.required-for-online";
+/// User data key to store the description of the connection.
+const DESCRIPTION_KEY: &str = "org.opensuse.agama.description";
+/// User data key to store the tags of the connection (comma-separated).
+const TAGS_KEY: &str = "org.opensuse.agama.tags";
+/// Prefix of the user data keys to store the connection metadata.
+const METADATA_PREFIX: &str = "org.opensuse.agama.metadata.";
+/// LLDP modes (see the `connection.lldp` setting in nm-settings(5)).
+const NM_LLDP_DEFAULT: i32 = -1;
+const NM_LLDP_ENABLE_RX: i32 = 1;
+/// Value of the `route-metric` setting to use the default metric.
+const NM_DEFAULT_ROUTE_METRIC: i64 = -1;
+/// Value of the `ipv6.addr-gen-mode` setting to build the addresses from the interface identifier
+/// (it is required to use a token).
+const NM_ADDR_GEN_MODE_EUI64: i32 = 0;
+/// Value of the `ipv6.addr-gen-mode` setting to build stable privacy addresses (RFC 7217).
+const NM_ADDR_GEN_MODE_STABLE_PRIVACY: i32 = 1;
+/// Settings that might contain secrets and the flags that determine where they are stored.
+const SECRET_FLAGS: [(&str, &[&str]); 5] = [
+    (WIRELESS_SECURITY_KEY, &["psk-flags", "wep-key-flags"]),
+    (IEEE8021X_KEY, &["password-flags"]),
+    (PPPOE_KEY, &["password-flags"]),
+    (GSM_KEY, &["password-flags", "pin-flags"]),
+    (MACSEC_KEY, &["mka-cak-flags"]),
+];
 
 /// Converts a connection struct into a HashMap that can be sent over D-Bus.
 ///
@@ -46,6 +83,7 @@ pub fn connection_to_dbus<'a>(
         let slave_type = match controller.config {
             ConnectionConfig::Bond(_) => BOND_KEY,
             ConnectionConfig::Bridge(_) => BRIDGE_KEY,
+            ConnectionConfig::Vrf(_) => VRF_KEY,
             _ => {
                 log::error!("Controller {} has unhandled config type", controller.id);
                 ""
@@ -66,6 +104,23 @@ pub fn connection_to_dbus<'a>(
         connection_dbus.insert("zone", zone.into());
     }
 
+    connection_dbus.insert("mdns", (conn.mdns as i32).into());
+    connection_dbus.insert("llmnr", (conn.llmnr as i32).into());
+    let lldp = if conn.lldp {
+        NM_LLDP_ENABLE_RX
+    } else {
+        NM_LLDP_DEFAULT
+    };
+    connection_dbus.insert("lldp", lldp.into());
+    let permissions: Vec<String> = conn
+        .permissions
+        .iter()
+        .map(|user| format!("user:{}:", user))
+        .collect();
+    connection_dbus.insert("permissions", permissions.into());
+    connection_dbus.insert("autoconnect", (!conn.only_on_target).into());
+    result.insert(USER_KEY, user_data_to_dbus(conn));
+
     result.insert("ipv4", ip_config_to_ipv4_dbus(&conn.ip_config));
     result.insert("ipv6", ip_config_to_ipv6_dbus(&conn.ip_config));
     result.insert("match", match_config_to_dbus(&conn.match_config));
@@ -106,6 +161,42 @@ pub fn connection_to_dbus<'a>(
             connection_dbus.insert("type", INFINIBAND_KEY.into());
             result.insert(INFINIBAND_KEY, infiniband_config_to_dbus(infiniband));
         }
+        ConnectionConfig::Pppoe(pppoe) => {
+            connection_dbus.insert("type", PPPOE_KEY.into());
+            result.insert(PPPOE_KEY, pppoe_config_to_dbus(pppoe));
+        }
+        ConnectionConfig::Modem(modem) => {
+            connection_dbus.insert("type", GSM_KEY.into());
+            result.insert(GSM_KEY, modem_config_to_dbus(modem));
+        }
+        ConnectionConfig::Vrf(vrf) => {
+            connection_dbus.insert("type", VRF_KEY.into());
+            if !connection_dbus.contains_key("interface-name") {
+                connection_dbus.insert("interface-name", conn.id.as_str().into());
+            }
+            result.insert(VRF_KEY, vrf_config_to_dbus(vrf));
+        }
+        ConnectionConfig::Macvlan(macvlan) => {
+            connection_dbus.insert("type", MACVLAN_KEY.into());
+            if !connection_dbus.contains_key("interface-name") {
+                connection_dbus.insert("interface-name", conn.id.as_str().into());
+            }
+            result.insert(MACVLAN_KEY, macvlan_config_to_dbus(macvlan));
+        }
+        ConnectionConfig::Ipvlan(ipvlan) => {
+            connection_dbus.insert("type", IPVLAN_KEY.into());
+            if !connection_dbus.contains_key("interface-name") {
+                connection_dbus.insert("interface-name", conn.id.as_str().into());
+            }
+            result.insert(IPVLAN_KEY, ipvlan_config_to_dbus(ipvlan));
+        }
+        ConnectionConfig::Macsec(macsec) => {
+            connection_dbus.insert("type", MACSEC_KEY.into());
+            if !connection_dbus.contains_key("interface-name") {
+                connection_dbus.insert("interface-name", conn.id.as_str().into());
+            }
+            result.insert(MACSEC_KEY, macsec_config_to_dbus(macsec));
+        }
         _ => {}
     }
 
@@ -116,6 +207,7 @@ pub fn connection_to_dbus<'a>(
         PortConfig::None => {}
     }
 
+    secret_flags_to_dbus(&mut result, conn.secret_storage);
     result.insert("connection", connection_dbus);
     result
 }
@@ -129,6 +221,7 @@ pub fn connection_from_dbus(conn: OwnedNestedHash) -> Option<Connection> {
     if let Some(bridge_port_config) = bridge_port_config_from_dbus(&conn) {
         connection.port_config = PortConfig::Bridge(bridge_port_config);
     }
+    connection.secret_storage = secret_storage_from_dbus(&conn);
 
     if let Some(wireless_config) = wireless_config_from_dbus(&conn) {
         connection.config = ConnectionConfig::Wireless(wireless_config);
@@ -140,6 +233,17 @@ pub fn connection_from_dbus(conn: OwnedNestedHash) -> Option<Connection> {
         return Some(connection);
     }
 
+    if let Some(bond_config) = team_config_from_dbus(&conn) {
+        log::warn!(
+            "Converting the team connection '{}' into a bond ({})",
+            connection.id,
+            bond_config.mode
+        );
+        connection.config = ConnectionConfig::Bond(bond_config);
+        connection.migrated_from_team = true;
+        return Some(connection);
+    }
+
     if let Some(vlan_config) = vlan_config_from_dbus(&conn) {
         connection.config = ConnectionConfig::Vlan(vlan_config);
         return Some(connection);
@@ -155,6 +259,36 @@ pub fn connection_from_dbus(conn: OwnedNestedHash) -> Option<Connection> {
         return Some(connection);
     }
 
+    if let Some(pppoe_config) = pppoe_config_from_dbus(&conn) {
+        connection.config = ConnectionConfig::Pppoe(pppoe_config);
+        return Some(connection);
+    }
+
+    if let Some(modem_config) = modem_config_from_dbus(&conn) {
+        connection.config = ConnectionConfig::Modem(modem_config);
+        return Some(connection);
+    }
+
+    if let Some(vrf_config) = vrf_config_from_dbus(&conn) {
+        connection.config = ConnectionConfig::Vrf(vrf_config);
+        return Some(connection);
+    }
+
+    if let Some(macvlan_config) = macvlan_config_from_dbus(&conn) {
+        connection.config = ConnectionConfig::Macvlan(macvlan_config);
+        return Some(connection);
+    }
+
+    if let Some(ipvlan_config) = ipvlan_config_from_dbus(&conn) {
+        connection.config = ConnectionConfig::Ipvlan(ipvlan_config);
+        return Some(connection);
+    }
+
+    if let Some(macsec_config) = macsec_config_from_dbus(&conn) {
+        connection.config = ConnectionConfig::Macsec(macsec_config);
+        return Some(connection);
+    }
+
     if conn.get(DUMMY_KEY).is_some() {
         connection.config = ConnectionConfig::Dummy;
         return Some(connection);
@@ -196,6 +330,11 @@ pub fn merge_dbus_connections<'a>(
         }
         merged.insert(key.as_str(), inner);
     }
+    // the user data is kept by Agama, so it must be written even if the original
+    // connection does not contain it
+    if let Some(user) = updated.get(USER_KEY) {
+        merged.entry(USER_KEY).or_insert_with(|| user.clone());
+    }
     cleanup_dbus_connection(&mut merged);
     merged
 }
@@ -235,6 +374,9 @@ pub fn cleanup_dbus_connection(conn: &mut NestedHash) {
         if ipv6.get("address-data").is_some_and(is_empty_value) {
             ipv6.remove("gateway");
         }
+        if ipv6.get("token").is_some_and(is_empty_value) {
+            ipv6.remove("token");
+        }
     }
 }
 
@@ -248,6 +390,153 @@ pub fn controller_from_dbus(conn: &OwnedNestedHash) -> Option<String> {
     Some(master.to_string())
 }
 
+/// Returns the settings of the connection that might contain secrets.
+///
+/// NetworkManager does not include the secrets in `GetSettings`, so they must be requested
+/// separately for each of these settings through `GetSecrets`.
+///
+/// * `conn`: connection represented as an OwnedNestedHash.
+pub fn secret_settings_from_dbus(conn: &OwnedNestedHash) -> Vec<&'static str> {
+    SECRET_FLAGS
+        .into_iter()
+        .map(|(key, _)| key)
+        .filter(|key| conn.contains_key(*key))
+        .collect()
+}
+
+/// Sets the secret flags of the settings that might contain secrets.
+///
+/// * `conn`: connection represented as a NestedHash.
+/// * `storage`: where the secrets are stored.
+fn secret_flags_to_dbus(conn: &mut NestedHash, storage: SecretStorage) {
+    for (key, flags) in SECRET_FLAGS {
+        if let Some(section) = conn.get_mut(key) {
+            for flag in flags {
+                section.insert(*flag, (storage as u32).into());
+            }
+        }
+    }
+}
+
+/// Determines where the secrets are stored from the first secret flag in the connection.
+///
+/// * `conn`: connection represented as an OwnedNestedHash.
+fn secret_storage_from_dbus(conn: &OwnedNestedHash) -> SecretStorage {
+    SECRET_FLAGS
+        .iter()
+        .filter_map(|(key, flags)| Some((conn.get(*key)?, flags)))
+        .flat_map(|(section, flags)| flags.iter().filter_map(|f| section.get(*f)))
+        .find_map(|flags| flags.downcast_ref::<u32>().copied())
+        .map(SecretStorage::from)
+        .unwrap_or_default()
+}
+
+/// Merges the secrets returned by `GetSecrets` into the connection settings.
+///
+/// * `conn`: connection represented as an OwnedNestedHash.
+/// * `secrets`: secrets to merge.
+pub fn merge_secrets_from_dbus(conn: &mut OwnedNestedHash, secrets: OwnedNestedHash) {
+    for (key, secrets_section) in secrets {
+        if let Some(section) = conn.get_mut(&key) {
+            section.extend(secrets_section);
+        }
+    }
+}
+
+/// Converts the global DNS settings to NetworkManager's `GlobalDnsConfiguration`.
+///
+/// An empty configuration removes the global settings, so the per-connection ones are used.
+///
+/// * `servers`: name servers.
+/// * `searches`: search domains.
+pub fn global_dns_to_dbus(
+    servers: &[IpAddr],
+    searches: &[String],
+) -> HashMap<&'static str, zvariant::Value<'static>> {
+    let mut config = HashMap::new();
+    if servers.is_empty() && searches.is_empty() {
+        return config;
+    }
+
+    let servers: Vec<String> = servers.iter().map(IpAddr::to_string).collect();
+    let default_domain = HashMap::from([("servers", Value::new(servers))]);
+    let domains = HashMap::from([("*", Value::new(default_domain))]);
+    config.insert("searches", Value::new(searches.to_vec()));
+    config.insert("domains", Value::new(domains));
+    config
+}
+
+/// Converts an element of the device `LldpNeighbors` property to a [LldpNeighbor].
+///
+/// It returns `None` if the chassis or the port IDs are missing.
+///
+/// * `neighbor`: LLDP neighbor attributes.
+pub fn lldp_neighbor_from_dbus(neighbor: &HashMap<String, OwnedValue>) -> Option<LldpNeighbor> {
+    let get_str = |key: &str| -> Option<String> {
+        let value: &str = neighbor.get(key)?.downcast_ref()?;
+        Some(value.to_string())
+    };
+
+    Some(LldpNeighbor {
+        chassis_id: get_str("chassis-id")?,
+        system_name: get_str("system-name"),
+        port_id: get_str("port-id")?,
+        port_description: get_str("port-description"),
+        vlan_id: neighbor
+            .get("ieee-802-1-pvid")
+            .and_then(|v| v.downcast_ref::<u32>())
+            .copied(),
+    })
+}
+
+/// Converts the runtime addresses of a device (`AddressData` or `NameserverData` properties) to
+/// a list of [IpInet].
+///
+/// Elements without a prefix (e.g., name servers) get the longest one.
+///
+/// * `data`: list of addresses as returned by NetworkManager.
+pub fn runtime_addresses_from_dbus(data: &[HashMap<String, OwnedValue>]) -> Vec<IpInet> {
+    data.iter()
+        .filter_map(|item| {
+            let address: &str = item.get("address")?.downcast_ref()?;
+            let address: IpAddr = address.parse().ok()?;
+            let prefix = match item.get("prefix") {
+                Some(prefix) => *prefix.downcast_ref::<u32>()? as u8,
+                None if address.is_ipv4() => 32,
+                None => 128,
+            };
+            IpInet::new(address, prefix).ok()
+        })
+        .collect()
+}
+
+/// Converts the runtime routes of a device (`RouteData` property) to a list of [IpRoute].
+///
+/// Only the routes going through a gateway are included. The default routes are skipped too,
+/// because they are described by the gateway setting.
+///
+/// * `data`: list of routes as returned by NetworkManager.
+pub fn runtime_routes_from_dbus(data: &[HashMap<String, OwnedValue>]) -> Vec<IpRoute> {
+    data.iter()
+        .filter_map(|item| {
+            let dest: &str = item.get("dest")?.downcast_ref()?;
+            let prefix = *item.get("prefix")?.downcast_ref::<u32>()? as u8;
+            if prefix == 0 {
+                return None;
+            }
+            let next_hop: &str = item.get("next-hop")?.downcast_ref()?;
+            Some(IpRoute {
+                destination: IpInet::new(dest.parse().ok()?, prefix).ok()?,
+                next_hop: Some(next_hop.parse().ok()?),
+                metric: item
+                    .get("metric")
+                    .and_then(|m| m.downcast_ref::<u32>())
+                    .copied(),
+            })
+        })
+        .collect()
+}
+
 fn ip_config_to_ipv4_dbus(ip_config: &IpConfig) -> HashMap<&str, zvariant::Value> {
     let addresses: Vec<HashMap<&str, Value>> = ip_config
         .addresses
@@ -290,6 +579,12 @@ fn ip_config_to_ipv4_dbus(ip_config: &IpConfig) -> HashMap<&str, zvariant::Value
     if let Some(gateway) = &ip_config.gateway4 {
         ipv4_dbus.insert("gateway", gateway.to_string().into());
     }
+
+    let route_metric = ip_config
+        .route_metric4
+        .map_or(NM_DEFAULT_ROUTE_METRIC, i64::from);
+    ipv4_dbus.insert("route-metric", route_metric.into());
+    ipv4_dbus.insert("never-default", ip_config.never_default4.into());
     ipv4_dbus
 }
 
@@ -335,6 +630,25 @@ fn ip_config_to_ipv6_dbus(ip_config: &IpConfig) -> HashMap<&str, zvariant::Value
     if let Some(gateway) = &ip_config.gateway6 {
         ipv6_dbus.insert("gateway", gateway.to_string().into());
     }
+
+    let route_metric = ip_config
+        .route_metric6
+        .map_or(NM_DEFAULT_ROUTE_METRIC, i64::from);
+    ipv6_dbus.insert("route-metric", route_metric.into());
+    ipv6_dbus.insert("never-default", ip_config.never_default6.into());
+
+    // an empty token is removed by cleanup_dbus_connection
+    let token = ip_config.token6.map(|t| t.to_string()).unwrap_or_default();
+    if !token.is_empty() {
+        ipv6_dbus.insert("addr-gen-mode", NM_ADDR_GEN_MODE_EUI64.into());
+    } else if let Some(mode) = ip_config.addr_gen_mode6 {
+        let mode = match mode {
+            Ipv6AddrGenMode::Eui64 => NM_ADDR_GEN_MODE_EUI64,
+            Ipv6AddrGenMode::StablePrivacy => NM_ADDR_GEN_MODE_STABLE_PRIVACY,
+        };
+        ipv6_dbus.insert("addr-gen-mode", mode.into());
+    }
+    ipv6_dbus.insert("token", token.into());
     ipv6_dbus
 }
 
@@ -362,8 +676,20 @@ fn wireless_config_to_dbus<'a>(
     let mut security: HashMap<&str, zvariant::Value> =
         HashMap::from([("key-mgmt", config.security.to_string().into())]);
 
+    // with 802.1X authentication, the password belongs to the 802-1x setting
+    let eap = matches!(
+        config.security,
+        SecurityProtocol::DynamicWEP
+            | SecurityProtocol::WPA2Enterprise
+            | SecurityProtocol::WPA3Only
+    );
+    let mut ieee8021x: HashMap<&str, zvariant::Value> = HashMap::new();
     if let Some(password) = &config.password {
-        security.insert("psk", password.to_string().into());
+        if eap {
+            ieee8021x.insert("password", password.as_str().into());
+        } else {
+            security.insert("psk", password.to_string().into());
+        }
     }
     if let Some(wep_security) = &config.wep_security {
         security.insert(
@@ -391,7 +717,35 @@ fn wireless_config_to_dbus<'a>(
         security.insert("wep-tx-keyidx", wep_security.wep_key_index.into());
     }
 
-    NestedHash::from([(WIRELESS_KEY, wireless), (WIRELESS_SECURITY_KEY, security)])
+    let mut result =
+        NestedHash::from([(WIRELESS_KEY, wireless), (WIRELESS_SECURITY_KEY, security)]);
+    if eap {
+        result.insert(IEEE8021X_KEY, ieee8021x);
+    }
+    result
+}
+
+/// Converts the Agama specific settings into NetworkManager's user data.
+///
+/// * `conn`: connection to take the settings from.
+fn user_data_to_dbus(conn: &Connection) -> HashMap<&str, zvariant::Value> {
+    let mut data: HashMap<String, String> = HashMap::new();
+    if conn.only_on_target {
+        data.insert(ONLY_ON_TARGET_KEY.to_string(), "true".to_string());
+    }
+    if !conn.required_for_online {
+        data.insert(REQUIRED_FOR_ONLINE_KEY.to_string(), "false".to_string());
+    }
+    if let Some(description) = &conn.description {
+        data.insert(DESCRIPTION_KEY.to_string(), description.to_string());
+    }
+    if !conn.tags.is_empty() {
+        data.insert(TAGS_KEY.to_string(), conn.tags.join(","));
+    }
+    for (key, value) in &conn.metadata {
+        data.insert(format!("{}{}", METADATA_PREFIX, key), value.to_string());
+    }
+    HashMap::from([("data", Value::new(data))])
 }
 
 fn bond_config_to_dbus(config: &BondConfig) -> HashMap<&str, zvariant::Value> {
@@ -530,6 +884,201 @@ fn infiniband_config_from_dbus(conn: &OwnedNestedHash) -> Option<InfinibandConfi
     Some(infiniband_config)
 }
 
+fn pppoe_config_to_dbus(config: &PppoeConfig) -> HashMap<&str, zvariant::Value> {
+    let mut pppoe: HashMap<&str, zvariant::Value> = HashMap::from([
+        ("username", config.username.as_str().into()),
+        ("parent", config.parent.as_str().into()),
+    ]);
+
+    if let Some(password) = &config.password {
+        pppoe.insert("password", password.as_str().into());
+    }
+
+    if let Some(service) = &config.service {
+        pppoe.insert("service", service.as_str().into());
+    }
+
+    pppoe
+}
+
+fn pppoe_config_from_dbus(conn: &OwnedNestedHash) -> Option<PppoeConfig> {
+    let pppoe = conn.get(PPPOE_KEY)?;
+
+    let mut pppoe_config = PppoeConfig::default();
+
+    if let Some(username) = pppoe.get("username") {
+        pppoe_config.username = username.downcast_ref::<str>()?.to_string();
+    }
+
+    if let Some(parent) = pppoe.get("parent") {
+        pppoe_config.parent = parent.downcast_ref::<str>()?.to_string();
+    }
+
+    if let Some(service) = pppoe.get("service") {
+        pppoe_config.service = Some(service.downcast_ref::<str>()?.to_string());
+    }
+
+    if let Some(password) = pppoe.get("password") {
+        pppoe_config.password = Some(password.downcast_ref::<str>()?.to_string());
+    }
+
+    Some(pppoe_config)
+}
+
+fn modem_config_to_dbus(config: &ModemConfig) -> HashMap<&str, zvariant::Value> {
+    let mut gsm: HashMap<&str, zvariant::Value> =
+        HashMap::from([("apn", config.apn.as_str().into())]);
+
+    if let Some(pin) = &config.pin {
+        gsm.insert("pin", pin.as_str().into());
+    }
+
+    if let Some(username) = &config.username {
+        gsm.insert("username", username.as_str().into());
+    }
+
+    if let Some(password) = &config.password {
+        gsm.insert("password", password.as_str().into());
+    }
+
+    gsm
+}
+
+fn modem_config_from_dbus(conn: &OwnedNestedHash) -> Option<ModemConfig> {
+    let gsm = conn.get(GSM_KEY)?;
+
+    let mut modem_config = ModemConfig::default();
+
+    if let Some(apn) = gsm.get("apn") {
+        modem_config.apn = apn.downcast_ref::<str>()?.to_string();
+    }
+
+    if let Some(username) = gsm.get("username") {
+        modem_config.username = Some(username.downcast_ref::<str>()?.to_string());
+    }
+
+    if let Some(pin) = gsm.get("pin") {
+        modem_config.pin = Some(pin.downcast_ref::<str>()?.to_string());
+    }
+
+    if let Some(password) = gsm.get("password") {
+        modem_config.password = Some(password.downcast_ref::<str>()?.to_string());
+    }
+
+    Some(modem_config)
+}
+
+fn vrf_config_to_dbus(config: &VrfConfig) -> HashMap<&str, zvariant::Value> {
+    HashMap::from([("table", config.table.into())])
+}
+
+fn vrf_config_from_dbus(conn: &OwnedNestedHash) -> Option<VrfConfig> {
+    let vrf = conn.get(VRF_KEY)?;
+
+    let mut vrf_config = VrfConfig::default();
+
+    if let Some(table) = vrf.get("table") {
+        vrf_config.table = *table.downcast_ref::<u32>()?;
+    }
+
+    Some(vrf_config)
+}
+
+fn macvlan_config_to_dbus(config: &MacvlanConfig) -> HashMap<&str, zvariant::Value> {
+    HashMap::from([
+        ("parent", config.parent.clone().into()),
+        ("mode", (config.mode as u32).into()),
+    ])
+}
+
+fn macvlan_config_from_dbus(conn: &OwnedNestedHash) -> Option<MacvlanConfig> {
+    let macvlan = conn.get(MACVLAN_KEY)?;
+
+    let parent: &str = macvlan.get("parent")?.downcast_ref()?;
+    let mut macvlan_config = MacvlanConfig {
+        parent: parent.to_string(),
+        ..Default::default()
+    };
+
+    if let Some(mode) = macvlan.get("mode") {
+        let mode: u32 = *mode.downcast_ref()?;
+        macvlan_config.mode = mode.try_into().unwrap_or_default();
+    }
+
+    Some(macvlan_config)
+}
+
+fn ipvlan_config_to_dbus(config: &IpvlanConfig) -> HashMap<&str, zvariant::Value> {
+    HashMap::from([
+        ("parent", config.parent.clone().into()),
+        ("mode", (config.mode as u32).into()),
+    ])
+}
+
+fn ipvlan_config_from_dbus(conn: &OwnedNestedHash) -> Option<IpvlanConfig> {
+    let ipvlan = conn.get(IPVLAN_KEY)?;
+
+    let parent: &str = ipvlan.get("parent")?.downcast_ref()?;
+    let mut ipvlan_config = IpvlanConfig {
+        parent: parent.to_string(),
+        ..Default::default()
+    };
+
+    if let Some(mode) = ipvlan.get("mode") {
+        let mode: u32 = *mode.downcast_ref()?;
+        ipvlan_config.mode = mode.try_into().unwrap_or_default();
+    }
+
+    Some(ipvlan_config)
+}
+
+fn macsec_config_to_dbus(config: &MacsecConfig) -> HashMap<&str, zvariant::Value> {
+    let mut macsec: HashMap<&str, zvariant::Value> = HashMap::from([
+        ("parent", config.parent.as_str().into()),
+        ("mode", (config.mode as i32).into()),
+        ("encrypt", config.encrypt.into()),
+    ]);
+
+    if let Some(cak) = &config.cak {
+        macsec.insert("mka-cak", cak.as_str().into());
+    }
+
+    if let Some(ckn) = &config.ckn {
+        macsec.insert("mka-ckn", ckn.as_str().into());
+    }
+
+    macsec
+}
+
+fn macsec_config_from_dbus(conn: &OwnedNestedHash) -> Option<MacsecConfig> {
+    let macsec = conn.get(MACSEC_KEY)?;
+
+    let mut macsec_config = MacsecConfig::default();
+
+    if let Some(parent) = macsec.get("parent") {
+        macsec_config.parent = parent.downcast_ref::<str>()?.to_string();
+    }
+
+    if let Some(mode) = macsec.get("mode") {
+        let mode: i32 = *mode.downcast_ref()?;
+        macsec_config.mode = mode.try_into().unwrap_or_default();
+    }
+
+    if let Some(encrypt) = macsec.get("encrypt") {
+        macsec_config.encrypt = *encrypt.downcast_ref::<bool>()?;
+    }
+
+    if let Some(cak) = macsec.get("mka-cak") {
+        macsec_config.cak = Some(cak.downcast_ref::<str>()?.to_string());
+    }
+
+    if let Some(ckn) = macsec.get("mka-ckn") {
+        macsec_config.ckn = Some(ckn.downcast_ref::<str>()?.to_string());
+    }
+
+    Some(macsec_config)
+}
+
 /// Converts a MatchConfig struct into a HashMap that can be sent over D-Bus.
 ///
 /// * `match_config`: MatchConfig to convert.
@@ -578,6 +1127,52 @@ fn base_connection_from_dbus(conn: &OwnedNestedHash) -> Option<Connection> {
         base_connection.firewall_zone = Some(zone.to_string());
     }
 
+    if let Some(mdns) = connection.get("mdns") {
+        let mdns: i32 = *mdns.downcast_ref()?;
+        base_connection.mdns = mdns.try_into().ok()?;
+    }
+
+    if let Some(llmnr) = connection.get("llmnr") {
+        let llmnr: i32 = *llmnr.downcast_ref()?;
+        base_connection.llmnr = llmnr.try_into().ok()?;
+    }
+
+    if let Some(lldp) = connection.get("lldp") {
+        let lldp: i32 = *lldp.downcast_ref()?;
+        base_connection.lldp = lldp == NM_LLDP_ENABLE_RX;
+    }
+
+    if let Some(permissions) = connection.get("permissions") {
+        let permissions = permissions.downcast_ref::<zbus::zvariant::Array>()?;
+        for permission in permissions.get() {
+            let permission: &str = permission.downcast_ref()?;
+            // only the "user:<name>:[<reserved>]" entries are defined by NetworkManager
+            if let Some(user) = permission
+                .strip_prefix("user:")
+                .and_then(|p| p.split(':').next())
+            {
+                base_connection.permissions.push(user.to_string());
+            }
+        }
+    }
+
+    if let Some(user) = conn.get(USER_KEY) {
+        let mut data = user_data_from_dbus(user);
+        base_connection.only_on_target = data.get(ONLY_ON_TARGET_KEY).is_some_and(|v| v == "true");
+        base_connection.required_for_online = !data
+            .get(REQUIRED_FOR_ONLINE_KEY)
+            .is_some_and(|v| v == "false");
+        base_connection.description = data.remove(DESCRIPTION_KEY);
+        base_connection.tags = data
+            .remove(TAGS_KEY)
+            .map(|tags| tags.split(',').map(str::to_string).collect())
+            .unwrap_or_default();
+        base_connection.metadata = data
+            .into_iter()
+            .filter_map(|(k, v)| Some((k.strip_prefix(METADATA_PREFIX)?.to_string(), v)))
+            .collect();
+    }
+
     if let Some(ethernet_config) = conn.get(ETHERNET_KEY) {
         base_connection.mac_address = mac_address_from_dbus(ethernet_config)?;
     } else if let Some(wireless_config) = conn.get(WIRELESS_KEY) {
@@ -668,6 +1263,15 @@ fn ip_config_from_dbus(conn: &OwnedNestedHash) -> Option<IpConfig> {
             let gateway: &str = gateway.downcast_ref()?;
             ip_config.gateway4 = Some(gateway.parse().unwrap());
         }
+
+        if let Some(route_metric) = ipv4.get("route-metric") {
+            let route_metric: i64 = *route_metric.downcast_ref()?;
+            ip_config.route_metric4 = u32::try_from(route_metric).ok();
+        }
+
+        if let Some(never_default) = ipv4.get("never-default") {
+            ip_config.never_default4 = *never_default.downcast_ref()?;
+        }
     }
 
     if let Some(ipv6) = conn.get("ipv6") {
@@ -692,6 +1296,28 @@ fn ip_config_from_dbus(conn: &OwnedNestedHash) -> Option<IpConfig> {
             let gateway: &str = gateway.downcast_ref()?;
             ip_config.gateway6 = Some(gateway.parse().unwrap());
         }
+
+        if let Some(route_metric) = ipv6.get("route-metric") {
+            let route_metric: i64 = *route_metric.downcast_ref()?;
+            ip_config.route_metric6 = u32::try_from(route_metric).ok();
+        }
+
+        if let Some(never_default) = ipv6.get("never-default") {
+            ip_config.never_default6 = *never_default.downcast_ref()?;
+        }
+
+        if let Some(token) = ipv6.get("token") {
+            let token: &str = token.downcast_ref()?;
+            ip_config.token6 = token.parse().ok();
+        }
+
+        if let Some(mode) = ipv6.get("addr-gen-mode") {
+            ip_config.addr_gen_mode6 = match *mode.downcast_ref::<i32>()? {
+                NM_ADDR_GEN_MODE_EUI64 => Some(Ipv6AddrGenMode::Eui64),
+                NM_ADDR_GEN_MODE_STABLE_PRIVACY => Some(Ipv6AddrGenMode::StablePrivacy),
+                _ => None,
+            };
+        }
     }
 
     Some(ip_config)
@@ -812,17 +1438,40 @@ fn wireless_config_from_dbus(conn: &OwnedNestedHash) -> Option<WirelessConfig> {
             .get("wep-tx-keyidx")
             .and_then(|idx| idx.downcast_ref::<u32>().cloned())
             .unwrap_or_default();
+        let keys = ["wep-key0", "wep-key1", "wep-key2", "wep-key3"]
+            .iter()
+            .filter_map(|k| security.get(*k)?.downcast_ref::<str>())
+            .map(str::to_string)
+            .collect();
         wireless_config.wep_security = Some(WEPSecurity {
             wep_key_type,
             auth_alg,
             wep_key_index,
-            ..Default::default()
+            keys,
         });
+
+        if let Some(psk) = security.get("psk") {
+            wireless_config.password = Some(psk.downcast_ref::<str>()?.to_string());
+        }
+    }
+
+    if let Some(password) = conn.get(IEEE8021X_KEY).and_then(|s| s.get("password")) {
+        wireless_config.password = Some(password.downcast_ref::<str>()?.to_string());
     }
 
     Some(wireless_config)
 }
 
+fn user_data_from_dbus(user: &HashMap<String, OwnedValue>) -> HashMap<String, String> {
+    let Some(dict) = user
+        .get("data")
+        .and_then(|d| d.downcast_ref::<zvariant::Dict>())
+    else {
+        return HashMap::new();
+    };
+    <HashMap<String, String>>::try_from(dict.clone()).unwrap_or_default()
+}
+
 fn bond_config_from_dbus(conn: &OwnedNestedHash) -> Option<BondConfig> {
     let Some(bond) = conn.get(BOND_KEY) else {
         return None;
@@ -845,6 +1494,46 @@ fn bond_config_from_dbus(conn: &OwnedNestedHash) -> Option<BondConfig> {
     Some(bond)
 }
 
+/// Converts the settings of a team connection into a bond configuration.
+///
+/// NetworkManager stores the team settings as a teamd JSON document. Only the runner is
+/// considered to find out the bond mode. The "roundrobin" runner is used when it is missing.
+///
+/// * `conn`: connection settings.
+fn team_config_from_dbus(conn: &OwnedNestedHash) -> Option<BondConfig> {
+    let team = conn.get(TEAM_KEY)?;
+    let config: serde_json::Value = match team.get("config") {
+        Some(config) => {
+            let config: &str = config.downcast_ref()?;
+            serde_json::from_str(config).unwrap_or_default()
+        }
+        None => Default::default(),
+    };
+
+    let runner = &config["runner"];
+    let mode = match runner["name"].as_str().unwrap_or("roundrobin") {
+        "activebackup" => BondMode::ActiveBackup,
+        "loadbalance" => BondMode::BalanceXOR,
+        "broadcast" => BondMode::Broadcast,
+        "lacp" => BondMode::LACP,
+        "roundrobin" | "random" => BondMode::RoundRobin,
+        other => {
+            log::warn!("Unknown team runner '{}', using balance-rr", other);
+            BondMode::RoundRobin
+        }
+    };
+
+    let mut options = HashMap::new();
+    if mode == BondMode::LACP && runner["fast_rate"].as_bool() == Some(true) {
+        options.insert("lacp_rate".to_string(), "fast".to_string());
+    }
+
+    Some(BondConfig {
+        mode,
+        options: BondOptions(options),
+    })
+}
+
 fn vlan_config_to_dbus(cfg: &VlanConfig) -> NestedHash {
     let vlan: HashMap<&str, zvariant::Value> = HashMap::from([
         ("id", cfg.id.into()),
@@ -905,16 +1594,28 @@ fn is_empty_value(value: &zvariant::Value) -> bool {
 #[cfg(test)]
 mod test {
     use super::{
-        connection_from_dbus, connection_to_dbus, merge_dbus_connections, NestedHash,
-        OwnedNestedHash,
+        connection_from_dbus, connection_to_dbus, global_dns_to_dbus, ip_config_to_ipv6_dbus,
+        lldp_neighbor_from_dbus, merge_dbus_connections, merge_secrets_from_dbus,
+        runtime_addresses_from_dbus, runtime_routes_from_dbus, secret_settings_from_dbus,
+        NestedHash, OwnedNestedHash,
     };
     use crate::network::{
         model::*,
-        nm::dbus::{BOND_KEY, ETHERNET_KEY, INFINIBAND_KEY, WIRELESS_KEY, WIRELESS_SECURITY_KEY},
+        nm::dbus::{
+            BOND_KEY, DESCRIPTION_KEY, ETHERNET_KEY, GSM_KEY, IEEE8021X_KEY, INFINIBAND_KEY,
+            IPVLAN_KEY, MACSEC_KEY, MACVLAN_KEY, METADATA_PREFIX, NM_ADDR_GEN_MODE_EUI64,
+            NM_ADDR_GEN_MODE_STABLE_PRIVACY, ONLY_ON_TARGET_KEY, PPPOE_KEY,
+            REQUIRED_FOR_ONLINE_KEY, TAGS_KEY, TEAM_KEY, USER_KEY, VRF_KEY, WIRELESS_KEY,
+            WIRELESS_SECURITY_KEY,
+        },
     };
     use agama_lib::network::types::{BondMode, SSID};
     use cidr::IpInet;
-    use std::{collections::HashMap, net::IpAddr, str::FromStr};
+    use std::{
+        collections::HashMap,
+        net::{IpAddr, Ipv6Addr},
+        str::FromStr,
+    };
     use uuid::Uuid;
     use zbus::zvariant::{self, Array, Dict, OwnedValue, Value};
 
@@ -953,6 +1654,8 @@ mod test {
                 "route-data".to_string(),
                 Value::new(route_v4_data).to_owned(),
             ),
+            ("route-metric".to_string(), Value::new(50_i64).to_owned()),
+            ("never-default".to_string(), Value::new(false).to_owned()),
         ]);
 
         let address_v6_data = vec![HashMap::from([
@@ -985,6 +1688,10 @@ mod test {
                 "route-data".to_string(),
                 Value::new(route_v6_data).to_owned(),
             ),
+            ("route-metric".to_string(), Value::new(-1_i64).to_owned()),
+            ("never-default".to_string(), Value::new(true).to_owned()),
+            ("token".to_string(), Value::new("::10").to_owned()),
+            ("addr-gen-mode".to_string(), Value::new(0_i32).to_owned()),
         ]);
 
         let match_section = HashMap::from([(
@@ -1041,12 +1748,43 @@ mod test {
                 metric: Some(100)
             }])
         );
+        assert_eq!(ip_config.route_metric4, Some(50));
+        assert_eq!(ip_config.route_metric6, None);
+        assert!(!ip_config.never_default4);
+        assert!(ip_config.never_default6);
+        assert_eq!(ip_config.token6, Some(Ipv6Addr::from_str("::10").unwrap()));
+        assert_eq!(ip_config.addr_gen_mode6, Some(Ipv6AddrGenMode::Eui64));
     }
 
     #[test]
-    fn test_connection_from_dbus_missing_connection() {
-        let dbus_conn: HashMap<String, HashMap<String, OwnedValue>> = HashMap::new();
-        let connection = connection_from_dbus(dbus_conn);
+    fn test_addr_gen_mode_to_dbus() {
+        let mut ip_config = IpConfig {
+            addr_gen_mode6: Some(Ipv6AddrGenMode::StablePrivacy),
+            ..Default::default()
+        };
+        let ipv6_dbus = ip_config_to_ipv6_dbus(&ip_config);
+        let addr_gen_mode: i32 = *ipv6_dbus
+            .get("addr-gen-mode")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert_eq!(addr_gen_mode, NM_ADDR_GEN_MODE_STABLE_PRIVACY);
+
+        // the token requires EUI-64
+        ip_config.token6 = Some(Ipv6Addr::from_str("::10").unwrap());
+        let ipv6_dbus = ip_config_to_ipv6_dbus(&ip_config);
+        let addr_gen_mode: i32 = *ipv6_dbus
+            .get("addr-gen-mode")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert_eq!(addr_gen_mode, NM_ADDR_GEN_MODE_EUI64);
+    }
+
+    #[test]
+    fn test_connection_from_dbus_missing_connection() {
+        let dbus_conn: HashMap<String, HashMap<String, OwnedValue>> = HashMap::new();
+        let connection = connection_from_dbus(dbus_conn);
         assert_eq!(connection, None);
     }
 
@@ -1196,6 +1934,377 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_connection_from_dbus_pppoe() {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("dsl").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+        ]);
+        let pppoe_section = HashMap::from([
+            ("username".to_string(), Value::new("user@isp").to_owned()),
+            ("parent".to_string(), Value::new("eth0").to_owned()),
+        ]);
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (PPPOE_KEY.to_string(), pppoe_section),
+        ]);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        let ConnectionConfig::Pppoe(pppoe) = &connection.config else {
+            panic!("Wrong connection type")
+        };
+        assert_eq!(pppoe.username, "user@isp");
+        assert_eq!(pppoe.parent, "eth0");
+        assert_eq!(pppoe.service, None);
+    }
+
+    #[test]
+    fn test_connection_from_dbus_with_secrets() {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("dsl").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+        ]);
+        let pppoe_section = HashMap::from([
+            ("username".to_string(), Value::new("user@isp").to_owned()),
+            ("parent".to_string(), Value::new("eth0").to_owned()),
+        ]);
+        let mut dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (PPPOE_KEY.to_string(), pppoe_section),
+        ]);
+        assert_eq!(secret_settings_from_dbus(&dbus_conn), vec![PPPOE_KEY]);
+
+        let secrets = HashMap::from([(
+            PPPOE_KEY.to_string(),
+            HashMap::from([("password".to_string(), Value::new("secret").to_owned())]),
+        )]);
+        merge_secrets_from_dbus(&mut dbus_conn, secrets);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        let ConnectionConfig::Pppoe(pppoe) = &connection.config else {
+            panic!("Wrong connection type")
+        };
+        assert_eq!(pppoe.password, Some("secret".to_string()));
+        assert!(connection.missing_secrets().is_empty());
+    }
+
+    #[test]
+    fn test_secret_storage() {
+        let mut wireless = build_base_connection();
+        wireless.config = ConnectionConfig::Wireless(Default::default());
+        wireless.secret_storage = SecretStorage::Agent;
+        let wireless_dbus = connection_to_dbus(&wireless, None);
+
+        let security = wireless_dbus.get(WIRELESS_SECURITY_KEY).unwrap();
+        let psk_flags: u32 = *security.get("psk-flags").unwrap().downcast_ref().unwrap();
+        assert_eq!(psk_flags, 1);
+        assert!(wireless_dbus
+            .get("ipv4")
+            .unwrap()
+            .get("psk-flags")
+            .is_none());
+
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("dsl").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+        ]);
+        let pppoe_section = HashMap::from([
+            ("username".to_string(), Value::new("user@isp").to_owned()),
+            ("parent".to_string(), Value::new("eth0").to_owned()),
+            ("password-flags".to_string(), Value::new(2_u32).to_owned()),
+        ]);
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (PPPOE_KEY.to_string(), pppoe_section),
+        ]);
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        assert_eq!(connection.secret_storage, SecretStorage::NotSaved);
+    }
+
+    #[test]
+    fn test_dbus_from_pppoe_connection() {
+        let config = PppoeConfig {
+            username: "user@isp".to_string(),
+            password: Some("secret".to_string()),
+            service: None,
+            parent: "eth0".to_string(),
+        };
+        let mut pppoe = build_base_connection();
+        pppoe.config = ConnectionConfig::Pppoe(config);
+        let pppoe_dbus = connection_to_dbus(&pppoe, None);
+
+        let connection = pppoe_dbus.get("connection").unwrap();
+        let ty: &str = connection.get("type").unwrap().downcast_ref().unwrap();
+        assert_eq!(ty, PPPOE_KEY);
+
+        let pppoe = pppoe_dbus.get(PPPOE_KEY).unwrap();
+        let username: &str = pppoe.get("username").unwrap().downcast_ref().unwrap();
+        assert_eq!(username, "user@isp");
+        let password: &str = pppoe.get("password").unwrap().downcast_ref().unwrap();
+        assert_eq!(password, "secret");
+        let parent: &str = pppoe.get("parent").unwrap().downcast_ref().unwrap();
+        assert_eq!(parent, "eth0");
+        assert!(pppoe.get("service").is_none());
+        assert!(!pppoe_dbus.contains_key(ETHERNET_KEY));
+    }
+
+    #[test]
+    fn test_connection_from_dbus_modem() {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("lte").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+        ]);
+        let gsm_section = HashMap::from([(
+            "apn".to_string(),
+            Value::new("internet.example.com").to_owned(),
+        )]);
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (GSM_KEY.to_string(), gsm_section),
+        ]);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        let ConnectionConfig::Modem(modem) = &connection.config else {
+            panic!("Wrong connection type")
+        };
+        assert_eq!(modem.apn, "internet.example.com");
+        assert_eq!(modem.username, None);
+    }
+
+    #[test]
+    fn test_dbus_from_modem_connection() {
+        let config = ModemConfig {
+            apn: "internet.example.com".to_string(),
+            pin: Some("1234".to_string()),
+            ..Default::default()
+        };
+        let mut modem = build_base_connection();
+        modem.config = ConnectionConfig::Modem(config);
+        let modem_dbus = connection_to_dbus(&modem, None);
+
+        let connection = modem_dbus.get("connection").unwrap();
+        let ty: &str = connection.get("type").unwrap().downcast_ref().unwrap();
+        assert_eq!(ty, GSM_KEY);
+
+        let gsm = modem_dbus.get(GSM_KEY).unwrap();
+        let apn: &str = gsm.get("apn").unwrap().downcast_ref().unwrap();
+        assert_eq!(apn, "internet.example.com");
+        let pin: &str = gsm.get("pin").unwrap().downcast_ref().unwrap();
+        assert_eq!(pin, "1234");
+        assert!(gsm.get("username").is_none());
+        assert!(gsm.get("password").is_none());
+    }
+
+    #[test]
+    fn test_connection_from_dbus_vrf() {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("vrf-mgmt").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+            ("interface-name".to_string(), Value::new("mgmt").to_owned()),
+        ]);
+        let vrf_section = HashMap::from([("table".to_string(), Value::new(10_u32).to_owned())]);
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (VRF_KEY.to_string(), vrf_section),
+        ]);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        let ConnectionConfig::Vrf(vrf) = &connection.config else {
+            panic!("Wrong connection type")
+        };
+        assert_eq!(vrf.table, 10);
+    }
+
+    #[test]
+    fn test_dbus_from_vrf_connection() {
+        let mut vrf = build_base_connection();
+        vrf.config = ConnectionConfig::Vrf(VrfConfig { table: 10 });
+        let vrf_dbus = connection_to_dbus(&vrf, None);
+
+        let connection = vrf_dbus.get("connection").unwrap();
+        let ty: &str = connection.get("type").unwrap().downcast_ref().unwrap();
+        assert_eq!(ty, VRF_KEY);
+        let interface: &str = connection
+            .get("interface-name")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert_eq!(interface, "agama");
+
+        let table: u32 = *vrf_dbus
+            .get(VRF_KEY)
+            .unwrap()
+            .get("table")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert_eq!(table, 10);
+        assert!(!vrf_dbus.contains_key(ETHERNET_KEY));
+
+        let mut eth0 = build_base_connection();
+        eth0.controller = Some(vrf.uuid);
+        let eth0_dbus = connection_to_dbus(&eth0, Some(&vrf));
+        let connection = eth0_dbus.get("connection").unwrap();
+        let slave_type: &str = connection
+            .get("slave-type")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert_eq!(slave_type, VRF_KEY);
+    }
+
+    #[test]
+    fn test_connection_from_dbus_macvlan() {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("macvlan0").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+        ]);
+        let macvlan_section = HashMap::from([
+            ("parent".to_string(), Value::new("eth0").to_owned()),
+            ("mode".to_string(), Value::new(3_u32).to_owned()),
+        ]);
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (MACVLAN_KEY.to_string(), macvlan_section),
+        ]);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        let ConnectionConfig::Macvlan(macvlan) = &connection.config else {
+            panic!("Wrong connection type")
+        };
+        assert_eq!(macvlan.parent, "eth0");
+        assert_eq!(macvlan.mode, MacvlanMode::Private);
+    }
+
+    #[test]
+    fn test_dbus_from_macvlan_connection() {
+        let mut macvlan = build_base_connection();
+        macvlan.config = ConnectionConfig::Macvlan(MacvlanConfig {
+            parent: "eth0".to_string(),
+            mode: MacvlanMode::Passthru,
+        });
+        let macvlan_dbus = connection_to_dbus(&macvlan, None);
+
+        let connection = macvlan_dbus.get("connection").unwrap();
+        let ty: &str = connection.get("type").unwrap().downcast_ref().unwrap();
+        assert_eq!(ty, MACVLAN_KEY);
+
+        let macvlan = macvlan_dbus.get(MACVLAN_KEY).unwrap();
+        let parent: &str = macvlan.get("parent").unwrap().downcast_ref().unwrap();
+        assert_eq!(parent, "eth0");
+        let mode: u32 = *macvlan.get("mode").unwrap().downcast_ref().unwrap();
+        assert_eq!(mode, 4);
+    }
+
+    #[test]
+    fn test_connection_from_dbus_ipvlan() {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("ipvlan0").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+        ]);
+        let ipvlan_section = HashMap::from([
+            ("parent".to_string(), Value::new("eth0").to_owned()),
+            ("mode".to_string(), Value::new(2_u32).to_owned()),
+        ]);
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (IPVLAN_KEY.to_string(), ipvlan_section),
+        ]);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        let ConnectionConfig::Ipvlan(ipvlan) = &connection.config else {
+            panic!("Wrong connection type")
+        };
+        assert_eq!(ipvlan.parent, "eth0");
+        assert_eq!(ipvlan.mode, IpvlanMode::L3);
+    }
+
+    #[test]
+    fn test_dbus_from_ipvlan_connection() {
+        let mut ipvlan = build_base_connection();
+        ipvlan.config = ConnectionConfig::Ipvlan(IpvlanConfig {
+            parent: "eth0".to_string(),
+            mode: IpvlanMode::L3s,
+        });
+        let ipvlan_dbus = connection_to_dbus(&ipvlan, None);
+
+        let connection = ipvlan_dbus.get("connection").unwrap();
+        let ty: &str = connection.get("type").unwrap().downcast_ref().unwrap();
+        assert_eq!(ty, IPVLAN_KEY);
+
+        let ipvlan = ipvlan_dbus.get(IPVLAN_KEY).unwrap();
+        let mode: u32 = *ipvlan.get("mode").unwrap().downcast_ref().unwrap();
+        assert_eq!(mode, 3);
+    }
+
+    #[test]
+    fn test_connection_from_dbus_macsec() {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("macsec0").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+        ]);
+        let macsec_section = HashMap::from([
+            ("parent".to_string(), Value::new("eth0").to_owned()),
+            ("mode".to_string(), Value::new(0_i32).to_owned()),
+            ("encrypt".to_string(), Value::new(false).to_owned()),
+            (
+                "mka-cak".to_string(),
+                Value::new("0123456789abcdef").to_owned(),
+            ),
+            (
+                "mka-ckn".to_string(),
+                Value::new("fedcba9876543210").to_owned(),
+            ),
+            ("mka-cak-flags".to_string(), Value::new(1_u32).to_owned()),
+        ]);
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (MACSEC_KEY.to_string(), macsec_section),
+        ]);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        assert_eq!(connection.secret_storage, SecretStorage::Agent);
+        let ConnectionConfig::Macsec(macsec) = &connection.config else {
+            panic!("Wrong connection type")
+        };
+        assert_eq!(macsec.parent, "eth0");
+        assert_eq!(macsec.mode, MacsecMode::Psk);
+        assert!(!macsec.encrypt);
+        assert_eq!(macsec.cak, Some("0123456789abcdef".to_string()));
+        assert_eq!(macsec.ckn, Some("fedcba9876543210".to_string()));
+    }
+
+    #[test]
+    fn test_dbus_from_macsec_connection() {
+        let mut macsec = build_base_connection();
+        macsec.config = ConnectionConfig::Macsec(MacsecConfig {
+            parent: "eth0".to_string(),
+            mode: MacsecMode::Eap,
+            ..Default::default()
+        });
+        let macsec_dbus = connection_to_dbus(&macsec, None);
+
+        let connection = macsec_dbus.get("connection").unwrap();
+        let ty: &str = connection.get("type").unwrap().downcast_ref().unwrap();
+        assert_eq!(ty, MACSEC_KEY);
+
+        let macsec = macsec_dbus.get(MACSEC_KEY).unwrap();
+        let mode: i32 = *macsec.get("mode").unwrap().downcast_ref().unwrap();
+        assert_eq!(mode, 1);
+        let encrypt: bool = *macsec.get("encrypt").unwrap().downcast_ref().unwrap();
+        assert!(encrypt);
+        assert!(macsec.get("mka-cak").is_none());
+        assert!(macsec.get("mka-cak-flags").is_some());
+    }
+
     #[test]
     fn test_dbus_from_wireless_connection() {
         let config = WirelessConfig {
@@ -1293,6 +2402,32 @@ mod test {
         assert_eq!(wep_key1, "hello");
     }
 
+    #[test]
+    fn test_dbus_from_wireless_eap_connection() {
+        let config = WirelessConfig {
+            security: SecurityProtocol::WPA2Enterprise,
+            password: Some("eap-password".to_string()),
+            ssid: SSID(vec![97, 103, 97, 109, 97]),
+            ..Default::default()
+        };
+        let mut wireless = build_base_connection();
+        wireless.config = ConnectionConfig::Wireless(config);
+        wireless.secret_storage = SecretStorage::NotSaved;
+        let wireless_dbus = connection_to_dbus(&wireless, None);
+
+        let security = wireless_dbus.get(WIRELESS_SECURITY_KEY).unwrap();
+        assert!(security.get("psk").is_none());
+        let ieee8021x = wireless_dbus.get(IEEE8021X_KEY).unwrap();
+        let password: &str = ieee8021x.get("password").unwrap().downcast_ref().unwrap();
+        assert_eq!(password, "eap-password");
+        let flags: u32 = *ieee8021x
+            .get("password-flags")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert_eq!(flags, SecretStorage::NotSaved as u32);
+    }
+
     #[test]
     fn test_dbus_from_ethernet_connection() {
         let ethernet = build_base_connection();
@@ -1300,6 +2435,369 @@ mod test {
         check_dbus_base_connection(&ethernet_dbus);
     }
 
+    #[test]
+    fn test_connection_from_dbus_resolve_modes() {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("eth0").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+            ("mdns".to_string(), Value::new(2_i32).to_owned()),
+            ("llmnr".to_string(), Value::new(1_i32).to_owned()),
+        ]);
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (ETHERNET_KEY.to_string(), HashMap::new()),
+        ]);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        assert_eq!(connection.mdns, ResolveMode::Yes);
+        assert_eq!(connection.llmnr, ResolveMode::Resolve);
+    }
+
+    #[test]
+    fn test_dbus_from_resolve_modes() {
+        let mut ethernet = build_base_connection();
+        ethernet.mdns = ResolveMode::No;
+        let ethernet_dbus = connection_to_dbus(&ethernet, None);
+
+        let connection = ethernet_dbus.get("connection").unwrap();
+        let mdns: i32 = *connection.get("mdns").unwrap().downcast_ref().unwrap();
+        assert_eq!(mdns, 0);
+        let llmnr: i32 = *connection.get("llmnr").unwrap().downcast_ref().unwrap();
+        assert_eq!(llmnr, -1);
+    }
+
+    #[test]
+    fn test_connection_from_dbus_only_on_target() {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("eth0").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+        ]);
+        let data = HashMap::from([(ONLY_ON_TARGET_KEY.to_string(), "true".to_string())]);
+        let user_section = HashMap::from([("data".to_string(), Value::new(data).to_owned())]);
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (USER_KEY.to_string(), user_section),
+            (ETHERNET_KEY.to_string(), HashMap::new()),
+        ]);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        assert!(connection.only_on_target);
+    }
+
+    #[test]
+    fn test_dbus_from_only_on_target() {
+        let mut ethernet = build_base_connection();
+        ethernet.only_on_target = true;
+        let ethernet_dbus = connection_to_dbus(&ethernet, None);
+
+        let connection = ethernet_dbus.get("connection").unwrap();
+        let autoconnect: bool = *connection
+            .get("autoconnect")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert!(!autoconnect);
+
+        let user = ethernet_dbus.get(USER_KEY).unwrap();
+        let data: &zvariant::Dict = user.get("data").unwrap().downcast_ref().unwrap();
+        let data = <HashMap<String, String>>::try_from(data.clone()).unwrap();
+        assert_eq!(data.get(ONLY_ON_TARGET_KEY), Some(&"true".to_string()));
+    }
+
+    #[test]
+    fn test_required_for_online_dbus() {
+        let mut ethernet = build_base_connection();
+        ethernet.required_for_online = false;
+        let ethernet_dbus = connection_to_dbus(&ethernet, None);
+        let user = ethernet_dbus.get(USER_KEY).unwrap();
+        let data: &zvariant::Dict = user.get("data").unwrap().downcast_ref().unwrap();
+        let data = <HashMap<String, String>>::try_from(data.clone()).unwrap();
+        assert_eq!(
+            data.get(REQUIRED_FOR_ONLINE_KEY),
+            Some(&"false".to_string())
+        );
+
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("eth0").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+        ]);
+        let user_section = HashMap::from([("data".to_string(), Value::new(data).to_owned())]);
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section.clone()),
+            (USER_KEY.to_string(), user_section),
+            (ETHERNET_KEY.to_string(), HashMap::new()),
+        ]);
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        assert!(!connection.required_for_online);
+
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (ETHERNET_KEY.to_string(), HashMap::new()),
+        ]);
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        assert!(connection.required_for_online);
+    }
+
+    #[test]
+    fn test_lldp_neighbor_from_dbus() {
+        let neighbor = HashMap::from([
+            (
+                "chassis-id".to_string(),
+                Value::new("00:11:22:33:44:55").to_owned(),
+            ),
+            ("port-id".to_string(), Value::new("Gi1/0/12").to_owned()),
+            ("system-name".to_string(), Value::new("switch01").to_owned()),
+            (
+                "ieee-802-1-pvid".to_string(),
+                Value::new(100_u32).to_owned(),
+            ),
+        ]);
+        let neighbor = lldp_neighbor_from_dbus(&neighbor).unwrap();
+        assert_eq!(
+            neighbor,
+            LldpNeighbor {
+                chassis_id: "00:11:22:33:44:55".to_string(),
+                system_name: Some("switch01".to_string()),
+                port_id: "Gi1/0/12".to_string(),
+                port_description: None,
+                vlan_id: Some(100),
+            }
+        );
+
+        let incomplete = HashMap::from([(
+            "chassis-id".to_string(),
+            Value::new("00:11:22:33:44:55").to_owned(),
+        )]);
+        assert!(lldp_neighbor_from_dbus(&incomplete).is_none());
+    }
+
+    #[test]
+    fn test_runtime_addresses_from_dbus() {
+        let data = vec![
+            HashMap::from([
+                ("address".to_string(), Value::new("192.168.1.10").to_owned()),
+                ("prefix".to_string(), Value::new(24_u32).to_owned()),
+            ]),
+            HashMap::from([("address".to_string(), Value::new("192.168.1.1").to_owned())]),
+            HashMap::from([("address".to_string(), Value::new("invalid").to_owned())]),
+        ];
+        let addresses = runtime_addresses_from_dbus(&data);
+        assert_eq!(
+            addresses,
+            vec![
+                "192.168.1.10/24".parse::<IpInet>().unwrap(),
+                "192.168.1.1/32".parse::<IpInet>().unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_runtime_routes_from_dbus() {
+        let data = vec![
+            HashMap::from([
+                ("dest".to_string(), Value::new("0.0.0.0").to_owned()),
+                ("prefix".to_string(), Value::new(0_u32).to_owned()),
+                ("next-hop".to_string(), Value::new("192.168.1.1").to_owned()),
+            ]),
+            HashMap::from([
+                ("dest".to_string(), Value::new("192.168.1.0").to_owned()),
+                ("prefix".to_string(), Value::new(24_u32).to_owned()),
+            ]),
+            HashMap::from([
+                ("dest".to_string(), Value::new("10.0.0.0").to_owned()),
+                ("prefix".to_string(), Value::new(8_u32).to_owned()),
+                (
+                    "next-hop".to_string(),
+                    Value::new("192.168.1.254").to_owned(),
+                ),
+                ("metric".to_string(), Value::new(100_u32).to_owned()),
+            ]),
+        ];
+        let routes = runtime_routes_from_dbus(&data);
+        assert_eq!(
+            routes,
+            vec![IpRoute {
+                destination: "10.0.0.0/8".parse().unwrap(),
+                next_hop: Some("192.168.1.254".parse().unwrap()),
+                metric: Some(100)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_connection_from_dbus_team() {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("team0").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+        ]);
+        let config = r#"{"runner": {"name": "lacp", "fast_rate": true}}"#;
+        let team_section = HashMap::from([("config".to_string(), Value::new(config).to_owned())]);
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (TEAM_KEY.to_string(), team_section),
+        ]);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        assert!(connection.migrated_from_team);
+        let ConnectionConfig::Bond(bond) = connection.config else {
+            panic!("Unexpected connection config: {:?}", connection.config);
+        };
+        assert_eq!(bond.mode, BondMode::LACP);
+        assert_eq!(
+            bond.options.0.get("lacp_rate").map(String::as_str),
+            Some("fast")
+        );
+    }
+
+    #[test]
+    fn test_connection_from_dbus_team_without_config() {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("team0").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+        ]);
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (TEAM_KEY.to_string(), HashMap::new()),
+        ]);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        let ConnectionConfig::Bond(bond) = connection.config else {
+            panic!("Unexpected connection config: {:?}", connection.config);
+        };
+        assert_eq!(bond.mode, BondMode::RoundRobin);
+    }
+
+    #[test]
+    fn test_global_dns_to_dbus() {
+        let servers: Vec<IpAddr> = vec!["192.168.1.1".parse().unwrap()];
+        let searches = vec!["example.net".to_string()];
+        let config = global_dns_to_dbus(&servers, &searches);
+
+        let domains = config.get("domains").unwrap().clone();
+        let domains = <HashMap<String, HashMap<String, Vec<String>>>>::try_from(domains).unwrap();
+        assert_eq!(
+            domains["*"].get("servers"),
+            Some(&vec!["192.168.1.1".to_string()])
+        );
+
+        let searches = config.get("searches").unwrap().clone();
+        assert_eq!(
+            <Vec<String>>::try_from(searches).unwrap(),
+            vec!["example.net".to_string()]
+        );
+
+        assert!(global_dns_to_dbus(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_connection_from_dbus_description_and_metadata() {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("eth0").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+        ]);
+        let data = HashMap::from([
+            (
+                DESCRIPTION_KEY.to_string(),
+                "Production network".to_string(),
+            ),
+            (format!("{}ticket", METADATA_PREFIX), "ABC-123".to_string()),
+            (TAGS_KEY.to_string(), "storage-net,mgmt".to_string()),
+            ("org.example.other".to_string(), "ignored".to_string()),
+        ]);
+        let user_section = HashMap::from([("data".to_string(), Value::new(data).to_owned())]);
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (USER_KEY.to_string(), user_section),
+            (ETHERNET_KEY.to_string(), HashMap::new()),
+        ]);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        assert_eq!(
+            connection.description,
+            Some("Production network".to_string())
+        );
+        assert_eq!(
+            connection.metadata,
+            HashMap::from([("ticket".to_string(), "ABC-123".to_string())])
+        );
+        assert_eq!(
+            connection.tags,
+            vec!["storage-net".to_string(), "mgmt".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_dbus_from_description_and_metadata() {
+        let mut ethernet = build_base_connection();
+        ethernet.description = Some("Production network".to_string());
+        ethernet.metadata = HashMap::from([("ticket".to_string(), "ABC-123".to_string())]);
+        ethernet.tags = vec!["storage-net".to_string(), "mgmt".to_string()];
+        let ethernet_dbus = connection_to_dbus(&ethernet, None);
+
+        let user = ethernet_dbus.get(USER_KEY).unwrap();
+        let data: &zvariant::Dict = user.get("data").unwrap().downcast_ref().unwrap();
+        let data = <HashMap<String, String>>::try_from(data.clone()).unwrap();
+        assert_eq!(
+            data.get(DESCRIPTION_KEY),
+            Some(&"Production network".to_string())
+        );
+        assert_eq!(
+            data.get(&format!("{}ticket", METADATA_PREFIX)),
+            Some(&"ABC-123".to_string())
+        );
+        assert_eq!(data.get(TAGS_KEY), Some(&"storage-net,mgmt".to_string()));
+    }
+
+    #[test]
+    fn test_connection_from_dbus_permissions() {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("eth0").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+            (
+                "permissions".to_string(),
+                Value::new(vec!["user:alice:", "user:bob"]).to_owned(),
+            ),
+        ]);
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (ETHERNET_KEY.to_string(), HashMap::new()),
+        ]);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        assert_eq!(
+            connection.permissions,
+            vec!["alice".to_string(), "bob".to_string()]
+        );
+
+        let dbus_conn = connection_to_dbus(&connection, None);
+        let permissions = dbus_conn["connection"]["permissions"].clone();
+        assert_eq!(
+            <Vec<String>>::try_from(permissions).unwrap(),
+            vec!["user:alice:".to_string(), "user:bob:".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_dbus_from_permissions() {
+        let mut ethernet = build_base_connection();
+        ethernet.permissions = vec!["alice".to_string()];
+        let ethernet_dbus = connection_to_dbus(&ethernet, None);
+
+        let connection = ethernet_dbus.get("connection").unwrap();
+        let permissions = connection.get("permissions").unwrap().clone();
+        assert_eq!(
+            <Vec<String>>::try_from(permissions).unwrap(),
+            vec!["user:alice:".to_string()]
+        );
+    }
+
     #[test]
     fn test_merge_dbus_connections() {
         let mut original = OwnedNestedHash::new();
@@ -1446,6 +2944,9 @@ mod test {
                 next_hop: Some(IpAddr::from_str("2001:db8::1").unwrap()),
                 metric: Some(100),
             }]),
+            route_metric4: Some(200),
+            never_default6: true,
+            token6: Some(Ipv6Addr::from_str("::10").unwrap()),
             ..Default::default()
         };
         let mac_address = MacAddress::from_str("FD:CB:A9:87:65:43").unwrap();
@@ -1473,6 +2974,18 @@ mod test {
         let ipv4_dbus = conn_dbus.get("ipv4").unwrap();
         let gateway4: &str = ipv4_dbus.get("gateway").unwrap().downcast_ref().unwrap();
         assert_eq!(gateway4, "192.168.0.1");
+        let route_metric4: i64 = *ipv4_dbus
+            .get("route-metric")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert_eq!(route_metric4, 200);
+        let never_default4: bool = *ipv4_dbus
+            .get("never-default")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert!(!never_default4);
         let routes4_array: Array = ipv4_dbus
             .get("route-data")
             .unwrap()
@@ -1496,6 +3009,26 @@ mod test {
         let ipv6_dbus = conn_dbus.get("ipv6").unwrap();
         let gateway6: &str = ipv6_dbus.get("gateway").unwrap().downcast_ref().unwrap();
         assert_eq!(gateway6, "::ffff:192.168.0.1");
+        let route_metric6: i64 = *ipv6_dbus
+            .get("route-metric")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert_eq!(route_metric6, -1);
+        let never_default6: bool = *ipv6_dbus
+            .get("never-default")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert!(never_default6);
+        let token: &str = ipv6_dbus.get("token").unwrap().downcast_ref().unwrap();
+        assert_eq!(token, "::10");
+        let addr_gen_mode: i32 = *ipv6_dbus
+            .get("addr-gen-mode")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert_eq!(addr_gen_mode, NM_ADDR_GEN_MODE_EUI64);
         let routes6_array: Array = ipv6_dbus
             .get("route-data")
             .unwrap()
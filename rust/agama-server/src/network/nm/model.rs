@@ -7,7 +7,10 @@
 /// Using the newtype pattern around an String is enough. For proper support, we might replace this
 /// struct with an enum.
 use crate::network::{
-    model::{Ipv4Method, Ipv6Method, SecurityProtocol, WirelessMode},
+    model::{
+        Ipv4Method, Ipv6Method, ModemCapabilities, ModemTechnology, SecurityProtocol, WirelessBand,
+        WirelessCapabilities, WirelessCipher, WirelessMode,
+    },
     nm::error::NmError,
 };
 use agama_lib::network::types::DeviceType;
@@ -84,12 +87,124 @@ impl TryFrom<NmDeviceType> for DeviceType {
             NmDeviceType(1) => Ok(DeviceType::Ethernet),
             NmDeviceType(2) => Ok(DeviceType::Wireless),
             NmDeviceType(3) => Ok(DeviceType::Dummy),
+            NmDeviceType(8) => Ok(DeviceType::Modem),
             NmDeviceType(10) => Ok(DeviceType::Bond),
+            NmDeviceType(18) => Ok(DeviceType::Macvlan),
+            NmDeviceType(21) => Ok(DeviceType::Macsec),
+            NmDeviceType(31) => Ok(DeviceType::Vrf),
+            NmDeviceType(34) => Ok(DeviceType::Ipvlan),
             NmDeviceType(_) => Err(NmError::UnsupportedDeviceType(value.into())),
         }
     }
 }
 
+/// Wireless device capabilities
+///
+/// Bitmask of NMDeviceWifiCapabilities flags.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NmWirelessCapabilities(pub u32);
+
+impl NmWirelessCapabilities {
+    const CIPHER_WEP40: u32 = 0x1;
+    const CIPHER_WEP104: u32 = 0x2;
+    const CIPHER_TKIP: u32 = 0x4;
+    const CIPHER_CCMP: u32 = 0x8;
+    const AP: u32 = 0x40;
+    const ADHOC: u32 = 0x80;
+    const FREQ_VALID: u32 = 0x100;
+    const FREQ_2GHZ: u32 = 0x200;
+    const FREQ_5GHZ: u32 = 0x400;
+    const MESH: u32 = 0x1000;
+
+    fn has(&self, flag: u32) -> bool {
+        self.0 & flag != 0
+    }
+}
+
+impl From<NmWirelessCapabilities> for WirelessCapabilities {
+    fn from(value: NmWirelessCapabilities) -> Self {
+        let mut modes = vec![WirelessMode::Infra];
+        let optional_modes = [
+            (NmWirelessCapabilities::ADHOC, WirelessMode::AdHoc),
+            (NmWirelessCapabilities::AP, WirelessMode::AP),
+            (NmWirelessCapabilities::MESH, WirelessMode::Mesh),
+        ];
+        modes.extend(
+            optional_modes
+                .into_iter()
+                .filter(|(flag, _)| value.has(*flag))
+                .map(|(_, mode)| mode),
+        );
+
+        // the frequency flags are meaningful only if FREQ_VALID is set
+        let mut bands = vec![];
+        if value.has(NmWirelessCapabilities::FREQ_VALID) {
+            if value.has(NmWirelessCapabilities::FREQ_2GHZ) {
+                bands.push(WirelessBand::BG);
+            }
+            if value.has(NmWirelessCapabilities::FREQ_5GHZ) {
+                bands.push(WirelessBand::A);
+            }
+        }
+
+        let ciphers = [
+            (NmWirelessCapabilities::CIPHER_WEP40, WirelessCipher::Wep40),
+            (
+                NmWirelessCapabilities::CIPHER_WEP104,
+                WirelessCipher::Wep104,
+            ),
+            (NmWirelessCapabilities::CIPHER_TKIP, WirelessCipher::Tkip),
+            (NmWirelessCapabilities::CIPHER_CCMP, WirelessCipher::Ccmp),
+        ]
+        .into_iter()
+        .filter(|(flag, _)| value.has(*flag))
+        .map(|(_, cipher)| cipher)
+        .collect();
+
+        WirelessCapabilities {
+            modes,
+            bands,
+            ciphers,
+        }
+    }
+}
+
+/// Modem device capabilities
+///
+/// Bitmask of NMDeviceModemCapabilities flags.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NmModemCapabilities(pub u32);
+
+impl NmModemCapabilities {
+    const POTS: u32 = 0x1;
+    const CDMA_EVDO: u32 = 0x2;
+    const GSM_UMTS: u32 = 0x4;
+    const LTE: u32 = 0x8;
+    const FIVE_G_NR: u32 = 0x40;
+
+    fn has(&self, flag: u32) -> bool {
+        self.0 & flag != 0
+    }
+}
+
+impl From<NmModemCapabilities> for ModemCapabilities {
+    fn from(value: NmModemCapabilities) -> Self {
+        let technologies = [
+            (NmModemCapabilities::POTS, ModemTechnology::Pots),
+            (NmModemCapabilities::CDMA_EVDO, ModemTechnology::CdmaEvdo),
+            (NmModemCapabilities::GSM_UMTS, ModemTechnology::GsmUmts),
+            (NmModemCapabilities::LTE, ModemTechnology::Lte),
+            (NmModemCapabilities::FIVE_G_NR, ModemTechnology::FiveGNr),
+        ]
+        .into_iter()
+        .filter(|(flag, _)| value.has(*flag))
+        .map(|(_, technology)| technology)
+        .collect();
+
+        ModemCapabilities { technologies }
+    }
+}
+
 /// Key management
 ///
 /// Using the newtype pattern around an String is enough. For proper support, we might replace this
@@ -188,3 +303,34 @@ pub struct NmIp4Config {
     pub gateway: Option<String>,
     pub method: NmMethod,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wireless_capabilities_from_nm() {
+        // WEP40 | CCMP | AP | FREQ_VALID | FREQ_2GHZ
+        let caps: WirelessCapabilities = NmWirelessCapabilities(0x1 | 0x8 | 0x40 | 0x300).into();
+        assert_eq!(caps.modes, vec![WirelessMode::Infra, WirelessMode::AP]);
+        assert_eq!(caps.bands, vec![WirelessBand::BG]);
+        assert_eq!(
+            caps.ciphers,
+            vec![WirelessCipher::Wep40, WirelessCipher::Ccmp]
+        );
+
+        // the frequency flags are ignored if FREQ_VALID is not set
+        let caps: WirelessCapabilities = NmWirelessCapabilities(0x400).into();
+        assert!(caps.bands.is_empty());
+    }
+
+    #[test]
+    fn test_modem_capabilities_from_nm() {
+        // GSM_UMTS | LTE
+        let caps: ModemCapabilities = NmModemCapabilities(0x4 | 0x8).into();
+        assert_eq!(
+            caps.technologies,
+            vec![ModemTechnology::GsmUmts, ModemTechnology::Lte]
+        );
+    }
+}
@@ -252,6 +252,22 @@ trait NetworkManager {
     fn wwan_hardware_enabled(&self) -> zbus::Result<bool>;
 }
 
+/// # DBus interface proxy for: `org.freedesktop.NetworkManager.Connection.Active`
+#[dbus_proxy(
+    interface = "org.freedesktop.NetworkManager.Connection.Active",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager/ActiveConnection/1"
+)]
+trait ActiveConnection {
+    /// Id property
+    #[dbus_proxy(property)]
+    fn id(&self) -> zbus::Result<String>;
+
+    /// State property
+    #[dbus_proxy(property)]
+    fn state(&self) -> zbus::Result<u32>;
+}
+
 /// # DBus interface proxies for: `org.freedesktop.NetworkManager.Device`
 ///
 /// This code was generated by `zbus-xmlgen` `3.1.0` from DBus introspection data.
@@ -582,3 +598,93 @@ trait Connection {
     #[dbus_proxy(property)]
     fn unsaved(&self) -> zbus::Result<bool>;
 }
+
+/// # DBus interface proxies for: `org.freedesktop.NetworkManager.Device.Wireless`
+///
+/// Only the properties used by Agama are included.
+#[dbus_proxy(
+    interface = "org.freedesktop.NetworkManager.Device.Wireless",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager/Devices/1"
+)]
+trait WirelessDevice {
+    /// WirelessCapabilities property
+    #[dbus_proxy(property)]
+    fn wireless_capabilities(&self) -> zbus::Result<u32>;
+}
+
+/// # DBus interface proxies for: `org.freedesktop.NetworkManager.Device.Modem`
+///
+/// Only the properties used by Agama are included.
+#[dbus_proxy(
+    interface = "org.freedesktop.NetworkManager.Device.Modem",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager/Devices/1"
+)]
+trait ModemDevice {
+    /// CurrentCapabilities property
+    #[dbus_proxy(property)]
+    fn current_capabilities(&self) -> zbus::Result<u32>;
+}
+
+/// # DBus interface proxies for: `org.freedesktop.NetworkManager.IP4Config`
+///
+/// Only the properties used by Agama are included.
+#[dbus_proxy(
+    interface = "org.freedesktop.NetworkManager.IP4Config",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager/IP4Config/1"
+)]
+trait IP4Config {
+    /// AddressData property
+    #[dbus_proxy(property)]
+    fn address_data(
+        &self,
+    ) -> zbus::Result<Vec<std::collections::HashMap<String, zbus::zvariant::OwnedValue>>>;
+
+    /// Gateway property
+    #[dbus_proxy(property)]
+    fn gateway(&self) -> zbus::Result<String>;
+
+    /// NameserverData property
+    #[dbus_proxy(property)]
+    fn nameserver_data(
+        &self,
+    ) -> zbus::Result<Vec<std::collections::HashMap<String, zbus::zvariant::OwnedValue>>>;
+
+    /// RouteData property
+    #[dbus_proxy(property)]
+    fn route_data(
+        &self,
+    ) -> zbus::Result<Vec<std::collections::HashMap<String, zbus::zvariant::OwnedValue>>>;
+}
+
+/// # DBus interface proxies for: `org.freedesktop.NetworkManager.IP6Config`
+///
+/// Only the properties used by Agama are included.
+#[dbus_proxy(
+    interface = "org.freedesktop.NetworkManager.IP6Config",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager/IP6Config/1"
+)]
+trait IP6Config {
+    /// AddressData property
+    #[dbus_proxy(property)]
+    fn address_data(
+        &self,
+    ) -> zbus::Result<Vec<std::collections::HashMap<String, zbus::zvariant::OwnedValue>>>;
+
+    /// Gateway property
+    #[dbus_proxy(property)]
+    fn gateway(&self) -> zbus::Result<String>;
+
+    /// Nameservers property
+    #[dbus_proxy(property)]
+    fn nameservers(&self) -> zbus::Result<Vec<Vec<u8>>>;
+
+    /// RouteData property
+    #[dbus_proxy(property)]
+    fn route_data(
+        &self,
+    ) -> zbus::Result<Vec<std::collections::HashMap<String, zbus::zvariant::OwnedValue>>>;
+}
@@ -0,0 +1,89 @@
+//! Watcher for the changes made directly in NetworkManager (e.g., using nmcli).
+use crate::network::{Action, TrackedAction};
+use agama_lib::error::ServiceError;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_stream::StreamExt;
+use zbus::{MatchRule, MessageStream, MessageType};
+
+const SETTINGS_PATH: &str = "/org/freedesktop/NetworkManager/Settings";
+const SETTINGS_IFACE: &str = "org.freedesktop.NetworkManager.Settings";
+const CONNECTION_IFACE: &str = "org.freedesktop.NetworkManager.Settings.Connection";
+
+/// Listens for NetworkManager signals about connections changes and asks the
+/// [NetworkSystem](crate::network::NetworkSystem) to synchronize its state.
+pub struct NetworkManagerWatcher {
+    connection: zbus::Connection,
+}
+
+impl NetworkManagerWatcher {
+    /// Creates a watcher using the given D-Bus connection.
+    ///
+    /// * `connection`: connection to the bus where NetworkManager lives.
+    pub fn new(connection: zbus::Connection) -> Self {
+        Self { connection }
+    }
+
+    /// Creates a watcher on the system bus.
+    pub async fn from_system() -> Result<Self, ServiceError> {
+        let connection = zbus::Connection::system().await?;
+        Ok(Self::new(connection))
+    }
+
+    /// Listens for changes, sending an [Action::SyncConnections] for each of them.
+    ///
+    /// * `actions`: sending-half of a channel to send actions.
+    pub async fn run(self, actions: UnboundedSender<TrackedAction>) -> Result<(), ServiceError> {
+        let rule = MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .path_namespace(SETTINGS_PATH)?
+            .build();
+        let mut stream = MessageStream::for_match_rule(rule, &self.connection, None).await?;
+
+        while let Some(message) = stream.next().await {
+            let Ok(message) = message else {
+                continue;
+            };
+            let header = message.header()?;
+            let (Some(interface), Some(member)) = (header.interface()?, header.member()?) else {
+                continue;
+            };
+            if is_connection_change(interface.as_str(), member.as_str()) {
+                log::info!("NetworkManager connections changed ({})", member);
+                if actions.send(Action::SyncConnections.into()).is_err() {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Determines whether a signal reports a change in the connections.
+///
+/// * `interface`: D-Bus interface of the signal.
+/// * `member`: signal name.
+fn is_connection_change(interface: &str, member: &str) -> bool {
+    matches!(
+        (interface, member),
+        (SETTINGS_IFACE, "NewConnection")
+            | (SETTINGS_IFACE, "ConnectionRemoved")
+            | (CONNECTION_IFACE, "Updated")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_connection_change() {
+        assert!(is_connection_change(SETTINGS_IFACE, "NewConnection"));
+        assert!(is_connection_change(SETTINGS_IFACE, "ConnectionRemoved"));
+        assert!(is_connection_change(CONNECTION_IFACE, "Updated"));
+        assert!(!is_connection_change(CONNECTION_IFACE, "Removed"));
+        assert!(!is_connection_change(
+            "org.freedesktop.DBus.Properties",
+            "PropertiesChanged"
+        ));
+    }
+}
@@ -0,0 +1,103 @@
+//! Support for the wireless regulatory domain.
+//!
+//! The regulatory domain determines which wireless channels (and transmission power) can be used.
+//! It is set at runtime through `iw reg set` and persisted as a `cfg80211` module option, so it is
+//! kept after rebooting (including the installed system).
+use anyhow::{anyhow, Context};
+use std::{fs, path::Path, process::Command};
+
+/// Configuration file to persist the regulatory domain.
+pub const REGDOM_CONF_PATH: &str = "/etc/modprobe.d/90-agama-regdom.conf";
+/// Regulatory domain that applies when no country is set.
+const WORLD_REGDOM: &str = "00";
+
+/// Determines whether the given regulatory domain is valid (an ISO 3166-1 alpha-2 code or "00").
+///
+/// * `code`: regulatory domain code.
+pub fn is_valid(code: &str) -> bool {
+    code == WORLD_REGDOM || (code.len() == 2 && code.chars().all(|c| c.is_ascii_uppercase()))
+}
+
+/// Returns the current regulatory domain or `None` if it is not set.
+pub fn current() -> anyhow::Result<Option<String>> {
+    let output = Command::new("iw")
+        .args(["reg", "get"])
+        .output()
+        .context("Could not run 'iw reg get'")?;
+    Ok(regdom_from_iw(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Sets the regulatory domain and persists the setting.
+///
+/// * `code`: regulatory domain code. `None` means resetting it to the default one.
+pub fn write(code: Option<&str>) -> anyhow::Result<()> {
+    let code = code.unwrap_or(WORLD_REGDOM);
+    let status = Command::new("iw")
+        .args(["reg", "set", code])
+        .status()
+        .context("Could not run 'iw reg set'")?;
+    if !status.success() {
+        return Err(anyhow!("Could not set the regulatory domain to '{}'", code));
+    }
+
+    if code == WORLD_REGDOM {
+        if Path::new(REGDOM_CONF_PATH).exists() {
+            fs::remove_file(REGDOM_CONF_PATH)?;
+        }
+    } else {
+        fs::write(REGDOM_CONF_PATH, regdom_to_conf(code))?;
+    }
+    Ok(())
+}
+
+/// Extracts the global regulatory domain from the `iw reg get` output.
+///
+/// * `output`: command output.
+fn regdom_from_iw(output: &str) -> Option<String> {
+    let mut lines = output.lines().map(str::trim);
+    lines.find(|l| *l == "global")?;
+    let country = lines.next()?.strip_prefix("country ")?;
+    let (code, _) = country.split_once(':')?;
+    if code == WORLD_REGDOM {
+        None
+    } else {
+        Some(code.to_string())
+    }
+}
+
+/// Renders the module configuration to persist the regulatory domain.
+///
+/// * `code`: regulatory domain code.
+fn regdom_to_conf(code: &str) -> String {
+    format!("options cfg80211 ieee80211_regdom={}\n", code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid() {
+        assert!(is_valid("DE"));
+        assert!(is_valid("00"));
+        assert!(!is_valid("de"));
+        assert!(!is_valid("DEU"));
+    }
+
+    #[test]
+    fn test_regdom_from_iw() {
+        let output = "global\ncountry ES: DFS-ETSI\n\t(2400 - 2483 @ 40), (N/A, 20)\n";
+        assert_eq!(regdom_from_iw(output), Some("ES".to_string()));
+
+        let output = "global\ncountry 00: DFS-UNSET\n\t(2402 - 2472 @ 40), (6, 20)\n";
+        assert_eq!(regdom_from_iw(output), None);
+    }
+
+    #[test]
+    fn test_regdom_to_conf() {
+        assert_eq!(
+            regdom_to_conf("CZ"),
+            "options cfg80211 ieee80211_regdom=CZ\n"
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! Detection of the network sessions (e.g., SSH or the web UI) established with the system.
+//!
+//! Removing the connection that carries a session would cut it, so those operations must be
+//! confirmed. The sessions are found by reading the TCP sockets from `/proc/net`: only the
+//! established sockets whose local port is also a listening one (e.g., 22 for SSH or the web
+//! server port) are inbound sessions. Their local (non-loopback) addresses are taken.
+use std::{
+    collections::HashSet,
+    fs,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+const TCP_SOCKETS_PATHS: [&str; 2] = ["/proc/net/tcp", "/proc/net/tcp6"];
+/// Socket state for established connections.
+const ESTABLISHED: &str = "01";
+/// Socket state for listening sockets.
+const LISTEN: &str = "0A";
+
+/// TCP socket as read from `/proc/net/tcp*`.
+struct Socket {
+    address: IpAddr,
+    port: u16,
+    state: String,
+}
+
+/// Returns the local addresses of the inbound TCP sessions.
+pub fn local_addresses() -> HashSet<IpAddr> {
+    let contents: Vec<String> = TCP_SOCKETS_PATHS
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .collect();
+    addresses_from_sockets(&contents.join("\n"))
+}
+
+/// Extracts the local addresses of the inbound sessions from the `/proc/net/tcp*` files.
+///
+/// * `contents`: contents of the files.
+fn addresses_from_sockets(contents: &str) -> HashSet<IpAddr> {
+    let sockets: Vec<Socket> = contents.lines().filter_map(parse_socket).collect();
+    let listening: HashSet<u16> = sockets
+        .iter()
+        .filter(|s| s.state == LISTEN)
+        .map(|s| s.port)
+        .collect();
+    sockets
+        .iter()
+        .filter(|s| s.state == ESTABLISHED && listening.contains(&s.port))
+        .map(|s| s.address)
+        .filter(|address| !address.is_loopback())
+        .collect()
+}
+
+/// Parses a line from a `/proc/net/tcp*` file.
+///
+/// It returns `None` for the header and the malformed lines.
+///
+/// * `line`: line to parse.
+fn parse_socket(line: &str) -> Option<Socket> {
+    let mut fields = line.split_whitespace().skip(1);
+    let local = fields.next()?;
+    let state = fields.nth(1)?;
+    let (address, port) = local.split_once(':')?;
+    Some(Socket {
+        address: parse_address(address)?,
+        port: u16::from_str_radix(port, 16).ok()?,
+        state: state.to_string(),
+    })
+}
+
+/// Parses an address as it is written in `/proc/net/tcp*`.
+///
+/// The address is written as a sequence of 32-bit words in hexadecimal, using the host byte
+/// order. IPv4-mapped IPv6 addresses are converted to IPv4.
+///
+/// * `hex`: address in hexadecimal.
+fn parse_address(hex: &str) -> Option<IpAddr> {
+    let mut bytes = vec![];
+    for index in (0..hex.len()).step_by(8) {
+        let word = u32::from_str_radix(hex.get(index..index + 8)?, 16).ok()?;
+        bytes.extend(word.to_ne_bytes());
+    }
+    match bytes.len() {
+        4 => Some(IpAddr::V4(Ipv4Addr::new(
+            bytes[0], bytes[1], bytes[2], bytes[3],
+        ))),
+        16 => {
+            let bytes: [u8; 16] = bytes.try_into().ok()?;
+            let address = Ipv6Addr::from(bytes);
+            match address.to_ipv4_mapped() {
+                Some(ipv4) => Some(IpAddr::V4(ipv4)),
+                None => Some(IpAddr::V6(address)),
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[cfg(target_endian = "little")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_addresses_from_sockets() {
+        let contents = r#"  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 1000 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:9C40 0100007F:0016 01 00000000:00000000 00:00000000 00000000     0        0 1001 1 0000000000000000 20 4 30 10 -1
+   2: 0A01A8C0:0016 6401A8C0:D4E2 01 00000000:00000000 02:0009A2A6 00000000     0        0 1002 2 0000000000000000 20 4 31 10 -1
+   3: 0B01A8C0:B5C2 0101A8C0:01BB 01 00000000:00000000 02:0009A2A6 00000000     0        0 1003 2 0000000000000000 20 4 31 10 -1
+  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000000000000:01BB 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 1004 1 0000000000000000 100 0 0 10 0
+   1: 0000000000000000FFFF00000C01A8C0:01BB 0000000000000000FFFF00006401A8C0:D4E3 01 00000000:00000000 02:0009A2A6 00000000     0        0 1005 2 0000000000000000 20 4 31 10 -1
+"#;
+        assert_eq!(
+            addresses_from_sockets(contents),
+            HashSet::from([
+                IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10)),
+                IpAddr::V4(Ipv4Addr::new(192, 168, 1, 12)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_address() {
+        assert_eq!(
+            parse_address("0A01A8C0"),
+            Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10)))
+        );
+        assert_eq!(
+            parse_address("0000000000000000FFFF00000A01A8C0"),
+            Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10)))
+        );
+        assert_eq!(
+            parse_address("000080FE00000000FF005450003512FE"),
+            Some("fe80::5054:ff:fe12:3500".parse().unwrap())
+        );
+        assert_eq!(parse_address("0A01A8"), None);
+    }
+}
@@ -0,0 +1,173 @@
+//! Bandwidth limits for the installer traffic.
+//!
+//! On shared links, the installation might saturate the connection while downloading the
+//! packages. To avoid that, the traffic of an interface can be limited using `tc`:
+//!
+//! * The outgoing traffic goes through a token bucket filter (`tbf`) as the root queueing
+//!   discipline.
+//! * The incoming traffic (i.e., the downloads) is policed, dropping the packets above the
+//!   limit. TCP reacts to the drops by slowing down.
+//!
+//! The limits only affect the live system: they are set at runtime, so they are not written to
+//! the connections and they do not reach the installed system. The rules created by Agama are
+//! marked (with the [QDISC_HANDLE] handle and the [FILTER_PREF] preference), so they can be
+//! removed later without touching other rules.
+use anyhow::{anyhow, Context};
+use std::process::Command;
+
+/// Handle of the root queueing discipline.
+const QDISC_HANDLE: &str = "a9a:";
+/// Preference of the ingress filter.
+const FILTER_PREF: &str = "49300";
+/// Minimum burst size (in bytes). It must be bigger than the MTU.
+const MIN_BURST: u64 = 16384;
+
+/// Sets (or removes) the bandwidth limit of an interface.
+///
+/// * `interface`: interface name.
+/// * `limit`: limit in Kbit/s or `None` to remove it.
+pub fn write(interface: &str, limit: Option<u32>) -> anyhow::Result<()> {
+    let Some(limit) = limit else {
+        return remove(interface);
+    };
+
+    log::info!(
+        "Limiting the bandwidth of {} to {} Kbit/s",
+        interface,
+        limit
+    );
+    let rate = format!("{}kbit", limit);
+    let burst = burst(limit).to_string();
+    tc(
+        interface,
+        &[
+            "qdisc",
+            "replace",
+            "dev",
+            interface,
+            "root",
+            "handle",
+            QDISC_HANDLE,
+            "tbf",
+            "rate",
+            &rate,
+            "burst",
+            &burst,
+            "latency",
+            "100ms",
+        ],
+    )?;
+    tc(
+        interface,
+        &["qdisc", "replace", "dev", interface, "ingress"],
+    )?;
+    tc(
+        interface,
+        &[
+            "filter",
+            "replace",
+            "dev",
+            interface,
+            "ingress",
+            "pref",
+            FILTER_PREF,
+            "matchall",
+            "action",
+            "police",
+            "rate",
+            &rate,
+            "burst",
+            &burst,
+            "drop",
+        ],
+    )
+}
+
+/// Removes the bandwidth limit set by Agama, if any.
+///
+/// * `interface`: interface name.
+fn remove(interface: &str) -> anyhow::Result<()> {
+    let output = Command::new("tc")
+        .args(["qdisc", "show", "dev", interface, "root"])
+        .output()
+        .context("Could not run 'tc qdisc show'")?;
+    let qdiscs = String::from_utf8_lossy(&output.stdout);
+    if !is_limited(&qdiscs) {
+        return Ok(());
+    }
+
+    log::info!("Removing the bandwidth limit of {}", interface);
+    tc(interface, &["qdisc", "del", "dev", interface, "root"])?;
+    tc(
+        interface,
+        &[
+            "filter",
+            "del",
+            "dev",
+            interface,
+            "ingress",
+            "pref",
+            FILTER_PREF,
+        ],
+    )
+}
+
+/// Runs `tc` with the given arguments.
+///
+/// * `interface`: interface the command refers to (for the error messages).
+/// * `args`: arguments.
+fn tc(interface: &str, args: &[&str]) -> anyhow::Result<()> {
+    let output = Command::new("tc")
+        .args(args)
+        .output()
+        .context("Could not run 'tc'")?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(anyhow!(
+        "Could not change the bandwidth limit of {}: {}",
+        interface,
+        stderr.trim()
+    ))
+}
+
+/// Determines whether the root queueing discipline was set by Agama.
+///
+/// * `qdiscs`: output of `tc qdisc show` (e.g., `qdisc tbf a9a: root refcnt 2 rate 8Mbit ...`).
+fn is_limited(qdiscs: &str) -> bool {
+    qdiscs
+        .lines()
+        .any(|l| l.starts_with(&format!("qdisc tbf {} root", QDISC_HANDLE)))
+}
+
+/// Returns the burst size (in bytes) for a limit, which allows sending at full speed for 100ms.
+///
+/// * `limit`: limit in Kbit/s.
+fn burst(limit: u32) -> u64 {
+    (u64::from(limit) * 1000 / 8 / 10).max(MIN_BURST)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_limited() {
+        assert!(is_limited(
+            "qdisc tbf a9a: root refcnt 2 rate 8Mbit burst 100000b lat 100ms\n"
+        ));
+        assert!(!is_limited(
+            "qdisc fq_codel 0: root refcnt 2 limit 10240p flows 1024\n"
+        ));
+        assert!(!is_limited(
+            "qdisc tbf 1: root refcnt 2 rate 8Mbit burst 100000b lat 100ms\n"
+        ));
+    }
+
+    #[test]
+    fn test_burst() {
+        assert_eq!(burst(1000), MIN_BURST);
+        assert_eq!(burst(100_000), 1_250_000);
+    }
+}
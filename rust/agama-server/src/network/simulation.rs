@@ -0,0 +1,532 @@
+//! Simulated network backend
+//!
+//! [SimulationAdapter] fakes the responses of NetworkManager, adding some latency and, if
+//! requested, failures. It allows developing the clients (e.g., the web UI) against a realistic
+//! asynchronous behavior without real hardware.
+//!
+//! The simulation is described by a [SimulationProfile], which can be read from a JSON file like
+//! this one (all the keys are optional):
+//!
+//! ```json
+//! {
+//!   "readDelay": 200,
+//!   "writeDelay": 1000,
+//!   "failingConnections": ["Guest Wi-Fi"],
+//!   "dhcp": {
+//!     "eth0": { "type": "nak", "times": 2, "address": "192.168.1.10/24" }
+//!   },
+//!   "connections": [
+//!     { "id": "eth0", "interface": "eth0", "ipConfig": { "method4": "auto" } }
+//!   ]
+//! }
+//! ```
+//!
+//! The connections use the serialized form of [Connection]. If they are not given, a wired and a
+//! wireless connection are simulated.
+//!
+//! The `dhcp` key scripts how the DHCP server answers when a connection (identified by its ID)
+//! which gets its IPv4 configuration automatically is activated (see [DhcpScript]). It allows
+//! testing the activation timeouts and the retries deterministically.
+use crate::network::{
+    model::{
+        ApplyResult, Connection, ConnectionApplyResult, ConnectionChange, ConnectionConfig, Device,
+        IpConfig, Ipv4Method, Ipv6Method, WirelessConfig,
+    },
+    Adapter, CancellationFlag, NetworkAdapterError, NetworkState,
+};
+use agama_lib::{
+    error::ServiceError,
+    network::types::{DeviceType, SSID},
+};
+use async_trait::async_trait;
+use cidr::IpInet;
+use serde::Deserialize;
+use std::{collections::HashMap, path::Path, time::Duration};
+use thiserror::Error;
+use tokio::sync::Mutex;
+
+#[derive(Error, Debug)]
+pub enum SimulationError {
+    #[error("Could not read the simulation profile: {0}")]
+    IO(#[from] std::io::Error),
+    #[error("Invalid simulation profile: {0}")]
+    InvalidProfile(#[from] serde_json::Error),
+}
+
+/// Describes how the network backend is simulated
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SimulationProfile {
+    /// Time (in milliseconds) to read the configuration.
+    pub read_delay: u64,
+    /// Time (in milliseconds) to write each connection.
+    pub write_delay: u64,
+    /// IDs of the connections that cannot be written.
+    pub failing_connections: Vec<String>,
+    /// Whether reading the configuration fails.
+    pub fail_reads: bool,
+    /// Whether the system has access to Internet.
+    pub online: bool,
+    /// Initial connections.
+    pub connections: Vec<Connection>,
+    /// DHCP server behavior for each connection ID. The connections which are not included are
+    /// activated right away.
+    pub dhcp: HashMap<String, DhcpScript>,
+}
+
+/// Scripted answers of the simulated DHCP server
+///
+/// The attempts are counted for each connection, so applying the configuration again (e.g.,
+/// retrying after a failure) gets the next answer.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DhcpScript {
+    /// Offers the address after some time (in milliseconds). If it takes longer than the
+    /// activation timeout, the activation fails.
+    Offer {
+        address: IpInet,
+        #[serde(default)]
+        delay: u64,
+    },
+    /// Rejects the first requests (or all of them, if `times` is not given) and offers the
+    /// address afterwards.
+    Nak {
+        times: Option<u32>,
+        address: Option<IpInet>,
+    },
+    /// Offers a different address on each attempt, cycling through the list.
+    Changing { addresses: Vec<IpInet> },
+}
+
+impl Default for SimulationProfile {
+    fn default() -> Self {
+        let mut eth0 = Connection::new("Wired connection 1".to_string(), DeviceType::Ethernet);
+        eth0.interface = Some("eth0".to_string());
+        eth0.ip_config.method4 = Ipv4Method::Auto;
+        eth0.ip_config.method6 = Ipv6Method::Auto;
+
+        let mut wlan0 = Connection::new("Guest Wi-Fi".to_string(), DeviceType::Wireless);
+        wlan0.interface = Some("wlan0".to_string());
+        wlan0.ip_config.method4 = Ipv4Method::Auto;
+        wlan0.config = ConnectionConfig::Wireless(WirelessConfig {
+            ssid: SSID(b"Guest".to_vec()),
+            ..Default::default()
+        });
+
+        Self {
+            read_delay: 200,
+            write_delay: 1000,
+            failing_connections: vec![],
+            fail_reads: false,
+            online: true,
+            connections: vec![eth0, wlan0],
+            dhcp: HashMap::new(),
+        }
+    }
+}
+
+impl SimulationProfile {
+    /// Reads the profile from a JSON file.
+    ///
+    /// * `path`: profile location.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SimulationError> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+/// Adapter that simulates a network backend
+///
+/// The written configuration is kept in memory, so it is returned by the next reads.
+pub struct SimulationAdapter {
+    profile: SimulationProfile,
+    state: Mutex<NetworkState>,
+    dhcp: Mutex<DhcpState>,
+    activation_timeout: Option<u32>,
+}
+
+/// State of the simulated DHCP server
+#[derive(Debug, Default)]
+struct DhcpState {
+    /// Number of activation attempts for each connection ID.
+    attempts: HashMap<String, u32>,
+    /// Leased address for each interface.
+    leases: HashMap<String, IpInet>,
+}
+
+impl SimulationAdapter {
+    /// Creates an adapter that follows the given profile.
+    ///
+    /// * `profile`: simulation profile.
+    pub fn new(profile: SimulationProfile) -> Self {
+        let connections = profile.connections.clone();
+        let devices = connections.iter().filter_map(device_for).collect();
+        Self {
+            profile,
+            state: Mutex::new(NetworkState::new(devices, connections)),
+            dhcp: Mutex::new(DhcpState::default()),
+            activation_timeout: None,
+        }
+    }
+
+    /// Sets the time (in seconds) to wait for the activation of the connections that do not
+    /// define their own timeout.
+    ///
+    /// * `timeout`: activation timeout.
+    pub fn with_activation_timeout(mut self, timeout: Option<u32>) -> Self {
+        self.activation_timeout = timeout;
+        self
+    }
+
+    async fn delay(millis: u64) {
+        tokio::time::sleep(Duration::from_millis(millis)).await;
+    }
+
+    /// Simulates the activation of a connection, following the DHCP script.
+    ///
+    /// As the real backend, it only waits for the activation (and reports the failures) when
+    /// there is an activation timeout. Otherwise, the offered address is leased right away.
+    ///
+    /// * `conn`: connection to activate.
+    /// * `cancel`: flag to cancel the activation.
+    async fn activate(&self, conn: &Connection, cancel: &CancellationFlag) -> Result<(), String> {
+        let Some(interface) = &conn.interface else {
+            return Ok(());
+        };
+        if !conn.is_up() || conn.only_on_target || conn.ip_config.method4 != Ipv4Method::Auto {
+            return Ok(());
+        }
+        let Some(script) = self.profile.dhcp.get(&conn.id) else {
+            return Ok(());
+        };
+
+        let attempt = {
+            let mut dhcp = self.dhcp.lock().await;
+            let attempts = dhcp.attempts.entry(conn.id.clone()).or_default();
+            *attempts += 1;
+            *attempts
+        };
+        let timeout = conn.activation_timeout.or(self.activation_timeout);
+
+        let offer = match script {
+            DhcpScript::Offer { address, delay } => Some((*address, *delay)),
+            DhcpScript::Nak { times, address } => {
+                if !times.is_some_and(|times| attempt > times) {
+                    return match timeout {
+                        Some(_) => Err("connection activation failed (DHCP NAK)".to_string()),
+                        None => Ok(()),
+                    };
+                }
+                address.map(|address| (address, 0))
+            }
+            DhcpScript::Changing { addresses } => {
+                let index = (attempt - 1) as usize % addresses.len().max(1);
+                addresses.get(index).map(|address| (*address, 0))
+            }
+        };
+        let Some((address, delay)) = offer else {
+            return Ok(());
+        };
+
+        if let Some(timeout) = timeout {
+            let timeout = u64::from(timeout) * 1000;
+            Self::delay(delay.min(timeout)).await;
+            if cancel.is_cancelled() {
+                return Err("connection activation cancelled".to_string());
+            }
+            if delay > timeout {
+                return Err("connection activation timed out".to_string());
+            }
+        }
+
+        let mut dhcp = self.dhcp.lock().await;
+        dhcp.leases.insert(interface.clone(), address);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Adapter for SimulationAdapter {
+    async fn read(&self) -> Result<NetworkState, NetworkAdapterError> {
+        Self::delay(self.profile.read_delay).await;
+        if self.profile.fail_reads {
+            return Err(NetworkAdapterError::Read(ServiceError::UnsuccessfulAction(
+                "reading the simulated configuration".to_string(),
+            )));
+        }
+        Ok(self.state.lock().await.clone())
+    }
+
+    async fn is_online(&self) -> Result<bool, NetworkAdapterError> {
+        Ok(self.profile.online)
+    }
+
+    async fn write(
+        &self,
+        network: &NetworkState,
+        cancel: &CancellationFlag,
+    ) -> Result<ApplyResult, NetworkAdapterError> {
+        let mut state = self.state.lock().await;
+        let mut result = ApplyResult::default();
+        let mut connections = vec![];
+
+        for conn in &network.connections {
+            let old_conn = state.get_connection_snapshot(conn.uuid);
+            if old_conn.as_ref() == Some(conn) {
+                connections.push(conn.clone());
+                continue;
+            }
+
+            Self::delay(self.profile.write_delay).await;
+            let mut error = if cancel.is_cancelled() {
+                Some("applying the configuration was cancelled".to_string())
+            } else if self.profile.failing_connections.contains(&conn.id) {
+                Some(format!("Simulated failure writing '{}'", conn.id))
+            } else {
+                None
+            };
+
+            let change = if conn.is_removed() {
+                ConnectionChange::Removed
+            } else if old_conn.is_some() {
+                ConnectionChange::Updated
+            } else {
+                ConnectionChange::Added
+            };
+
+            // keep the previous version of the connections that could not be written
+            match (&error, old_conn) {
+                (None, _) if !conn.is_removed() => connections.push(conn.clone()),
+                (Some(_), Some(old_conn)) => connections.push(old_conn.clone()),
+                _ => {}
+            }
+
+            if error.is_none() && !conn.is_removed() {
+                error = self.activate(conn, cancel).await.err();
+            }
+
+            result.connections.push(ConnectionApplyResult {
+                id: conn.id.clone(),
+                uuid: conn.uuid,
+                change,
+                error,
+            });
+        }
+
+        *state = NetworkState {
+            connections,
+            ..network.clone()
+        };
+        Ok(result)
+    }
+
+    async fn device_ip_config(&self, device: &str) -> Result<IpConfig, NetworkAdapterError> {
+        let dhcp = self.dhcp.lock().await;
+        let Some(address) = dhcp.leases.get(device) else {
+            return Err(NetworkAdapterError::Read(ServiceError::UnsuccessfulAction(
+                format!("reading the IP configuration of {}", device),
+            )));
+        };
+        Ok(IpConfig {
+            method4: Ipv4Method::Auto,
+            addresses: vec![*address],
+            ..Default::default()
+        })
+    }
+}
+
+/// Returns the device the connection is bound to, if any.
+fn device_for(conn: &Connection) -> Option<Device> {
+    let type_ = match conn.config {
+        ConnectionConfig::Wireless(_) => DeviceType::Wireless,
+        ConnectionConfig::Bond(_) => DeviceType::Bond,
+        ConnectionConfig::Vrf(_) => DeviceType::Vrf,
+        ConnectionConfig::Macvlan(_) => DeviceType::Macvlan,
+        ConnectionConfig::Ipvlan(_) => DeviceType::Ipvlan,
+        ConnectionConfig::Macsec(_) => DeviceType::Macsec,
+        ConnectionConfig::Loopback => DeviceType::Loopback,
+        _ => DeviceType::Ethernet,
+    };
+    Some(Device {
+        name: conn.interface.clone()?,
+        type_,
+        wireless_capabilities: None,
+        modem_capabilities: None,
+        managed: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn profile() -> SimulationProfile {
+        SimulationProfile {
+            read_delay: 0,
+            write_delay: 0,
+            failing_connections: vec!["Guest Wi-Fi".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read() {
+        let adapter = SimulationAdapter::new(profile());
+        let state = adapter.read().await.unwrap();
+        assert_eq!(state.connections.len(), 2);
+        let names: Vec<_> = state.devices.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["eth0", "wlan0"]);
+
+        let adapter = SimulationAdapter::new(SimulationProfile {
+            fail_reads: true,
+            ..profile()
+        });
+        assert!(adapter.read().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_write() {
+        let adapter = SimulationAdapter::new(profile());
+        let mut state = adapter.read().await.unwrap();
+        for conn in state.connections.iter_mut() {
+            Arc::make_mut(conn).ip_config.method4 = Ipv4Method::Manual;
+        }
+        let eth1 = Connection::new("eth1".to_string(), DeviceType::Ethernet);
+        state.add_connection(eth1).unwrap();
+
+        let result = adapter
+            .write(&state, &CancellationFlag::default())
+            .await
+            .unwrap();
+        let summary = result.summary();
+        assert_eq!(summary.added, 1);
+        assert_eq!(summary.updated, 1);
+        assert_eq!(summary.failed, vec!["Guest Wi-Fi".to_string()]);
+
+        let state = adapter.read().await.unwrap();
+        assert_eq!(state.connections.len(), 3);
+        let eth0 = state.get_connection("Wired connection 1").unwrap();
+        assert_eq!(eth0.ip_config.method4, Ipv4Method::Manual);
+        let wlan0 = state.get_connection("Guest Wi-Fi").unwrap();
+        assert_eq!(wlan0.ip_config.method4, Ipv4Method::Auto);
+    }
+
+    /// Applies a change to the eth0 connection and returns the activation error, if any.
+    async fn apply_eth0(adapter: &SimulationAdapter, timeout: Option<u32>) -> Option<String> {
+        let mut state = adapter.read().await.unwrap();
+        let eth0 = state.get_connection_mut("Wired connection 1").unwrap();
+        eth0.activation_timeout = timeout;
+        // change the connection, so it is written (and activated) again
+        let description = eth0.description.take().unwrap_or_default();
+        eth0.description = Some(format!("{description}*"));
+        let result = adapter
+            .write(&state, &CancellationFlag::default())
+            .await
+            .unwrap();
+        result.connections[0].error.clone()
+    }
+
+    fn dhcp_profile(script: DhcpScript) -> SimulationProfile {
+        SimulationProfile {
+            dhcp: HashMap::from([("Wired connection 1".to_string(), script)]),
+            ..profile()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dhcp_delayed_offer() {
+        let address: IpInet = "192.168.1.10/24".parse().unwrap();
+        let adapter = SimulationAdapter::new(dhcp_profile(DhcpScript::Offer {
+            address,
+            delay: 1500,
+        }));
+        assert!(adapter.device_ip_config("eth0").await.is_err());
+
+        let error = apply_eth0(&adapter, Some(1)).await;
+        assert_eq!(error, Some("connection activation timed out".to_string()));
+        assert!(adapter.device_ip_config("eth0").await.is_err());
+
+        assert_eq!(apply_eth0(&adapter, Some(2)).await, None);
+        let ip_config = adapter.device_ip_config("eth0").await.unwrap();
+        assert_eq!(ip_config.addresses, vec![address]);
+    }
+
+    #[tokio::test]
+    async fn test_dhcp_nak() {
+        let address: IpInet = "192.168.1.10/24".parse().unwrap();
+        let adapter = SimulationAdapter::new(dhcp_profile(DhcpScript::Nak {
+            times: Some(2),
+            address: Some(address),
+        }))
+        .with_activation_timeout(Some(1));
+
+        for _ in 0..2 {
+            let error = apply_eth0(&adapter, None).await;
+            assert_eq!(
+                error,
+                Some("connection activation failed (DHCP NAK)".to_string())
+            );
+        }
+        assert_eq!(apply_eth0(&adapter, None).await, None);
+        let ip_config = adapter.device_ip_config("eth0").await.unwrap();
+        assert_eq!(ip_config.addresses, vec![address]);
+    }
+
+    #[tokio::test]
+    async fn test_dhcp_changing_addresses() {
+        let addresses: Vec<IpInet> = vec![
+            "192.168.1.10/24".parse().unwrap(),
+            "192.168.1.11/24".parse().unwrap(),
+        ];
+        let adapter = SimulationAdapter::new(dhcp_profile(DhcpScript::Changing {
+            addresses: addresses.clone(),
+        }));
+
+        for expected in [addresses[0], addresses[1], addresses[0]] {
+            assert_eq!(apply_eth0(&adapter, None).await, None);
+            let ip_config = adapter.device_ip_config("eth0").await.unwrap();
+            assert_eq!(ip_config.addresses, vec![expected]);
+        }
+    }
+
+    #[test]
+    fn test_profile_from_json() {
+        let json = r#"{ "writeDelay": 10, "connections": [{ "id": "eth0" }] }"#;
+        let profile: SimulationProfile = serde_json::from_str(json).unwrap();
+        assert_eq!(profile.write_delay, 10);
+        assert_eq!(profile.read_delay, 200);
+        assert_eq!(profile.connections.len(), 1);
+        assert_eq!(profile.connections[0].id, "eth0");
+    }
+
+    #[test]
+    fn test_dhcp_script_from_json() {
+        let json = r#"{ "dhcp": {
+            "eth0": { "type": "offer", "address": "192.168.1.10/24", "delay": 100 },
+            "eth1": { "type": "nak" },
+            "eth2": { "type": "changing", "addresses": ["10.0.0.1/8"] }
+        } }"#;
+        let profile: SimulationProfile = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            profile.dhcp["eth0"],
+            DhcpScript::Offer {
+                address: "192.168.1.10/24".parse().unwrap(),
+                delay: 100
+            }
+        );
+        assert_eq!(
+            profile.dhcp["eth1"],
+            DhcpScript::Nak {
+                times: None,
+                address: None
+            }
+        );
+        assert_eq!(
+            profile.dhcp["eth2"],
+            DhcpScript::Changing {
+                addresses: vec!["10.0.0.1/8".parse().unwrap()]
+            }
+        );
+    }
+}
@@ -1,7 +1,21 @@
 use super::{error::NetworkStateError, NetworkAdapterError};
-use crate::network::{dbus::Tree, model::Connection, Action, Adapter, NetworkState};
+use crate::{
+    access::AccessPolicy,
+    network::{
+        action::ConnectionsPage,
+        dbus::Tree,
+        model::{
+            ApplyResult, ApplySummary, BondCheck, ConfigIssue, ConflictPolicy, Connection,
+            ConnectionConfig, ConnectionSummary, ConnectionTemplate, ConnectionsChanges, IdMapping,
+        },
+        ndisc, session, Action, Adapter, ApplyStatus, CancellationFlag, Health, NetworkState,
+        RequestId, TrackedAction,
+    },
+};
 use agama_lib::network::types::DeviceType;
-use std::{error::Error, sync::Arc};
+#[cfg(feature = "network-debug")]
+use std::collections::VecDeque;
+use std::{collections::HashMap, error::Error, sync::Arc};
 use tokio::sync::{
     mpsc::{self, UnboundedReceiver, UnboundedSender},
     Mutex,
@@ -9,51 +23,203 @@ use tokio::sync::{
 use uuid::Uuid;
 use zbus::zvariant::OwnedObjectPath;
 
+/// Number of processed actions to keep for troubleshooting.
+#[cfg(feature = "network-debug")]
+const ACTIONS_HISTORY_SIZE: usize = 100;
+
 /// Represents the network system using holding the state and setting up the D-Bus tree.
 pub struct NetworkSystem<T: Adapter> {
     /// Network state
     pub state: NetworkState,
     /// Side of the channel to send actions.
-    actions_tx: UnboundedSender<Action>,
-    actions_rx: UnboundedReceiver<Action>,
+    actions_tx: UnboundedSender<TrackedAction>,
+    actions_rx: UnboundedReceiver<TrackedAction>,
     tree: Arc<Mutex<Tree>>,
     /// Adapter to read/write the network state.
     adapter: T,
+    /// Policy to resolve the conflicts between connections sharing the same ID.
+    conflict_policy: ConflictPolicy,
+    /// Mapping between the original connection IDs and the resulting ones.
+    ids_mapping: Vec<IdMapping>,
+    /// Connections as they were when last read from the adapter. They are used to find out
+    /// which connections have pending changes.
+    base_connections: Vec<Arc<Connection>>,
+    /// Result of the last time the configuration was applied.
+    last_apply_result: ApplyResult,
+    /// Errors from the last time each connection was written (cleared on success).
+    last_errors: HashMap<Uuid, String>,
+    /// Flag to cancel applying the configuration.
+    apply_cancellation: CancellationFlag,
+    /// Request the action being processed comes from.
+    request_id: RequestId,
+    /// Health information exposed through D-Bus.
+    health: Health,
+    /// Number of actions that (might) have changed the connections.
+    connections_generation: u64,
+    /// Identifies this instance of the service, so the change tokens are not valid after a
+    /// restart.
+    instance_id: Uuid,
+    /// Last processed actions, from the oldest to the newest.
+    #[cfg(feature = "network-debug")]
+    actions_history: VecDeque<(RequestId, &'static str)>,
 }
 
 impl<T: Adapter> NetworkSystem<T> {
-    pub fn new(conn: zbus::Connection, adapter: T) -> Self {
+    pub fn new(conn: zbus::Connection, adapter: T, access: AccessPolicy) -> Self {
         let (actions_tx, actions_rx) = mpsc::unbounded_channel();
-        let tree = Tree::new(conn, actions_tx.clone());
+        let apply_cancellation = CancellationFlag::default();
+        let health = Health::default();
+        let tree = Tree::new(
+            conn,
+            actions_tx.clone(),
+            access,
+            apply_cancellation.clone(),
+            health.clone(),
+        );
         Self {
             state: NetworkState::default(),
             actions_tx,
             actions_rx,
             tree: Arc::new(Mutex::new(tree)),
             adapter,
+            conflict_policy: ConflictPolicy::default(),
+            ids_mapping: vec![],
+            base_connections: vec![],
+            last_apply_result: ApplyResult::default(),
+            last_errors: HashMap::new(),
+            apply_cancellation,
+            request_id: RequestId::default(),
+            health,
+            connections_generation: 0,
+            instance_id: Uuid::new_v4(),
+            #[cfg(feature = "network-debug")]
+            actions_history: VecDeque::with_capacity(ACTIONS_HISTORY_SIZE),
         }
     }
 
-    /// Writes the network configuration.
-    pub async fn write(&mut self) -> Result<(), NetworkAdapterError> {
-        self.adapter.write(&self.state).await?;
-        self.state = self.adapter.read().await?;
+    /// Writes the network configuration and returns a summary of the changes.
+    ///
+    /// The result for each connection is kept and it can be retrieved later.
+    pub async fn write(&mut self) -> Result<ApplySummary, NetworkAdapterError> {
+        self.apply_cancellation.reset();
+        let result = self
+            .adapter
+            .write(&self.state, &self.apply_cancellation)
+            .await;
+        self.set_apply_result(result)?;
+        self.read().await?;
+        Ok(self.last_apply_result.summary())
+    }
+
+    /// Writes the pending changes of the connections with the given tag and returns a summary of
+    /// the changes.
+    ///
+    /// The pending changes of the rest of the connections are kept, so they can be applied later.
+    ///
+    /// * `tag`: tag of the connections to write.
+    pub async fn write_group(&mut self, tag: &str) -> Result<ApplySummary, NetworkAdapterError> {
+        self.apply_cancellation.reset();
+        let members = self.state.group_members(tag, &self.base_connections);
+        let group = self.state.group_state(tag, &self.base_connections);
+        let result = self.adapter.write(&group, &self.apply_cancellation).await;
+        self.set_apply_result(result)?;
+
+        let external = self.adapter.read().await;
+        self.health.set_backend_available(external.is_ok());
+        let external = external?;
+        // forget the connections of the group, so they are taken from the backend again
+        self.state
+            .connections
+            .retain(|c| !members.contains(&c.uuid));
+        self.base_connections.retain(|c| !members.contains(&c.uuid));
+        self.reconcile(external);
+        Ok(self.last_apply_result.summary())
+    }
+
+    /// Keeps the result of writing the configuration.
+    ///
+    /// * `result`: result returned by the adapter.
+    fn set_apply_result(
+        &mut self,
+        result: Result<ApplyResult, NetworkAdapterError>,
+    ) -> Result<(), NetworkAdapterError> {
+        self.last_apply_result = match result {
+            Ok(result) => {
+                self.health
+                    .set_last_apply(ApplyStatus::from_result(&result));
+                result.update_last_errors(&mut self.last_errors);
+                result
+            }
+            Err(error) => {
+                self.health
+                    .set_last_apply(ApplyStatus::Failure(error.to_string()));
+                return Err(error);
+            }
+        };
+        Ok(())
+    }
+
+    /// Reads the network configuration, resolving the conflicts between connections sharing the
+    /// same ID according to the [ConflictPolicy].
+    async fn read(&mut self) -> Result<(), NetworkAdapterError> {
+        let state = self.adapter.read().await;
+        self.health.set_backend_available(state.is_ok());
+        let previous = std::mem::replace(&mut self.state, state?);
+        self.state.keep_local_settings(&previous);
+        self.ids_mapping = self.state.resolve_conflicts(self.conflict_policy);
+        self.base_connections = self.state.connections.clone();
         Ok(())
     }
 
+    /// Synchronizes the connections with the ones in the adapter, keeping the pending changes.
+    ///
+    /// It returns the changes in the connections.
+    async fn sync_connections(&mut self) -> Result<ConnectionsChanges, NetworkAdapterError> {
+        let external = self.adapter.read().await;
+        self.health.set_backend_available(external.is_ok());
+        Ok(self.reconcile(external?))
+    }
+
+    /// Reloads the adapter configuration and reads the devices and connections again, keeping
+    /// the pending changes.
+    ///
+    /// It returns the changes in the connections.
+    async fn refresh(&mut self) -> Result<ConnectionsChanges, NetworkAdapterError> {
+        self.adapter.reload().await?;
+        let external = self.adapter.read().await;
+        self.health.set_backend_available(external.is_ok());
+        let mut external = external?;
+        self.state.devices = std::mem::take(&mut external.devices);
+        Ok(self.reconcile(external))
+    }
+
+    /// Reconciles the connections with the ones read from the adapter.
+    ///
+    /// * `external`: state read from the adapter.
+    fn reconcile(&mut self, mut external: NetworkState) -> ConnectionsChanges {
+        external.keep_local_settings(&self.state);
+        self.ids_mapping = external.resolve_conflicts(self.conflict_policy);
+        let changes = self
+            .state
+            .reconcile(&self.base_connections, external.connections.clone());
+        self.base_connections = external.connections;
+        changes
+    }
+
     /// Returns a clone of the
     /// [UnboundedSender](https://docs.rs/tokio/latest/tokio/sync/mpsc/struct.UnboundedSender.html)
     /// to execute [actions](Action).
-    pub fn actions_tx(&self) -> UnboundedSender<Action> {
+    pub fn actions_tx(&self) -> UnboundedSender<TrackedAction> {
         self.actions_tx.clone()
     }
 
     /// Populates the D-Bus tree with the known devices and connections.
     pub async fn setup(&mut self) -> Result<(), Box<dyn Error>> {
-        self.state = self.adapter.read().await?;
+        self.read().await?;
         let mut tree = self.tree.lock().await;
-        tree.set_connections(&mut self.state.connections).await?;
-        tree.set_devices(&self.state.devices).await?;
+        tree.set_connections(&self.state.connections).await?;
+        tree.set_devices(&self.state.visible_devices()).await?;
+        tree.add_global_settings().await?;
         Ok(())
     }
 
@@ -61,23 +227,49 @@ impl<T: Adapter> NetworkSystem<T> {
     ///
     /// This function is expected to be executed on a separate thread.
     pub async fn listen(&mut self) {
-        while let Some(action) = self.actions_rx.recv().await {
+        while let Some(tracked) = self.actions_rx.recv().await {
+            let (request_id, action) = tracked.take();
+            self.request_id = request_id;
+            log::debug!("[{}] Processing action {}", request_id, action.name());
+            #[cfg(feature = "network-debug")]
+            self.record_action(request_id, action.name());
             if let Err(error) = self.dispatch_action(action).await {
-                eprintln!("Could not process the action: {}", error);
+                log::error!("[{}] Could not process the action: {}", request_id, error);
             }
         }
     }
 
     /// Dispatch an action.
     pub async fn dispatch_action(&mut self, action: Action) -> Result<(), Box<dyn Error>> {
+        if action.modifies_connections() {
+            self.connections_generation += 1;
+        }
+
         match action {
             Action::AddConnection(name, ty, tx) => {
                 let result = self.add_connection_action(name, ty).await;
                 tx.send(result).unwrap();
             }
+            Action::AddConnections(template, interfaces, tx) => {
+                let result = self.add_connections_action(&template, &interfaces).await;
+                tx.send(result).unwrap();
+            }
+            Action::ImportConnections(conns, tx) => {
+                let result = self.import_connections_action(conns).await;
+                tx.send(result).unwrap();
+            }
+            Action::CloneConnection(source_id, new_id, with_secrets, tx) => {
+                let result = self
+                    .clone_connection_action(&source_id, &new_id, with_secrets)
+                    .await;
+                tx.send(result).unwrap();
+            }
+            Action::CreateFromDevice(name, as_static, tx) => {
+                let result = self.create_from_device_action(&name, as_static).await;
+                tx.send(result).unwrap();
+            }
             Action::GetConnection(uuid, tx) => {
-                let conn = self.state.get_connection_by_uuid(uuid);
-                tx.send(conn.cloned()).unwrap();
+                tx.send(self.state.get_connection_snapshot(uuid)).unwrap();
             }
             Action::GetConnectionPath(uuid, tx) => {
                 let tree = self.tree.lock().await;
@@ -92,6 +284,80 @@ impl<T: Adapter> NetworkSystem<T> {
                 let result = self.get_controller_action(uuid);
                 tx.send(result).unwrap()
             }
+            Action::GetRegulatoryDomain(tx) => {
+                tx.send(self.state.regulatory_domain.clone()).unwrap();
+            }
+            Action::SetRegulatoryDomain(code, tx) => {
+                let result = self.state.set_regulatory_domain(code);
+                tx.send(result).unwrap();
+            }
+            Action::GetHostname(tx) => {
+                tx.send(self.state.hostname.clone()).unwrap();
+            }
+            Action::SetHostname(hostname, tx) => {
+                let result = self.state.set_hostname(hostname);
+                tx.send(result).unwrap();
+            }
+            Action::GetConnectivity(tx) => {
+                tx.send(self.state.connectivity.clone()).unwrap();
+            }
+            Action::UpdateConnectivity(config) => {
+                self.state.connectivity = *config;
+            }
+            Action::GetDns(tx) => {
+                tx.send(self.state.dns.clone()).unwrap();
+            }
+            Action::UpdateDns(config) => {
+                self.state.dns = *config;
+            }
+            Action::GetEffectiveDns(device, tx) => {
+                tx.send(self.state.effective_dns(&device)).unwrap();
+            }
+            Action::GetKdump(tx) => {
+                tx.send(self.state.kdump.clone()).unwrap();
+            }
+            Action::UpdateKdump(config) => {
+                self.state.kdump = *config;
+            }
+            Action::IsOnline(tx) => {
+                tx.send(self.adapter.is_online().await).unwrap();
+            }
+            Action::GetLldpNeighbors(device, tx) => {
+                tx.send(self.adapter.lldp_neighbors(&device).await).unwrap();
+            }
+            Action::GetIpv6RaInfo(device, tx) => {
+                tx.send(ndisc::router_advertisements(&device).await)
+                    .unwrap();
+            }
+            Action::SetDeviceManaged(name, managed, tx) => {
+                let result = self.adapter.set_device_managed(&name, managed).await;
+                if result.is_ok() {
+                    if let Some(device) = self.state.devices.iter_mut().find(|d| d.name == name) {
+                        device.managed = managed;
+                    }
+                }
+                tx.send(result).unwrap();
+            }
+            Action::GetIgnoredDevices(tx) => {
+                tx.send(self.state.ignored_devices.clone()).unwrap();
+            }
+            Action::SetIgnoredDevices(names) => {
+                self.state.ignored_devices = names;
+                let mut tree = self.tree.lock().await;
+                tree.set_devices(&self.state.visible_devices()).await?;
+            }
+            Action::GetAddrGenMode(tx) => {
+                tx.send(self.state.addr_gen_mode).unwrap();
+            }
+            Action::SetAddrGenMode(mode) => {
+                self.state.addr_gen_mode = mode;
+            }
+            Action::GetBandwidthLimit(tx) => {
+                tx.send(self.state.bandwidth_limit).unwrap();
+            }
+            Action::SetBandwidthLimit(limit) => {
+                self.state.bandwidth_limit = limit;
+            }
             Action::GetDevicesPaths(tx) => {
                 let tree = self.tree.lock().await;
                 tx.send(tree.devices_paths()).unwrap();
@@ -100,6 +366,21 @@ impl<T: Adapter> NetworkSystem<T> {
                 let tree = self.tree.lock().await;
                 tx.send(tree.connections_paths()).unwrap();
             }
+            Action::GetConnectionsByTag(tag, tx) => {
+                let paths = self.get_connections_by_tag_action(&tag).await;
+                tx.send(paths).unwrap();
+            }
+            Action::GetConnectionsSummary(tx) => {
+                let summary = self.get_connections_summary_action().await;
+                tx.send(summary).unwrap();
+            }
+            Action::GetConnectionsPage(offset, limit, token, tx) => {
+                let result = self.get_connections_page_action(offset, limit, token).await;
+                tx.send(result).unwrap();
+            }
+            Action::GetChangeToken(tx) => {
+                tx.send(self.change_token()).unwrap();
+            }
             Action::SetPorts(uuid, ports, rx) => {
                 let result = self.set_ports_action(uuid, *ports);
                 rx.send(result).unwrap();
@@ -112,33 +393,238 @@ impl<T: Adapter> NetworkSystem<T> {
                 tree.remove_connection(uuid).await?;
                 self.state.remove_connection(uuid)?;
             }
+            Action::RemoveAllConnections => {
+                let uuids: Vec<Uuid> = self
+                    .state
+                    .connections
+                    .iter()
+                    .filter(|c| !c.is_removed())
+                    .map(|c| c.uuid)
+                    .collect();
+                let mut tree = self.tree.lock().await;
+                for uuid in uuids {
+                    tree.remove_connection(uuid).await?;
+                    self.state.remove_connection(uuid)?;
+                }
+            }
+            Action::GetSessionConnections(tx) => {
+                let uuids = self.get_session_connections_action().await;
+                tx.send(uuids).unwrap();
+            }
             Action::Apply(tx) => {
                 let result = self.write().await;
+                match &result {
+                    Ok(summary) => log::info!(
+                        "[{}] Network configuration applied: {} added, {} updated, {} removed",
+                        self.request_id,
+                        summary.added,
+                        summary.updated,
+                        summary.removed
+                    ),
+                    Err(error) => log::error!(
+                        "[{}] Could not apply the network configuration: {}",
+                        self.request_id,
+                        error
+                    ),
+                }
                 let failed = result.is_err();
                 tx.send(result).unwrap();
                 if failed {
                     return Ok(());
                 }
-
-                // TODO: re-creating the tree is kind of brute-force and it sends signals about
-                // adding/removing interfaces. We should add/update/delete objects as needed.
-                // NOTE updating the tree at the same time than dispatching actions can cause a
-                // deadlock. We might consider using message passing too but at this point
-                // is enough to use a separate task.
-                let mut connections = self.state.connections.clone();
-                let tree = Arc::clone(&self.tree);
-                tokio::spawn(async move {
-                    let mut tree = tree.lock().await;
-                    if let Err(e) = tree.set_connections(&mut connections).await {
-                        log::error!("Could not update the D-Bus tree: {}", e);
+                self.refresh_connections_tree(None);
+            }
+            Action::ApplyGroup(tag, tx) => {
+                let result = self.write_group(&tag).await;
+                match &result {
+                    Ok(summary) => log::info!(
+                        "[{}] Network configuration applied for the '{}' group: {} added, {} updated, {} removed",
+                        self.request_id,
+                        tag,
+                        summary.added,
+                        summary.updated,
+                        summary.removed
+                    ),
+                    Err(error) => log::error!(
+                        "[{}] Could not apply the network configuration for the '{}' group: {}",
+                        self.request_id,
+                        tag,
+                        error
+                    ),
+                }
+                let failed = result.is_err();
+                tx.send(result).unwrap();
+                if failed {
+                    return Ok(());
+                }
+                self.refresh_connections_tree(None);
+            }
+            Action::GetLastApplyResult(tx) => {
+                tx.send(self.last_apply_result.clone()).unwrap();
+            }
+            Action::GetLastError(uuid, tx) => {
+                tx.send(self.last_errors.get(&uuid).cloned()).unwrap();
+            }
+            Action::SyncConnections => {
+                let changes = self.sync_connections().await?;
+                if !changes.is_empty() {
+                    self.refresh_connections_tree(Some(changes));
+                }
+            }
+            Action::Refresh(tx) => {
+                let changes = match self.refresh().await {
+                    Ok(changes) => changes,
+                    Err(error) => {
+                        tx.send(Err(error)).unwrap();
+                        return Ok(());
+                    }
+                };
+                let result = {
+                    let mut tree = self.tree.lock().await;
+                    tree.set_devices(&self.state.visible_devices())
+                        .await
+                        .map_err(NetworkAdapterError::Read)
+                };
+                tx.send(result).unwrap();
+                self.refresh_connections_tree(Some(changes));
+            }
+            Action::GetConflictPolicy(tx) => {
+                tx.send(self.conflict_policy).unwrap();
+            }
+            Action::SetConflictPolicy(policy, tx) => {
+                self.conflict_policy = policy;
+                // resolve the conflicts again keeping the pending changes
+                let changes = match self.sync_connections().await {
+                    Ok(changes) => changes,
+                    Err(error) => {
+                        tx.send(Err(error)).unwrap();
+                        return Ok(());
                     }
-                });
+                };
+                tx.send(Ok(())).unwrap();
+                self.refresh_connections_tree(Some(changes));
+            }
+            Action::GetIdsMapping(tx) => {
+                tx.send(self.ids_mapping.clone()).unwrap();
+            }
+            Action::GetTeamMigrations(tx) => {
+                tx.send(self.state.team_migrations()).unwrap();
+            }
+            Action::GetIssues(tx) => {
+                let mut issues = self.state.issues();
+                issues.extend(
+                    self.last_apply_result
+                        .conflicts
+                        .iter()
+                        .map(ConfigIssue::from),
+                );
+                tx.send(issues).unwrap();
+            }
+            Action::VerifyBond(id, tx) => {
+                let result = self.verify_bond_action(&id).await;
+                tx.send(result).unwrap();
+            }
+            #[cfg(feature = "network-debug")]
+            Action::DumpState(tx) => {
+                tx.send(self.dump_state()?).unwrap();
+            }
+            #[cfg(feature = "network-debug")]
+            Action::DumpActions(tx) => {
+                let actions = self
+                    .actions_history
+                    .iter()
+                    .map(|(id, name)| (id.to_string(), name.to_string()))
+                    .collect();
+                tx.send(actions).unwrap();
             }
         }
 
         Ok(())
     }
 
+    /// Keeps track of the processed action, forgetting the oldest ones.
+    ///
+    /// * `request_id`: request the action comes from.
+    /// * `name`: action name.
+    #[cfg(feature = "network-debug")]
+    fn record_action(&mut self, request_id: RequestId, name: &'static str) {
+        if self.actions_history.len() == ACTIONS_HISTORY_SIZE {
+            self.actions_history.pop_front();
+        }
+        self.actions_history.push_back((request_id, name));
+    }
+
+    /// Returns a JSON representation of the network state.
+    ///
+    /// The secrets (e.g., wireless passwords) are not included.
+    #[cfg(feature = "network-debug")]
+    fn dump_state(&self) -> Result<String, serde_json::Error> {
+        let connections: Vec<_> = self
+            .state
+            .connections
+            .iter()
+            .map(|c| {
+                let mut conn = Connection::clone(c);
+                conn.remove_secrets();
+                conn
+            })
+            .collect();
+        let devices: Vec<_> = self
+            .state
+            .devices
+            .iter()
+            .map(|d| {
+                serde_json::json!({
+                    "name": d.name,
+                    "type": d.type_.to_string(),
+                    "managed": d.managed,
+                })
+            })
+            .collect();
+        let dump = serde_json::json!({
+            "connections": connections,
+            "devices": devices,
+            "ignoredDevices": self.state.ignored_devices,
+            "regulatoryDomain": self.state.regulatory_domain,
+            "hostname": self.state.hostname,
+            "dns": {
+                "servers": self.state.dns.servers,
+                "searches": self.state.dns.searches,
+                "policy": self.state.dns.policy,
+            },
+            "conflictPolicy": self.conflict_policy.to_string(),
+            "addrGenMode": self.state.addr_gen_mode,
+            "bandwidthLimit": self.state.bandwidth_limit,
+            "connectionsGeneration": self.connections_generation,
+            "lastApply": self.last_apply_result.connections,
+        });
+        serde_json::to_string_pretty(&dump)
+    }
+
+    /// Re-creates the connections in the D-Bus tree.
+    ///
+    /// * `changes`: changes to notify (if any) once the tree is updated.
+    fn refresh_connections_tree(&self, changes: Option<ConnectionsChanges>) {
+        // TODO: re-creating the tree is kind of brute-force and it sends signals about
+        // adding/removing interfaces. We should add/update/delete objects as needed.
+        // NOTE updating the tree at the same time than dispatching actions can cause a
+        // deadlock. We might consider using message passing too but at this point
+        // is enough to use a separate task.
+        let connections = self.state.connections.clone();
+        let tree = Arc::clone(&self.tree);
+        let request_id = self.request_id;
+        tokio::spawn(async move {
+            let mut tree = tree.lock().await;
+            if let Err(e) = tree.set_connections(&connections).await {
+                log::error!("[{}] Could not update the D-Bus tree: {}", request_id, e);
+                return;
+            }
+            if let Some(changes) = changes {
+                tree.notify_connections_changes(&changes, &connections);
+            }
+        });
+    }
+
     async fn add_connection_action(
         &mut self,
         name: String,
@@ -155,6 +641,83 @@ impl<T: Adapter> NetworkSystem<T> {
         Ok(path)
     }
 
+    async fn add_connections_action(
+        &mut self,
+        template: &ConnectionTemplate,
+        interfaces: &[String],
+    ) -> Result<Vec<(String, OwnedObjectPath)>, NetworkStateError> {
+        let conns = self
+            .state
+            .add_connections_from_template(template, interfaces)?;
+        let mut tree = self.tree.lock().await;
+        let mut paths = Vec::with_capacity(conns.len());
+        for conn in conns {
+            let path = tree
+                .add_connection(&conn)
+                .await
+                .expect("Could not update the D-Bus tree");
+            paths.push((conn.id, path));
+        }
+        Ok(paths)
+    }
+
+    async fn import_connections_action(
+        &mut self,
+        conns: Vec<Connection>,
+    ) -> Result<Vec<(String, OwnedObjectPath)>, NetworkStateError> {
+        self.state.add_connections(conns.clone())?;
+        let mut tree = self.tree.lock().await;
+        let mut paths = Vec::with_capacity(conns.len());
+        for conn in conns {
+            let path = tree
+                .add_connection(&conn)
+                .await
+                .expect("Could not update the D-Bus tree");
+            paths.push((conn.id, path));
+        }
+        Ok(paths)
+    }
+
+    async fn clone_connection_action(
+        &mut self,
+        source_id: &str,
+        new_id: &str,
+        with_secrets: bool,
+    ) -> Result<OwnedObjectPath, NetworkStateError> {
+        let conn = self
+            .state
+            .clone_connection(source_id, new_id, with_secrets)?
+            .clone();
+        let mut tree = self.tree.lock().await;
+        let path = tree
+            .add_connection(&conn)
+            .await
+            .expect("Could not update the D-Bus tree");
+        Ok(path)
+    }
+
+    async fn create_from_device_action(
+        &mut self,
+        name: &str,
+        as_static: bool,
+    ) -> Result<OwnedObjectPath, NetworkStateError> {
+        let runtime = self
+            .adapter
+            .device_ip_config(name)
+            .await
+            .map_err(|e| NetworkStateError::AdapterError(e.to_string()))?;
+        let conn = self
+            .state
+            .add_connection_from_device(name, runtime, as_static)?
+            .clone();
+        let mut tree = self.tree.lock().await;
+        let path = tree
+            .add_connection(&conn)
+            .await
+            .expect("Could not update the D-Bus tree");
+        Ok(path)
+    }
+
     fn set_ports_action(
         &mut self,
         uuid: Uuid,
@@ -187,6 +750,118 @@ impl<T: Adapter> NetworkSystem<T> {
         Ok((conn, controlled))
     }
 
+    async fn verify_bond_action(&self, id: &str) -> Result<BondCheck, NetworkStateError> {
+        let conn = self
+            .state
+            .get_connection(id)
+            .ok_or(NetworkStateError::UnknownConnection(id.to_string()))?;
+        if !matches!(conn.config, ConnectionConfig::Bond(_)) {
+            return Err(NetworkStateError::NotBondConnection(id.to_string()));
+        }
+
+        let interface = conn.interface.as_deref().unwrap_or(&conn.id);
+        let ports = self
+            .adapter
+            .bond_ports(interface)
+            .await
+            .map_err(|e| NetworkStateError::AdapterError(e.to_string()))?;
+        Ok(BondCheck { ports })
+    }
+
+    async fn get_connections_summary_action(&self) -> Vec<(ConnectionSummary, OwnedObjectPath)> {
+        let tree = self.tree.lock().await;
+        let mut summary: Vec<_> = self
+            .state
+            .connections
+            .iter()
+            .filter(|c| !c.is_removed())
+            .filter_map(|c| Some((c.summary(), tree.connection_path(c.uuid)?)))
+            .collect();
+        summary.sort_by(|(_, a), (_, b)| a.as_str().cmp(b.as_str()));
+        summary
+    }
+
+    /// Returns a page of the connections summary.
+    ///
+    /// * `offset`: number of connections to skip.
+    /// * `limit`: maximum number of connections to return (0 means no limit).
+    /// * `token`: change token from a previous page, if any.
+    async fn get_connections_page_action(
+        &self,
+        offset: usize,
+        limit: usize,
+        token: Option<String>,
+    ) -> Result<ConnectionsPage, NetworkStateError> {
+        let current = self.change_token();
+        if let Some(token) = token.filter(|t| *t != current) {
+            return Err(NetworkStateError::OutdatedChangeToken(token));
+        }
+
+        let summary = self.get_connections_summary_action().await;
+        let total = summary.len();
+        let limit = if limit == 0 { usize::MAX } else { limit };
+        let items = summary.into_iter().skip(offset).take(limit).collect();
+        Ok(ConnectionsPage {
+            token: current,
+            total,
+            items,
+        })
+    }
+
+    /// Returns a token identifying the current state of the connections.
+    fn change_token(&self) -> String {
+        format!(
+            "{}-{}",
+            self.instance_id.simple(),
+            self.connections_generation
+        )
+    }
+
+    /// Returns the UUIDs of the active connections which carry a network session.
+    ///
+    /// A connection carries a session when its interface holds the local address of an
+    /// established TCP session (e.g., SSH or the web UI).
+    async fn get_session_connections_action(&self) -> Vec<Uuid> {
+        let addresses = session::local_addresses();
+        if addresses.is_empty() {
+            return vec![];
+        }
+
+        let mut uuids = vec![];
+        for conn in &self.state.connections {
+            let Some(interface) = &conn.interface else {
+                continue;
+            };
+            if conn.is_removed() || !conn.is_up() {
+                continue;
+            }
+            let Ok(config) = self.adapter.device_ip_config(interface).await else {
+                continue;
+            };
+            if config
+                .addresses
+                .iter()
+                .any(|a| addresses.contains(&a.address()))
+            {
+                uuids.push(conn.uuid);
+            }
+        }
+        uuids
+    }
+
+    /// Returns the D-Bus paths of the connections with the given tag.
+    ///
+    /// * `tag`: tag to search for.
+    async fn get_connections_by_tag_action(&self, tag: &str) -> Vec<OwnedObjectPath> {
+        let tree = self.tree.lock().await;
+        self.state
+            .connections
+            .iter()
+            .filter(|c| !c.is_removed() && c.has_tag(tag))
+            .filter_map(|c| tree.connection_path(c.uuid))
+            .collect()
+    }
+
     async fn get_connection_path_by_id_action(&mut self, id: &str) -> Option<OwnedObjectPath> {
         let conn = self.state.get_connection(id)?;
         let tree = self.tree.lock().await;
@@ -0,0 +1,113 @@
+//! Support for waiting for the network when booting the installed system.
+//!
+//! By default, `NetworkManager-wait-online.service` waits for all the devices that have a
+//! connection, so an unplugged port delays the services that need the network. The devices whose
+//! connections are not [required for online](crate::network::model::Connection::required_for_online)
+//! are listed in a NetworkManager configuration snippet which sets them to not wait for the
+//! carrier. The snippet is not applied to the live system: it is copied to the installed one.
+use super::model::NetworkState;
+use std::{collections::BTreeSet, fs, io, path::Path};
+
+/// NetworkManager configuration snippet for the installed system.
+pub const TARGET_WAIT_ONLINE_CONF_PATH: &str = "/run/agama/network/90-agama-wait-online.conf";
+
+/// Writes the wait-online settings for the installed system.
+///
+/// The file is removed if all the connections are required, so NetworkManager's defaults apply.
+///
+/// * `network`: network state to take the connections from.
+pub fn write(network: &NetworkState) -> io::Result<()> {
+    let path = Path::new(TARGET_WAIT_ONLINE_CONF_PATH);
+    let interfaces = optional_interfaces(network);
+    if interfaces.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, wait_online_to_conf(&interfaces))
+}
+
+/// Returns the sorted names of the interfaces the installed system should not wait for.
+///
+/// An interface is optional only when none of its connections is required.
+///
+/// * `network`: network state to take the connections from.
+fn optional_interfaces(network: &NetworkState) -> Vec<String> {
+    let mut optional = BTreeSet::new();
+    let mut required = BTreeSet::new();
+    for conn in &network.connections {
+        if conn.is_removed() || conn.is_loopback() {
+            continue;
+        }
+        let Some(interface) = &conn.interface else {
+            continue;
+        };
+        if conn.required_for_online {
+            required.insert(interface.as_str());
+        } else {
+            optional.insert(interface.as_str());
+        }
+    }
+    optional
+        .difference(&required)
+        .map(|i| i.to_string())
+        .collect()
+}
+
+/// Renders the NetworkManager configuration for the optional interfaces.
+///
+/// * `interfaces`: names of the optional interfaces.
+fn wait_online_to_conf(interfaces: &[String]) -> String {
+    let devices: Vec<_> = interfaces
+        .iter()
+        .map(|i| format!("interface-name:{}", i))
+        .collect();
+    format!(
+        "[device-agama-optional]\nmatch-device={}\ncarrier-wait-timeout=0\n",
+        devices.join(",")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::model::Connection;
+    use agama_lib::network::types::DeviceType;
+
+    fn connection(id: &str, interface: &str, required: bool) -> Connection {
+        let mut conn = Connection::new(id.to_string(), DeviceType::Ethernet);
+        conn.interface = Some(interface.to_string());
+        conn.required_for_online = required;
+        conn
+    }
+
+    #[test]
+    fn test_optional_interfaces() {
+        let connections = vec![
+            connection("eth0", "eth0", true),
+            connection("eth2", "eth2", false),
+            connection("eth1", "eth1", false),
+            connection("eth0-backup", "eth0", false),
+        ];
+        let network = NetworkState::new(vec![], connections);
+        assert_eq!(optional_interfaces(&network), vec!["eth1", "eth2"]);
+
+        let network = NetworkState::new(vec![], vec![connection("eth0", "eth0", true)]);
+        assert!(optional_interfaces(&network).is_empty());
+    }
+
+    #[test]
+    fn test_wait_online_to_conf() {
+        let interfaces = vec!["eth1".to_string(), "eth2".to_string()];
+        assert_eq!(
+            wait_online_to_conf(&interfaces),
+            "[device-agama-optional]\nmatch-device=interface-name:eth1,interface-name:eth2\n\
+             carrier-wait-timeout=0\n"
+        );
+    }
+}
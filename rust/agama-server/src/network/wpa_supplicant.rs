@@ -0,0 +1,376 @@
+//! Import of the wireless networks defined in a wpa_supplicant configuration file.
+//!
+//! Systems which predate NetworkManager (or which are configured by hand) usually define their
+//! wireless networks in `/etc/wpa_supplicant/wpa_supplicant.conf`, using one block per network:
+//!
+//! ```text
+//! network={
+//!     ssid="agama"
+//!     psk="secret"
+//!     key_mgmt=WPA-PSK
+//! }
+//! ```
+//!
+//! Each block is converted into a wireless [Connection] which uses automatic IP configuration. The
+//! global options (e.g., `ctrl_interface` or `country`) and the unsupported keys are ignored, as
+//! well as the disabled networks.
+use super::{
+    error::NetworkStateError,
+    model::{
+        Connection, ConnectionConfig, Ipv4Method, Ipv6Method, SecurityProtocol, WEPAuthAlg,
+        WEPKeyType, WEPSecurity, WirelessConfig, WirelessMode,
+    },
+};
+use agama_lib::network::types::{DeviceType, SSID};
+use std::{collections::HashMap, fs, path::Path, str::FromStr};
+
+/// Default location of the wpa_supplicant configuration.
+pub const WPA_SUPPLICANT_CONF_PATH: &str = "/etc/wpa_supplicant/wpa_supplicant.conf";
+
+/// Maximum number of WEP keys.
+const WEP_KEYS: usize = 4;
+
+/// Network block, as a map of keys and values.
+type NetworkBlock = HashMap<String, String>;
+
+/// Reads the wireless connections from a wpa_supplicant configuration file.
+///
+/// * `path`: path of the configuration file.
+pub fn read(path: &Path) -> Result<Vec<Connection>, NetworkStateError> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        NetworkStateError::InvalidWpaSupplicantConf(format!("{}: {}", path.display(), e))
+    })?;
+    parse(&content)
+}
+
+/// Parses the wireless connections from the content of a wpa_supplicant configuration file.
+///
+/// The connections are named after the `id_str` key or, if it is not set, after the SSID. When
+/// the same name is used several times, a suffix is added (e.g., "agama (2)").
+///
+/// * `content`: content of the configuration file.
+pub fn parse(content: &str) -> Result<Vec<Connection>, NetworkStateError> {
+    let mut conns: Vec<Connection> = vec![];
+    for block in network_blocks(content)? {
+        if block.get("disabled").is_some_and(|d| d != "0") {
+            log::info!("Skipping a disabled wireless network");
+            continue;
+        }
+        let mut conn = connection_from_block(&block)?;
+        let name = conn.id.clone();
+        let mut index = 1;
+        while conns.iter().any(|c| c.id == conn.id) {
+            index += 1;
+            conn.id = format!("{} ({})", name, index);
+        }
+        conns.push(conn);
+    }
+    Ok(conns)
+}
+
+/// Splits the configuration into network blocks.
+///
+/// * `content`: content of the configuration file.
+fn network_blocks(content: &str) -> Result<Vec<NetworkBlock>, NetworkStateError> {
+    let invalid = |number: usize, line: &str| {
+        NetworkStateError::InvalidWpaSupplicantConf(format!("line {}: '{}'", number + 1, line))
+    };
+
+    let mut blocks = vec![];
+    let mut current: Option<NetworkBlock> = None;
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some(block) = current.as_mut() else {
+            let compact: String = line.split_whitespace().collect();
+            if compact == "network={" {
+                current = Some(NetworkBlock::new());
+            }
+            continue;
+        };
+
+        if line == "}" {
+            blocks.extend(current.take());
+            continue;
+        }
+        let (key, value) = line.split_once('=').ok_or_else(|| invalid(number, line))?;
+        block.insert(key.trim().to_string(), value.trim().to_string());
+    }
+
+    if current.is_some() {
+        return Err(NetworkStateError::InvalidWpaSupplicantConf(
+            "unterminated network block".to_string(),
+        ));
+    }
+    Ok(blocks)
+}
+
+/// Builds a wireless connection from a network block.
+///
+/// * `block`: network block.
+fn connection_from_block(block: &NetworkBlock) -> Result<Connection, NetworkStateError> {
+    let ssid = block.get("ssid").ok_or_else(|| {
+        NetworkStateError::InvalidWpaSupplicantConf("network block without SSID".to_string())
+    })?;
+    let ssid = parse_ssid(ssid)?;
+
+    let mut config = WirelessConfig {
+        ssid: ssid.clone(),
+        security: security_protocol(block)?,
+        hidden: block.get("scan_ssid").is_some_and(|s| s == "1"),
+        ..Default::default()
+    };
+    if let Some(mode) = block.get("mode") {
+        config.mode = match mode.as_str() {
+            "0" => WirelessMode::Infra,
+            "1" => WirelessMode::AdHoc,
+            "2" => WirelessMode::AP,
+            "5" => WirelessMode::Mesh,
+            _ => return Err(NetworkStateError::InvalidWirelessMode(mode.to_string())),
+        };
+    }
+    if let Some(bssid) = block.get("bssid") {
+        config.bssid = Some(bssid.parse().map_err(|_| {
+            NetworkStateError::InvalidWpaSupplicantConf(format!("invalid BSSID '{}'", bssid))
+        })?);
+    }
+    config.password = match config.security {
+        SecurityProtocol::WPA2Enterprise
+        | SecurityProtocol::WPA3Only
+        | SecurityProtocol::DynamicWEP => block.get("password").map(|p| unquote(p)),
+        _ => block.get("psk").map(|p| unquote(p)),
+    };
+    config.wep_security = wep_security(block)?;
+
+    let id = match block.get("id_str") {
+        Some(id) => unquote(id),
+        None => String::from_utf8_lossy(&ssid.0).to_string(),
+    };
+    let mut conn = Connection::new(id, DeviceType::Wireless);
+    conn.ip_config.method4 = Ipv4Method::Auto;
+    conn.ip_config.method6 = Ipv6Method::Auto;
+    conn.config = ConnectionConfig::Wireless(config);
+    Ok(conn)
+}
+
+/// Parses an SSID as written by wpa_supplicant: a (printf-escaped) quoted string or the bytes in
+/// hexadecimal.
+///
+/// * `value`: SSID value.
+fn parse_ssid(value: &str) -> Result<SSID, NetworkStateError> {
+    let result = if value.starts_with('"') || value.starts_with("P\"") {
+        SSID::from_str(value)
+    } else {
+        SSID::from_str(&format!("hex:{}", value))
+    };
+    result.map_err(|e| NetworkStateError::InvalidWpaSupplicantConf(e.to_string()))
+}
+
+/// Determines the security protocol from the `key_mgmt` key.
+///
+/// When several methods are allowed, the most compatible one is used (e.g., WPA2 when both WPA2
+/// and WPA3 are allowed). If the key is missing, wpa_supplicant allows WPA-PSK and WPA-EAP, so it
+/// depends on whether a pre-shared key is given.
+///
+/// * `block`: network block.
+fn security_protocol(block: &NetworkBlock) -> Result<SecurityProtocol, NetworkStateError> {
+    let Some(key_mgmt) = block.get("key_mgmt") else {
+        return Ok(if block.contains_key("psk") {
+            SecurityProtocol::WPA2
+        } else {
+            SecurityProtocol::WPA2Enterprise
+        });
+    };
+
+    let methods: Vec<&str> = key_mgmt.split_whitespace().collect();
+    let has = |names: &[&str]| methods.iter().any(|m| names.contains(m));
+    let protocol = if has(&["WPA-PSK", "WPA-PSK-SHA256", "FT-PSK"]) {
+        SecurityProtocol::WPA2
+    } else if has(&["SAE", "FT-SAE"]) {
+        SecurityProtocol::WPA3Personal
+    } else if has(&["WPA-EAP", "WPA-EAP-SHA256", "FT-EAP"]) {
+        SecurityProtocol::WPA2Enterprise
+    } else if has(&["WPA-EAP-SUITE-B", "WPA-EAP-SUITE-B-192"]) {
+        SecurityProtocol::WPA3Only
+    } else if has(&["OWE"]) {
+        SecurityProtocol::OWE
+    } else if has(&["IEEE8021X"]) {
+        SecurityProtocol::DynamicWEP
+    } else if has(&["NONE"]) {
+        SecurityProtocol::WEP
+    } else {
+        return Err(NetworkStateError::InvalidSecurityProtocol(
+            key_mgmt.to_string(),
+        ));
+    };
+    Ok(protocol)
+}
+
+/// Reads the WEP keys (`wep_key0` to `wep_key3`), if any.
+///
+/// * `block`: network block.
+fn wep_security(block: &NetworkBlock) -> Result<Option<WEPSecurity>, NetworkStateError> {
+    let keys: Vec<(usize, String)> = (0..WEP_KEYS)
+        .filter_map(|i| block.get(&format!("wep_key{}", i)).map(|k| (i, unquote(k))))
+        .collect();
+    if keys.is_empty() {
+        return Ok(None);
+    }
+
+    let tx_index: usize = match block.get("wep_tx_keyidx") {
+        Some(index) => index
+            .parse()
+            .ok()
+            .filter(|i| *i < WEP_KEYS)
+            .ok_or_else(|| {
+                NetworkStateError::InvalidWpaSupplicantConf(format!(
+                    "invalid WEP key index '{}'",
+                    index
+                ))
+            })?,
+        None => 0,
+    };
+    // the keys are stored without gaps, so the index is adjusted
+    let wep_key_index = keys.iter().position(|(i, _)| *i == tx_index).unwrap_or(0);
+    let auth_alg = match block.get("auth_alg").map(String::as_str) {
+        Some("SHARED") => WEPAuthAlg::Shared,
+        Some("LEAP") => WEPAuthAlg::Leap,
+        Some(_) => WEPAuthAlg::Open,
+        None => WEPAuthAlg::Unset,
+    };
+
+    Ok(Some(WEPSecurity {
+        auth_alg,
+        wep_key_type: WEPKeyType::Key,
+        keys: keys.into_iter().map(|(_, k)| k).collect(),
+        wep_key_index: wep_key_index as u32,
+    }))
+}
+
+/// Removes the quotes around a value, if any.
+///
+/// * `value`: value to unquote.
+fn unquote(value: &str) -> String {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONF: &str = r#"
+ctrl_interface=/var/run/wpa_supplicant
+country=DE
+
+# home network
+network={
+    ssid="agama"
+    psk="agama.test"
+    key_mgmt=WPA-PSK
+    scan_ssid=1
+}
+
+network={
+    ssid=6167616d61
+    key_mgmt=SAE
+    psk="other"
+    bssid=00:11:22:33:44:55
+}
+
+network={
+    ssid="office"
+    id_str="work"
+    key_mgmt=WPA-EAP
+    identity="jane"
+    password="s3cr3t"
+}
+
+network = {
+    ssid="legacy"
+    key_mgmt=NONE
+    wep_key1="abcde"
+    wep_key2="fghij"
+    wep_tx_keyidx=2
+    auth_alg=SHARED
+}
+
+network={
+    ssid="old"
+    disabled=1
+}
+"#;
+
+    fn wireless(conn: &Connection) -> &WirelessConfig {
+        match &conn.config {
+            ConnectionConfig::Wireless(config) => config,
+            _ => panic!("Not a wireless connection"),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let conns = parse(CONF).unwrap();
+        let ids: Vec<_> = conns.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["agama", "agama (2)", "work", "legacy"]);
+
+        let agama = &conns[0];
+        assert_eq!(agama.ip_config.method4, Ipv4Method::Auto);
+        let config = wireless(agama);
+        assert_eq!(config.ssid, SSID(b"agama".to_vec()));
+        assert_eq!(config.password, Some("agama.test".to_string()));
+        assert_eq!(config.security, SecurityProtocol::WPA2);
+        assert!(config.hidden);
+
+        let config = wireless(&conns[1]);
+        assert_eq!(config.ssid, SSID(b"agama".to_vec()));
+        assert_eq!(config.security, SecurityProtocol::WPA3Personal);
+        assert_eq!(config.bssid.unwrap().to_string(), "00:11:22:33:44:55");
+
+        let config = wireless(&conns[2]);
+        assert_eq!(config.security, SecurityProtocol::WPA2Enterprise);
+        assert_eq!(config.password, Some("s3cr3t".to_string()));
+
+        let config = wireless(&conns[3]);
+        assert_eq!(config.security, SecurityProtocol::WEP);
+        let wep = config.wep_security.as_ref().unwrap();
+        assert_eq!(wep.keys, vec!["abcde", "fghij"]);
+        assert_eq!(wep.wep_key_index, 1);
+        assert_eq!(wep.auth_alg, WEPAuthAlg::Shared);
+    }
+
+    #[test]
+    fn test_security_protocol() {
+        let block = |key_mgmt: &str| NetworkBlock::from([("key_mgmt".into(), key_mgmt.into())]);
+        assert_eq!(
+            security_protocol(&block("SAE WPA-PSK")).unwrap(),
+            SecurityProtocol::WPA2
+        );
+        assert_eq!(
+            security_protocol(&block("OWE")).unwrap(),
+            SecurityProtocol::OWE
+        );
+        assert!(security_protocol(&block("UNKNOWN")).is_err());
+
+        let block = NetworkBlock::from([("psk".into(), "\"secret\"".into())]);
+        assert_eq!(security_protocol(&block).unwrap(), SecurityProtocol::WPA2);
+        assert_eq!(
+            security_protocol(&NetworkBlock::new()).unwrap(),
+            SecurityProtocol::WPA2Enterprise
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse("network={\n  ssid=\"agama\"\n").is_err());
+        assert!(parse("network={\n  psk=\"agama\"\n}\n").is_err());
+        assert!(parse("network={\n  ssid\n}\n").is_err());
+        assert!(parse("network={\n  ssid=zz\n}\n").is_err());
+    }
+}
@@ -0,0 +1,122 @@
+//! Minimal support for checking authorizations through [polkit](https://www.freedesktop.org/software/polkit/docs/latest/).
+//!
+//! Agama services are exposed on a private bus, so the D-Bus sender cannot be used as a polkit
+//! subject. Instead, the process ID and the user ID of the caller are retrieved from the bus and
+//! checked against the polkit authority running on the system bus. The start time of the process
+//! is included too, so polkit can detect whether the process ID has been reused (see
+//! CVE-2013-4288).
+use std::{collections::HashMap, fs};
+use zbus::{
+    dbus_proxy,
+    fdo::{self, DBusProxy},
+    names::BusName,
+    zvariant::Value,
+    MessageHeader,
+};
+
+/// Action to read the secrets (e.g., wireless passwords) from the network service.
+pub const READ_NETWORK_SECRETS: &str = "org.opensuse.Agama1.Network.ReadSecrets";
+
+#[dbus_proxy(
+    interface = "org.freedesktop.PolicyKit1.Authority",
+    default_service = "org.freedesktop.PolicyKit1",
+    default_path = "/org/freedesktop/PolicyKit1/Authority"
+)]
+trait Authority {
+    /// CheckAuthorization method
+    fn check_authorization(
+        &self,
+        subject: &(&str, HashMap<&str, Value<'_>>),
+        action_id: &str,
+        details: HashMap<&str, &str>,
+        flags: u32,
+        cancellation_id: &str,
+    ) -> zbus::Result<(bool, bool, HashMap<String, String>)>;
+}
+
+/// Checks whether the sender of a message is authorized to perform the given action.
+///
+/// It returns an `AccessDenied` error if the caller is not authorized.
+///
+/// * `connection`: connection the message was received on.
+/// * `header`: message header.
+/// * `action_id`: polkit action ID.
+pub async fn check_authorization(
+    connection: &zbus::Connection,
+    header: &MessageHeader<'_>,
+    action_id: &str,
+) -> fdo::Result<()> {
+    let Some(sender) = header.sender()? else {
+        return Err(fdo::Error::AccessDenied("Unknown sender".to_string()));
+    };
+
+    let dbus = DBusProxy::new(connection).await?;
+    let sender = BusName::from(sender.to_owned());
+    let pid = dbus.get_connection_unix_process_id(sender.clone()).await?;
+    let uid = dbus.get_connection_unix_user(sender).await?;
+    let Some(start_time) = process_start_time(pid) else {
+        return Err(fdo::Error::AccessDenied(format!(
+            "Could not determine the start time of process {pid}"
+        )));
+    };
+
+    let system = zbus::Connection::system().await?;
+    let authority = AuthorityProxy::new(&system).await?;
+    let subject = (
+        "unix-process",
+        HashMap::from([
+            ("pid", Value::from(pid)),
+            ("start-time", Value::from(start_time)),
+            ("uid", Value::from(uid as i32)),
+        ]),
+    );
+    let (authorized, _, _) = authority
+        .check_authorization(&subject, action_id, HashMap::new(), 0, "")
+        .await?;
+
+    if authorized {
+        Ok(())
+    } else {
+        log::warn!(
+            "Process {} is not authorized to perform '{}'",
+            pid,
+            action_id
+        );
+        Err(fdo::Error::AccessDenied(format!(
+            "Not authorized to perform '{action_id}'"
+        )))
+    }
+}
+
+/// Returns the start time of a process (in clock ticks since boot).
+///
+/// * `pid`: process ID.
+fn process_start_time(pid: u32) -> Option<u64> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    start_time_from_stat(&stat)
+}
+
+/// Extracts the start time from the content of a `/proc/<pid>/stat` file.
+///
+/// The process name (2nd field) might contain spaces or parenthesis, so the fields are counted
+/// from the last closing parenthesis (see proc(5)).
+///
+/// * `stat`: content of the stat file.
+fn start_time_from_stat(stat: &str) -> Option<u64> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    // the start time is the 22nd field and the remaining ones start at the 3rd
+    fields.split_whitespace().nth(19)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::start_time_from_stat;
+
+    #[test]
+    fn test_start_time_from_stat() {
+        let stat = "1234 (agama (web) x) S 1 1234 1234 0 -1 4194560 1000 0 0 0 10 5 0 0 20 0 \
+                    1 0 987654 12345678 1024 18446744073709551615 1 1 0 0 0 0 0 0 0 0 0 0 17 0";
+        assert_eq!(start_time_from_stat(stat), Some(987654));
+        assert_eq!(start_time_from_stat("1234 (agama) S 1"), None);
+    }
+}
@@ -0,0 +1,57 @@
+//! Support for the s390 I/O devices
+//!
+//! On mainframes, the I/O devices (DASDs, zFCP adapters, network cards, etc.) are attached
+//! through channels and they must be made available before the network or the storage can be
+//! configured. This module exposes the `/org/opensuse/Agama1/S390` object, which implements the
+//! `org.opensuse.Agama1.S390` interface to:
+//!
+//! * List the channel devices (see [ChannelDevice]) and the ignored ones (the `cio_ignore` list).
+//! * Remove devices from the `cio_ignore` list, so the kernel senses them.
+//! * Activate and deactivate the devices (using `chzdev`), persisting the configuration.
+//!
+//! The object is only exported on s390 systems.
+
+mod dbus;
+mod devices;
+
+pub use dbus::S390;
+pub use devices::ChannelDevice;
+
+use crate::access::AccessPolicy;
+use thiserror::Error;
+use zbus::Connection;
+
+/// Errors related to the s390 devices.
+#[derive(Error, Debug)]
+pub enum S390Error {
+    #[error("Invalid device ID: '{0}'")]
+    InvalidDeviceId(String),
+    #[error("Could not run '{0}': {1}")]
+    CommandNotRun(String, std::io::Error),
+    #[error("'{0}' failed: {1}")]
+    CommandFailed(String, String),
+}
+
+impl From<S390Error> for zbus::fdo::Error {
+    fn from(value: S390Error) -> zbus::fdo::Error {
+        zbus::fdo::Error::Failed(format!("s390 error: {value}"))
+    }
+}
+
+/// Exports the s390 D-Bus objects if running on an s390 system.
+///
+/// * `connection`: D-Bus connection to use.
+/// * `access`: policy to restrict the operations that modify the system.
+pub async fn export_dbus_objects(
+    connection: &Connection,
+    access: AccessPolicy,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !cfg!(target_arch = "s390x") {
+        log::info!("Not an s390 system, skipping the s390 interface");
+        return Ok(());
+    }
+
+    dbus::export(connection, access).await?;
+    log::info!("Started s390 interface");
+    Ok(())
+}
@@ -0,0 +1,119 @@
+//! D-Bus interface for the s390 devices.
+use super::devices;
+use crate::access::AccessPolicy;
+use zbus::{dbus_interface, Connection, MessageHeader, SignalContext};
+
+const PATH: &str = "/org/opensuse/Agama1/S390";
+
+/// D-Bus interface to activate the s390 channel devices.
+///
+/// The devices are read from the system each time, so the changes done by other tools (e.g.,
+/// the storage service activating a DASD) are taken into account.
+pub struct S390 {
+    access: AccessPolicy,
+}
+
+impl S390 {
+    /// Creates an S390 interface object.
+    ///
+    /// * `access`: policy to restrict the operations that modify the system.
+    pub fn new(access: AccessPolicy) -> Self {
+        Self { access }
+    }
+
+    /// Notifies the changes in the list of devices.
+    ///
+    /// * `ctxt`: signal context to notify the changes.
+    async fn notify_changes(&self, ctxt: &SignalContext<'_>) -> zbus::Result<()> {
+        self.devices_changed(ctxt).await?;
+        self.ignored_devices_changed(ctxt).await
+    }
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.S390")]
+impl S390 {
+    /// Channel devices which are not ignored.
+    ///
+    /// Each element of the list has these parts:
+    ///
+    /// * The bus ID (e.g., "0.0.0150").
+    /// * The device type (e.g., "3390/0c").
+    /// * The control unit type (e.g., "3990/e9").
+    /// * Whether the device is online.
+    #[dbus_interface(property)]
+    fn devices(&self) -> zbus::fdo::Result<Vec<(String, String, String, bool)>> {
+        let devices = devices::devices()?
+            .into_iter()
+            .map(|d| (d.id, d.device_type, d.control_unit, d.online))
+            .collect();
+        Ok(devices)
+    }
+
+    /// Ranges of devices in the `cio_ignore` list (e.g., "0.0.0000-0.0.014f").
+    #[dbus_interface(property)]
+    fn ignored_devices(&self) -> zbus::fdo::Result<Vec<String>> {
+        Ok(devices::ignored_devices()?)
+    }
+
+    /// Removes the given devices from the `cio_ignore` list.
+    ///
+    /// * `ids`: bus IDs of the devices.
+    async fn unignore(
+        &self,
+        ids: Vec<String>,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        devices::unignore(&ids)?;
+        self.notify_changes(&ctxt).await?;
+        Ok(())
+    }
+
+    /// Activates the given devices, removing them from the `cio_ignore` list if needed.
+    ///
+    /// The configuration is persisted, so it is copied to the installed system.
+    ///
+    /// * `ids`: bus IDs of the devices.
+    async fn activate(
+        &self,
+        ids: Vec<String>,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        devices::activate(&ids)?;
+        self.notify_changes(&ctxt).await?;
+        Ok(())
+    }
+
+    /// Deactivates the given devices.
+    ///
+    /// * `ids`: bus IDs of the devices.
+    async fn deactivate(
+        &self,
+        ids: Vec<String>,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.access.check(connection, &header).await?;
+        devices::deactivate(&ids)?;
+        self.notify_changes(&ctxt).await?;
+        Ok(())
+    }
+}
+
+/// Exports the s390 object.
+///
+/// * `connection`: D-Bus connection to use.
+/// * `access`: policy to restrict the operations that modify the system.
+pub async fn export(connection: &Connection, access: AccessPolicy) -> zbus::Result<()> {
+    connection
+        .object_server()
+        .at(PATH, S390::new(access))
+        .await?;
+    Ok(())
+}
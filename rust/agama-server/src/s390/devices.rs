@@ -0,0 +1,238 @@
+//! Channel devices management.
+//!
+//! It relies on the s390-tools commands: `lscss` (to list the devices), `cio_ignore` (to handle
+//! the list of ignored devices) and `chzdev` (to activate and deactivate the devices).
+use super::S390Error;
+use std::process::Command;
+
+/// Channel device.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChannelDevice {
+    /// Bus ID (e.g., "0.0.0150").
+    pub id: String,
+    /// Device type and model (e.g., "3390/0c").
+    pub device_type: String,
+    /// Control unit type and model (e.g., "3990/e9").
+    pub control_unit: String,
+    /// Whether the device is online.
+    pub online: bool,
+}
+
+/// Determines whether the given string is a valid bus ID.
+///
+/// A bus ID has the "<cssid>.<ssid>.<devno>" form, where `cssid` and `ssid` are hexadecimal
+/// digits and `devno` is a 4 digits hexadecimal number (e.g., "0.0.0150").
+///
+/// * `id`: bus ID to check.
+pub fn is_valid_id(id: &str) -> bool {
+    let parts: Vec<&str> = id.split('.').collect();
+    let [cssid, ssid, devno] = parts.as_slice() else {
+        return false;
+    };
+    is_hex(cssid, 1) && is_hex(ssid, 1) && is_hex(devno, 4)
+}
+
+/// Returns the channel devices which are not ignored.
+pub fn devices() -> Result<Vec<ChannelDevice>, S390Error> {
+    let output = run("lscss", &[])?;
+    Ok(devices_from_lscss(&output))
+}
+
+/// Returns the ranges of ignored devices (e.g., "0.0.0000-0.0.014f").
+pub fn ignored_devices() -> Result<Vec<String>, S390Error> {
+    let output = run("cio_ignore", &["--list"])?;
+    Ok(ranges_from_cio_ignore(&output))
+}
+
+/// Removes the given devices from the list of ignored devices.
+///
+/// * `ids`: bus IDs of the devices.
+pub fn unignore(ids: &[String]) -> Result<(), S390Error> {
+    let ids = normalize_ids(ids)?;
+    if ids.is_empty() {
+        return Ok(());
+    }
+    run("cio_ignore", &["--remove", &ids.join(",")])?;
+    Ok(())
+}
+
+/// Activates the given devices, persisting the configuration.
+///
+/// The devices are removed from the list of ignored devices before activating them.
+///
+/// * `ids`: bus IDs of the devices.
+pub fn activate(ids: &[String]) -> Result<(), S390Error> {
+    unignore(ids)?;
+    for id in normalize_ids(ids)? {
+        run("chzdev", &["--enable", "--yes", &id])?;
+    }
+    Ok(())
+}
+
+/// Deactivates the given devices, removing their persistent configuration.
+///
+/// * `ids`: bus IDs of the devices.
+pub fn deactivate(ids: &[String]) -> Result<(), S390Error> {
+    for id in normalize_ids(ids)? {
+        run("chzdev", &["--disable", "--yes", &id])?;
+    }
+    Ok(())
+}
+
+/// Checks and normalizes (lower-case) the given bus IDs.
+///
+/// * `ids`: bus IDs to normalize.
+fn normalize_ids(ids: &[String]) -> Result<Vec<String>, S390Error> {
+    ids.iter()
+        .map(|id| {
+            let id = id.trim().to_lowercase();
+            if is_valid_id(&id) {
+                Ok(id)
+            } else {
+                Err(S390Error::InvalidDeviceId(id))
+            }
+        })
+        .collect()
+}
+
+fn is_hex(value: &str, len: usize) -> bool {
+    value.len() == len && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Runs a command and returns its output.
+///
+/// * `command`: command to run.
+/// * `args`: command arguments.
+fn run(command: &str, args: &[&str]) -> Result<String, S390Error> {
+    let full_command = [&[command], args].concat().join(" ");
+    let output = Command::new(command)
+        .args(args)
+        .output()
+        .map_err(|e| S390Error::CommandNotRun(full_command.clone(), e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(S390Error::CommandFailed(full_command, stderr));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Extracts the devices from the `lscss` output.
+///
+/// The "Use" column is empty when the device is not online, so the columns are located using
+/// the header.
+///
+/// * `output`: command output.
+fn devices_from_lscss(output: &str) -> Vec<ChannelDevice> {
+    let mut lines = output.lines();
+    let Some(use_column) = lines.next().and_then(|header| header.find("Use")) else {
+        return vec![];
+    };
+
+    lines
+        .filter(|l| !l.starts_with('-'))
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let id = columns.next()?;
+            let _subchannel = columns.next()?;
+            let device_type = columns.next()?;
+            let control_unit = columns.next()?;
+            if !is_valid_id(id) {
+                return None;
+            }
+            let online = line
+                .get(use_column..)
+                .is_some_and(|rest| rest.starts_with("yes"));
+            Some(ChannelDevice {
+                id: id.to_string(),
+                device_type: device_type.to_string(),
+                control_unit: control_unit.to_string(),
+                online,
+            })
+        })
+        .collect()
+}
+
+/// Extracts the ranges of ignored devices from the `cio_ignore --list` output.
+///
+/// * `output`: command output.
+fn ranges_from_cio_ignore(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|l| l.split('-').all(is_valid_id))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_id() {
+        assert!(is_valid_id("0.0.0150"));
+        assert!(is_valid_id("0.1.fc00"));
+        assert!(!is_valid_id("0.0.150"));
+        assert!(!is_valid_id("0.0150"));
+        assert!(!is_valid_id("0.0.015g"));
+    }
+
+    #[test]
+    fn test_normalize_ids() {
+        let ids = vec!["0.0.FC00 ".to_string()];
+        assert_eq!(normalize_ids(&ids).unwrap(), vec!["0.0.fc00".to_string()]);
+
+        let ids = vec!["fc00".to_string()];
+        assert!(matches!(
+            normalize_ids(&ids),
+            Err(S390Error::InvalidDeviceId(_))
+        ));
+    }
+
+    #[test]
+    fn test_devices_from_lscss() {
+        let output = "\
+Device   Subchan.  DevType CU Type Use  PIM PAM POM  CHPIDs
+----------------------------------------------------------------------
+0.0.0150 0.0.0000  3390/0c 3990/e9 yes  c0  c0  ff   34400000 00000000
+0.0.0700 0.0.0001  1732/01 1731/01      80  80  ff   00000000 00000000
+";
+        let devices = devices_from_lscss(output);
+        assert_eq!(
+            devices,
+            vec![
+                ChannelDevice {
+                    id: "0.0.0150".to_string(),
+                    device_type: "3390/0c".to_string(),
+                    control_unit: "3990/e9".to_string(),
+                    online: true
+                },
+                ChannelDevice {
+                    id: "0.0.0700".to_string(),
+                    device_type: "1732/01".to_string(),
+                    control_unit: "1731/01".to_string(),
+                    online: false
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ranges_from_cio_ignore() {
+        let output = "\
+Ignored devices:
+=================
+0.0.0000-0.0.014f
+0.0.0151-0.0.06ff
+0.0.ffff
+";
+        assert_eq!(
+            ranges_from_cio_ignore(output),
+            vec![
+                "0.0.0000-0.0.014f".to_string(),
+                "0.0.0151-0.0.06ff".to_string(),
+                "0.0.ffff".to_string()
+            ]
+        );
+    }
+}
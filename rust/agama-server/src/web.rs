@@ -22,7 +22,10 @@ mod service;
 mod state;
 mod ws;
 
-use agama_lib::{connection, error::ServiceError, progress::ProgressMonitor};
+use agama_lib::{
+    connection, connection_manager::ConnectionManager, error::ServiceError,
+    progress::ProgressMonitor,
+};
 pub use auth::generate_token;
 pub use config::ServiceConfig;
 pub use docs::ApiDoc;
@@ -55,7 +58,8 @@ pub async fn service(
 pub async fn run_monitor(events: EventsSender) -> Result<(), ServiceError> {
     let presenter = EventsProgressPresenter::new(events.clone());
     let connection = connection().await?;
-    let mut monitor = ProgressMonitor::new(connection.clone()).await?;
+    let services = ConnectionManager::new(connection.clone()).await?;
+    let mut monitor = ProgressMonitor::with_manager(&services).await?;
     tokio::spawn(async move {
         if let Err(error) = monitor.run(presenter).await {
             eprintln!("Could not monitor the D-Bus server: {}", error);
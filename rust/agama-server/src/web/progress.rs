@@ -37,4 +37,6 @@ impl ProgressPresenter for EventsProgressPresenter {
     }
 
     async fn finish(&mut self) {}
+
+    async fn failed(&mut self, _message: &str) {}
 }
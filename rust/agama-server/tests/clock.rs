@@ -0,0 +1,31 @@
+pub mod common;
+
+use self::common::DBusServer;
+use agama_server::{access::AccessPolicy, clock};
+use std::error::Error;
+use tokio::test;
+
+#[test]
+async fn test_setter_access_denied() -> Result<(), Box<dyn Error>> {
+    let mut server = DBusServer::new().start().await?;
+    // no one is running the tests with this user ID
+    let access = AccessPolicy::new(vec![u32::MAX], vec![]);
+    clock::export_dbus_objects(&server.connection(), access).await?;
+    server.request_name().await?;
+
+    let proxy = zbus::Proxy::new(
+        &server.connection(),
+        "org.opensuse.Agama1",
+        "/org/opensuse/Agama1/Clock",
+        "org.opensuse.Agama1.Clock",
+    )
+    .await?;
+    // reading is not restricted
+    let ntp_server: String = proxy.get_property("NtpServer").await?;
+
+    let result = proxy.set_property("NtpServer", "ntp.example.net").await;
+    assert!(matches!(result, Err(zbus::fdo::Error::AccessDenied(_))));
+    let current: String = proxy.get_property("NtpServer").await?;
+    assert_eq!(current, ntp_server);
+    Ok(())
+}
@@ -0,0 +1,30 @@
+pub mod common;
+
+use self::common::DBusServer;
+use agama_server::{access::AccessPolicy, manager};
+use std::error::Error;
+use tokio::test;
+
+#[test]
+async fn test_probe_access_denied() -> Result<(), Box<dyn Error>> {
+    let mut server = DBusServer::new().start().await?;
+    // no one is running the tests with this user ID
+    let access = AccessPolicy::new(vec![u32::MAX], vec![]);
+    manager::export_dbus_objects(&server.connection(), access).await?;
+    server.request_name().await?;
+
+    let proxy = zbus::Proxy::new(
+        &server.connection(),
+        "org.opensuse.Agama1",
+        "/org/opensuse/Agama1/Manager",
+        "org.opensuse.Agama1.Manager",
+    )
+    .await?;
+    let result: zbus::Result<()> = proxy.call("Probe", &()).await;
+    let error = zbus::fdo::Error::from(result.unwrap_err());
+    assert!(matches!(error, zbus::fdo::Error::AccessDenied(_)));
+
+    let phase: u32 = proxy.get_property("CurrentInstallationPhase").await?;
+    assert_eq!(phase, 0);
+    Ok(())
+}
@@ -1,20 +1,31 @@
 pub mod common;
 
 use self::common::{async_retry, DBusServer};
+use agama_lib::error::ServiceError;
 use agama_lib::network::{
     settings::{self},
     types::DeviceType,
     NetworkClient,
 };
-use agama_server::network::{
-    self,
-    model::{self, Ipv4Method, Ipv6Method},
-    Adapter, NetworkAdapterError, NetworkService, NetworkState,
+use agama_server::{
+    access::AccessPolicy,
+    introspection,
+    network::{
+        self,
+        model::{self, ApplyResult, Ipv4Method, Ipv6Method},
+        Adapter, CancellationFlag, NetworkAdapterError, NetworkService, NetworkState,
+    },
 };
 use async_trait::async_trait;
 use cidr::IpInet;
-use std::error::Error;
+use futures_util::StreamExt;
+use std::{
+    error::Error,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use tokio::test;
+use zbus::{MatchRule, MessageStream, MessageType};
 
 #[derive(Default)]
 pub struct NetworkTestAdapter(network::NetworkState);
@@ -25,8 +36,31 @@ impl Adapter for NetworkTestAdapter {
         Ok(self.0.clone())
     }
 
-    async fn write(&self, _network: &network::NetworkState) -> Result<(), NetworkAdapterError> {
-        unimplemented!("Not used in tests");
+    async fn write(
+        &self,
+        _network: &network::NetworkState,
+        _cancel: &CancellationFlag,
+    ) -> Result<ApplyResult, NetworkAdapterError> {
+        Ok(ApplyResult::default())
+    }
+}
+
+/// Adapter whose state can be changed while the service is running.
+#[derive(Clone, Default)]
+pub struct SharedTestAdapter(Arc<Mutex<network::NetworkState>>);
+
+#[async_trait]
+impl Adapter for SharedTestAdapter {
+    async fn read(&self) -> Result<network::NetworkState, NetworkAdapterError> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+
+    async fn write(
+        &self,
+        _network: &network::NetworkState,
+        _cancel: &CancellationFlag,
+    ) -> Result<ApplyResult, NetworkAdapterError> {
+        Ok(ApplyResult::default())
     }
 }
 
@@ -37,6 +71,9 @@ async fn test_read_connections() -> Result<(), Box<dyn Error>> {
     let device = model::Device {
         name: String::from("eth0"),
         type_: DeviceType::Ethernet,
+        wireless_capabilities: None,
+        modem_capabilities: None,
+        managed: true,
     };
     let eth0 = model::Connection::new("eth0".to_string(), DeviceType::Ethernet);
     let state = NetworkState::new(vec![device], vec![eth0]);
@@ -54,6 +91,70 @@ async fn test_read_connections() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+async fn test_connections_summary() -> Result<(), Box<dyn Error>> {
+    let mut server = DBusServer::new().start().await?;
+
+    let mut eth0 = model::Connection::new("Wired connection 1".to_string(), DeviceType::Ethernet);
+    eth0.interface = Some("eth0".to_string());
+    eth0.ip_config.method4 = Ipv4Method::Auto;
+    let state = NetworkState::new(vec![], vec![eth0]);
+    let adapter = NetworkTestAdapter(state);
+
+    NetworkService::start(&server.connection(), adapter).await?;
+    server.request_name().await?;
+
+    let client = NetworkClient::new(server.connection()).await?;
+    let summary = async_retry(|| client.connections_summary()).await?;
+    assert_eq!(summary.len(), 1);
+    let eth0 = summary.first().unwrap();
+    assert_eq!(eth0.id, "Wired connection 1");
+    assert_eq!(eth0.type_, "ethernet");
+    assert_eq!(eth0.interface, "eth0");
+    assert_eq!(eth0.method4, "auto");
+    assert_eq!(eth0.state, "up");
+    Ok(())
+}
+
+#[test]
+async fn test_connections_page() -> Result<(), Box<dyn Error>> {
+    let mut server = DBusServer::new().start().await?;
+
+    let connections = ["eth0", "eth1", "eth2"]
+        .iter()
+        .map(|id| model::Connection::new(id.to_string(), DeviceType::Ethernet))
+        .collect();
+    let state = NetworkState::new(vec![], connections);
+    let adapter = NetworkTestAdapter(state);
+
+    NetworkService::start(&server.connection(), adapter).await?;
+    server.request_name().await?;
+
+    let client = NetworkClient::new(server.connection()).await?;
+    let first = async_retry(|| client.connections_page(0, 2, None)).await?;
+    assert_eq!(first.total, 3);
+    let ids: Vec<_> = first.connections.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, vec!["eth0", "eth1"]);
+    assert_eq!(client.change_token().await?, first.token);
+
+    let second = client.connections_page(2, 2, Some(&first.token)).await?;
+    assert_eq!(second.token, first.token);
+    let ids: Vec<_> = second.connections.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, vec!["eth2"]);
+
+    let eth3 = settings::NetworkConnection {
+        id: "eth3".to_string(),
+        ..Default::default()
+    };
+    client.add_or_update_connection(&eth3).await?;
+    assert_ne!(client.change_token().await?, first.token);
+    assert!(client
+        .connections_page(2, 2, Some(&first.token))
+        .await
+        .is_err());
+    Ok(())
+}
+
 #[test]
 async fn test_add_connection() -> Result<(), Box<dyn Error>> {
     let mut server = DBusServer::new().start().await?;
@@ -73,10 +174,11 @@ async fn test_add_connection() -> Result<(), Box<dyn Error>> {
         method6: Some("disabled".to_string()),
         addresses: addresses.clone(),
         wireless: Some(settings::WirelessSettings {
-            password: "123456".to_string(),
+            password: Some("123456".to_string()),
             security: "wpa-psk".to_string(),
             ssid: "TEST".to_string(),
             mode: "infrastructure".to_string(),
+            ..Default::default()
         }),
         ..Default::default()
     };
@@ -98,6 +200,45 @@ async fn test_add_connection() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+async fn test_add_connection_by_name() -> Result<(), Box<dyn Error>> {
+    let mut server = DBusServer::new().start().await?;
+
+    let adapter = NetworkTestAdapter(NetworkState::default());
+    NetworkService::start(&server.connection(), adapter).await?;
+    server.request_name().await?;
+
+    let proxy = zbus::Proxy::new(
+        &server.connection(),
+        "org.opensuse.Agama1",
+        "/org/opensuse/Agama1/Network/connections",
+        "org.opensuse.Agama1.Network.Connections",
+    )
+    .await?;
+    let _path: zbus::zvariant::OwnedObjectPath =
+        async_retry(|| proxy.call("AddConnectionByName", &("wlan0", "wireless"))).await?;
+
+    let client = NetworkClient::new(server.connection()).await?;
+    // the wireless interface is exported once the D-Bus tree is refreshed
+    let conn = async_retry(|| async {
+        let conns = client.connections().await?;
+        conns
+            .into_iter()
+            .find(|c| c.device_type() == DeviceType::Wireless)
+            .ok_or(ServiceError::FailedOperation(
+                "not refreshed yet".to_string(),
+            ))
+    })
+    .await?;
+    assert_eq!(conn.id, "wlan0");
+
+    let result: zbus::Result<zbus::zvariant::OwnedObjectPath> = proxy
+        .call("AddConnectionByName", &("eth0", "unknown"))
+        .await;
+    assert!(result.is_err());
+    Ok(())
+}
+
 #[test]
 async fn test_add_bond_connection() -> Result<(), Box<dyn Error>> {
     let mut server = DBusServer::new().start().await?;
@@ -146,6 +287,9 @@ async fn test_update_connection() -> Result<(), Box<dyn Error>> {
     let device = model::Device {
         name: String::from("eth0"),
         type_: DeviceType::Ethernet,
+        wireless_capabilities: None,
+        modem_capabilities: None,
+        managed: true,
     };
     let eth0 = model::Connection::new("eth0".to_string(), DeviceType::Ethernet);
     let state = NetworkState::new(vec![device], vec![eth0]);
@@ -160,8 +304,268 @@ async fn test_update_connection() -> Result<(), Box<dyn Error>> {
 
     let mut dbus_eth0 = async_retry(|| client.get_connection("eth0")).await?;
     dbus_eth0.interface = Some("eth0".to_string());
+    dbus_eth0.required_for_online = Some(false);
     client.add_or_update_connection(&dbus_eth0).await?;
     let dbus_eth0 = client.get_connection("eth0").await?;
     assert_eq!(dbus_eth0.interface, Some("eth0".to_string()));
+    assert_eq!(dbus_eth0.required_for_online, Some(false));
+    Ok(())
+}
+
+#[test]
+async fn test_conflict_policy_keeps_changes() -> Result<(), Box<dyn Error>> {
+    let mut server = DBusServer::new().start().await?;
+
+    let eth0 = model::Connection::new("eth0".to_string(), DeviceType::Ethernet);
+    let adapter = NetworkTestAdapter(NetworkState::new(vec![], vec![eth0]));
+    NetworkService::start(&server.connection(), adapter).await?;
+    server.request_name().await?;
+
+    let client = NetworkClient::new(server.connection()).await?;
+    let mut dbus_eth0 = async_retry(|| client.get_connection("eth0")).await?;
+    dbus_eth0.description = Some("Pending change".to_string());
+    client.add_or_update_connection(&dbus_eth0).await?;
+
+    let proxy = zbus::Proxy::new(
+        &server.connection(),
+        "org.opensuse.Agama1",
+        "/org/opensuse/Agama1/Network/connections",
+        "org.opensuse.Agama1.Network.Connections",
+    )
+    .await?;
+    proxy.set_property("ConflictPolicy", "skip").await?;
+
+    let dbus_eth0 = async_retry(|| client.get_connection("eth0")).await?;
+    assert_eq!(dbus_eth0.description, Some("Pending change".to_string()));
+    Ok(())
+}
+
+#[test]
+async fn test_conflict_policy_replace() -> Result<(), Box<dyn Error>> {
+    let mut server = DBusServer::new().start().await?;
+
+    let first = model::Connection::new("eth0".to_string(), DeviceType::Ethernet);
+    let last = model::Connection::new("eth0".to_string(), DeviceType::Ethernet);
+    let uuid = last.uuid;
+    let adapter = NetworkTestAdapter(NetworkState::new(vec![], vec![first, last]));
+    NetworkService::start(&server.connection(), adapter).await?;
+    server.request_name().await?;
+
+    let proxy = zbus::Proxy::new(
+        &server.connection(),
+        "org.opensuse.Agama1",
+        "/org/opensuse/Agama1/Network/connections",
+        "org.opensuse.Agama1.Network.Connections",
+    )
+    .await?;
+    proxy.set_property("ConflictPolicy", "replace").await?;
+
+    let path: zbus::zvariant::OwnedObjectPath = async_retry(|| async {
+        let path: zbus::zvariant::OwnedObjectPath =
+            proxy.call("GetConnectionById", &("eth0",)).await?;
+        let conn = zbus::Proxy::new(
+            &server.connection(),
+            "org.opensuse.Agama1",
+            path.clone(),
+            "org.opensuse.Agama1.Network.Connection",
+        )
+        .await?;
+        let found: String = conn.get_property("Uuid").await?;
+        if found == uuid.to_string() {
+            Ok(path)
+        } else {
+            Err(zbus::Error::Failure("Not replaced yet".to_string()))
+        }
+    })
+    .await?;
+    assert_eq!(
+        path.as_str(),
+        "/org/opensuse/Agama1/Network/connections/eth0"
+    );
+    Ok(())
+}
+
+#[test]
+async fn test_schema() -> Result<(), Box<dyn Error>> {
+    let mut server = DBusServer::new().start().await?;
+
+    let types = [
+        DeviceType::Ethernet,
+        DeviceType::Bond,
+        DeviceType::Wireless,
+        DeviceType::Pppoe,
+        DeviceType::Modem,
+        DeviceType::Vrf,
+        DeviceType::Macvlan,
+        DeviceType::Ipvlan,
+        DeviceType::Macsec,
+    ];
+    let connections = types
+        .iter()
+        .enumerate()
+        .map(|(i, t)| model::Connection::new(format!("conn{}", i), *t))
+        .collect();
+    let adapter = NetworkTestAdapter(NetworkState::new(vec![], connections));
+    NetworkService::start(&server.connection(), adapter).await?;
+    server.request_name().await?;
+
+    let prefix = "org.opensuse.Agama1";
+    let names: Vec<String> = async_retry(|| async {
+        let schema =
+            introspection::schema(&server.connection(), prefix, "/org/opensuse/Agama1", prefix)
+                .await?;
+        let names: Vec<_> = schema.interfaces.into_iter().map(|i| i.name).collect();
+        if names.iter().any(|n| n.ends_with(".Connection.MACsec")) {
+            Ok(names)
+        } else {
+            Err(Box::<dyn Error>::from(
+                "The connections are not exported yet",
+            ))
+        }
+    })
+    .await?;
+
+    assert!(names.iter().all(|n| n.starts_with(prefix)));
+    for name in [
+        "Issues",
+        "Network.Connections",
+        "Network.Connection",
+        "Network.Connection.Bond",
+        "Network.Connection.Wireless",
+        "Network.Connection.PPPoE",
+        "Network.Connection.Modem",
+        "Network.Connection.VRF",
+        "Network.Connection.MACVLAN",
+        "Network.Connection.IPVLAN",
+        "Network.Devices",
+    ] {
+        let name = format!("{}.{}", prefix, name);
+        assert!(names.contains(&name), "{} is missing", name);
+    }
+    Ok(())
+}
+
+#[test]
+async fn test_applied_configuration_signal() -> Result<(), Box<dyn Error>> {
+    let mut server = DBusServer::new().start().await?;
+
+    let eth0 = model::Connection::new("eth0".to_string(), DeviceType::Ethernet);
+    let state = NetworkState::new(vec![], vec![eth0]);
+    let adapter = NetworkTestAdapter(state);
+
+    NetworkService::start(&server.connection(), adapter).await?;
+    server.request_name().await?;
+
+    let client = NetworkClient::new(server.connection()).await?;
+    let _conns = async_retry(|| client.connections()).await?;
+
+    let rule = MatchRule::builder()
+        .msg_type(MessageType::Signal)
+        .interface("org.opensuse.Agama1.Network.Connections")?
+        .member("AppliedConfiguration")?
+        .build();
+    let mut signals = MessageStream::for_match_rule(rule, &server.connection(), None).await?;
+
+    client.apply().await?;
+    let message = tokio::time::timeout(Duration::from_secs(5), signals.next())
+        .await?
+        .unwrap()?;
+    let (added, updated, removed, failed): (u32, u32, u32, Vec<String>) = message.body()?;
+    assert_eq!((added, updated, removed), (0, 0, 0));
+    assert!(failed.is_empty());
+    Ok(())
+}
+
+#[test]
+async fn test_refresh() -> Result<(), Box<dyn Error>> {
+    let mut server = DBusServer::new().start().await?;
+
+    let device = model::Device {
+        name: String::from("eth0"),
+        type_: DeviceType::Ethernet,
+        wireless_capabilities: None,
+        modem_capabilities: None,
+        managed: true,
+    };
+    let eth0 = model::Connection::new("eth0".to_string(), DeviceType::Ethernet);
+    let adapter = SharedTestAdapter::default();
+    *adapter.0.lock().unwrap() = NetworkState::new(vec![device.clone()], vec![eth0.clone()]);
+
+    NetworkService::start(&server.connection(), adapter.clone()).await?;
+    server.request_name().await?;
+
+    let client = NetworkClient::new(server.connection()).await?;
+    let _conns = async_retry(|| client.connections()).await?;
+
+    // udev renames the interface and a new connection appears
+    let renamed = model::Device {
+        name: String::from("enp1s0"),
+        ..device
+    };
+    let enp1s0 = model::Connection::new("enp1s0".to_string(), DeviceType::Ethernet);
+    *adapter.0.lock().unwrap() = NetworkState::new(vec![renamed], vec![eth0, enp1s0]);
+
+    client.refresh().await?;
+    let devices = client.available_devices().await?;
+    let names: Vec<_> = devices.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, vec!["enp1s0"]);
+    let dbus_enp1s0 = async_retry(|| client.get_connection("enp1s0")).await?;
+    assert_eq!(dbus_enp1s0.id, "enp1s0");
+    Ok(())
+}
+
+#[test]
+async fn test_api_versions() -> Result<(), Box<dyn Error>> {
+    let mut server = DBusServer::new().start().await?;
+
+    let adapter = NetworkTestAdapter(NetworkState::default());
+    NetworkService::start(&server.connection(), adapter).await?;
+    server.request_name().await?;
+
+    let client = NetworkClient::new(server.connection()).await?;
+    // the versions are published before the rest of the global settings
+    let _dns = async_retry(|| client.dns()).await?;
+
+    assert_eq!(client.api_versions().await?, vec![1]);
+    assert!(client.supports_api_version(1).await?);
+    assert!(!client.supports_api_version(2).await?);
+    Ok(())
+}
+
+#[test]
+async fn test_setter_access_denied() -> Result<(), Box<dyn Error>> {
+    let mut server = DBusServer::new().start().await?;
+
+    let device = model::Device {
+        name: String::from("eth0"),
+        type_: DeviceType::Ethernet,
+        wireless_capabilities: None,
+        modem_capabilities: None,
+        managed: true,
+    };
+    let eth0 = model::Connection::new("eth0".to_string(), DeviceType::Ethernet);
+    let adapter = NetworkTestAdapter(NetworkState::new(vec![device], vec![eth0]));
+    // no one is running the tests with this user ID
+    let access = AccessPolicy::new(vec![u32::MAX], vec![]);
+    NetworkService::start_with_access(&server.connection(), adapter, access).await?;
+    server.request_name().await?;
+
+    let client = NetworkClient::new(server.connection()).await?;
+    // reading is not restricted
+    let limit = async_retry(|| client.bandwidth_limit()).await?;
+
+    let result = client
+        .set_bandwidth_limit(limit.unwrap_or_default() + 1)
+        .await;
+    let Err(ServiceError::DBus(zbus::Error::FDO(error))) = result else {
+        panic!("Unexpected result: {:?}", result);
+    };
+    assert!(matches!(*error, zbus::fdo::Error::AccessDenied(_)));
+    assert_eq!(client.bandwidth_limit().await?, limit);
+
+    let conns = client.connections().await?;
+    let mut eth0 = conns.first().unwrap().clone();
+    eth0.description = Some("Restricted".to_string());
+    assert!(client.add_or_update_connection(&eth0).await.is_err());
     Ok(())
 }
@@ -120,6 +120,25 @@ impl TryFrom<SettingValue> for Option<bool> {
     }
 }
 
+impl TryFrom<SettingValue> for u32 {
+    type Error = ConversionError;
+
+    fn try_from(value: SettingValue) -> Result<Self, Self::Error> {
+        value
+            .0
+            .parse()
+            .map_err(|_| ConversionError::InvalidValue(value.to_string(), "number".to_string()))
+    }
+}
+
+impl TryFrom<SettingValue> for Option<u32> {
+    type Error = ConversionError;
+
+    fn try_from(value: SettingValue) -> Result<Self, Self::Error> {
+        Ok(Some(value.try_into()?))
+    }
+}
+
 impl TryFrom<SettingValue> for String {
     type Error = ConversionError;
 
@@ -159,6 +178,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_try_from_u32() {
+        let value = SettingValue("300".to_string());
+        let value: u32 = value.try_into().unwrap();
+        assert_eq!(value, 300);
+
+        let value = SettingValue("-1".to_string());
+        let value: Result<u32, ConversionError> = value.try_into();
+        let error = value.unwrap_err();
+        assert_eq!(error.to_string(), "Invalid value '-1', expected a number");
+    }
+
     #[test]
     fn test_try_from_string() {
         let value = SettingValue("some value".to_string());
@@ -36,9 +36,15 @@ Requires:       dbus-1-common
 BuildRequires:  dbus-1-daemon
 BuildRequires:  clang-devel
 BuildRequires:  pkgconfig(pam)
+# required for compiling the translations of the command-line interface
+BuildRequires:  gettext-tools
+# protoc, required for compiling the gRPC API (the "grpc" feature)
+BuildRequires:  protobuf-devel
 # required by autoinstallation
 Requires:       jsonnet
 Requires:       lshw
+# required for verifying the signature of the downloaded files (gpgv)
+Requires:       gpg2
 # required by "agama logs store"
 Requires:       bzip2
 Requires:       tar
@@ -51,6 +57,14 @@ BuildRequires:  timezone
 # required for getting the languages information
 BuildRequires:  python-langtable-data
 Requires:       python-langtable-data
+# required for setting the wireless regulatory domain
+Requires:       iw
+# required for detecting the IP address conflicts (arping)
+Requires:       iputils
+# required for inspecting the IPv6 router advertisements (rdisc6)
+Requires:       ndisc6
+# required for limiting the bandwidth (tc)
+Requires:       iproute2
 # dependency on the YaST part of Agama
 Requires:       agama-yast
 
@@ -70,6 +84,9 @@ Summary:        Agama command-line interface
 License:        GPL-2.0-only
 Url:            https://github.com/opensuse/agama
 
+# driving a remote installation (--host)
+Recommends:     openssh-clients
+
 %description -n agama-cli
 Command line program to interact with the Agama installer.
 
@@ -91,6 +108,15 @@ install -D -d -m 0755 %{buildroot}%{_datadir}/agama-cli
 install -m 0644 %{_builddir}/agama/agama-lib/share/profile.schema.json %{buildroot}%{_datadir}/agama-cli
 install --directory %{buildroot}%{_datadir}/dbus-1/agama-services
 install -m 0644 --target-directory=%{buildroot}%{_datadir}/dbus-1/agama-services %{_builddir}/agama/share/*.service
+install -D -p -m 0644 %{_builddir}/agama/share/org.opensuse.Agama1.policy %{buildroot}%{_datadir}/polkit-1/actions/org.opensuse.Agama1.policy
+for po in %{_builddir}/agama/po/*.po; do
+  [ -e "$po" ] || continue
+  lang=$(basename "$po" .po)
+  install -D -d -m 0755 %{buildroot}%{_datadir}/locale/$lang/LC_MESSAGES
+  msgfmt -o %{buildroot}%{_datadir}/locale/$lang/LC_MESSAGES/agama-cli.mo "$po"
+done
+# there might be no translations yet
+%find_lang agama-cli || touch agama-cli.lang
 
 
 %check
@@ -107,8 +133,9 @@ echo $PATH
 %{_bindir}/agama-web-server
 %{_datadir}/dbus-1/agama-services
 %{_pam_vendordir}/agama
+%{_datadir}/polkit-1/actions/org.opensuse.Agama1.policy
 
-%files -n agama-cli
+%files -n agama-cli -f agama-cli.lang
 %{_bindir}/agama
 %dir %{_datadir}/agama-cli
 %{_datadir}/agama-cli/profile.schema.json
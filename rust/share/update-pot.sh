@@ -0,0 +1,36 @@
+#! /bin/sh
+
+# Extracts the translatable messages of agama-cli and agama-lib into po/agama-cli.pot.
+#
+# It requires xgettext >= 0.24 (Rust support). xgettext does not look into the attributes, so the
+# messages of the "#[error(...)]" attributes are turned into plain expressions in a temporary copy
+# of the sources, keeping the line numbers. Additionally, the paths of the "tr!" macro are removed,
+# as xgettext only recognizes the plain name.
+
+set -e
+
+ROOT=$(cd "$(dirname "$0")/.." && pwd)
+TMPDIR=$(mktemp --directory --suffix "-agama-pot")
+trap 'rm -rf "$TMPDIR"' EXIT
+
+cd "$ROOT"
+for file in $(find agama-cli/src agama-lib/src -name "*.rs" | sort); do
+  mkdir -p "$TMPDIR/$(dirname "$file")"
+  sed -E \
+    -e 's/^([[:space:]]*)#\[error\((.*)\)\]$/\1fn _error() { (\2); }/' \
+    -e 's/[a-z_]+::tr!/tr!/g' \
+    "$file" > "$TMPDIR/$file"
+done
+
+cd "$TMPDIR"
+find agama-cli/src agama-lib/src -name "*.rs" | sort | xgettext \
+  --language=Rust \
+  --from-code=UTF-8 \
+  --keyword='tr!' \
+  --keyword=tr \
+  --keyword=ntr:1,2 \
+  --add-comments=TRANSLATORS \
+  --package-name=agama-cli \
+  --msgid-bugs-address=https://github.com/openSUSE/agama/issues \
+  --files-from=- \
+  --output="$ROOT/po/agama-cli.pot"
@@ -69,6 +69,13 @@ module Agama
           [current_step.id, current_step.description]
         end
 
+        # Percentage of the current step which is already done
+        #
+        # @return [Integer] -1 if it is unknown
+        def progress_step_percentage
+          backend.progress&.percentage || -1
+        end
+
         # Whether the progress has finished
         #
         # @return [Boolean]
@@ -78,6 +85,13 @@ module Agama
           backend.progress.finished?
         end
 
+        # Error message if the progress failed
+        #
+        # @return [String] empty string if the progress did not fail
+        def progress_error
+          backend.progress&.error || ""
+        end
+
         # D-Bus properties of the Progress interface
         #
         # @return [Hash]
@@ -103,7 +117,9 @@ module Agama
             dbus_interface PROGRESS_INTERFACE do
               dbus_reader :progress_total_steps, "u", dbus_name: "TotalSteps"
               dbus_reader :progress_current_step, "(us)", dbus_name: "CurrentStep"
+              dbus_reader :progress_step_percentage, "i", dbus_name: "StepPercentage"
               dbus_reader :progress_finished, "b", dbus_name: "Finished"
+              dbus_reader :progress_error, "s", dbus_name: "Error"
             end
           end
         end
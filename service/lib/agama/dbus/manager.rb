@@ -62,6 +62,12 @@ module Agama
         dbus_method(:Commit, "") { install_phase }
         dbus_method(:CanInstall, "out result:b") { can_install? }
         dbus_method(:CollectLogs, "out tarball_filesystem_path:s") { collect_logs }
+        dbus_method(
+          :GetRecentLogs, "in service:s, in lines:u, out logs:as, out cursor:s"
+        ) { |service, lines| recent_logs(service, lines) }
+        dbus_method(
+          :GetLogsAfter, "in service:s, in cursor:s, out logs:as, out cursor:s"
+        ) { |service, cursor| logs_after(service, cursor) }
         dbus_method(:Finish, "") { finish_phase }
         dbus_reader :installation_phases, "aa{sv}"
         dbus_reader :current_installation_phase, "u"
@@ -97,6 +103,30 @@ module Agama
         backend.collect_logs
       end
 
+      # Most recent log lines of a service
+      #
+      # @param service [String] Service name (e.g., "software")
+      # @param lines [Integer] Maximum number of lines
+      # @return [Array(Array<String>, String)] Lines and the journal cursor of the last one
+      def recent_logs(service, lines)
+        backend.service_logs.recent(service, lines)
+      rescue ArgumentError => e
+        raise ::DBus::Error, e.message
+      end
+
+      # Log lines of a service written after the given journal cursor
+      #
+      # It allows following the logs by calling it periodically.
+      #
+      # @param service [String] Service name (e.g., "software")
+      # @param cursor [String] Cursor returned by a previous call
+      # @return [Array(Array<String>, String)] Lines and the journal cursor of the last one
+      def logs_after(service, cursor)
+        backend.service_logs.after(service, cursor)
+      rescue ArgumentError => e
+        raise ::DBus::Error, e.message
+      end
+
       # Last action for the installer
       def finish_phase
         backend.finish_installation
@@ -105,6 +105,7 @@ module Agama
 
           dbus_method(:UsedDiskSpace, "out SpaceSize:s") { backend.used_disk_space }
 
+          dbus_method(:SetOffline, "in offline:b") { |offline| backend.offline = offline }
           dbus_method(:Probe) { probe }
           dbus_method(:Propose) { propose }
           dbus_method(:Install) { install }
@@ -60,6 +60,12 @@ module Agama
 
         dbus_reader :first_user, "(sssba{sv})"
 
+        dbus_reader :users, "aa{sv}"
+
+        dbus_reader :ssh_host_keys, "as", dbus_name: "SSHHostKeys"
+
+        dbus_reader :ssh_host_keys_source, "a{sv}", dbus_name: "SSHHostKeysSource"
+
         dbus_method :SetRootPassword,
           "in Value:s, in Encrypted:b, out result:u" do |value, encrypted|
           logger.info "Setting Root Password"
@@ -115,6 +121,31 @@ module Agama
           0
         end
 
+        dbus_method :SetUsers,
+          # It returns the same structure than SetFirstUser
+          "in Users:aa{sv}, out result:(bas)" do |users_data|
+          logger.info "Setting users #{users_data.map { |u| u["UserName"] }}"
+          issues = backend.assign_users(users_data)
+
+          if issues.empty?
+            dbus_properties_changed(USERS_INTERFACE, { "Users" => users }, [])
+            update_validation
+          else
+            logger.info "Users fatal issues detected: #{issues}"
+          end
+
+          [[issues.empty?, issues]]
+        end
+
+        dbus_method :ImportSSHHostKeys, "in Source:a{sv}, out result:(bas)" do |source|
+          logger.info "Importing SSH host keys from #{source}"
+          issues = backend.ssh_host_keys.import(source)
+
+          dbus_properties_changed(USERS_INTERFACE,
+            { "SSHHostKeys" => ssh_host_keys, "SSHHostKeysSource" => ssh_host_keys_source }, [])
+          [[issues.empty?, issues]]
+        end
+
         dbus_method :Write, "out result:u" do
           logger.info "Writting users"
 
@@ -132,15 +163,40 @@ module Agama
 
         return ["", "", "", false, {}] unless user
 
+        # the password is write-only, so only whether it is set is exposed
         [
           user.full_name,
           user.name,
-          user.password_content || "",
+          "",
           backend.autologin?(user),
-          {}
+          { "PasswordSet" => !user.password_content.to_s.empty? }
         ]
       end
 
+      def users
+        backend.users.map do |user|
+          groups = backend.user_groups(user.name)
+          {
+            "UserName"    => user.name,
+            "FullName"    => user.full_name,
+            # the password is write-only, so only whether it is set is exposed
+            "PasswordSet" => !user.password_content.to_s.empty?,
+            "UID"         => user.uid&.to_i,
+            "Groups"      => groups.empty? ? nil : groups,
+            "Shell"       => user.shell,
+            "Sudo"        => backend.sudo_rules[user.name]
+          }.compact
+        end
+      end
+
+      def ssh_host_keys
+        backend.ssh_host_keys.names
+      end
+
+      def ssh_host_keys_source
+        backend.ssh_host_keys.source
+      end
+
       def root_password_set
         backend.root_password?
       end
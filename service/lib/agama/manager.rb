@@ -26,6 +26,7 @@ require "agama/proxy_setup"
 require "agama/with_progress"
 require "agama/installation_phase"
 require "agama/service_status_recorder"
+require "agama/service_logs"
 require "agama/dbus/service_status"
 require "agama/dbus/clients/locale"
 require "agama/dbus/clients/software"
@@ -91,7 +92,7 @@ module Agama
       logger.info("Config phase done")
     rescue StandardError => e
       logger.error "Startup error: #{e.inspect}. Backtrace: #{e.backtrace}"
-      # TODO: report errors
+      fail_progress(e.message)
     ensure
       service_status.idle
       finish_progress
@@ -127,6 +128,7 @@ module Agama
       logger.info("Install phase done")
     rescue StandardError => e
       logger.error "Installation error: #{e.inspect}. Backtrace: #{e.backtrace}"
+      fail_progress(e.message)
     ensure
       service_status.idle
       finish_progress
@@ -206,6 +208,13 @@ module Agama
       users.valid? && !software.errors? && !storage.errors?
     end
 
+    # Reader for the logs of the services
+    #
+    # @return [ServiceLogs]
+    def service_logs
+      @service_logs ||= ServiceLogs.new
+    end
+
     # Collects the logs and stores them into an archive
     #
     # @param path [String] directory where to store logs
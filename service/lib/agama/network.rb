@@ -38,9 +38,19 @@ module Agama
     #
     # * Copies the connections configuration for NetworkManager, as Agama is not
     #   performing further configuration of the network.
+    # * Enables the connections that were only meant for the installed system.
+    # * Copies the wireless regulatory domain configuration.
+    # * Writes the DNS configuration, setting up the selected resolver stack.
+    # * Copies the devices the system does not wait for when booting.
+    # * Updates the kdump network settings.
     # * Enables the NetworkManager service.
     def install
       copy_files
+      enable_only_on_target_connections
+      copy_regdom_file
+      copy_dns_file
+      copy_wait_online_file
+      update_kdump_config
       enable_service
 
       ProxySetup.instance.install
@@ -55,6 +65,34 @@ module Agama
     RUN_NM_DIR = "/run/NetworkManager"
     private_constant :ETC_NM_DIR
 
+    MODPROBE_DIR = "/etc/modprobe.d"
+    private_constant :MODPROBE_DIR
+
+    # DNS configuration for the installed system written by Agama
+    DNS_CONF_PATH = "/run/agama/network/90-agama-dns.conf"
+    private_constant :DNS_CONF_PATH
+
+    # NetworkManager-wait-online settings for the installed system written by Agama
+    WAIT_ONLINE_CONF_PATH = "/run/agama/network/90-agama-wait-online.conf"
+    private_constant :WAIT_ONLINE_CONF_PATH
+
+    # kdump network settings for the installed system written by Agama
+    KDUMP_CONF_PATH = "/run/agama/network/kdump"
+    private_constant :KDUMP_CONF_PATH
+
+    # kdump configuration file
+    KDUMP_SYSCONFIG = "/etc/sysconfig/kdump"
+    private_constant :KDUMP_SYSCONFIG
+
+    # resolv.conf managed by systemd-resolved
+    RESOLVED_RESOLV_CONF = "../run/systemd/resolve/stub-resolv.conf"
+    private_constant :RESOLVED_RESOLV_CONF
+
+    # NetworkManager user data key that marks the connections to activate only in the
+    # installed system
+    ONLY_ON_TARGET_KEY = "org.opensuse.agama.only-on-target"
+    private_constant :ONLY_ON_TARGET_KEY
+
     def enable_service
       service = Yast2::Systemd::Service.find("NetworkManager")
       if service.nil?
@@ -80,6 +118,107 @@ module Agama
         File.join(ETC_NM_DIR, "system-connections"),
         File.join(Yast::Installation.destdir, ETC_NM_DIR, "system-connections")
       )
+
+      # configuration snippets written by Agama (e.g., the connectivity check settings)
+      copy_directory(
+        File.join(ETC_NM_DIR, "conf.d"),
+        File.join(Yast::Installation.destdir, ETC_NM_DIR, "conf.d"),
+        "90-agama-*.conf"
+      )
+    end
+
+    # Enables the autoconnect option of the connections only meant for the installed system
+    #
+    # Agama disables the autoconnect option of those connections, so they are not activated in the
+    # live system.
+    def enable_only_on_target_connections
+      dir = File.join(Yast::Installation.destdir, ETC_NM_DIR, "system-connections")
+      Dir.glob(File.join(dir, "*")).each do |path|
+        content = File.read(path)
+        next unless content.match?(/^#{Regexp.escape(ONLY_ON_TARGET_KEY)}=true$/)
+
+        File.write(path, content.gsub(/^autoconnect=false\n/, ""))
+      end
+    end
+
+    # Copies the wireless regulatory domain configuration written by Agama
+    def copy_regdom_file
+      copy_directory(
+        MODPROBE_DIR,
+        File.join(Yast::Installation.destdir, MODPROBE_DIR),
+        "90-agama-regdom.conf"
+      )
+    end
+
+    # Copies the DNS configuration written by Agama and sets up the resolver stack
+    #
+    # When using systemd-resolved, the service is enabled and resolv.conf points to its stub
+    # resolver. When NetworkManager writes resolv.conf as a plain file, any existing link is
+    # removed so it is not followed.
+    def copy_dns_file
+      return unless File.exist?(DNS_CONF_PATH)
+
+      conf_dir = File.join(Yast::Installation.destdir, ETC_NM_DIR, "conf.d")
+      FileUtils.mkdir_p(conf_dir)
+      FileUtils.cp(DNS_CONF_PATH, conf_dir)
+
+      content = File.read(DNS_CONF_PATH)
+      if content.match?(/^dns=systemd-resolved$/)
+        enable_resolved
+      elsif content.match?(/^rc-manager=file$/)
+        resolv_conf = File.join(Yast::Installation.destdir, "etc", "resolv.conf")
+        FileUtils.rm_f(resolv_conf) if File.symlink?(resolv_conf)
+      end
+    end
+
+    # Copies the NetworkManager-wait-online settings written by Agama
+    #
+    # They list the devices the installed system should not wait for when booting.
+    def copy_wait_online_file
+      return unless File.exist?(WAIT_ONLINE_CONF_PATH)
+
+      conf_dir = File.join(Yast::Installation.destdir, ETC_NM_DIR, "conf.d")
+      FileUtils.mkdir_p(conf_dir)
+      FileUtils.cp(WAIT_ONLINE_CONF_PATH, conf_dir)
+    end
+
+    # Merges the kdump network settings written by Agama into the kdump configuration
+    #
+    # The variables that already exist in the configuration are replaced and the rest are appended.
+    def update_kdump_config
+      return unless File.exist?(KDUMP_CONF_PATH)
+
+      sysconfig = File.join(Yast::Installation.destdir, KDUMP_SYSCONFIG)
+      content = File.exist?(sysconfig) ? File.read(sysconfig) : +""
+      File.readlines(KDUMP_CONF_PATH, chomp: true).each do |line|
+        name, = line.split("=", 2)
+        next if name.nil? || name.empty?
+
+        regexp = /^#{Regexp.escape(name)}=.*$/
+        if content.match?(regexp)
+          content = content.gsub(regexp) { line }
+        else
+          content << "\n" unless content.empty? || content.end_with?("\n")
+          content << "#{line}\n"
+        end
+      end
+
+      FileUtils.mkdir_p(File.dirname(sysconfig))
+      File.write(sysconfig, content)
+    end
+
+    # Enables systemd-resolved and links resolv.conf to its stub resolver
+    def enable_resolved
+      service = Yast2::Systemd::Service.find("systemd-resolved")
+      if service.nil?
+        logger.error "systemd-resolved service was not found"
+        return
+      end
+
+      service.enable
+      resolv_conf = File.join(Yast::Installation.destdir, "etc", "resolv.conf")
+      FileUtils.mkdir_p(File.dirname(resolv_conf))
+      FileUtils.ln_sf(RESOLVED_RESOLV_CONF, resolv_conf)
     end
 
     # Copies a directory
@@ -89,11 +228,12 @@ module Agama
     #
     # @param source [String] source directory
     # @param target [String] target directory
-    def copy_directory(source, target)
+    # @param pattern [String] pattern of the files to copy
+    def copy_directory(source, target, pattern = "*")
       return unless Dir.exist?(source)
 
       FileUtils.mkdir_p(target)
-      FileUtils.cp(Dir.glob(File.join(source, "*")), target)
+      FileUtils.cp(Dir.glob(File.join(source, pattern)), target)
     end
   end
 end
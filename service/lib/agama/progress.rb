@@ -38,6 +38,9 @@ module Agama
   #   progress.current_step.description             #=> "Doing step2"
   #
   #   progress.finished?                            #=> false
+  #
+  #   progress.percentage = 42                      # reports the progress within the current step
+  #   progress.percentage                           #=> 42
 
   #   progress.step("Doing step3") do               # calls on_change callbacks, executes the given
   #     progress.current_step.description           # block and calls on_finish callbacks
@@ -73,6 +76,16 @@ module Agama
     # @return [Integer]
     attr_reader :total_steps
 
+    # Percentage of the current step which is already done, if known
+    #
+    # @return [Integer, nil]
+    attr_reader :percentage
+
+    # Error message if the progress failed
+    #
+    # @return [String, nil]
+    attr_reader :error
+
     # Constructor
     #
     # @param total_steps [Integer] total number of steps
@@ -81,6 +94,7 @@ module Agama
       @current_step = nil
       @counter = 0
       @finished = false
+      @error = nil
       @on_change_callbacks = []
       @on_finish_callbacks = []
     end
@@ -108,6 +122,7 @@ module Agama
 
       @counter += 1
       @current_step = Step.new(@counter, description)
+      @percentage = nil
       @on_change_callbacks.each(&:call)
 
       result = block_given? ? block.call : nil
@@ -117,6 +132,18 @@ module Agama
       result
     end
 
+    # Sets the percentage of the current step which is already done
+    #
+    # It calls the `on_change` callbacks.
+    #
+    # @param value [Integer, nil] percentage (0-100) or nil if it is unknown
+    def percentage=(value)
+      return if finished?
+
+      @percentage = value&.clamp(0, 100)
+      @on_change_callbacks.each(&:call)
+    end
+
     # Whether the last step was already done
     #
     # @return [Boolean]
@@ -132,6 +159,23 @@ module Agama
       @on_finish_callbacks.each(&:call)
     end
 
+    # Finishes the progress because of an error and runs the `on_finish` callbacks
+    #
+    # @param message [String] error message
+    def fail(message)
+      return if finished?
+
+      @error = message
+      finish
+    end
+
+    # Whether the progress finished because of an error
+    #
+    # @return [Boolean]
+    def failed?
+      !error.nil?
+    end
+
     # Adds a callback to be called when progress changes
     #
     # @param block [Proc]
@@ -150,6 +194,7 @@ module Agama
     #
     # @return [String]
     def to_s
+      return "Failed: #{error}" if failed?
       return "Finished" if finished?
 
       "#{current_step.description} (#{@counter}/#{total_steps})"
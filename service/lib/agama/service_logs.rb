@@ -0,0 +1,82 @@
+# frozen_string_literal: true
+
+# Copyright (c) [2024] SUSE LLC
+#
+# All Rights Reserved.
+#
+# This program is free software; you can redistribute it and/or modify it
+# under the terms of version 2 of the GNU General Public License as published
+# by the Free Software Foundation.
+#
+# This program is distributed in the hope that it will be useful, but WITHOUT
+# ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+# FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+# more details.
+#
+# You should have received a copy of the GNU General Public License along
+# with this program; if not, contact SUSE LLC.
+#
+# To contact SUSE LLC about this file by physical or electronic mail, you may
+# find current contact information at www.suse.com.
+
+require "yast2/execute"
+
+module Agama
+  # Reads the logs of the Agama services from the systemd journal
+  #
+  # All the services run under the agama unit, so the lines are filtered using the service name
+  # included by agamactl in each message (e.g., "[INFO]: software: Probing software").
+  #
+  # Together with the lines, it returns the journal cursor of the last one, which can be used to
+  # read only the new lines later (e.g., to follow the logs).
+  class ServiceLogs
+    # Services whose logs can be read
+    SERVICES = ["manager", "software", "storage", "users"].freeze
+
+    # Returns the most recent lines of a service
+    #
+    # @param service [String] Service name (see {SERVICES})
+    # @param lines [Integer] Maximum number of lines
+    # @return [Array(Array<String>, String)] Lines and the cursor of the last one
+    def recent(service, lines)
+      read(service, "--lines=#{lines}")
+    end
+
+    # Returns the lines of a service written after the given cursor
+    #
+    # @param service [String] Service name (see {SERVICES})
+    # @param cursor [String] Journal cursor. If it is empty, all the lines are returned.
+    # @return [Array(Array<String>, String)] Lines and the cursor of the last one. If there are no
+    #   new lines, the given cursor is returned.
+    def after(service, cursor)
+      return read(service) if cursor.empty?
+
+      lines, new_cursor = read(service, "--after-cursor=#{cursor}")
+      [lines, new_cursor.empty? ? cursor : new_cursor]
+    end
+
+  private
+
+    CURSOR_PREFIX = "-- cursor: "
+    private_constant :CURSOR_PREFIX
+
+    # @param service [String]
+    # @param args [Array<String>] Additional journalctl arguments
+    # @return [Array(Array<String>, String)]
+    def read(service, *args)
+      raise ArgumentError, "Unknown service '#{service}'" unless SERVICES.include?(service)
+
+      command = [
+        "journalctl", "--unit=agama", "--output=cat", "--no-pager", "--show-cursor",
+        "--grep=^\\[[A-Z]+\\]: #{service}: ", *args
+      ]
+      # journalctl exits with 1 when no line matches
+      output, _status = Yast::Execute.locally!(
+        command, stdout: :capture, allowed_exitstatus: [0, 1]
+      )
+      lines = output.to_s.lines.map(&:chomp)
+      cursor = lines.last&.start_with?(CURSOR_PREFIX) ? lines.pop.delete_prefix(CURSOR_PREFIX) : ""
+      [lines, cursor]
+    end
+  end
+end
@@ -44,6 +44,9 @@ module Agama
           Yast::Pkg.CallbackDonePackage(
             fun_ref(method(:package_installed), "string (integer, string)")
           )
+          Yast::Pkg.CallbackProgressPackage(
+            fun_ref(method(:package_progress), "boolean (integer)")
+          )
         end
 
       private
@@ -63,6 +66,15 @@ module Agama
           ""
         end
 
+        # Reports the progress of the package being installed
+        #
+        # @param percent [Integer]
+        # @return [Boolean] true to continue with the installation
+        def package_progress(percent)
+          progress.percentage = percent
+          true
+        end
+
         def msg
           "Installing packages (#{@total - @installed} remains)"
         end
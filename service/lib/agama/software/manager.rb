@@ -296,6 +296,15 @@ module Agama
         on_local { !!Yast::Package.Available(name) }
       end
 
+      # Sets whether the system is offline
+      #
+      # When it is offline, the remote repositories are not read.
+      #
+      # @param value [Boolean]
+      def offline=(value)
+        repositories.offline = value
+      end
+
       # Counts how much disk space installation will use.
       # @return [String]
       # @note Reimplementation of Yast::Package.CountSizeToBeInstalled
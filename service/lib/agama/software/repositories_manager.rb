@@ -30,8 +30,14 @@ module Agama
       # @return [Array<Repository>]
       attr_reader :repositories
 
+      # Whether the system is offline. In that case, the remote repositories are not read.
+      #
+      # @return [Boolean]
+      attr_accessor :offline
+
       def initialize
         @repositories = []
+        @offline = false
       end
 
       # Adds a new repository
@@ -66,10 +72,13 @@ module Agama
       #
       # As a side effect, it disables those repositories that cannot be read.
       # The intentation is to prevent the proposal from trying to read them
-      # again.
+      # again. When the system is offline, the remote repositories are disabled
+      # without trying to read them.
       def load
         repositories.each do |repo|
-          if repo.probe
+          if offline && repo.remote?
+            repo.disable!
+          elsif repo.probe
             repo.enable!
           else
             repo.disable!
@@ -31,6 +31,17 @@ module Agama
     #
     # @see RepositoriesManager
     class Repository < Y2Packager::Repository
+      # URL schemes of the repositories that are accessed through the network
+      REMOTE_SCHEMES = ["http", "https", "ftp", "tftp", "nfs", "nfs4", "cifs", "smb"].freeze
+      private_constant :REMOTE_SCHEMES
+
+      # Determines whether the repository is accessed through the network
+      #
+      # @return [Boolean]
+      def remote?
+        REMOTE_SCHEMES.include?(url.to_s.split(":").first.to_s.downcase)
+      end
+
       # Probes a repository
       #
       # @return [Boolean] true if the repository can be read; false otherwise
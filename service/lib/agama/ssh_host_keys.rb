@@ -0,0 +1,208 @@
+# frozen_string_literal: true
+
+# Copyright (c) [2024] SUSE LLC
+#
+# All Rights Reserved.
+#
+# This program is free software; you can redistribute it and/or modify it
+# under the terms of version 2 of the GNU General Public License as published
+# by the Free Software Foundation.
+#
+# This program is distributed in the hope that it will be useful, but WITHOUT
+# ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+# FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+# more details.
+#
+# You should have received a copy of the GNU General Public License along
+# with this program; if not, contact SUSE LLC.
+#
+# To contact SUSE LLC about this file by physical or electronic mail, you may
+# find current contact information at www.suse.com.
+
+require "digest"
+require "fileutils"
+require "tmpdir"
+require "yast"
+require "yast2/execute"
+require "transfer/file_from_url"
+
+Yast.import "URL"
+
+module Agama
+  # Imports the SSH host keys of a previous installation and copies them to the installed system
+  #
+  # Keeping the host keys avoids changing the server fingerprints after a reinstallation. The keys
+  # can be read from a device containing a previous installation or from a tar archive. They are
+  # read when importing them, so the source is not needed when writing them.
+  #
+  # As the archive contains private keys, its SHA-256 checksum is required to make sure that it was
+  # not tampered with.
+  class SSHHostKeys
+    include Yast::Transfer::FileFromUrl
+
+    # Error importing the keys
+    class ImportError < StandardError; end
+
+    SSH_DIR = "etc/ssh"
+    private_constant :SSH_DIR
+
+    KEYS_GLOB = "ssh_host_*key{,.pub}"
+    private_constant :KEYS_GLOB
+
+    # Location the keys were imported from
+    #
+    # @return [Hash] a hash containing a "Device" or an "Url" (and "Sha256") key; empty if no keys
+    #   were imported
+    attr_reader :source
+
+    # @param logger [Logger]
+    def initialize(logger)
+      @logger = logger
+      @source = {}
+      @keys = {}
+    end
+
+    # Names of the imported key files
+    #
+    # @return [Array<String>]
+    def names
+      keys.keys.sort
+    end
+
+    # Imports the keys from the given source
+    #
+    # The previously imported keys are discarded, even if the import fails.
+    #
+    # @param source [Hash] it can contain a "Device" or an "Url" key. The "Url" one requires the
+    #   SHA-256 checksum of the archive ("Sha256" key). An empty hash just discards the imported
+    #   keys.
+    # @return [Array<String>] the list of issues found
+    def import(source)
+      @source = {}
+      @keys = {}
+      device = source["Device"].to_s
+      url = source["Url"].to_s
+      return [] if device.empty? && url.empty?
+
+      if device.empty?
+        sha256 = source["Sha256"].to_s.downcase
+        @keys = read_from_url(url, sha256)
+        @source = { "Url" => url, "Sha256" => sha256 }
+      else
+        @keys = read_from_device(device)
+        @source = { "Device" => device }
+      end
+      logger.info "Imported SSH host keys: #{names}"
+      []
+    rescue ImportError => e
+      logger.error "Could not import the SSH host keys: #{e.message}"
+      [e.message]
+    end
+
+    # Writes the imported keys to the given root directory
+    #
+    # @param root_dir [String] root directory of the installed system
+    def write(root_dir)
+      return if keys.empty?
+
+      dir = File.join(root_dir, SSH_DIR)
+      FileUtils.mkdir_p(dir)
+      keys.each do |name, content|
+        path = File.join(dir, name)
+        mode = name.end_with?(".pub") ? 0o644 : 0o600
+        File.open(path, "w", mode) { |f| f.write(content) }
+        File.chmod(mode, path)
+      end
+    end
+
+  private
+
+    # @return [Logger]
+    attr_reader :logger
+
+    # @return [Hash<String, String>] content of the keys indexed by file name
+    attr_reader :keys
+
+    # Reads the keys from the /etc/ssh directory of the given device
+    #
+    # @param device [String]
+    # @return [Hash<String, String>]
+    def read_from_device(device)
+      Dir.mktmpdir do |mount_point|
+        begin
+          Yast::Execute.locally!("/usr/bin/mount", "-o", "ro", device, mount_point)
+        rescue Cheetah::ExecutionFailed
+          raise ImportError, "Could not mount the device #{device}"
+        end
+
+        begin
+          read_keys(Dir.glob(File.join(mount_point, SSH_DIR, KEYS_GLOB)))
+        ensure
+          Yast::Execute.locally!("/usr/bin/umount", mount_point)
+        end
+      end
+    end
+
+    # Reads the keys from the tar archive at the given URL
+    #
+    # The keys can be at any level of the archive.
+    #
+    # @param url [String]
+    # @param sha256 [String] expected SHA-256 checksum of the archive
+    # @return [Hash<String, String>]
+    def read_from_url(url, sha256)
+      raise ImportError, "The SHA-256 checksum of #{url} is required" if sha256.empty?
+
+      Dir.mktmpdir do |dir|
+        archive = File.join(dir, "ssh_host_keys.tar")
+        raise ImportError, "Could not download #{url}" unless download(url, archive)
+
+        actual = Digest::SHA256.file(archive).hexdigest
+        if actual != sha256
+          raise ImportError, "Checksum mismatch for #{url}: expected #{sha256}, got #{actual}"
+        end
+
+        keys_dir = File.join(dir, "keys")
+        FileUtils.mkdir_p(keys_dir)
+        begin
+          Yast::Execute.locally!("/usr/bin/tar", "-xf", archive, "-C", keys_dir)
+        rescue Cheetah::ExecutionFailed
+          raise ImportError, "Could not extract the archive #{url}"
+        end
+
+        read_keys(Dir.glob(File.join(keys_dir, "**", KEYS_GLOB)))
+      end
+    end
+
+    # Reads the given key files
+    #
+    # Only regular files are considered, so symbolic links cannot point outside the source.
+    #
+    # @param paths [Array<String>]
+    # @return [Hash<String, String>]
+    def read_keys(paths)
+      paths = paths.select { |p| File.lstat(p).file? }
+      raise ImportError, "No SSH host keys were found" if paths.empty?
+
+      paths.each_with_object({}) { |p, keys| keys[File.basename(p)] = File.binread(p) }
+    end
+
+    # Downloads a file
+    #
+    # @param location [String] file URL
+    # @param target [String] path to copy the file to
+    # @return [Boolean] whether the file was successfully downloaded
+    def download(location, target)
+      url = Yast::URL.Parse(location)
+
+      get_file_from_url(
+        scheme:    url["scheme"],
+        host:      url["host"],
+        urlpath:   url["path"],
+        localfile: target,
+        urltok:    url,
+        destdir:   "/"
+      )
+    end
+  end
+end
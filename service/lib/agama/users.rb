@@ -19,13 +19,18 @@
 # To contact SUSE LLC about this file by physical or electronic mail, you may
 # find current contact information at www.suse.com.
 
+require "fileutils"
+require "tempfile"
 require "yast"
 require "y2users"
 require "y2users/linux" # FIXME: linux is not in y2users file
 require "yast2/execute"
 require "agama/helpers"
+require "agama/ssh_host_keys"
 require "agama/validation_error"
 
+Yast.import "Installation"
+
 module Agama
   # Backend class using YaST code.
   #
@@ -75,7 +80,36 @@ module Agama
     #
     # @return [Y2Users::User, nil]
     def first_user
-      config.users.reject(&:root?).first
+      config.users.reject(&:root?).reject { |u| users.include?(u) }.first
+    end
+
+    # Users to create in addition to root and the first user
+    #
+    # @return [Array<Y2Users::User>]
+    def users
+      @users ||= []
+    end
+
+    # Names of the supplementary groups of the given user
+    #
+    # @param user_name [String]
+    # @return [Array<String>]
+    def user_groups(user_name)
+      config.groups.select { |g| g.users_name.include?(user_name) }.map(&:name)
+    end
+
+    # Sudo rules indexed by user name
+    #
+    # @return [Hash<String, String>]
+    def sudo_rules
+      @sudo_rules ||= {}
+    end
+
+    # SSH host keys to copy to the installed system
+    #
+    # @return [SSHHostKeys]
+    def ssh_host_keys
+      @ssh_host_keys ||= SSHHostKeys.new(logger)
     end
 
     # Clears the root password
@@ -88,16 +122,18 @@ module Agama
     #
     # @param full_name [String]
     # @param user_name [String]
-    # @param password [String]
+    # @param password [String] plain password (if empty, the current one is kept)
     # @param auto_login [Boolean]
     # @param _data [Hash]
     # @return [Array] the list of fatal issues found
     def assign_first_user(full_name, user_name, password, auto_login, _data)
+      # the password is not exposed, so an empty one means keeping the current password
+      current_password = first_user&.password if password.to_s.empty?
       remove_first_user
 
       user = Y2Users::User.new(user_name)
       user.gecos = [full_name]
-      user.password = Y2Users::Password.create_plain(password)
+      user.password = current_password || Y2Users::Password.create_plain(password)
       fatal_issues = user.issues.map.select(&:error?)
       return fatal_issues.map(&:message) unless fatal_issues.empty?
 
@@ -109,8 +145,47 @@ module Agama
 
     # Removes the first user
     def remove_first_user
-      old_users = config.users.reject(&:root?)
-      config.detach(old_users) unless old_users.empty?
+      user = first_user
+      config.detach(user) if user
+    end
+
+    # Replaces the users to create in addition to root and the first user
+    #
+    # The users are not changed if any of them presents an error.
+    #
+    # The passwords are not exposed, so an empty one means keeping the current password of the
+    # user with the same name.
+    #
+    # @param users_data [Array<Hash>] each hash can contain the "UserName", "FullName", "Password",
+    #   "EncryptedPassword", "UID", "Groups", "Shell" and "Sudo" keys
+    # @return [Array<String>] the list of fatal issues found
+    def assign_users(users_data)
+      new_users = users_data.map { |d| build_user(d) }
+      issues = new_users.flat_map { |u| u.issues.map.select(&:error?).map(&:message) }
+      issues.concat(duplicated_names(new_users))
+      issues.concat(sudo_issues(users_data))
+      return issues unless issues.empty?
+
+      remove_users
+      new_users.each { |u| config.attach(u) }
+      @users = new_users
+      users_data.each do |data|
+        assign_groups(data["UserName"], data.fetch("Groups", []))
+        sudo = data["Sudo"].to_s
+        sudo_rules[data["UserName"]] = sudo unless sudo.empty?
+      end
+      []
+    end
+
+    # Removes the users defined in addition to root and the first user
+    def remove_users
+      names = users.map(&:name)
+      config.groups.each { |g| g.users_name.reject! { |n| names.include?(n) } }
+      config.detach(users) unless users.empty?
+      config.detach(groups) unless groups.empty?
+      @users = []
+      @groups = []
+      @sudo_rules = {}
     end
 
     def write
@@ -125,6 +200,8 @@ module Agama
           logger.error(issues.inspect) unless issues.empty?
         end
       end
+      write_sudo_rules
+      ssh_host_keys.write(Yast::Installation.destdir)
     end
 
     # Validates the users configuration
@@ -151,11 +228,150 @@ module Agama
 
     attr_reader :logger
 
+    SUDOERS_DIR = "etc/sudoers.d"
+    private_constant :SUDOERS_DIR
+
+    VISUDO = "/usr/sbin/visudo"
+    private_constant :VISUDO
+
     # Determines whether a first user is defined or not
     #
     # @return [Boolean]
     def first_user?
-      config.users.reject(&:root?).any?
+      !first_user.nil?
+    end
+
+    # Groups created for the additional users
+    #
+    # @return [Array<Y2Users::Group>]
+    def groups
+      @groups ||= []
+    end
+
+    # Builds a user from its D-Bus representation
+    #
+    # @param data [Hash]
+    # @return [Y2Users::User]
+    def build_user(data)
+      user = Y2Users::User.new(data["UserName"].to_s)
+      user.gecos = [data["FullName"]] if data["FullName"]
+      user.uid = data["UID"].to_s if data["UID"]
+      user.shell = data["Shell"] if data["Shell"]
+      password = data["Password"].to_s
+      user.password = if password.empty?
+        users.find { |u| u.name == user.name }&.password
+      elsif data["EncryptedPassword"]
+        Y2Users::Password.create_encrypted(password)
+      else
+        Y2Users::Password.create_plain(password)
+      end
+      user
+    end
+
+    # Returns an issue for each name used more than once (including the first user and root)
+    #
+    # @param new_users [Array<Y2Users::User>]
+    # @return [Array<String>]
+    def duplicated_names(new_users)
+      names = new_users.map(&:name) + [first_user&.name, "root"].compact
+      names.tally.select { |_, count| count > 1 }.keys.map do |name|
+        "The user name '#{name}' is used more than once"
+      end
+    end
+
+    # Returns an issue for each invalid sudo rule
+    #
+    # A rule cannot contain line breaks (so it only applies to its user) and it must pass the
+    # syntax check of visudo.
+    #
+    # @param users_data [Array<Hash>]
+    # @return [Array<String>]
+    def sudo_issues(users_data)
+      users_data.each_with_object([]) do |data, issues|
+        rule = data["Sudo"].to_s
+        next if rule.empty?
+
+        user_name = data["UserName"]
+        if rule.match?(/[\r\n]/)
+          issues << "The sudo rule for '#{user_name}' cannot contain line breaks"
+        elsif !valid_sudoers?(sudoers_entry(user_name, rule))
+          issues << "The sudo rule for '#{user_name}' is not valid"
+        end
+      end
+    end
+
+    # Line to add to the sudoers configuration for the given user
+    #
+    # @param user_name [String]
+    # @param rule [String]
+    # @return [String]
+    def sudoers_entry(user_name, rule)
+      "#{user_name} #{rule}\n"
+    end
+
+    # Determines whether the given content is a valid sudoers file
+    #
+    # @param content [String]
+    # @return [Boolean]
+    def valid_sudoers?(content)
+      Tempfile.create("agama-sudoers") do |file|
+        file.write(content)
+        file.flush
+        valid_sudoers_file?(file.path)
+      end
+    end
+
+    # Checks the syntax of a sudoers file using visudo
+    #
+    # @param path [String]
+    # @return [Boolean]
+    def valid_sudoers_file?(path)
+      Yast::Execute.locally!(VISUDO, "-cf", path)
+      true
+    rescue Cheetah::ExecutionFailed, SystemCallError => e
+      logger.error("Invalid sudoers file #{path}: #{e.message}")
+      false
+    end
+
+    # Adds the user to the given groups, creating the ones that do not exist
+    #
+    # @param user_name [String]
+    # @param group_names [Array<String>]
+    def assign_groups(user_name, group_names)
+      group_names.each do |name|
+        group = config.groups.by_name(name)
+        unless group
+          group = Y2Users::Group.new(name)
+          config.attach(group)
+          groups << group
+        end
+        group.users_name << user_name unless group.users_name.include?(user_name)
+      end
+    end
+
+    # Writes the sudo rules to the target system
+    #
+    # Each user gets its own file, so the rules can be removed independently. The files are
+    # checked with visudo before putting them in place, so a broken file does not prevent sudo
+    # from working.
+    def write_sudo_rules
+      return if sudo_rules.empty?
+
+      dir = File.join(Yast::Installation.destdir, SUDOERS_DIR)
+      FileUtils.mkdir_p(dir)
+      sudo_rules.each do |user_name, rule|
+        # sudo ignores the files containing a dot
+        path = File.join(dir, "agama-#{user_name.tr(".", "_")}")
+        tmp_path = "#{path}.new"
+        File.write(tmp_path, sudoers_entry(user_name, rule))
+        File.chmod(0o440, tmp_path)
+        if valid_sudoers_file?(tmp_path)
+          File.rename(tmp_path, path)
+        else
+          logger.error("Skipping the sudo rule for #{user_name}")
+          File.delete(tmp_path)
+        end
+      end
     end
 
     def without_run_mount(&block)
@@ -54,6 +54,15 @@ module Agama
       progress.finish
     end
 
+    # Finishes the current progress because of an error
+    #
+    # @param message [String] error message
+    def fail_progress(message)
+      return if progress.nil? || progress.finished?
+
+      progress.fail(message)
+    end
+
     # Registers an on_change callback to be added to the progress
     #
     # @param block [Proc]
@@ -90,6 +90,26 @@ describe DBusObjectWithProgressInterface do
     end
   end
 
+  describe "#progress_step_percentage" do
+    context "if there is no progress" do
+      it "returns -1" do
+        expect(subject.progress_step_percentage).to eq(-1)
+      end
+    end
+
+    context "if the percentage of the current step is known" do
+      before do
+        subject.backend.start_progress(2)
+        progress.step("step 1")
+        progress.percentage = 42
+      end
+
+      it "returns the percentage" do
+        expect(subject.progress_step_percentage).to eq(42)
+      end
+    end
+  end
+
   describe "#progress_finished" do
     context "if there is no progress" do
       it "returns true" do
@@ -139,9 +159,11 @@ describe DBusObjectWithProgressInterface do
 
     it "returns de D-Bus properties of the progress interface" do
       expected_properties = {
-        "TotalSteps"  => 2,
-        "CurrentStep" => [1, "step 1"],
-        "Finished"    => false
+        "TotalSteps"     => 2,
+        "CurrentStep"    => [1, "step 1"],
+        "StepPercentage" => -1,
+        "Finished"       => false,
+        "Error"          => ""
       }
       expect(subject.progress_properties).to eq(expected_properties)
     end
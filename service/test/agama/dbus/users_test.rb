@@ -79,9 +79,39 @@ describe Agama::DBus::Users do
         allow(backend).to receive(:autologin?).with(user).and_return(true)
       end
 
-      it "returns the first user data" do
-        expect(subject.first_user).to eq(["Test user", "test", "12345", true, {}])
+      it "returns the first user data without the password" do
+        expect(subject.first_user)
+          .to eq(["Test user", "test", "", true, { "PasswordSet" => true }])
       end
     end
   end
+
+  describe "users" do
+    let(:user) do
+      instance_double(Y2Users::User,
+        full_name:        "John Doe",
+        name:             "john",
+        password_content: "12345",
+        uid:              "1500",
+        shell:            nil)
+    end
+
+    before do
+      allow(backend).to receive(:users).and_return([user])
+      allow(backend).to receive(:user_groups).with("john").and_return(["wheel"])
+      allow(backend).to receive(:sudo_rules).and_return({})
+    end
+
+    it "returns the users data without the passwords" do
+      expect(subject.users).to contain_exactly(
+        {
+          "UserName"    => "john",
+          "FullName"    => "John Doe",
+          "PasswordSet" => true,
+          "UID"         => 1500,
+          "Groups"      => ["wheel"]
+        }
+      )
+    end
+  end
 end
@@ -135,6 +135,17 @@ describe Agama::Manager do
       expect(users).to receive(:write)
       subject.install_phase
     end
+
+    context "if the installation fails" do
+      before do
+        allow(software).to receive(:install).and_raise(StandardError, "Could not install packages")
+      end
+
+      it "reports the error through the progress" do
+        subject.install_phase
+        expect(subject.progress.error).to eq("Could not install packages")
+      end
+    end
   end
 
   let(:idle) { Agama::DBus::ServiceStatus::IDLE }
@@ -35,12 +35,14 @@ describe Agama::Network do
       File.join(rootdir, "etc", "NetworkManager", "system-connections")
     end
     let(:targetdir) { File.join(rootdir, "mnt") }
+    let(:modprobedir) { File.join(rootdir, "etc", "modprobe.d") }
     let(:service) { instance_double(Yast2::Systemd::Service, enable: nil) }
 
     before do
       allow(Yast::Installation).to receive(:destdir).and_return(targetdir)
       allow(Yast2::Systemd::Service).to receive(:find).with("NetworkManager").and_return(service)
       stub_const("Agama::Network::ETC_NM_DIR", etcdir)
+      stub_const("Agama::Network::MODPROBE_DIR", modprobedir)
     end
 
     after do
@@ -61,6 +63,122 @@ describe Agama::Network do
       end
     end
 
+    context "when there are connections to activate only in the installed system" do
+      let(:connections_dir) { File.join(etcdir, "system-connections") }
+
+      before do
+        FileUtils.mkdir_p(connections_dir)
+        File.write(
+          File.join(connections_dir, "vlan.nmconnection"),
+          "[connection]\nid=vlan\nautoconnect=false\n\n" \
+          "[user]\norg.opensuse.agama.only-on-target=true\n"
+        )
+        File.write(
+          File.join(connections_dir, "wired.nmconnection"),
+          "[connection]\nid=wired\nautoconnect=false\n"
+        )
+      end
+
+      it "enables the autoconnect option of those connections" do
+        network.install
+        target_dir = File.join(targetdir, connections_dir)
+        expect(File.read(File.join(target_dir, "vlan.nmconnection")))
+          .to_not include("autoconnect=false")
+        expect(File.read(File.join(target_dir, "wired.nmconnection")))
+          .to include("autoconnect=false")
+      end
+    end
+
+    context "when Agama configuration snippets are present" do
+      before do
+        FileUtils.mkdir_p(File.join(etcdir, "conf.d"))
+        FileUtils.touch(File.join(etcdir, "conf.d", "90-agama-connectivity.conf"))
+        FileUtils.touch(File.join(etcdir, "conf.d", "other.conf"))
+      end
+
+      it "copies only the Agama snippets" do
+        network.install
+        conf_dir = File.join(targetdir, etcdir, "conf.d")
+        expect(File).to exist(File.join(conf_dir, "90-agama-connectivity.conf"))
+        expect(File).to_not exist(File.join(conf_dir, "other.conf"))
+      end
+    end
+
+    context "when the wireless regulatory domain is configured" do
+      before do
+        FileUtils.mkdir_p(modprobedir)
+        FileUtils.touch(File.join(modprobedir, "90-agama-regdom.conf"))
+        FileUtils.touch(File.join(modprobedir, "other.conf"))
+      end
+
+      it "copies the regulatory domain configuration" do
+        network.install
+        expect(File).to exist(File.join(targetdir, modprobedir, "90-agama-regdom.conf"))
+        expect(File).to_not exist(File.join(targetdir, modprobedir, "other.conf"))
+      end
+    end
+
+    context "when the DNS configuration is present" do
+      let(:dns_conf) { File.join(rootdir, "90-agama-dns.conf") }
+      let(:resolved) { instance_double(Yast2::Systemd::Service, enable: nil) }
+      let(:resolv_conf) { File.join(targetdir, "etc", "resolv.conf") }
+
+      before do
+        stub_const("Agama::Network::DNS_CONF_PATH", dns_conf)
+        allow(Yast2::Systemd::Service).to receive(:find).with("systemd-resolved")
+          .and_return(resolved)
+        File.write(dns_conf, content)
+      end
+
+      context "and it selects systemd-resolved" do
+        let(:content) { "[main]\ndns=systemd-resolved\n" }
+
+        it "copies the configuration" do
+          network.install
+          expect(File).to exist(File.join(targetdir, etcdir, "conf.d", "90-agama-dns.conf"))
+        end
+
+        it "enables systemd-resolved and links resolv.conf to its stub resolver" do
+          expect(resolved).to receive(:enable)
+          network.install
+          expect(File.readlink(resolv_conf)).to eq("../run/systemd/resolve/stub-resolv.conf")
+        end
+      end
+
+      context "and it selects a plain resolv.conf file" do
+        let(:content) { "[main]\ndns=default\nrc-manager=file\n" }
+
+        before do
+          FileUtils.mkdir_p(File.dirname(resolv_conf))
+          File.symlink("/run/netconfig/resolv.conf", resolv_conf)
+        end
+
+        it "removes the resolv.conf link" do
+          expect(resolved).to_not receive(:enable)
+          network.install
+          expect(File.symlink?(resolv_conf)).to eq(false)
+        end
+      end
+    end
+
+    context "when the wait-online settings are present" do
+      let(:wait_online_conf) { File.join(rootdir, "90-agama-wait-online.conf") }
+
+      before do
+        stub_const("Agama::Network::WAIT_ONLINE_CONF_PATH", wait_online_conf)
+        File.write(
+          wait_online_conf,
+          "[device-agama-optional]\nmatch-device=interface-name:eth1\ncarrier-wait-timeout=0\n"
+        )
+      end
+
+      it "copies the settings" do
+        network.install
+        target = File.join(targetdir, etcdir, "conf.d", "90-agama-wait-online.conf")
+        expect(File.read(target)).to eq(File.read(wait_online_conf))
+      end
+    end
+
     context "when NetworkManager configuration files are not available" do
       it "does not try to copy any file" do
         expect(FileUtils).to_not receive(:cp_r)
@@ -68,6 +186,29 @@ describe Agama::Network do
       end
     end
 
+    context "when the kdump network settings are present" do
+      let(:kdump_conf) { File.join(rootdir, "kdump") }
+      let(:sysconfig) { File.join(targetdir, "etc", "sysconfig", "kdump") }
+
+      before do
+        stub_const("Agama::Network::KDUMP_CONF_PATH", kdump_conf)
+        File.write(
+          kdump_conf,
+          "KDUMP_NETCONFIG=\"eth0:static\"\nKDUMP_SAVEDIR=\"nfs://192.168.1.1/var/crash\"\n"
+        )
+        FileUtils.mkdir_p(File.dirname(sysconfig))
+        File.write(sysconfig, "KDUMP_KEEP_OLD_DUMPS=\"5\"\nKDUMP_NETCONFIG=\"auto\"\n")
+      end
+
+      it "updates the kdump configuration" do
+        network.install
+        expect(File.read(sysconfig)).to eq(
+          "KDUMP_KEEP_OLD_DUMPS=\"5\"\nKDUMP_NETCONFIG=\"eth0:static\"\n" \
+          "KDUMP_SAVEDIR=\"nfs://192.168.1.1/var/crash\"\n"
+        )
+      end
+    end
+
     context "when NetworkManager connections are not defined" do
       before do
         FileUtils.mkdir_p(etcdir)
@@ -189,6 +189,39 @@ describe Agama::Progress do
     end
   end
 
+  describe "#percentage=" do
+    let(:steps) { 2 }
+
+    before { subject.step("step 1") }
+
+    it "sets the percentage of the current step" do
+      subject.percentage = 42
+      expect(subject.percentage).to eq(42)
+    end
+
+    it "limits the percentage to the 0-100 range" do
+      subject.percentage = 120
+      expect(subject.percentage).to eq(100)
+    end
+
+    it "calls 'on_change' callbacks" do
+      callback = proc {}
+      subject.on_change(&callback)
+
+      expect(callback).to receive(:call)
+      subject.percentage = 42
+    end
+
+    context "when a new step starts" do
+      before { subject.percentage = 42 }
+
+      it "resets the percentage" do
+        subject.step("step 2")
+        expect(subject.percentage).to be_nil
+      end
+    end
+  end
+
   describe "#finished?" do
     let(:steps) { 3 }
 
@@ -228,6 +261,31 @@ describe Agama::Progress do
     end
   end
 
+  describe "#fail" do
+    let(:steps) { 2 }
+
+    before { subject.step("step 1") }
+
+    it "finishes the progress" do
+      subject.fail("Something went wrong")
+      expect(subject.finished?).to eq(true)
+      expect(subject.failed?).to eq(true)
+    end
+
+    it "keeps the error message" do
+      subject.fail("Something went wrong")
+      expect(subject.error).to eq("Something went wrong")
+    end
+
+    it "calls 'on_finish' callbacks" do
+      callback = proc {}
+      subject.on_finish(&callback)
+
+      expect(callback).to receive(:call)
+      subject.fail("Something went wrong")
+    end
+  end
+
   describe "#to_s" do
     let(:steps) { 2 }
 
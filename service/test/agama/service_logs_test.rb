@@ -0,0 +1,91 @@
+# frozen_string_literal: true
+
+# Copyright (c) [2024] SUSE LLC
+#
+# All Rights Reserved.
+#
+# This program is free software; you can redistribute it and/or modify it
+# under the terms of version 2 of the GNU General Public License as published
+# by the Free Software Foundation.
+#
+# This program is distributed in the hope that it will be useful, but WITHOUT
+# ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+# FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+# more details.
+#
+# You should have received a copy of the GNU General Public License along
+# with this program; if not, contact SUSE LLC.
+#
+# To contact SUSE LLC about this file by physical or electronic mail, you may
+# find current contact information at www.suse.com.
+
+require_relative "../test_helper"
+require "agama/service_logs"
+
+describe Agama::ServiceLogs do
+  let(:output) do
+    "[INFO]: software: Probing software\n" \
+      "[INFO]: software: Software probed\n" \
+      "-- cursor: s=123;i=2\n"
+  end
+
+  before do
+    allow(Yast::Execute).to receive(:locally!).and_return([output, 0])
+  end
+
+  describe "#recent" do
+    it "returns the most recent lines of the service and the cursor of the last one" do
+      expect(Yast::Execute).to receive(:locally!) do |command, **|
+        expect(command).to include("--unit=agama", "--lines=10")
+        expect(command).to include(a_string_matching(/--grep=.*software: /))
+        [output, 0]
+      end
+
+      lines, cursor = subject.recent("software", 10)
+      expect(lines).to eq(["[INFO]: software: Probing software",
+                           "[INFO]: software: Software probed"])
+      expect(cursor).to eq("s=123;i=2")
+    end
+
+    context "when the service is unknown" do
+      it "raises an ArgumentError" do
+        expect { subject.recent("unknown", 10) }.to raise_error(ArgumentError)
+      end
+    end
+  end
+
+  describe "#after" do
+    it "returns the lines written after the given cursor" do
+      expect(Yast::Execute).to receive(:locally!) do |command, **|
+        expect(command).to include("--after-cursor=s=123;i=1")
+        [output, 0]
+      end
+
+      lines, cursor = subject.after("software", "s=123;i=1")
+      expect(lines.size).to eq(2)
+      expect(cursor).to eq("s=123;i=2")
+    end
+
+    context "when there are no new lines" do
+      let(:output) { "" }
+
+      it "returns the given cursor" do
+        lines, cursor = subject.after("software", "s=123;i=2")
+        expect(lines).to be_empty
+        expect(cursor).to eq("s=123;i=2")
+      end
+    end
+
+    context "when the cursor is empty" do
+      it "returns all the lines" do
+        expect(Yast::Execute).to receive(:locally!) do |command, **|
+          expect(command.grep(/--after-cursor|--lines/)).to be_empty
+          [output, 0]
+        end
+
+        lines, = subject.after("software", "")
+        expect(lines.size).to eq(2)
+      end
+    end
+  end
+end
@@ -71,6 +71,30 @@ describe Agama::Software::RepositoriesManager do
       expect(Yast::Pkg).to receive(:SourceLoad)
       subject.load
     end
+
+    context "when the system is offline" do
+      let(:remote_repo) do
+        instance_double(Agama::Software::Repository, disable!: nil, remote?: true)
+      end
+
+      before do
+        subject.repositories << remote_repo
+        allow(repo).to receive(:remote?).and_return(false)
+        allow(repo1).to receive(:remote?).and_return(false)
+        subject.offline = true
+      end
+
+      it "disables the remote repositories without reading them" do
+        expect(remote_repo).to_not receive(:probe)
+        expect(remote_repo).to receive(:disable!)
+        subject.load
+      end
+
+      it "enables the local repositories that can be read" do
+        expect(repo).to receive(:enable!)
+        subject.load
+      end
+    end
   end
 
   describe "#delete_all" do
@@ -31,6 +31,25 @@ describe Agama::Software::Repository do
     )
   end
 
+  describe "#remote?" do
+    it "returns true for a repository accessed through the network" do
+      expect(subject.remote?).to eq(true)
+    end
+
+    context "when the repository is local" do
+      subject do
+        described_class.new(
+          repo_id: 1, repo_alias: "dvd", name: "DVD", url: "dvd:/?devices=/dev/sr0",
+          enabled: true, autorefresh: false, product_dir: "/"
+        )
+      end
+
+      it "returns false" do
+        expect(subject.remote?).to eq(false)
+      end
+    end
+  end
+
   describe "#probe" do
     before do
       allow(Yast::Pkg).to receive(:RepositoryProbe).with(/example.net/, "/")
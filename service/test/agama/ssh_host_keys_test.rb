@@ -0,0 +1,176 @@
+# frozen_string_literal: true
+
+# Copyright (c) [2024] SUSE LLC
+#
+# All Rights Reserved.
+#
+# This program is free software; you can redistribute it and/or modify it
+# under the terms of version 2 of the GNU General Public License as published
+# by the Free Software Foundation.
+#
+# This program is distributed in the hope that it will be useful, but WITHOUT
+# ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+# FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+# more details.
+#
+# You should have received a copy of the GNU General Public License along
+# with this program; if not, contact SUSE LLC.
+#
+# To contact SUSE LLC about this file by physical or electronic mail, you may
+# find current contact information at www.suse.com.
+
+require_relative "../test_helper"
+require "agama/ssh_host_keys"
+require "tmpdir"
+
+describe Agama::SSHHostKeys do
+  subject { described_class.new(logger) }
+
+  let(:logger) { Logger.new($stdout, level: :warn) }
+
+  # Simulates mounting a device with the given files in /etc/ssh
+  def mock_device(files)
+    allow(Yast::Execute).to receive(:locally!).with("/usr/bin/umount", anything)
+    allow(Yast::Execute).to receive(:locally!)
+      .with("/usr/bin/mount", "-o", "ro", "/dev/sda2", anything) do |*args|
+        ssh_dir = File.join(args.last, "etc", "ssh")
+        FileUtils.mkdir_p(ssh_dir)
+        files.each { |name, content| File.write(File.join(ssh_dir, name), content) }
+      end
+  end
+
+  describe "#import" do
+    context "when importing from a device" do
+      before do
+        mock_device(
+          "ssh_host_ed25519_key"     => "private",
+          "ssh_host_ed25519_key.pub" => "public",
+          "sshd_config"              => "config"
+        )
+      end
+
+      it "reads the SSH host keys" do
+        expect(subject.import("Device" => "/dev/sda2")).to be_empty
+        expect(subject.names).to eq(["ssh_host_ed25519_key", "ssh_host_ed25519_key.pub"])
+        expect(subject.source).to eq("Device" => "/dev/sda2")
+      end
+
+      it "unmounts the device" do
+        expect(Yast::Execute).to receive(:locally!).with("/usr/bin/umount", anything)
+        subject.import("Device" => "/dev/sda2")
+      end
+    end
+
+    context "when the device does not contain any key" do
+      before { mock_device("sshd_config" => "config") }
+
+      it "returns an issue" do
+        expect(subject.import("Device" => "/dev/sda2")).to contain_exactly(/No SSH host keys/)
+        expect(subject.names).to be_empty
+        expect(subject.source).to be_empty
+      end
+    end
+
+    context "when the device cannot be mounted" do
+      before do
+        allow(Yast::Execute).to receive(:locally!).with("/usr/bin/mount", any_args)
+          .and_raise(Cheetah::ExecutionFailed.new([], "", nil, nil))
+      end
+
+      it "returns an issue" do
+        expect(subject.import("Device" => "/dev/sda2")).to contain_exactly(/mount/)
+      end
+    end
+
+    context "when importing from a URL" do
+      let(:archive) { "keys archive" }
+      let(:sha256) { Digest::SHA256.hexdigest(archive) }
+
+      before do
+        allow(subject).to receive(:get_file_from_url) do |localfile:, **|
+          File.write(localfile, archive)
+          true
+        end
+        allow(Yast::Execute).to receive(:locally!)
+          .with("/usr/bin/tar", "-xf", anything, "-C", anything) do |*args|
+            File.write(File.join(args.last, "ssh_host_ed25519_key"), "private")
+          end
+      end
+
+      it "reads the SSH host keys" do
+        source = { "Url" => "https://example.net/keys.tar", "Sha256" => sha256.upcase }
+        expect(subject.import(source)).to be_empty
+        expect(subject.names).to eq(["ssh_host_ed25519_key"])
+        expect(subject.source)
+          .to eq("Url" => "https://example.net/keys.tar", "Sha256" => sha256)
+      end
+
+      context "and the checksum is missing" do
+        it "returns an issue without downloading the archive" do
+          expect(subject).to_not receive(:get_file_from_url)
+          expect(subject.import("Url" => "https://example.net/keys.tar"))
+            .to contain_exactly(/checksum/)
+          expect(subject.names).to be_empty
+        end
+      end
+
+      context "and the checksum does not match" do
+        it "returns an issue" do
+          source = { "Url" => "https://example.net/keys.tar", "Sha256" => "0" * 64 }
+          expect(subject.import(source)).to contain_exactly(/Checksum mismatch/)
+          expect(subject.names).to be_empty
+        end
+      end
+    end
+
+    context "when the archive cannot be downloaded" do
+      before do
+        allow(subject).to receive(:get_file_from_url).and_return(false)
+      end
+
+      it "returns an issue" do
+        expect(subject.import("Url" => "http://example.net/keys.tar", "Sha256" => "0" * 64))
+          .to contain_exactly(/download/)
+      end
+    end
+
+    context "when an empty source is given" do
+      before do
+        mock_device("ssh_host_ed25519_key" => "private")
+        subject.import("Device" => "/dev/sda2")
+      end
+
+      it "discards the imported keys" do
+        expect(subject.import({})).to be_empty
+        expect(subject.names).to be_empty
+        expect(subject.source).to be_empty
+      end
+    end
+  end
+
+  describe "#write" do
+    let(:root_dir) { Dir.mktmpdir }
+
+    before do
+      mock_device(
+        "ssh_host_ed25519_key"     => "private",
+        "ssh_host_ed25519_key.pub" => "public"
+      )
+      subject.import("Device" => "/dev/sda2")
+    end
+
+    after { FileUtils.remove_entry(root_dir) }
+
+    it "copies the keys to the target system" do
+      subject.write(root_dir)
+
+      private_key = File.join(root_dir, "etc", "ssh", "ssh_host_ed25519_key")
+      expect(File.read(private_key)).to eq("private")
+      expect(File.stat(private_key).mode & 0o777).to eq(0o600)
+
+      public_key = File.join(root_dir, "etc", "ssh", "ssh_host_ed25519_key.pub")
+      expect(File.read(public_key)).to eq("public")
+      expect(File.stat(public_key).mode & 0o777).to eq(0o644)
+    end
+  end
+end
@@ -21,6 +21,7 @@
 
 require_relative "../test_helper"
 require "agama/users"
+require "tmpdir"
 
 describe Agama::Users do
   subject(:storage) { described_class.new(logger) }
@@ -101,6 +102,15 @@ describe Agama::Users do
           user = users_config.users.by_name("john")
           expect(user.full_name).to eq("John Doe")
         end
+
+        context "and no password is given" do
+          it "keeps the current password" do
+            subject.assign_first_user("Jane Doe", "jane", "", false, {})
+
+            user = users_config.users.by_name("jane")
+            expect(user.password).to eq(Y2Users::Password.create_plain("12345"))
+          end
+        end
       end
 
       it "returns an empty array of issues" do
@@ -138,6 +148,147 @@ describe Agama::Users do
     end
   end
 
+  describe "#assign_users" do
+    let(:users_data) do
+      [
+        {
+          "UserName" => "john", "FullName" => "John Doe", "Password" => "12345", "UID" => 1500,
+          "Groups" => ["wheel", "docker"], "Shell" => "/usr/bin/zsh", "Sudo" => "ALL=(ALL) ALL"
+        },
+        { "UserName" => "ana", "Password" => "$6$abc", "EncryptedPassword" => true }
+      ]
+    end
+
+    before do
+      allow(Yast::Execute).to receive(:locally!)
+    end
+
+    it "adds the users to the user's configuration" do
+      subject.assign_users(users_data)
+      user = users_config.users.by_name("john")
+      expect(user.full_name).to eq("John Doe")
+      expect(user.uid).to eq("1500")
+      expect(user.shell).to eq("/usr/bin/zsh")
+      expect(user.password).to eq(Y2Users::Password.create_plain("12345"))
+
+      user = users_config.users.by_name("ana")
+      expect(user.password).to eq(Y2Users::Password.create_encrypted("$6$abc"))
+    end
+
+    it "adds the users to the given groups" do
+      subject.assign_users(users_data)
+      expect(subject.user_groups("john")).to contain_exactly("wheel", "docker")
+      expect(subject.user_groups("ana")).to be_empty
+    end
+
+    it "keeps the sudo rules" do
+      subject.assign_users(users_data)
+      expect(subject.sudo_rules).to eq("john" => "ALL=(ALL) ALL")
+    end
+
+    it "does not consider the users as the first user" do
+      subject.assign_users(users_data)
+      expect(subject.first_user).to be_nil
+    end
+
+    it "returns an empty array of issues" do
+      expect(subject.assign_users(users_data)).to be_empty
+    end
+
+    context "when some users were already defined" do
+      before do
+        subject.assign_users(users_data)
+      end
+
+      it "replaces the users with the new ones" do
+        subject.assign_users([{ "UserName" => "peter", "Password" => "12345" }])
+        expect(users_config.users.by_name("john")).to be_nil
+        expect(users_config.users.by_name("peter")).to_not be_nil
+        expect(subject.user_groups("john")).to be_empty
+        expect(subject.sudo_rules).to be_empty
+      end
+
+      it "keeps the current password of the users if no password is given" do
+        subject.assign_users([{ "UserName" => "ana", "FullName" => "Ana" }])
+        user = users_config.users.by_name("ana")
+        expect(user.full_name).to eq("Ana")
+        expect(user.password).to eq(Y2Users::Password.create_encrypted("$6$abc"))
+      end
+    end
+
+    context "when a user name is repeated" do
+      before do
+        subject.assign_first_user("Jane Doe", "jane", "12345", false, {})
+      end
+
+      it "does not add the users to the config" do
+        subject.assign_users([{ "UserName" => "jane", "Password" => "12345" }])
+        expect(subject.users).to be_empty
+      end
+
+      it "returns an array with the issue" do
+        issues = subject.assign_users([{ "UserName" => "jane", "Password" => "12345" }])
+        expect(issues).to contain_exactly(/jane/)
+      end
+    end
+
+    context "when a sudo rule contains a line break" do
+      let(:sudo_data) do
+        [
+          {
+            "UserName" => "john", "Password" => "12345",
+            "Sudo" => "ALL=(ALL) ALL\nALL ALL=(ALL) ALL"
+          }
+        ]
+      end
+
+      it "does not add the users to the config" do
+        subject.assign_users(sudo_data)
+        expect(users_config.users.by_name("john")).to be_nil
+        expect(subject.sudo_rules).to be_empty
+      end
+
+      it "returns an array with the issue" do
+        expect(subject.assign_users(sudo_data)).to contain_exactly(/line breaks/)
+      end
+    end
+
+    context "when a sudo rule does not pass the visudo check" do
+      before do
+        allow(Yast::Execute).to receive(:locally!).with("/usr/sbin/visudo", "-cf", anything)
+          .and_raise(Cheetah::ExecutionFailed.new([], "", nil, nil))
+      end
+
+      it "does not add the users to the config" do
+        subject.assign_users(users_data)
+        expect(users_config.users.by_name("john")).to be_nil
+      end
+
+      it "returns an array with the issue" do
+        expect(subject.assign_users(users_data)).to contain_exactly(/sudo rule for 'john'/)
+      end
+    end
+
+    context "when some user presents a critical error" do
+      it "does not add any user to the config" do
+        subject.assign_users(users_data + [{ "UserName" => "ldap", "Password" => "12345" }])
+        expect(users_config.users.by_name("john")).to be_nil
+      end
+    end
+  end
+
+  describe "#remove_first_user" do
+    before do
+      subject.assign_users([{ "UserName" => "john", "Password" => "12345" }])
+      subject.assign_first_user("Jane Doe", "jane", "12345", false, {})
+    end
+
+    it "keeps the rest of users" do
+      subject.remove_first_user
+      expect(users_config.users.by_name("john")).to_not be_nil
+    end
+  end
+
   describe "#write" do
     let(:writer) { instance_double(Y2Users::Linux::Writer, write: issues) }
     let(:issues) { [] }
@@ -177,6 +328,47 @@ describe Agama::Users do
       end
     end
 
+    context "when some user has a sudo rule" do
+      let(:destdir) { Dir.mktmpdir }
+
+      before do
+        allow(Yast::Installation).to receive(:destdir).and_return(destdir)
+        subject.assign_users(
+          [{ "UserName" => "john.doe", "Password" => "12345", "Sudo" => "ALL=(ALL) ALL" }]
+        )
+      end
+
+      after do
+        FileUtils.remove_entry(destdir)
+      end
+
+      it "writes the rule to the sudoers directory" do
+        subject.write
+        path = File.join(destdir, "etc", "sudoers.d", "agama-john_doe")
+        expect(File.read(path)).to eq("john.doe ALL=(ALL) ALL\n")
+        expect(File.stat(path).mode & 0o777).to eq(0o440)
+      end
+
+      it "checks the file with visudo" do
+        path = File.join(destdir, "etc", "sudoers.d", "agama-john_doe.new")
+        expect(Yast::Execute).to receive(:locally!).with("/usr/sbin/visudo", "-cf", path)
+        subject.write
+      end
+
+      context "and the file does not pass the visudo check" do
+        before do
+          allow(Yast::Execute).to receive(:locally!).with("/usr/sbin/visudo", "-cf", anything)
+            .and_raise(Cheetah::ExecutionFailed.new([], "", nil, nil))
+        end
+
+        it "does not write the rule" do
+          subject.write
+          dir = File.join(destdir, "etc", "sudoers.d")
+          expect(Dir.children(dir)).to be_empty
+        end
+      end
+    end
+
     it "writes without /run bind mounted" do
       expect(Yast::Execute).to receive(:locally!).with(/umount/, anything)
 
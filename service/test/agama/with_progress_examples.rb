@@ -118,4 +118,24 @@ shared_examples "progress" do
       end
     end
   end
+
+  describe "#fail_progress" do
+    context "when the current progress is not finished" do
+      before do
+        subject.start_progress(1)
+      end
+
+      it "finishes the current progress with the given error" do
+        subject.fail_progress("Something went wrong")
+        expect(subject.progress.finished?).to eq(true)
+        expect(subject.progress.error).to eq("Something went wrong")
+      end
+    end
+
+    context "when there is no progress" do
+      it "does not crash" do
+        subject.fail_progress("Something went wrong")
+      end
+    end
+  end
 end